@@ -0,0 +1,538 @@
+//! A minimal entity component system.
+//!
+//! An [Entity] is only an identifier, all of its state lives in components,
+//! which can be any `'static` type. The [World] stores every component type
+//! in its own column indexed by the entity, and can be queried for all
+//! entities having one or two given component types.
+//!
+//! Game logic is split into systems, functions run on the [World] once per
+//! frame in the order they were added to a [Schedule], getting the [Time]
+//! of the frame. Fixed systems run once per fixed step instead.
+//!
+//! A [Simulation] advances the world in fixed steps only, driven by a log
+//! of commands and a seeded [Rng], so replaying the log gives the same
+//! world on every run.
+//!
+//! ```
+//! # use std::time::Duration;
+//! # use ecs::{Schedule, Time, World};
+//! struct Position(f32);
+//! struct Velocity(f32);
+//!
+//! let mut world = World::new();
+//! let entity = world.spawn();
+//! world.insert(entity, Position(0.0));
+//! world.insert(entity, Velocity(2.0));
+//!
+//! let mut schedule = Schedule::new();
+//! schedule.add_system("movement", |world, time| {
+//!     let moved = world
+//!         .query2::<Position, Velocity>()
+//!         .map(|(entity, position, velocity)| {
+//!             (entity, position.0 + velocity.0 * time.delta().as_secs_f32())
+//!         })
+//!         .collect::<Vec<_>>();
+//!     for (entity, position) in moved {
+//!         world.insert(entity, Position(position));
+//!     }
+//! });
+//!
+//! let mut time = Time::new();
+//! time.advance(Duration::from_millis(500));
+//! schedule.run(&mut world, &time);
+//! assert_eq!(world.get::<Position>(entity).unwrap().0, 1.0);
+//! ```
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    fmt,
+};
+
+pub use rng::Rng;
+pub use simulation::{Checksum, Simulation};
+pub use time::Time;
+
+mod rng;
+mod simulation;
+mod time;
+
+/// Identifies an object in the [World].
+///
+/// The slot of a despawned entity is reused, but with a new generation,
+/// so stale handles never refer to the new entity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Entity {
+    index: u32,
+    generation: u32,
+}
+
+impl fmt::Display for Entity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}v{}", self.index, self.generation)
+    }
+}
+
+/// Type erased access to a [ComponentColumn], used when despawning.
+trait Column: Any {
+    fn remove_index(&mut self, index: usize);
+}
+
+/// All components of one type, indexed by [Entity::index].
+struct ComponentColumn<C> {
+    components: Vec<Option<C>>,
+}
+
+impl<C: 'static> Column for ComponentColumn<C> {
+    fn remove_index(&mut self, index: usize) {
+        if let Some(component) = self.components.get_mut(index) {
+            *component = None;
+        }
+    }
+}
+
+impl<C> ComponentColumn<C> {
+    fn get(&self, index: usize) -> Option<&C> {
+        self.components.get(index)?.as_ref()
+    }
+
+    fn iter(&self) -> impl Iterator<Item = (usize, &C)> {
+        self.components
+            .iter()
+            .enumerate()
+            .filter_map(|(index, component)| Some((index, component.as_ref()?)))
+    }
+}
+
+/// Stores the entities, their components and global resources.
+#[derive(Default)]
+pub struct World {
+    // The current generation of every slot, odd while the slot is alive.
+    generations: Vec<u32>,
+    free: Vec<u32>,
+    columns: HashMap<TypeId, Box<dyn Column>>,
+    resources: HashMap<TypeId, Box<dyn Any>>,
+}
+
+impl fmt::Debug for World {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("World")
+            .field("entities", &self.len())
+            .field("component_types", &self.columns.len())
+            .field("resources", &self.resources.len())
+            .finish()
+    }
+}
+
+impl World {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new entity without any components.
+    pub fn spawn(&mut self) -> Entity {
+        let index = match self.free.pop() {
+            Some(index) => index,
+            None => {
+                self.generations.push(0);
+                (self.generations.len() - 1) as u32
+            }
+        };
+        let generation = &mut self.generations[index as usize];
+        *generation += 1;
+
+        Entity {
+            index,
+            generation: *generation,
+        }
+    }
+
+    /// Remove `entity` with all of its components.
+    ///
+    /// Returns `false` if the entity was already despawned.
+    pub fn despawn(&mut self, entity: Entity) -> bool {
+        if !self.is_alive(entity) {
+            return false;
+        }
+        for column in self.columns.values_mut() {
+            column.remove_index(entity.index as usize);
+        }
+        self.generations[entity.index as usize] += 1;
+        self.free.push(entity.index);
+        true
+    }
+
+    pub fn is_alive(&self, entity: Entity) -> bool {
+        self.generations.get(entity.index as usize) == Some(&entity.generation)
+    }
+
+    /// The number of living entities.
+    pub fn len(&self) -> usize {
+        self.generations.len() - self.free.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Attach `component` to `entity`, returning the component of the same
+    /// type it replaced.
+    ///
+    /// # Panics
+    ///
+    /// If `entity` was despawned.
+    pub fn insert<C: 'static>(&mut self, entity: Entity, component: C) -> Option<C> {
+        assert!(self.is_alive(entity), "entity {entity} was despawned");
+
+        let components = &mut self.column_mut::<C>().components;
+        let index = entity.index as usize;
+        if components.len() <= index {
+            components.resize_with(index + 1, || None);
+        }
+        components[index].replace(component)
+    }
+
+    /// Detach the component of type `C` from `entity`.
+    pub fn remove<C: 'static>(&mut self, entity: Entity) -> Option<C> {
+        if !self.is_alive(entity) {
+            return None;
+        }
+        self.columns
+            .get_mut(&TypeId::of::<C>())
+            .and_then(|column| downcast_mut::<C>(column.as_mut()))?
+            .components
+            .get_mut(entity.index as usize)?
+            .take()
+    }
+
+    pub fn get<C: 'static>(&self, entity: Entity) -> Option<&C> {
+        if !self.is_alive(entity) {
+            return None;
+        }
+        self.column::<C>()?.get(entity.index as usize)
+    }
+
+    pub fn get_mut<C: 'static>(&mut self, entity: Entity) -> Option<&mut C> {
+        if !self.is_alive(entity) {
+            return None;
+        }
+        self.columns
+            .get_mut(&TypeId::of::<C>())
+            .and_then(|column| downcast_mut::<C>(column.as_mut()))?
+            .components
+            .get_mut(entity.index as usize)?
+            .as_mut()
+    }
+
+    /// Every entity with a component of type `C`.
+    ///
+    /// Entities are visited in the order of their slots, which matches the
+    /// order they were spawned in until an entity is despawned.
+    pub fn query<C: 'static>(&self) -> impl Iterator<Item = (Entity, &C)> {
+        self.column::<C>()
+            .into_iter()
+            .flat_map(|column| column.iter())
+            .map(|(index, component)| (self.entity(index), component))
+    }
+
+    /// Like [World::query], but the components can be modified.
+    pub fn query_mut<C: 'static>(&mut self) -> impl Iterator<Item = (Entity, &mut C)> {
+        let generations = &self.generations;
+        self.columns
+            .get_mut(&TypeId::of::<C>())
+            .and_then(|column| downcast_mut::<C>(column.as_mut()))
+            .into_iter()
+            .flat_map(|column| column.components.iter_mut().enumerate())
+            .filter_map(move |(index, component)| {
+                let entity = Entity {
+                    index: index as u32,
+                    generation: generations[index],
+                };
+                Some((entity, component.as_mut()?))
+            })
+    }
+
+    /// Every entity with both a component of type `A` and `B`.
+    pub fn query2<A: 'static, B: 'static>(&self) -> impl Iterator<Item = (Entity, &A, &B)> {
+        let second = self.column::<B>();
+        self.column::<A>()
+            .zip(second)
+            .into_iter()
+            .flat_map(|(first, second)| {
+                first
+                    .iter()
+                    .filter_map(|(index, a)| Some((index, a, second.get(index)?)))
+            })
+            .map(|(index, a, b)| (self.entity(index), a, b))
+    }
+
+    /// Store a global value, which isn't attached to any entity,
+    /// returning the resource of the same type it replaced.
+    pub fn insert_resource<R: 'static>(&mut self, resource: R) -> Option<R> {
+        self.resources
+            .insert(TypeId::of::<R>(), Box::new(resource))
+            .and_then(|previous| previous.downcast().ok())
+            .map(|previous| *previous)
+    }
+
+    pub fn remove_resource<R: 'static>(&mut self) -> Option<R> {
+        self.resources
+            .remove(&TypeId::of::<R>())
+            .and_then(|resource| resource.downcast().ok())
+            .map(|resource| *resource)
+    }
+
+    pub fn resource<R: 'static>(&self) -> Option<&R> {
+        self.resources.get(&TypeId::of::<R>())?.downcast_ref()
+    }
+
+    pub fn resource_mut<R: 'static>(&mut self) -> Option<&mut R> {
+        self.resources.get_mut(&TypeId::of::<R>())?.downcast_mut()
+    }
+
+    /// The living entity in the slot `index`.
+    fn entity(&self, index: usize) -> Entity {
+        Entity {
+            index: index as u32,
+            generation: self.generations[index],
+        }
+    }
+
+    fn column<C: 'static>(&self) -> Option<&ComponentColumn<C>> {
+        let column: &dyn Any = self.columns.get(&TypeId::of::<C>())?.as_ref();
+        column.downcast_ref()
+    }
+
+    fn column_mut<C: 'static>(&mut self) -> &mut ComponentColumn<C> {
+        let column = self.columns.entry(TypeId::of::<C>()).or_insert_with(|| {
+            Box::new(ComponentColumn::<C> {
+                components: Vec::new(),
+            })
+        });
+        downcast_mut(column.as_mut()).expect("columns are stored under their own type id")
+    }
+}
+
+fn downcast_mut<C: 'static>(column: &mut dyn Column) -> Option<&mut ComponentColumn<C>> {
+    let column: &mut dyn Any = column;
+    column.downcast_mut()
+}
+
+/// A function updating the [World], called once per frame with the [Time].
+pub type System = Box<dyn FnMut(&mut World, &Time)>;
+
+/// Runs systems in a fixed order.
+#[derive(Default)]
+pub struct Schedule {
+    systems: Vec<(&'static str, System)>,
+    fixed_systems: Vec<(&'static str, System)>,
+}
+
+impl fmt::Debug for Schedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.system_names().chain(self.fixed_system_names()))
+            .finish()
+    }
+}
+
+impl Schedule {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append `system` to the schedule, it runs after every system added before.
+    ///
+    /// `name` only identifies the system when debugging, each run of the
+    /// system is traced in a `system` span with the name.
+    pub fn add_system(
+        &mut self,
+        name: &'static str,
+        system: impl FnMut(&mut World, &Time) + 'static,
+    ) -> &mut Self {
+        self.systems.push((name, Box::new(system)));
+        self
+    }
+
+    /// Append `system` to the systems run once per fixed step, after every
+    /// fixed system added before.
+    ///
+    /// Fixed systems advance by [fixed_delta](Time::fixed_delta) instead of
+    /// the frame's delta, the same amount on every machine.
+    pub fn add_fixed_system(
+        &mut self,
+        name: &'static str,
+        system: impl FnMut(&mut World, &Time) + 'static,
+    ) -> &mut Self {
+        self.fixed_systems.push((name, Box::new(system)));
+        self
+    }
+
+    /// The names of the systems in the order they run.
+    pub fn system_names(&self) -> impl Iterator<Item = &'static str> {
+        self.systems.iter().map(|(name, _)| *name)
+    }
+
+    /// The names of the fixed systems in the order they run.
+    pub fn fixed_system_names(&self) -> impl Iterator<Item = &'static str> {
+        self.fixed_systems.iter().map(|(name, _)| *name)
+    }
+
+    /// Run every system once on `world` for the frame of `time`, then the
+    /// fixed systems for each of its [fixed_steps](Time::fixed_steps).
+    pub fn run(&mut self, world: &mut World, time: &Time) {
+        for (name, system) in &mut self.systems {
+            let _span = tracing::trace_span!("system", name).entered();
+            system(world, time);
+        }
+        for _ in 0..time.fixed_steps() {
+            for (name, system) in &mut self.fixed_systems {
+                let _span = tracing::trace_span!("fixed_system", name).entered();
+                system(world, time);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{Schedule, Time, World};
+
+    #[derive(Debug, PartialEq)]
+    struct Name(&'static str);
+
+    #[derive(Debug, PartialEq)]
+    struct Health(u32);
+
+    #[test]
+    fn components_are_attached_to_their_entity() {
+        let mut world = World::new();
+        let first = world.spawn();
+        let second = world.spawn();
+        world.insert(first, Name("first"));
+        world.insert(second, Name("second"));
+        world.insert(second, Health(3));
+
+        assert_eq!(world.get::<Name>(first), Some(&Name("first")));
+        assert_eq!(world.get::<Health>(first), None);
+        assert_eq!(world.insert(second, Health(5)), Some(Health(3)));
+        world.get_mut::<Health>(second).unwrap().0 += 1;
+        assert_eq!(world.get::<Health>(second), Some(&Health(6)));
+        assert_eq!(world.remove::<Name>(second), Some(Name("second")));
+        assert_eq!(world.get::<Name>(second), None);
+    }
+
+    #[test]
+    fn despawned_entities_are_not_reused() {
+        let mut world = World::new();
+        let old = world.spawn();
+        world.insert(old, Health(1));
+        assert!(world.despawn(old));
+        assert!(!world.despawn(old));
+
+        let new = world.spawn();
+        assert_ne!(old, new);
+        assert!(!world.is_alive(old));
+        assert_eq!(world.get::<Health>(old), None);
+        // The component of the old entity is gone with it.
+        assert_eq!(world.get::<Health>(new), None);
+        assert_eq!(world.len(), 1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn insert_into_despawned_entity() {
+        let mut world = World::new();
+        let entity = world.spawn();
+        world.despawn(entity);
+        world.insert(entity, Health(1));
+    }
+
+    #[test]
+    fn queries_skip_entities_without_the_components() {
+        let mut world = World::new();
+        let named = world.spawn();
+        let both = world.spawn();
+        let healthy = world.spawn();
+        world.insert(named, Name("named"));
+        world.insert(both, Name("both"));
+        world.insert(both, Health(2));
+        world.insert(healthy, Health(3));
+
+        let names: Vec<_> = world.query::<Name>().map(|(_, name)| name.0).collect();
+        assert_eq!(names, ["named", "both"]);
+
+        let pairs: Vec<_> = world.query2::<Name, Health>().collect();
+        assert_eq!(pairs, [(both, &Name("both"), &Health(2))]);
+
+        for (_, health) in world.query_mut::<Health>() {
+            health.0 *= 10;
+        }
+        assert_eq!(world.get::<Health>(healthy), Some(&Health(30)));
+        assert_eq!(world.query::<u8>().count(), 0);
+    }
+
+    #[test]
+    fn resources_are_unique_per_type() {
+        let mut world = World::new();
+        assert_eq!(world.insert_resource(Health(1)), None);
+        assert_eq!(world.insert_resource(Health(2)), Some(Health(1)));
+        world.resource_mut::<Health>().unwrap().0 += 1;
+        assert_eq!(world.resource::<Health>(), Some(&Health(3)));
+        assert_eq!(world.remove_resource::<Health>(), Some(Health(3)));
+        assert_eq!(world.resource::<Health>(), None);
+    }
+
+    #[test]
+    fn systems_run_in_order() {
+        let mut world = World::new();
+        world.insert_resource(Vec::<&'static str>::new());
+
+        let mut schedule = Schedule::new();
+        schedule
+            .add_system("first", |world, _| {
+                world.resource_mut::<Vec<&str>>().unwrap().push("first")
+            })
+            .add_system("second", |world, time| {
+                assert_eq!(time.real_delta(), Duration::from_millis(16));
+                world.resource_mut::<Vec<&str>>().unwrap().push("second")
+            });
+        let mut time = Time::new();
+        time.advance(Duration::from_millis(16));
+        schedule.run(&mut world, &time);
+
+        assert_eq!(
+            schedule.system_names().collect::<Vec<_>>(),
+            ["first", "second"]
+        );
+        assert_eq!(
+            world.resource::<Vec<&str>>(),
+            Some(&vec!["first", "second"])
+        );
+    }
+
+    #[test]
+    fn fixed_systems_run_every_fixed_step() {
+        let mut world = World::new();
+        world.insert_resource(Vec::<&'static str>::new());
+
+        let mut schedule = Schedule::new();
+        schedule
+            .add_fixed_system("fixed", |world, _| {
+                world.resource_mut::<Vec<&str>>().unwrap().push("fixed")
+            })
+            .add_system("frame", |world, _| {
+                world.resource_mut::<Vec<&str>>().unwrap().push("frame")
+            });
+        let mut time = Time::new();
+        time.set_fixed_delta(Duration::from_millis(10));
+        time.advance(Duration::from_millis(25));
+        schedule.run(&mut world, &time);
+
+        assert_eq!(
+            world.resource::<Vec<&str>>(),
+            Some(&vec!["frame", "fixed", "fixed"])
+        );
+    }
+}
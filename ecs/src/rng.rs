@@ -0,0 +1,60 @@
+use std::ops::Range;
+
+/// A small, fast random number generator, giving the same numbers for the
+/// same seed on every platform. Not meant for anything security related.
+///
+/// Game logic which has to be [deterministic](crate::Simulation) takes its
+/// random numbers from the [Rng] resource of the simulated world.
+///
+/// ```
+/// # use ecs::Rng;
+/// let mut first = Rng::new(7);
+/// let mut second = Rng::new(7);
+/// assert_eq!(first.next_u64(), second.next_u64());
+///
+/// let value = first.range(&(2.0..3.0));
+/// assert!((2.0..3.0).contains(&value));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    /// SplitMix64.
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniformly in `[0, 1)`.
+    pub fn unit(&mut self) -> f32 {
+        // The 24 bits an `f32` can represent exactly.
+        (self.next_u64() >> 40) as f32 / (1 << 24) as f32
+    }
+
+    /// Uniformly in `range`.
+    pub fn range(&mut self, range: &Range<f32>) -> f32 {
+        range.start + (range.end - range.start) * self.unit()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Rng;
+
+    #[test]
+    fn units_stay_below_one() {
+        let mut rng = Rng::new(42);
+        for _ in 0..1000 {
+            let value = rng.unit();
+            assert!((0.0..1.0).contains(&value));
+        }
+        assert_ne!(Rng::new(1).next_u64(), Rng::new(2).next_u64());
+    }
+}
@@ -0,0 +1,328 @@
+use std::{fmt, hash::Hasher, marker::PhantomData, time::Duration};
+
+use crate::{Rng, Schedule, Time, World};
+
+/// Advances a [World] deterministically, one fixed step at a time.
+///
+/// Every step gets the commands of type `C` issued for it, e.g. the orders
+/// of all players, as a resource of the world. The systems only see the
+/// commands, the fixed [Time] of the step and an [Rng] resource seeded
+/// when the simulation started, never the system clock. They run in the
+/// order of the [Schedule], so the same seed and log of commands always
+/// lead to the same world, which is what replays and lockstep multiplayer
+/// build on. Compare the [checksums](Simulation::checksum) to make sure.
+///
+/// ```
+/// # use ecs::{Schedule, Simulation, World};
+/// # use std::hash::Hash;
+/// struct Score(u32);
+///
+/// let mut world = World::new();
+/// world.insert_resource(Score(0));
+/// let mut schedule = Schedule::new();
+/// schedule.add_fixed_system("score", |world, _| {
+///     let points = *world.resource::<u32>().unwrap();
+///     world.resource_mut::<Score>().unwrap().0 += points;
+/// });
+///
+/// let mut simulation = Simulation::<u32>::new(world, schedule, 1);
+/// simulation.replay([3, 0, 2]);
+/// assert_eq!(simulation.world().resource::<Score>().unwrap().0, 5);
+/// assert_eq!(simulation.tick(), 3);
+///
+/// // Sent to the other players, to check they are still in sync.
+/// let checksum = simulation.checksum(|world, checksum| {
+///     world.resource::<Score>().unwrap().0.hash(checksum)
+/// });
+/// ```
+pub struct Simulation<C> {
+    world: World,
+    schedule: Schedule,
+    time: Time,
+    commands: PhantomData<fn(C)>,
+}
+
+impl<C> fmt::Debug for Simulation<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Simulation")
+            .field("world", &self.world)
+            .field("schedule", &self.schedule)
+            .field("tick", &self.time.frame())
+            .finish()
+    }
+}
+
+impl<C: 'static> Simulation<C> {
+    /// Simulate `world` with the systems of `schedule`, adding an [Rng]
+    /// resource seeded with `seed`.
+    ///
+    /// Steps are 1/60 s long.
+    pub fn new(mut world: World, schedule: Schedule, seed: u64) -> Self {
+        world.insert_resource(Rng::new(seed));
+        Self {
+            world,
+            schedule,
+            time: Time::new(),
+            commands: PhantomData,
+        }
+    }
+
+    /// Use steps of `fixed_delta`, before the first step.
+    pub fn with_fixed_delta(mut self, fixed_delta: Duration) -> Self {
+        self.time.set_fixed_delta(fixed_delta);
+        self
+    }
+
+    pub fn world(&self) -> &World {
+        &self.world
+    }
+
+    /// The time of the last step.
+    pub fn time(&self) -> &Time {
+        &self.time
+    }
+
+    /// The number of steps taken so far.
+    pub fn tick(&self) -> u64 {
+        self.time.frame()
+    }
+
+    /// Run the schedule for one fixed step with the `commands` issued for it.
+    ///
+    /// The commands are a resource of the world while the systems run.
+    pub fn step(&mut self, commands: C) {
+        self.world.insert_resource(commands);
+        self.time.advance_fixed();
+        self.schedule.run(&mut self.world, &self.time);
+        self.world.remove_resource::<C>();
+    }
+
+    /// Take a step for each entry of `log`, in order.
+    pub fn replay(&mut self, log: impl IntoIterator<Item = C>) {
+        for commands in log {
+            self.step(commands);
+        }
+    }
+
+    /// The [Checksum] of the tick, the [Rng] and the state `hash` feeds
+    /// from the world, for comparing simulations step by step.
+    pub fn checksum(&self, hash: impl FnOnce(&World, &mut Checksum)) -> u64 {
+        let mut checksum = Checksum::new();
+        checksum.write_u64(self.tick());
+        if let Some(rng) = self.world.resource::<Rng>() {
+            checksum.write_u64(rng.clone().next_u64());
+        }
+        hash(&self.world, &mut checksum);
+        checksum.finish()
+    }
+}
+
+/// FNV-1a of the bytes written, the same on every platform and run unlike
+/// the hashers of the standard library.
+///
+/// Integers are written in little endian, floats can be added with
+/// [write_f32](Checksum::write_f32).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Checksum(u64);
+
+impl Default for Checksum {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Checksum {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the exact bits of `value`.
+    pub fn write_f32(&mut self, value: f32) {
+        self.write_u32(value.to_bits());
+    }
+}
+
+impl Hasher for Checksum {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn write_u16(&mut self, value: u16) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_u32(&mut self, value: u32) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_u128(&mut self, value: u128) {
+        self.write(&value.to_le_bytes());
+    }
+
+    // As wide on every platform.
+    fn write_usize(&mut self, value: usize) {
+        self.write_u64(value as u64);
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hash::Hash;
+
+    use super::*;
+
+    #[derive(Debug, Clone, Copy)]
+    struct Position([f32; 2]);
+
+    #[derive(Debug, Clone, Copy)]
+    struct Velocity([f32; 2]);
+
+    /// The orders of the players for one step.
+    #[derive(Debug, Clone, Copy)]
+    enum Command {
+        Spawn([f32; 2]),
+        // Pushes every unit away from the point.
+        Scatter([f32; 2]),
+        Halt,
+    }
+
+    fn game(seed: u64) -> Simulation<Vec<Command>> {
+        let mut schedule = Schedule::new();
+        schedule
+            .add_fixed_system("commands", |world, _| {
+                let commands = world.resource::<Vec<Command>>().unwrap().clone();
+                for command in commands {
+                    match command {
+                        Command::Spawn(position) => {
+                            let unit = world.spawn();
+                            world.insert(unit, Position(position));
+                            world.insert(unit, Velocity([0.0, 0.0]));
+                        }
+                        Command::Scatter(from) => {
+                            for (_, velocity) in world.query_mut::<Velocity>() {
+                                velocity.0 = [velocity.0[0] - from[0], velocity.0[1] - from[1]];
+                            }
+                        }
+                        Command::Halt => {
+                            for (_, velocity) in world.query_mut::<Velocity>() {
+                                velocity.0 = [0.0, 0.0];
+                            }
+                        }
+                    }
+                }
+            })
+            .add_fixed_system("wander", |world, _| {
+                let units = world.query::<Velocity>().count();
+                let mut rng = world.remove_resource::<Rng>().unwrap();
+                let nudges = (0..units)
+                    .map(|_| [rng.range(&(-1.0..1.0)), rng.range(&(-1.0..1.0))])
+                    .collect::<Vec<_>>();
+                world.insert_resource(rng);
+                for ((_, velocity), nudge) in world.query_mut::<Velocity>().zip(nudges) {
+                    velocity.0 = [velocity.0[0] + nudge[0], velocity.0[1] + nudge[1]];
+                }
+            })
+            .add_fixed_system("movement", |world, time| {
+                let delta = time.delta().as_secs_f32();
+                let moved = world
+                    .query2::<Position, Velocity>()
+                    .map(|(entity, position, velocity)| {
+                        let [x, y] = position.0;
+                        (
+                            entity,
+                            [x + velocity.0[0] * delta, y + velocity.0[1] * delta],
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                for (entity, position) in moved {
+                    world.insert(entity, Position(position));
+                }
+            });
+        Simulation::new(World::new(), schedule, seed)
+    }
+
+    fn input_log() -> Vec<Vec<Command>> {
+        (0..120)
+            .map(|tick| match tick % 40 {
+                0 => vec![
+                    Command::Spawn([tick as f32, 1.0]),
+                    Command::Spawn([0.0, 0.5]),
+                ],
+                10 => vec![Command::Scatter([2.0, -1.0])],
+                30 => vec![Command::Halt, Command::Spawn([-3.0, 3.0])],
+                _ => Vec::new(),
+            })
+            .collect()
+    }
+
+    fn checksum(simulation: &Simulation<Vec<Command>>) -> u64 {
+        simulation.checksum(|world, checksum| {
+            for (entity, position) in world.query::<Position>() {
+                entity.hash(checksum);
+                for value in position.0 {
+                    checksum.write_f32(value);
+                }
+            }
+            for (entity, velocity) in world.query::<Velocity>() {
+                entity.hash(checksum);
+                for value in velocity.0 {
+                    checksum.write_f32(value);
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn replays_end_in_the_same_state() {
+        let mut recorded = game(7);
+        let mut checksums = Vec::new();
+        for commands in input_log() {
+            recorded.step(commands);
+            checksums.push(checksum(&recorded));
+        }
+
+        // Replayed step by step, as a lockstep peer would.
+        let mut replayed = game(7);
+        for (commands, expected) in input_log().into_iter().zip(&checksums) {
+            replayed.step(commands);
+            assert_eq!(
+                checksum(&replayed),
+                *expected,
+                "diverged at tick {}",
+                replayed.tick()
+            );
+        }
+        assert_eq!(replayed.world().len(), 9);
+
+        // Another seed wanders differently.
+        let mut other = game(8);
+        other.replay(input_log());
+        assert_ne!(checksum(&other), *checksums.last().unwrap());
+    }
+
+    #[test]
+    fn checksums_are_stable() {
+        let mut checksum = Checksum::new();
+        checksum.write(b"voxon");
+        assert_eq!(checksum.finish(), 0xef25_6482_3634_a977);
+
+        let mut simulation = game(1);
+        simulation.replay(input_log());
+        assert_eq!(simulation.tick(), 120);
+        assert_eq!(
+            simulation.time().elapsed(),
+            Duration::from_secs(1) / 60 * 120
+        );
+    }
+}
@@ -0,0 +1,257 @@
+use std::time::Duration;
+
+use web_time::Instant;
+
+/// The most fixed steps a single frame may run, so a long stall doesn't
+/// trigger an ever growing number of catch-up steps.
+const MAX_FIXED_STEPS: u32 = 8;
+
+/// Clock of the running application, passed to every system.
+///
+/// Game time is real time multiplied by the [scale](Time::scale), and
+/// stops while [paused](Time::paused). The real time is still available
+/// for things which keep going regardless, like the camera or UI.
+///
+/// Besides the variable frame delta, the clock counts the fixed size
+/// steps of [fixed_delta](Time::fixed_delta) due this frame, for logic
+/// which has to advance at a constant rate, like physics.
+///
+/// ```
+/// # use std::time::Duration;
+/// # use ecs::Time;
+/// let mut time = Time::new();
+/// time.set_scale(0.5);
+/// time.advance(Duration::from_millis(40));
+///
+/// assert_eq!(time.delta(), Duration::from_millis(20));
+/// assert_eq!(time.real_delta(), Duration::from_millis(40));
+/// assert_eq!(time.frame(), 1);
+/// // One 1/60 s step fits into the 20 ms of game time.
+/// assert_eq!(time.fixed_steps(), 1);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Time {
+    startup: Instant,
+    last_update: Instant,
+    frame: u64,
+    real_delta: Duration,
+    real_elapsed: Duration,
+    delta: Duration,
+    elapsed: Duration,
+    scale: f32,
+    paused: bool,
+    fixed_delta: Duration,
+    // Game time not yet consumed by fixed steps.
+    accumulator: Duration,
+    fixed_steps: u32,
+}
+
+impl Default for Time {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Time {
+    /// Start the clock, no time has passed yet.
+    ///
+    /// Fixed steps are 1/60 s long.
+    pub fn new() -> Self {
+        let now = Instant::now();
+        Self {
+            startup: now,
+            last_update: now,
+            frame: 0,
+            real_delta: Duration::ZERO,
+            real_elapsed: Duration::ZERO,
+            delta: Duration::ZERO,
+            elapsed: Duration::ZERO,
+            scale: 1.0,
+            paused: false,
+            fixed_delta: Duration::from_secs(1) / 60,
+            accumulator: Duration::ZERO,
+            fixed_steps: 0,
+        }
+    }
+
+    /// Start a new frame, advancing by the real time since the last update.
+    pub fn update(&mut self) {
+        let now = Instant::now();
+        let real_delta = now.duration_since(self.last_update);
+        self.last_update = now;
+        self.advance(real_delta);
+    }
+
+    /// Start a new frame, advancing by `real_delta`.
+    ///
+    /// Unlike [update](Time::update) it doesn't look at the system clock,
+    /// which makes it useful for headless rendering and tests.
+    pub fn advance(&mut self, real_delta: Duration) {
+        self.frame += 1;
+        self.real_delta = real_delta;
+        self.real_elapsed += real_delta;
+
+        self.delta = if self.paused {
+            Duration::ZERO
+        } else {
+            real_delta.mul_f32(self.scale)
+        };
+        self.elapsed += self.delta;
+
+        self.accumulator += self.delta;
+        self.fixed_steps = 0;
+        while self.accumulator >= self.fixed_delta && self.fixed_steps < MAX_FIXED_STEPS {
+            self.accumulator -= self.fixed_delta;
+            self.fixed_steps += 1;
+        }
+        // Drop what couldn't be caught up with.
+        if self.fixed_steps == MAX_FIXED_STEPS {
+            self.accumulator = self.accumulator.min(self.fixed_delta);
+        }
+    }
+
+    /// Start a new frame of exactly one fixed step, ignoring the scale and
+    /// pausing, for [deterministic](crate::Simulation) updates.
+    ///
+    /// The real time advances by the step as well, nothing is read from the
+    /// system clock.
+    pub fn advance_fixed(&mut self) {
+        self.frame += 1;
+        self.real_delta = self.fixed_delta;
+        self.real_elapsed += self.fixed_delta;
+        self.delta = self.fixed_delta;
+        self.elapsed += self.fixed_delta;
+        self.fixed_steps = 1;
+    }
+
+    /// When the clock was started.
+    pub fn startup(&self) -> Instant {
+        self.startup
+    }
+
+    /// The number of frames started so far.
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// Game time passed during the last frame.
+    pub fn delta(&self) -> Duration {
+        self.delta
+    }
+
+    /// Game time passed since startup.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Real time passed during the last frame, ignoring the scale and pausing.
+    pub fn real_delta(&self) -> Duration {
+        self.real_delta
+    }
+
+    /// Real time passed since startup, ignoring the scale and pausing.
+    pub fn real_elapsed(&self) -> Duration {
+        self.real_elapsed
+    }
+
+    /// How much faster game time passes than real time.
+    pub fn scale(&self) -> f32 {
+        self.scale
+    }
+
+    /// Slow down or speed up game time, negative scales are treated as 0.
+    pub fn set_scale(&mut self, scale: f32) {
+        self.scale = scale.max(0.0);
+    }
+
+    pub fn paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Stop or resume game time, starting with the next frame.
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+
+    /// The length of a fixed step in game time.
+    pub fn fixed_delta(&self) -> Duration {
+        self.fixed_delta
+    }
+
+    pub fn set_fixed_delta(&mut self, fixed_delta: Duration) {
+        assert!(!fixed_delta.is_zero(), "fixed steps can't be empty");
+        self.fixed_delta = fixed_delta;
+    }
+
+    /// The number of fixed steps to run this frame.
+    pub fn fixed_steps(&self) -> u32 {
+        self.fixed_steps
+    }
+
+    /// How far game time is into the next fixed step in the range `[0, 1)`,
+    /// for interpolating between the last two steps.
+    pub fn fixed_overstep(&self) -> f32 {
+        self.accumulator.as_secs_f32() / self.fixed_delta.as_secs_f32()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{MAX_FIXED_STEPS, Time};
+
+    #[test]
+    fn pausing_stops_game_time_only() {
+        let mut time = Time::new();
+        time.advance(Duration::from_millis(10));
+        time.set_paused(true);
+        time.advance(Duration::from_millis(10));
+
+        assert_eq!(time.delta(), Duration::ZERO);
+        assert_eq!(time.elapsed(), Duration::from_millis(10));
+        assert_eq!(time.real_elapsed(), Duration::from_millis(20));
+        assert_eq!(time.fixed_steps(), 0);
+        assert_eq!(time.frame(), 2);
+    }
+
+    #[test]
+    fn fixed_steps_carry_the_remainder() {
+        let mut time = Time::new();
+        time.set_fixed_delta(Duration::from_millis(10));
+
+        time.advance(Duration::from_millis(15));
+        assert_eq!(time.fixed_steps(), 1);
+        assert_eq!(time.fixed_overstep(), 0.5);
+
+        time.advance(Duration::from_millis(15));
+        assert_eq!(time.fixed_steps(), 2);
+        assert_eq!(time.fixed_overstep(), 0.0);
+    }
+
+    #[test]
+    fn fixed_frames_are_one_step_long() {
+        let mut time = Time::new();
+        time.set_scale(0.5);
+        time.set_paused(true);
+        time.advance_fixed();
+        time.advance_fixed();
+
+        assert_eq!(time.fixed_steps(), 1);
+        assert_eq!(time.delta(), time.fixed_delta());
+        assert_eq!(time.elapsed(), time.fixed_delta() * 2);
+        assert_eq!(time.fixed_overstep(), 0.0);
+        assert_eq!(time.frame(), 2);
+    }
+
+    #[test]
+    fn long_stalls_are_not_caught_up() {
+        let mut time = Time::new();
+        time.set_fixed_delta(Duration::from_millis(10));
+
+        time.advance(Duration::from_secs(1));
+        assert_eq!(time.fixed_steps(), MAX_FIXED_STEPS);
+        time.advance(Duration::ZERO);
+        assert!(time.fixed_steps() <= 1);
+    }
+}
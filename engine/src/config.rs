@@ -0,0 +1,419 @@
+//! Settings kept between runs in a TOML file.
+//!
+//! Every setting is optional, the ones missing from the file keep their
+//! defaults:
+//! ```toml
+//! [window]
+//! width = 1280
+//! height = 720
+//! fullscreen = false
+//! vsync = true
+//!
+//! [camera]
+//! # The horizontal field of view in degrees.
+//! fov = 100.0
+//!
+//! # How the main window renders while in the background, "render",
+//! # "throttle" to the `throttled_fps` or "pause".
+//! [pacing]
+//! unfocused = "throttle"
+//! minimized = "pause"
+//! throttled_fps = 15
+//!
+//! [mouse_look]
+//! sensitivity = 0.01
+//! invert_y = true
+//!
+//! # Replaces the default bindings of the listed actions, see `input::Input::load_bindings`.
+//! [bindings]
+//! move_forward = ["KeyZ", "ArrowUp"]
+//! ```
+//!
+//! The apps read the settings on startup through the accessors of [Config],
+//! and set them when the user changes them. [save](Config::save) only writes
+//! the file if something changed.
+
+use std::{
+    collections::BTreeMap,
+    fmt, io,
+    path::{Path, PathBuf},
+};
+
+use input::{Action, Binding, BindingsFile, Input, MouseLook};
+use lina::angle::Deg;
+use serde::{Deserialize, Serialize};
+use winit::dpi::LogicalSize;
+
+use crate::pacing::PacingSettings;
+
+/// The settings of an app and the file they are kept in.
+///
+/// The [default](Config::default) has no file, it is never saved.
+#[derive(Debug, Clone, Default)]
+pub struct Config {
+    path: Option<PathBuf>,
+    settings: Settings,
+    changed: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct Settings {
+    window: WindowSettings,
+    camera: CameraSettings,
+    pacing: PacingSettings,
+    mouse_look: MouseLook,
+    bindings: BTreeMap<Action, Vec<Binding>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct WindowSettings {
+    // Logical pixels, the physical size depends on the scale factor of the monitor.
+    width: u32,
+    height: u32,
+    fullscreen: bool,
+    vsync: bool,
+}
+
+impl Default for WindowSettings {
+    fn default() -> Self {
+        Self {
+            width: 1024,
+            height: 768,
+            fullscreen: false,
+            vsync: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct CameraSettings {
+    // Degrees, the unit people know their field of view in.
+    fov: f32,
+}
+
+impl Default for CameraSettings {
+    fn default() -> Self {
+        Self { fov: 90.0 }
+    }
+}
+
+impl Config {
+    /// Load the settings from the file at `path`, which they are saved to later.
+    ///
+    /// A missing file gives the defaults, it is created on the first save.
+    /// On platforms without a file system, like the browser, the defaults are
+    /// used as well.
+    pub fn load(path: impl Into<PathBuf>) -> Result<Self, ConfigError> {
+        let path = path.into();
+        let settings = match std::fs::read_to_string(&path) {
+            Ok(source) => toml::from_str(&source).map_err(ConfigError::Parse)?,
+            Err(err)
+                if matches!(
+                    err.kind(),
+                    io::ErrorKind::NotFound | io::ErrorKind::Unsupported
+                ) =>
+            {
+                Settings::default()
+            }
+            Err(err) => return Err(ConfigError::Io(err)),
+        };
+        Ok(Self {
+            path: Some(path),
+            settings,
+            changed: false,
+        })
+    }
+
+    /// Write the settings back to the file they were loaded from, if any of
+    /// them changed since.
+    pub fn save(&mut self) -> Result<(), ConfigError> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if !self.changed {
+            return Ok(());
+        }
+        let source = toml::to_string_pretty(&self.settings).map_err(ConfigError::Write)?;
+        std::fs::write(path, source).map_err(ConfigError::Io)?;
+        self.changed = false;
+        Ok(())
+    }
+
+    /// The file the settings are saved to, `None` for the [default](Config::default).
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Whether any setting changed since the last [save](Config::save).
+    pub fn changed(&self) -> bool {
+        self.changed
+    }
+
+    /// The inner size of the main window.
+    pub fn window_size(&self) -> LogicalSize<u32> {
+        let window = &self.settings.window;
+        LogicalSize::new(window.width, window.height)
+    }
+
+    pub fn set_window_size(&mut self, size: LogicalSize<u32>) {
+        self.update(|settings| &mut settings.window.width, size.width);
+        self.update(|settings| &mut settings.window.height, size.height);
+    }
+
+    /// Whether the main window covers the whole monitor.
+    pub fn fullscreen(&self) -> bool {
+        self.settings.window.fullscreen
+    }
+
+    pub fn set_fullscreen(&mut self, fullscreen: bool) {
+        self.update(|settings| &mut settings.window.fullscreen, fullscreen);
+    }
+
+    /// Whether presenting frames waits for the vertical sync.
+    pub fn vsync(&self) -> bool {
+        self.settings.window.vsync
+    }
+
+    pub fn set_vsync(&mut self, vsync: bool) {
+        self.update(|settings| &mut settings.window.vsync, vsync);
+    }
+
+    /// The horizontal field of view of the main camera.
+    pub fn fov(&self) -> Deg {
+        Deg(self.settings.camera.fov)
+    }
+
+    pub fn set_fov(&mut self, fov: impl Into<Deg>) {
+        let Deg(fov) = fov.into();
+        self.update(|settings| &mut settings.camera.fov, fov);
+    }
+
+    /// How often the main window renders while in the background.
+    pub fn pacing(&self) -> PacingSettings {
+        self.settings.pacing
+    }
+
+    pub fn set_pacing(&mut self, pacing: PacingSettings) {
+        self.update(|settings| &mut settings.pacing, pacing);
+    }
+
+    pub fn mouse_look(&self) -> MouseLook {
+        self.settings.mouse_look
+    }
+
+    pub fn set_mouse_look(&mut self, mouse_look: MouseLook) {
+        self.update(|settings| &mut settings.mouse_look, mouse_look);
+    }
+
+    /// The bindings replacing the defaults of `action`, `None` if it keeps
+    /// its defaults.
+    pub fn bindings(&self, action: Action) -> Option<&[Binding]> {
+        self.settings.bindings.get(&action).map(Vec::as_slice)
+    }
+
+    /// Replace the default bindings of `action`.
+    pub fn set_bindings(&mut self, action: Action, bindings: Vec<Binding>) {
+        if self.bindings(action) != Some(&bindings) {
+            self.settings.bindings.insert(action, bindings);
+            self.changed = true;
+        }
+    }
+
+    /// Whether the settings replace the default bindings of any action.
+    pub fn has_bindings(&self) -> bool {
+        !self.settings.bindings.is_empty()
+    }
+
+    /// Take over the bindings and the [MouseLook] of a bindings `file`,
+    /// e.g. one written before the settings had bindings.
+    pub fn import_bindings(&mut self, file: BindingsFile) {
+        if let Some(mouse_look) = file.mouse_look {
+            self.set_mouse_look(mouse_look);
+        }
+        for (action, bindings) in file.actions {
+            self.set_bindings(action, bindings);
+        }
+    }
+
+    /// Set up `input` with the bindings and the [MouseLook] of the settings.
+    ///
+    /// The actions without bindings in the settings keep the ones of `input`.
+    pub fn apply_to_input(&self, input: &mut Input) {
+        for (action, bindings) in &self.settings.bindings {
+            input.rebind(*action, bindings);
+        }
+        input.set_mouse_look(self.settings.mouse_look);
+    }
+
+    /// Set the `field` of the settings to `value`, comparing only that
+    /// field, as some are set every frame.
+    fn update<T: PartialEq>(&mut self, field: impl FnOnce(&mut Settings) -> &mut T, value: T) {
+        let field = field(&mut self.settings);
+        if *field != value {
+            *field = value;
+            self.changed = true;
+        }
+    }
+}
+
+/// Why the settings could not be loaded or saved.
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(io::Error),
+    /// The file is not valid TOML or has unknown settings.
+    Parse(toml::de::Error),
+    Write(toml::ser::Error),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(err) => write!(f, "{err}"),
+            ConfigError::Parse(err) => write!(f, "invalid settings file: {err}"),
+            ConfigError::Write(err) => write!(f, "failed to write the settings: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ConfigError::Io(err) => Some(err),
+            ConfigError::Parse(err) => Some(err),
+            ConfigError::Write(err) => Some(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use winit::keyboard::KeyCode;
+
+    use super::*;
+    use crate::pacing::Inactive;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("engine-{name}-{}.toml", std::process::id()))
+    }
+
+    #[test]
+    fn missing_settings_keep_their_defaults() {
+        let path = temp_path("partial");
+        std::fs::write(
+            &path,
+            "[window]\nvsync = false\n\n[camera]\nfov = 75.0\n\n[pacing]\nunfocused = \"pause\"\n",
+        )
+        .unwrap();
+        let config = Config::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(!config.vsync());
+        assert_eq!(config.fov(), Deg(75.0));
+        assert_eq!(config.window_size(), LogicalSize::new(1024, 768));
+        assert_eq!(config.mouse_look(), MouseLook::default());
+        assert_eq!(config.pacing().unfocused, Inactive::Pause);
+        assert_eq!(config.pacing().minimized, Inactive::Pause);
+        assert!(!config.changed());
+    }
+
+    #[test]
+    fn changes_are_written_back() {
+        let path = temp_path("round-trip");
+        let mut config = Config::load(&path).unwrap();
+        // Nothing changed, so the file isn't created.
+        config.save().unwrap();
+        assert!(!path.exists());
+
+        config.set_fullscreen(true);
+        config.set_window_size(LogicalSize::new(1280, 720));
+        config.set_mouse_look(MouseLook {
+            invert_y: true,
+            ..MouseLook::default()
+        });
+        config.set_bindings(Action::Jump, vec![Binding::Key(KeyCode::KeyJ)]);
+        assert!(config.changed());
+        config.save().unwrap();
+        assert!(!config.changed());
+        config.set_bindings(Action::Jump, vec![Binding::Key(KeyCode::KeyJ)]);
+        assert!(!config.changed());
+
+        let loaded = Config::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(loaded.fullscreen());
+        assert_eq!(loaded.window_size(), LogicalSize::new(1280, 720));
+        assert!(loaded.mouse_look().invert_y);
+        assert_eq!(
+            loaded.bindings(Action::Jump),
+            Some([Binding::Key(KeyCode::KeyJ)].as_slice())
+        );
+        assert_eq!(loaded.bindings(Action::Sprint), None);
+    }
+
+    #[test]
+    fn bindings_files_are_imported() {
+        let mut config = Config::default();
+        assert!(!config.has_bindings());
+        let file = BindingsFile::parse(
+            r#"
+            move_forward = ["KeyZ"]
+
+            [mouse_look]
+            invert_y = true
+            "#,
+        )
+        .unwrap();
+        config.import_bindings(file);
+
+        assert!(config.changed());
+        assert!(config.has_bindings());
+        assert_eq!(
+            config.bindings(Action::MoveForward),
+            Some([Binding::Key(KeyCode::KeyZ)].as_slice())
+        );
+        assert!(config.mouse_look().invert_y);
+    }
+
+    #[test]
+    fn setting_the_same_value_is_no_change() {
+        let mut config = Config::default();
+        config.set_vsync(true);
+        config.set_fov(Deg(90.0));
+
+        assert!(!config.changed());
+    }
+
+    #[test]
+    fn invalid_files_are_rejected() {
+        let path = temp_path("invalid");
+        std::fs::write(&path, "[window]\nvsnyc = false\n").unwrap();
+        let result = Config::load(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(matches!(result, Err(ConfigError::Parse(_))));
+    }
+
+    #[test]
+    fn input_gets_the_bindings_and_mouse_look() {
+        let mut config = Config::default();
+        config.set_bindings(Action::MoveForward, vec![Binding::Key(KeyCode::ArrowUp)]);
+        config.set_mouse_look(MouseLook {
+            sensitivity: 0.01,
+            ..MouseLook::default()
+        });
+        let mut input = Input::new();
+        config.apply_to_input(&mut input);
+
+        assert_eq!(
+            input.bindings(Action::MoveForward).collect::<Vec<_>>(),
+            [Binding::Key(KeyCode::ArrowUp)]
+        );
+        assert_eq!(
+            input.bindings(Action::MoveBackward).collect::<Vec<_>>(),
+            [Binding::Key(KeyCode::KeyS)]
+        );
+        assert_eq!(input.mouse_look().sensitivity, 0.01);
+    }
+}
@@ -0,0 +1,239 @@
+//! A typed event bus, letting the parts of an app tell each other what
+//! happened without knowing who listens.
+//!
+//! Any `'static` type can be an event. Events are published into a queue of
+//! their type on the [Events] bus, and every [Subscriber] of the type reads
+//! each of them once. The queues keep the events of the current and the last
+//! frame, so a subscriber reading once per frame sees all events, no matter
+//! whether it reads before or after they are published.
+//! ```
+//! # use engine::events::Events;
+//! struct Resized(u32, u32);
+//!
+//! let mut events = Events::new();
+//! let mut renderer = events.subscribe::<Resized>();
+//!
+//! // In the window code:
+//! events.publish(Resized(800, 600));
+//!
+//! // In the renderer:
+//! for Resized(width, height) in events.read(&mut renderer) {
+//!     assert_eq!((*width, *height), (800, 600));
+//! }
+//! assert_eq!(events.read(&mut renderer).count(), 0);
+//!
+//! events.end_frame();
+//! ```
+
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    fmt,
+    marker::PhantomData,
+};
+
+/// Type erased access to a [Queue], used when a frame ends.
+trait AnyQueue: Any {
+    fn end_frame(&mut self);
+}
+
+/// The events of one type published in the last and the current frame.
+struct Queue<E> {
+    previous: Vec<E>,
+    current: Vec<E>,
+    // The id of the first event in `previous`, ids count all events ever published.
+    first_id: u64,
+}
+
+impl<E> Queue<E> {
+    fn end_id(&self) -> u64 {
+        self.first_id + (self.previous.len() + self.current.len()) as u64
+    }
+}
+
+impl<E: 'static> AnyQueue for Queue<E> {
+    fn end_frame(&mut self) {
+        self.first_id += self.previous.len() as u64;
+        self.previous = std::mem::take(&mut self.current);
+    }
+}
+
+/// The event queues of an app, one for each event type.
+#[derive(Default)]
+pub struct Events {
+    queues: HashMap<TypeId, Box<dyn AnyQueue>>,
+}
+
+impl fmt::Debug for Events {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Events")
+            .field("event_types", &self.queues.len())
+            .finish()
+    }
+}
+
+impl Events {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue `event` for the subscribers of its type.
+    pub fn publish<E: 'static>(&mut self, event: E) {
+        self.queue_mut::<E>().current.push(event);
+    }
+
+    /// Start listening to the events of type `E`, the ones published before
+    /// aren't read.
+    pub fn subscribe<E: 'static>(&self) -> Subscriber<E> {
+        Subscriber {
+            next_id: self.queue::<E>().map_or(0, Queue::end_id),
+            event: PhantomData,
+        }
+    }
+
+    /// The events of type `E` `subscriber` hasn't read yet, in the order they
+    /// were published.
+    ///
+    /// Events older than the last frame are gone, a subscriber not reading
+    /// for two frames misses them.
+    pub fn read<'a, E: 'static>(
+        &'a self,
+        subscriber: &mut Subscriber<E>,
+    ) -> impl Iterator<Item = &'a E> + 'a {
+        let (previous, current): (&[E], &[E]) = match self.queue::<E>() {
+            Some(queue) => {
+                let skip = subscriber.next_id.saturating_sub(queue.first_id) as usize;
+                subscriber.next_id = queue.end_id();
+                let (previous, current) = (&queue.previous, &queue.current);
+                let skip_previous = skip.min(previous.len());
+                (
+                    &previous[skip_previous..],
+                    &current[(skip - skip_previous).min(current.len())..],
+                )
+            }
+            None => (&[], &[]),
+        };
+        previous.iter().chain(current)
+    }
+
+    /// Drop the events of the last frame, the ones of the current frame
+    /// stay readable during the next one.
+    ///
+    /// Meant to be called once at the end of every frame.
+    pub fn end_frame(&mut self) {
+        for queue in self.queues.values_mut() {
+            queue.end_frame();
+        }
+    }
+
+    fn queue<E: 'static>(&self) -> Option<&Queue<E>> {
+        let queue: &dyn Any = self.queues.get(&TypeId::of::<E>())?.as_ref();
+        queue.downcast_ref()
+    }
+
+    fn queue_mut<E: 'static>(&mut self) -> &mut Queue<E> {
+        let queue = self.queues.entry(TypeId::of::<E>()).or_insert_with(|| {
+            Box::new(Queue::<E> {
+                previous: Vec::new(),
+                current: Vec::new(),
+                first_id: 0,
+            })
+        });
+        let queue: &mut dyn Any = queue.as_mut();
+        queue
+            .downcast_mut()
+            .expect("queues are stored under their own type id")
+    }
+}
+
+/// Reads the events of type `E` from [Events], remembering which ones it
+/// has seen.
+pub struct Subscriber<E> {
+    next_id: u64,
+    // Only marks the event type, subscribers can be sent whatever `E` is.
+    event: PhantomData<fn() -> E>,
+}
+
+impl<E> fmt::Debug for Subscriber<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Subscriber")
+            .field("event", &std::any::type_name::<E>())
+            .field("next_id", &self.next_id)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Resized(u32);
+
+    #[derive(Debug, PartialEq)]
+    struct Selected(&'static str);
+
+    #[test]
+    fn subscribers_read_their_own_event_type() {
+        let mut events = Events::new();
+        let mut resized = events.subscribe::<Resized>();
+        let mut selected = events.subscribe::<Selected>();
+        events.publish(Resized(1));
+        events.publish(Selected("unit"));
+        events.publish(Resized(2));
+
+        assert_eq!(
+            events.read(&mut resized).collect::<Vec<_>>(),
+            [&Resized(1), &Resized(2)]
+        );
+        assert_eq!(
+            events.read(&mut selected).collect::<Vec<_>>(),
+            [&Selected("unit")]
+        );
+    }
+
+    #[test]
+    fn every_subscriber_reads_each_event_once() {
+        let mut events = Events::new();
+        let mut early = events.subscribe::<Resized>();
+        let mut late = events.subscribe::<Resized>();
+        events.publish(Resized(1));
+        assert_eq!(events.read(&mut early).count(), 1);
+        events.publish(Resized(2));
+        events.end_frame();
+
+        // Reading before the events of the frame are published.
+        assert_eq!(events.read(&mut early).collect::<Vec<_>>(), [&Resized(2)]);
+        events.publish(Resized(3));
+        assert_eq!(
+            events.read(&mut late).collect::<Vec<_>>(),
+            [&Resized(1), &Resized(2), &Resized(3)]
+        );
+        assert_eq!(events.read(&mut late).count(), 0);
+    }
+
+    #[test]
+    fn events_are_kept_for_one_more_frame() {
+        let mut events = Events::new();
+        events.publish(Resized(1));
+        let mut subscriber = events.subscribe::<Resized>();
+        events.publish(Resized(2));
+        events.end_frame();
+        events.publish(Resized(3));
+        events.end_frame();
+
+        // The events of the first frame are gone.
+        assert_eq!(
+            events.read(&mut subscriber).collect::<Vec<_>>(),
+            [&Resized(3)]
+        );
+    }
+
+    #[test]
+    fn reading_unpublished_events() {
+        let events = Events::new();
+        let mut subscriber = events.subscribe::<Resized>();
+
+        assert_eq!(events.read(&mut subscriber).count(), 0);
+    }
+}
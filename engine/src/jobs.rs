@@ -0,0 +1,295 @@
+//! Running work like meshing, pathfinding or loading assets on a pool of
+//! worker threads, so the main thread is free to render.
+//!
+//! The workers steal jobs from each other's queues, so they stay busy as
+//! long as there is work. Results are handed back to the thread which
+//! spawned the jobs, which uploads them to the GPU, as wgpu resources are
+//! best submitted from one thread.
+//! ```
+//! # use engine::jobs::Jobs;
+//! let jobs = Jobs::new(2);
+//!
+//! // Loading in the background, picked up by a later frame.
+//! let mut loading = jobs.spawn(|| vec![1, 2, 3]);
+//!
+//! // Splitting up the work of this frame.
+//! let squares = jobs.map(&[1, 2, 3], |value| value * value);
+//! assert_eq!(squares, [1, 4, 9]);
+//!
+//! let mut sums = [0, 0];
+//! jobs.scope(|scope| {
+//!     let [first, second] = &mut sums;
+//!     scope.spawn(|| *first = squares.iter().sum());
+//!     scope.spawn(|| *second = squares.len());
+//! });
+//! assert_eq!(sums, [14, 3]);
+//!
+//! let loaded = loop {
+//!     match loading.take() {
+//!         Some(loaded) => break loaded,
+//!         // Render a frame in the meantime.
+//!         None => std::thread::yield_now(),
+//!     }
+//! };
+//! assert_eq!(loaded, [1, 2, 3]);
+//! ```
+//!
+//! Where there are no threads, like in the browser, the [inline](Jobs::inline)
+//! pool runs the jobs on the thread asking for their results.
+
+use std::{
+    any::Any,
+    fmt,
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        Arc,
+        mpsc::{self, Receiver, TryRecvError},
+    },
+    thread,
+};
+
+use rayon::{ThreadPool, ThreadPoolBuilder, prelude::*};
+
+/// A handle to the worker threads, cheap to clone and share with the parts
+/// of an app spawning jobs.
+#[derive(Clone)]
+pub struct Jobs {
+    // `None` runs the jobs inline.
+    pool: Option<Arc<ThreadPool>>,
+}
+
+impl fmt::Debug for Jobs {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Jobs")
+            .field("threads", &self.threads())
+            .finish()
+    }
+}
+
+impl Default for Jobs {
+    /// A worker for every core but the one of the main thread, or an
+    /// [inline](Jobs::inline) pool where there are no threads.
+    fn default() -> Self {
+        if cfg!(target_arch = "wasm32") {
+            return Self::inline();
+        }
+        let cores = thread::available_parallelism().map_or(1, |cores| cores.get());
+        Self::new(cores.saturating_sub(1).max(1))
+    }
+}
+
+impl Jobs {
+    /// Start a pool of `threads` workers.
+    pub fn new(threads: usize) -> Self {
+        let pool = ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .thread_name(|index| format!("job worker {index}"))
+            .build()
+            .expect("failed to spawn the job worker threads");
+        Self {
+            pool: Some(Arc::new(pool)),
+        }
+    }
+
+    /// A pool without workers, running every job on the calling thread.
+    pub fn inline() -> Self {
+        Self { pool: None }
+    }
+
+    /// The number of worker threads, 0 for an [inline](Jobs::inline) pool.
+    pub fn threads(&self) -> usize {
+        self.pool
+            .as_ref()
+            .map_or(0, |pool| pool.current_num_threads())
+    }
+
+    /// Run `job` on a worker, its result is picked up with the returned [Task].
+    ///
+    /// Without workers the job runs once its result is asked for.
+    pub fn spawn<T: Send + 'static>(&self, job: impl FnOnce() -> T + Send + 'static) -> Task<T> {
+        let Some(pool) = &self.pool else {
+            return Task {
+                state: TaskState::Deferred(Box::new(job)),
+            };
+        };
+        let (sender, result) = mpsc::channel();
+        pool.spawn(move || {
+            // Panics are handed to the task, instead of aborting in the worker.
+            let _ = sender.send(panic::catch_unwind(AssertUnwindSafe(job)));
+        });
+        Task {
+            state: TaskState::Spawned(result),
+        }
+    }
+
+    /// Run `work` with a [Scope] to spawn jobs borrowing from the caller,
+    /// returning once all of them are done.
+    pub fn scope<'scope, R: Send>(&self, work: impl FnOnce(&Scope<'_, 'scope>) -> R + Send) -> R {
+        match &self.pool {
+            Some(pool) => pool.scope(|scope| work(&Scope { scope: Some(scope) })),
+            None => work(&Scope { scope: None }),
+        }
+    }
+
+    /// Call `job` on every item, spread over the workers.
+    pub fn for_each<T: Send>(&self, items: &mut [T], job: impl Fn(&mut T) + Send + Sync) {
+        match &self.pool {
+            Some(pool) => pool.install(|| items.par_iter_mut().for_each(job)),
+            None => items.iter_mut().for_each(job),
+        }
+    }
+
+    /// The results of `job` for every item in order, spread over the workers.
+    pub fn map<T: Sync, R: Send>(
+        &self,
+        items: &[T],
+        job: impl Fn(&T) -> R + Send + Sync,
+    ) -> Vec<R> {
+        match &self.pool {
+            Some(pool) => pool.install(|| items.par_iter().map(job).collect()),
+            None => items.iter().map(job).collect(),
+        }
+    }
+}
+
+/// Spawns jobs which may borrow from outside of [Jobs::scope].
+pub struct Scope<'a, 'scope> {
+    // `None` runs the jobs inline.
+    scope: Option<&'a rayon::Scope<'scope>>,
+}
+
+impl<'scope> Scope<'_, 'scope> {
+    /// Run `job` on a worker, [Jobs::scope] waits for it to finish.
+    pub fn spawn(&self, job: impl FnOnce() + Send + 'scope) {
+        match self.scope {
+            Some(scope) => scope.spawn(|_| job()),
+            None => job(),
+        }
+    }
+}
+
+/// The result of a job [spawned](Jobs::spawn) on the workers.
+///
+/// Dropping the task doesn't stop the job, its result is dropped instead.
+pub struct Task<T> {
+    state: TaskState<T>,
+}
+
+enum TaskState<T> {
+    // Runs when the result is asked for, without workers.
+    Deferred(Box<dyn FnOnce() -> T + Send>),
+    Spawned(Receiver<thread::Result<T>>),
+    Taken,
+}
+
+impl<T> fmt::Debug for Task<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = match self.state {
+            TaskState::Deferred(_) => "Deferred",
+            TaskState::Spawned(_) => "Spawned",
+            TaskState::Taken => "Taken",
+        };
+        f.debug_struct("Task").field("state", &state).finish()
+    }
+}
+
+impl<T> Task<T> {
+    /// The result if the job is done, `None` while it runs or once the
+    /// result was taken.
+    ///
+    /// A job which panicked panics here.
+    pub fn take(&mut self) -> Option<T> {
+        match std::mem::replace(&mut self.state, TaskState::Taken) {
+            TaskState::Deferred(job) => Some(job()),
+            TaskState::Spawned(result) => match result.try_recv() {
+                Ok(result) => Some(unwind(result)),
+                Err(TryRecvError::Empty) => {
+                    self.state = TaskState::Spawned(result);
+                    None
+                }
+                Err(TryRecvError::Disconnected) => panic!("{DROPPED}"),
+            },
+            TaskState::Taken => None,
+        }
+    }
+
+    /// Block until the job is done and return its result.
+    ///
+    /// # Panics
+    ///
+    /// If the job panicked or the result was [taken](Task::take) already.
+    pub fn wait(self) -> T {
+        match self.state {
+            TaskState::Deferred(job) => job(),
+            TaskState::Spawned(result) => unwind(result.recv().expect(DROPPED)),
+            TaskState::Taken => panic!("the result of the task was taken already"),
+        }
+    }
+}
+
+const DROPPED: &str = "the job was dropped before it ran";
+
+fn unwind<T>(result: Result<T, Box<dyn Any + Send>>) -> T {
+    result.unwrap_or_else(|payload| panic::resume_unwind(payload))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    #[test]
+    fn spawned_jobs_run_on_the_workers() {
+        let jobs = Jobs::new(2);
+        let main = thread::current().id();
+        let task = jobs.spawn(move || thread::current().id() != main);
+
+        assert_eq!(jobs.threads(), 2);
+        assert!(task.wait());
+    }
+
+    #[test]
+    fn inline_jobs_run_when_taken() {
+        let jobs = Jobs::inline();
+        let runs = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&runs);
+        let mut task = jobs.spawn(move || counter.fetch_add(1, Ordering::Relaxed));
+
+        assert_eq!(runs.load(Ordering::Relaxed), 0);
+        assert_eq!(task.take(), Some(0));
+        assert_eq!(task.take(), None);
+        assert_eq!(runs.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn scoped_jobs_borrow_from_the_caller() {
+        for jobs in [Jobs::new(2), Jobs::inline()] {
+            let mut counts = vec![0; 8];
+            jobs.scope(|scope| {
+                for (index, count) in counts.iter_mut().enumerate() {
+                    scope.spawn(move || *count = index * 2);
+                }
+            });
+            assert_eq!(counts, [0, 2, 4, 6, 8, 10, 12, 14]);
+        }
+    }
+
+    #[test]
+    fn parallel_for_keeps_the_order() {
+        for jobs in [Jobs::new(3), Jobs::inline()] {
+            let mut values = (0..100).collect::<Vec<_>>();
+            jobs.for_each(&mut values, |value| *value += 1);
+            assert_eq!(
+                jobs.map(&values, |value| value * 2),
+                (1..=100).map(|value| value * 2).collect::<Vec<_>>()
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "broken job")]
+    fn panics_are_passed_on() {
+        Jobs::new(1).spawn(|| panic!("broken job")).wait();
+    }
+}
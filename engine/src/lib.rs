@@ -0,0 +1,12 @@
+//! Services shared by the apps built on the engine, which aren't about
+//! math or rendering, like the settings they keep between runs, their
+//! windows and how often those are rendered, the events their parts send
+//! each other, the jobs they run in the background or how the players of
+//! a multiplayer game stay in sync.
+
+pub mod config;
+pub mod events;
+pub mod jobs;
+pub mod net;
+pub mod pacing;
+pub mod window;
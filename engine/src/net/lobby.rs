@@ -0,0 +1,348 @@
+use std::{
+    fmt, io,
+    marker::PhantomData,
+    net::{SocketAddr, ToSocketAddrs},
+    time::Duration,
+};
+
+use web_time::Instant;
+
+use super::{
+    Commands, Lockstep, MAX_PLAYERS, NetError, Player, TIMEOUT,
+    protocol::{Message, Socket},
+};
+
+/// How often the lobby is sent to the clients, which measures their round
+/// trip times, and how often clients ask to join.
+const PING_INTERVAL: Duration = Duration::from_millis(200);
+
+/// The fewest ticks commands are scheduled ahead, to make up for jitter.
+const MIN_INPUT_DELAY: u64 = 2;
+
+/// The most ticks commands are scheduled ahead, more makes the game feel
+/// too sluggish to be played anyway.
+const MAX_INPUT_DELAY: u64 = 30;
+
+/// The lobby of a game hosted on this machine, which the other players
+/// [join](Client::join).
+///
+/// Commands of the type `C` are played with.
+pub struct Host<C> {
+    socket: Socket,
+    players: Vec<Player>,
+    // The address of player `index + 1` and when they were last heard from.
+    clients: Vec<(SocketAddr, Instant)>,
+    // Pings are the microseconds since.
+    epoch: Instant,
+    last_ping: Option<Instant>,
+    commands: PhantomData<fn(C)>,
+}
+
+impl<C> fmt::Debug for Host<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Host")
+            .field("socket", &self.socket)
+            .field("players", &self.players)
+            .finish()
+    }
+}
+
+impl<C: Commands> Host<C> {
+    /// Open a lobby on `address`, with the host called `name` as the first
+    /// player.
+    pub fn bind(address: impl ToSocketAddrs, name: impl Into<String>) -> Result<Self, NetError> {
+        Ok(Self {
+            socket: Socket::bind(address)?,
+            players: vec![Player {
+                name: name.into(),
+                rtt: None,
+            }],
+            clients: Vec::new(),
+            epoch: Instant::now(),
+            last_ping: None,
+            commands: PhantomData,
+        })
+    }
+
+    /// Where the clients join, with the port the system chose if it was 0.
+    pub fn local_addr(&self) -> Result<SocketAddr, NetError> {
+        self.socket.local_addr()
+    }
+
+    /// The players in the lobby, starting with the host.
+    pub fn players(&self) -> &[Player] {
+        &self.players
+    }
+
+    /// Let players join and leave, and keep the lobby of every client up
+    /// to date.
+    ///
+    /// Meant to be called every frame. Players which weren't heard from for
+    /// the [TIMEOUT] are dropped, once the lobby is full other players are
+    /// ignored.
+    pub fn poll(&mut self) -> Result<(), NetError> {
+        let now = Instant::now();
+        while let Some((from, message)) = self.socket.receive::<C>()? {
+            let client = self
+                .clients
+                .iter()
+                .position(|(address, _)| *address == from);
+            match (message, client) {
+                (Message::Join { name }, None) if self.players.len() < MAX_PLAYERS => {
+                    self.players.push(Player { name, rtt: None });
+                    self.clients.push((from, now));
+                    // Everyone gets to know the new player right away.
+                    self.last_ping = None;
+                }
+                // The lobby didn't arrive.
+                (Message::Join { .. }, Some(client)) => {
+                    self.clients[client].1 = now;
+                    self.send_lobby(client)?;
+                }
+                (Message::Pong { ping }, Some(client)) => {
+                    self.clients[client].1 = now;
+                    let sent = self.epoch + Duration::from_micros(ping);
+                    self.players[client + 1].rtt = Some(now.saturating_duration_since(sent));
+                }
+                (Message::Leave, Some(client)) => self.remove(client),
+                _ => {}
+            }
+        }
+
+        let mut client = 0;
+        while client < self.clients.len() {
+            if now.duration_since(self.clients[client].1) > TIMEOUT {
+                self.remove(client);
+            } else {
+                client += 1;
+            }
+        }
+
+        if self
+            .last_ping
+            .is_none_or(|last| now.duration_since(last) >= PING_INTERVAL)
+        {
+            self.last_ping = Some(now);
+            for client in 0..self.clients.len() {
+                self.send_lobby(client)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Start the game with the players in the lobby, every player seeds
+    /// their simulation with `seed`.
+    ///
+    /// The input delay is chosen so commands reach every player within it
+    /// when the simulation runs at one step per `tick`.
+    pub fn start(self, seed: u64, tick: Duration) -> Lockstep<C> {
+        let slowest = self
+            .players
+            .iter()
+            .filter_map(|player| player.rtt)
+            .max()
+            .unwrap_or_default();
+        let clients = self.clients.iter().map(|(address, _)| *address).collect();
+        Lockstep::host(
+            self.socket,
+            self.players,
+            clients,
+            seed,
+            input_delay(slowest, tick),
+        )
+    }
+
+    fn send_lobby(&self, client: usize) -> Result<(), NetError> {
+        let ping = Instant::now().duration_since(self.epoch).as_micros() as u64;
+        self.socket.send(
+            self.clients[client].0,
+            &Message::<C>::Lobby {
+                you: client + 1,
+                players: self.players.clone(),
+                ping,
+            },
+        )
+    }
+
+    fn remove(&mut self, client: usize) {
+        self.clients.remove(client);
+        self.players.remove(client + 1);
+        self.last_ping = None;
+    }
+}
+
+/// The ticks commands take to reach every player, when a message takes
+/// `rtt` to the host and back for the slowest one.
+///
+/// A command goes to the host and from there to the other players, about
+/// a round trip, with another tick to spare.
+fn input_delay(rtt: Duration, tick: Duration) -> u64 {
+    let ticks = rtt.as_secs_f64() / tick.as_secs_f64();
+    (ticks.ceil() as u64 + 1).clamp(MIN_INPUT_DELAY, MAX_INPUT_DELAY)
+}
+
+/// A player waiting in the lobby of a [Host] to start the game.
+pub struct Client<C> {
+    socket: Socket,
+    host: SocketAddr,
+    name: String,
+    // The lobby as last sent by the host, `you` is `None` until it arrived.
+    you: Option<usize>,
+    players: Vec<Player>,
+    // The seed and input delay, once the host started the game.
+    start: Option<(u64, u64)>,
+    last_heard: Instant,
+    last_join: Option<Instant>,
+    commands: PhantomData<fn(C)>,
+}
+
+impl<C> fmt::Debug for Client<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Client")
+            .field("socket", &self.socket)
+            .field("host", &self.host)
+            .field("you", &self.you)
+            .field("players", &self.players)
+            .finish()
+    }
+}
+
+impl<C: Commands> Client<C> {
+    /// Ask the host at `host` for a place in its lobby, as the player called
+    /// `name`.
+    pub fn join(host: impl ToSocketAddrs, name: impl Into<String>) -> Result<Self, NetError> {
+        let host = host.to_socket_addrs()?.next().ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, "the host has no address")
+        })?;
+        let any = if host.is_ipv4() {
+            "0.0.0.0:0"
+        } else {
+            "[::]:0"
+        };
+        Ok(Self {
+            socket: Socket::bind(any)?,
+            host,
+            name: name.into(),
+            you: None,
+            players: Vec::new(),
+            start: None,
+            last_heard: Instant::now(),
+            last_join: None,
+            commands: PhantomData,
+        })
+    }
+
+    /// Whether the host let the player in.
+    pub fn joined(&self) -> bool {
+        self.you.is_some()
+    }
+
+    /// The players in the lobby, starting with the host, empty until the
+    /// player [joined](Client::joined).
+    pub fn players(&self) -> &[Player] {
+        &self.players
+    }
+
+    /// Whether the host started the game, which is then played with
+    /// [into_lockstep](Client::into_lockstep).
+    pub fn started(&self) -> bool {
+        self.start.is_some()
+    }
+
+    /// Keep asking to join until the host answers, and keep the lobby up
+    /// to date.
+    ///
+    /// Meant to be called every frame.
+    pub fn poll(&mut self) -> Result<(), NetError> {
+        let now = Instant::now();
+        while let Some((from, message)) = self.socket.receive::<C>()? {
+            if from != self.host {
+                continue;
+            }
+            self.last_heard = now;
+            match message {
+                Message::Lobby { you, players, ping } => {
+                    self.you = Some(you);
+                    self.players = players;
+                    self.socket.send(self.host, &Message::<C>::Pong { ping })?;
+                }
+                Message::Start {
+                    you,
+                    players,
+                    seed,
+                    input_delay,
+                } => {
+                    self.you = Some(you);
+                    self.players = players;
+                    self.start = Some((seed, input_delay));
+                }
+                Message::Leave => return Err(NetError::Left(0)),
+                _ => {}
+            }
+        }
+
+        if now.duration_since(self.last_heard) > TIMEOUT {
+            return Err(NetError::TimedOut(0));
+        }
+        if self.you.is_none()
+            && self
+                .last_join
+                .is_none_or(|last| now.duration_since(last) >= PING_INTERVAL)
+        {
+            self.last_join = Some(now);
+            let name = self.name.clone();
+            self.socket.send(self.host, &Message::<C>::Join { name })?;
+        }
+        Ok(())
+    }
+
+    /// Play the game the host [started](Client::started).
+    ///
+    /// # Panics
+    ///
+    /// If the game didn't start yet.
+    pub fn into_lockstep(self) -> Lockstep<C> {
+        let (Some((seed, input_delay)), Some(you)) = (self.start, self.you) else {
+            panic!("the host didn't start the game yet");
+        };
+        Lockstep::client(self.socket, self.host, you, self.players, seed, input_delay)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::net::poll_until;
+
+    #[test]
+    fn players_join_the_lobby() {
+        let mut host = Host::<u32>::bind("127.0.0.1:0", "Ana").unwrap();
+        let address = host.local_addr().unwrap();
+        let mut ben = Client::<u32>::join(address, "Ben").unwrap();
+        let mut cy = Client::<u32>::join(address, "Cy").unwrap();
+
+        poll_until(|| {
+            host.poll()?;
+            ben.poll()?;
+            cy.poll()?;
+            Ok(ben.players().len() == 3 && cy.players().len() == 3)
+        });
+        let names = |players: &[Player]| {
+            players
+                .iter()
+                .map(|player| player.name.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(host.players()), ["Ana", "Ben", "Cy"]);
+        assert_eq!(names(ben.players()), names(cy.players()));
+        assert!(ben.joined() && !ben.started());
+    }
+
+    #[test]
+    fn input_delay_covers_the_slowest_round_trip() {
+        let tick = Duration::from_secs(1) / 60;
+        assert_eq!(input_delay(Duration::ZERO, tick), MIN_INPUT_DELAY);
+        assert_eq!(input_delay(Duration::from_millis(110), tick), 8);
+        assert_eq!(input_delay(Duration::from_secs(5), tick), MAX_INPUT_DELAY);
+    }
+}
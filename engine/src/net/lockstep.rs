@@ -0,0 +1,612 @@
+use std::{collections::VecDeque, fmt, net::SocketAddr};
+
+use web_time::Instant;
+
+use super::{
+    Commands, NetError, Player, TIMEOUT,
+    protocol::{MAX_BATCH, Message, Socket},
+};
+
+/// How many of its latest checksums the host keeps to compare with the ones
+/// of the clients, which are a few ticks behind.
+const CHECKSUMS: usize = 256;
+
+/// Keeps the simulations of all players of a game in step.
+///
+/// Every tick is a turn with the commands of every player in the order of
+/// the [players](Lockstep::players). A player [submits](Lockstep::submit)
+/// their commands for the tick [input_delay](Lockstep::input_delay) ticks
+/// after the one they just simulated, until then the turn travels to every
+/// player. The first turns only have the default commands.
+///
+/// Created by [Host::start](super::Host::start) and [Client::into_lockstep](super::Client::into_lockstep).
+/// Dropping it leaves the game.
+pub struct Lockstep<C: Commands> {
+    socket: Socket,
+    player: usize,
+    players: Vec<Player>,
+    seed: u64,
+    input_delay: u64,
+    // The turns which arrived but weren't taken, the first one is for `tick`.
+    turns: VecDeque<Vec<C>>,
+    tick: u64,
+    role: Role<C>,
+}
+
+enum Role<C> {
+    Host(Hosting<C>),
+    Client(Joined<C>),
+}
+
+/// What the host keeps track of to collect and hand out the turns.
+struct Hosting<C> {
+    clients: Vec<Remote>,
+    // The commands of every player for the turns after the ones built.
+    commands: Vec<VecDeque<C>>,
+    built: u64,
+    // The built turns some client didn't confirm yet, the first one is for
+    // tick `unconfirmed`.
+    sent: VecDeque<Vec<C>>,
+    unconfirmed: u64,
+    // The latest ticks of the host and their checksums.
+    checksums: VecDeque<(u64, u64)>,
+}
+
+/// A client as seen by the host.
+struct Remote {
+    address: SocketAddr,
+    last_heard: Instant,
+    // Whether the client sent anything since the game started, the start
+    // is sent until it did.
+    started: bool,
+    // The number of commands received from the client.
+    commands: u64,
+    // The number of turns the client confirmed.
+    turns: u64,
+    // The latest tick of the client and its checksum, until the host
+    // reached the tick itself.
+    checksum: Option<(u64, u64)>,
+}
+
+/// What a client keeps track of to send its commands.
+struct Joined<C> {
+    host: SocketAddr,
+    last_heard: Instant,
+    // The commands of the player the host didn't confirm yet, the first one
+    // is for tick `unconfirmed`.
+    outbox: VecDeque<C>,
+    unconfirmed: u64,
+    checksum: Option<(u64, u64)>,
+}
+
+impl<C: Commands> fmt::Debug for Lockstep<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Lockstep")
+            .field("socket", &self.socket)
+            .field("player", &self.player)
+            .field("players", &self.players)
+            .field("input_delay", &self.input_delay)
+            .field("tick", &self.tick)
+            .field("ready_turns", &self.turns.len())
+            .finish()
+    }
+}
+
+impl<C: Commands> Lockstep<C> {
+    pub(super) fn host(
+        socket: Socket,
+        players: Vec<Player>,
+        clients: Vec<SocketAddr>,
+        seed: u64,
+        input_delay: u64,
+    ) -> Self {
+        let now = Instant::now();
+        let hosting = Hosting {
+            clients: clients
+                .into_iter()
+                .map(|address| Remote {
+                    address,
+                    last_heard: now,
+                    started: false,
+                    commands: 0,
+                    turns: 0,
+                    checksum: None,
+                })
+                .collect(),
+            commands: vec![VecDeque::new(); players.len()],
+            built: 0,
+            sent: VecDeque::new(),
+            unconfirmed: 0,
+            checksums: VecDeque::new(),
+        };
+        Self::new(socket, 0, players, seed, input_delay, Role::Host(hosting))
+    }
+
+    pub(super) fn client(
+        socket: Socket,
+        host: SocketAddr,
+        player: usize,
+        players: Vec<Player>,
+        seed: u64,
+        input_delay: u64,
+    ) -> Self {
+        let joined = Joined {
+            host,
+            last_heard: Instant::now(),
+            outbox: VecDeque::new(),
+            unconfirmed: 0,
+            checksum: None,
+        };
+        Self::new(
+            socket,
+            player,
+            players,
+            seed,
+            input_delay,
+            Role::Client(joined),
+        )
+    }
+
+    fn new(
+        socket: Socket,
+        player: usize,
+        players: Vec<Player>,
+        seed: u64,
+        input_delay: u64,
+        role: Role<C>,
+    ) -> Self {
+        let mut lockstep = Self {
+            socket,
+            player,
+            players,
+            seed,
+            input_delay,
+            turns: VecDeque::new(),
+            tick: 0,
+            role,
+        };
+        for _ in 0..input_delay {
+            lockstep.submit(C::default());
+        }
+        lockstep
+    }
+
+    /// The index of this player in the [players](Lockstep::players).
+    pub fn player(&self) -> usize {
+        self.player
+    }
+
+    /// The players of the game, starting with the host.
+    pub fn players(&self) -> &[Player] {
+        &self.players
+    }
+
+    /// The seed every player starts the simulation with.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// How many ticks after being submitted commands are carried out.
+    pub fn input_delay(&self) -> u64 {
+        self.input_delay
+    }
+
+    /// The number of turns taken so far.
+    pub fn tick(&self) -> u64 {
+        self.tick
+    }
+
+    /// How many turns arrived and can be taken right away, more than one
+    /// if the simulation fell behind.
+    pub fn ready_turns(&self) -> usize {
+        self.turns.len()
+    }
+
+    /// The commands of every player for the next tick, `None` until the
+    /// turn arrived.
+    ///
+    /// The simulation waits for the turn instead of going ahead without it.
+    pub fn next_turn(&mut self) -> Option<Vec<C>> {
+        let turn = self.turns.pop_front()?;
+        self.tick += 1;
+        Some(turn)
+    }
+
+    /// Issue the `commands` of this player, carried out [input_delay](Lockstep::input_delay)
+    /// ticks after the last one taken.
+    ///
+    /// Meant to be called once after every [turn taken](Lockstep::next_turn),
+    /// the other players wait for the commands of every tick.
+    pub fn submit(&mut self, commands: C) {
+        match &mut self.role {
+            Role::Host(hosting) => hosting.commands[0].push_back(commands),
+            Role::Client(joined) => joined.outbox.push_back(commands),
+        }
+    }
+
+    /// Compare the state of the simulation after the last turn taken with
+    /// the other players, to notice when they went out of sync.
+    ///
+    /// The `checksum` has to cover everything the simulation depends on,
+    /// like `ecs::Simulation::checksum`.
+    pub fn report_checksum(&mut self, checksum: u64) {
+        let checksum = (self.tick, checksum);
+        match &mut self.role {
+            Role::Host(hosting) => {
+                if hosting.checksums.len() == CHECKSUMS {
+                    hosting.checksums.pop_front();
+                }
+                hosting.checksums.push_back(checksum);
+            }
+            Role::Client(joined) => joined.checksum = Some(checksum),
+        }
+    }
+
+    /// Receive the commands and turns of the other players and send the
+    /// ones they are missing.
+    ///
+    /// Meant to be called every frame. The game can't go on without any of
+    /// the players, or if one of them is out of sync.
+    pub fn poll(&mut self) -> Result<(), NetError> {
+        match &mut self.role {
+            Role::Host(hosting) => hosting.poll(&mut self.socket, &mut self.turns, &self.players),
+            Role::Client(joined) => joined.poll(&mut self.socket, &mut self.turns, self.tick),
+        }?;
+        if let Role::Host(hosting) = &self.role {
+            for (index, remote) in hosting.clients.iter().enumerate() {
+                let message = if remote.started {
+                    let confirmed = (remote.turns - hosting.unconfirmed) as usize;
+                    Message::Turns {
+                        first: remote.turns,
+                        turns: hosting
+                            .sent
+                            .iter()
+                            .skip(confirmed)
+                            .take(MAX_BATCH)
+                            .cloned()
+                            .collect(),
+                        commands: remote.commands,
+                    }
+                } else {
+                    Message::Start {
+                        you: index + 1,
+                        players: self.players.clone(),
+                        seed: self.seed,
+                        input_delay: self.input_delay,
+                    }
+                };
+                self.socket.send(remote.address, &message)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<C: Commands> Hosting<C> {
+    fn poll(
+        &mut self,
+        socket: &mut Socket,
+        turns: &mut VecDeque<Vec<C>>,
+        players: &[Player],
+    ) -> Result<(), NetError> {
+        let now = Instant::now();
+        while let Some((from, message)) = socket.receive::<C>()? {
+            let Some(client) = self
+                .clients
+                .iter()
+                .position(|remote| remote.address == from)
+            else {
+                continue;
+            };
+            let player = client + 1;
+            let remote = &mut self.clients[client];
+            remote.last_heard = now;
+            match message {
+                Message::Commands {
+                    first,
+                    commands,
+                    turns,
+                    checksum,
+                } => {
+                    remote.started = true;
+                    remote.turns = remote.turns.max(turns.min(self.built));
+                    for (tick, commands) in (first..).zip(commands) {
+                        // Later ones wait for the missing ones to be resent.
+                        if tick == remote.commands {
+                            self.commands[player].push_back(commands);
+                            remote.commands += 1;
+                        }
+                    }
+                    if checksum.is_some() {
+                        remote.checksum = checksum;
+                    }
+                }
+                Message::Leave => return Err(NetError::Left(player)),
+                _ => {}
+            }
+        }
+
+        // Build the turns every player sent their commands for.
+        while self.commands.iter().all(|commands| !commands.is_empty()) {
+            let turn = self
+                .commands
+                .iter_mut()
+                .map(|commands| commands.pop_front().expect("checked to be there"))
+                .collect::<Vec<_>>();
+            debug_assert_eq!(turn.len(), players.len());
+            self.sent.push_back(turn.clone());
+            turns.push_back(turn);
+            self.built += 1;
+        }
+        let confirmed = self
+            .clients
+            .iter()
+            .map(|remote| remote.turns)
+            .min()
+            .unwrap_or(self.built);
+        while self.unconfirmed < confirmed {
+            self.sent.pop_front();
+            self.unconfirmed += 1;
+        }
+
+        for (client, remote) in self.clients.iter_mut().enumerate() {
+            if now.duration_since(remote.last_heard) > TIMEOUT {
+                return Err(NetError::TimedOut(client + 1));
+            }
+            let Some((tick, checksum)) = remote.checksum else {
+                continue;
+            };
+            // Checksums the host doesn't have yet stay for later.
+            if self
+                .checksums
+                .back()
+                .is_none_or(|(latest, _)| tick <= *latest)
+            {
+                remote.checksum = None;
+                if let Some((_, expected)) = self.checksums.iter().find(|(at, _)| *at == tick)
+                    && *expected != checksum
+                {
+                    return Err(NetError::Desync {
+                        player: client + 1,
+                        tick,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<C: Commands> Joined<C> {
+    fn poll(
+        &mut self,
+        socket: &mut Socket,
+        turns: &mut VecDeque<Vec<C>>,
+        tick: u64,
+    ) -> Result<(), NetError> {
+        let now = Instant::now();
+        while let Some((from, message)) = socket.receive::<C>()? {
+            if from != self.host {
+                continue;
+            }
+            self.last_heard = now;
+            match message {
+                Message::Turns {
+                    first,
+                    turns: arrived,
+                    commands,
+                } => {
+                    for (at, turn) in (first..).zip(arrived) {
+                        if at == tick + turns.len() as u64 {
+                            turns.push_back(turn);
+                        }
+                    }
+                    while self.unconfirmed < commands && self.outbox.pop_front().is_some() {
+                        self.unconfirmed += 1;
+                    }
+                }
+                Message::Leave => return Err(NetError::Left(0)),
+                _ => {}
+            }
+        }
+        if now.duration_since(self.last_heard) > TIMEOUT {
+            return Err(NetError::TimedOut(0));
+        }
+
+        socket.send(
+            self.host,
+            &Message::Commands {
+                first: self.unconfirmed,
+                commands: self.outbox.iter().take(MAX_BATCH).cloned().collect(),
+                turns: tick + turns.len() as u64,
+                checksum: self.checksum,
+            },
+        )
+    }
+}
+
+impl<C: Commands> Drop for Lockstep<C> {
+    fn drop(&mut self) {
+        let addresses = match &self.role {
+            Role::Host(hosting) => hosting
+                .clients
+                .iter()
+                .map(|remote| remote.address)
+                .collect(),
+            Role::Client(joined) => vec![joined.host],
+        };
+        // The others notice by timing out if it doesn't arrive.
+        for address in addresses {
+            let _ = self.socket.send(address, &Message::<C>::Leave);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{hash::Hash, time::Duration};
+
+    use ecs::{Rng, Schedule, Simulation, World};
+
+    use super::*;
+    use crate::net::{Client, Host, poll_until};
+
+    /// Where the player wants new units.
+    type Orders = Vec<[f32; 2]>;
+
+    #[derive(Debug, Clone, Copy)]
+    struct Unit {
+        owner: usize,
+        position: [f32; 2],
+    }
+
+    fn game(seed: u64) -> Simulation<Vec<Orders>> {
+        let mut schedule = Schedule::new();
+        schedule
+            .add_fixed_system("orders", |world, _| {
+                let turn = world.resource::<Vec<Orders>>().unwrap().clone();
+                for (owner, orders) in turn.into_iter().enumerate() {
+                    for position in orders {
+                        let unit = world.spawn();
+                        world.insert(unit, Unit { owner, position });
+                    }
+                }
+            })
+            .add_fixed_system("wander", |world, _| {
+                let mut rng = world.remove_resource::<Rng>().unwrap();
+                for (_, unit) in world.query_mut::<Unit>() {
+                    unit.position[0] += rng.range(&(-1.0..1.0));
+                    unit.position[1] += rng.range(&(-1.0..1.0));
+                }
+                world.insert_resource(rng);
+            });
+        Simulation::new(World::new(), schedule, seed)
+    }
+
+    fn checksum(simulation: &Simulation<Vec<Orders>>) -> u64 {
+        simulation.checksum(|world, checksum| {
+            for (entity, unit) in world.query::<Unit>() {
+                entity.hash(checksum);
+                unit.owner.hash(checksum);
+                checksum.write_f32(unit.position[0]);
+                checksum.write_f32(unit.position[1]);
+            }
+        })
+    }
+
+    /// A host and `clients` which joined it, in a started game.
+    fn start(clients: usize) -> Vec<Lockstep<Orders>> {
+        let mut host = Host::<Orders>::bind("127.0.0.1:0", "Ana").unwrap();
+        let address = host.local_addr().unwrap();
+        let mut joining = (0..clients)
+            .map(|index| Client::join(address, format!("Client {index}")).unwrap())
+            .collect::<Vec<_>>();
+        poll_until(|| {
+            host.poll()?;
+            joining.iter_mut().try_for_each(Client::poll)?;
+            Ok(host.players().len() == clients + 1)
+        });
+
+        let mut host = host.start(7, Duration::from_secs(1) / 60);
+        poll_until(|| {
+            host.poll()?;
+            joining.iter_mut().try_for_each(Client::poll)?;
+            Ok(joining.iter().all(Client::started))
+        });
+        let clients = joining.into_iter().map(Client::into_lockstep);
+        std::iter::once(host).chain(clients).collect()
+    }
+
+    /// Take the turns which arrived, ordering a unit every few ticks, and
+    /// return the checksums after each of them.
+    fn play(lockstep: &mut Lockstep<Orders>, simulation: &mut Simulation<Vec<Orders>>) -> Vec<u64> {
+        let mut checksums = Vec::new();
+        while let Some(turn) = lockstep.next_turn() {
+            simulation.step(turn);
+            checksums.push(checksum(simulation));
+            lockstep.report_checksum(checksum(simulation));
+            let tick = lockstep.tick() + lockstep.player() as u64;
+            let orders = if tick.is_multiple_of(10) {
+                vec![[tick as f32, lockstep.player() as f32]]
+            } else {
+                Vec::new()
+            };
+            lockstep.submit(orders);
+        }
+        checksums
+    }
+
+    #[test]
+    fn players_step_through_the_same_turns() {
+        const TICKS: usize = 60;
+        let mut players = start(2);
+        assert_eq!(players[2].player(), 2);
+        assert_eq!(players[1].players()[0].name, "Ana");
+        assert!(players.iter().all(|player| player.input_delay() >= 2));
+
+        let mut simulations = players
+            .iter()
+            .map(|player| game(player.seed()))
+            .collect::<Vec<_>>();
+        let mut checksums = vec![Vec::new(); players.len()];
+        poll_until(|| {
+            for ((lockstep, simulation), checksums) in
+                players.iter_mut().zip(&mut simulations).zip(&mut checksums)
+            {
+                lockstep.poll()?;
+                if checksums.len() < TICKS {
+                    checksums.extend(play(lockstep, simulation));
+                }
+            }
+            Ok(checksums.iter().all(|checksums| checksums.len() >= TICKS))
+        });
+
+        assert_eq!(checksums[0], checksums[1]);
+        assert_eq!(checksums[0], checksums[2]);
+        // Every player's orders were carried out.
+        let owners = simulations[2]
+            .world()
+            .query::<Unit>()
+            .map(|(_, unit)| unit.owner)
+            .collect::<std::collections::BTreeSet<_>>();
+        assert_eq!(owners.len(), 3);
+    }
+
+    #[test]
+    fn diverging_players_are_noticed() {
+        let mut players = start(1);
+        // The client starts from another seed.
+        let mut simulations = [game(players[0].seed()), game(players[0].seed() + 1)];
+        let start = Instant::now();
+        let error = loop {
+            assert!(start.elapsed() < Duration::from_secs(5), "timed out");
+            let mut error = None;
+            for (lockstep, simulation) in players.iter_mut().zip(&mut simulations) {
+                match lockstep.poll() {
+                    Ok(()) => _ = play(lockstep, simulation),
+                    Err(err) => error = Some(err),
+                }
+            }
+            if let Some(error) = error {
+                break error;
+            }
+            std::thread::sleep(Duration::from_millis(1));
+        };
+
+        assert!(
+            matches!(error, NetError::Desync { player: 1, .. }),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn leaving_ends_the_game() {
+        let mut players = start(2);
+        players.pop();
+
+        poll_until(|| match players[0].poll() {
+            Err(NetError::Left(2)) => Ok(true),
+            other => other.map(|()| false),
+        });
+    }
+}
@@ -0,0 +1,141 @@
+//! Lockstep multiplayer over UDP.
+//!
+//! Instead of the state of the game, the players only send each other their
+//! commands, and every player runs the same deterministic simulation, like
+//! `ecs::Simulation`, with them. One player [hosts](Host) the game, the
+//! others join it as [clients](Client) and wait in its lobby until the host
+//! starts the game. From then on [Lockstep] collects the commands of every
+//! player for a tick at the host, which hands out the complete turn to all
+//! players. A player only simulates a tick once its turn arrived, so all of
+//! them step through the same turns.
+//!
+//! Commands are scheduled a few ticks ahead, the [input delay](Lockstep::input_delay),
+//! which is chosen from the round trip times measured in the lobby. This
+//! gives them the time to reach every player before they are needed, so the
+//! simulation doesn't wait for the network. Lost packets are made up for by
+//! sending everything until it is confirmed.
+//! ```no_run
+//! # use engine::net::{Host, NetError};
+//! # use std::time::Duration;
+//! # fn simulate(turn: Vec<Vec<u32>>) -> u64 { 0 }
+//! # fn main() -> Result<(), NetError> {
+//! # let fixed_steps = 1;
+//! // The other players join with `Client::join("<address of the host>:7777", name)`.
+//! let mut lobby = Host::<Vec<u32>>::bind("0.0.0.0:7777", "Ana")?;
+//! while lobby.players().len() < 2 {
+//!     lobby.poll()?;
+//! }
+//! let mut game = lobby.start(7, Duration::from_secs(1) / 60);
+//!
+//! // Every frame:
+//! game.poll()?;
+//! for _ in 0..fixed_steps {
+//!     // Waits for the turn to arrive, the next frame tries again.
+//!     let Some(turn) = game.next_turn() else {
+//!         break;
+//!     };
+//!     // Step the simulation with the commands of every player.
+//!     let checksum = simulate(turn);
+//!     game.report_checksum(checksum);
+//!     // Issued now, carried out `game.input_delay()` ticks later.
+//!     game.submit(vec![42]);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//! Nothing is encrypted or authenticated, it is meant for playing with
+//! friends on a LAN or over a forwarded port.
+
+use std::{fmt, io, time::Duration};
+
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+pub use lobby::{Client, Host};
+pub use lockstep::Lockstep;
+
+mod lobby;
+mod lockstep;
+mod protocol;
+
+/// The commands of one player for one tick, e.g. a list of orders.
+///
+/// Meant to be small, the commands of a few ticks are sent in every packet.
+pub trait Commands: Serialize + DeserializeOwned + Clone + Default {}
+
+impl<T: Serialize + DeserializeOwned + Clone + Default> Commands for T {}
+
+/// A player of a game, identified by their index in the list of players.
+///
+/// The host is the first player.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Player {
+    pub name: String,
+    /// How long a message takes to the host and back, `None` for the host
+    /// and while it wasn't measured yet.
+    pub rtt: Option<Duration>,
+}
+
+/// How many players fit into a game.
+pub const MAX_PLAYERS: usize = 16;
+
+/// How long a player may stay silent before they are dropped.
+pub const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Why a game can't go on.
+#[derive(Debug)]
+pub enum NetError {
+    Io(io::Error),
+    /// A message couldn't be encoded.
+    Encode(postcard::Error),
+    /// Nothing was heard from the player for the [TIMEOUT].
+    TimedOut(usize),
+    /// The player left the game.
+    Left(usize),
+    /// The simulation of the player came to a different state at the tick
+    /// than the one of the host.
+    Desync {
+        player: usize,
+        tick: u64,
+    },
+}
+
+impl fmt::Display for NetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NetError::Io(err) => write!(f, "{err}"),
+            NetError::Encode(err) => write!(f, "failed to encode a message: {err}"),
+            NetError::TimedOut(player) => write!(f, "player {player} timed out"),
+            NetError::Left(player) => write!(f, "player {player} left the game"),
+            NetError::Desync { player, tick } => {
+                write!(f, "player {player} is out of sync since tick {tick}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for NetError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            NetError::Io(err) => Some(err),
+            NetError::Encode(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for NetError {
+    fn from(err: io::Error) -> Self {
+        NetError::Io(err)
+    }
+}
+
+/// Poll the players until `poll` returns that they are done, failing after
+/// a few seconds.
+#[cfg(test)]
+fn poll_until(mut poll: impl FnMut() -> Result<bool, NetError>) {
+    let start = web_time::Instant::now();
+    while !poll().unwrap() {
+        assert!(start.elapsed() < Duration::from_secs(5), "timed out");
+        std::thread::sleep(Duration::from_millis(1));
+    }
+}
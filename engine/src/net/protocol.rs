@@ -0,0 +1,167 @@
+//! The messages the players send each other, one per UDP packet.
+
+use std::{
+    io,
+    net::{SocketAddr, ToSocketAddrs, UdpSocket},
+};
+
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+use super::{NetError, Player};
+
+/// Starts every packet, packets of other programs or versions are ignored.
+///
+/// The last byte is the version, bump it whenever the messages change.
+const MAGIC: [u8; 4] = *b"ls\x00\x01";
+
+/// How many commands or turns are sent at most in one packet.
+pub(super) const MAX_BATCH: usize = 32;
+
+/// What the players send each other, the host talks to every client and
+/// the clients only to the host.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(super) enum Message<C> {
+    /// Asks the host for a place in its lobby, sent until the lobby arrives.
+    Join {
+        name: String,
+    },
+    /// The players in the lobby, sent by the host regularly.
+    ///
+    /// Answered with a [Message::Pong] of the `ping`.
+    Lobby {
+        you: usize,
+        players: Vec<Player>,
+        ping: u64,
+    },
+    Pong {
+        ping: u64,
+    },
+    /// The game started, sent by the host until the client sends commands.
+    Start {
+        you: usize,
+        players: Vec<Player>,
+        seed: u64,
+        input_delay: u64,
+    },
+    /// The commands of a client from tick `first` on which the host didn't
+    /// confirm yet, the number of turns the client received and the
+    /// checksum of its latest tick.
+    Commands {
+        first: u64,
+        commands: Vec<C>,
+        turns: u64,
+        checksum: Option<(u64, u64)>,
+    },
+    /// The turns from tick `first` on which the client didn't confirm yet
+    /// and the number of commands of the client the host received.
+    Turns {
+        first: u64,
+        turns: Vec<Vec<C>>,
+        commands: u64,
+    },
+    /// The sender left.
+    Leave,
+}
+
+/// A non-blocking UDP socket sending and receiving [Message]s.
+#[derive(Debug)]
+pub(super) struct Socket {
+    socket: UdpSocket,
+    buffer: Box<[u8]>,
+}
+
+impl Socket {
+    pub(super) fn bind(address: impl ToSocketAddrs) -> Result<Self, NetError> {
+        let socket = UdpSocket::bind(address)?;
+        socket.set_nonblocking(true)?;
+        Ok(Self {
+            socket,
+            // The largest UDP payload.
+            buffer: vec![0; 65_507].into_boxed_slice(),
+        })
+    }
+
+    pub(super) fn local_addr(&self) -> Result<SocketAddr, NetError> {
+        Ok(self.socket.local_addr()?)
+    }
+
+    /// Send `message` to `to`, dropping it if the socket is busy like the
+    /// network might.
+    pub(super) fn send<C: Serialize>(
+        &self,
+        to: SocketAddr,
+        message: &Message<C>,
+    ) -> Result<(), NetError> {
+        let packet = encode(message)?;
+        match self.socket.send_to(&packet, to) {
+            Err(err) if err.kind() != io::ErrorKind::WouldBlock => Err(err.into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// The next message which arrived and its sender, `None` once there
+    /// are no more.
+    pub(super) fn receive<C: DeserializeOwned>(
+        &mut self,
+    ) -> Result<Option<(SocketAddr, Message<C>)>, NetError> {
+        loop {
+            match self.socket.recv_from(&mut self.buffer) {
+                Ok((length, from)) => {
+                    if let Some(message) = decode(&self.buffer[..length]) {
+                        return Ok(Some((from, message)));
+                    }
+                }
+                Err(err) if err.kind() == io::ErrorKind::WouldBlock => return Ok(None),
+                // Some platforms report a packet which didn't arrive on the
+                // next receive, the player timing out covers it.
+                Err(err) if err.kind() == io::ErrorKind::ConnectionReset => {}
+                Err(err) => return Err(err.into()),
+            }
+        }
+    }
+}
+
+fn encode<C: Serialize>(message: &Message<C>) -> Result<Vec<u8>, NetError> {
+    postcard::to_extend(message, MAGIC.to_vec()).map_err(NetError::Encode)
+}
+
+/// The message in `packet`, `None` if it isn't one of ours.
+fn decode<C: DeserializeOwned>(packet: &[u8]) -> Option<Message<C>> {
+    postcard::from_bytes(packet.strip_prefix(&MAGIC)?).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn messages_survive_encoding() {
+        let message = Message::Turns {
+            first: 12,
+            turns: vec![vec![vec![1u32, 2], vec![]], vec![vec![3], vec![4]]],
+            commands: 14,
+        };
+        let packet = encode(&message).unwrap();
+        assert_eq!(decode(&packet), Some(message));
+
+        let lobby = Message::<()>::Lobby {
+            you: 1,
+            players: vec![Player {
+                name: "Ana".to_string(),
+                rtt: Some(Duration::from_millis(25)),
+            }],
+            ping: 7,
+        };
+        assert_eq!(decode(&encode(&lobby).unwrap()), Some(lobby));
+    }
+
+    #[test]
+    fn foreign_packets_are_ignored() {
+        let packet = encode(&Message::<()>::Leave).unwrap();
+        assert_eq!(decode::<()>(&packet[1..]), None);
+        assert_eq!(decode::<()>(b"GET / HTTP/1.1"), None);
+        assert_eq!(decode::<()>(&MAGIC), None);
+    }
+}
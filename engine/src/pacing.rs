@@ -0,0 +1,234 @@
+//! Slowing down or stopping the rendering of a window nobody looks at.
+//!
+//! Rendering continuously keeps the GPU and a CPU core busy even while the
+//! window is minimized or in the background. [Pacing] follows the state of
+//! the window and tells the event loop when the next frame is due, so it can
+//! sleep in between:
+//! ```no_run
+//! # use engine::pacing::{Pacing, PacingSettings};
+//! # use web_time::Instant;
+//! # use winit::{event::WindowEvent, event_loop::ActiveEventLoop, window::Window};
+//! # fn run(event_loop: &ActiveEventLoop, window: &Window, event: &WindowEvent) {
+//! let mut pacing = Pacing::new(PacingSettings::default());
+//!
+//! // In `window_event`:
+//! pacing.handle_window_event(event);
+//!
+//! // In `about_to_wait`:
+//! let wakeup = pacing.wakeup(Instant::now());
+//! if wakeup.redraw {
+//!     window.request_redraw();
+//! }
+//! event_loop.set_control_flow(wakeup.control_flow);
+//! # }
+//! ```
+
+use serde::{Deserialize, Serialize};
+use web_time::{Duration, Instant};
+use winit::event::WindowEvent;
+use winit::event_loop::ControlFlow;
+
+/// How a window renders while it isn't the one the user works with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Inactive {
+    /// Keep rendering as fast as possible.
+    Render,
+    /// Render at the [throttled_fps](PacingSettings::throttled_fps).
+    Throttle,
+    /// Stop rendering until the window is active again.
+    Pause,
+}
+
+/// When a window renders, kept with the rest of the
+/// [settings](crate::config::Config::pacing).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PacingSettings {
+    /// While another window has the focus.
+    pub unfocused: Inactive,
+    /// While the window is minimized or completely covered.
+    pub minimized: Inactive,
+    /// The frame rate of [Inactive::Throttle].
+    pub throttled_fps: u32,
+}
+
+impl Default for PacingSettings {
+    fn default() -> Self {
+        Self {
+            unfocused: Inactive::Throttle,
+            minimized: Inactive::Pause,
+            throttled_fps: 15,
+        }
+    }
+}
+
+/// What the event loop should do before it waits for new events.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Wakeup {
+    /// A frame is due, the window should be redrawn.
+    pub redraw: bool,
+    /// How long the event loop may wait for events, until the next frame is due.
+    pub control_flow: ControlFlow,
+}
+
+/// Tracks whether a window is focused and visible, and paces its frames
+/// by the [PacingSettings].
+#[derive(Debug, Clone)]
+pub struct Pacing {
+    settings: PacingSettings,
+    // Windows start focused, some platforms don't report it.
+    focused: bool,
+    occluded: bool,
+    // Minimizing resizes the window to nothing on Windows instead of occluding it.
+    zero_size: bool,
+    last_frame: Option<Instant>,
+}
+
+impl Pacing {
+    pub fn new(settings: PacingSettings) -> Self {
+        Self {
+            settings,
+            focused: true,
+            occluded: false,
+            zero_size: false,
+            last_frame: None,
+        }
+    }
+
+    pub fn settings(&self) -> PacingSettings {
+        self.settings
+    }
+
+    pub fn set_settings(&mut self, settings: PacingSettings) {
+        self.settings = settings;
+    }
+
+    /// Follow the focus and visibility of the window.
+    ///
+    /// Other events are ignored.
+    pub fn handle_window_event(&mut self, event: &WindowEvent) {
+        match event {
+            WindowEvent::Focused(focused) => self.focused = *focused,
+            WindowEvent::Occluded(occluded) => self.occluded = *occluded,
+            WindowEvent::Resized(size) => self.zero_size = size.width == 0 || size.height == 0,
+            _ => (),
+        }
+    }
+
+    /// How the window renders right now, [Inactive::Render] while it is
+    /// focused and visible.
+    pub fn mode(&self) -> Inactive {
+        if self.occluded || self.zero_size {
+            self.settings.minimized
+        } else if !self.focused {
+            self.settings.unfocused
+        } else {
+            Inactive::Render
+        }
+    }
+
+    /// Whether a frame is due at `now` and how long the event loop may
+    /// sleep, meant to be called in `about_to_wait`.
+    ///
+    /// A frame reported as due counts as rendered.
+    pub fn wakeup(&mut self, now: Instant) -> Wakeup {
+        match self.mode() {
+            Inactive::Render => {
+                self.last_frame = Some(now);
+                Wakeup {
+                    redraw: true,
+                    control_flow: ControlFlow::Poll,
+                }
+            }
+            Inactive::Throttle => {
+                let interval = Duration::from_secs(1) / self.settings.throttled_fps.max(1);
+                let due = self.last_frame.is_none_or(|last| now >= last + interval);
+                if due {
+                    self.last_frame = Some(now);
+                }
+                let next = self.last_frame.map_or(now, |last| last + interval);
+                Wakeup {
+                    redraw: due,
+                    control_flow: ControlFlow::WaitUntil(next),
+                }
+            }
+            Inactive::Pause => Wakeup {
+                redraw: false,
+                control_flow: ControlFlow::Wait,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use winit::dpi::PhysicalSize;
+
+    use super::*;
+
+    #[test]
+    fn renders_continuously_while_active() {
+        let mut pacing = Pacing::new(PacingSettings::default());
+        let now = Instant::now();
+
+        for _ in 0..3 {
+            let wakeup = pacing.wakeup(now);
+            assert!(wakeup.redraw);
+            assert_eq!(wakeup.control_flow, ControlFlow::Poll);
+        }
+    }
+
+    #[test]
+    fn throttles_while_unfocused() {
+        let mut pacing = Pacing::new(PacingSettings {
+            throttled_fps: 10,
+            ..PacingSettings::default()
+        });
+        pacing.handle_window_event(&WindowEvent::Focused(false));
+        let start = Instant::now();
+        let interval = Duration::from_millis(100);
+
+        let first = pacing.wakeup(start);
+        assert!(first.redraw);
+        assert_eq!(first.control_flow, ControlFlow::WaitUntil(start + interval));
+        // Woken up early by some other event.
+        let early = pacing.wakeup(start + interval / 2);
+        assert!(!early.redraw);
+        assert_eq!(early.control_flow, ControlFlow::WaitUntil(start + interval));
+        assert!(pacing.wakeup(start + interval).redraw);
+
+        pacing.handle_window_event(&WindowEvent::Focused(true));
+        assert_eq!(pacing.mode(), Inactive::Render);
+    }
+
+    #[test]
+    fn pauses_while_minimized() {
+        let mut pacing = Pacing::new(PacingSettings::default());
+        pacing.handle_window_event(&WindowEvent::Resized(PhysicalSize::new(0, 0)));
+
+        let wakeup = pacing.wakeup(Instant::now());
+        assert!(!wakeup.redraw);
+        assert_eq!(wakeup.control_flow, ControlFlow::Wait);
+
+        pacing.handle_window_event(&WindowEvent::Resized(PhysicalSize::new(800, 600)));
+        pacing.handle_window_event(&WindowEvent::Occluded(true));
+        assert_eq!(pacing.mode(), Inactive::Pause);
+        pacing.handle_window_event(&WindowEvent::Occluded(false));
+        assert_eq!(pacing.mode(), Inactive::Render);
+    }
+
+    #[test]
+    fn minimized_takes_precedence_over_unfocused() {
+        let mut pacing = Pacing::new(PacingSettings {
+            unfocused: Inactive::Render,
+            minimized: Inactive::Throttle,
+            ..PacingSettings::default()
+        });
+        pacing.handle_window_event(&WindowEvent::Focused(false));
+        assert_eq!(pacing.mode(), Inactive::Render);
+
+        pacing.handle_window_event(&WindowEvent::Occluded(true));
+        assert_eq!(pacing.mode(), Inactive::Throttle);
+    }
+}
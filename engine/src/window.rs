@@ -0,0 +1,281 @@
+//! Opening the windows of an app and keeping their title up to date.
+//!
+//! ```no_run
+//! # use engine::window::{WindowOptions, load_icon};
+//! # use winit::{dpi::LogicalSize, event_loop::ActiveEventLoop};
+//! # fn resumed(event_loop: &ActiveEventLoop, icon_png: &[u8]) {
+//! let mut window = WindowOptions::new("Voxon")
+//!     .with_size(LogicalSize::new(1280, 720))
+//!     .with_min_size(LogicalSize::new(320, 240))
+//!     .with_icon(load_icon(icon_png).unwrap())
+//!     .open(event_loop)
+//!     .unwrap();
+//!
+//! // Shows "Voxon - 60 FPS".
+//! window.show_fps(60.0);
+//! # }
+//! ```
+
+use std::{fmt, ops::Deref, sync::Arc};
+
+use web_time::{Duration, Instant};
+use winit::{
+    dpi::LogicalSize,
+    error::OsError,
+    event_loop::ActiveEventLoop,
+    window::{BadIcon, Fullscreen, Icon, Window, WindowAttributes},
+};
+
+/// How a window is opened, its size in logical pixels, which are scaled
+/// by the DPI of the monitor.
+#[derive(Debug, Clone)]
+pub struct WindowOptions {
+    title: String,
+    size: LogicalSize<u32>,
+    min_size: Option<LogicalSize<u32>>,
+    max_size: Option<LogicalSize<u32>>,
+    fullscreen: bool,
+    icon: Option<Icon>,
+}
+
+impl WindowOptions {
+    /// A resizable 1024 by 768 window without an icon.
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            size: LogicalSize::new(1024, 768),
+            min_size: None,
+            max_size: None,
+            fullscreen: false,
+            icon: None,
+        }
+    }
+
+    /// The size of the inner area, without the decorations.
+    pub fn with_size(mut self, size: LogicalSize<u32>) -> Self {
+        self.size = size;
+        self
+    }
+
+    /// The window can't be resized below `size`.
+    pub fn with_min_size(mut self, size: LogicalSize<u32>) -> Self {
+        self.min_size = Some(size);
+        self
+    }
+
+    /// The window can't be resized above `size`.
+    pub fn with_max_size(mut self, size: LogicalSize<u32>) -> Self {
+        self.max_size = Some(size);
+        self
+    }
+
+    /// Cover the whole monitor the window is opened on.
+    pub fn with_fullscreen(mut self, fullscreen: bool) -> Self {
+        self.fullscreen = fullscreen;
+        self
+    }
+
+    /// Shown in the title bar and the taskbar, ignored in the browser.
+    pub fn with_icon(mut self, icon: Icon) -> Self {
+        self.icon = Some(icon);
+        self
+    }
+
+    /// The attributes winit opens the window with.
+    ///
+    /// In the browser the window is a canvas appended to the page.
+    pub fn attributes(&self) -> WindowAttributes {
+        let attributes = Window::default_attributes()
+            .with_title(&self.title)
+            .with_inner_size(self.size)
+            .with_fullscreen(fullscreen(self.fullscreen))
+            .with_window_icon(self.icon.clone());
+        let attributes = match self.min_size {
+            Some(size) => attributes.with_min_inner_size(size),
+            None => attributes,
+        };
+        let attributes = match self.max_size {
+            Some(size) => attributes.with_max_inner_size(size),
+            None => attributes,
+        };
+        #[cfg(target_arch = "wasm32")]
+        let attributes = {
+            use winit::platform::web::WindowAttributesExtWebSys;
+            attributes.with_append(true)
+        };
+        attributes
+    }
+
+    pub fn open(self, event_loop: &ActiveEventLoop) -> Result<AppWindow, OsError> {
+        let window = event_loop.create_window(self.attributes())?;
+        Ok(AppWindow {
+            window: Arc::new(window),
+            title: self.title,
+            fps_shown: None,
+        })
+    }
+}
+
+/// How often [AppWindow::show_fps] updates the title, changing it every
+/// frame is slow on some platforms and unreadable anyway.
+const FPS_INTERVAL: Duration = Duration::from_millis(500);
+
+/// An open window, derefs to the winit [Window].
+///
+/// The title is owned by the wrapper, so the frame rate can be shown next
+/// to it.
+#[derive(Debug)]
+pub struct AppWindow {
+    window: Arc<Window>,
+    title: String,
+    // When the frame rate was put into the title last.
+    fps_shown: Option<Instant>,
+}
+
+impl AppWindow {
+    /// The winit window, shared e.g. with the surface rendering to it.
+    pub fn shared(&self) -> Arc<Window> {
+        Arc::clone(&self.window)
+    }
+
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// Replace the title, removing the frame rate until it is shown again.
+    pub fn set_title(&mut self, title: impl Into<String>) {
+        self.title = title.into();
+        self.fps_shown = None;
+        self.window.set_title(&self.title);
+    }
+
+    /// Show `fps` after the title, at most twice a second.
+    pub fn show_fps(&mut self, fps: f64) {
+        let now = Instant::now();
+        if self
+            .fps_shown
+            .is_some_and(|shown| now.duration_since(shown) < FPS_INTERVAL)
+        {
+            return;
+        }
+        self.fps_shown = Some(now);
+        self.window.set_title(&fps_title(&self.title, fps));
+    }
+
+    /// The size of the inner area in logical pixels, the physical size
+    /// divided by the scale factor of the monitor.
+    pub fn logical_size(&self) -> LogicalSize<u32> {
+        self.window
+            .inner_size()
+            .to_logical(self.window.scale_factor())
+    }
+
+    pub fn is_fullscreen(&self) -> bool {
+        self.window.fullscreen().is_some()
+    }
+
+    /// Cover the whole monitor the window is on, or go back to the size it
+    /// had before.
+    pub fn set_fullscreen(&self, fullscreen: bool) {
+        self.window.set_fullscreen(self::fullscreen(fullscreen));
+    }
+}
+
+impl Deref for AppWindow {
+    type Target = Window;
+
+    fn deref(&self) -> &Window {
+        &self.window
+    }
+}
+
+fn fullscreen(fullscreen: bool) -> Option<Fullscreen> {
+    // Borderless doesn't change the video mode, so switching is instant.
+    fullscreen.then_some(Fullscreen::Borderless(None))
+}
+
+fn fps_title(title: &str, fps: f64) -> String {
+    format!("{title} - {fps:.0} FPS")
+}
+
+/// Decode an encoded PNG image into a window [Icon].
+pub fn load_icon(bytes: &[u8]) -> Result<Icon, IconError> {
+    let image = image::load_from_memory(bytes)
+        .map_err(IconError::Decode)?
+        .to_rgba8();
+    let (width, height) = image.dimensions();
+    Icon::from_rgba(image.into_raw(), width, height).map_err(IconError::Invalid)
+}
+
+/// Why an icon could not be loaded.
+#[derive(Debug)]
+pub enum IconError {
+    Decode(image::ImageError),
+    /// The image can't be used as an icon, e.g. it is too large.
+    Invalid(BadIcon),
+}
+
+impl fmt::Display for IconError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IconError::Decode(err) => write!(f, "invalid icon image: {err}"),
+            IconError::Invalid(err) => write!(f, "invalid icon: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for IconError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            IconError::Decode(err) => Some(err),
+            IconError::Invalid(err) => Some(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use image::{ImageFormat, RgbaImage};
+    use winit::dpi::Size;
+
+    use super::*;
+
+    #[test]
+    fn attributes_use_logical_sizes() {
+        let attributes = WindowOptions::new("Title")
+            .with_size(LogicalSize::new(800, 600))
+            .with_min_size(LogicalSize::new(320, 240))
+            .with_fullscreen(true)
+            .attributes();
+
+        assert_eq!(attributes.title, "Title");
+        assert_eq!(
+            attributes.inner_size,
+            Some(Size::Logical(LogicalSize::new(800.0, 600.0)))
+        );
+        assert_eq!(
+            attributes.min_inner_size,
+            Some(Size::Logical(LogicalSize::new(320.0, 240.0)))
+        );
+        assert_eq!(attributes.max_inner_size, None);
+        assert_eq!(attributes.fullscreen, Some(Fullscreen::Borderless(None)));
+    }
+
+    #[test]
+    fn fps_is_rounded() {
+        assert_eq!(fps_title("Voxon", 59.7), "Voxon - 60 FPS");
+    }
+
+    #[test]
+    fn icons_from_png() {
+        let mut png = Vec::new();
+        RgbaImage::new(16, 16)
+            .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+            .unwrap();
+
+        assert!(load_icon(&png).is_ok());
+        assert!(matches!(load_icon(&png[..20]), Err(IconError::Decode(_))));
+    }
+}
@@ -0,0 +1,39 @@
+use criterion::{Criterion, criterion_group, criterion_main};
+use graphic::noise::{Fbm, Noise, Perlin, Simplex};
+use std::hint::black_box;
+
+fn noise(c: &mut Criterion) {
+    let perlin = Perlin::new(1);
+    let simplex = Simplex::new(1);
+    c.bench_function("perlin 2d", |b| {
+        b.iter(|| perlin.sample2(black_box(12.3), black_box(45.6)))
+    });
+    c.bench_function("perlin 3d", |b| {
+        b.iter(|| perlin.sample3(black_box(12.3), black_box(45.6), black_box(7.8)))
+    });
+    c.bench_function("simplex 2d", |b| {
+        b.iter(|| simplex.sample2(black_box(12.3), black_box(45.6)))
+    });
+    c.bench_function("simplex 3d", |b| {
+        b.iter(|| simplex.sample3(black_box(12.3), black_box(45.6), black_box(7.8)))
+    });
+
+    // A heightmap tile, as generated for the terrain.
+    let fbm = Fbm::new(&simplex, 5).with_frequency(1.0 / 64.0);
+    c.bench_function("fbm 5 octaves 2d 64x64", |b| {
+        b.iter(|| {
+            let mut sum = 0.0;
+            for z in 0..64 {
+                for x in 0..64 {
+                    sum += fbm.sample2(black_box(x as f32), black_box(z as f32));
+                }
+            }
+            sum
+        })
+    });
+
+    c.bench_function("seed permutation", |b| b.iter(|| Perlin::new(black_box(7))));
+}
+
+criterion_group!(benches, noise);
+criterion_main!(benches);
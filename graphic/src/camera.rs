@@ -3,94 +3,364 @@
 //! Right handed, Y-up coordinate system
 //!
 
-use lina::{matrix::Matrix, v, vector::Vector};
+use std::time::Duration;
+
+use lina::{angle::Rad, matrix::Matrix, v, vector::Vector};
 use quaternion::Quaternion;
 
 use crate::transform::look_at;
 
+/// How the [Camera] reacts to rotations.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CameraMode {
+    /// Rotate freely around every axis, angles are not restricted.
+    ///
+    /// Pitching past 90 degrees turns the camera upside down.
+    FreeFly,
+    /// Classic first person camera, the horizon always stays level.
+    ///
+    /// [roll](Camera::roll) has no effect and the pitch is clamped
+    /// between `min_pitch` and `max_pitch` radians. To never flip over,
+    /// the range should stay within `(-PI/2, PI/2)`.
+    Fps { min_pitch: f32, max_pitch: f32 },
+}
+
+impl CameraMode {
+    /// [CameraMode::Fps] allowing to look up and down by 89 degrees.
+    ///
+    /// Looking exactly straight up or down would make the yaw ambiguous.
+    pub fn fps() -> Self {
+        let limit = 89.0f32.to_radians();
+        CameraMode::Fps {
+            min_pitch: -limit,
+            max_pitch: limit,
+        }
+    }
+}
+
 /// Simple Camera with basic movement support.
 ///
 /// It supports a basic classic FPS like movement,
 /// by going forward, backwards, up/down and turning left/right.
+///
+/// The orientation is applied as roll, yaw and then pitch, see
+/// [CameraMode] on how the angles are restricted.
+///
+/// ```
+/// # use graphic::camera::{Camera, CameraMode};
+/// let mut camera = Camera::default();
+/// camera.set_mode(CameraMode::fps());
+///
+/// camera.roll(1.0);
+/// camera.pitch(3.0);
+///
+/// assert_eq!(camera.roll_angle(), 0.0);
+/// assert_eq!(camera.pitch_angle(), 89.0f32.to_radians());
+/// ```
 pub struct Camera {
-    eye: Vector<f32, 3>,
+    // Where the input moved the camera.
+    target: Pose,
+    // What is rendered, approaching `target` when smoothing is enabled.
+    current: Pose,
+    mode: CameraMode,
+    smoothing: Option<Smoothing>,
+}
+
+/// Time constants of the exponential camera smoothing.
+///
+/// Each frame the camera covers the fraction
+/// ```text
+/// 1 - e^(-dt / time_constant)
+/// ```
+/// of the remaining distance to its target. After one time constant about
+/// 63% of a sudden change is applied, after three about 95%.
+/// As the fraction depends on the frame time `dt`, the camera moves the same
+/// way regardless of the frame rate.
+///
+/// A zero time constant disables smoothing for that component.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Smoothing {
+    pub translation: Duration,
+    pub rotation: Duration,
+}
+
+impl Smoothing {
+    fn factor(dt: Duration, time_constant: Duration) -> f32 {
+        if time_constant.is_zero() {
+            return 1.0;
+        }
+        1.0 - (-dt.as_secs_f32() / time_constant.as_secs_f32()).exp()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Pose {
+    eye: Vector<f64, 3>,
     pitch: f32,
     roll: f32,
     yaw: f32,
 }
 
-impl Camera {
-    fn recalculate_orientation(&self) -> Quaternion<f32> {
-        let pitch = Quaternion::<f32>::new_unit(self.pitch, v![1.0, 0.0, 0.0]);
+impl Pose {
+    fn orientation(&self) -> Quaternion<f32> {
+        let pitch = Quaternion::<f32>::new_unit(Rad(self.pitch), v![1.0, 0.0, 0.0]);
         // Camera is looking down at the -Z direction.
-        let roll = Quaternion::<f32>::new_unit(self.roll, v![0.0, 0.0, -1.0]);
-        let yaw = Quaternion::<f32>::new_unit(self.yaw, v![0.0, 1.0, 0.0]);
+        let roll = Quaternion::<f32>::new_unit(Rad(self.roll), v![0.0, 0.0, -1.0]);
+        let yaw = Quaternion::<f32>::new_unit(Rad(self.yaw), v![0.0, 1.0, 0.0]);
 
         roll * yaw * pitch
     }
+}
+
+impl Camera {
+    /// Position of the camera in world space.
+    ///
+    /// Kept in `f64`, so the camera moves smoothly even kilometers away from
+    /// the world's origin.
+    pub fn eye(&self) -> Vector<f64, 3> {
+        self.current.eye
+    }
 
-    pub fn eye(&self) -> Vector<f32, 3> {
-        self.eye
+    /// The unit vector the camera is looking along in world space, e.g. for
+    /// picking what is in the middle of the view.
+    ///
+    /// ```
+    /// # use graphic::camera::Camera;
+    /// # use lina::v;
+    /// let camera = Camera::default();
+    /// assert_eq!(camera.look_direction(), v![0.0, 0.0, -1.0]);
+    /// ```
+    pub fn look_direction(&self) -> Vector<f32, 3> {
+        self.current
+            .orientation()
+            .rotate_vector_unit(v![0.0, 0.0, -1.0])
     }
 
+    /// Movement follows the orientation the camera is turning towards,
+    /// so it stays responsive with smoothing enabled.
     pub fn move_on_look_at_vector(&mut self, units: f32) {
-        let q = self.recalculate_orientation();
+        let q = self.target.orientation();
 
-        let look_dir = Quaternion::from_vector(v![0.0, 0.0, -1.0])
-            .conjugate_by(q)
-            .vector();
+        let look_dir = q.rotate_vector_unit(v![0.0, 0.0, -1.0]);
 
-        self.eye += look_dir * units;
+        self.target.eye += (look_dir * units).map(f64::from);
+        self.sync();
     }
 
     pub fn move_on_right_vector(&mut self, units: f32) {
-        let q = self.recalculate_orientation();
+        let q = self.target.orientation();
 
-        let look_dir = Quaternion::from_vector(v![0.0, 0.0, -1.0])
-            .conjugate_by(q)
-            .vector();
-        let up_dir = Quaternion::from_vector(v![0.0, 1.0, 0.0])
-            .conjugate_by(q)
-            .vector();
+        let look_dir = q.rotate_vector_unit(v![0.0, 0.0, -1.0]);
+        let up_dir = q.rotate_vector_unit(v![0.0, 1.0, 0.0]);
 
         let right = look_dir.cross(up_dir).norm();
-        self.eye += right * units;
+        self.target.eye += (right * units).map(f64::from);
+        self.sync();
     }
 
     pub fn move_on_up_vector(&mut self, units: f32) {
-        let q = self.recalculate_orientation();
+        let q = self.target.orientation();
+
+        let up_dir = q.rotate_vector_unit(v![0.0, 1.0, 0.0]);
+        self.target.eye += (up_dir * units).map(f64::from);
+        self.sync();
+    }
+
+    /// Rotation around the camera's X axis in radians.
+    pub fn pitch_angle(&self) -> f32 {
+        self.current.pitch
+    }
+
+    /// Rotation around the camera's -Z axis in radians.
+    pub fn roll_angle(&self) -> f32 {
+        self.current.roll
+    }
+
+    /// Rotation around the Y axis in radians.
+    pub fn yaw_angle(&self) -> f32 {
+        self.current.yaw
+    }
+
+    pub fn mode(&self) -> CameraMode {
+        self.mode
+    }
+
+    /// Switch the [CameraMode].
+    ///
+    /// Switching to [CameraMode::Fps] levels the camera and clamps the
+    /// current pitch into the allowed range.
+    pub fn set_mode(&mut self, mode: CameraMode) {
+        self.mode = mode;
+        self.restrict_angles();
+    }
+
+    pub fn smoothing(&self) -> Option<Smoothing> {
+        self.smoothing
+    }
+
+    /// Enable or disable smoothing the movement of the camera.
+    ///
+    /// With smoothing the movements and rotations only set a target,
+    /// which the camera approaches on each [update](Camera::update).
+    /// Without it they are applied immediately.
+    ///
+    /// ```
+    /// # use std::time::Duration;
+    /// # use graphic::camera::{Camera, Smoothing};
+    /// let mut camera = Camera::default();
+    /// camera.set_smoothing(Some(Smoothing {
+    ///     translation: Duration::from_millis(100),
+    ///     rotation: Duration::from_millis(50),
+    /// }));
+    ///
+    /// camera.yaw(1.0);
+    /// assert_eq!(camera.yaw_angle(), 0.0);
+    ///
+    /// camera.update(Duration::from_millis(50));
+    /// let yaw = camera.yaw_angle();
+    /// assert!(0.6 < yaw && yaw < 0.7);
+    ///
+    /// camera.update(Duration::from_secs(2));
+    /// assert!(1.0 - camera.yaw_angle() < 1e-6);
+    /// ```
+    pub fn set_smoothing(&mut self, smoothing: Option<Smoothing>) {
+        self.smoothing = smoothing;
+        self.sync();
+    }
+
+    /// Advance the camera towards its target by the `dt` elapsed time.
+    ///
+    /// Has no effect without smoothing.
+    pub fn update(&mut self, dt: Duration) {
+        let Some(smoothing) = self.smoothing else {
+            return;
+        };
+
+        let translation = Smoothing::factor(dt, smoothing.translation);
+        let rotation = Smoothing::factor(dt, smoothing.rotation);
+        let (current, target) = (&mut self.current, &self.target);
 
-        let up_dir = Quaternion::from_vector(v![0.0, 1.0, 0.0])
-            .conjugate_by(q)
-            .vector();
-        self.eye += up_dir * units;
+        current.eye = current.eye.lerp(target.eye, f64::from(translation));
+        current.pitch += (target.pitch - current.pitch) * rotation;
+        current.roll += (target.roll - current.roll) * rotation;
+        current.yaw += (target.yaw - current.yaw) * rotation;
+    }
+
+    /// Place the camera at `eye` with the given angles in radians, skipping
+    /// the smoothing.
+    ///
+    /// The angles are restricted by the [CameraMode].
+    ///
+    /// ```
+    /// # use graphic::camera::Camera;
+    /// # use lina::v;
+    /// let mut camera = Camera::default();
+    /// camera.set_pose(v![1.0, 2.0, 3.0], 0.5, 1.0, 0.0);
+    ///
+    /// assert_eq!(camera.eye(), v![1.0, 2.0, 3.0]);
+    /// assert_eq!(camera.pitch_angle(), 0.5);
+    /// assert_eq!(camera.yaw_angle(), 1.0);
+    /// ```
+    pub fn set_pose(&mut self, eye: Vector<f64, 3>, pitch: f32, yaw: f32, roll: f32) {
+        self.target = Pose {
+            eye,
+            pitch,
+            roll,
+            yaw,
+        };
+        self.restrict_angles();
+        self.current = self.target;
+    }
+
+    /// Move the camera to `eye` without changing where it looks, skipping
+    /// the smoothing, e.g. to follow a player.
+    ///
+    /// ```
+    /// # use std::time::Duration;
+    /// # use graphic::camera::{Camera, Smoothing};
+    /// # use lina::v;
+    /// let mut camera = Camera::default();
+    /// camera.set_smoothing(Some(Smoothing {
+    ///     translation: Duration::from_millis(100),
+    ///     rotation: Duration::from_millis(50),
+    /// }));
+    /// camera.yaw(1.0);
+    /// camera.set_eye(v![1.0, 2.0, 3.0]);
+    ///
+    /// assert_eq!(camera.eye(), v![1.0, 2.0, 3.0]);
+    /// // Still turning towards the new yaw.
+    /// assert_eq!(camera.yaw_angle(), 0.0);
+    /// ```
+    pub fn set_eye(&mut self, eye: Vector<f64, 3>) {
+        self.target.eye = eye;
+        self.current.eye = eye;
     }
 
     pub fn roll(&mut self, radians: f32) {
-        self.roll += radians;
+        self.target.roll += radians;
+        self.restrict_angles();
     }
 
     pub fn pitch(&mut self, radians: f32) {
-        self.pitch += radians;
+        self.target.pitch += radians;
+        self.restrict_angles();
     }
 
     pub fn yaw(&mut self, radians: f32) {
-        self.yaw += radians;
+        self.target.yaw += radians;
+        self.sync();
+    }
+
+    fn restrict_angles(&mut self) {
+        if let CameraMode::Fps {
+            min_pitch,
+            max_pitch,
+        } = self.mode
+        {
+            self.target.roll = 0.0;
+            self.target.pitch = self.target.pitch.clamp(min_pitch, max_pitch);
+        }
+        self.sync();
+    }
+
+    // Without smoothing every change is applied immediately.
+    fn sync(&mut self) {
+        if self.smoothing.is_none() {
+            self.current = self.target;
+        }
     }
 
     pub fn as_transform_matrix(&self) -> Matrix<f32, 4, 4> {
-        let q = self.recalculate_orientation();
+        self.as_relative_transform_matrix(v![0.0, 0.0, 0.0])
+    }
 
-        let look_dir = Quaternion::from_vector(v![0.0, 0.0, -1.0])
-            .conjugate_by(q)
-            .vector();
-        let up_dir = Quaternion::from_vector(v![0.0, 1.0, 0.0])
-            .conjugate_by(q)
-            .vector();
+    /// The [as_transform_matrix](Camera::as_transform_matrix) of the world
+    /// moved by `-origin`.
+    ///
+    /// Far away from the world's origin the translation of the view matrix
+    /// is too large for `f32` to keep the precision of what is close to the
+    /// camera. Rendering everything relative to the eye, with the offsets
+    /// taken in `f64`, keeps the numbers small.
+    ///
+    /// ```
+    /// # use graphic::camera::Camera;
+    /// # use lina::v;
+    /// let mut camera = Camera::default();
+    /// camera.set_eye(v![20000.25, 0.0, 5.0]);
+    ///
+    /// // A millimeter in front of the eye stays a millimeter away.
+    /// let view = camera.as_relative_transform_matrix(camera.eye());
+    /// let point = view * v![0.0, 0.0, -0.001, 1.0];
+    /// assert_eq!(point, v![0.0, 0.0, -0.001, 1.0]);
+    /// ```
+    pub fn as_relative_transform_matrix(&self, origin: Vector<f64, 3>) -> Matrix<f32, 4, 4> {
+        let q = self.current.orientation();
 
-        let target = self.eye + look_dir;
-        // Unwrap is perfectly safe as we are in a 4x4 matrix
-        look_at(self.eye, target, up_dir)
+        let look_dir = self.look_direction();
+        let up_dir = q.rotate_vector_unit(v![0.0, 1.0, 0.0]);
+
+        let eye = (self.current.eye - origin).map(|value| value as f32);
+        look_at(eye, eye + look_dir, up_dir)
     }
 }
 
@@ -98,11 +368,17 @@ impl Camera {
 /// until we provide proper construction pattern to it.
 impl Default for Camera {
     fn default() -> Self {
-        Self {
+        let pose = Pose {
             eye: v![0.0, 0.0, 5.0],
             pitch: 0.0,
             roll: 0.0,
             yaw: 0.0,
+        };
+        Self {
+            target: pose,
+            current: pose,
+            mode: CameraMode::FreeFly,
+            smoothing: None,
         }
     }
 }
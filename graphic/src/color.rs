@@ -0,0 +1,174 @@
+//! Colors in **linear** RGB with straight (not premultiplied) alpha.
+//!
+//! Lighting, blending and the GPU's sRGB render targets all work with linear
+//! values, so that is how colors are stored. Colors picked in an image
+//! editor or written as hex codes are usually sRGB encoded bytes though,
+//! they have to go through [Color::from_srgba8] first:
+//!
+//! ```
+//! # use graphic::color::Color;
+//! let orange = Color::from_srgb_hex(0xff8000);
+//!
+//! assert_eq!(orange.r, 1.0);
+//! // Mid gray in sRGB is a lot darker in linear space.
+//! assert!((orange.g - 0.2158605).abs() < 1e-6);
+//! assert_eq!(orange.to_srgba8(), [255, 128, 0, 255]);
+//! ```
+
+/// A linear RGBA color, every channel is nominally in the range `[0, 1]`.
+///
+/// Laid out as four `f32`s, so it can be used as a `vec4f` in vertices and
+/// uniforms directly with the `bytemuck` feature.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Color {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+    pub a: f32,
+}
+
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for Color {}
+// Four `f32`s without any padding.
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Pod for Color {}
+
+impl Color {
+    pub const TRANSPARENT: Color = Color::new(0.0, 0.0, 0.0, 0.0);
+    pub const BLACK: Color = Color::rgb(0.0, 0.0, 0.0);
+    pub const WHITE: Color = Color::rgb(1.0, 1.0, 1.0);
+    pub const GRAY: Color = Color::rgb(0.5, 0.5, 0.5);
+    pub const RED: Color = Color::rgb(1.0, 0.0, 0.0);
+    pub const GREEN: Color = Color::rgb(0.0, 1.0, 0.0);
+    pub const BLUE: Color = Color::rgb(0.0, 0.0, 1.0);
+    pub const YELLOW: Color = Color::rgb(1.0, 1.0, 0.0);
+    pub const CYAN: Color = Color::rgb(0.0, 1.0, 1.0);
+    pub const MAGENTA: Color = Color::rgb(1.0, 0.0, 1.0);
+
+    pub const fn new(r: f32, g: f32, b: f32, a: f32) -> Self {
+        Self { r, g, b, a }
+    }
+
+    /// An opaque color.
+    pub const fn rgb(r: f32, g: f32, b: f32) -> Self {
+        Self::new(r, g, b, 1.0)
+    }
+
+    /// The same color with its alpha replaced.
+    pub const fn with_alpha(self, a: f32) -> Self {
+        Self { a, ..self }
+    }
+
+    /// Decode sRGB encoded bytes, alpha is linear already.
+    ///
+    /// ```
+    /// # use graphic::color::Color;
+    /// assert_eq!(Color::from_srgba8([255, 255, 0, 255]), Color::YELLOW);
+    /// assert_eq!(Color::from_srgba8([0, 0, 0, 0]), Color::TRANSPARENT);
+    /// ```
+    pub fn from_srgba8([r, g, b, a]: [u8; 4]) -> Self {
+        Self::new(
+            srgb_to_linear(r as f32 / 255.0),
+            srgb_to_linear(g as f32 / 255.0),
+            srgb_to_linear(b as f32 / 255.0),
+            a as f32 / 255.0,
+        )
+    }
+
+    /// An opaque color from a `0xRRGGBB` sRGB hex code.
+    pub fn from_srgb_hex(hex: u32) -> Self {
+        let [_, r, g, b] = hex.to_be_bytes();
+        Self::from_srgba8([r, g, b, 255])
+    }
+
+    /// Encode as sRGB bytes, the inverse of [from_srgba8](Color::from_srgba8).
+    ///
+    /// Channels outside of `[0, 1]` are clamped.
+    ///
+    /// ```
+    /// # use graphic::color::Color;
+    /// for byte in 0..=255 {
+    ///     let srgba = [byte, 0, 255 - byte, byte];
+    ///     assert_eq!(Color::from_srgba8(srgba).to_srgba8(), srgba);
+    /// }
+    /// assert_eq!(Color::rgb(2.0, -1.0, 0.5).to_srgba8(), [255, 0, 188, 255]);
+    /// ```
+    pub fn to_srgba8(self) -> [u8; 4] {
+        let to_byte = |value: f32| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+        [
+            to_byte(linear_to_srgb(self.r)),
+            to_byte(linear_to_srgb(self.g)),
+            to_byte(linear_to_srgb(self.b)),
+            to_byte(self.a),
+        ]
+    }
+
+    pub const fn to_array(self) -> [f32; 4] {
+        [self.r, self.g, self.b, self.a]
+    }
+}
+
+impl From<[f32; 4]> for Color {
+    fn from([r, g, b, a]: [f32; 4]) -> Self {
+        Self::new(r, g, b, a)
+    }
+}
+
+impl From<Color> for [f32; 4] {
+    fn from(color: Color) -> Self {
+        color.to_array()
+    }
+}
+
+/// Channel by channel, which blends linear colors correctly.
+///
+/// ```
+/// # use graphic::color::Color;
+/// let halfway = Color::BLACK * 0.5 + Color::WHITE.with_alpha(0.0) * 0.5;
+///
+/// assert_eq!(halfway, Color::new(0.5, 0.5, 0.5, 0.5));
+/// ```
+impl std::ops::Add for Color {
+    type Output = Color;
+
+    fn add(self, other: Color) -> Color {
+        Color::new(
+            self.r + other.r,
+            self.g + other.g,
+            self.b + other.b,
+            self.a + other.a,
+        )
+    }
+}
+
+impl std::ops::Mul<f32> for Color {
+    type Output = Color;
+
+    fn mul(self, factor: f32) -> Color {
+        Color::new(
+            self.r * factor,
+            self.g * factor,
+            self.b * factor,
+            self.a * factor,
+        )
+    }
+}
+
+/// The sRGB electro-optical transfer function, for a single channel in `[0, 1]`.
+pub fn srgb_to_linear(value: f32) -> f32 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// The inverse of [srgb_to_linear].
+pub fn linear_to_srgb(value: f32) -> f32 {
+    if value <= 0.0031308 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
@@ -0,0 +1,117 @@
+//! Spherical and cylindrical coordinates, for placing things around a
+//! center, like an orbiting camera.
+//!
+//! Both follow the Y up convention of the [crate]: the azimuth is the angle
+//! around the Y axis, from Z towards X, so a positive azimuth turns the same
+//! way as a [rotate_y](crate::transform::rotate_y). The polar angle of the
+//! spherical coordinates is measured from the Y axis, the poles are straight
+//! up and down.
+//!
+//! ```
+//! # use graphic::coordinates::{Cylindrical, Spherical};
+//! # use lina::v;
+//! # use std::f32::consts::PI;
+//! let on_the_equator = Spherical { radius: 2.0, polar: PI / 2.0, azimuth: PI / 2.0 };
+//! assert!(on_the_equator.to_cartesian().approx_eq(&v![2.0, 0.0, 0.0], 1e-6));
+//!
+//! let spherical = Spherical::from_cartesian(v![0.0, 3.0, 3.0]);
+//! assert!((spherical.polar - PI / 4.0).abs() < 1e-6);
+//! assert_eq!(spherical.azimuth, 0.0);
+//!
+//! let cylindrical = Cylindrical::from_cartesian(v![0.0, 3.0, 3.0]);
+//! assert_eq!(cylindrical, Cylindrical { radius: 3.0, azimuth: 0.0, height: 3.0 });
+//! let back = Spherical::from(cylindrical);
+//! assert!((back.radius - spherical.radius).abs() < 1e-6);
+//! assert!((back.polar - spherical.polar).abs() < 1e-6);
+//! ```
+
+use lina::{v, vector::Vector};
+
+/// A point given by its distance from the origin and two angles in radians.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Spherical {
+    pub radius: f32,
+    /// From the Y axis, between 0 and π.
+    pub polar: f32,
+    /// Around the Y axis from Z towards X, between -π and π.
+    pub azimuth: f32,
+}
+
+impl Spherical {
+    /// The origin and the points on the Y axis have an azimuth of 0, the
+    /// origin a polar angle of 0 as well.
+    pub fn from_cartesian(point: Vector<f32, 3>) -> Self {
+        let radius = point.length();
+        let polar = if radius > 0.0 {
+            (point[1] / radius).clamp(-1.0, 1.0).acos()
+        } else {
+            0.0
+        };
+        Self {
+            radius,
+            polar,
+            azimuth: point[0].atan2(point[2]),
+        }
+    }
+
+    pub fn to_cartesian(&self) -> Vector<f32, 3> {
+        let (sin_polar, cos_polar) = self.polar.sin_cos();
+        let (sin_azimuth, cos_azimuth) = self.azimuth.sin_cos();
+        v![
+            self.radius * sin_polar * sin_azimuth,
+            self.radius * cos_polar,
+            self.radius * sin_polar * cos_azimuth
+        ]
+    }
+}
+
+/// A point given by its distance from the Y axis, the angle around it in
+/// radians and its height along it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cylindrical {
+    pub radius: f32,
+    /// Around the Y axis from Z towards X, between -π and π.
+    pub azimuth: f32,
+    pub height: f32,
+}
+
+impl Cylindrical {
+    /// The points on the Y axis have an azimuth of 0.
+    pub fn from_cartesian(point: Vector<f32, 3>) -> Self {
+        Self {
+            radius: point[0].hypot(point[2]),
+            azimuth: point[0].atan2(point[2]),
+            height: point[1],
+        }
+    }
+
+    pub fn to_cartesian(&self) -> Vector<f32, 3> {
+        let (sin_azimuth, cos_azimuth) = self.azimuth.sin_cos();
+        v![
+            self.radius * sin_azimuth,
+            self.height,
+            self.radius * cos_azimuth
+        ]
+    }
+}
+
+impl From<Spherical> for Cylindrical {
+    fn from(spherical: Spherical) -> Self {
+        let (sin_polar, cos_polar) = spherical.polar.sin_cos();
+        Self {
+            radius: spherical.radius * sin_polar,
+            azimuth: spherical.azimuth,
+            height: spherical.radius * cos_polar,
+        }
+    }
+}
+
+impl From<Cylindrical> for Spherical {
+    fn from(cylindrical: Cylindrical) -> Self {
+        Self {
+            radius: cylindrical.radius.hypot(cylindrical.height),
+            polar: cylindrical.radius.atan2(cylindrical.height),
+            azimuth: cylindrical.azimuth,
+        }
+    }
+}
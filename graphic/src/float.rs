@@ -0,0 +1,89 @@
+//! The floating point types the transformations can be built with.
+//!
+//! `f32` is what the GPU works with, but far away from the origin it runs
+//! out of precision quickly, a millimeter is lost at a few kilometers. Large
+//! worlds can keep their positions and build their matrices in `f64` on the
+//! CPU, and convert the results to `f32` only when uploading them:
+//! ```
+//! # use graphic::float::Float;
+//! # use graphic::transform::{inv_translate, translate};
+//! // A millimeter away from a camera 10000 km from the origin.
+//! let model = translate(1.0e7_f64 + 0.001, 0.0, 0.0);
+//! let view = inv_translate(1.0e7_f64, 0.0, 0.0);
+//! let model_view = (view * model).map(Float::to_f32);
+//! assert!((model_view[(0, 3)] - 0.001).abs() < 1.0e-6);
+//!
+//! // In f32 the millimeter is gone before the matrices are multiplied.
+//! let model = translate(1.0e7_f32 + 0.001, 0.0, 0.0);
+//! let view = inv_translate(1.0e7_f32, 0.0, 0.0);
+//! assert_eq!((view * model)[(0, 3)], 0.0);
+//! ```
+//!
+//! `f16` isn't supported, Rust only provides it on nightly.
+
+use std::fmt::Debug;
+use std::iter::Sum;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use lina::vector::{One, Sqrt, Zero};
+
+/// A floating point type the transformations can be built with, `f32` or
+/// `f64`.
+pub trait Float:
+    Copy
+    + Default
+    + Debug
+    + PartialOrd
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+    + Sum
+    + Sqrt<Output = Self>
+    + Zero
+    + One
+{
+    const TWO: Self;
+    const PI: Self;
+    const INFINITY: Self;
+    const NEG_INFINITY: Self;
+
+    fn sin(self) -> Self;
+    fn cos(self) -> Self;
+    fn tan(self) -> Self;
+
+    /// Rounded to the nearest `f32`, for uploading to the GPU.
+    fn to_f32(self) -> f32;
+}
+
+macro_rules! impl_float_for_float_types {
+    ($($T:ident),*) => {
+        $(
+            impl Float for $T {
+                const TWO: Self = 2.0;
+                const PI: Self = std::$T::consts::PI;
+                const INFINITY: Self = $T::INFINITY;
+                const NEG_INFINITY: Self = $T::NEG_INFINITY;
+
+                fn sin(self) -> Self {
+                    self.sin()
+                }
+
+                fn cos(self) -> Self {
+                    self.cos()
+                }
+
+                fn tan(self) -> Self {
+                    self.tan()
+                }
+
+                fn to_f32(self) -> f32 {
+                    self as f32
+                }
+            }
+        )*
+    };
+}
+
+impl_float_for_float_types!(f32, f64);
@@ -44,18 +44,24 @@
 //! of matrix transformation will be inverted in the end, they can simply generate the inverted matrix
 //! equivalent transformation right from the start.
 
-use lina::{m, matrix::Matrix, v, vector::Vector};
+use lina::{matrix::Matrix, v, vector::Vector};
+
+use crate::float::Float;
+
 pub mod camera;
+pub mod color;
+pub mod coordinates;
+pub mod float;
+pub mod noise;
+pub mod plane;
+pub mod projection;
+pub mod spline;
 pub mod transform;
+pub mod viewport;
 
-#[rustfmt::skip]
-pub fn identity_matrix() -> Matrix<f32, 4, 4> {
-    m![
-        [1.0, 0.0, 0.0, 0.0],
-        [0.0, 1.0, 0.0, 0.0],
-        [0.0, 0.0, 1.0, 0.0],
-        [0.0, 0.0, 0.0, 1.0]
-    ]
+/// Shorthand for the 4x4 [Matrix::identity] used by the transformations.
+pub fn identity_matrix<T: Float>() -> Matrix<T, 4, 4> {
+    Matrix::identity()
 }
 
 /// Convenience function for generating cross product for 4D vectors
@@ -63,10 +69,15 @@ pub fn identity_matrix() -> Matrix<f32, 4, 4> {
 /// As the cross product doesn't exist for 4D vectors, this function takes the first
 /// three elements from each and calculates the cross product on those.
 /// Finally padding it out with a zero and returning a 4D vector.
-pub fn cross(lhs: Vector<f32, 4>, rhs: Vector<f32, 4>) -> Vector<f32, 4> {
+pub fn cross<T: Float>(lhs: Vector<T, 4>, rhs: Vector<T, 4>) -> Vector<T, 4> {
     let lhs_3 = Vector::from_array([lhs[0], lhs[1], lhs[2]]);
     let rhs_3 = Vector::from_array([rhs[0], rhs[1], rhs[2]]);
 
     let cross_product = lhs_3.cross(rhs_3);
-    v![cross_product[0], cross_product[1], cross_product[2], 0.0]
+    v![
+        cross_product[0],
+        cross_product[1],
+        cross_product[2],
+        T::ZERO
+    ]
 }
@@ -0,0 +1,402 @@
+//! Seeded gradient noise for generating terrain and other content.
+//!
+//! [Perlin] and [Simplex] noise are smooth pseudo random functions in two or
+//! three dimensions, [Fbm] sums several octaves of either for more detail.
+//! The same seed gives the same values on every platform: only basic float
+//! arithmetic and rounding is used, no functions like `sin` whose precision
+//! depends on the platform's math library.
+//!
+//! ```
+//! # use graphic::noise::{Fbm, Noise, Perlin};
+//! let hills = Fbm::new(Perlin::new(7), 4).with_frequency(1.0 / 32.0);
+//!
+//! let height = 10.0 * hills.sample2(12.5, -3.0);
+//! assert!(height.abs() <= 10.0);
+//! assert_eq!(height, 10.0 * hills.sample2(12.5, -3.0));
+//!
+//! // Generated content depends on the exact values, they must not change.
+//! assert_eq!(Perlin::new(3).sample2(0.5, 0.25), 0.1508789);
+//! ```
+
+/// A noise function, returning values in `[-1, 1]`.
+pub trait Noise {
+    fn sample2(&self, x: f32, y: f32) -> f32;
+    fn sample3(&self, x: f32, y: f32, z: f32) -> f32;
+}
+
+impl<N: Noise + ?Sized> Noise for &N {
+    fn sample2(&self, x: f32, y: f32) -> f32 {
+        (**self).sample2(x, y)
+    }
+
+    fn sample3(&self, x: f32, y: f32, z: f32) -> f32 {
+        (**self).sample3(x, y, z)
+    }
+}
+
+/// The lattice points are hashed with a shuffled table of the numbers
+/// `0..256`, repeated to save wrapping the sums of two lookups.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Permutation([u8; 512]);
+
+impl Permutation {
+    fn new(seed: u64) -> Self {
+        let mut rng = SplitMix64(seed);
+        let mut table = [0; 256];
+        for (i, value) in table.iter_mut().enumerate() {
+            *value = i as u8;
+        }
+        // Fisher-Yates.
+        for i in (1..table.len()).rev() {
+            let j = (rng.next() % (i as u64 + 1)) as usize;
+            table.swap(i, j);
+        }
+
+        let mut repeated = [0; 512];
+        repeated[..256].copy_from_slice(&table);
+        repeated[256..].copy_from_slice(&table);
+        Self(repeated)
+    }
+
+    /// The hash of the lattice point `(x, y)`, both wrapped to `0..256`.
+    fn hash2(&self, x: usize, y: usize) -> usize {
+        self.0[x + self.0[y] as usize] as usize
+    }
+
+    fn hash3(&self, x: usize, y: usize, z: usize) -> usize {
+        self.0[x + self.hash2(y, z)] as usize
+    }
+}
+
+/// A small, fast generator for the permutations, not for anything else.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
+
+/// The gradients of the 2D noise, towards the corners and edges of a square.
+const GRADIENTS_2: [[f32; 2]; 8] = [
+    [1.0, 1.0],
+    [-1.0, 1.0],
+    [1.0, -1.0],
+    [-1.0, -1.0],
+    [1.0, 0.0],
+    [-1.0, 0.0],
+    [0.0, 1.0],
+    [0.0, -1.0],
+];
+
+/// The gradients of the 3D noise, towards the edges of a cube.
+const GRADIENTS_3: [[f32; 3]; 12] = [
+    [1.0, 1.0, 0.0],
+    [-1.0, 1.0, 0.0],
+    [1.0, -1.0, 0.0],
+    [-1.0, -1.0, 0.0],
+    [1.0, 0.0, 1.0],
+    [-1.0, 0.0, 1.0],
+    [1.0, 0.0, -1.0],
+    [-1.0, 0.0, -1.0],
+    [0.0, 1.0, 1.0],
+    [0.0, -1.0, 1.0],
+    [0.0, 1.0, -1.0],
+    [0.0, -1.0, -1.0],
+];
+
+fn gradient2(hash: usize, x: f32, y: f32) -> f32 {
+    let [gx, gy] = GRADIENTS_2[hash % GRADIENTS_2.len()];
+    gx * x + gy * y
+}
+
+fn gradient3(hash: usize, x: f32, y: f32, z: f32) -> f32 {
+    let [gx, gy, gz] = GRADIENTS_3[hash % GRADIENTS_3.len()];
+    gx * x + gy * y + gz * z
+}
+
+/// The lattice cell containing `value`, wrapped to the permutation table,
+/// and the position inside of it.
+fn cell(value: f32) -> (usize, f32) {
+    let floor = value.floor();
+    ((floor as i32 & 255) as usize, value - floor)
+}
+
+/// Perlin's quintic `6t^5 - 15t^4 + 10t^3`, smooth up to the second derivative.
+fn fade(t: f32) -> f32 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+fn lerp(t: f32, a: f32, b: f32) -> f32 {
+    a + t * (b - a)
+}
+
+/// Ken Perlin's improved gradient noise.
+///
+/// It is 0 at every integer lattice point, so sample between them, e.g. by
+/// scaling the coordinates down. The noise repeats every 256 units.
+///
+/// ```
+/// # use graphic::noise::{Noise, Perlin};
+/// let noise = Perlin::new(42);
+/// assert_eq!(noise.sample2(3.0, -5.0), 0.0);
+/// assert_ne!(noise.sample2(3.5, -5.5), 0.0);
+/// assert_eq!(noise.sample3(0.5, 1.5, 2.5), noise.sample3(256.5, 1.5, 2.5));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Perlin {
+    permutation: Permutation,
+}
+
+impl Perlin {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            permutation: Permutation::new(seed),
+        }
+    }
+}
+
+impl Noise for Perlin {
+    fn sample2(&self, x: f32, y: f32) -> f32 {
+        let (xi, x) = cell(x);
+        let (yi, y) = cell(y);
+        let (u, v) = (fade(x), fade(y));
+
+        let hash = |dx, dy| self.permutation.hash2(xi + dx, yi + dy);
+        let bottom = lerp(
+            u,
+            gradient2(hash(0, 0), x, y),
+            gradient2(hash(1, 0), x - 1.0, y),
+        );
+        let top = lerp(
+            u,
+            gradient2(hash(0, 1), x, y - 1.0),
+            gradient2(hash(1, 1), x - 1.0, y - 1.0),
+        );
+        lerp(v, bottom, top)
+    }
+
+    fn sample3(&self, x: f32, y: f32, z: f32) -> f32 {
+        let (xi, x) = cell(x);
+        let (yi, y) = cell(y);
+        let (zi, z) = cell(z);
+        let (u, v, w) = (fade(x), fade(y), fade(z));
+
+        // The gradient of the corner `(dx, dy, dz)` evaluated at the point.
+        let corner = |dx: usize, dy: usize, dz: usize| {
+            let hash = self.permutation.hash3(xi + dx, yi + dy, zi + dz);
+            gradient3(hash, x - dx as f32, y - dy as f32, z - dz as f32)
+        };
+        let face = |dz| {
+            lerp(
+                v,
+                lerp(u, corner(0, 0, dz), corner(1, 0, dz)),
+                lerp(u, corner(0, 1, dz), corner(1, 1, dz)),
+            )
+        };
+        // The 3D gradients are longer than 1, the noise is scaled back.
+        lerp(w, face(0), face(1)) / 1.1
+    }
+}
+
+/// Skews the input space onto the 2D simplex grid of triangles, `(sqrt(3) - 1) / 2`.
+const SKEW_2: f32 = 0.366_025_42;
+/// Unskews back, `(3 - sqrt(3)) / 6`.
+const UNSKEW_2: f32 = 0.211_324_87;
+const SKEW_3: f32 = 1.0 / 3.0;
+const UNSKEW_3: f32 = 1.0 / 6.0;
+
+/// Simplex noise, with fewer directional artifacts than [Perlin] noise.
+///
+/// Samples are computed from the corners of a triangle or tetrahedron
+/// instead of a square or cube. The noise repeats every 256 units along
+/// the skewed axes of that grid.
+///
+/// ```
+/// # use graphic::noise::{Noise, Simplex};
+/// let noise = Simplex::new(42);
+/// assert_eq!(noise.sample2(0.0, 0.0), 0.0);
+/// assert_ne!(noise.sample3(0.3, 0.2, 0.1), 0.0);
+/// assert_ne!(noise.sample3(0.3, 0.2, 0.1), Simplex::new(43).sample3(0.3, 0.2, 0.1));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Simplex {
+    permutation: Permutation,
+}
+
+impl Simplex {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            permutation: Permutation::new(seed),
+        }
+    }
+}
+
+impl Noise for Simplex {
+    fn sample2(&self, x: f32, y: f32) -> f32 {
+        let skew = (x + y) * SKEW_2;
+        let (i, j) = ((x + skew).floor(), (y + skew).floor());
+        let unskew = (i + j) * UNSKEW_2;
+        // Relative to the first corner.
+        let (x0, y0) = (x - (i - unskew), y - (j - unskew));
+        // The middle corner of the triangle the point is in.
+        let (i1, j1) = if x0 > y0 { (1, 0) } else { (0, 1) };
+        let corners = [
+            (0, 0, x0, y0),
+            (i1, j1, x0 - i1 as f32 + UNSKEW_2, y0 - j1 as f32 + UNSKEW_2),
+            (1, 1, x0 - 1.0 + 2.0 * UNSKEW_2, y0 - 1.0 + 2.0 * UNSKEW_2),
+        ];
+
+        let (ii, jj) = ((i as i32 & 255) as usize, (j as i32 & 255) as usize);
+        let sum = corners
+            .into_iter()
+            .map(|(di, dj, x, y)| {
+                let falloff = 0.5 - x * x - y * y;
+                if falloff <= 0.0 {
+                    return 0.0;
+                }
+                let hash = self.permutation.hash2(ii + di, jj + dj);
+                falloff * falloff * falloff * falloff * gradient2(hash, x, y)
+            })
+            .sum::<f32>();
+        70.0 * sum
+    }
+
+    fn sample3(&self, x: f32, y: f32, z: f32) -> f32 {
+        let skew = (x + y + z) * SKEW_3;
+        let (i, j, k) = ((x + skew).floor(), (y + skew).floor(), (z + skew).floor());
+        let unskew = (i + j + k) * UNSKEW_3;
+        let (x0, y0, z0) = (x - (i - unskew), y - (j - unskew), z - (k - unskew));
+        // The second and third corner of the tetrahedron the point is in,
+        // by the order of the coordinates.
+        let (second, third) = if x0 >= y0 {
+            if y0 >= z0 {
+                ([1, 0, 0], [1, 1, 0])
+            } else if x0 >= z0 {
+                ([1, 0, 0], [1, 0, 1])
+            } else {
+                ([0, 0, 1], [1, 0, 1])
+            }
+        } else if y0 < z0 {
+            ([0, 0, 1], [0, 1, 1])
+        } else if x0 < z0 {
+            ([0, 1, 0], [0, 1, 1])
+        } else {
+            ([0, 1, 0], [1, 1, 0])
+        };
+
+        let (ii, jj, kk) = (
+            (i as i32 & 255) as usize,
+            (j as i32 & 255) as usize,
+            (k as i32 & 255) as usize,
+        );
+        [[0, 0, 0], second, third, [1, 1, 1]]
+            .into_iter()
+            .map(|offset| {
+                let corner_unskew = offset.iter().sum::<usize>() as f32 * UNSKEW_3;
+                let x = x0 - offset[0] as f32 + corner_unskew;
+                let y = y0 - offset[1] as f32 + corner_unskew;
+                let z = z0 - offset[2] as f32 + corner_unskew;
+                let falloff = 0.6 - x * x - y * y - z * z;
+                if falloff <= 0.0 {
+                    return 0.0;
+                }
+                let hash = self
+                    .permutation
+                    .hash3(ii + offset[0], jj + offset[1], kk + offset[2]);
+                falloff * falloff * falloff * falloff * gradient3(hash, x, y, z)
+            })
+            .sum::<f32>()
+            * 32.0
+    }
+}
+
+/// Fractional Brownian motion, the sum of `octaves` samples of `noise` with
+/// increasing frequency and decreasing amplitude.
+///
+/// Every octave is sampled at `lacunarity` times the frequency of the one
+/// before, with `gain` times its amplitude. The sum is divided by the total
+/// amplitude, so it stays in `[-1, 1]`.
+///
+/// ```
+/// # use graphic::noise::{Fbm, Noise, Simplex};
+/// let rough = Fbm::new(Simplex::new(1), 6).with_gain(0.7);
+/// let smooth = Fbm::new(Simplex::new(1), 1);
+/// assert_eq!(smooth.sample2(0.4, 0.9), Simplex::new(1).sample2(0.4, 0.9));
+/// assert_ne!(rough.sample2(0.4, 0.9), smooth.sample2(0.4, 0.9));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fbm<N> {
+    pub noise: N,
+    pub octaves: u32,
+    /// The frequency of the first octave, 1 by default.
+    pub frequency: f32,
+    /// 2 by default.
+    pub lacunarity: f32,
+    /// 0.5 by default.
+    pub gain: f32,
+}
+
+impl<N: Noise> Fbm<N> {
+    pub fn new(noise: N, octaves: u32) -> Self {
+        Self {
+            noise,
+            octaves,
+            frequency: 1.0,
+            lacunarity: 2.0,
+            gain: 0.5,
+        }
+    }
+
+    pub fn with_frequency(self, frequency: f32) -> Self {
+        Self { frequency, ..self }
+    }
+
+    pub fn with_lacunarity(self, lacunarity: f32) -> Self {
+        Self { lacunarity, ..self }
+    }
+
+    pub fn with_gain(self, gain: f32) -> Self {
+        Self { gain, ..self }
+    }
+
+    /// Sum the octaves of `sample`, called with the frequency and an offset
+    /// moving every octave to a different part of the noise. Otherwise the
+    /// octaves would line up at the origin.
+    fn sum(&self, sample: impl Fn(f32, f32) -> f32) -> f32 {
+        let mut frequency = self.frequency;
+        let mut amplitude = 1.0;
+        let mut total = 0.0;
+        let mut sum = 0.0;
+        for octave in 0..self.octaves {
+            sum += amplitude * sample(frequency, octave as f32 * 31.7);
+            total += amplitude;
+            frequency *= self.lacunarity;
+            amplitude *= self.gain;
+        }
+        if total > 0.0 { sum / total } else { 0.0 }
+    }
+}
+
+impl<N: Noise> Noise for Fbm<N> {
+    fn sample2(&self, x: f32, y: f32) -> f32 {
+        self.sum(|frequency, offset| {
+            self.noise
+                .sample2(x * frequency + offset, y * frequency + offset)
+        })
+    }
+
+    fn sample3(&self, x: f32, y: f32, z: f32) -> f32 {
+        self.sum(|frequency, offset| {
+            self.noise.sample3(
+                x * frequency + offset,
+                y * frequency + offset,
+                z * frequency + offset,
+            )
+        })
+    }
+}
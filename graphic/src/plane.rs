@@ -0,0 +1,143 @@
+//! Infinite planes and the distances of points and rays to them.
+//!
+//! A [Plane] is given by its unit `normal` and its distance `d` from the
+//! origin along the normal. The points `p` of the plane are the ones with
+//! ```text
+//! p * normal = d
+//! ```
+//! the same form [reflect](crate::transform::reflect) uses.
+//!
+//! The normal also picks a side, the distances are positive in front of the
+//! plane, where the normal points to, and negative behind it. The six planes
+//! of a view frustum e.g. all face inwards, so a point is inside when it is
+//! in front of every plane.
+
+use lina::{matrix::Matrix, v, vector::Vector};
+
+use crate::transform::normal_matrix;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Plane {
+    /// Always normalized.
+    pub normal: Vector<f32, 3>,
+    pub d: f32,
+}
+
+impl Plane {
+    /// The plane with the points `p` for which `p * normal = d`.
+    ///
+    /// The `normal` doesn't have to be of unit length, the plane is
+    /// normalized along with it.
+    pub fn new(normal: Vector<f32, 3>, d: f32) -> Self {
+        let length = normal.length();
+        Self {
+            normal: normal * (1.0 / length),
+            d: d / length,
+        }
+    }
+
+    /// The plane through `point` facing `normal`, which doesn't have to be of
+    /// unit length.
+    ///
+    /// ```
+    /// # use graphic::plane::Plane;
+    /// # use lina::v;
+    /// let ground = Plane::from_point_normal(v![3.0, 2.0, -1.0], v![0.0, 4.0, 0.0]);
+    ///
+    /// assert_eq!(ground, Plane::new(v![0.0, 1.0, 0.0], 2.0));
+    /// ```
+    pub fn from_point_normal(point: Vector<f32, 3>, normal: Vector<f32, 3>) -> Self {
+        let normal = normal.normalized();
+        Self {
+            normal,
+            d: point * normal,
+        }
+    }
+
+    /// The plane through the points of the triangle `a`, `b`, `c`, facing the
+    /// side the points are counter-clockwise from, like the front faces.
+    ///
+    /// The points must not lie on a line.
+    ///
+    /// ```
+    /// # use graphic::plane::Plane;
+    /// # use lina::v;
+    /// let plane = Plane::from_points(v![0.0, 0.0, 1.0], v![1.0, 0.0, 1.0], v![0.0, 1.0, 1.0]);
+    ///
+    /// assert_eq!(plane, Plane::new(v![0.0, 0.0, 1.0], 1.0));
+    /// ```
+    pub fn from_points(a: Vector<f32, 3>, b: Vector<f32, 3>, c: Vector<f32, 3>) -> Self {
+        Self::from_point_normal(a, (b - a).cross(c - a))
+    }
+
+    /// The distance of `point` from the plane, negative behind it.
+    ///
+    /// ```
+    /// # use graphic::plane::Plane;
+    /// # use lina::v;
+    /// let ground = Plane::new(v![0.0, 1.0, 0.0], 2.0);
+    ///
+    /// assert_eq!(ground.signed_distance(v![5.0, 3.5, 1.0]), 1.5);
+    /// assert_eq!(ground.signed_distance(v![5.0, -1.0, 1.0]), -3.0);
+    /// ```
+    pub fn signed_distance(&self, point: Vector<f32, 3>) -> f32 {
+        point * self.normal - self.d
+    }
+
+    /// The point of the plane closest to `point`.
+    pub fn project(&self, point: Vector<f32, 3>) -> Vector<f32, 3> {
+        point - self.normal * self.signed_distance(point)
+    }
+
+    /// How far along the ray from `origin` in the normalized `direction` the
+    /// plane is hit, from either side.
+    ///
+    /// `None` if the ray runs parallel to the plane or points away from it.
+    /// A ray starting on the plane hits it right away.
+    ///
+    /// ```
+    /// # use graphic::plane::Plane;
+    /// # use lina::v;
+    /// let ground = Plane::new(v![0.0, 1.0, 0.0], 0.0);
+    /// let down = v![0.0, -1.0, 0.0];
+    ///
+    /// assert_eq!(ground.ray_distance(v![1.0, 4.0, 2.0], down), Some(4.0));
+    /// assert_eq!(ground.ray_distance(v![1.0, -4.0, 2.0], down), None);
+    /// assert_eq!(ground.ray_distance(v![1.0, 4.0, 2.0], v![1.0, 0.0, 0.0]), None);
+    /// ```
+    pub fn ray_distance(&self, origin: Vector<f32, 3>, direction: Vector<f32, 3>) -> Option<f32> {
+        let approach = direction * self.normal;
+        if approach == 0.0 {
+            return None;
+        }
+        let distance = -self.signed_distance(origin) / approach;
+        (distance >= 0.0).then_some(distance)
+    }
+
+    /// The plane moved along with its points by the affine `matrix`.
+    ///
+    /// The normal is transformed with the [normal_matrix], so non-uniform
+    /// scaling and shearing keep it perpendicular to the plane. Reflections
+    /// flip the side the plane faces along with everything else.
+    ///
+    /// ```
+    /// # use graphic::plane::Plane;
+    /// # use graphic::transform::{rotate_z, scale, translate};
+    /// # use lina::angle::Deg;
+    /// # use lina::v;
+    /// let ground = Plane::new(v![0.0, 1.0, 0.0], 0.0);
+    ///
+    /// let raised = ground.transformed(&(translate(0.0, 3.0, 0.0) * scale(2.0, 5.0, 2.0)));
+    /// assert_eq!(raised, Plane::new(v![0.0, 1.0, 0.0], 3.0));
+    ///
+    /// let wall = ground.transformed(&rotate_z(Deg(-90.0)));
+    /// assert!(wall.normal.approx_eq(&v![1.0, 0.0, 0.0], 1e-6));
+    /// assert!(wall.d.abs() < 1e-6);
+    /// ```
+    pub fn transformed(&self, matrix: &Matrix<f32, 4, 4>) -> Self {
+        let point = self.normal * self.d;
+        let point = *matrix * v![point[0], point[1], point[2], 1.0];
+        let point = v![point[0], point[1], point[2]];
+        Self::from_point_normal(point, normal_matrix(matrix) * self.normal)
+    }
+}
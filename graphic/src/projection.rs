@@ -0,0 +1,247 @@
+//! Projection parameters with a cached projection matrix.
+
+use lina::{angle::Rad, m, matrix::Matrix, vector::Vector};
+
+use crate::transform::{
+    orthographic_aspect, perspective_proj_sym, perspective_proj_sym_inf,
+    perspective_proj_sym_inf_rev, perspective_proj_sym_rev,
+};
+
+/// The kind of projection with its kind specific parameter.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProjectionMode {
+    /// Perspective projection with the **horizontal field of view**.
+    Perspective { fov_x: Rad },
+    /// Orthographic projection with the height of the view volume in **world space**.
+    Orthographic { height: f32 },
+}
+
+/// A projection described by its parameters instead of a raw matrix.
+///
+/// The [Matrix] is regenerated whenever a parameter changes, so
+/// [matrix](Projection::matrix) is free to call every frame.
+///
+/// The parameters follow the same rules as the functions in
+/// [transform](crate::transform). `z_near` and `z_far` are negative, as the
+/// camera looks down the -Z axis. For a perspective projection `z_far` may be
+/// negative infinity.
+///
+/// With [set_reverse_z](Projection::set_reverse_z) the depth is mapped in reverse,
+/// see [perspective_proj_sym_rev] on what that requires from the renderer.
+///
+/// ```
+/// # use std::f32::consts::PI;
+/// # use graphic::projection::Projection;
+/// # use graphic::transform::perspective_proj_sym_h_fov;
+/// # use lina::angle::Rad;
+/// let mut projection = Projection::perspective(Rad(PI / 2.0), 4.0 / 3.0, -1.0, -100.0);
+/// projection.set_aspect_ratio(16.0 / 9.0);
+///
+/// assert_eq!(
+///     projection.matrix(),
+///     perspective_proj_sym_h_fov(Rad(PI / 2.0), 16.0 / 9.0, -1.0, -100.0)
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Projection {
+    mode: ProjectionMode,
+    aspect_ratio: f32,
+    z_near: f32,
+    z_far: f32,
+    reverse_z: bool,
+    jitter: Vector<f32, 2>,
+    matrix: Matrix<f32, 4, 4>,
+}
+
+impl Projection {
+    /// Create a perspective [Projection] using the **horizontal field of view** `fov_x`.
+    pub fn perspective(fov_x: impl Into<Rad>, aspect_ratio: f32, z_near: f32, z_far: f32) -> Self {
+        Self::new(
+            ProjectionMode::Perspective {
+                fov_x: fov_x.into(),
+            },
+            aspect_ratio,
+            z_near,
+            z_far,
+        )
+    }
+
+    /// Create an orthographic [Projection] for a view volume `height` tall, centered
+    /// on the -Z axis.
+    ///
+    /// Orthographic projections don't support an infinite `z_far`.
+    pub fn orthographic(height: f32, aspect_ratio: f32, z_near: f32, z_far: f32) -> Self {
+        Self::new(
+            ProjectionMode::Orthographic { height },
+            aspect_ratio,
+            z_near,
+            z_far,
+        )
+    }
+
+    fn new(mode: ProjectionMode, aspect_ratio: f32, z_near: f32, z_far: f32) -> Self {
+        let mut projection = Self {
+            mode,
+            aspect_ratio,
+            z_near,
+            z_far,
+            reverse_z: false,
+            jitter: Vector::new(),
+            matrix: Matrix::identity(),
+        };
+        projection.update();
+        projection
+    }
+
+    /// The projection [Matrix], including the jitter offset.
+    pub fn matrix(&self) -> Matrix<f32, 4, 4> {
+        self.matrix
+    }
+
+    pub fn mode(&self) -> ProjectionMode {
+        self.mode
+    }
+
+    /// Switch between a perspective and orthographic projection, keeping
+    /// all other parameters.
+    pub fn set_mode(&mut self, mode: ProjectionMode) {
+        self.mode = mode;
+        self.update();
+    }
+
+    /// The horizontal field of view, `None` for an orthographic projection.
+    pub fn fov(&self) -> Option<Rad> {
+        match self.mode {
+            ProjectionMode::Perspective { fov_x } => Some(fov_x),
+            ProjectionMode::Orthographic { .. } => None,
+        }
+    }
+
+    /// Set the horizontal field of view.
+    ///
+    /// Turns an orthographic projection into a perspective one.
+    pub fn set_fov(&mut self, fov_x: impl Into<Rad>) {
+        self.set_mode(ProjectionMode::Perspective {
+            fov_x: fov_x.into(),
+        });
+    }
+
+    pub fn aspect_ratio(&self) -> f32 {
+        self.aspect_ratio
+    }
+
+    /// Set the aspect ratio, the width divided by the height of the viewport.
+    ///
+    /// The [Matrix] is only regenerated if the value changed, so it can be called
+    /// with the current size of the window every frame.
+    pub fn set_aspect_ratio(&mut self, aspect_ratio: f32) {
+        if self.aspect_ratio != aspect_ratio {
+            self.aspect_ratio = aspect_ratio;
+            self.update();
+        }
+    }
+
+    pub fn near(&self) -> f32 {
+        self.z_near
+    }
+
+    pub fn set_near(&mut self, z_near: f32) {
+        self.z_near = z_near;
+        self.update();
+    }
+
+    pub fn far(&self) -> f32 {
+        self.z_far
+    }
+
+    pub fn set_far(&mut self, z_far: f32) {
+        self.z_far = z_far;
+        self.update();
+    }
+
+    pub fn reverse_z(&self) -> bool {
+        self.reverse_z
+    }
+
+    /// Map `z_near` to 1.0 and `z_far` to 0.0 instead of the other way around.
+    pub fn set_reverse_z(&mut self, reverse_z: bool) {
+        self.reverse_z = reverse_z;
+        self.update();
+    }
+
+    pub fn jitter(&self) -> Vector<f32, 2> {
+        self.jitter
+    }
+
+    /// Offset the projected image by `jitter` in **normalized device coordinates**.
+    ///
+    /// Temporal anti-aliasing samples a slightly different position within each
+    /// pixel every frame. For an offset of `p` pixels in a viewport `size` pixels
+    /// large the jitter is:
+    /// ```text
+    /// jitter = (2 * p.x / size.x, -2 * p.y / size.y)
+    /// ```
+    /// The Y component is negated as the screen Y axis points down.
+    ///
+    /// The offset is applied after the perspective division, so it shifts near
+    /// and far objects by the same sub-pixel amount.
+    ///
+    /// ```
+    /// # use graphic::projection::Projection;
+    /// # use lina::v;
+    /// let mut projection = Projection::orthographic(2.0, 1.0, -1.0, -10.0);
+    /// projection.set_jitter(v![0.01, -0.02]);
+    ///
+    /// let center = projection.matrix() * v![0.0, 0.0, -5.0, 1.0];
+    /// assert_eq!(center[0] / center[3], 0.01);
+    /// assert_eq!(center[1] / center[3], -0.02);
+    /// ```
+    pub fn set_jitter(&mut self, jitter: Vector<f32, 2>) {
+        self.jitter = jitter;
+        self.update();
+    }
+
+    fn update(&mut self) {
+        let projection = match self.mode {
+            ProjectionMode::Perspective { fov_x: Rad(fov_x) } => {
+                let right = -self.z_near * (fov_x / 2.0).tan();
+                let top = right / self.aspect_ratio;
+                match (self.z_far == f32::NEG_INFINITY, self.reverse_z) {
+                    (false, false) => perspective_proj_sym(right, top, self.z_near, self.z_far),
+                    (false, true) => perspective_proj_sym_rev(right, top, self.z_near, self.z_far),
+                    (true, false) => perspective_proj_sym_inf(right, top, self.z_near),
+                    (true, true) => perspective_proj_sym_inf_rev(right, top, self.z_near),
+                }
+            }
+            ProjectionMode::Orthographic { height } => {
+                let projection =
+                    orthographic_aspect(height, self.aspect_ratio, self.z_near, self.z_far);
+                if self.reverse_z {
+                    // z' = 1 - z, w is always 1 for an orthographic projection.
+                    #[rustfmt::skip]
+                    let reverse = m![
+                        [1.0, 0.0, 0.0,  0.0],
+                        [0.0, 1.0, 0.0,  0.0],
+                        [0.0, 0.0, -1.0, 1.0],
+                        [0.0, 0.0, 0.0,  1.0]
+                    ];
+                    reverse * projection
+                } else {
+                    projection
+                }
+            }
+        };
+
+        // The last component of a clip space position is `w`, so the translation
+        // gets scaled by it, resulting in the same offset for every depth after the
+        // perspective division.
+        #[rustfmt::skip]
+        let jitter = m![
+            [1.0, 0.0, 0.0, self.jitter[0]],
+            [0.0, 1.0, 0.0, self.jitter[1]],
+            [0.0, 0.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0]
+        ];
+        self.matrix = jitter * projection;
+    }
+}
@@ -0,0 +1,186 @@
+//! Smooth paths through space, e.g. for camera fly-throughs.
+//!
+//! A [Spline] is a chain of cubic Bézier segments, each running from one
+//! point to the next, pulled towards two control points in between. It can be
+//! given by its control points, or as a Catmull-Rom spline passing through
+//! every point.
+//!
+//! The parameter `t` runs from 0 at the start to 1 at the end of the spline,
+//! each segment getting an equal share. As the segments differ in length and
+//! the speed varies along them, moving `t` evenly doesn't move evenly along
+//! the path. [ArcLength] maps distances along the path to `t` for that.
+
+use lina::vector::Vector;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spline {
+    // The start, the two control points and the end of every segment, the
+    // end shared with the start of the next one.
+    points: Vec<Vector<f32, 3>>,
+}
+
+impl Spline {
+    /// The spline of cubic Bézier segments given by `points`: the start, the
+    /// two control points and the end of the first segment, then the two
+    /// control points and the end of every following one.
+    ///
+    /// Panics unless there are `3n + 1` points for `n > 0` segments.
+    ///
+    /// ```
+    /// # use graphic::spline::Spline;
+    /// # use lina::v;
+    /// let arc = Spline::bezier(vec![
+    ///     v![0.0, 0.0, 0.0],
+    ///     v![0.0, 1.0, 0.0],
+    ///     v![1.0, 1.0, 0.0],
+    ///     v![1.0, 0.0, 0.0],
+    /// ]);
+    ///
+    /// assert_eq!(arc.position(0.5), v![0.5, 0.75, 0.0]);
+    /// // Leaving towards the first control point.
+    /// assert_eq!(arc.tangent(0.0), v![0.0, 3.0, 0.0]);
+    /// ```
+    pub fn bezier(points: Vec<Vector<f32, 3>>) -> Self {
+        assert!(
+            points.len() >= 4 && points.len() % 3 == 1,
+            "a Bézier spline has 3 points per segment and an end"
+        );
+        Self { points }
+    }
+
+    /// The Catmull-Rom spline passing through all `points` in order.
+    ///
+    /// At every point the path heads the way from the point before to the
+    /// one after, the first and last point only look at their one neighbor.
+    ///
+    /// Panics if there are less than two points.
+    ///
+    /// ```
+    /// # use graphic::spline::Spline;
+    /// # use lina::v;
+    /// let points = vec![v![0.0, 0.0, 0.0], v![1.0, 2.0, 0.0], v![3.0, 2.0, 1.0]];
+    /// let spline = Spline::catmull_rom(&points);
+    ///
+    /// assert_eq!(spline.position(0.0), points[0]);
+    /// assert_eq!(spline.position(0.5), points[1]);
+    /// assert_eq!(spline.position(1.0), points[2]);
+    /// // The middle point is passed heading from the first to the last.
+    /// // Twice the speed, as the segment only covers half of `t`.
+    /// assert!(spline.tangent(0.5).approx_eq(&(points[2] - points[0]), 1e-6));
+    /// ```
+    pub fn catmull_rom(points: &[Vector<f32, 3>]) -> Self {
+        assert!(points.len() >= 2, "a spline needs at least two points");
+        let last = points.len() - 1;
+        let tangent = |index: usize| {
+            let (before, after) = (index.saturating_sub(1), (index + 1).min(last));
+            (points[after] - points[before]) * (1.0 / (after - before) as f32)
+        };
+
+        // The Hermite segments as Béziers, their control points a third of
+        // the tangent away from the ends.
+        let mut bezier = vec![points[0]];
+        for index in 0..last {
+            let (start, end) = (points[index], points[index + 1]);
+            bezier.push(start + tangent(index) * (1.0 / 3.0));
+            bezier.push(end - tangent(index + 1) * (1.0 / 3.0));
+            bezier.push(end);
+        }
+        Self { points: bezier }
+    }
+
+    pub fn segment_count(&self) -> usize {
+        (self.points.len() - 1) / 3
+    }
+
+    /// The point at `t`, clamped to the ends.
+    pub fn position(&self, t: f32) -> Vector<f32, 3> {
+        let ([p0, p1, p2, p3], t) = self.segment(t);
+        let s = 1.0 - t;
+        p0 * (s * s * s) + p1 * (3.0 * s * s * t) + p2 * (3.0 * s * t * t) + p3 * (t * t * t)
+    }
+
+    /// The direction of travel at `t`, its length the speed along the path
+    /// as `t` changes.
+    pub fn tangent(&self, t: f32) -> Vector<f32, 3> {
+        let ([p0, p1, p2, p3], t) = self.segment(t);
+        let s = 1.0 - t;
+        let derivative =
+            (p1 - p0) * (3.0 * s * s) + (p2 - p1) * (6.0 * s * t) + (p3 - p2) * (3.0 * t * t);
+        // Each segment only covers its share of `t`.
+        derivative * self.segment_count() as f32
+    }
+
+    // The points of the segment at `t` and `t` within it.
+    fn segment(&self, t: f32) -> ([Vector<f32, 3>; 4], f32) {
+        let count = self.segment_count();
+        let scaled = t.clamp(0.0, 1.0) * count as f32;
+        let index = (scaled as usize).min(count - 1);
+        let first = index * 3;
+        (
+            std::array::from_fn(|point| self.points[first + point]),
+            scaled - index as f32,
+        )
+    }
+}
+
+/// The length along a [Spline] at evenly spaced values of `t`, for moving
+/// along it at a constant speed.
+///
+/// ```
+/// # use graphic::spline::{ArcLength, Spline};
+/// # use lina::v;
+/// // A short and a long segment.
+/// let spline = Spline::catmull_rom(&[v![0.0, 0.0, 0.0], v![1.0, 0.0, 0.0], v![4.0, 0.0, 0.0]]);
+/// let arc_length = ArcLength::new(&spline, 64);
+///
+/// assert!((arc_length.length() - 4.0).abs() < 1e-4);
+/// // Half way along the path is well into the second segment.
+/// let t = arc_length.parameter(2.0);
+/// assert!((spline.position(t)[0] - 2.0).abs() < 1e-3);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArcLength {
+    // The length up to `t = i / (lengths.len() - 1)`.
+    lengths: Vec<f32>,
+}
+
+impl ArcLength {
+    /// Measure `spline` along straight lines between `samples_per_segment`
+    /// points of every segment, more are more precise.
+    pub fn new(spline: &Spline, samples_per_segment: usize) -> Self {
+        let samples = spline.segment_count() * samples_per_segment.max(1);
+        let mut lengths = Vec::with_capacity(samples + 1);
+        let mut length = 0.0;
+        let mut previous = spline.position(0.0);
+        lengths.push(length);
+        for sample in 1..=samples {
+            let position = spline.position(sample as f32 / samples as f32);
+            length += (position - previous).length();
+            lengths.push(length);
+            previous = position;
+        }
+        Self { lengths }
+    }
+
+    /// The length of the whole spline.
+    pub fn length(&self) -> f32 {
+        *self.lengths.last().expect("there is the start at least")
+    }
+
+    /// The `t` `distance` along the spline, clamped to the ends.
+    pub fn parameter(&self, distance: f32) -> f32 {
+        let distance = distance.clamp(0.0, self.length());
+        let samples = self.lengths.len() - 1;
+        let after = self
+            .lengths
+            .partition_point(|length| *length < distance)
+            .clamp(1, samples);
+        let (start, end) = (self.lengths[after - 1], self.lengths[after]);
+        let within = if end > start {
+            (distance - start) / (end - start)
+        } else {
+            0.0
+        };
+        (after - 1) as f32 / samples as f32 + within / samples as f32
+    }
+}
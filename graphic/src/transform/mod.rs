@@ -51,15 +51,24 @@
 //! from [Real-Time Rendering](https://www.realtimerendering.com/) book one may understand
 //! what is necessary.
 
-use lina::{m, matrix::Matrix, vector::Vector};
+use lina::{matrix::Matrix, v, vector::Vector};
+
+use crate::float::Float;
+
+mod normal;
 mod project;
+mod reflect;
 mod rotate;
 mod scale;
+mod shear;
 mod translate;
 
+pub use normal::*;
 pub use project::*;
+pub use reflect::*;
 pub use rotate::*;
 pub use scale::*;
+pub use shear::*;
 pub use translate::*;
 
 /// Generate a "Point At" [Matrix] for object `O`.
@@ -87,21 +96,22 @@ pub use translate::*;
 /// It doesn't handle the case when the `up` vector is parallel to the vector between
 /// `source` and `target`.
 #[rustfmt::skip]
-pub fn point_at(
-    source: Vector<f32, 3>,
-    target: Vector<f32, 3>,
-    up: Vector<f32, 3>,
-) -> Matrix<f32, 4, 4> {
+pub fn point_at<T: Float>(
+    source: Vector<T, 3>,
+    target: Vector<T, 3>,
+    up: Vector<T, 3>,
+) -> Matrix<T, 4, 4> {
     let forward = (source - target).normalized();
     let left = up.cross(forward).normalized();
     let up = forward.cross(left).normalized();
 
-    m![
-        [left[0], up[0], forward[0], source[0]],
-        [left[1], up[1], forward[1], source[1]],
-        [left[2], up[2], forward[2], source[2]],
-        [0.0, 0.0, 0.0, 1.0],
-    ]
+    let (zero, one) = (T::ZERO, T::ONE);
+    Matrix::from_cols([
+        v![left[0],    left[1],    left[2],    zero],
+        v![up[0],      up[1],      up[2],      zero],
+        v![forward[0], forward[1], forward[2], zero],
+        v![source[0],  source[1],  source[2],  one],
+    ])
 }
 
 /// Generate a "Look At" [Matrix] for object `O`.
@@ -122,7 +132,6 @@ pub fn point_at(
 /// # use graphic::transform::look_at;
 /// # use graphic::identity_matrix;
 /// # use lina::v;
-/// # use float_eq::assert_float_eq;
 /// let source = v![1.0, 2.0, 3.0];
 /// let target = v![4.0, 5.0, 6.0];
 /// let up = v![0.0, 1.0, 0.0];
@@ -133,7 +142,7 @@ pub fn point_at(
 /// let identity = identity_matrix();
 /// let point_look = point_at * look_at;
 /// 
-/// point_look.as_slices().iter().flatten().zip(identity.as_slices().iter().flatten()).for_each(|(l, r)| assert_float_eq!(*l, *r, abs <= 3.0 * f32::EPSILON));
+/// assert!(point_look.approx_eq(&identity, 3.0 * f32::EPSILON));
 /// ```
 /// 
 /// Mostly used for handling cameras in a scene. In practice a camera
@@ -150,19 +159,20 @@ pub fn point_at(
 /// The `O` object is expected to be located at the `source`, in world space, with its
 /// desired final position being at the origin.
 #[rustfmt::skip]
-pub fn look_at(
-    source: Vector<f32, 3>,
-    target: Vector<f32, 3>,
-    up: Vector<f32, 3>,
-) -> Matrix<f32, 4, 4> {
+pub fn look_at<T: Float>(
+    source: Vector<T, 3>,
+    target: Vector<T, 3>,
+    up: Vector<T, 3>,
+) -> Matrix<T, 4, 4> {
     let forward = (source - target).normalized();
     let left = up.cross(forward).normalized();
     let up = forward.cross(left).normalized();
 
-    m![
-        [left[0],    left[1],    left[2],    -source * left],
-        [up[0],      up[1],      up[2],      -source * up],
-        [forward[0], forward[1], forward[2], -source * forward],
-        [0.0,        0.0,        0.0,        1.0],
-    ]
+    let (zero, one) = (T::ZERO, T::ONE);
+    Matrix::from_rows([
+        v![left[0],    left[1],    left[2],    -(source * left)],
+        v![up[0],      up[1],      up[2],      -(source * up)],
+        v![forward[0], forward[1], forward[2], -(source * forward)],
+        v![zero,       zero,       zero,       one],
+    ])
 }
@@ -0,0 +1,50 @@
+use lina::matrix::Matrix;
+
+use crate::float::Float;
+
+/// Generate the matrix transforming the normals of an object with the `model` matrix.
+///
+/// Normals are not points, they have to stay perpendicular to the surface.
+/// With a non-uniform scaling or shearing in `model` transforming them the
+/// same way as the vertices would tilt them. Given the upper 3x3 part `M` of
+/// the `model`, the normal matrix `N` is its inverse-transpose:
+/// ```text
+/// N = (M^-1)^T = adj(M)^T / det(M)
+/// ```
+/// The translation part of `model` is ignored, as normals are directions.
+///
+/// Reflections have a negative determinant, which the division keeps
+/// pointing the normals outwards. When `M` is singular, e.g. an object scaled
+/// to zero in one dimension, `adj(M)^T` is returned instead, which still
+/// results in the correct directions.
+///
+/// Transformed normals don't preserve their length, so they have to be
+/// renormalized.
+///
+/// ```
+/// # use graphic::transform::{normal_matrix, scale};
+/// # use lina::v;
+/// // Stretch along the X axis.
+/// let model = scale(4.0, 1.0, 1.0);
+/// let N = normal_matrix(&model);
+///
+/// // The normal of the x = y plane.
+/// let normal = v![1.0, -1.0, 0.0];
+/// // Two points on the x = y plane, stretched.
+/// let a = model * v![0.0, 0.0, 0.0, 1.0];
+/// let b = model * v![1.0, 1.0, 0.0, 1.0];
+/// let tangent = (b - a).xyz().unwrap();
+///
+/// // The transformed normal is still perpendicular to the surface.
+/// assert_eq!((N * normal) * tangent, 0.0);
+/// ```
+pub fn normal_matrix<T: Float>(model: &Matrix<T, 4, 4>) -> Matrix<T, 3, 3> {
+    let matrix = model.upper_left_3x3();
+    let cofactor = matrix.adjoint().transpose();
+    let determinant = matrix.determinant();
+    if determinant == T::ZERO {
+        cofactor
+    } else {
+        cofactor * (T::ONE / determinant)
+    }
+}
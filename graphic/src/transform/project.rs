@@ -1,6 +1,6 @@
-use std::f32::consts::PI;
+use lina::{angle::Rad, m, matrix::Matrix};
 
-use lina::{m, matrix::Matrix};
+use crate::float::Float;
 
 /// Generate an orthographic projection matrix for the given AABB (axis aligned bounding box).
 /// 
@@ -42,28 +42,108 @@ use lina::{m, matrix::Matrix};
 /// Checks are provided for debug builds only, otherwise the caller must ensure the provided
 /// values are correct.
 #[rustfmt::skip]
-pub fn orthographic_proj(
-    left: f32,
-    right: f32,
-    bottom: f32,
-    top: f32,
-    z_near: f32,
-    z_far: f32,
-) -> Matrix<f32, 4, 4> {
+pub fn orthographic_proj<T: Float>(
+    left: T,
+    right: T,
+    bottom: T,
+    top: T,
+    z_near: T,
+    z_far: T,
+) -> Matrix<T, 4, 4> {
+    let (zero, one, two) = (T::ZERO, T::ONE, T::TWO);
     debug_assert!(left < right);
     debug_assert!(bottom < top);
     debug_assert!(z_far < z_near);
-    debug_assert!(z_near < 0.0);
-    debug_assert!(z_far != f32::INFINITY);
+    debug_assert!(z_near < zero);
+    debug_assert!(z_far != T::INFINITY);
 
     m![
-        [2.0 / (right - left), 0.0,                  0.0,                    -(right + left)  / (right - left)],
-        [0.0,                  2.0 / (top - bottom), 0.0,                    -(top + bottom) / (top - bottom)],
-        [0.0,                  0.0,                  1.0 / (z_far - z_near), -z_near / (z_far - z_near)],
-        [0.0,                  0.0,                  0.0,                    1.0]
+        [two / (right - left), zero,                 zero,                   -(right + left)  / (right - left)],
+        [zero,                 two / (top - bottom), zero,                   -(top + bottom) / (top - bottom)],
+        [zero,                 zero,                 one / (z_far - z_near), -z_near / (z_far - z_near)],
+        [zero,                 zero,                 zero,                   one]
     ]
 }
 
+/// Generate an orthographic projection matrix for a view volume centered on the
+/// -Z axis.
+///
+/// Affine.
+///
+/// A shorthand for [orthographic_proj] where the view volume is `width` wide and
+/// `height` tall:
+/// ```text
+/// left = -width / 2
+/// right = width / 2
+/// bottom = -height / 2
+/// top = height / 2
+/// ```
+///
+/// # Preconditions
+///
+/// ```text
+/// 0.0 < width
+/// 0.0 < height
+/// ```
+/// and all requirements of [orthographic_proj] for `z_near` and `z_far`.
+///
+/// ```
+/// # use graphic::transform::{orthographic_centered, orthographic_proj};
+/// assert_eq!(
+///     orthographic_centered(8.0, 6.0, -1.0, -100.0),
+///     orthographic_proj(-4.0, 4.0, -3.0, 3.0, -1.0, -100.0)
+/// );
+/// ```
+pub fn orthographic_centered<T: Float>(
+    width: T,
+    height: T,
+    z_near: T,
+    z_far: T,
+) -> Matrix<T, 4, 4> {
+    debug_assert!(T::ZERO < width);
+    debug_assert!(T::ZERO < height);
+
+    let right = width / T::TWO;
+    let top = height / T::TWO;
+
+    orthographic_proj(-right, right, -top, top, z_near, z_far)
+}
+
+/// Generate an orthographic projection matrix for a view volume centered on the
+/// -Z axis, `height` tall with its width given by the `aspect_ratio`.
+///
+/// Affine.
+///
+/// Keeping the `height` fixed and deriving the width from the aspect ratio of the
+/// window avoids stretching the image on resize. The `height` is then what controls
+/// the zoom level.
+///
+/// # Preconditions
+///
+/// ```text
+/// 0.0 < height
+/// 0.0 < aspect_ratio
+/// ```
+/// and all requirements of [orthographic_proj] for `z_near` and `z_far`.
+///
+/// ```
+/// # use graphic::transform::{orthographic_aspect, orthographic_centered};
+/// assert_eq!(
+///     orthographic_aspect(6.0, 16.0 / 9.0, -1.0, -100.0),
+///     orthographic_centered(6.0 * 16.0 / 9.0, 6.0, -1.0, -100.0)
+/// );
+/// ```
+pub fn orthographic_aspect<T: Float>(
+    height: T,
+    aspect_ratio: T,
+    z_near: T,
+    z_far: T,
+) -> Matrix<T, 4, 4> {
+    debug_assert!(T::ZERO < aspect_ratio);
+
+    orthographic_centered(height * aspect_ratio, height, z_near, z_far)
+}
+
 /// Generate a perspective projection matrix for potentially asymmetric frustrum.
 /// 
 /// [perspective_proj_g], perspective projection generic implements the most
@@ -102,21 +182,22 @@ pub fn orthographic_proj(
 /// Checks are provided for debug builds only, otherwise the caller must ensure the provided
 /// values are correct.
 #[rustfmt::skip]
-pub fn perspective_proj_g(
-    left: f32,
-    right: f32,
-    bottom: f32,
-    top: f32,
-    z_near: f32,
-    z_far: f32,
-) -> Matrix<f32, 4, 4> {
-    debug_assert!(left < 0.0);
-    debug_assert!(0.0 < right);
-    debug_assert!(bottom < 0.0);
-    debug_assert!(0.0 < top);
-    debug_assert!(z_near < 0.0);
+pub fn perspective_proj_g<T: Float>(
+    left: T,
+    right: T,
+    bottom: T,
+    top: T,
+    z_near: T,
+    z_far: T,
+) -> Matrix<T, 4, 4> {
+    let (zero, one, two) = (T::ZERO, T::ONE, T::TWO);
+    debug_assert!(left < zero);
+    debug_assert!(zero < right);
+    debug_assert!(bottom < zero);
+    debug_assert!(zero < top);
+    debug_assert!(z_near < zero);
     debug_assert!(z_far < z_near);
-    debug_assert!(z_far > f32::NEG_INFINITY);
+    debug_assert!(z_far > T::NEG_INFINITY);
 
     // The values are inverted, because the matrix expects them to be positive.
     // They could be expected to positive on the interface as well, but that would
@@ -126,10 +207,10 @@ pub fn perspective_proj_g(
     let z_far = -z_far;
 
      m![
-        [(2.0 * z_near) / (right - left), 0.0, (right + left) / (right - left), 0.0],
-        [0.0, (2.0 * z_near) / (top - bottom), (top + bottom) / (top - bottom), 0.0],
-        [0.0, 0.0, -z_far/(z_far - z_near), -(z_far * z_near) / (z_far - z_near)],
-        [0.0, 0.0, -1.0, 0.0] 
+        [(two * z_near) / (right - left), zero, (right + left) / (right - left), zero],
+        [zero, (two * z_near) / (top - bottom), (top + bottom) / (top - bottom), zero],
+        [zero, zero, -z_far/(z_far - z_near), -(z_far * z_near) / (z_far - z_near)],
+        [zero, zero, -one, zero]
     ]
 }
 
@@ -139,18 +220,19 @@ pub fn perspective_proj_g(
 /// All requirements from [perspective_proj_g] stand, with the exception
 /// that `z_far` is assumed to be -infinite.
 #[rustfmt::skip]
-pub fn perspective_proj_g_inf(
-    left: f32,
-    right: f32,
-    bottom: f32,
-    top: f32,
-    z_near: f32,
-) -> Matrix<f32, 4, 4> {
-    debug_assert!(left < 0.0);
-    debug_assert!(0.0 < right);
-    debug_assert!(bottom < 0.0);
-    debug_assert!(0.0 < top);
-    debug_assert!(z_near < 0.0);
+pub fn perspective_proj_g_inf<T: Float>(
+    left: T,
+    right: T,
+    bottom: T,
+    top: T,
+    z_near: T,
+) -> Matrix<T, 4, 4> {
+    let (zero, one, two) = (T::ZERO, T::ONE, T::TWO);
+    debug_assert!(left < zero);
+    debug_assert!(zero < right);
+    debug_assert!(bottom < zero);
+    debug_assert!(zero < top);
+    debug_assert!(z_near < zero);
     // The values are inverted, because the matrix expects them to be positive.
     // They could be expected to positive on the interface as well, but that would
     // only lead to unnecessary confusion as this matrix expect the camera
@@ -158,10 +240,10 @@ pub fn perspective_proj_g_inf(
     let z_near = -z_near;
 
      m![
-        [(2.0 * z_near) / (right - left), 0.0, (right + left) / (right - left), 0.0],
-        [0.0, (2.0 * z_near) / (top - bottom), (top + bottom) / (top - bottom), 0.0],
-        [0.0, 0.0, -1.0, -z_near],
-        [0.0, 0.0, -1.0, 0.0] 
+        [(two * z_near) / (right - left), zero, (right + left) / (right - left), zero],
+        [zero, (two * z_near) / (top - bottom), (top + bottom) / (top - bottom), zero],
+        [zero, zero, -one, -z_near],
+        [zero, zero, -one, zero]
     ]
 }
 
@@ -173,17 +255,18 @@ pub fn perspective_proj_g_inf(
 /// top = -bottom
 /// ```
 #[rustfmt::skip]
-pub fn perspective_proj_sym(
-    right: f32,
-    top: f32,
-    z_near: f32,
-    z_far: f32,
-) -> Matrix<f32, 4, 4> {
-    debug_assert!(0.0 < right);
-    debug_assert!(0.0 < top);
-    debug_assert!(z_near < 0.0);
+pub fn perspective_proj_sym<T: Float>(
+    right: T,
+    top: T,
+    z_near: T,
+    z_far: T,
+) -> Matrix<T, 4, 4> {
+    let (zero, one) = (T::ZERO, T::ONE);
+    debug_assert!(zero < right);
+    debug_assert!(zero < top);
+    debug_assert!(z_near < zero);
     debug_assert!(z_far < z_near);
-    debug_assert!(z_far > f32::NEG_INFINITY);
+    debug_assert!(z_far > T::NEG_INFINITY);
     // The values are inverted, because the matrix expects them to be positive.
     // They could be expected to positive on the interface as well, but that would
     // only lead to unnecessary confusion as this matrix expect the camera
@@ -192,10 +275,10 @@ pub fn perspective_proj_sym(
     let z_far = -z_far;
 
      m![
-        [z_near / right,    0.0,          0.0,                      0.0],
-        [0.0,               z_near / top, 0.0,                      0.0],
-        [0.0,               0.0,          -z_far/(z_far - z_near),  -(z_far * z_near) / (z_far - z_near)],
-        [0.0,               0.0,          -1.0,                     0.0] 
+        [z_near / right,    zero,         zero,                     zero],
+        [zero,              z_near / top, zero,                     zero],
+        [zero,              zero,         -z_far/(z_far - z_near),  -(z_far * z_near) / (z_far - z_near)],
+        [zero,              zero,         -one,                     zero]
     ]
 }
 
@@ -209,14 +292,15 @@ pub fn perspective_proj_sym(
 /// ```
 /// and `z_far` being -infinity.
 #[rustfmt::skip]
-pub fn perspective_proj_sym_inf(
-    right: f32,
-    top: f32,
-    z_near: f32,
-) -> Matrix<f32, 4, 4> {
-    debug_assert!(0.0 < right);
-    debug_assert!(0.0 < top);
-    debug_assert!(z_near < 0.0);
+pub fn perspective_proj_sym_inf<T: Float>(
+    right: T,
+    top: T,
+    z_near: T,
+) -> Matrix<T, 4, 4> {
+    let (zero, one) = (T::ZERO, T::ONE);
+    debug_assert!(zero < right);
+    debug_assert!(zero < top);
+    debug_assert!(z_near < zero);
     // The values are inverted, because the matrix expects them to be positive.
     // They could be expected to positive on the interface as well, but that would
     // only lead to unnecessary confusion as this matrix expect the camera
@@ -224,10 +308,107 @@ pub fn perspective_proj_sym_inf(
     let z_near = -z_near;
 
      m![
-        [z_near / right,    0.0,           0.0,   0.0],
-        [0.0,               z_near / top,  0.0,   0.0],
-        [0.0,               0.0,          -1.0,   -z_near],
-        [0.0,               0.0,          -1.0,    0.0] 
+        [z_near / right,    zero,          zero,  zero],
+        [zero,              z_near / top,  zero,  zero],
+        [zero,              zero,          -one,  -z_near],
+        [zero,              zero,          -one,  zero]
+    ]
+}
+
+/// Generate a reverse-Z perspective projection matrix with a symmetric frustrum.
+/// 
+/// All requirements from [perspective_proj_sym] stand, but the depth is mapped
+/// in reverse, `z_near` to 1.0 and `z_far` to 0.0.
+/// 
+/// Floating point numbers are denser around zero. A regular projection already
+/// concentrates most of the depth values close to `z_near`, so storing them in a
+/// floating point depth buffer leaves the far distances with barely any precision.
+/// Reversing the mapping cancels the two effects out, resulting in almost uniform
+/// precision over the whole range and much less z-fighting in large scenes.
+/// 
+/// To render with a reverse-Z projection:
+/// - clear the depth buffer to 0.0 instead of 1.0,
+/// - use the `Greater` depth compare function instead of `Less`
+///   (`GreaterEqual` instead of `LessEqual`),
+/// - use a 32 bit floating point depth format, with a fixed point format
+///   there is nothing to gain.
+/// 
+/// ```
+/// # use graphic::transform::perspective_proj_sym_rev;
+/// # use lina::v;
+/// # use float_eq::assert_float_eq;
+/// let P = perspective_proj_sym_rev(1.0_f32, 1.0, -1.0, -100.0);
+/// 
+/// let near = P * v![0.0, 0.0, -1.0, 1.0];
+/// let far = P * v![0.0, 0.0, -100.0, 1.0];
+/// 
+/// assert_float_eq!(near[2] / near[3], 1.0, ulps <= 1);
+/// assert_float_eq!(far[2] / far[3], 0.0, abs <= f32::EPSILON);
+/// ```
+#[rustfmt::skip]
+pub fn perspective_proj_sym_rev<T: Float>(
+    right: T,
+    top: T,
+    z_near: T,
+    z_far: T,
+) -> Matrix<T, 4, 4> {
+    let (zero, one) = (T::ZERO, T::ONE);
+    debug_assert!(zero < right);
+    debug_assert!(zero < top);
+    debug_assert!(z_near < zero);
+    debug_assert!(z_far < z_near);
+    debug_assert!(z_far > T::NEG_INFINITY);
+    // The values are inverted, because the matrix expects them to be positive.
+    let z_near = -z_near;
+    let z_far = -z_far;
+
+     m![
+        [z_near / right,    zero,         zero,                     zero],
+        [zero,              z_near / top, zero,                     zero],
+        [zero,              zero,         z_near/(z_far - z_near),  (z_far * z_near) / (z_far - z_near)],
+        [zero,              zero,         -one,                     zero]
+    ]
+}
+
+/// Generate a reverse-Z perspective projection matrix with a symmetric frustrum and
+/// **-infinite z_far** distance.
+/// 
+/// All requirements from [perspective_proj_sym_inf] stand, but the depth is mapped
+/// in reverse, `z_near` to 1.0 and infinitely far away to 0.0.
+/// See [perspective_proj_sym_rev] on how to render with it.
+/// 
+/// Unlike the regular infinite projection, this one loses next to no precision
+/// with the infinite far plane.
+/// 
+/// ```
+/// # use graphic::transform::perspective_proj_sym_inf_rev;
+/// # use lina::v;
+/// let P = perspective_proj_sym_inf_rev(1.0, 1.0, -0.5);
+/// 
+/// let near = P * v![0.0, 0.0, -0.5, 1.0];
+/// let far = P * v![0.0, 0.0, -1.0e30, 1.0];
+/// 
+/// assert_eq!(near[2] / near[3], 1.0);
+/// assert!(far[2] / far[3] < 1.0e-30);
+/// ```
+#[rustfmt::skip]
+pub fn perspective_proj_sym_inf_rev<T: Float>(
+    right: T,
+    top: T,
+    z_near: T,
+) -> Matrix<T, 4, 4> {
+    let (zero, one) = (T::ZERO, T::ONE);
+    debug_assert!(zero < right);
+    debug_assert!(zero < top);
+    debug_assert!(z_near < zero);
+    // The values are inverted, because the matrix expects them to be positive.
+    let z_near = -z_near;
+
+     m![
+        [z_near / right,    zero,          zero,  zero],
+        [zero,              z_near / top,  zero,  zero],
+        [zero,              zero,          zero,  z_near],
+        [zero,              zero,          -one,  zero]
     ]
 }
 
@@ -242,7 +423,7 @@ pub fn perspective_proj_sym_inf(
 /// # Preconditions
 /// 
 /// All arguments should be defined in **world space**.
-/// `fov_x` is expected to be in **radians**.
+/// `fov_x` is anything convertible to [Rad], e.g. a [Deg](lina::angle::Deg).
 /// 
 /// ```text
 /// 0.0 < fov_x
@@ -261,20 +442,21 @@ pub fn perspective_proj_sym_inf(
 /// Checks are provided for debug builds only, otherwise the caller must ensure the provided
 /// values are correct.
 #[rustfmt::skip]
-pub fn perspective_proj_sym_h_fov(
-    fov_x: f32,
-    aspect_ratio: f32,
-    z_near: f32,
-    z_far: f32,
-) -> Matrix<f32, 4, 4> {
-    debug_assert!(0.0 < fov_x);
-    debug_assert!(fov_x < PI);
-    debug_assert!(0.0 < aspect_ratio);
-    debug_assert!(z_near < 0.0);
+pub fn perspective_proj_sym_h_fov<T: Float>(
+    fov_x: impl Into<Rad<T>>,
+    aspect_ratio: T,
+    z_near: T,
+    z_far: T,
+) -> Matrix<T, 4, 4> {
+    let Rad(fov_x) = fov_x.into();
+    debug_assert!(T::ZERO < fov_x);
+    debug_assert!(fov_x < T::PI);
+    debug_assert!(T::ZERO < aspect_ratio);
+    debug_assert!(z_near < T::ZERO);
     debug_assert!(z_far < z_near);
-    debug_assert!(z_far > f32::NEG_INFINITY);
+    debug_assert!(z_far > T::NEG_INFINITY);
 
-    let tangent = (fov_x / 2.0).tan();
+    let tangent = (fov_x / T::TWO).tan();
     // The z_near is negated because it comes in as a negative value
     // but we do not wish to invert the `right` value.
     let right = -z_near * tangent;
@@ -288,22 +470,23 @@ pub fn perspective_proj_sym_h_fov(
 /// 
 /// All requirements from [perspective_proj_sym_h_fov] stand, with the only
 /// difference being that now the **vertical field of view** (`fov_y`) has
-/// to be provided in radians.
+/// to be provided.
 #[rustfmt::skip]
-pub fn perspective_proj_sym_v_fov(
-    fov_y: f32,
-    aspect_ratio: f32,
-    z_near: f32,
-    z_far: f32,
-) -> Matrix<f32, 4, 4> {
-    debug_assert!(0.0 < fov_y);
-    debug_assert!(fov_y < PI);
-    debug_assert!(0.0 < aspect_ratio);
-    debug_assert!(z_near < 0.0);
+pub fn perspective_proj_sym_v_fov<T: Float>(
+    fov_y: impl Into<Rad<T>>,
+    aspect_ratio: T,
+    z_near: T,
+    z_far: T,
+) -> Matrix<T, 4, 4> {
+    let Rad(fov_y) = fov_y.into();
+    debug_assert!(T::ZERO < fov_y);
+    debug_assert!(fov_y < T::PI);
+    debug_assert!(T::ZERO < aspect_ratio);
+    debug_assert!(z_near < T::ZERO);
     debug_assert!(z_far < z_near);
-    debug_assert!(z_far > f32::NEG_INFINITY);
+    debug_assert!(z_far > T::NEG_INFINITY);
 
-    let tangent = (fov_y / 2.0).tan();
+    let tangent = (fov_y / T::TWO).tan();
     // The z_near is negated because it comes in as a negative value
     // but we do not wish to invert the `right` value.
     let top = -z_near * tangent;
@@ -311,3 +494,30 @@ pub fn perspective_proj_sym_v_fov(
 
     perspective_proj_sym(right, top, z_near, z_far)
 }
+
+/// Generate a reverse-Z perspective projection matrix with a symmetric frustrum and
+/// **-infinite z_far** distance using horizontal FOV and aspect ratio.
+/// 
+/// All requirements from [perspective_proj_sym_h_fov] stand, except for `z_far`,
+/// which is -infinity.
+/// See [perspective_proj_sym_rev] on how to render with it.
+#[rustfmt::skip]
+pub fn perspective_proj_sym_h_fov_inf_rev<T: Float>(
+    fov_x: impl Into<Rad<T>>,
+    aspect_ratio: T,
+    z_near: T,
+) -> Matrix<T, 4, 4> {
+    let Rad(fov_x) = fov_x.into();
+    debug_assert!(T::ZERO < fov_x);
+    debug_assert!(fov_x < T::PI);
+    debug_assert!(T::ZERO < aspect_ratio);
+    debug_assert!(z_near < T::ZERO);
+
+    let tangent = (fov_x / T::TWO).tan();
+    // The z_near is negated because it comes in as a negative value
+    // but we do not wish to invert the `right` value.
+    let right = -z_near * tangent;
+    let top = right / aspect_ratio;
+
+    perspective_proj_sym_inf_rev(right, top, z_near)
+}
@@ -0,0 +1,64 @@
+use lina::{m, matrix::Matrix, vector::Vector};
+
+use crate::float::Float;
+
+/// Generate a reflection matrix, mirroring points across a plane.
+///
+/// The plane is defined by its `plane_normal` and any `plane_point` on it.
+/// The normal doesn't have to be of unit length.
+/// For the unit normal `n` and `d = plane_point * n`:
+/// ```text
+/// p' = p - 2((p * n) - d)n
+/// ```
+///
+/// Affine, orthogonal.
+/// Inverts handedness, so the vertex order of triangles gets flipped, which
+/// has to be accounted for when culling back faces.
+///
+/// ```
+/// # use graphic::transform::reflect;
+/// # use lina::v;
+/// // Mirror across the y = 2 plane.
+/// let R = reflect(v![0.0, 1.0, 0.0], v![0.0, 2.0, 0.0]);
+///
+/// assert_eq!(R * v![1.0, 3.0, -1.0, 1.0], v![1.0, 1.0, -1.0, 1.0]);
+/// ```
+#[rustfmt::skip]
+pub fn reflect<T: Float>(plane_normal: Vector<T, 3>, plane_point: Vector<T, 3>) -> Matrix<T, 4, 4> {
+    let (zero, one, two) = (T::ZERO, T::ONE, T::TWO);
+    let n = plane_normal.normalized();
+    let d = two * (plane_point * n);
+
+    m![
+        [one - two * n[0] * n[0], -two * n[0] * n[1],      -two * n[0] * n[2],      d * n[0]],
+        [-two * n[1] * n[0],      one - two * n[1] * n[1], -two * n[1] * n[2],      d * n[1]],
+        [-two * n[2] * n[0],      -two * n[2] * n[1],      one - two * n[2] * n[2], d * n[2]],
+        [zero,                    zero,                    zero,                    one]
+    ]
+}
+
+/// Generate the inverse of the [reflect] matrix.
+///
+/// Reflecting twice across the same plane restores the original position,
+/// so the reflection is its own inverse.
+///
+/// Affine, orthogonal.
+///
+/// ```
+/// # use graphic::transform::{reflect, inv_reflect};
+/// # use graphic::identity_matrix;
+/// # use lina::v;
+/// let normal = v![1.0, 2.0, -0.5];
+/// let point = v![3.0, -1.0, 2.0];
+///
+/// let R = reflect(normal, point);
+/// let R_inv = inv_reflect(normal, point);
+///
+/// assert!((R * R_inv).approx_eq(&identity_matrix(), 4.0 * f32::EPSILON));
+/// ```
+pub fn inv_reflect<T: Float>(
+    plane_normal: Vector<T, 3>,
+    plane_point: Vector<T, 3>,
+) -> Matrix<T, 4, 4> {
+    reflect(plane_normal, plane_point)
+}
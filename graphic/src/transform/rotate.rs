@@ -1,24 +1,28 @@
-use lina::{m, matrix::Matrix};
+use lina::{angle::Rad, m, matrix::Matrix};
 
-/// Generate counter-clockwise R rotation matrix by the given radians around the X axis.
+use crate::float::Float;
+
+/// Generate counter-clockwise R rotation matrix by the given angle around the X axis.
 /// 
 /// Affine, orthogonal.
 /// 
 /// Prone to "Gimbal lock", if used with other matrix rotations.
 #[rustfmt::skip]
-pub fn rotate_x(rad_angle: f32) -> Matrix<f32, 4, 4> {
-    let cosine = rad_angle.cos();
-    let sine = rad_angle.sin();
+pub fn rotate_x<T: Float>(angle: impl Into<Rad<T>>) -> Matrix<T, 4, 4> {
+    let (zero, one) = (T::ZERO, T::ONE);
+    let Rad(angle) = angle.into();
+    let cosine = angle.cos();
+    let sine = angle.sin();
     m![
-        [1.0, 0.0,    0.0,    0.0],
-        [0.0, cosine, -sine,  0.0], 
-        [0.0, sine,   cosine, 0.0],
-        [0.0, 0.0,    0.0,    1.0]    
+        [one,  zero,   zero,   zero],
+        [zero, cosine, -sine,  zero],
+        [zero, sine,   cosine, zero],
+        [zero, zero,   zero,   one]
     ]
 }
 
 /// Generate the inverse rotation of [rotate_x].
-/// Rotate clockwise by the given radians around the X axis.
+/// Rotate clockwise by the given angle around the X axis.
 ///
 /// Affine, orthogonal.
 ///
@@ -28,7 +32,8 @@ pub fn rotate_x(rad_angle: f32) -> Matrix<f32, 4, 4> {
 /// # use graphic::transform::rotate_x;
 /// # use graphic::transform::inv_rotate_x;
 /// # use graphic::identity_matrix;
-/// let radians = PI/2.0;
+/// # use lina::angle::Rad;
+/// let radians = Rad(PI/2.0);
 /// let Rx = rotate_x(radians);
 /// let Rx_inv = inv_rotate_x(radians);
 ///
@@ -36,29 +41,31 @@ pub fn rotate_x(rad_angle: f32) -> Matrix<f32, 4, 4> {
 ///
 /// assert_eq!(Rx * Rx_inv, identity);
 /// ```
-pub fn inv_rotate_x(rad_angle: f32) -> Matrix<f32, 4, 4> {
-    rotate_x(-rad_angle)
+pub fn inv_rotate_x<T: Float>(angle: impl Into<Rad<T>>) -> Matrix<T, 4, 4> {
+    rotate_x(-angle.into())
 }
 
-/// Generate counter-clockwise R rotation matrix by the given radians around the Y axis.
+/// Generate counter-clockwise R rotation matrix by the given angle around the Y axis.
 /// 
 /// Affine, orthogonal.
 /// 
 /// Prone to "Gimbal lock", if used with other matrix rotations. 
 #[rustfmt::skip]
-pub fn rotate_y(rad_angle: f32) -> Matrix<f32, 4, 4> {
-    let cosine = rad_angle.cos();
-    let sine = rad_angle.sin();
-    m![ 
-        [cosine, 0.0,  sine,    0.0],
-        [0.0,    1.0,  0.0,     0.0], 
-        [-sine,  0.0,  cosine,  0.0],
-        [0.0,    0.0,  0.0,     1.0] 
+pub fn rotate_y<T: Float>(angle: impl Into<Rad<T>>) -> Matrix<T, 4, 4> {
+    let (zero, one) = (T::ZERO, T::ONE);
+    let Rad(angle) = angle.into();
+    let cosine = angle.cos();
+    let sine = angle.sin();
+    m![
+        [cosine, zero, sine,   zero],
+        [zero,   one,  zero,   zero],
+        [-sine,  zero, cosine, zero],
+        [zero,   zero, zero,   one]
     ]
 }
 
 /// Generate the inverse rotation of [rotate_y].
-/// Rotate clockwise by the given radians around the Y axis.
+/// Rotate clockwise by the given angle around the Y axis.
 ///
 /// Affine, orthogonal.
 ///
@@ -68,7 +75,8 @@ pub fn rotate_y(rad_angle: f32) -> Matrix<f32, 4, 4> {
 /// # use graphic::transform::rotate_y;
 /// # use graphic::transform::inv_rotate_y;
 /// # use graphic::identity_matrix;
-/// let radians = PI/2.0;
+/// # use lina::angle::Rad;
+/// let radians = Rad(PI/2.0);
 /// let Rx = rotate_y(radians);
 /// let Rx_inv = inv_rotate_y(radians);
 ///
@@ -76,29 +84,31 @@ pub fn rotate_y(rad_angle: f32) -> Matrix<f32, 4, 4> {
 ///
 /// assert_eq!(Rx * Rx_inv, identity);
 /// ```
-pub fn inv_rotate_y(rad_angle: f32) -> Matrix<f32, 4, 4> {
-    rotate_y(-rad_angle)
+pub fn inv_rotate_y<T: Float>(angle: impl Into<Rad<T>>) -> Matrix<T, 4, 4> {
+    rotate_y(-angle.into())
 }
 
-/// Generate counter-clockwise R rotation matrix by the given radians around the Z axis.
+/// Generate counter-clockwise R rotation matrix by the given angle around the Z axis.
 /// 
 /// Affine, orthogonal.
 /// 
 /// Prone to "Gimbal lock", if used with other matrix rotations.
 #[rustfmt::skip]
-pub fn rotate_z(rad_angle: f32) -> Matrix<f32, 4, 4> {
-    let cosine = rad_angle.cos();
-    let sine = rad_angle.sin();
+pub fn rotate_z<T: Float>(angle: impl Into<Rad<T>>) -> Matrix<T, 4, 4> {
+    let (zero, one) = (T::ZERO, T::ONE);
+    let Rad(angle) = angle.into();
+    let cosine = angle.cos();
+    let sine = angle.sin();
     m![
-         [cosine, -sine,  0.0, 0.0],
-         [sine,   cosine, 0.0, 0.0],
-         [0.0,    0.0,    1.0, 0.0], 
-         [0.0,    0.0,    0.0, 1.0] 
+        [cosine, -sine,  zero, zero],
+        [sine,   cosine, zero, zero],
+        [zero,   zero,   one,  zero],
+        [zero,   zero,   zero, one]
     ]
 }
 
 /// Generate the inverse rotation of [rotate_z].
-/// Rotate clockwise by the given radians around the Z axis.
+/// Rotate clockwise by the given angle around the Z axis.
 ///
 /// Affine, orthogonal.
 ///
@@ -108,7 +118,8 @@ pub fn rotate_z(rad_angle: f32) -> Matrix<f32, 4, 4> {
 /// # use graphic::transform::rotate_z;
 /// # use graphic::transform::inv_rotate_z;
 /// # use graphic::identity_matrix;
-/// let radians = PI/2.0;
+/// # use lina::angle::Rad;
+/// let radians = Rad(PI/2.0);
 /// let Rx = rotate_z(radians);
 /// let Rx_inv = inv_rotate_z(radians);
 ///
@@ -116,6 +127,6 @@ pub fn rotate_z(rad_angle: f32) -> Matrix<f32, 4, 4> {
 ///
 /// assert_eq!(Rx * Rx_inv, identity);
 /// ```
-pub fn inv_rotate_z(rad_angle: f32) -> Matrix<f32, 4, 4> {
-    rotate_z(-rad_angle)
+pub fn inv_rotate_z<T: Float>(angle: impl Into<Rad<T>>) -> Matrix<T, 4, 4> {
+    rotate_z(-angle.into())
 }
@@ -1,5 +1,7 @@
 use lina::{m, matrix::Matrix, vector::Vector};
 
+use crate::float::Float;
+
 // Generate S scaling matrix from the given scaling factors.
 /// 
 /// Affine, orthogonal.
@@ -10,12 +12,13 @@ use lina::{m, matrix::Matrix, vector::Vector};
 /// reflecting a triangle, may invert it's vertex order, resulting
 /// in incorrect rendering. 
 #[rustfmt::skip]
-pub fn scale(scale_x: f32, scale_y: f32, scale_z: f32) -> Matrix<f32, 4, 4> {
+pub fn scale<T: Float>(scale_x: T, scale_y: T, scale_z: T) -> Matrix<T, 4, 4> {
+    let (zero, one) = (T::ZERO, T::ONE);
     m![
-        [scale_x, 0.0,     0.0,     0.0],
-        [0.0,     scale_y, 0.0,     0.0],
-        [0.0,     0.0,     scale_z, 0.0],
-        [0.0,     0.0,     0.0,     1.0]
+        [scale_x, zero,    zero,    zero],
+        [zero,    scale_y, zero,    zero],
+        [zero,    zero,    scale_z, zero],
+        [zero,    zero,    zero,    one]
     ]
 }
 
@@ -42,8 +45,8 @@ pub fn scale(scale_x: f32, scale_y: f32, scale_z: f32) -> Matrix<f32, 4, 4> {
 /// assert_eq!(S * S_inv, identity);
 /// ```
 #[rustfmt::skip]
-pub fn inv_scale(scale_x: f32, scale_y: f32, scale_z: f32) -> Matrix<f32, 4, 4> {
-    scale(1.0/scale_x, 1.0/scale_y, 1.0/scale_z)
+pub fn inv_scale<T: Float>(scale_x: T, scale_y: T, scale_z: T) -> Matrix<T, 4, 4> {
+    scale(T::ONE/scale_x, T::ONE/scale_y, T::ONE/scale_z)
 }
 
 /// Generate S scaling matrix from the given scaling [Vector].
@@ -52,7 +55,7 @@ pub fn inv_scale(scale_x: f32, scale_y: f32, scale_z: f32) -> Matrix<f32, 4, 4>
 /// 
 /// [Vector] based wrapper for [scale].
 #[rustfmt::skip]
-pub fn scale_v(s: Vector<f32, 3>) -> Matrix<f32, 4, 4> {
+pub fn scale_v<T: Float>(s: Vector<T, 3>) -> Matrix<T, 4, 4> {
     scale(s[0], s[1], s[2])
 }
 
@@ -82,6 +85,6 @@ pub fn scale_v(s: Vector<f32, 3>) -> Matrix<f32, 4, 4> {
 /// assert_eq!(S * S_inv, identity);
 /// ```
 #[rustfmt::skip]
-pub fn inv_scale_v(s: Vector<f32, 3>) -> Matrix<f32, 4, 4> {
+pub fn inv_scale_v<T: Float>(s: Vector<T, 3>) -> Matrix<T, 4, 4> {
     inv_scale(s[0], s[1], s[2])
 }
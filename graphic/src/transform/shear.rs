@@ -0,0 +1,107 @@
+use lina::{m, matrix::Matrix};
+
+use crate::float::Float;
+
+/// Generate a shearing matrix, shearing the `x` coordinate by the `y` coordinate.
+///
+/// ```text
+/// x' = x + factor * y
+/// ```
+///
+/// Affine.
+/// Preserves volume and handedness, the determinant is always 1.
+///
+/// ```
+/// # use graphic::transform::shear_xy;
+/// # use lina::v;
+/// let H = shear_xy(2.0);
+///
+/// assert_eq!(H * v![1.0, 1.0, 1.0, 1.0], v![3.0, 1.0, 1.0, 1.0]);
+/// ```
+#[rustfmt::skip]
+pub fn shear_xy<T: Float>(factor: T) -> Matrix<T, 4, 4> {
+    let (zero, one) = (T::ZERO, T::ONE);
+    m![
+        [one,  factor, zero, zero],
+        [zero, one,    zero, zero],
+        [zero, zero,   one,  zero],
+        [zero, zero,   zero, one]
+    ]
+}
+
+/// Generate the inverse of the [shear_xy] matrix.
+///
+/// Affine.
+///
+/// ```
+/// # use graphic::transform::{shear_xy, inv_shear_xy};
+/// # use graphic::identity_matrix;
+/// assert_eq!(shear_xy(2.5) * inv_shear_xy(2.5), identity_matrix());
+/// ```
+pub fn inv_shear_xy<T: Float>(factor: T) -> Matrix<T, 4, 4> {
+    shear_xy(-factor)
+}
+
+/// Generate a shearing matrix, shearing the `x` coordinate by the `z` coordinate.
+///
+/// ```text
+/// x' = x + factor * z
+/// ```
+///
+/// Affine.
+/// Preserves volume and handedness, the determinant is always 1.
+#[rustfmt::skip]
+pub fn shear_xz<T: Float>(factor: T) -> Matrix<T, 4, 4> {
+    let (zero, one) = (T::ZERO, T::ONE);
+    m![
+        [one,  zero, factor, zero],
+        [zero, one,  zero,   zero],
+        [zero, zero, one,    zero],
+        [zero, zero, zero,   one]
+    ]
+}
+
+/// Generate the inverse of the [shear_xz] matrix.
+///
+/// Affine.
+///
+/// ```
+/// # use graphic::transform::{shear_xz, inv_shear_xz};
+/// # use graphic::identity_matrix;
+/// assert_eq!(shear_xz(-1.5) * inv_shear_xz(-1.5), identity_matrix());
+/// ```
+pub fn inv_shear_xz<T: Float>(factor: T) -> Matrix<T, 4, 4> {
+    shear_xz(-factor)
+}
+
+/// Generate a shearing matrix, shearing the `y` coordinate by the `z` coordinate.
+///
+/// ```text
+/// y' = y + factor * z
+/// ```
+///
+/// Affine.
+/// Preserves volume and handedness, the determinant is always 1.
+#[rustfmt::skip]
+pub fn shear_yz<T: Float>(factor: T) -> Matrix<T, 4, 4> {
+    let (zero, one) = (T::ZERO, T::ONE);
+    m![
+        [one,  zero, zero,   zero],
+        [zero, one,  factor, zero],
+        [zero, zero, one,    zero],
+        [zero, zero, zero,   one]
+    ]
+}
+
+/// Generate the inverse of the [shear_yz] matrix.
+///
+/// Affine.
+///
+/// ```
+/// # use graphic::transform::{shear_yz, inv_shear_yz};
+/// # use graphic::identity_matrix;
+/// assert_eq!(shear_yz(0.5) * inv_shear_yz(0.5), identity_matrix());
+/// ```
+pub fn inv_shear_yz<T: Float>(factor: T) -> Matrix<T, 4, 4> {
+    shear_yz(-factor)
+}
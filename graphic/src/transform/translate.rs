@@ -1,17 +1,20 @@
 use lina::{m, matrix::Matrix, vector::Vector};
 
+use crate::float::Float;
+
 /// Generate a T translation matrix given 3 scalars.
 /// 
 /// Move a point.
 /// Affine, orthogonal.
 /// Preserves handedness.
 #[rustfmt::skip]
-pub fn translate(translate_x: f32, translate_y: f32, translate_z: f32) -> Matrix<f32, 4, 4> {
+pub fn translate<T: Float>(translate_x: T, translate_y: T, translate_z: T) -> Matrix<T, 4, 4> {
+    let (zero, one) = (T::ZERO, T::ONE);
     m![
-        [1.0, 0.0, 0.0, translate_x],
-        [0.0, 1.0, 0.0, translate_y],
-        [0.0, 0.0, 1.0, translate_z],
-        [0.0, 0.0, 0.0, 1.0]
+        [one,  zero, zero, translate_x],
+        [zero, one,  zero, translate_y],
+        [zero, zero, one,  translate_z],
+        [zero, zero, zero, one]
     ]
 }
 
@@ -33,7 +36,7 @@ pub fn translate(translate_x: f32, translate_y: f32, translate_z: f32) -> Matrix
 ///
 /// assert_eq!(T * T_inv, identity);
 /// ```
-pub fn inv_translate(translate_x: f32, translate_y: f32, translate_z: f32) -> Matrix<f32, 4, 4> {
+pub fn inv_translate<T: Float>(translate_x: T, translate_y: T, translate_z: T) -> Matrix<T, 4, 4> {
     translate(-translate_x, -translate_y, -translate_z)
 }
 
@@ -41,7 +44,7 @@ pub fn inv_translate(translate_x: f32, translate_y: f32, translate_z: f32) -> Ma
 /// 
 /// Vector based wrapper for [translate].
 #[rustfmt::skip]
-pub fn translate_v(t: &Vector<f32, 3>) -> Matrix<f32, 4, 4> {
+pub fn translate_v<T: Float>(t: &Vector<T, 3>) -> Matrix<T, 4, 4> {
     translate(t[0], t[1], t[2])
 }
 
@@ -63,6 +66,6 @@ pub fn translate_v(t: &Vector<f32, 3>) -> Matrix<f32, 4, 4> {
 ///
 /// assert_eq!(T * T_inv, identity);
 /// ```
-pub fn inv_translate_v(t_inv: &Vector<f32, 3>) -> Matrix<f32, 4, 4> {
+pub fn inv_translate_v<T: Float>(t_inv: &Vector<T, 3>) -> Matrix<T, 4, 4> {
     inv_translate(t_inv[0], t_inv[1], t_inv[2])
 }
@@ -0,0 +1,92 @@
+//! Mapping between the **normalized view volume** and the screen.
+//!
+//! After the projection and the perspective division the vertices end up in
+//! normalized device coordinates (NDC), using the `WebGPU` convention:
+//! ```text
+//! -1.0 <= x <= 1.0
+//! -1.0 <= y <= 1.0
+//!  0.0 <= z <= 1.0
+//! ```
+//! with +Y pointing up. The screen (framebuffer) coordinates are in pixels,
+//! with the origin in the top left corner and +Y pointing down:
+//! ```text
+//! 0.0       <= x <= width
+//! 0.0       <= y <= height
+//! min_depth <= z <= max_depth
+//! ```
+//! The depth range is the same as the one given to the viewport of the render pass,
+//! by default `[0.0, 1.0]`.
+//!
+//! The GPU performs the NDC to screen mapping on its own. These matrices are meant
+//! for everything done on the CPU side, e.g. picking objects with the cursor or
+//! placing HUD elements over objects of the scene.
+
+use lina::{m, matrix::Matrix};
+
+/// Generate the matrix mapping normalized device coordinates to screen coordinates.
+///
+/// Affine.
+///
+/// ```text
+/// x_s = (x + 1) * width / 2
+/// y_s = (1 - y) * height / 2
+/// z_s = min_depth + z * (max_depth - min_depth)
+/// ```
+///
+/// ```
+/// # use graphic::viewport::ndc_to_screen;
+/// # use lina::v;
+/// let S = ndc_to_screen(800.0, 600.0, 0.0, 1.0);
+///
+/// // The top left corner of the NDC.
+/// assert_eq!(S * v![-1.0, 1.0, 0.0, 1.0], v![0.0, 0.0, 0.0, 1.0]);
+/// // The center.
+/// assert_eq!(S * v![0.0, 0.0, 0.5, 1.0], v![400.0, 300.0, 0.5, 1.0]);
+/// ```
+#[rustfmt::skip]
+pub fn ndc_to_screen(width: f32, height: f32, min_depth: f32, max_depth: f32) -> Matrix<f32, 4, 4> {
+    m![
+        [width / 2.0, 0.0,           0.0,                   width / 2.0],
+        [0.0,         -height / 2.0, 0.0,                   height / 2.0],
+        [0.0,         0.0,           max_depth - min_depth, min_depth],
+        [0.0,         0.0,           0.0,                   1.0]
+    ]
+}
+
+/// Generate the matrix mapping screen coordinates to normalized device coordinates.
+///
+/// The inverse of [ndc_to_screen].
+///
+/// Affine.
+///
+/// # Panics
+///
+/// If `width` or `height` is zero, or `min_depth` equals `max_depth`.
+/// Checks are provided for debug builds only.
+///
+/// ```
+/// # use graphic::viewport::{ndc_to_screen, screen_to_ndc};
+/// # use graphic::identity_matrix;
+/// # use lina::v;
+/// let S_inv = screen_to_ndc(800.0, 600.0, 0.0, 1.0);
+///
+/// // The cursor in the bottom right corner.
+/// assert_eq!(S_inv * v![800.0, 600.0, 0.0, 1.0], v![1.0, -1.0, 0.0, 1.0]);
+///
+/// let S = ndc_to_screen(800.0, 600.0, 0.0, 1.0);
+/// assert_eq!(S * S_inv, identity_matrix());
+/// ```
+#[rustfmt::skip]
+pub fn screen_to_ndc(width: f32, height: f32, min_depth: f32, max_depth: f32) -> Matrix<f32, 4, 4> {
+    debug_assert!(width != 0.0);
+    debug_assert!(height != 0.0);
+    debug_assert!(min_depth != max_depth);
+
+    let depth_range = max_depth - min_depth;
+    m![
+        [2.0 / width, 0.0,           0.0,               -1.0],
+        [0.0,         -2.0 / height, 0.0,               1.0],
+        [0.0,         0.0,           1.0 / depth_range, -min_depth / depth_range],
+        [0.0,         0.0,           0.0,               1.0]
+    ]
+}
@@ -0,0 +1,233 @@
+//! Loading [Binding]s from TOML.
+//!
+//! Every action listed in the file replaces all of its default bindings,
+//! the actions which aren't listed keep them:
+//! ```toml
+//! # AZERTY
+//! move_forward = ["KeyZ", "ArrowUp"]
+//! move_left = ["KeyQ"]
+//! move_down = ["KeyA"]
+//! # Mouse buttons are `MouseLeft`, `MouseRight`, `MouseMiddle`,
+//! # `MouseBack`, `MouseForward` or `Mouse` followed by a number.
+//! navigate = ["MouseRight", "Space"]
+//!
+//! [mouse_look]
+//! invert_y = true
+//! ```
+//! The `[mouse_look]` table sets the [MouseLook](crate::MouseLook), replacing
+//! the previous one.
+//! Keys use the names of winit's [KeyCode]s, which identify them by their
+//! position on a US keyboard, regardless of the layout.
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+use serde::{
+    Deserialize, Deserializer, Serialize, Serializer,
+    de::{self, IntoDeserializer, value::StrDeserializer},
+};
+use winit::{event::MouseButton, keyboard::KeyCode};
+
+use crate::{Action, Binding, Input, MouseLook};
+
+/// Why a bindings file couldn't be loaded.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BindingsError(toml::de::Error);
+
+impl fmt::Display for BindingsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid bindings file: {}", self.0)
+    }
+}
+
+impl std::error::Error for BindingsError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.0)
+    }
+}
+
+impl FromStr for Binding {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        if let Some(button) = name.strip_prefix("Mouse") {
+            let button = match button {
+                "Left" => MouseButton::Left,
+                "Right" => MouseButton::Right,
+                "Middle" => MouseButton::Middle,
+                "Back" => MouseButton::Back,
+                "Forward" => MouseButton::Forward,
+                other => MouseButton::Other(
+                    other
+                        .parse()
+                        .map_err(|_| format!("unknown mouse button `{name}`"))?,
+                ),
+            };
+            return Ok(Binding::Mouse(button));
+        }
+
+        let deserializer: StrDeserializer<'_, de::value::Error> = name.into_deserializer();
+        KeyCode::deserialize(deserializer)
+            .map(Binding::Key)
+            .map_err(|_| format!("unknown key `{name}`"))
+    }
+}
+
+impl Serialize for Binding {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let button = match self {
+            // Written as the name of the variant, the same it is parsed from.
+            Binding::Key(key_code) => return key_code.serialize(serializer),
+            Binding::Mouse(MouseButton::Left) => "Left",
+            Binding::Mouse(MouseButton::Right) => "Right",
+            Binding::Mouse(MouseButton::Middle) => "Middle",
+            Binding::Mouse(MouseButton::Back) => "Back",
+            Binding::Mouse(MouseButton::Forward) => "Forward",
+            Binding::Mouse(MouseButton::Other(number)) => {
+                return serializer.collect_str(&format_args!("Mouse{number}"));
+            }
+        };
+        serializer.collect_str(&format_args!("Mouse{button}"))
+    }
+}
+
+impl<'de> Deserialize<'de> for Binding {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        name.parse().map_err(de::Error::custom)
+    }
+}
+
+/// The contents of a bindings file.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct BindingsFile {
+    pub mouse_look: Option<MouseLook>,
+    /// The bindings replacing the defaults of the listed actions.
+    #[serde(flatten)]
+    pub actions: BTreeMap<Action, Vec<Binding>>,
+}
+
+impl BindingsFile {
+    /// Parse the TOML `source` of a bindings file.
+    pub fn parse(source: &str) -> Result<Self, BindingsError> {
+        toml::from_str(source).map_err(BindingsError)
+    }
+}
+
+impl Input {
+    /// Replace the bindings of the actions listed in the TOML `source`.
+    ///
+    /// Nothing is changed if the file is invalid.
+    pub fn load_bindings(&mut self, source: &str) -> Result<(), BindingsError> {
+        let file = BindingsFile::parse(source)?;
+        if let Some(mouse_look) = file.mouse_look {
+            self.mouse_look = mouse_look;
+        }
+        for (action, bindings) in file.actions {
+            self.rebind(action, &bindings);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use winit::{event::MouseButton, keyboard::KeyCode};
+
+    use crate::{Action, Binding, Input, MouseLook};
+
+    #[test]
+    fn listed_actions_replace_their_defaults() {
+        let mut input = Input::new();
+        input
+            .load_bindings(
+                r#"
+                move_forward = ["KeyZ", "ArrowUp"]
+                navigate = ["Mouse4"]
+                "#,
+            )
+            .unwrap();
+
+        assert_eq!(
+            input.bindings(Action::MoveForward).collect::<Vec<_>>(),
+            [Binding::Key(KeyCode::KeyZ), Binding::Key(KeyCode::ArrowUp)]
+        );
+        assert_eq!(
+            input.bindings(Action::Navigate).collect::<Vec<_>>(),
+            [Binding::Mouse(MouseButton::Other(4))]
+        );
+        // Not listed, so still bound to the default.
+        assert_eq!(
+            input.bindings(Action::MoveBackward).collect::<Vec<_>>(),
+            [Binding::Key(KeyCode::KeyS)]
+        );
+    }
+
+    #[test]
+    fn invalid_files_change_nothing() {
+        let mut input = Input::new();
+        let error = input
+            .load_bindings(
+                r#"move_forward = ["KeyZ"]
+                sprint = ["Shift"]"#,
+            )
+            .unwrap_err();
+        assert!(error.to_string().contains("unknown key `Shift`"));
+        assert!(input.load_bindings("fly = [\"KeyF\"]").is_err());
+
+        assert_eq!(
+            input.bindings(Action::MoveForward).collect::<Vec<_>>(),
+            [Binding::Key(KeyCode::KeyW)]
+        );
+    }
+
+    #[test]
+    fn mouse_look_table() {
+        let mut input = Input::new();
+        input
+            .load_bindings(
+                r#"
+                jump = ["KeyJ"]
+
+                [mouse_look]
+                sensitivity = 0.01
+                invert_y = true
+                "#,
+            )
+            .unwrap();
+
+        assert_eq!(
+            input.mouse_look(),
+            MouseLook {
+                sensitivity: 0.01,
+                invert_x: false,
+                invert_y: true,
+            }
+        );
+        assert_eq!(
+            input.bindings(Action::Jump).collect::<Vec<_>>(),
+            [Binding::Key(KeyCode::KeyJ)]
+        );
+        assert!(input.load_bindings("[mouse_look]\ninvert = true").is_err());
+    }
+
+    #[test]
+    fn bindings_are_written_as_parsed() {
+        for binding in [
+            Binding::Key(KeyCode::KeyW),
+            Binding::Key(KeyCode::ArrowUp),
+            Binding::Mouse(MouseButton::Right),
+            Binding::Mouse(MouseButton::Other(4)),
+        ] {
+            let value = toml::Value::try_from(binding).unwrap();
+            assert_eq!(value.as_str().unwrap().parse(), Ok(binding));
+        }
+    }
+
+    #[test]
+    fn parses_mouse_buttons() {
+        assert_eq!(
+            "MouseMiddle".parse(),
+            Ok(Binding::Mouse(MouseButton::Middle))
+        );
+        assert!("MouseWheel".parse::<Binding>().is_err());
+    }
+}
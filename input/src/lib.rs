@@ -0,0 +1,373 @@
+//! Map raw keyboard and mouse input to engine actions.
+//!
+//! Instead of checking individual keys, applications query [Input] for
+//! [Action]s and [Axis] values. Every action can be bound to any number
+//! of keys and mouse buttons, which can be changed at runtime or loaded
+//! from a [bindings file](Input::load_bindings). How the mouse turns the
+//! camera is set by [MouseLook].
+//!
+//! The [InputEvent]s of a session can be [recorded](Recording) and
+//! [played back](Playback) later, e.g. to reproduce a bug.
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+use winit::{
+    event::{ElementState, MouseButton, WindowEvent},
+    keyboard::KeyCode,
+};
+
+pub use bindings::{BindingsError, BindingsFile};
+pub use mouse_look::MouseLook;
+pub use recording::{InputEvent, Playback, Recording, RecordingError, TimedEvent};
+
+mod bindings;
+mod mouse_look;
+mod recording;
+
+/// Something the user wants to do, independent of how it is triggered.
+///
+/// Named in snake case in bindings files, e.g. `move_forward`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    MoveForward,
+    MoveBackward,
+    MoveLeft,
+    MoveRight,
+    MoveUp,
+    MoveDown,
+    /// Move faster while active.
+    Sprint,
+    /// Mouse movement and scrolling controls the camera while active.
+    Navigate,
+    ToggleOverlay,
+    ToggleWireframe,
+    /// Remove the block in the middle of the view.
+    BreakBlock,
+    /// Put a block against the one in the middle of the view.
+    PlaceBlock,
+    /// Jump while walking.
+    Jump,
+    /// Switch between flying around freely and walking.
+    ToggleWalk,
+    /// Throw a rigid body from the camera.
+    ThrowBody,
+    /// Save the next frame as an image.
+    Screenshot,
+    /// Start or stop saving every frame as an image, e.g. to make a video.
+    ToggleFrameDump,
+}
+
+/// A pair of opposing [Action]s.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Axis {
+    /// [Action::MoveForward] is positive, [Action::MoveBackward] is negative.
+    Forward,
+    /// [Action::MoveRight] is positive, [Action::MoveLeft] is negative.
+    Right,
+    /// [Action::MoveUp] is positive, [Action::MoveDown] is negative.
+    Up,
+}
+
+impl Axis {
+    /// The actions driving the axis in the positive and negative direction.
+    pub fn actions(&self) -> (Action, Action) {
+        match self {
+            Axis::Forward => (Action::MoveForward, Action::MoveBackward),
+            Axis::Right => (Action::MoveRight, Action::MoveLeft),
+            Axis::Up => (Action::MoveUp, Action::MoveDown),
+        }
+    }
+}
+
+/// A physical input which can trigger an [Action].
+///
+/// Keys are identified by their physical location, so the default
+/// bindings work the same way regardless of the keyboard layout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Binding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+}
+
+/// Tracks the state of every [Binding] and resolves them to [Action]s.
+#[derive(Debug, Clone)]
+pub struct Input {
+    bindings: BTreeMap<Binding, Action>,
+    pressed: BTreeSet<Binding>,
+    // Actions which became active since the last `end_frame`.
+    activated: BTreeSet<Action>,
+    mouse_look: MouseLook,
+}
+
+impl Default for Input {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Input {
+    /// Create an input map with the default bindings.
+    ///
+    /// WASD moves horizontally, E and Q vertically, shift sprints
+    /// and holding the right mouse button enables navigation.
+    /// F1 toggles the overlay, F3 the wireframe mode.
+    /// The left mouse button breaks blocks, the middle one places them.
+    /// F switches to walking, where space jumps. T throws bodies.
+    /// F12 takes a screenshot, F11 starts and stops dumping every frame.
+    pub fn new() -> Self {
+        let mut input = Self::unbound();
+        for (key, action) in [
+            (KeyCode::KeyW, Action::MoveForward),
+            (KeyCode::KeyS, Action::MoveBackward),
+            (KeyCode::KeyA, Action::MoveLeft),
+            (KeyCode::KeyD, Action::MoveRight),
+            (KeyCode::KeyE, Action::MoveUp),
+            (KeyCode::KeyQ, Action::MoveDown),
+            (KeyCode::ShiftLeft, Action::Sprint),
+            (KeyCode::ShiftRight, Action::Sprint),
+            (KeyCode::F1, Action::ToggleOverlay),
+            (KeyCode::F3, Action::ToggleWireframe),
+            (KeyCode::KeyF, Action::ToggleWalk),
+            (KeyCode::Space, Action::Jump),
+            (KeyCode::KeyT, Action::ThrowBody),
+            (KeyCode::F12, Action::Screenshot),
+            (KeyCode::F11, Action::ToggleFrameDump),
+        ] {
+            input.bind(Binding::Key(key), action);
+        }
+        input.bind(Binding::Mouse(MouseButton::Right), Action::Navigate);
+        input.bind(Binding::Mouse(MouseButton::Left), Action::BreakBlock);
+        input.bind(Binding::Mouse(MouseButton::Middle), Action::PlaceBlock);
+
+        input
+    }
+
+    /// Create an input map without any bindings.
+    pub fn unbound() -> Self {
+        Self {
+            bindings: BTreeMap::new(),
+            pressed: BTreeSet::new(),
+            activated: BTreeSet::new(),
+            mouse_look: MouseLook::default(),
+        }
+    }
+
+    /// Make `binding` trigger `action`, replacing its previous action.
+    ///
+    /// An action may have any number of bindings.
+    pub fn bind(&mut self, binding: Binding, action: Action) {
+        self.bindings.insert(binding, action);
+    }
+
+    /// Remove `binding`, returning the action it was bound to.
+    pub fn unbind(&mut self, binding: Binding) -> Option<Action> {
+        self.bindings.remove(&binding)
+    }
+
+    /// Remove every binding of `action`.
+    pub fn unbind_action(&mut self, action: Action) {
+        self.bindings.retain(|_, bound| *bound != action);
+    }
+
+    /// Make `bindings` the only ones triggering `action`.
+    pub fn rebind(&mut self, action: Action, bindings: &[Binding]) {
+        self.unbind_action(action);
+        for binding in bindings {
+            self.bind(*binding, action);
+        }
+    }
+
+    /// All the bindings triggering `action`.
+    pub fn bindings(&self, action: Action) -> impl Iterator<Item = Binding> + '_ {
+        self.bindings
+            .iter()
+            .filter(move |(_, bound)| **bound == action)
+            .map(|(binding, _)| *binding)
+    }
+
+    /// How mouse movement turns the camera.
+    pub fn mouse_look(&self) -> MouseLook {
+        self.mouse_look
+    }
+
+    pub fn set_mouse_look(&mut self, mouse_look: MouseLook) {
+        self.mouse_look = mouse_look;
+    }
+
+    /// Update the state from a window event.
+    ///
+    /// Events other than keyboard and mouse button input are ignored.
+    pub fn handle_window_event(&mut self, event: &WindowEvent) {
+        if let Some(event) = InputEvent::from_window_event(event) {
+            self.handle_event(&event);
+        }
+    }
+
+    /// Update the state from a live or [played back](Playback) event.
+    ///
+    /// Events other than keyboard and mouse button input are ignored.
+    pub fn handle_event(&mut self, event: &InputEvent) {
+        match *event {
+            InputEvent::Key { key, state } => self.set_state(Binding::Key(key), state),
+            InputEvent::MouseButton { button, state } => {
+                self.set_state(Binding::Mouse(button), state);
+            }
+            _ => (),
+        }
+    }
+
+    /// Update the state of a single binding.
+    pub fn set_state(&mut self, binding: Binding, state: ElementState) {
+        match state {
+            ElementState::Pressed => {
+                // Key repeats don't activate the action again.
+                if self.pressed.insert(binding)
+                    && let Some(action) = self.bindings.get(&binding)
+                {
+                    self.activated.insert(*action);
+                }
+            }
+            ElementState::Released => {
+                self.pressed.remove(&binding);
+            }
+        }
+    }
+
+    /// Release everything.
+    ///
+    /// Should be called when the window loses focus, as release events
+    /// are not received in that case, leaving actions stuck as active.
+    pub fn clear(&mut self) {
+        self.pressed.clear();
+        self.activated.clear();
+    }
+
+    /// Mark the end of a frame, resetting [is_activated](Input::is_activated).
+    pub fn end_frame(&mut self) {
+        self.activated.clear();
+    }
+
+    /// `true` if any binding of `action` is held down.
+    pub fn is_active(&self, action: Action) -> bool {
+        self.pressed
+            .iter()
+            .any(|binding| self.bindings.get(binding) == Some(&action))
+    }
+
+    /// `true` if `action` was triggered since the last [end_frame](Input::end_frame).
+    ///
+    /// Meant for actions which should happen once per press, like toggles.
+    pub fn is_activated(&self, action: Action) -> bool {
+        self.activated.contains(&action)
+    }
+
+    /// The value of `axis` in the range `[-1, 1]`.
+    ///
+    /// Opposing actions being active at the same time cancel each other out.
+    pub fn axis(&self, axis: Axis) -> f32 {
+        let (positive, negative) = axis.actions();
+        let value = |action| if self.is_active(action) { 1.0 } else { 0.0 };
+        value(positive) - value(negative)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_bindings() {
+        let mut input = Input::new();
+        assert!(!input.is_active(Action::MoveForward));
+
+        input.set_state(Binding::Key(KeyCode::KeyW), ElementState::Pressed);
+        assert!(input.is_active(Action::MoveForward));
+        assert_eq!(input.axis(Axis::Forward), 1.0);
+
+        input.set_state(Binding::Key(KeyCode::KeyW), ElementState::Released);
+        assert!(!input.is_active(Action::MoveForward));
+        assert_eq!(input.axis(Axis::Forward), 0.0);
+    }
+
+    #[test]
+    fn any_binding_activates() {
+        let mut input = Input::new();
+        input.set_state(Binding::Key(KeyCode::ShiftLeft), ElementState::Pressed);
+        input.set_state(Binding::Key(KeyCode::ShiftRight), ElementState::Pressed);
+        input.set_state(Binding::Key(KeyCode::ShiftLeft), ElementState::Released);
+
+        assert!(input.is_active(Action::Sprint));
+    }
+
+    #[test]
+    fn opposing_actions_cancel() {
+        let mut input = Input::new();
+        input.set_state(Binding::Key(KeyCode::KeyA), ElementState::Pressed);
+        assert_eq!(input.axis(Axis::Right), -1.0);
+
+        input.set_state(Binding::Key(KeyCode::KeyD), ElementState::Pressed);
+        assert_eq!(input.axis(Axis::Right), 0.0);
+    }
+
+    #[test]
+    fn rebinding() {
+        let mut input = Input::new();
+        input.unbind_action(Action::MoveForward);
+        input.bind(Binding::Key(KeyCode::ArrowUp), Action::MoveForward);
+        assert_eq!(
+            input.bindings(Action::MoveForward).collect::<Vec<_>>(),
+            [Binding::Key(KeyCode::ArrowUp)]
+        );
+
+        input.set_state(Binding::Key(KeyCode::KeyW), ElementState::Pressed);
+        assert!(!input.is_active(Action::MoveForward));
+        input.set_state(Binding::Key(KeyCode::ArrowUp), ElementState::Pressed);
+        assert!(input.is_active(Action::MoveForward));
+
+        // Rebinding a pressed key takes effect immediately.
+        assert_eq!(
+            input.unbind(Binding::Key(KeyCode::ArrowUp)),
+            Some(Action::MoveForward)
+        );
+        assert!(!input.is_active(Action::MoveForward));
+    }
+
+    #[test]
+    fn activated_once_per_press() {
+        let mut input = Input::new();
+        input.set_state(Binding::Key(KeyCode::F3), ElementState::Pressed);
+        assert!(input.is_activated(Action::ToggleWireframe));
+        input.end_frame();
+
+        // A key repeat
+        input.set_state(Binding::Key(KeyCode::F3), ElementState::Pressed);
+        assert!(!input.is_activated(Action::ToggleWireframe));
+
+        input.set_state(Binding::Key(KeyCode::F3), ElementState::Released);
+        input.set_state(Binding::Key(KeyCode::F3), ElementState::Pressed);
+        assert!(input.is_activated(Action::ToggleWireframe));
+    }
+
+    #[test]
+    fn played_back_events() {
+        let mut input = Input::new();
+        input.handle_event(&InputEvent::Key {
+            key: KeyCode::KeyD,
+            state: ElementState::Pressed,
+        });
+        input.handle_event(&InputEvent::Focused(false));
+
+        assert_eq!(input.axis(Axis::Right), 1.0);
+    }
+
+    #[test]
+    fn mouse_bindings() {
+        let mut input = Input::new();
+        input.set_state(Binding::Mouse(MouseButton::Right), ElementState::Pressed);
+        assert!(input.is_active(Action::Navigate));
+
+        input.clear();
+        assert!(!input.is_active(Action::Navigate));
+    }
+}
@@ -0,0 +1,73 @@
+use serde::{Deserialize, Serialize};
+
+/// How far and which way the camera turns when the mouse moves.
+///
+/// Loaded from the `[mouse_look]` table of a [bindings file](crate::Input::load_bindings),
+/// the settings which aren't listed keep their defaults:
+/// ```toml
+/// [mouse_look]
+/// sensitivity = 0.01
+/// invert_y = true
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MouseLook {
+    /// Radians turned per device unit of mouse movement.
+    pub sensitivity: f32,
+    /// Moving the mouse to the right turns to the left.
+    pub invert_x: bool,
+    /// Moving the mouse forward looks down.
+    pub invert_y: bool,
+}
+
+impl Default for MouseLook {
+    fn default() -> Self {
+        Self {
+            sensitivity: 0.02,
+            invert_x: false,
+            invert_y: false,
+        }
+    }
+}
+
+impl MouseLook {
+    /// The yaw and pitch in radians for the mouse moving by `delta`, the
+    /// device units of a `DeviceEvent::MouseMotion`.
+    ///
+    /// Positive angles turn counter-clockwise, to the left and up.
+    ///
+    /// ```
+    /// # use input::MouseLook;
+    /// let mouse_look = MouseLook { invert_y: true, ..MouseLook::default() };
+    ///
+    /// // Right and forward, screen coordinates grow downwards.
+    /// let (yaw, pitch) = mouse_look.turn((10.0, -5.0));
+    /// assert!(yaw < 0.0);
+    /// assert!(pitch < 0.0);
+    /// ```
+    pub fn turn(&self, delta: (f64, f64)) -> (f32, f32) {
+        let sign = |invert| if invert { 1.0 } else { -1.0 };
+        (
+            sign(self.invert_x) * self.sensitivity * delta.0 as f32,
+            sign(self.invert_y) * self.sensitivity * delta.1 as f32,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inverting_flips_one_axis() {
+        let normal = MouseLook::default();
+        let inverted = MouseLook {
+            invert_x: true,
+            ..normal
+        };
+
+        let (yaw, pitch) = normal.turn((50.0, -25.0));
+        assert_eq!((yaw, pitch), (-1.0, 0.5));
+        assert_eq!(inverted.turn((50.0, -25.0)), (1.0, 0.5));
+    }
+}
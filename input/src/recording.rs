@@ -0,0 +1,324 @@
+//! Recording the input of a session and playing it back, e.g. to reproduce
+//! a bug or to drive a smoke test.
+//!
+//! Recordings are saved as RON, one event per line with the seconds since
+//! the recording started:
+//! ```ron
+//! (
+//!     events: [
+//!         (time: 0.0, event: Focused(true)),
+//!         (time: 1.25, event: MouseButton(button: Right, state: Pressed)),
+//!         (time: 1.5, event: MouseMotion(delta: (12.0, -3.0))),
+//!     ],
+//! )
+//! ```
+use std::{fmt, io, path::Path, time::Duration};
+
+use serde::{Deserialize, Serialize};
+use winit::{
+    event::{DeviceEvent, ElementState, MouseButton, MouseScrollDelta, WindowEvent},
+    keyboard::{KeyCode, PhysicalKey},
+};
+
+/// A keyboard or mouse event, as far as an app reacts to it.
+///
+/// Unlike winit's events they can be created, saved and loaded.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum InputEvent {
+    Key {
+        key: KeyCode,
+        state: ElementState,
+    },
+    MouseButton {
+        button: MouseButton,
+        state: ElementState,
+    },
+    MouseWheel {
+        delta: MouseScrollDelta,
+    },
+    /// The cursor moved to `position` in physical pixels of the window.
+    CursorMoved {
+        position: [f64; 2],
+    },
+    CursorLeft,
+    Focused(bool),
+    /// The mouse moved by `delta` device units, even if the cursor is grabbed.
+    MouseMotion {
+        delta: (f64, f64),
+    },
+}
+
+impl InputEvent {
+    /// The input of `event`, `None` for other window events.
+    pub fn from_window_event(event: &WindowEvent) -> Option<Self> {
+        match event {
+            WindowEvent::KeyboardInput { event, .. } => match event.physical_key {
+                PhysicalKey::Code(key) => Some(InputEvent::Key {
+                    key,
+                    state: event.state,
+                }),
+                PhysicalKey::Unidentified(_) => None,
+            },
+            WindowEvent::MouseInput { state, button, .. } => Some(InputEvent::MouseButton {
+                button: *button,
+                state: *state,
+            }),
+            WindowEvent::MouseWheel { delta, .. } => Some(InputEvent::MouseWheel { delta: *delta }),
+            WindowEvent::CursorMoved { position, .. } => Some(InputEvent::CursorMoved {
+                position: [position.x, position.y],
+            }),
+            WindowEvent::CursorLeft { .. } => Some(InputEvent::CursorLeft),
+            WindowEvent::Focused(focused) => Some(InputEvent::Focused(*focused)),
+            _ => None,
+        }
+    }
+
+    /// The input of `event`, `None` for other device events.
+    pub fn from_device_event(event: &DeviceEvent) -> Option<Self> {
+        match event {
+            DeviceEvent::MouseMotion { delta } => Some(InputEvent::MouseMotion { delta: *delta }),
+            _ => None,
+        }
+    }
+}
+
+/// An [InputEvent] and when it happened.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TimedEvent {
+    /// Since the recording started, saved in seconds.
+    #[serde(with = "seconds")]
+    pub time: Duration,
+    pub event: InputEvent,
+}
+
+/// The input events of a session in the order they happened.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Recording {
+    events: Vec<TimedEvent>,
+}
+
+impl Recording {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add `event`, which happened `time` after the recording started.
+    ///
+    /// Events stay in order, an event before the last one counts as
+    /// happening at the same time.
+    pub fn push(&mut self, time: Duration, event: InputEvent) {
+        let time = time.max(self.duration());
+        self.events.push(TimedEvent { time, event });
+    }
+
+    pub fn events(&self) -> &[TimedEvent] {
+        &self.events
+    }
+
+    /// The time of the last event.
+    pub fn duration(&self) -> Duration {
+        self.events
+            .last()
+            .map_or(Duration::ZERO, |event| event.time)
+    }
+
+    /// Parse a recording from RON.
+    pub fn from_ron(source: &str) -> Result<Self, RecordingError> {
+        ron::from_str(source).map_err(RecordingError::Parse)
+    }
+
+    /// The recording as RON, one event per line.
+    pub fn to_ron(&self) -> Result<String, RecordingError> {
+        let config = ron::ser::PrettyConfig::default().depth_limit(2);
+        ron::ser::to_string_pretty(self, config).map_err(RecordingError::Write)
+    }
+
+    /// Load the recording saved at `path`.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, RecordingError> {
+        Self::from_ron(&std::fs::read_to_string(path)?)
+    }
+
+    /// Save the recording to `path`, replacing the file.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), RecordingError> {
+        std::fs::write(path, self.to_ron()?)?;
+        Ok(())
+    }
+}
+
+/// Hands out the events of a [Recording] once their time has come.
+///
+/// ```
+/// # use std::time::Duration;
+/// # use input::{InputEvent, Playback, Recording};
+/// let mut recording = Recording::new();
+/// recording.push(Duration::from_secs(1), InputEvent::Focused(true));
+/// let mut playback = Playback::new(recording);
+///
+/// assert_eq!(playback.due(Duration::from_millis(500)).count(), 0);
+/// assert_eq!(playback.due(Duration::from_secs(2)).count(), 1);
+/// assert!(playback.is_finished());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Playback {
+    recording: Recording,
+    // The index of the next event to hand out.
+    next: usize,
+}
+
+impl Playback {
+    pub fn new(recording: Recording) -> Self {
+        Self { recording, next: 0 }
+    }
+
+    /// The events up to `time` after the playback started which weren't
+    /// handed out yet, in order.
+    pub fn due(&mut self, time: Duration) -> impl Iterator<Item = InputEvent> + '_ {
+        let start = self.next;
+        let pending = &self.recording.events[start..];
+        self.next += pending
+            .iter()
+            .take_while(|event| event.time <= time)
+            .count();
+        self.recording.events[start..self.next]
+            .iter()
+            .map(|event| event.event)
+    }
+
+    /// Whether every event was handed out.
+    pub fn is_finished(&self) -> bool {
+        self.next == self.recording.events.len()
+    }
+}
+
+/// Why a recording couldn't be saved or loaded.
+#[derive(Debug)]
+pub enum RecordingError {
+    Io(io::Error),
+    /// The file is not valid RON or has unknown events.
+    Parse(ron::error::SpannedError),
+    Write(ron::Error),
+}
+
+impl fmt::Display for RecordingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecordingError::Io(err) => write!(f, "{err}"),
+            RecordingError::Parse(err) => write!(f, "invalid input recording: {err}"),
+            RecordingError::Write(err) => write!(f, "failed to write the input recording: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for RecordingError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RecordingError::Io(err) => Some(err),
+            RecordingError::Parse(err) => Some(err),
+            RecordingError::Write(err) => Some(err),
+        }
+    }
+}
+
+impl From<io::Error> for RecordingError {
+    fn from(err: io::Error) -> Self {
+        RecordingError::Io(err)
+    }
+}
+
+/// (De)serializes a [Duration] as fractional seconds, which are easier to
+/// read and edit than seconds and nanoseconds.
+mod seconds {
+    use std::time::Duration;
+
+    use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
+
+    pub fn serialize<S: Serializer>(time: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        time.as_secs_f64().serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        let seconds = f64::deserialize(deserializer)?;
+        Duration::try_from_secs_f64(seconds).map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recording() -> Recording {
+        let mut recording = Recording::new();
+        for (millis, event) in [
+            (0, InputEvent::Focused(true)),
+            (
+                250,
+                InputEvent::Key {
+                    key: KeyCode::KeyW,
+                    state: ElementState::Pressed,
+                },
+            ),
+            (
+                500,
+                InputEvent::MouseMotion {
+                    delta: (12.0, -3.5),
+                },
+            ),
+            (
+                500,
+                InputEvent::MouseWheel {
+                    delta: MouseScrollDelta::LineDelta(0.0, 1.0),
+                },
+            ),
+            (
+                1500,
+                InputEvent::CursorMoved {
+                    position: [640.0, 360.5],
+                },
+            ),
+            (1750, InputEvent::CursorLeft),
+        ] {
+            recording.push(Duration::from_millis(millis), event);
+        }
+        recording
+    }
+
+    #[test]
+    fn recordings_survive_saving() {
+        let recording = recording();
+        let source = recording.to_ron().unwrap();
+
+        assert_eq!(source.lines().count(), recording.events().len() + 4);
+        assert_eq!(Recording::from_ron(&source).unwrap(), recording);
+        assert!(matches!(
+            Recording::from_ron("(events: [(time: 1.0, event: Teleport)])"),
+            Err(RecordingError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn events_are_played_back_in_time() {
+        let mut playback = Playback::new(recording());
+
+        assert_eq!(
+            playback.due(Duration::ZERO).collect::<Vec<_>>(),
+            [InputEvent::Focused(true)]
+        );
+        assert_eq!(playback.due(Duration::from_millis(100)).count(), 0);
+        assert_eq!(playback.due(Duration::from_millis(500)).count(), 3);
+        assert!(!playback.is_finished());
+        assert_eq!(playback.due(Duration::from_secs(10)).count(), 2);
+        assert!(playback.is_finished());
+    }
+
+    #[test]
+    fn events_stay_in_order() {
+        let mut recording = recording();
+        recording.push(Duration::from_secs(1), InputEvent::Focused(false));
+
+        assert_eq!(recording.duration(), Duration::from_millis(1750));
+        assert_eq!(
+            recording.events().last().unwrap().time,
+            recording.duration()
+        );
+    }
+}
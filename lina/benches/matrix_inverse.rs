@@ -0,0 +1,24 @@
+use criterion::{Criterion, criterion_group, criterion_main};
+use lina::m;
+use std::hint::black_box;
+
+fn matrix_inverse(c: &mut Criterion) {
+    // Rotated around Z and translated, so both inverses apply.
+    let (sin, cos) = 0.5f32.sin_cos();
+    let rigid = m![
+        [cos, -sin, 0.0, 1.0],
+        [sin, cos, 0.0, 2.0],
+        [0.0, 0.0, 1.0, 3.0],
+        [0.0, 0.0, 0.0, 1.0]
+    ];
+    c.bench_function("inverse 4x4 f32", |b| b.iter(|| black_box(rigid).inverse()));
+    c.bench_function("inverse_rigid 4x4 f32", |b| {
+        b.iter(|| black_box(rigid).inverse_rigid())
+    });
+
+    let m = m![[1.2f32, -2.1, 5.6], [0.0, 1.0, -2.4], [-1.2, 0.8, 3.0]];
+    c.bench_function("inverse 3x3 f32", |b| b.iter(|| black_box(m).inverse()));
+}
+
+criterion_group!(benches, matrix_inverse);
+criterion_main!(benches);
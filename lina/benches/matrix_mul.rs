@@ -0,0 +1,71 @@
+use criterion::{Criterion, criterion_group, criterion_main};
+use lina::matrix::Matrix;
+use lina::{m, v};
+use std::hint::black_box;
+
+/// The 4x4 f32 product written out by hand, to compare the generic kernel
+/// of `Mul` against.
+fn mul_unrolled(lhs: Matrix<f32, 4, 4>, rhs: Matrix<f32, 4, 4>) -> Matrix<f32, 4, 4> {
+    let [a, b, c, d] = *lhs.as_slices();
+    let [r0, r1, r2, r3] = *rhs.as_slices();
+    let row = |l: [f32; 4]| {
+        [
+            l[0] * r0[0] + l[1] * r1[0] + l[2] * r2[0] + l[3] * r3[0],
+            l[0] * r0[1] + l[1] * r1[1] + l[2] * r2[1] + l[3] * r3[1],
+            l[0] * r0[2] + l[1] * r1[2] + l[2] * r2[2] + l[3] * r3[2],
+            l[0] * r0[3] + l[1] * r1[3] + l[2] * r2[3] + l[3] * r3[3],
+        ]
+    };
+    Matrix::from_matrix([row(a), row(b), row(c), row(d)])
+}
+
+fn matrix_mul(c: &mut Criterion) {
+    let lhs = m![
+        [1.0f32, 2.0, 3.0, 4.0],
+        [5.0, 6.0, 7.0, 8.0],
+        [9.0, 10.0, 11.0, 12.0],
+        [13.0, 14.0, 15.0, 16.0]
+    ];
+    let rhs = m![
+        [0.5f32, -1.0, 0.0, 2.0],
+        [1.5, 0.25, -2.0, 0.0],
+        [0.0, 3.0, 1.0, -0.5],
+        [0.0, 0.0, 0.0, 1.0]
+    ];
+    assert_eq!(mul_unrolled(lhs, rhs), lhs * rhs);
+    c.bench_function("mul 4x4 f32", |b| {
+        b.iter(|| black_box(lhs) * black_box(rhs))
+    });
+    c.bench_function("mul 4x4 f32 unrolled", |b| {
+        b.iter(|| mul_unrolled(black_box(lhs), black_box(rhs)))
+    });
+
+    let lhs = m![[1.0f64, 2.0, 3.0], [4.0, 5.0, 6.0], [7.0, 8.0, 9.0]];
+    let rhs = m![[9.0f64, 8.0, 7.0], [6.0, 5.0, 4.0], [3.0, 2.0, 1.0]];
+    c.bench_function("mul 3x3 f64", |b| {
+        b.iter(|| black_box(lhs) * black_box(rhs))
+    });
+
+    let transforms = [lhs; 8].map(|m| m * 0.5);
+    c.bench_function("chain 8 3x3 f64", |b| {
+        b.iter(|| {
+            black_box(transforms)
+                .into_iter()
+                .fold(rhs, |acc, transform| acc * transform)
+        })
+    });
+
+    let lhs = m![
+        [1.0f32, 0.0, 0.0, 4.0],
+        [0.0, 1.0, 0.0, 5.0],
+        [0.0, 0.0, 1.0, 6.0],
+        [0.0, 0.0, 0.0, 1.0]
+    ];
+    let point = v![1.0f32, 2.0, 3.0, 1.0];
+    c.bench_function("mul 4x4 f32 vector", |b| {
+        b.iter(|| black_box(lhs) * black_box(point))
+    });
+}
+
+criterion_group!(benches, matrix_mul);
+criterion_main!(benches);
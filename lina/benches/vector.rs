@@ -0,0 +1,24 @@
+use criterion::{Criterion, criterion_group, criterion_main};
+use lina::v;
+use std::hint::black_box;
+
+fn vector(c: &mut Criterion) {
+    let lhs = v![1.0f32, -2.0, 3.5];
+    let rhs = v![0.5f32, 4.0, -1.0];
+    c.bench_function("normalize 3 f32", |b| {
+        b.iter(|| black_box(lhs).normalized())
+    });
+    c.bench_function("length 3 f32", |b| b.iter(|| black_box(lhs).length()));
+    c.bench_function("dot 3 f32", |b| b.iter(|| black_box(lhs) * black_box(rhs)));
+    c.bench_function("cross 3 f32", |b| {
+        b.iter(|| black_box(lhs).cross(black_box(rhs)))
+    });
+
+    let lhs = v![1.0f32, -2.0, 3.5, 1.0];
+    c.bench_function("normalize 4 f32", |b| {
+        b.iter(|| black_box(lhs).normalized())
+    });
+}
+
+criterion_group!(benches, vector);
+criterion_main!(benches);
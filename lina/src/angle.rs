@@ -0,0 +1,116 @@
+//! Angles tagged with their unit.
+//!
+//! The functions taking an angle accept anything convertible into [Rad], so
+//! passing degrees where radians are expected is caught by the compiler
+//! instead of turning the scene upside down:
+//!
+//! ```
+//! # use lina::angle::{Deg, Rad};
+//! fn half_turn(angle: impl Into<Rad>) -> bool {
+//!     (angle.into().0 - std::f32::consts::PI).abs() < 1e-6
+//! }
+//!
+//! assert!(half_turn(Deg(180.0)));
+//! assert!(half_turn(Rad(std::f32::consts::PI)));
+//! ```
+
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+/// An angle in radians.
+///
+/// Defaults to `f32`, the type the renderer works with.
+#[derive(Debug, Default, PartialEq, PartialOrd, Clone, Copy)]
+pub struct Rad<T = f32>(pub T);
+
+/// An angle in degrees, see [Rad].
+#[derive(Debug, Default, PartialEq, PartialOrd, Clone, Copy)]
+pub struct Deg<T = f32>(pub T);
+
+macro_rules! impl_angle_ops {
+    ($($Angle: ident),* $(,)*) => {$(
+        impl<T: Neg<Output = T>> Neg for $Angle<T> {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                $Angle(-self.0)
+            }
+        }
+
+        impl<T: Add<Output = T>> Add for $Angle<T> {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self {
+                $Angle(self.0 + rhs.0)
+            }
+        }
+
+        impl<T: Sub<Output = T>> Sub for $Angle<T> {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self {
+                $Angle(self.0 - rhs.0)
+            }
+        }
+
+        impl<T: Mul<Output = T>> Mul<T> for $Angle<T> {
+            type Output = Self;
+
+            fn mul(self, rhs: T) -> Self {
+                $Angle(self.0 * rhs)
+            }
+        }
+
+        impl<T: Div<Output = T>> Div<T> for $Angle<T> {
+            type Output = Self;
+
+            fn div(self, rhs: T) -> Self {
+                $Angle(self.0 / rhs)
+            }
+        }
+    )*};
+}
+
+impl_angle_ops!(Rad, Deg);
+
+macro_rules! impl_conversions_for_float_types {
+    ($($T: ty),* $(,)*) => {$(
+        impl From<Deg<$T>> for Rad<$T> {
+            fn from(angle: Deg<$T>) -> Self {
+                Rad(angle.0.to_radians())
+            }
+        }
+
+        impl From<Rad<$T>> for Deg<$T> {
+            fn from(angle: Rad<$T>) -> Self {
+                Deg(angle.0.to_degrees())
+            }
+        }
+    )*};
+}
+
+impl_conversions_for_float_types!(f32, f64);
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use float_eq::assert_float_eq;
+
+    use super::{Deg, Rad};
+
+    #[test]
+    fn conversions() {
+        assert_float_eq!(Rad::from(Deg(90.0_f64)).0, PI / 2.0, ulps <= 1);
+        assert_float_eq!(Deg::from(Rad(PI)).0, 180.0, ulps <= 1);
+        assert_float_eq!(Deg::from(Rad::from(Deg(-37.5_f64))).0, -37.5, ulps <= 1);
+    }
+
+    #[test]
+    fn arithmetic_keeps_the_unit() {
+        assert_eq!(-Deg(30.0), Deg(-30.0));
+        assert_eq!(Deg(30.0) + Deg(15.0), Deg(45.0));
+        assert_eq!(Rad(2.0) - Rad(0.5), Rad(1.5));
+        assert_eq!(Rad(1.0) * 3.0, Rad(3.0));
+        assert_eq!(Deg(90.0) / 2.0, Deg(45.0));
+    }
+}
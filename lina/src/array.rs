@@ -0,0 +1,91 @@
+//! Internal helpers for building the fixed size arrays backing
+//! [Vector](crate::vector::Vector) and [Matrix](crate::matrix::Matrix).
+
+/// Combine two arrays element by element.
+///
+/// ```text
+/// out[i] = f(lhs[i], rhs[i])
+/// ```
+pub(crate) fn zip_map<Lhs, Rhs, Out, const N: usize>(
+    lhs: [Lhs; N],
+    rhs: [Rhs; N],
+    mut f: impl FnMut(Lhs, Rhs) -> Out,
+) -> [Out; N]
+where
+    Lhs: Copy,
+    Rhs: Copy,
+{
+    std::array::from_fn(|i| f(lhs[i], rhs[i]))
+}
+
+/// Combine two nested arrays element by element.
+///
+/// ```text
+/// out[i][j] = f(lhs[i][j], rhs[i][j])
+/// ```
+pub(crate) fn zip_map_2d<Lhs, Rhs, Out, const COLS: usize, const ROWS: usize>(
+    lhs: [[Lhs; COLS]; ROWS],
+    rhs: [[Rhs; COLS]; ROWS],
+    mut f: impl FnMut(Lhs, Rhs) -> Out,
+) -> [[Out; COLS]; ROWS]
+where
+    Lhs: Copy,
+    Rhs: Copy,
+{
+    zip_map(lhs, rhs, |lhs, rhs| zip_map(lhs, rhs, &mut f))
+}
+
+/// Fill an array with the next `N` elements of `iter`.
+///
+/// Fails with the number of elements taken if `iter` runs out before.
+pub(crate) fn try_fill<T, const N: usize>(iter: impl Iterator<Item = T>) -> Result<[T; N], usize>
+where
+    T: Copy,
+{
+    let mut iter = iter.fuse();
+    let values: [Option<T>; N] = std::array::from_fn(|_| iter.next());
+    match values.iter().position(Option::is_none) {
+        Some(taken) => Err(taken),
+        None => Ok(values.map(Option::unwrap)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zip_map_pairs_elements() {
+        assert_eq!(zip_map([1, 2, 3], [10, 20, 30], |l, r| l + r), [11, 22, 33]);
+        assert_eq!(zip_map([1.5f32], [2u8], |l, r| l * r as f32), [3.0]);
+    }
+
+    #[test]
+    fn zip_map_2d_pairs_elements() {
+        let lhs = [[1, 2], [3, 4], [5, 6]];
+        let rhs = [[6, 5], [4, 3], [2, 1]];
+
+        assert_eq!(
+            zip_map_2d(lhs, rhs, |l, r| l * r),
+            [[6, 10], [12, 12], [10, 6]]
+        );
+    }
+
+    #[test]
+    fn zip_map_visits_in_order() {
+        let mut visited = Vec::new();
+        zip_map_2d([[0, 1], [2, 3]], [[0; 2]; 2], |l, _| visited.push(l));
+
+        assert_eq!(visited, [0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn try_fill_stops_after_n() {
+        let mut iter = 1..10;
+
+        assert_eq!(try_fill::<_, 3>(&mut iter), Ok([1, 2, 3]));
+        assert_eq!(iter.next(), Some(4));
+        assert_eq!(try_fill::<_, 4>(1..3), Err(2));
+        assert_eq!(try_fill::<u8, 0>(std::iter::empty()), Ok([]));
+    }
+}
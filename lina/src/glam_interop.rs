@@ -0,0 +1,113 @@
+//! Conversions from and to the [glam] types, enabled by the `glam` feature.
+//!
+//! The vectors convert component by component. glam stores its matrices
+//! column by column, the conversions keep the elements at the same row and
+//! column.
+//!
+//! ```
+//! # use lina::{m, v};
+//! # use lina::matrix::Matrix;
+//! let translation = m![
+//!     [1.0_f32, 0.0, 0.0, 5.0],
+//!     [0.0, 1.0, 0.0, 6.0],
+//!     [0.0, 0.0, 1.0, 7.0],
+//!     [0.0, 0.0, 0.0, 1.0]
+//! ];
+//!
+//! let mat4 = glam::Mat4::from(translation);
+//! assert_eq!(mat4.transform_point3(glam::Vec3::ZERO), glam::Vec3::new(5.0, 6.0, 7.0));
+//! assert_eq!(Matrix::from(mat4), translation);
+//!
+//! let v: glam::Vec3 = v![1.0, 2.0, 3.0].into();
+//! assert_eq!(v.y, 2.0);
+//! ```
+
+use crate::matrix::Matrix;
+use crate::vector::Vector;
+
+macro_rules! impl_vector_conversions {
+    ($($T: ty, $N: literal => $Glam: ty),* $(,)*) => {$(
+        impl From<Vector<$T, $N>> for $Glam {
+            fn from(vector: Vector<$T, $N>) -> Self {
+                <$Glam>::from_array(vector.data)
+            }
+        }
+
+        impl From<$Glam> for Vector<$T, $N> {
+            fn from(vector: $Glam) -> Self {
+                Vector::from_array(vector.to_array())
+            }
+        }
+    )*};
+}
+
+impl_vector_conversions!(
+    f32, 2 => glam::Vec2,
+    f32, 3 => glam::Vec3,
+    f32, 4 => glam::Vec4,
+    f64, 2 => glam::DVec2,
+    f64, 3 => glam::DVec3,
+    f64, 4 => glam::DVec4,
+    i32, 2 => glam::IVec2,
+    i32, 3 => glam::IVec3,
+    i32, 4 => glam::IVec4,
+    u32, 2 => glam::UVec2,
+    u32, 3 => glam::UVec3,
+    u32, 4 => glam::UVec4,
+);
+
+macro_rules! impl_matrix_conversions {
+    ($($T: ty, $N: literal => $Glam: ty),* $(,)*) => {$(
+        impl From<Matrix<$T, $N, $N>> for $Glam {
+            fn from(matrix: Matrix<$T, $N, $N>) -> Self {
+                <$Glam>::from_cols_array_2d(&matrix.transpose().data)
+            }
+        }
+
+        impl From<$Glam> for Matrix<$T, $N, $N> {
+            fn from(matrix: $Glam) -> Self {
+                Matrix::from_matrix(matrix.to_cols_array_2d()).transpose()
+            }
+        }
+    )*};
+}
+
+impl_matrix_conversions!(
+    f32, 2 => glam::Mat2,
+    f32, 3 => glam::Mat3,
+    f32, 4 => glam::Mat4,
+    f64, 2 => glam::DMat2,
+    f64, 3 => glam::DMat3,
+    f64, 4 => glam::DMat4,
+);
+
+#[cfg(test)]
+mod tests {
+    use crate::matrix::Matrix;
+    use crate::vector::Vector;
+    use crate::{m, v};
+
+    #[test]
+    fn vectors_round_trip() {
+        let vector = v![1.0, -2.0, 3.5, 4.0];
+        let glam: glam::DVec4 = vector.into();
+
+        assert_eq!(glam, glam::DVec4::new(1.0, -2.0, 3.5, 4.0));
+        assert_eq!(Vector::from(glam), vector);
+        assert_eq!(Vector::from(glam::IVec3::new(1, 2, 3)), v![1, 2, 3]);
+    }
+
+    #[test]
+    fn matrices_keep_rows_and_columns() {
+        let matrix = m![[1.0_f32, 2.0, 3.0], [4.0, 5.0, 6.0], [7.0, 8.0, 9.0]];
+        let glam = glam::Mat3::from(matrix);
+
+        assert_eq!(glam.row(0), glam::Vec3::new(1.0, 2.0, 3.0));
+        assert_eq!(glam.col(0), glam::Vec3::new(1.0, 4.0, 7.0));
+        assert_eq!(Matrix::from(glam), matrix);
+        // Products agree as well.
+        let product = glam::Mat3::from(matrix * matrix);
+        assert_eq!(product, glam * glam);
+        assert_eq!(Vector::from(glam * glam::Vec3::X), matrix.col(0));
+    }
+}
@@ -0,0 +1,24 @@
+use std::fmt;
+
+/// Error returned when constructing a [Vector](crate::vector::Vector) or
+/// [Matrix](crate::matrix::Matrix) from a number of elements that doesn't
+/// match its size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LengthError {
+    /// The number of elements required.
+    pub expected: usize,
+    /// The number of elements received.
+    pub actual: usize,
+}
+
+impl fmt::Display for LengthError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "expected {} elements, got {}",
+            self.expected, self.actual
+        )
+    }
+}
+
+impl std::error::Error for LengthError {}
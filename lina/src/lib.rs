@@ -6,5 +6,15 @@
 //! ## Planned improvements
 //!
 
+pub mod angle;
 pub mod matrix;
 pub mod vector;
+
+mod array;
+#[cfg(feature = "glam")]
+mod glam_interop;
+mod length_error;
+#[cfg(feature = "mint")]
+mod mint_interop;
+
+pub use length_error::LengthError;
@@ -1,6 +1,5 @@
-use std::mem;
-
 use super::Matrix;
+use crate::array::zip_map_2d;
 
 impl<ValueType, const COLS: usize, const ROWS: usize> std::ops::Add<Matrix<ValueType, COLS, ROWS>>
     for Matrix<ValueType, COLS, ROWS>
@@ -11,22 +10,9 @@ where
 
     /// Implement `Matrix<T> + Matrix<T>` operation.
     fn add(self, rhs: Matrix<ValueType, COLS, ROWS>) -> Self::Output {
-        let mut data = [[mem::MaybeUninit::<ValueType>::uninit(); COLS]; ROWS];
-
-        // Given that the two matrices have the same shape, we can simply flatten the internal structures
-        // and apply the operation per element.
-        for (elem, (lhs, rhs)) in data
-            .iter_mut()
-            .flatten()
-            .zip(self.data.iter().flatten().zip(rhs.data.iter().flatten()))
-        {
-            elem.write(*lhs + *rhs);
+        Matrix {
+            data: zip_map_2d(self.data, rhs.data, |lhs, rhs| lhs + rhs),
         }
-
-        let ptr = &mut data as *mut _ as *mut [[ValueType; COLS]; ROWS];
-        let transmuted = unsafe { ptr.read() };
-
-        Matrix { data: transmuted }
     }
 }
 
@@ -1,5 +1,18 @@
 use crate::matrix::Matrix;
 
+impl<ValueType> Matrix<ValueType, 2, 2>
+where
+    ValueType: Copy
+        + std::ops::Mul<Output = ValueType>
+        + std::ops::Sub<Output = ValueType>
+        + std::ops::Neg<Output = ValueType>,
+{
+    /// Generate the adjoint matrix.
+    pub fn adjoint(&self) -> Matrix<ValueType, 2, 2> {
+        Matrix::from_matrix([[self[(1, 1)], -self[(0, 1)]], [-self[(1, 0)], self[(0, 0)]]])
+    }
+}
+
 impl<ValueType> Matrix<ValueType, 3, 3>
 where
     ValueType: Copy
@@ -23,11 +36,77 @@ where
     }
 }
 
+impl<ValueType> Matrix<ValueType, 4, 4>
+where
+    ValueType: Copy
+        + std::ops::Mul<Output = ValueType>
+        + std::ops::Sub<Output = ValueType>
+        + std::ops::Add<Output = ValueType>
+        + std::ops::Neg<Output = ValueType>,
+{
+    /// Generate the adjoint matrix.
+    ///
+    /// Every cofactor is expanded from the same 2x2 minors used by
+    /// [determinant](Matrix::determinant), `s` from the top two rows
+    /// and `c` from the bottom two.
+    pub fn adjoint(&self) -> Matrix<ValueType, 4, 4> {
+        let s0 = self[(0, 0)] * self[(1, 1)] - self[(1, 0)] * self[(0, 1)];
+        let s1 = self[(0, 0)] * self[(1, 2)] - self[(1, 0)] * self[(0, 2)];
+        let s2 = self[(0, 0)] * self[(1, 3)] - self[(1, 0)] * self[(0, 3)];
+        let s3 = self[(0, 1)] * self[(1, 2)] - self[(1, 1)] * self[(0, 2)];
+        let s4 = self[(0, 1)] * self[(1, 3)] - self[(1, 1)] * self[(0, 3)];
+        let s5 = self[(0, 2)] * self[(1, 3)] - self[(1, 2)] * self[(0, 3)];
+
+        let c0 = self[(2, 0)] * self[(3, 1)] - self[(3, 0)] * self[(2, 1)];
+        let c1 = self[(2, 0)] * self[(3, 2)] - self[(3, 0)] * self[(2, 2)];
+        let c2 = self[(2, 0)] * self[(3, 3)] - self[(3, 0)] * self[(2, 3)];
+        let c3 = self[(2, 1)] * self[(3, 2)] - self[(3, 1)] * self[(2, 2)];
+        let c4 = self[(2, 1)] * self[(3, 3)] - self[(3, 1)] * self[(2, 3)];
+        let c5 = self[(2, 2)] * self[(3, 3)] - self[(3, 2)] * self[(2, 3)];
+
+        Matrix::from_matrix([
+            [
+                self[(1, 1)] * c5 - self[(1, 2)] * c4 + self[(1, 3)] * c3,
+                -(self[(0, 1)] * c5) + self[(0, 2)] * c4 - self[(0, 3)] * c3,
+                self[(3, 1)] * s5 - self[(3, 2)] * s4 + self[(3, 3)] * s3,
+                -(self[(2, 1)] * s5) + self[(2, 2)] * s4 - self[(2, 3)] * s3,
+            ],
+            [
+                -(self[(1, 0)] * c5) + self[(1, 2)] * c2 - self[(1, 3)] * c1,
+                self[(0, 0)] * c5 - self[(0, 2)] * c2 + self[(0, 3)] * c1,
+                -(self[(3, 0)] * s5) + self[(3, 2)] * s2 - self[(3, 3)] * s1,
+                self[(2, 0)] * s5 - self[(2, 2)] * s2 + self[(2, 3)] * s1,
+            ],
+            [
+                self[(1, 0)] * c4 - self[(1, 1)] * c2 + self[(1, 3)] * c0,
+                -(self[(0, 0)] * c4) + self[(0, 1)] * c2 - self[(0, 3)] * c0,
+                self[(3, 0)] * s4 - self[(3, 1)] * s2 + self[(3, 3)] * s0,
+                -(self[(2, 0)] * s4) + self[(2, 1)] * s2 - self[(2, 3)] * s0,
+            ],
+            [
+                -(self[(1, 0)] * c3) + self[(1, 1)] * c1 - self[(1, 2)] * c0,
+                self[(0, 0)] * c3 - self[(0, 1)] * c1 + self[(0, 2)] * c0,
+                -(self[(3, 0)] * s3) + self[(3, 1)] * s1 - self[(3, 2)] * s0,
+                self[(2, 0)] * s3 - self[(2, 1)] * s1 + self[(2, 2)] * s0,
+            ],
+        ])
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::m;
     use float_eq::assert_float_eq;
 
+    #[test]
+    fn adjoint_2x2_int() {
+        let m = m![[1, 2], [3, 4]];
+        let result_adjoint = m.adjoint();
+        let expected_adjoint = m![[4, -2], [-3, 1]];
+
+        assert_eq!(result_adjoint, expected_adjoint);
+    }
+
     #[test]
     fn adjoint_int() {
         let m = m![[1, 2, 3], [4, 5, 6], [0, 0, 9]];
@@ -50,4 +129,23 @@ mod tests {
             .zip(expected_adjoint.as_slices().iter().flatten())
             .for_each(|(l, r)| assert_float_eq!(l, r, ulps <= 1));
     }
+
+    #[test]
+    fn adjoint_4x4_int() {
+        let m = m![[1, 0, 2, -1], [3, 0, 0, 5], [2, 1, 4, -3], [1, 0, 5, 0]];
+        let result_adjoint = m.adjoint();
+        let expected_adjoint = m![
+            [25, 5, 0, -10],
+            [-75, 3, 30, 6],
+            [-5, -1, 0, 8],
+            [-15, 3, 0, 6]
+        ];
+
+        assert_eq!(result_adjoint, expected_adjoint);
+        // M * adj(M) = det(M) * I
+        assert_eq!(
+            m * result_adjoint,
+            m![[30, 0, 0, 0], [0, 30, 0, 0], [0, 0, 30, 0], [0, 0, 0, 30]]
+        );
+    }
 }
@@ -0,0 +1,65 @@
+use float_eq::{FloatEq, FloatEqUlpsTol, UlpsTol};
+
+use crate::matrix::Matrix;
+
+impl<ValueType, const COLS: usize, const ROWS: usize> Matrix<ValueType, COLS, ROWS>
+where
+    ValueType: FloatEq<Tol = ValueType> + FloatEqUlpsTol,
+    UlpsTol<ValueType>: Sized,
+{
+    /// Check if every element is within `epsilon` of the other.
+    ///
+    /// ```
+    /// # use lina::m;
+    /// # use lina::matrix::Matrix;
+    /// let m: Matrix<f64, 2, 2> = m![[0.1 + 0.2, 0.0], [0.0, 1.0]];
+    ///
+    /// assert_ne!(m, m![[0.3, 0.0], [0.0, 1.0]]);
+    /// assert!(m.approx_eq(&m![[0.3, 0.0], [0.0, 1.0]], f64::EPSILON));
+    /// ```
+    pub fn approx_eq(&self, other: &Matrix<ValueType, COLS, ROWS>, epsilon: ValueType) -> bool {
+        self.data
+            .iter()
+            .flatten()
+            .zip(other.data.iter().flatten())
+            .all(|(lhs, rhs)| lhs.eq_abs(rhs, &epsilon))
+    }
+
+    /// Check if every element is at most `max_ulps` representable values away from the other.
+    ///
+    /// See [Vector::ulps_eq](crate::vector::Vector::ulps_eq).
+    pub fn ulps_eq(
+        &self,
+        other: &Matrix<ValueType, COLS, ROWS>,
+        max_ulps: UlpsTol<ValueType>,
+    ) -> bool {
+        self.data
+            .iter()
+            .flatten()
+            .zip(other.data.iter().flatten())
+            .all(|(lhs, rhs)| lhs.eq_ulps(rhs, &max_ulps))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{m, matrix::Matrix};
+
+    #[test]
+    fn approx_eq() {
+        let m: Matrix<f64, 3, 2> = m![[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]];
+
+        assert!(m.approx_eq(&m![[1.0, 2.0, 3.0], [4.0, 5.0, 6.01]], 0.1));
+        assert!(!m.approx_eq(&m![[1.0, 2.0, 3.0], [4.0, 5.0, 6.2]], 0.1));
+    }
+
+    #[test]
+    fn ulps_eq() {
+        let m: Matrix<f32, 2, 2> = m![[1.0, 0.5], [0.25, 2.0]];
+        let mut other = m;
+        other.data[1][1] = f32::from_bits(2.0f32.to_bits() + 2);
+
+        assert!(m.ulps_eq(&other, 2));
+        assert!(!m.ulps_eq(&other, 1));
+    }
+}
@@ -1,5 +1,17 @@
 use crate::matrix::Matrix;
 
+impl<ValueType> Matrix<ValueType, 2, 2>
+where
+    ValueType: Copy
+        + std::ops::Mul<Output = ValueType>
+        + std::ops::Sub<Output = ValueType>
+        + std::ops::Add<Output = ValueType>,
+{
+    pub fn determinant(&self) -> ValueType {
+        self[(0, 0)] * self[(1, 1)] - self[(0, 1)] * self[(1, 0)]
+    }
+}
+
 impl<ValueType> Matrix<ValueType, 3, 3>
 where
     ValueType: Copy
@@ -17,12 +29,54 @@ where
     }
 }
 
+impl<ValueType> Matrix<ValueType, 4, 4>
+where
+    ValueType: Copy
+        + std::ops::Mul<Output = ValueType>
+        + std::ops::Sub<Output = ValueType>
+        + std::ops::Add<Output = ValueType>,
+{
+    /// Calculated by the Laplace expansion along the first two rows,
+    /// pairing each 2x2 minor of the top rows with its complementary
+    /// minor from the bottom rows.
+    pub fn determinant(&self) -> ValueType {
+        let s0 = self[(0, 0)] * self[(1, 1)] - self[(1, 0)] * self[(0, 1)];
+        let s1 = self[(0, 0)] * self[(1, 2)] - self[(1, 0)] * self[(0, 2)];
+        let s2 = self[(0, 0)] * self[(1, 3)] - self[(1, 0)] * self[(0, 3)];
+        let s3 = self[(0, 1)] * self[(1, 2)] - self[(1, 1)] * self[(0, 2)];
+        let s4 = self[(0, 1)] * self[(1, 3)] - self[(1, 1)] * self[(0, 3)];
+        let s5 = self[(0, 2)] * self[(1, 3)] - self[(1, 2)] * self[(0, 3)];
+
+        let c0 = self[(2, 0)] * self[(3, 1)] - self[(3, 0)] * self[(2, 1)];
+        let c1 = self[(2, 0)] * self[(3, 2)] - self[(3, 0)] * self[(2, 2)];
+        let c2 = self[(2, 0)] * self[(3, 3)] - self[(3, 0)] * self[(2, 3)];
+        let c3 = self[(2, 1)] * self[(3, 2)] - self[(3, 1)] * self[(2, 2)];
+        let c4 = self[(2, 1)] * self[(3, 3)] - self[(3, 1)] * self[(2, 3)];
+        let c5 = self[(2, 2)] * self[(3, 3)] - self[(3, 2)] * self[(2, 3)];
+
+        s0 * c5 - s1 * c4 + s2 * c3 + s3 * c2 - s4 * c1 + s5 * c0
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use float_eq::assert_float_eq;
 
     use crate::m;
 
+    #[test]
+    fn determinant_2x2_int() {
+        assert_eq!(m![[1, 2], [3, 4]].determinant(), -2);
+        assert_eq!(m![[2, 4], [1, 2]].determinant(), 0);
+    }
+
+    #[test]
+    fn determinant_2x2_f32() {
+        let m = m![[1.5f32, -2.0], [0.5, 4.0]];
+
+        assert_float_eq!(m.determinant(), 7.0, ulps <= 1);
+    }
+
     #[test]
     fn determinant_zero_int() {
         let m = m![[1, 2, 3], [4, 5, 6], [7, 8, 9]];
@@ -49,4 +103,31 @@ mod tests {
 
         assert_float_eq!(result_determinant, expected_determinant, ulps <= 1);
     }
+
+    #[test]
+    fn determinant_4x4_int() {
+        let m = m![[1, 0, 2, -1], [3, 0, 0, 5], [2, 1, 4, -3], [1, 0, 5, 0]];
+
+        assert_eq!(m.determinant(), 30);
+    }
+
+    #[test]
+    fn determinant_4x4_zero_int() {
+        // The last row is the sum of the first two.
+        let m = m![[1, 2, 3, 4], [5, 6, 7, 8], [2, 0, 1, 3], [6, 8, 10, 12]];
+
+        assert_eq!(m.determinant(), 0);
+    }
+
+    #[test]
+    fn determinant_4x4_triangular_f64() {
+        let m = m![
+            [2.0f64, 1.0, -3.0, 4.0],
+            [0.0, 0.5, 7.0, 1.0],
+            [0.0, 0.0, -4.0, 2.0],
+            [0.0, 0.0, 0.0, 1.5]
+        ];
+
+        assert_float_eq!(m.determinant(), -6.0, ulps <= 1);
+    }
 }
@@ -0,0 +1,77 @@
+use std::fmt;
+
+use crate::matrix::Matrix;
+
+impl<ValueType, const COLS: usize, const ROWS: usize> fmt::Display for Matrix<ValueType, COLS, ROWS>
+where
+    ValueType: fmt::Display,
+{
+    /// Format the matrix row by row, with the columns right aligned.
+    ///
+    /// The precision is applied to every element.
+    ///
+    /// ```
+    /// # use lina::m;
+    /// # use lina::matrix::Matrix;
+    /// let m: Matrix<f32, 3, 2> = m![[1.0, -20.0, 0.5], [0.0, 1.0, 100.0]];
+    ///
+    /// assert_eq!(
+    ///     format!("{m:.1}"),
+    ///     "[1.0 -20.0   0.5]\n\
+    ///      [0.0   1.0 100.0]"
+    /// );
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let elements = self
+            .data
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|value| match f.precision() {
+                        Some(precision) => format!("{value:.precision$}"),
+                        None => value.to_string(),
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let mut widths = [0; COLS];
+        for row in &elements {
+            for (width, element) in widths.iter_mut().zip(row) {
+                *width = (*width).max(element.chars().count());
+            }
+        }
+
+        for (i, row) in elements.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "[")?;
+            for (j, (element, width)) in row.iter().zip(widths).enumerate() {
+                if j > 0 {
+                    write!(f, " ")?;
+                }
+                write!(f, "{element:>width$}")?;
+            }
+            write!(f, "]")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{m, matrix::Matrix};
+
+    #[test]
+    fn display() {
+        let m: Matrix<i32, 2, 2> = m![[1, 200], [-30, 4]];
+        assert_eq!(m.to_string(), "[  1 200]\n[-30   4]");
+    }
+
+    #[test]
+    fn display_with_precision() {
+        let m: Matrix<f64, 2, 2> = m![[1.0, 0.0], [0.0, 1.0]];
+        assert_eq!(format!("{m:.2}"), "[1.00 0.00]\n[0.00 1.00]");
+    }
+}
@@ -0,0 +1,56 @@
+use crate::matrix::Matrix;
+
+impl<ValueType> Matrix<ValueType, 4, 4>
+where
+    ValueType: Copy,
+{
+    /// The elements column by column, the layout of a `mat4x4` in WGSL and
+    /// GLSL, ready to be uploaded to the GPU.
+    ///
+    /// The matrix itself is stored row by row, see [to_rows_array](Matrix::to_rows_array).
+    ///
+    /// ```
+    /// # use lina::m;
+    /// let translation = m![
+    ///     [1, 0, 0, 5],
+    ///     [0, 1, 0, 6],
+    ///     [0, 0, 1, 7],
+    ///     [0, 0, 0, 1]
+    /// ];
+    ///
+    /// // The translation is in the last column.
+    /// assert_eq!(translation.to_cols_array()[12..], [5, 6, 7, 1]);
+    /// ```
+    pub fn to_cols_array(&self) -> [ValueType; 16] {
+        std::array::from_fn(|index| self.data[index % 4][index / 4])
+    }
+
+    /// Create a [Matrix] from its elements column by column, the inverse of
+    /// [to_cols_array](Matrix::to_cols_array).
+    ///
+    /// ```
+    /// # use lina::m;
+    /// # use lina::matrix::Matrix;
+    /// let matrix = m![[1, 2, 3, 4], [5, 6, 7, 8], [9, 10, 11, 12], [13, 14, 15, 16]];
+    ///
+    /// assert_eq!(Matrix::from_cols_array(matrix.to_cols_array()), matrix);
+    /// ```
+    pub fn from_cols_array(values: [ValueType; 16]) -> Self {
+        Self {
+            data: std::array::from_fn(|i| std::array::from_fn(|j| values[j * 4 + i])),
+        }
+    }
+
+    /// The elements row by row, the way they are stored.
+    ///
+    /// ```
+    /// # use lina::m;
+    /// let matrix = m![[1, 2, 3, 4], [5, 6, 7, 8], [9, 10, 11, 12], [13, 14, 15, 16]];
+    ///
+    /// assert_eq!(matrix.to_rows_array()[..4], [1, 2, 3, 4]);
+    /// assert_eq!(matrix.to_rows_array(), matrix.transpose().to_cols_array());
+    /// ```
+    pub fn to_rows_array(&self) -> [ValueType; 16] {
+        std::array::from_fn(|index| self.data[index / 4][index % 4])
+    }
+}
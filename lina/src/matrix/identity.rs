@@ -0,0 +1,108 @@
+use crate::matrix::Matrix;
+use crate::vector::{One, Vector, Zero};
+
+impl<ValueType, const N: usize> Matrix<ValueType, N, N>
+where
+    ValueType: Copy + Zero + One,
+{
+    /// The identity [Matrix], ones in the main diagonal and zeros everywhere
+    /// else, usable in constants.
+    ///
+    /// Given any `M` square matrix with the same size and identity matrix `I`:
+    /// ```text
+    /// M * I = I * M = M
+    /// ```
+    ///
+    /// ```
+    /// # use lina::m;
+    /// # use lina::matrix::Matrix;
+    /// const IDENTITY: Matrix<i8, 3, 3> = Matrix::IDENTITY;
+    ///
+    /// assert_eq!(IDENTITY, m![[1, 0, 0], [0, 1, 0], [0, 0, 1]]);
+    /// ```
+    pub const IDENTITY: Self = {
+        let mut data = [[ValueType::ZERO; N]; N];
+        let mut i = 0;
+        while i < N {
+            data[i][i] = ValueType::ONE;
+            i += 1;
+        }
+        Self { data }
+    };
+
+    /// Create the [identity](Matrix::IDENTITY) [Matrix].
+    ///
+    /// ```
+    /// # use lina::m;
+    /// # use lina::matrix::Matrix;
+    /// const IDENTITY: Matrix<u32, 2, 2> = Matrix::identity();
+    ///
+    /// assert_eq!(IDENTITY, m![[1, 0], [0, 1]]);
+    /// ```
+    pub const fn identity() -> Self {
+        Self::IDENTITY
+    }
+
+    /// Create a [Matrix] with the components of `values` in the main diagonal
+    /// and zeros everywhere else.
+    ///
+    /// ```
+    /// # use lina::{m, v};
+    /// # use lina::matrix::Matrix;
+    /// let diagonal = Matrix::diagonal(v![2.0, 3.0]);
+    ///
+    /// assert_eq!(diagonal, m![[2.0, 0.0], [0.0, 3.0]]);
+    /// ```
+    pub fn diagonal(values: Vector<ValueType, N>) -> Self {
+        let mut data = [[ValueType::ZERO; N]; N];
+        for (i, row) in data.iter_mut().enumerate() {
+            row[i] = values.data[i];
+        }
+
+        Self { data }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::matrix::Matrix;
+    use crate::{m, v};
+
+    #[test]
+    fn identity() {
+        assert_eq!(Matrix::<u8, 1, 1>::identity(), m![[1]]);
+        assert_eq!(
+            Matrix::<f32, 4, 4>::identity(),
+            m![
+                [1.0, 0.0, 0.0, 0.0],
+                [0.0, 1.0, 0.0, 0.0],
+                [0.0, 0.0, 1.0, 0.0],
+                [0.0, 0.0, 0.0, 1.0]
+            ]
+        );
+    }
+
+    #[test]
+    fn identity_constant() {
+        assert_eq!(Matrix::<u8, 3, 3>::IDENTITY, Matrix::identity());
+        assert_eq!(Matrix::<i64, 2, 2>::IDENTITY, m![[1, 0], [0, 1]]);
+        assert_eq!(Matrix::<f64, 4, 4>::IDENTITY, Matrix::identity());
+    }
+
+    #[test]
+    fn identity_is_neutral() {
+        let m = m![[1.5, -2.0, 3.0], [0.0, 4.0, 1.0], [7.0, 8.0, -9.0]];
+        let identity = Matrix::<f64, 3, 3>::identity();
+
+        assert_eq!(m * identity, m);
+        assert_eq!(identity * m, m);
+    }
+
+    #[test]
+    fn diagonal() {
+        assert_eq!(
+            Matrix::diagonal(v![1, -2, 3]),
+            m![[1, 0, 0], [0, -2, 0], [0, 0, 3]]
+        );
+    }
+}
@@ -1,9 +1,36 @@
 use crate::matrix::Matrix;
+use crate::vector::{One, Zero};
+
+impl<ValueType> Matrix<ValueType, 2, 2>
+where
+    ValueType: Copy
+        + Zero
+        + One
+        + std::cmp::PartialEq
+        + std::ops::Add<Output = ValueType>
+        + std::ops::Mul<Output = ValueType>
+        + std::ops::Div<Output = ValueType>
+        + std::ops::Sub<Output = ValueType>
+        + std::ops::Neg<Output = ValueType>
+        + std::ops::Mul<Matrix<ValueType, 2, 2>, Output = Matrix<ValueType, 2, 2>>,
+{
+    /// Calculate the inverse of [Matrix].
+    ///
+    /// None is returned if the determinant was zero.
+    pub fn inverse(&self) -> Option<Matrix<ValueType, 2, 2>> {
+        let determinant = self.determinant();
+        if determinant == ValueType::ZERO {
+            return None;
+        }
+        Some((ValueType::ONE / determinant) * self.adjoint())
+    }
+}
 
 impl<ValueType> Matrix<ValueType, 3, 3>
 where
     ValueType: Copy
-        + std::convert::From<i8>
+        + Zero
+        + One
         + std::cmp::PartialEq
         + std::ops::Add<Output = ValueType>
         + std::ops::Mul<Output = ValueType>
@@ -26,10 +53,94 @@ where
     /// ```
     pub fn inverse(&self) -> Option<Matrix<ValueType, 3, 3>> {
         let determinant = self.determinant();
-        if determinant == ValueType::from(0) {
+        if determinant == ValueType::ZERO {
+            return None;
+        }
+        Some((ValueType::ONE / self.determinant()) * self.adjoint())
+    }
+}
+
+impl<ValueType> Matrix<ValueType, 4, 4>
+where
+    ValueType: Copy
+        + Zero
+        + One
+        + std::cmp::PartialEq
+        + std::ops::Add<Output = ValueType>
+        + std::ops::Mul<Output = ValueType>
+        + std::ops::Div<Output = ValueType>
+        + std::ops::Sub<Output = ValueType>
+        + std::ops::Neg<Output = ValueType>
+        + std::ops::Mul<Matrix<ValueType, 4, 4>, Output = Matrix<ValueType, 4, 4>>,
+{
+    /// Calculate the inverse of [Matrix].
+    ///
+    /// None is returned if the determinant was zero.
+    pub fn inverse(&self) -> Option<Matrix<ValueType, 4, 4>> {
+        let determinant = self.determinant();
+        if determinant == ValueType::ZERO {
             return None;
         }
-        Some((ValueType::from(1) / self.determinant()) * self.adjoint())
+        Some((ValueType::ONE / determinant) * self.adjoint())
+    }
+}
+
+impl Matrix<f32, 4, 4> {
+    /// Calculate the inverse of a rigid-body transformation, a rotation `R`
+    /// followed by a translation `t`, like the "Look At" and "Point At"
+    /// matrices.
+    ///
+    /// Instead of the general inverse, the rotation is transposed and the
+    /// translation rotated back and negated:
+    /// ```text
+    /// [R | t]^-1 = [R^T | -R^T * t]
+    /// [0 | 1]      [0   |  1      ]
+    /// ```
+    ///
+    /// In debug builds it panics if the matrix isn't rigid, a scaled or
+    /// projecting matrix would get a wrong inverse otherwise.
+    ///
+    /// ```
+    /// # use lina::m;
+    /// let m = m![
+    ///     [0.0f32, -1.0, 0.0, 1.0],
+    ///     [1.0, 0.0, 0.0, 2.0],
+    ///     [0.0, 0.0, 1.0, 3.0],
+    ///     [0.0, 0.0, 0.0, 1.0]
+    /// ];
+    ///
+    /// assert_eq!(m.inverse_rigid(), m.inverse().unwrap());
+    /// ```
+    pub fn inverse_rigid(&self) -> Matrix<f32, 4, 4> {
+        debug_assert!(self.is_rigid(1e-4), "not a rigid-body transformation");
+        let m = self.as_slices();
+        let mut inverse = [[0.0; 4]; 4];
+        for i in 0..3 {
+            for j in 0..3 {
+                inverse[i][j] = m[j][i];
+            }
+            inverse[i][3] = -(m[0][i] * m[0][3] + m[1][i] * m[1][3] + m[2][i] * m[2][3]);
+        }
+        inverse[3][3] = 1.0;
+        Matrix::from_matrix(inverse)
+    }
+
+    /// Whether the upper 3x3 part is orthonormal and the bottom row is
+    /// `[0, 0, 0, 1]`, within `epsilon`.
+    fn is_rigid(&self, epsilon: f32) -> bool {
+        let m = self.as_slices();
+        let orthonormal = (0..3).all(|i| {
+            (0..3).all(|j| {
+                let dot = m[0][i] * m[0][j] + m[1][i] * m[1][j] + m[2][i] * m[2][j];
+                let expected = if i == j { 1.0 } else { 0.0 };
+                (dot - expected).abs() <= epsilon
+            })
+        });
+        let bottom = m[3][0].abs() <= epsilon
+            && m[3][1].abs() <= epsilon
+            && m[3][2].abs() <= epsilon
+            && (m[3][3] - 1.0).abs() <= epsilon;
+        orthonormal && bottom
     }
 }
 
@@ -37,7 +148,17 @@ where
 mod tests {
     use float_eq::assert_float_eq;
 
-    use crate::m;
+    use crate::{m, matrix::Matrix};
+
+    #[test]
+    fn inverse_2x2_f32() {
+        let m = m![[4.0f32, 7.0], [2.0, 6.0]];
+        let result_inverse = m.inverse().unwrap();
+        let expected_inverse = m![[0.6, -0.7], [-0.2, 0.4]];
+
+        assert!(result_inverse.ulps_eq(&expected_inverse, 2));
+        assert_eq!(m![[2.0f32, 4.0], [1.0, 2.0]].inverse(), None);
+    }
 
     #[test]
     fn inverse_zero_int() {
@@ -57,6 +178,8 @@ mod tests {
     }
 
     #[test]
+    // The expected values are given with more precision than f32 has.
+    #[allow(clippy::excessive_precision)]
     fn inverse_non_zero_f32() {
         let m = m![[1.2f32, -2.1, 5.6], [0.0, 1.0, -2.4], [-1.2, 0.8, 3.0]];
         let result_inverse = m.inverse().unwrap();
@@ -74,4 +197,82 @@ mod tests {
             .zip(expected_inverse.as_slices().iter().flatten())
             .for_each(|(l, r)| assert_float_eq!(l, r, ulps <= 2));
     }
+
+    #[test]
+    fn inverse_4x4_zero_int() {
+        let m = m![[1, 2, 3, 4], [5, 6, 7, 8], [2, 0, 1, 3], [6, 8, 10, 12]];
+
+        assert_eq!(m.inverse(), None);
+    }
+
+    #[test]
+    fn inverse_4x4_f64() {
+        let m = m![
+            [2.0f64, -1.0, 0.0, 3.0],
+            [1.0, 4.0, -2.0, 0.0],
+            [0.0, 5.0, 1.0, -1.0],
+            [3.0, 0.0, 2.0, 1.0]
+        ];
+        let result_inverse = m.inverse().unwrap();
+        let expected_inverse = m![
+            [-22.0, 27.0, -26.0, 40.0],
+            [9.0, 3.0, 20.0, -7.0],
+            [7.0, -32.0, 27.0, 6.0],
+            [52.0, -17.0, 24.0, -29.0]
+        ];
+        let expected_inverse = (1.0 / 103.0) * expected_inverse;
+
+        assert!(result_inverse.approx_eq(&expected_inverse, 1e-15));
+        assert!((m * result_inverse).approx_eq(
+            &m![
+                [1.0, 0.0, 0.0, 0.0],
+                [0.0, 1.0, 0.0, 0.0],
+                [0.0, 0.0, 1.0, 0.0],
+                [0.0, 0.0, 0.0, 1.0]
+            ],
+            1e-15
+        ));
+    }
+
+    #[test]
+    fn inverse_rigid_4x4() {
+        // Rotated around an oblique axis and translated.
+        let (sin, cos) = 0.7f32.sin_cos();
+        let rotate_x = m![
+            [1.0f32, 0.0, 0.0, 0.0],
+            [0.0, cos, -sin, 0.0],
+            [0.0, sin, cos, 0.0],
+            [0.0, 0.0, 0.0, 1.0]
+        ];
+        let rotate_y = m![
+            [cos, 0.0, sin, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [-sin, 0.0, cos, 0.0],
+            [0.0, 0.0, 0.0, 1.0]
+        ];
+        let translate = m![
+            [1.0, 0.0, 0.0, -3.0],
+            [0.0, 1.0, 0.0, 5.5],
+            [0.0, 0.0, 1.0, 2.0],
+            [0.0, 0.0, 0.0, 1.0]
+        ];
+        let m = translate * rotate_y * rotate_x;
+
+        let inverse = m.inverse_rigid();
+        assert!(inverse.approx_eq(&m.inverse().unwrap(), 1e-5));
+        assert!((m * inverse).approx_eq(&Matrix::identity(), 1e-6));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "not a rigid-body transformation")]
+    fn inverse_rigid_of_scaled_4x4() {
+        let m = m![
+            [2.0f32, 0.0, 0.0, 1.0],
+            [0.0, 2.0, 0.0, 0.0],
+            [0.0, 0.0, 2.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0]
+        ];
+        m.inverse_rigid();
+    }
 }
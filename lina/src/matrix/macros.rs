@@ -48,3 +48,39 @@ macro_rules! lhs_scalar_mul_impl {
 lhs_scalar_mul_impl!(
     u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64
 );
+
+macro_rules! impl_neg_trait {
+   ($($T: ty),* $(,)*) => {$(
+        impl<const COLS: usize, const ROWS: usize> std::ops::Neg for Matrix<$T, COLS, ROWS>
+        where
+            $T: std::ops::Mul<Output = $T> + Copy,
+        {
+            type Output = Matrix<$T, COLS, ROWS>;
+
+            /// Perform the `-M` operation.
+            fn neg(self) -> Self::Output {
+                self * -1 as $T
+            }
+        }
+    )*};
+}
+
+impl_neg_trait!(i8, i16, i32, i64, i128, isize, f32, f64);
+
+#[cfg(test)]
+mod tests {
+    use crate::matrix::Matrix;
+
+    #[test]
+    fn negate() {
+        let m: Matrix<f32, 2, 3> = m![[1.0, -2.0], [3.0, 0.0], [-5.0, 6.0]];
+        assert_eq!(-m, m![[-1.0, 2.0], [-3.0, 0.0], [5.0, -6.0]]);
+    }
+
+    #[test]
+    fn negate_integers() {
+        let m: Matrix<i32, 2, 2> = m![[1, 2], [3, 4]];
+        assert_eq!(-m, m![[-1, -2], [-3, -4]]);
+        assert_eq!(-(-m), m);
+    }
+}
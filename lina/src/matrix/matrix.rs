@@ -1,4 +1,8 @@
+/// General [Matrix] structure with `ROWS` rows of `COLS` elements.
+///
+/// The memory layout is guaranteed to be the same as `[[ValueType; COLS]; ROWS]`.
 #[derive(Debug, PartialEq, Clone, Copy)]
+#[repr(transparent)]
 pub struct Matrix<ValueType, const COLS: usize, const ROWS: usize> {
     pub(crate) data: [[ValueType; COLS]; ROWS],
 }
@@ -41,21 +45,27 @@ where
     }
 
     pub fn transpose(&self) -> Matrix<ValueType, ROWS, COLS> {
-        let mut data = [[std::mem::MaybeUninit::<ValueType>::uninit(); ROWS]; COLS];
-
-        // We want the needless range loops, as we use the value to index multiple times.
-        #[allow(clippy::needless_range_loop)]
-        for i in 0..ROWS {
-            #[allow(clippy::needless_range_loop)]
-            for j in 0..COLS {
-                data[j][i].write(self.data[i][j]);
-            }
+        Matrix {
+            data: std::array::from_fn(|j| std::array::from_fn(|i| self.data[i][j])),
         }
+    }
 
-        let ptr = &mut data as *mut _ as *mut [[ValueType; ROWS]; COLS];
-        let transmuted = unsafe { ptr.read() };
-
-        Matrix { data: transmuted }
+    /// Create a new [Matrix] by applying `f` to every element, e.g. to
+    /// convert between value types.
+    ///
+    /// Example
+    /// ```
+    /// # use lina::m;
+    /// let m = m![[1.0_f64, 2.0], [3.0, 4.0]];
+    /// assert_eq!(m.map(|value| value as f32), m![[1.0_f32, 2.0], [3.0, 4.0]]);
+    /// ```
+    pub fn map<Output>(
+        &self,
+        mut f: impl FnMut(ValueType) -> Output,
+    ) -> Matrix<Output, COLS, ROWS> {
+        Matrix {
+            data: self.data.map(|row| row.map(&mut f)),
+        }
     }
 }
 
@@ -65,7 +75,9 @@ impl<ValueType, const COLS: usize, const ROWS: usize> Matrix<ValueType, COLS, RO
         &self.data
     }
 
-    pub fn from_matrix(values: [[ValueType; COLS]; ROWS]) -> Self {
+    /// Construct a [Matrix] from its rows, usable in constants like the
+    /// [m](crate::m) macro with a list of rows.
+    pub const fn from_matrix(values: [[ValueType; COLS]; ROWS]) -> Self {
         Self { data: values }
     }
 }
@@ -1,16 +1,26 @@
 mod add;
 mod add_assign;
 mod adjoint;
+mod approx_eq;
 mod default;
 mod determinant;
+mod display;
+mod flat;
+mod identity;
 mod index;
 mod index_mut;
 mod inverse;
 mod macros;
 mod mul;
 mod mul_assign;
+mod orthonormalize;
+#[cfg(feature = "bytemuck")]
+mod pod;
+mod row_col;
 mod sub;
 mod sub_assign;
+mod submatrix;
+mod try_from;
 
 #[allow(clippy::module_inception)]
 mod matrix;
@@ -1,44 +1,35 @@
-use std::mem;
-
-use crate::vector::Vector;
+use crate::vector::{Vector, Zero};
 
 use super::Matrix;
 
 impl<ValueType, const COLS: usize, const ROWS: usize> std::ops::Mul<Matrix<ValueType, ROWS, COLS>>
     for Matrix<ValueType, COLS, ROWS>
 where
-    ValueType:
-        std::ops::Mul<ValueType> + std::iter::Sum<<ValueType as std::ops::Mul>::Output> + Copy,
+    ValueType: std::ops::Mul<Output = ValueType> + std::ops::Add<Output = ValueType> + Zero + Copy,
 {
     type Output = Matrix<ValueType, ROWS, ROWS>;
 
     /// Implement `Matrix<T> * Matrix<T>` operation.
+    #[inline]
     fn mul(self, rhs: Matrix<ValueType, ROWS, COLS>) -> Self::Output {
-        let mut data = [[mem::MaybeUninit::<ValueType>::uninit(); ROWS]; ROWS];
-
-        // Not entirely sure if transposition or just reading the values
-        // would be best, but probably there are faster algorithms anyways.
-        // This will work for now.
-        let rhs = rhs.transpose();
-        // We want the needless range loops, as we use the value to index multiple times.
+        // Accumulate whole rows of the rhs scaled by the lhs elements:
+        // out[i] = sum_k(lhs[i][k] * rhs[k])
+        // This walks both matrices in memory order, so no transposed copy of the rhs
+        // is needed. With the sizes known at compile time the loops get fully unrolled,
+        // so the hand unrolled 4x4 f32 product in `benches/matrix_mul.rs` is no faster.
+        let mut data = [[ValueType::ZERO; ROWS]; ROWS];
+        // We want the needless range loops, as we use the values to index multiple times.
         #[allow(clippy::needless_range_loop)]
         for i in 0..ROWS {
-            #[allow(clippy::needless_range_loop)]
-            for j in 0..ROWS {
-                data[i][j].write(
-                    self.data[i]
-                        .iter()
-                        .zip(rhs.data[j].iter())
-                        .map(|(l, r)| *l * *r)
-                        .sum(),
-                );
+            for k in 0..COLS {
+                let l = self.data[i][k];
+                for j in 0..ROWS {
+                    data[i][j] = data[i][j] + l * rhs.data[k][j];
+                }
             }
         }
 
-        let ptr = &mut data as *mut _ as *mut [[ValueType; ROWS]; ROWS];
-        let transmuted = unsafe { ptr.read() };
-
-        Matrix { data: transmuted }
+        Matrix { data }
     }
 }
 
@@ -51,16 +42,9 @@ where
 
     /// Implement `Matrix<T> * T` operation.
     fn mul(self, rhs: ValueType) -> Self::Output {
-        let mut data = [[mem::MaybeUninit::<ValueType>::uninit(); COLS]; ROWS];
-
-        for (elem, lhs) in data.iter_mut().flatten().zip(self.data.iter().flatten()) {
-            elem.write(*lhs * rhs);
+        Matrix {
+            data: self.data.map(|row| row.map(|lhs| lhs * rhs)),
         }
-
-        let ptr = &mut data as *mut _ as *mut [[ValueType; COLS]; ROWS];
-        let transmuted = unsafe { ptr.read() };
-
-        Matrix { data: transmuted }
     }
 }
 
@@ -78,17 +62,7 @@ where
     /// `Vector` with `M` elements, it will produce a `Vector` with `N`
     /// elements.
     fn mul(self, rhs: Vector<ValueType, COLS>) -> Self::Output {
-        let mut data = [mem::MaybeUninit::<ValueType>::uninit(); ROWS];
-
-        for (elem, lhs) in data.iter_mut().zip(self.data.iter()) {
-            let dot = Vector::from_array(*lhs) * rhs;
-            elem.write(dot);
-        }
-
-        let ptr = &mut data as *mut _ as *mut [ValueType; ROWS];
-        let transmuted = unsafe { ptr.read() };
-
-        Vector::from_array(transmuted)
+        Vector::from_array(self.data.map(|lhs| Vector::from_array(lhs) * rhs))
     }
 }
 
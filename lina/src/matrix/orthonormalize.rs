@@ -0,0 +1,98 @@
+use crate::matrix::Matrix;
+use crate::vector::Sqrt;
+
+impl<ValueType> Matrix<ValueType, 3, 3>
+where
+    ValueType: Copy
+        + Default
+        + std::ops::Add<Output = ValueType>
+        + std::ops::Sub<Output = ValueType>
+        + std::ops::Mul<Output = ValueType>
+        + std::ops::Div<Output = ValueType>
+        + std::iter::Sum
+        + Sqrt<Output = ValueType>,
+{
+    /// Turn the columns of the [Matrix] in-place into an orthonormal basis.
+    ///
+    /// Uses the Gram-Schmidt process for columns `c0`, `c1`, `c2`:
+    /// ```text
+    /// c0' = c0 / |c0|
+    /// c1' = (c1 - (c1 * c0')c0') / |c1 - (c1 * c0')c0'|
+    /// c2' = c0' x c1'
+    /// ```
+    /// The first column keeps its direction, the second stays in the plane
+    /// of the first two, and the third is recomputed with the cross product
+    /// to keep the basis right-handed.
+    ///
+    /// Rotation matrices accumulated every frame drift away from being
+    /// orthonormal due to floating point errors, introducing skew and scale.
+    /// Orthonormalizing them from time to time restores a pure rotation.
+    ///
+    /// The first two columns must be linearly independent, otherwise the
+    /// result contains `NaN` values.
+    ///
+    /// ```
+    /// # use lina::m;
+    /// # use lina::matrix::Matrix;
+    /// let mut m = m![[2.0, 1.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 0.5]];
+    /// m.orthonormalize();
+    ///
+    /// assert!(m.approx_eq(&Matrix::identity(), 1e-15));
+    /// ```
+    pub fn orthonormalize(&mut self) {
+        let c0 = self.col(0).normalized();
+        let c1 = self.col(1);
+        let c1 = (c1 - c0 * (c1 * c0)).normalized();
+        let c2 = c0.cross(c1);
+
+        *self = Matrix::from_cols([c0, c1, c2]);
+    }
+
+    /// Generate an orthonormalized [Matrix] without modifying the current one.
+    ///
+    /// See [orthonormalize](Matrix::orthonormalize).
+    pub fn orthonormalized(&self) -> Matrix<ValueType, 3, 3> {
+        let mut m = *self;
+        m.orthonormalize();
+        m
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::m;
+    use crate::matrix::Matrix;
+
+    #[test]
+    fn orthonormalized_columns() {
+        let m = m![[1.0f64, 0.3, -2.0], [0.2, 2.0, 0.5], [-0.1, 0.4, 3.0]];
+        let result = m.orthonormalized();
+
+        // The transpose of an orthonormal matrix is its inverse.
+        assert!((result.transpose() * result).approx_eq(&Matrix::identity(), 1e-15));
+        assert!(result.determinant() > 0.0);
+        // The first column keeps its direction.
+        assert!(result.col(0).approx_eq(&m.col(0).normalized(), 1e-15));
+    }
+
+    #[test]
+    fn rotation_is_unchanged() {
+        let (sin, cos) = 0.7f32.sin_cos();
+        let rotation = m![[cos, -sin, 0.0], [sin, cos, 0.0], [0.0, 0.0, 1.0]];
+
+        assert!(rotation.orthonormalized().approx_eq(&rotation, 1e-6));
+    }
+
+    #[test]
+    fn drift_is_corrected() {
+        let (sin, cos) = 0.01f32.sin_cos();
+        let step = m![[cos, 0.0, sin], [0.0, 1.0, 0.0], [-sin, 0.0, cos]];
+        let mut rotation = Matrix::<f32, 3, 3>::identity();
+        for _ in 0..10000 {
+            rotation = rotation * step;
+        }
+        rotation.orthonormalize();
+
+        assert!((rotation.transpose() * rotation).approx_eq(&Matrix::identity(), 1e-6));
+    }
+}
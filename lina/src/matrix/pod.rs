@@ -0,0 +1,44 @@
+//! [bytemuck] support, enabled by the `bytemuck` feature.
+
+use bytemuck::{Pod, Zeroable};
+
+use crate::matrix::Matrix;
+
+// SAFETY: `Matrix` is `repr(transparent)` over `[[ValueType; COLS]; ROWS]`,
+// which is `Zeroable` and `Pod` if `ValueType` is.
+unsafe impl<ValueType: Zeroable, const COLS: usize, const ROWS: usize> Zeroable
+    for Matrix<ValueType, COLS, ROWS>
+{
+}
+unsafe impl<ValueType: Pod, const COLS: usize, const ROWS: usize> Pod
+    for Matrix<ValueType, COLS, ROWS>
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{m, matrix::Matrix};
+
+    #[test]
+    fn cast_to_bytes() {
+        let m: Matrix<f32, 4, 4> = m![
+            [1.0, 2.0, 3.0, 4.0],
+            [5.0, 6.0, 7.0, 8.0],
+            [9.0, 10.0, 11.0, 12.0],
+            [13.0, 14.0, 15.0, 16.0]
+        ];
+
+        // Row by row
+        let values = bytemuck::cast_slice::<u8, f32>(bytemuck::bytes_of(&m)).to_vec();
+        assert_eq!(
+            values,
+            (1..=16).map(|value| value as f32).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn zeroed() {
+        let m: Matrix<f64, 2, 3> = bytemuck::Zeroable::zeroed();
+        assert_eq!(m, Matrix::from_value(0.0));
+    }
+}
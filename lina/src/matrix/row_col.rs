@@ -0,0 +1,134 @@
+use crate::matrix::Matrix;
+use crate::vector::Vector;
+
+impl<ValueType, const COLS: usize, const ROWS: usize> Matrix<ValueType, COLS, ROWS>
+where
+    ValueType: Copy,
+{
+    /// Create a new [Matrix] where the `i`th [Vector] becomes the `i`th row.
+    ///
+    /// ```
+    /// # use lina::{m, v};
+    /// # use lina::matrix::Matrix;
+    /// let matrix = Matrix::from_rows([v![1, 2, 3], v![4, 5, 6]]);
+    ///
+    /// assert_eq!(matrix, m![[1, 2, 3], [4, 5, 6]]);
+    /// ```
+    pub fn from_rows(rows: [Vector<ValueType, COLS>; ROWS]) -> Self {
+        Self {
+            data: rows.map(|row| row.data),
+        }
+    }
+
+    /// Create a new [Matrix] where the `j`th [Vector] becomes the `j`th column.
+    ///
+    /// ```
+    /// # use lina::{m, v};
+    /// # use lina::matrix::Matrix;
+    /// let matrix = Matrix::from_cols([v![1, 2, 3], v![4, 5, 6]]);
+    ///
+    /// assert_eq!(matrix, m![[1, 4], [2, 5], [3, 6]]);
+    /// ```
+    pub fn from_cols(cols: [Vector<ValueType, ROWS>; COLS]) -> Self {
+        Self {
+            data: std::array::from_fn(|i| std::array::from_fn(|j| cols[j].data[i])),
+        }
+    }
+
+    /// Copy the `i`th row into a [Vector].
+    ///
+    /// Panics if `i` is out of bounds.
+    ///
+    /// ```
+    /// # use lina::{m, v};
+    /// let matrix = m![[1, 2, 3], [4, 5, 6]];
+    ///
+    /// assert_eq!(matrix.row(1), v![4, 5, 6]);
+    /// ```
+    pub fn row(&self, i: usize) -> Vector<ValueType, COLS> {
+        Vector::from_array(self.data[i])
+    }
+
+    /// Copy the `j`th column into a [Vector].
+    ///
+    /// Panics if `j` is out of bounds.
+    ///
+    /// ```
+    /// # use lina::{m, v};
+    /// let matrix = m![[1, 2, 3], [4, 5, 6]];
+    ///
+    /// assert_eq!(matrix.col(2), v![3, 6]);
+    /// ```
+    pub fn col(&self, j: usize) -> Vector<ValueType, ROWS> {
+        assert!(
+            j < COLS,
+            "column index {j} out of bounds for {COLS} columns"
+        );
+        Vector::from_array(self.data.map(|row| row[j]))
+    }
+
+    /// Overwrite the `i`th row with the components of `row`.
+    ///
+    /// Panics if `i` is out of bounds.
+    pub fn set_row(&mut self, i: usize, row: Vector<ValueType, COLS>) {
+        self.data[i] = row.data;
+    }
+
+    /// Overwrite the `j`th column with the components of `col`.
+    ///
+    /// Panics if `j` is out of bounds.
+    pub fn set_col(&mut self, j: usize, col: Vector<ValueType, ROWS>) {
+        for (row, value) in self.data.iter_mut().zip(col.data) {
+            row[j] = value;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::matrix::Matrix;
+    use crate::{m, v};
+
+    #[test]
+    fn from_rows_and_cols_are_transposes() {
+        let rows = Matrix::from_rows([v![1, 2], v![3, 4], v![5, 6]]);
+        let cols = Matrix::from_cols([v![1, 2], v![3, 4], v![5, 6]]);
+
+        assert_eq!(rows, m![[1, 2], [3, 4], [5, 6]]);
+        assert_eq!(cols, m![[1, 3, 5], [2, 4, 6]]);
+        assert_eq!(rows.transpose(), cols);
+    }
+
+    #[test]
+    fn row_col() {
+        let matrix = m![[1, 2, 3], [4, 5, 6], [7, 8, 9]];
+
+        assert_eq!(matrix.row(0), v![1, 2, 3]);
+        assert_eq!(matrix.row(2), v![7, 8, 9]);
+        assert_eq!(matrix.col(0), v![1, 4, 7]);
+        assert_eq!(matrix.col(1), v![2, 5, 8]);
+    }
+
+    #[test]
+    fn set_row_col() {
+        let mut matrix = Matrix::<i32, 3, 2>::new();
+        matrix.set_row(1, v![1, 2, 3]);
+        matrix.set_col(0, v![7, 8]);
+
+        assert_eq!(matrix, m![[7, 0, 0], [8, 2, 3]]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn col_out_of_bounds() {
+        let matrix = m![[1, 2], [3, 4]];
+        matrix.col(2);
+    }
+
+    #[test]
+    #[should_panic]
+    fn set_col_out_of_bounds() {
+        let mut matrix = m![[1, 2], [3, 4]];
+        matrix.set_col(2, v![0, 0]);
+    }
+}
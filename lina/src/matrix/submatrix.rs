@@ -0,0 +1,86 @@
+use crate::matrix::Matrix;
+
+impl<ValueType, const COLS: usize, const ROWS: usize> Matrix<ValueType, COLS, ROWS>
+where
+    ValueType: Copy,
+{
+    /// Copy the block of `SUB_ROWS` rows and `SUB_COLS` columns starting at
+    /// row `i` and column `j` into a new [Matrix].
+    ///
+    /// Panics if the block doesn't fit into the matrix.
+    ///
+    /// ```
+    /// # use lina::m;
+    /// let matrix = m![[1, 2, 3], [4, 5, 6], [7, 8, 9]];
+    ///
+    /// assert_eq!(matrix.submatrix::<2, 1>(1, 2), m![[6], [9]]);
+    /// assert_eq!(matrix.submatrix::<1, 3>(0, 0), m![[1, 2, 3]]);
+    /// ```
+    pub fn submatrix<const SUB_ROWS: usize, const SUB_COLS: usize>(
+        &self,
+        i: usize,
+        j: usize,
+    ) -> Matrix<ValueType, SUB_COLS, SUB_ROWS> {
+        assert!(
+            i + SUB_ROWS <= ROWS && j + SUB_COLS <= COLS,
+            "{SUB_ROWS}x{SUB_COLS} block at ({i}, {j}) out of bounds for {ROWS}x{COLS}"
+        );
+        Matrix {
+            data: std::array::from_fn(|row| std::array::from_fn(|col| self.data[i + row][j + col])),
+        }
+    }
+}
+
+impl<ValueType> Matrix<ValueType, 4, 4>
+where
+    ValueType: Copy,
+{
+    /// The upper left 3x3 block, the linear part of an affine transformation
+    /// without the translation, e.g. the rotation of a rigid-body one.
+    ///
+    /// ```
+    /// # use lina::m;
+    /// let transform = m![
+    ///     [0.0, -1.0, 0.0, 5.0],
+    ///     [1.0, 0.0, 0.0, 6.0],
+    ///     [0.0, 0.0, 1.0, 7.0],
+    ///     [0.0, 0.0, 0.0, 1.0]
+    /// ];
+    ///
+    /// assert_eq!(
+    ///     transform.upper_left_3x3(),
+    ///     m![[0.0, -1.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, 1.0]]
+    /// );
+    /// ```
+    pub fn upper_left_3x3(&self) -> Matrix<ValueType, 3, 3> {
+        self.submatrix(0, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::m;
+    use crate::matrix::Matrix;
+
+    #[test]
+    fn the_whole_matrix_is_a_submatrix() {
+        let matrix = m![[1, 2], [3, 4]];
+
+        assert_eq!(matrix.submatrix::<2, 2>(0, 0), matrix);
+    }
+
+    #[test]
+    fn lower_right_corner() {
+        let matrix =
+            Matrix::<i32, 4, 3>::from_matrix([[1, 2, 3, 4], [5, 6, 7, 8], [9, 10, 11, 12]]);
+
+        assert_eq!(matrix.submatrix::<2, 3>(1, 1), m![[6, 7, 8], [10, 11, 12]]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn out_of_bounds() {
+        let matrix = m![[1, 2], [3, 4]];
+        matrix.submatrix::<1, 2>(0, 1);
+    }
+}
@@ -0,0 +1,116 @@
+use crate::LengthError;
+use crate::array::try_fill;
+use crate::matrix::Matrix;
+
+/// Create a [Matrix] from a slice of exactly `ROWS * COLS` elements in row-major order.
+///
+/// ```
+/// # use lina::m;
+/// # use lina::matrix::Matrix;
+/// let values = [1, 2, 3, 4, 5, 6];
+///
+/// assert_eq!(Matrix::<i32, 3, 2>::try_from(&values[..]), Ok(m![[1, 2, 3], [4, 5, 6]]));
+/// assert!(Matrix::<i32, 3, 3>::try_from(&values[..]).is_err());
+/// ```
+impl<ValueType, const COLS: usize, const ROWS: usize> TryFrom<&[ValueType]>
+    for Matrix<ValueType, COLS, ROWS>
+where
+    ValueType: Copy,
+{
+    type Error = LengthError;
+
+    fn try_from(values: &[ValueType]) -> Result<Self, Self::Error> {
+        if values.len() != COLS * ROWS {
+            return Err(LengthError {
+                expected: COLS * ROWS,
+                actual: values.len(),
+            });
+        }
+
+        Ok(Matrix {
+            data: std::array::from_fn(|i| std::array::from_fn(|j| values[i * COLS + j])),
+        })
+    }
+}
+
+impl<ValueType, const COLS: usize, const ROWS: usize> Matrix<ValueType, COLS, ROWS>
+where
+    ValueType: Copy,
+{
+    /// Collect exactly `ROWS * COLS` elements of an iterator into a [Matrix]
+    /// in row-major order.
+    ///
+    /// At most `ROWS * COLS + 1` elements are taken, so an iterator with
+    /// too many elements is reported with `ROWS * COLS + 1` as the actual
+    /// length.
+    pub fn try_from_iter<I>(iter: I) -> Result<Self, LengthError>
+    where
+        I: IntoIterator<Item = ValueType>,
+    {
+        let mut iter = iter.into_iter();
+        let mut rows = [None; ROWS];
+        for (i, row) in rows.iter_mut().enumerate() {
+            let values = try_fill(&mut iter).map_err(|taken| LengthError {
+                expected: COLS * ROWS,
+                actual: i * COLS + taken,
+            })?;
+            *row = Some(values);
+        }
+        if iter.next().is_some() {
+            return Err(LengthError {
+                expected: COLS * ROWS,
+                actual: COLS * ROWS + 1,
+            });
+        }
+
+        Ok(Matrix {
+            data: rows.map(Option::unwrap),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::LengthError;
+    use crate::m;
+    use crate::matrix::Matrix;
+
+    #[test]
+    fn try_from_slice() {
+        let values = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
+
+        assert_eq!(
+            Matrix::<f32, 2, 3>::try_from(&values[..]),
+            Ok(m![[1.0, 2.0], [3.0, 4.0], [5.0, 6.0]])
+        );
+        assert_eq!(
+            Matrix::<f32, 2, 2>::try_from(&values[..]),
+            Err(LengthError {
+                expected: 4,
+                actual: 6
+            })
+        );
+    }
+
+    #[test]
+    fn try_from_iter() {
+        assert_eq!(
+            Matrix::<i32, 2, 2>::try_from_iter([1, 2, 3, 4]),
+            Ok(m![[1, 2], [3, 4]])
+        );
+        assert_eq!(
+            Matrix::<i32, 2, 2>::try_from_iter(0..3),
+            Err(LengthError {
+                expected: 4,
+                actual: 3
+            })
+        );
+        assert_eq!(
+            Matrix::<i32, 3, 2>::try_from_iter(0..),
+            Err(LengthError {
+                expected: 6,
+                actual: 7
+            })
+        );
+    }
+}
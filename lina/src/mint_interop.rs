@@ -0,0 +1,158 @@
+//! Conversions from and to the [mint] types, enabled by the `mint` feature.
+//!
+//! mint only defines the types, so libraries built on different math crates
+//! can exchange values through it. The vectors convert component by
+//! component, points to vectors as well. The matrices convert to both the
+//! column and row major mint matrices, keeping the elements at the same row
+//! and column, with the column major one as their [IntoMint](mint::IntoMint)
+//! type.
+//!
+//! ```
+//! # use lina::{m, v};
+//! # use lina::matrix::Matrix;
+//! let matrix = m![[1, 2], [3, 4]];
+//!
+//! let columns = mint::ColumnMatrix2::from(matrix);
+//! assert_eq!(columns.x, mint::Vector2 { x: 1, y: 3 });
+//! let rows = mint::RowMatrix2::from(matrix);
+//! assert_eq!(rows.x, mint::Vector2 { x: 1, y: 2 });
+//! assert_eq!(Matrix::from(columns), Matrix::from(rows));
+//!
+//! let point: mint::Point3<f32> = v![1.0, 2.0, 3.0].into();
+//! assert_eq!(point.z, 3.0);
+//! ```
+
+use crate::matrix::Matrix;
+use crate::vector::Vector;
+
+macro_rules! impl_vector_conversions {
+    ($($N: literal => $Mint: ident $(, $Point: ident)?);* $(;)*) => {$(
+        impl<ValueType> From<Vector<ValueType, $N>> for mint::$Mint<ValueType> {
+            fn from(vector: Vector<ValueType, $N>) -> Self {
+                vector.data.into()
+            }
+        }
+
+        impl<ValueType> From<mint::$Mint<ValueType>> for Vector<ValueType, $N> {
+            fn from(vector: mint::$Mint<ValueType>) -> Self {
+                Vector::from_array(vector.into())
+            }
+        }
+
+        impl<ValueType> mint::IntoMint for Vector<ValueType, $N> {
+            type MintType = mint::$Mint<ValueType>;
+        }
+
+        $(
+            impl<ValueType> From<Vector<ValueType, $N>> for mint::$Point<ValueType> {
+                fn from(vector: Vector<ValueType, $N>) -> Self {
+                    vector.data.into()
+                }
+            }
+
+            impl<ValueType> From<mint::$Point<ValueType>> for Vector<ValueType, $N> {
+                fn from(point: mint::$Point<ValueType>) -> Self {
+                    Vector::from_array(point.into())
+                }
+            }
+        )?
+    )*};
+}
+
+impl_vector_conversions!(
+    2 => Vector2, Point2;
+    3 => Vector3, Point3;
+    4 => Vector4;
+);
+
+macro_rules! impl_matrix_conversions {
+    ($($N: literal => $Columns: ident, $Rows: ident);* $(;)*) => {$(
+        impl<ValueType: Copy> From<Matrix<ValueType, $N, $N>> for mint::$Columns<ValueType> {
+            fn from(matrix: Matrix<ValueType, $N, $N>) -> Self {
+                matrix.transpose().data.into()
+            }
+        }
+
+        impl<ValueType: Copy> From<mint::$Columns<ValueType>> for Matrix<ValueType, $N, $N> {
+            fn from(matrix: mint::$Columns<ValueType>) -> Self {
+                Matrix::from_matrix(matrix.into()).transpose()
+            }
+        }
+
+        impl<ValueType: Copy> mint::IntoMint for Matrix<ValueType, $N, $N> {
+            type MintType = mint::$Columns<ValueType>;
+        }
+
+        impl<ValueType> From<Matrix<ValueType, $N, $N>> for mint::$Rows<ValueType> {
+            fn from(matrix: Matrix<ValueType, $N, $N>) -> Self {
+                matrix.data.into()
+            }
+        }
+
+        impl<ValueType> From<mint::$Rows<ValueType>> for Matrix<ValueType, $N, $N> {
+            fn from(matrix: mint::$Rows<ValueType>) -> Self {
+                Matrix::from_matrix(matrix.into())
+            }
+        }
+    )*};
+}
+
+impl_matrix_conversions!(
+    2 => ColumnMatrix2, RowMatrix2;
+    3 => ColumnMatrix3, RowMatrix3;
+    4 => ColumnMatrix4, RowMatrix4;
+);
+
+#[cfg(test)]
+mod tests {
+    use crate::matrix::Matrix;
+    use crate::vector::Vector;
+    use crate::{m, v};
+
+    #[test]
+    fn vectors_round_trip() {
+        let vector = v![1.0, -2.0, 3.5];
+        let mint: mint::Vector3<f64> = vector.into();
+
+        assert_eq!(
+            mint,
+            mint::Vector3 {
+                x: 1.0,
+                y: -2.0,
+                z: 3.5
+            }
+        );
+        assert_eq!(Vector::from(mint), vector);
+        assert_eq!(
+            Vector::from(mint::Vector4::from([1, 2, 3, 4])),
+            v![1, 2, 3, 4]
+        );
+    }
+
+    #[test]
+    fn column_matrices_hold_the_columns() {
+        let translation = m![
+            [1.0, 0.0, 0.0, 5.0],
+            [0.0, 1.0, 0.0, 6.0],
+            [0.0, 0.0, 1.0, 7.0],
+            [0.0, 0.0, 0.0, 1.0]
+        ];
+        let columns = mint::ColumnMatrix4::from(translation);
+
+        assert_eq!(
+            columns.w,
+            mint::Vector4 {
+                x: 5.0,
+                y: 6.0,
+                z: 7.0,
+                w: 1.0
+            }
+        );
+        assert_eq!(<[f64; 16]>::from(columns), translation.to_cols_array());
+        assert_eq!(Matrix::from(columns), translation);
+        assert_eq!(
+            Matrix::from(mint::RowMatrix4::from(translation)),
+            translation
+        );
+    }
+}
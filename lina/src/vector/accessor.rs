@@ -12,9 +12,7 @@ where
         if LENGTH < 3 {
             None
         } else {
-            let ptr = self.data.as_ptr() as *const [ValueType; 3];
-            let data = unsafe { *ptr };
-            Some(Vector::from_array(data))
+            Some(Vector::from_array(std::array::from_fn(|i| self.data[i])))
         }
     }
 }
@@ -1,6 +1,5 @@
-use std::mem;
-
 use super::vector::Vector;
+use crate::array::zip_map;
 
 impl<ValueType, const LENGTH: usize> std::ops::Add<Vector<ValueType, LENGTH>>
     for Vector<ValueType, LENGTH>
@@ -11,16 +10,9 @@ where
 
     /// Implement `Vector<T> + Vector<T>` operation.
     fn add(self, rhs: Vector<ValueType, LENGTH>) -> Self::Output {
-        let mut data = [mem::MaybeUninit::<ValueType>::uninit(); LENGTH];
-
-        for (elem, (lhs, rhs)) in data.iter_mut().zip(self.data.iter().zip(rhs.data.iter())) {
-            elem.write(*lhs + *rhs);
+        Vector {
+            data: zip_map(self.data, rhs.data, |lhs, rhs| lhs + rhs),
         }
-
-        let ptr = &mut data as *mut _ as *mut [ValueType; LENGTH];
-        let transmuted = unsafe { ptr.read() };
-
-        Vector { data: transmuted }
     }
 }
 
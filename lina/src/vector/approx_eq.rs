@@ -0,0 +1,69 @@
+use float_eq::{FloatEq, FloatEqUlpsTol, UlpsTol};
+
+use crate::vector::Vector;
+
+impl<ValueType, const LENGTH: usize> Vector<ValueType, LENGTH>
+where
+    ValueType: FloatEq<Tol = ValueType> + FloatEqUlpsTol,
+    UlpsTol<ValueType>: Sized,
+{
+    /// Check if every component is within `epsilon` of the other.
+    ///
+    /// Floating point calculations rarely produce exact results, so
+    /// comparing them with `==` is mostly useless.
+    ///
+    /// ```
+    /// # use lina::v;
+    /// let v = v![0.1f64 + 0.2, 1.0];
+    ///
+    /// assert_ne!(v, v![0.3, 1.0]);
+    /// assert!(v.approx_eq(&v![0.3, 1.0], f64::EPSILON));
+    /// ```
+    pub fn approx_eq(&self, other: &Vector<ValueType, LENGTH>, epsilon: ValueType) -> bool {
+        self.data
+            .iter()
+            .zip(&other.data)
+            .all(|(lhs, rhs)| lhs.eq_abs(rhs, &epsilon))
+    }
+
+    /// Check if every component is at most `max_ulps` representable values away from the other.
+    ///
+    /// Unlike [approx_eq](Vector::approx_eq) the tolerance scales with the
+    /// magnitude of the values, but it is useless for values close to zero.
+    ///
+    /// ```
+    /// # use lina::v;
+    /// let v = v![0.1f64 + 0.2, 1000.0];
+    ///
+    /// assert!(v.ulps_eq(&v![0.3, 1000.0], 1));
+    /// ```
+    pub fn ulps_eq(&self, other: &Vector<ValueType, LENGTH>, max_ulps: UlpsTol<ValueType>) -> bool {
+        self.data
+            .iter()
+            .zip(&other.data)
+            .all(|(lhs, rhs)| lhs.eq_ulps(rhs, &max_ulps))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::v;
+
+    #[test]
+    fn approx_eq() {
+        let v = v![1.0, 2.0, 3.0];
+
+        assert!(v.approx_eq(&v![1.05, 1.95, 3.0], 0.1));
+        assert!(!v.approx_eq(&v![1.05, 1.95, 3.2], 0.1));
+        assert!(!v.approx_eq(&v![f64::NAN, 2.0, 3.0], 0.1));
+    }
+
+    #[test]
+    fn ulps_eq() {
+        let v = v![1.0f32, 2.0];
+        let next = v![f32::from_bits(1.0f32.to_bits() + 1), 2.0];
+
+        assert!(v.ulps_eq(&next, 1));
+        assert!(!v.ulps_eq(&next, 0));
+    }
+}
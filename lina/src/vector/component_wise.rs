@@ -0,0 +1,146 @@
+use std::ops::{Add, Mul, Neg, Sub};
+
+use crate::vector::Vector;
+
+impl<ValueType, const LENGTH: usize> Vector<ValueType, LENGTH>
+where
+    ValueType: Copy + PartialOrd,
+{
+    /// Take the smaller value of each component.
+    ///
+    /// ```
+    /// # use lina::v;
+    /// assert_eq!(v![1, 5, 3].min(v![4, 2, 3]), [1, 2, 3]);
+    /// ```
+    pub fn min(self, rhs: Vector<ValueType, LENGTH>) -> Vector<ValueType, LENGTH> {
+        Vector {
+            data: std::array::from_fn(|i| {
+                if rhs.data[i] < self.data[i] {
+                    rhs.data[i]
+                } else {
+                    self.data[i]
+                }
+            }),
+        }
+    }
+
+    /// Take the larger value of each component.
+    ///
+    /// ```
+    /// # use lina::v;
+    /// assert_eq!(v![1, 5, 3].max(v![4, 2, 3]), [4, 5, 3]);
+    /// ```
+    pub fn max(self, rhs: Vector<ValueType, LENGTH>) -> Vector<ValueType, LENGTH> {
+        Vector {
+            data: std::array::from_fn(|i| {
+                if rhs.data[i] > self.data[i] {
+                    rhs.data[i]
+                } else {
+                    self.data[i]
+                }
+            }),
+        }
+    }
+
+    /// Restrict each component between the corresponding components of `min` and `max`.
+    ///
+    /// `min` is expected to be less or equal to `max` in every component.
+    ///
+    /// ```
+    /// # use lina::v;
+    /// let clamped = v![-1.0, 0.5, 2.0].clamp(v![0.0, 0.0, 0.0], v![1.0, 1.0, 1.0]);
+    /// assert_eq!(clamped, [0.0, 0.5, 1.0]);
+    /// ```
+    pub fn clamp(
+        self,
+        min: Vector<ValueType, LENGTH>,
+        max: Vector<ValueType, LENGTH>,
+    ) -> Vector<ValueType, LENGTH> {
+        self.max(min).min(max)
+    }
+}
+
+impl<ValueType, const LENGTH: usize> Vector<ValueType, LENGTH>
+where
+    ValueType: Copy + Default + PartialOrd + Neg<Output = ValueType>,
+{
+    /// Take the absolute value of each component.
+    ///
+    /// ```
+    /// # use lina::v;
+    /// assert_eq!(v![-1, 2, -3].abs(), [1, 2, 3]);
+    /// ```
+    pub fn abs(self) -> Vector<ValueType, LENGTH> {
+        Vector {
+            data: self.data.map(|value| {
+                if value < ValueType::default() {
+                    -value
+                } else {
+                    value
+                }
+            }),
+        }
+    }
+}
+
+impl<ValueType, const LENGTH: usize> Vector<ValueType, LENGTH>
+where
+    ValueType: Copy + Add<Output = ValueType> + Sub<Output = ValueType> + Mul<Output = ValueType>,
+{
+    /// Linearly interpolate between `self` and `rhs`.
+    ///
+    /// `t = 0` results in `self`, `t = 1` in `rhs`.
+    /// `t` is not clamped, values outside `[0, 1]` extrapolate.
+    ///
+    /// ```
+    /// # use lina::v;
+    /// assert_eq!(v![0.0, 2.0].lerp(v![1.0, 4.0], 0.5), [0.5, 3.0]);
+    /// ```
+    pub fn lerp(self, rhs: Vector<ValueType, LENGTH>, t: ValueType) -> Vector<ValueType, LENGTH> {
+        Vector {
+            data: std::array::from_fn(|i| self.data[i] + (rhs.data[i] - self.data[i]) * t),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::v;
+
+    #[test]
+    fn min_max() {
+        let a = v![1.0, -2.0, 3.0, 0.0];
+        let b = v![0.5, 2.0, 3.5, -0.0];
+
+        assert_eq!(a.min(b), [0.5, -2.0, 3.0, 0.0]);
+        assert_eq!(a.max(b), [1.0, 2.0, 3.5, 0.0]);
+        // Order doesn't matter.
+        assert_eq!(a.min(b), b.min(a));
+        assert_eq!(a.max(b), b.max(a));
+    }
+
+    #[test]
+    fn clamp() {
+        let min = v![0, 0, 0];
+        let max = v![10, 10, 10];
+
+        assert_eq!(v![-5, 5, 15].clamp(min, max), [0, 5, 10]);
+    }
+
+    #[test]
+    fn abs() {
+        assert_eq!(v![-1.5, 0.0, 2.5].abs(), [1.5, 0.0, 2.5]);
+        assert_eq!(v![i32::MIN + 1, 0, 7].abs(), [i32::MAX, 0, 7]);
+    }
+
+    #[test]
+    fn lerp() {
+        let a = v![0.0, 10.0, -4.0];
+        let b = v![1.0, 20.0, 4.0];
+
+        assert_eq!(a.lerp(b, 0.0), a);
+        assert_eq!(a.lerp(b, 1.0), b);
+        assert_eq!(a.lerp(b, 0.25), [0.25, 12.5, -2.0]);
+        assert_eq!(a.lerp(b, 2.0), [2.0, 30.0, 12.0]);
+    }
+}
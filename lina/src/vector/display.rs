@@ -0,0 +1,45 @@
+use std::fmt;
+
+use crate::vector::Vector;
+
+impl<ValueType, const LENGTH: usize> fmt::Display for Vector<ValueType, LENGTH>
+where
+    ValueType: fmt::Display,
+{
+    /// Format the vector as `[x, y, z]`.
+    ///
+    /// The precision is applied to every component.
+    ///
+    /// ```
+    /// # use lina::v;
+    /// let v = v![1.0, 2.5, -3.0];
+    ///
+    /// assert_eq!(format!("{v}"), "[1, 2.5, -3]");
+    /// assert_eq!(format!("{v:.2}"), "[1.00, 2.50, -3.00]");
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[")?;
+        for (i, value) in self.data.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            match f.precision() {
+                Some(precision) => write!(f, "{value:.precision$}")?,
+                None => write!(f, "{value}")?,
+            }
+        }
+        write!(f, "]")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{v, vector::Vector};
+
+    #[test]
+    fn display() {
+        assert_eq!(v![1, 2, 3].to_string(), "[1, 2, 3]");
+        assert_eq!(format!("{:.1}", v![0.25f32, 1.0]), "[0.2, 1.0]");
+        assert_eq!(Vector::<i32, 0>::new().to_string(), "[]");
+    }
+}
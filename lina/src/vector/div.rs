@@ -1,5 +1,5 @@
 use super::Vector;
-use std::{mem, ops::Div};
+use std::ops::Div;
 
 impl<ValueType, const LENGTH: usize> Div<ValueType> for Vector<ValueType, LENGTH>
 where
@@ -9,16 +9,9 @@ where
 
     /// Implement `Vector<T> / T` operation.
     fn div(self, rhs: ValueType) -> Self::Output {
-        let mut data = [mem::MaybeUninit::<ValueType>::uninit(); LENGTH];
-
-        for (elem, lhs) in data.iter_mut().zip(self.data.iter()) {
-            elem.write(*lhs / rhs);
+        Vector {
+            data: self.data.map(|lhs| lhs / rhs),
         }
-
-        let ptr = &mut data as *mut _ as *mut [ValueType; LENGTH];
-        let transmuted = unsafe { ptr.read() };
-
-        Vector { data: transmuted }
     }
 }
 
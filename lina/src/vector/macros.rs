@@ -7,8 +7,8 @@
 //! compiler will enter a loop, trying to determine the bounds are satisfied.
 //! The issue does not appear if the macro invocations for both A and B appear
 //! in the same file.
-use crate::vector::Sqrt;
 use crate::vector::Vector;
+use crate::vector::{One, Sqrt, Zero};
 
 /// Generate a [Vector](crate::vector::Vector) similar to the [vec!] macro.
 ///
@@ -91,6 +91,23 @@ macro_rules! impl_sqrt_for_float_types {
 
 impl_sqrt_for_float_types!(f32, f64);
 
+// Implement for built in number types
+macro_rules! impl_zero_one_for_number_types {
+    ($($T: ty),* $(,)*) => {$(
+        impl Zero for $T {
+            const ZERO: Self = 0 as $T;
+        }
+
+        impl One for $T {
+            const ONE: Self = 1 as $T;
+        }
+    )*};
+}
+
+impl_zero_one_for_number_types!(
+    u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64
+);
+
 macro_rules! impl_neg_trait {
    ($($T: ty),* $(,)*) => {$(
         impl<const LENGTH: usize> std::ops::Neg for Vector<$T, LENGTH>
@@ -115,4 +132,10 @@ mod tests {
     fn negate() {
         assert_eq!(-v![1.0, 2.0, 3.0], v![-1.0, -2.0, -3.0]);
     }
+
+    #[test]
+    fn negate_integers() {
+        assert_eq!(-v![1, -2, 0], v![-1, 2, 0]);
+        assert_eq!(-(-v![4i8, 5, 6]), v![4, 5, 6]);
+    }
 }
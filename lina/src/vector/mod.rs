@@ -11,8 +11,11 @@
 mod accessor;
 mod add;
 mod add_assign;
+mod approx_eq;
+mod component_wise;
 mod cross;
 mod default;
+mod display;
 mod div;
 mod div_assign;
 mod index;
@@ -20,12 +23,19 @@ mod index_mut;
 mod macros;
 mod mul;
 mod mul_assign;
+#[cfg(feature = "bytemuck")]
+mod pod;
+#[cfg(feature = "rand")]
+mod random;
 mod sqrt;
 mod sub;
 mod sub_assign;
+mod try_from;
+mod zero_one;
 
 // Re-export to allow users their own implementations.
 pub use sqrt::Sqrt;
+pub use zero_one::{One, Zero};
 
 // In this case module inception is allowed, because [vector] symbols
 // will be re-exported. The goal is to keep the modules structure separate from the
@@ -1,5 +1,3 @@
-use std::mem;
-
 use super::Vector;
 
 impl<ValueType, const LENGTH: usize> std::ops::Mul<ValueType> for Vector<ValueType, LENGTH>
@@ -10,16 +8,9 @@ where
 
     /// Performs the `Vector<T> * T` operation
     fn mul(self, rhs: ValueType) -> Self::Output {
-        let mut data = [mem::MaybeUninit::<ValueType>::uninit(); LENGTH];
-
-        for (elem, lhs) in data.iter_mut().zip(self.data.iter()) {
-            elem.write(*lhs * rhs);
+        Vector {
+            data: self.data.map(|lhs| lhs * rhs),
         }
-
-        let ptr = &mut data as *mut _ as *mut [ValueType; LENGTH];
-        let transmuted = unsafe { ptr.read() };
-
-        Vector { data: transmuted }
     }
 }
 
@@ -0,0 +1,34 @@
+//! [bytemuck] support, enabled by the `bytemuck` feature.
+
+use bytemuck::{Pod, Zeroable};
+
+use crate::vector::Vector;
+
+// SAFETY: `Vector` is `repr(transparent)` over `[ValueType; LENGTH]`,
+// which is `Zeroable` and `Pod` if `ValueType` is.
+unsafe impl<ValueType: Zeroable, const LENGTH: usize> Zeroable for Vector<ValueType, LENGTH> {}
+unsafe impl<ValueType: Pod, const LENGTH: usize> Pod for Vector<ValueType, LENGTH> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::{v, vector::Vector};
+
+    #[test]
+    fn cast_to_bytes() {
+        let v: Vector<f32, 2> = v![1.0, -2.0];
+        let bytes = [1.0f32.to_ne_bytes(), (-2.0f32).to_ne_bytes()].concat();
+
+        assert_eq!(bytemuck::bytes_of(&v), bytes);
+    }
+
+    #[test]
+    fn cast_slice() {
+        let vectors = [v![1u32, 2, 3], v![4, 5, 6]];
+
+        assert_eq!(bytemuck::cast_slice::<_, u32>(&vectors), [1, 2, 3, 4, 5, 6]);
+        assert_eq!(
+            bytemuck::cast::<[u32; 3], Vector<u32, 3>>([7, 8, 9]),
+            v![7, 8, 9]
+        );
+    }
+}
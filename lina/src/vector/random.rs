@@ -0,0 +1,98 @@
+//! Random vectors with [rand], enabled by the `rand` feature.
+
+use rand::Rng;
+
+use crate::vector::Vector;
+
+macro_rules! impl_random_for_float_types {
+    ($($T: ty),* $(,)*) => {$(
+        impl Vector<$T, 3> {
+            /// A random direction, uniformly distributed on the unit sphere.
+            ///
+            /// The height is uniform between the poles, as the surface of a
+            /// sphere slice only depends on its thickness (Archimedes'
+            /// hat-box theorem), then the direction around the pole is picked.
+            ///
+            /// ```
+            /// # use lina::vector::Vector;
+            /// # use rand::{SeedableRng, rngs::SmallRng};
+            /// let mut rng = SmallRng::seed_from_u64(1);
+            /// let direction = Vector::<f32, 3>::random_unit(&mut rng);
+            ///
+            /// assert!((direction.length() - 1.0).abs() < 1e-6);
+            /// ```
+            pub fn random_unit<R: Rng + ?Sized>(rng: &mut R) -> Vector<$T, 3> {
+                let z: $T = rng.random_range(-1.0..=1.0);
+                let angle: $T = rng.random_range(0.0..std::f64::consts::TAU as $T);
+                let radius = (1.0 - z * z).max(0.0).sqrt();
+                Vector::from_array([radius * angle.cos(), radius * angle.sin(), z])
+            }
+        }
+
+        impl<const LENGTH: usize> Vector<$T, LENGTH> {
+            /// A random point inside the axis aligned box from `min` to `max`,
+            /// uniformly distributed.
+            ///
+            /// ```
+            /// # use lina::v;
+            /// # use lina::vector::Vector;
+            /// # use rand::{SeedableRng, rngs::SmallRng};
+            /// let mut rng = SmallRng::seed_from_u64(1);
+            /// let point = Vector::<f32, 2>::random_in_box(&mut rng, v![-1.0, 0.0], v![1.0, 4.0]);
+            ///
+            /// assert!((-1.0..=1.0).contains(&point[0]));
+            /// assert!((0.0..=4.0).contains(&point[1]));
+            /// ```
+            pub fn random_in_box<R: Rng + ?Sized>(
+                rng: &mut R,
+                min: Vector<$T, LENGTH>,
+                max: Vector<$T, LENGTH>,
+            ) -> Vector<$T, LENGTH> {
+                Vector::from_array(std::array::from_fn(|i| {
+                    min[i] + (max[i] - min[i]) * rng.random::<$T>()
+                }))
+            }
+        }
+    )*};
+}
+
+impl_random_for_float_types!(f32, f64);
+
+#[cfg(test)]
+mod tests {
+    use rand::{SeedableRng, rngs::SmallRng};
+
+    use crate::{v, vector::Vector};
+
+    #[test]
+    fn random_units_cover_the_sphere() {
+        let mut rng = SmallRng::seed_from_u64(7);
+        let count = 10_000;
+        let mut sum = Vector::<f64, 3>::from_array([0.0; 3]);
+        let mut upper = 0;
+        for _ in 0..count {
+            let direction = Vector::<f64, 3>::random_unit(&mut rng);
+            assert!((direction.length() - 1.0).abs() < 1e-12);
+            sum += direction;
+            upper += usize::from(direction[1] > 0.0);
+        }
+        // Not biased towards any direction.
+        assert!((sum / count as f64).length() < 0.05);
+        assert!((upper as f64 / count as f64 - 0.5).abs() < 0.02);
+    }
+
+    #[test]
+    fn random_points_stay_in_the_box() {
+        let mut rng = SmallRng::seed_from_u64(7);
+        let (min, max) = (v![-2.0f32, 0.0, 10.0], v![2.0, 0.5, 10.0]);
+        let mut sum = 0.0f32;
+        for _ in 0..1000 {
+            let point = Vector::<f32, 3>::random_in_box(&mut rng, min, max);
+            for i in 0..3 {
+                assert!(min[i] <= point[i] && point[i] <= max[i]);
+            }
+            sum += point[0];
+        }
+        assert!((sum / 1000.0).abs() < 0.2);
+    }
+}
@@ -0,0 +1,128 @@
+use crate::LengthError;
+use crate::array::try_fill;
+use crate::vector::Vector;
+
+/// Create a [Vector] from a slice of exactly `LENGTH` elements.
+///
+/// ```
+/// # use lina::v;
+/// # use lina::vector::Vector;
+/// # use lina::LengthError;
+/// let values = [1.0, 2.0, 3.0];
+///
+/// assert_eq!(Vector::<f32, 3>::try_from(&values[..]), Ok(v![1.0, 2.0, 3.0]));
+/// assert_eq!(
+///     Vector::<f32, 3>::try_from(&values[..2]),
+///     Err(LengthError { expected: 3, actual: 2 })
+/// );
+/// ```
+impl<ValueType, const LENGTH: usize> TryFrom<&[ValueType]> for Vector<ValueType, LENGTH>
+where
+    ValueType: Copy,
+{
+    type Error = LengthError;
+
+    fn try_from(values: &[ValueType]) -> Result<Self, Self::Error> {
+        let data = values.try_into().map_err(|_| LengthError {
+            expected: LENGTH,
+            actual: values.len(),
+        })?;
+
+        Ok(Vector { data })
+    }
+}
+
+impl<ValueType, const LENGTH: usize> Vector<ValueType, LENGTH>
+where
+    ValueType: Copy,
+{
+    /// Collect exactly `LENGTH` elements of an iterator into a [Vector].
+    ///
+    /// At most `LENGTH + 1` elements are taken, so an iterator with too
+    /// many elements is reported with `LENGTH + 1` as the actual length.
+    ///
+    /// ```
+    /// # use lina::v;
+    /// # use lina::vector::Vector;
+    /// let line = "1.5 -2 0.25";
+    /// let parsed = line.split_whitespace().map(|s| s.parse::<f32>().unwrap());
+    ///
+    /// assert_eq!(Vector::<f32, 3>::try_from_iter(parsed), Ok(v![1.5, -2.0, 0.25]));
+    /// ```
+    pub fn try_from_iter<I>(iter: I) -> Result<Self, LengthError>
+    where
+        I: IntoIterator<Item = ValueType>,
+    {
+        let mut iter = iter.into_iter();
+        let data = try_fill(&mut iter).map_err(|actual| LengthError {
+            expected: LENGTH,
+            actual,
+        })?;
+        if iter.next().is_some() {
+            return Err(LengthError {
+                expected: LENGTH,
+                actual: LENGTH + 1,
+            });
+        }
+
+        Ok(Vector { data })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::LengthError;
+    use crate::v;
+    use crate::vector::Vector;
+
+    #[test]
+    fn try_from_slice() {
+        let values = [1, 2, 3, 4];
+
+        assert_eq!(Vector::<i32, 4>::try_from(&values[..]), Ok(v![1, 2, 3, 4]));
+        assert_eq!(
+            Vector::<i32, 2>::try_from(&values[..]),
+            Err(LengthError {
+                expected: 2,
+                actual: 4
+            })
+        );
+        assert_eq!(
+            Vector::<i32, 2>::try_from(&values[..0]),
+            Err(LengthError {
+                expected: 2,
+                actual: 0
+            })
+        );
+    }
+
+    #[test]
+    fn try_from_iter() {
+        assert_eq!(Vector::<u32, 3>::try_from_iter(1..4), Ok(v![1, 2, 3]));
+        assert_eq!(
+            Vector::<u32, 3>::try_from_iter(1..10),
+            Err(LengthError {
+                expected: 3,
+                actual: 4
+            })
+        );
+        assert_eq!(
+            Vector::<u32, 3>::try_from_iter(1..3),
+            Err(LengthError {
+                expected: 3,
+                actual: 2
+            })
+        );
+    }
+
+    #[test]
+    fn try_from_iter_stops_on_endless_iterators() {
+        assert_eq!(
+            Vector::<u32, 2>::try_from_iter(std::iter::repeat(7)),
+            Err(LengthError {
+                expected: 2,
+                actual: 3
+            })
+        );
+    }
+}
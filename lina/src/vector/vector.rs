@@ -13,7 +13,23 @@ use crate::vector::sqrt::Sqrt;
 /// and uninitialized memory allocation.
 /// Otherwise [Vector] does not impose other requirements
 /// only those that are necessary for each trait implementation.
-#[derive(Debug, PartialEq, Clone, Copy)]
+///
+/// The memory layout is guaranteed to be the same as `[ValueType; LENGTH]`.
+///
+/// Vectors of integers are [Eq], [Hash] and [Ord], so they can key maps and
+/// sets. They are ordered lexicographically, by their first component, then
+/// by the second one and so on:
+/// ```
+/// # use lina::v;
+/// # use std::collections::{BTreeSet, HashSet};
+/// let coords = [v![1, 0, 0], v![0, 2, 0], v![0, 1, 5], v![0, 2, 0]];
+///
+/// assert_eq!(HashSet::from(coords).len(), 3);
+/// let sorted: Vec<_> = BTreeSet::from(coords).into_iter().collect();
+/// assert_eq!(sorted, [v![0, 1, 5], v![0, 2, 0], v![1, 0, 0]]);
+/// ```
+#[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Clone, Copy)]
+#[repr(transparent)]
 pub struct Vector<ValueType, const LENGTH: usize> {
     pub(crate) data: [ValueType; LENGTH],
 }
@@ -54,6 +70,21 @@ where
             data: [default_value; LENGTH],
         }
     }
+
+    /// Create a new [Vector] by applying `f` to every element, e.g. to
+    /// convert between value types.
+    ///
+    /// Example
+    /// ```
+    /// # use lina::v;
+    /// let v = v![1.0_f64, 2.0, 3.0];
+    /// assert_eq!(v.map(|value| value as f32), v![1.0_f32, 2.0, 3.0]);
+    /// ```
+    pub fn map<Output>(&self, f: impl FnMut(ValueType) -> Output) -> Vector<Output, LENGTH> {
+        Vector {
+            data: self.data.map(f),
+        }
+    }
 }
 
 impl<ValueType, const LENGTH: usize> Vector<ValueType, LENGTH> {
@@ -63,9 +94,27 @@ impl<ValueType, const LENGTH: usize> Vector<ValueType, LENGTH> {
     }
 
     /// Construct a [Vector] from the given slice
-    pub fn from_array(values: [ValueType; LENGTH]) -> Vector<ValueType, LENGTH> {
+    ///
+    /// Usable in constants, like the [v](crate::v) macro with a list of
+    /// elements:
+    /// ```
+    /// # use lina::{v, vector::Vector};
+    /// const UP: Vector<f32, 3> = v![0.0, 1.0, 0.0];
+    /// const DOWN: Vector<f32, 3> = Vector::from_array([0.0, -1.0, 0.0]);
+    /// ```
+    pub const fn from_array(values: [ValueType; LENGTH]) -> Vector<ValueType, LENGTH> {
         Self { data: values }
     }
+
+    /// The components as an array, the inverse of [from_array](Vector::from_array).
+    ///
+    /// ```
+    /// # use lina::v;
+    /// assert_eq!(v![1, 2, 3].into_array(), [1, 2, 3]);
+    /// ```
+    pub fn into_array(self) -> [ValueType; LENGTH] {
+        self.data
+    }
 }
 
 impl<ValueType, const LENGTH: usize> PartialEq<[ValueType; LENGTH]> for Vector<ValueType, LENGTH>
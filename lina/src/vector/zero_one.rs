@@ -0,0 +1,13 @@
+/// The additive identity of a value type.
+///
+/// The standard library has no trait for it. Converting from a literal with
+/// `From<i8>` or `From<u8>` leaves out the unsigned or the smallest signed
+/// type, so generic code relying on it couldn't serve every integer type.
+pub trait Zero {
+    const ZERO: Self;
+}
+
+/// The multiplicative identity of a value type, see [Zero].
+pub trait One {
+    const ONE: Self;
+}
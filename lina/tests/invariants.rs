@@ -0,0 +1,90 @@
+//! Algebraic invariants checked on random inputs, so optimizations of the
+//! math can't silently break it.
+
+use lina::{matrix::Matrix, vector::Vector};
+use proptest::{array, prelude::*};
+
+fn vector<const N: usize>(range: std::ops::Range<f64>) -> impl Strategy<Value = Vector<f64, N>> {
+    array::uniform(range).prop_map(Vector::from_array)
+}
+
+fn matrix<const N: usize>(range: std::ops::Range<f64>) -> impl Strategy<Value = Matrix<f64, N, N>> {
+    array::uniform(array::uniform(range)).prop_map(Matrix::from_matrix)
+}
+
+/// Matrices far from singular, the identity scaled up with small values
+/// added, so their inverse can be expected to be accurate.
+fn invertible<const N: usize>() -> impl Strategy<Value = Matrix<f64, N, N>> {
+    matrix(-1.0..1.0).prop_map(|m| m + Matrix::identity() * (N as f64 + 1.0))
+}
+
+proptest! {
+    #[test]
+    fn matrix_multiplication_is_associative(
+        a in matrix::<4>(-10.0..10.0),
+        b in matrix::<4>(-10.0..10.0),
+        c in matrix::<4>(-10.0..10.0),
+    ) {
+        prop_assert!(((a * b) * c).approx_eq(&(a * (b * c)), 1e-9));
+    }
+
+    #[test]
+    fn matrix_multiplication_distributes_over_addition(
+        a in matrix::<3>(-10.0..10.0),
+        b in matrix::<3>(-10.0..10.0),
+        c in matrix::<3>(-10.0..10.0),
+    ) {
+        prop_assert!((a * (b + c)).approx_eq(&(a * b + a * c), 1e-10));
+    }
+
+    #[test]
+    fn transposing_reverses_products(a in matrix::<4>(-10.0..10.0), b in matrix::<4>(-10.0..10.0)) {
+        prop_assert_eq!(a.transpose().transpose(), a);
+        prop_assert!((a * b).transpose().approx_eq(&(b.transpose() * a.transpose()), 1e-10));
+    }
+
+    #[test]
+    fn inverses_multiply_to_identity(
+        m2 in invertible::<2>(),
+        m3 in invertible::<3>(),
+        m4 in invertible::<4>(),
+    ) {
+        prop_assert!((m2 * m2.inverse().unwrap()).approx_eq(&Matrix::identity(), 1e-12));
+        prop_assert!((m3 * m3.inverse().unwrap()).approx_eq(&Matrix::identity(), 1e-12));
+        prop_assert!((m4 * m4.inverse().unwrap()).approx_eq(&Matrix::identity(), 1e-12));
+        prop_assert!((m4.inverse().unwrap() * m4).approx_eq(&Matrix::identity(), 1e-12));
+    }
+
+    #[test]
+    fn determinants_multiply(a in matrix::<3>(-2.0..2.0), b in matrix::<3>(-2.0..2.0)) {
+        let product = (a * b).determinant();
+        prop_assert!((product - a.determinant() * b.determinant()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn normalized_vectors_have_unit_length(v in vector::<3>(-1e3..1e3)) {
+        prop_assume!(v.length() > 1e-3);
+        prop_assert!((v.normalized().length() - 1.0).abs() < 1e-12);
+
+        let v = Vector::<f32, 3>::from_array(std::array::from_fn(|i| v[i] as f32));
+        prop_assert!((v.normalized().length() - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn cross_products_are_orthogonal(a in vector::<3>(-10.0..10.0), b in vector::<3>(-10.0..10.0)) {
+        let cross = a.cross(b);
+        let scale = a.length() * b.length() * cross.length();
+        prop_assert!((cross * a).abs() <= 1e-12 * scale.max(1.0));
+        prop_assert!((cross * b).abs() <= 1e-12 * scale.max(1.0));
+        prop_assert!((cross + b.cross(a)).length() <= 1e-12);
+    }
+
+    #[test]
+    fn matrices_transform_vectors_linearly(
+        m in matrix::<4>(-10.0..10.0),
+        a in vector::<4>(-10.0..10.0),
+        b in vector::<4>(-10.0..10.0),
+    ) {
+        prop_assert!((m * (a + b)).approx_eq(&(m * a + m * b), 1e-10));
+    }
+}
@@ -0,0 +1,77 @@
+use criterion::{Criterion, criterion_group, criterion_main};
+use path::{Grid, find_path, smooth};
+use std::hint::black_box;
+
+/// Walls every 8 columns with a gap alternating between the top and the
+/// bottom, so the path winds through the whole grid.
+fn serpentine(size: usize) -> Grid {
+    let mut grid = Grid::new(size, size);
+    let size = size as i32;
+    for (wall, x) in (4..size).step_by(8).enumerate() {
+        let gap = if wall % 2 == 0 { size - 1 } else { 0 };
+        for y in (0..size).filter(|y| *y != gap) {
+            grid.set_blocked([x, y]);
+        }
+    }
+    grid
+}
+
+/// Scattered blocked and expensive cells, the same ones every run.
+fn rough(size: usize) -> Grid {
+    let mut grid = Grid::new(size, size);
+    let mut state: u32 = 1;
+    for y in 0..size as i32 {
+        for x in 0..size as i32 {
+            state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            match state >> 28 {
+                0 | 1 => grid.set_blocked([x, y]),
+                2..=4 => grid.set_cost([x, y], 3.0),
+                _ => {}
+            }
+        }
+    }
+    grid.set_cost([0, 0], 1.0);
+    let corner = size as i32 - 1;
+    grid.set_cost([corner, corner], 1.0);
+    grid
+}
+
+fn find_paths(c: &mut Criterion) {
+    let open = Grid::new(256, 256);
+    c.bench_function("find_path open 256x256", |b| {
+        b.iter(|| find_path(&open, black_box([0, 0]), black_box([255, 200])))
+    });
+
+    let rough = rough(256);
+    c.bench_function("find_path rough 256x256", |b| {
+        b.iter(|| find_path(&rough, black_box([0, 0]), black_box([255, 255])))
+    });
+
+    let maze = serpentine(256);
+    c.bench_function("find_path serpentine 256x256", |b| {
+        b.iter(|| find_path(&maze, black_box([0, 0]), black_box([255, 255])))
+    });
+    let path = find_path(&maze, [0, 0], [255, 255]).expect("the serpentine is connected");
+    c.bench_function("smooth serpentine 256x256", |b| {
+        b.iter(|| smooth(&maze, black_box(&path)))
+    });
+}
+
+fn find_paths_large(c: &mut Criterion) {
+    let mut group = c.benchmark_group("large");
+    // A single search takes long enough to be measured in few samples.
+    group.sample_size(10);
+
+    let open = Grid::new(1024, 1024);
+    group.bench_function("find_path open 1024x1024", |b| {
+        b.iter(|| find_path(&open, black_box([0, 0]), black_box([1023, 800])))
+    });
+    let maze = serpentine(1024);
+    group.bench_function("find_path serpentine 1024x1024", |b| {
+        b.iter(|| find_path(&maze, black_box([0, 0]), black_box([1023, 1023])))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, find_paths, find_paths_large);
+criterion_main!(benches);
@@ -0,0 +1,436 @@
+//! Pathfinding on a navigation grid.
+//!
+//! The walkable area is described by a [Grid] of square cells, each either
+//! blocked or costing some amount to move through. [find_path] searches the
+//! cheapest path between two cells with A*, stepping to any of the eight
+//! neighbors of a cell but never cutting the corner of a blocked one.
+//! [smooth] then drops the waypoints which can be skipped by walking in a
+//! straight line.
+//!
+//! The search is deterministic: among equally cheap paths the same one is
+//! found every time, regardless of how the grid was built.
+//!
+//! ```
+//! # use path::{Grid, find_path, smooth};
+//! let mut grid = Grid::new(5, 5);
+//! // A wall with a gap at the top.
+//! for y in 0..4 {
+//!     grid.set_blocked([2, y]);
+//! }
+//!
+//! let path = find_path(&grid, [0, 0], [4, 0]).unwrap();
+//! assert!(path.contains(&[2, 4]));
+//! assert_eq!(smooth(&grid, &path), [[0, 0], [1, 4], [3, 4], [4, 0]]);
+//! ```
+use std::{cmp::Ordering, collections::BinaryHeap};
+
+/// The position of a cell in a [Grid], X first.
+pub type Cell = [i32; 2];
+
+// The search adds up costs in fixed point, so that paths of the same cost
+// really tie rather than differ by rounding errors. These are the lengths
+// of a straight and a diagonal step.
+const STRAIGHT: u64 = 1024;
+const DIAGONAL: u64 = 1448;
+
+/// The offsets of the neighbors of a cell with the length of the step,
+/// in the order they are visited.
+const NEIGHBORS: [(Cell, u64); 8] = [
+    ([1, 0], STRAIGHT),
+    ([0, 1], STRAIGHT),
+    ([-1, 0], STRAIGHT),
+    ([0, -1], STRAIGHT),
+    ([1, 1], DIAGONAL),
+    ([-1, 1], DIAGONAL),
+    ([-1, -1], DIAGONAL),
+    ([1, -1], DIAGONAL),
+];
+
+/// A rectangle of cells, with the cost of moving through each.
+///
+/// Moving one cell wide through a cell costs its cost, diagonally `√2`
+/// times as much. Cells outside of the grid are blocked.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Grid {
+    width: i32,
+    height: i32,
+    // Row by row, infinite for the blocked cells.
+    costs: Vec<f32>,
+}
+
+impl Grid {
+    /// A `width` by `height` grid, every cell costing 1.
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width: i32::try_from(width).expect("the grid is too wide"),
+            height: i32::try_from(height).expect("the grid is too high"),
+            costs: vec![1.0; width * height],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width as usize
+    }
+
+    pub fn height(&self) -> usize {
+        self.height as usize
+    }
+
+    pub fn contains(&self, cell: Cell) -> bool {
+        (0..self.width).contains(&cell[0]) && (0..self.height).contains(&cell[1])
+    }
+
+    /// The cost of moving through `cell`, `None` if it is blocked.
+    pub fn cost(&self, cell: Cell) -> Option<f32> {
+        if !self.contains(cell) {
+            return None;
+        }
+        let cost = self.costs[self.index(cell)];
+        cost.is_finite().then_some(cost)
+    }
+
+    pub fn is_walkable(&self, cell: Cell) -> bool {
+        self.cost(cell).is_some()
+    }
+
+    /// Make moving through `cell` cost `cost`, e.g. more than 1 for rough
+    /// terrain which is better walked around.
+    ///
+    /// # Panics
+    ///
+    /// If `cell` is outside of the grid, or `cost` is less than 1, which
+    /// would make the search miss the cheapest paths.
+    pub fn set_cost(&mut self, cell: Cell, cost: f32) {
+        assert!(cost >= 1.0, "cells cost at least 1, got {cost}");
+        self.set(cell, cost);
+    }
+
+    /// Make `cell` impossible to move through.
+    ///
+    /// # Panics
+    ///
+    /// If `cell` is outside of the grid.
+    pub fn set_blocked(&mut self, cell: Cell) {
+        self.set(cell, f32::INFINITY);
+    }
+
+    fn set(&mut self, cell: Cell, cost: f32) {
+        assert!(self.contains(cell), "{cell:?} is outside of the grid");
+        let index = self.index(cell);
+        self.costs[index] = cost;
+    }
+
+    fn index(&self, cell: Cell) -> usize {
+        (cell[1] * self.width + cell[0]) as usize
+    }
+}
+
+/// A cell waiting to be expanded by [find_path].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Open {
+    // The cost so far plus the estimate of the rest.
+    estimate: u64,
+    // The estimate of the rest alone.
+    remaining: u64,
+    cell: Cell,
+}
+
+/// Ordered so that [BinaryHeap] pops the lowest estimate first. Ties go
+/// to the cell closer to the goal, then to the lower cell, which keeps
+/// the search deterministic.
+impl Ord for Open {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (other.estimate, other.remaining, other.cell).cmp(&(
+            self.estimate,
+            self.remaining,
+            self.cell,
+        ))
+    }
+}
+
+impl PartialOrd for Open {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// The cheapest path from `start` to `goal` on `grid`, with every cell
+/// along it from `start` to `goal`.
+///
+/// `None` if either of them is blocked or there is no path between them.
+pub fn find_path(grid: &Grid, start: Cell, goal: Cell) -> Option<Vec<Cell>> {
+    if !grid.is_walkable(start) || !grid.is_walkable(goal) {
+        return None;
+    }
+
+    let mut costs = vec![u64::MAX; grid.costs.len()];
+    let mut previous: Vec<Option<Cell>> = vec![None; grid.costs.len()];
+    let mut closed = vec![false; grid.costs.len()];
+    let mut open = BinaryHeap::new();
+
+    costs[grid.index(start)] = 0;
+    let remaining = heuristic(start, goal);
+    open.push(Open {
+        estimate: remaining,
+        remaining,
+        cell: start,
+    });
+
+    while let Some(Open { cell, .. }) = open.pop() {
+        let index = grid.index(cell);
+        // Cells are pushed again when a cheaper way to them is found.
+        if closed[index] {
+            continue;
+        }
+        closed[index] = true;
+        if cell == goal {
+            let mut path = vec![goal];
+            while let Some(cell) = previous[grid.index(path[path.len() - 1])] {
+                path.push(cell);
+            }
+            path.reverse();
+            return Some(path);
+        }
+
+        for ([x, y], length) in NEIGHBORS {
+            let next = [cell[0] + x, cell[1] + y];
+            let Some(cost) = grid.cost(next) else {
+                continue;
+            };
+            // Diagonal steps need both cells beside them to be free.
+            if x != 0
+                && y != 0
+                && !(grid.is_walkable([next[0], cell[1]]) && grid.is_walkable([cell[0], next[1]]))
+            {
+                continue;
+            }
+
+            let next_index = grid.index(next);
+            let cost = costs[index] + (length as f32 * cost).round() as u64;
+            if !closed[next_index] && cost < costs[next_index] {
+                costs[next_index] = cost;
+                previous[next_index] = Some(cell);
+                let remaining = heuristic(next, goal);
+                open.push(Open {
+                    estimate: cost + remaining,
+                    remaining,
+                    cell: next,
+                });
+            }
+        }
+    }
+    None
+}
+
+/// The cost from `from` to `to` if every cell on the way cost 1, which
+/// never overestimates the real cost.
+fn heuristic(from: Cell, to: Cell) -> u64 {
+    let dx = u64::from((from[0] - to[0]).unsigned_abs());
+    let dy = u64::from((from[1] - to[1]).unsigned_abs());
+    STRAIGHT * dx.max(dy) + (DIAGONAL - STRAIGHT) * dx.min(dy)
+}
+
+/// `path` without the waypoints which can be skipped by walking straight
+/// from the waypoint before to the one after. The first and the last
+/// cell are kept.
+///
+/// A straight line is only taken if every cell it crosses is walkable,
+/// without cutting corners, and none costs more than the most expensive
+/// cell of the part of the path it replaces.
+pub fn smooth(grid: &Grid, path: &[Cell]) -> Vec<Cell> {
+    let Some(&first) = path.first() else {
+        return Vec::new();
+    };
+
+    let mut smoothed = vec![first];
+    let mut anchor = 0;
+    while anchor + 1 < path.len() {
+        // Walk on to the farthest waypoint still in a straight line.
+        let mut next = anchor + 1;
+        let mut highest = cell_cost(grid, path[anchor]).max(cell_cost(grid, path[next]));
+        while let Some(&candidate) = path.get(next + 1) {
+            let highest_with = highest.max(cell_cost(grid, candidate));
+            if !in_line(grid, path[anchor], candidate, highest_with) {
+                break;
+            }
+            highest = highest_with;
+            next += 1;
+        }
+        smoothed.push(path[next]);
+        anchor = next;
+    }
+    smoothed
+}
+
+fn cell_cost(grid: &Grid, cell: Cell) -> f32 {
+    grid.cost(cell).unwrap_or(f32::INFINITY)
+}
+
+/// Whether every cell the line between the centers of `from` and `to`
+/// crosses costs at most `highest`.
+fn in_line(grid: &Grid, from: Cell, to: Cell, highest: f32) -> bool {
+    let passable = |cell: Cell| grid.cost(cell).is_some_and(|cost| cost <= highest);
+
+    let (dx, dy) = ((to[0] - from[0]).abs(), (to[1] - from[1]).abs());
+    let step = [(to[0] - from[0]).signum(), (to[1] - from[1]).signum()];
+    let mut cell = from;
+    let (mut x, mut y) = (0, 0);
+    while x < dx || y < dy {
+        // Which border of the cell the line leaves through, compared
+        // without divisions.
+        match ((1 + 2 * x) * dy).cmp(&((1 + 2 * y) * dx)) {
+            Ordering::Equal => {
+                // Exactly through a corner, touching both cells beside it.
+                if !passable([cell[0] + step[0], cell[1]])
+                    || !passable([cell[0], cell[1] + step[1]])
+                {
+                    return false;
+                }
+                cell = [cell[0] + step[0], cell[1] + step[1]];
+                x += 1;
+                y += 1;
+            }
+            Ordering::Less => {
+                cell[0] += step[0];
+                x += 1;
+            }
+            Ordering::Greater => {
+                cell[1] += step[1];
+                y += 1;
+            }
+        }
+        if !passable(cell) {
+            return false;
+        }
+    }
+    passable(from)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::SQRT_2;
+
+    use super::*;
+
+    /// The total cost of walking along `path` on `grid`.
+    fn path_cost(grid: &Grid, path: &[Cell]) -> f32 {
+        path.windows(2)
+            .map(|step| {
+                let diagonal = step[0][0] != step[1][0] && step[0][1] != step[1][1];
+                let length = if diagonal { SQRT_2 } else { 1.0 };
+                length * grid.cost(step[1]).unwrap()
+            })
+            .sum()
+    }
+
+    #[test]
+    fn straight_and_diagonal_paths() {
+        let grid = Grid::new(8, 8);
+        assert_eq!(find_path(&grid, [2, 3], [2, 3]), Some(vec![[2, 3]]));
+        assert_eq!(
+            find_path(&grid, [0, 0], [3, 0]),
+            Some(vec![[0, 0], [1, 0], [2, 0], [3, 0]])
+        );
+        assert_eq!(
+            find_path(&grid, [0, 0], [3, 3]),
+            Some(vec![[0, 0], [1, 1], [2, 2], [3, 3]])
+        );
+    }
+
+    #[test]
+    fn goes_around_walls_without_cutting_corners() {
+        let mut grid = Grid::new(6, 6);
+        for y in 0..5 {
+            grid.set_blocked([3, y]);
+        }
+
+        let path = find_path(&grid, [1, 1], [5, 0]).unwrap();
+        assert!(path.iter().all(|cell| grid.is_walkable(*cell)));
+        assert!(path.contains(&[3, 5]));
+        // Straight past the end of the wall, not diagonally around it.
+        assert!(path.contains(&[2, 5]) && path.contains(&[4, 5]));
+        assert!((path_cost(&grid, &path) - (9.0 + 2.0 * SQRT_2)).abs() < 1e-4);
+    }
+
+    #[test]
+    fn no_path_to_enclosed_or_blocked_cells() {
+        let mut grid = Grid::new(5, 5);
+        for cell in [[1, 0], [1, 1], [0, 1]] {
+            grid.set_blocked(cell);
+        }
+        assert_eq!(find_path(&grid, [0, 0], [4, 4]), None);
+        assert_eq!(find_path(&grid, [4, 4], [1, 1]), None);
+        assert_eq!(find_path(&grid, [4, 4], [5, 4]), None);
+    }
+
+    #[test]
+    fn expensive_cells_are_avoided() {
+        let mut grid = Grid::new(7, 3);
+        for x in 1..6 {
+            grid.set_cost([x, 1], 3.0);
+        }
+        let path = find_path(&grid, [0, 1], [6, 1]).unwrap();
+        assert!(
+            path.iter()
+                .all(|cell| cell[1] != 1 || cell[0] == 0 || cell[0] == 6)
+        );
+        assert!(path_cost(&grid, &path) < 6.0 * 3.0);
+    }
+
+    #[test]
+    fn ties_are_broken_the_same_way() {
+        // Both ways around the block cost the same.
+        let mut grid = Grid::new(3, 3);
+        grid.set_blocked([1, 1]);
+        let path = find_path(&grid, [0, 1], [2, 1]).unwrap();
+        assert_eq!(path, [[0, 1], [0, 0], [1, 0], [2, 0], [2, 1]]);
+
+        // Even when built differently.
+        let mut other = Grid::new(3, 3);
+        other.set_cost([1, 1], 2.0);
+        other.set_blocked([1, 1]);
+        assert_eq!(find_path(&other, [0, 1], [2, 1]).unwrap(), path);
+    }
+
+    #[test]
+    fn smoothing_keeps_the_corners() {
+        let grid = Grid::new(10, 10);
+        let path = find_path(&grid, [0, 0], [9, 3]).unwrap();
+        assert_eq!(smooth(&grid, &path), [[0, 0], [9, 3]]);
+
+        let mut grid = Grid::new(10, 10);
+        for y in 0..9 {
+            grid.set_blocked([5, y]);
+        }
+        let path = find_path(&grid, [0, 0], [9, 0]).unwrap();
+        let smoothed = smooth(&grid, &path);
+        assert_eq!(smoothed.first(), Some(&[0, 0]));
+        assert_eq!(smoothed.last(), Some(&[9, 0]));
+        assert!(smoothed.len() < path.len());
+        // Every shortcut stays clear of the wall.
+        for pair in smoothed.windows(2) {
+            assert!(in_line(&grid, pair[0], pair[1], 1.0));
+        }
+
+        assert!(smooth(&grid, &[]).is_empty());
+    }
+
+    #[test]
+    fn lines_through_corners_touch_both_cells() {
+        let mut grid = Grid::new(3, 3);
+        assert!(in_line(&grid, [0, 0], [2, 2], 1.0));
+        grid.set_blocked([1, 0]);
+        assert!(!in_line(&grid, [0, 0], [2, 2], 1.0));
+        // Not through a corner, only two of the cells are crossed.
+        assert!(in_line(&grid, [0, 0], [1, 2], 1.0));
+    }
+
+    #[test]
+    fn smoothing_stays_off_expensive_cells() {
+        let mut grid = Grid::new(5, 3);
+        grid.set_cost([2, 1], 5.0);
+        let path = [[0, 1], [1, 0], [2, 0], [3, 0], [4, 1]];
+        // Around the expensive cell rather than straight through it.
+        assert_eq!(smooth(&grid, &path), [[0, 1], [2, 0], [4, 1]]);
+    }
+}
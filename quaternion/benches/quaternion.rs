@@ -0,0 +1,33 @@
+use criterion::{Criterion, criterion_group, criterion_main};
+use lina::{angle::Rad, matrix::Matrix, v};
+use quaternion::Quaternion;
+use std::hint::black_box;
+
+fn quaternion(c: &mut Criterion) {
+    let lhs = Quaternion::<f32>::new_unit(Rad(0.5), v![1.0, 2.0, 3.0]);
+    let rhs = Quaternion::<f32>::new_unit(Rad(-1.2), v![0.0, 1.0, 0.0]);
+    c.bench_function("mul f32", |b| b.iter(|| black_box(lhs) * black_box(rhs)));
+    c.bench_function("normalize f32", |b| b.iter(|| black_box(lhs).normalized()));
+    c.bench_function("slerp f32", |b| {
+        b.iter(|| black_box(lhs).slerp(&black_box(rhs), 0.3))
+    });
+
+    let point = v![1.0f32, -2.0, 0.5];
+    let p = Quaternion::<f32>::from_vector(point);
+    c.bench_function("conjugate_by f32", |b| {
+        b.iter(|| black_box(p).conjugate_by(black_box(lhs)))
+    });
+    c.bench_function("rotate_vector f32", |b| {
+        b.iter(|| black_box(lhs).rotate_vector(black_box(point)))
+    });
+    c.bench_function("rotate_vector_unit f32", |b| {
+        b.iter(|| black_box(lhs).rotate_vector_unit(black_box(point)))
+    });
+
+    c.bench_function("into matrix f32", |b| {
+        b.iter(|| Matrix::<f32, 4, 4>::from(black_box(lhs)))
+    });
+}
+
+criterion_group!(benches, quaternion);
+criterion_main!(benches);
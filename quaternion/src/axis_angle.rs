@@ -0,0 +1,96 @@
+use lina::{v, vector::Vector};
+
+use crate::Quaternion;
+
+macro_rules! impl_axis_angle_for_float_types {
+    ($($T: ty),* $(,)*) => {$(
+        impl Quaternion<$T> {
+            /// Extract the rotation axis and angle of the quaternion.
+            ///
+            /// The inverse of [new_unit](Quaternion::new_unit). For a quaternion:
+            /// ```text
+            /// q = [cos(theta/2), u * sin(theta/2)]
+            /// ```
+            /// it returns the unit vector `u` and `theta` in radians, within the range `[0, 2PI]`.
+            ///
+            /// The length of the quaternion is ignored.
+            /// Without a rotation the axis is undefined, so for the identity
+            /// (and the zero quaternion) the X axis with a zero angle is returned.
+            ///
+            /// ```
+            /// # use std::f32::consts::PI;
+            /// # use quaternion::Quaternion;
+            /// # use lina::angle::Rad;
+            /// # use lina::v;
+            /// # use float_eq::assert_float_eq;
+            /// let q = Quaternion::<f32>::new_unit(Rad(PI/3.0), v![0.0, 2.0, 0.0]);
+            ///
+            /// let (axis, angle) = q.to_axis_angle();
+            ///
+            /// assert_float_eq!(angle, PI/3.0, ulps <= 1);
+            /// assert!(axis.ulps_eq(&v![0.0, 1.0, 0.0], 1));
+            ///
+            /// let (axis, angle) = Quaternion::<f32>::default().to_axis_angle();
+            /// assert_eq!(angle, 0.0);
+            /// assert_eq!(axis, v![1.0, 0.0, 0.0]);
+            /// ```
+            pub fn to_axis_angle(&self) -> (Vector<$T, 3>, $T) {
+                // sin(theta/2) scaled by the length of the quaternion
+                let sin = self.vector.length();
+                // Also true for the zero quaternion.
+                if sin <= <$T>::EPSILON * self.length() {
+                    return (v![1.0, 0.0, 0.0], 0.0);
+                }
+
+                // The common scale cancels out in the ratio.
+                (self.vector / sin, 2.0 * sin.atan2(self.scalar))
+            }
+        }
+    )*};
+}
+
+impl_axis_angle_for_float_types!(f32, f64);
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use float_eq::assert_float_eq;
+    use lina::{angle::Rad, v};
+
+    use crate::Quaternion;
+
+    #[test]
+    fn round_trip() {
+        for theta in [0.1, PI / 2.0, PI, 3.0 * PI / 2.0] {
+            let q = Quaternion::<f64>::new_unit(Rad(theta), v![1.0, -2.0, 3.0]);
+            let (axis, angle) = q.to_axis_angle();
+
+            assert_float_eq!(angle, theta, abs <= 1e-12);
+            axis.as_slice()
+                .iter()
+                .zip(v![1.0, -2.0, 3.0].normalized().as_slice())
+                .for_each(|(l, r)| assert_float_eq!(*l, *r, abs <= 1e-12));
+        }
+    }
+
+    #[test]
+    fn scale_is_ignored() {
+        let q = Quaternion::<f64>::new(5.0, Rad(PI / 2.0), v![0.0, 0.0, 1.0]);
+        let (axis, angle) = q.to_axis_angle();
+
+        assert_float_eq!(angle, PI / 2.0, abs <= 1e-12);
+        assert_eq!(axis, v![0.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn identity() {
+        let (axis, angle) = Quaternion::<f64>::new_parts(2.0, v![0.0, 0.0, 0.0]).to_axis_angle();
+        assert_eq!(axis, v![1.0, 0.0, 0.0]);
+        assert_eq!(angle, 0.0);
+
+        let (axis, angle) = Quaternion::<f64>::new_parts(0.0, v![0.0, 0.0, 0.0]).to_axis_angle();
+        assert_eq!(axis, v![1.0, 0.0, 0.0]);
+        assert_eq!(angle, 0.0);
+    }
+}
@@ -1,11 +1,8 @@
-use lina::vector::Vector;
-
 use crate::Quaternion;
 
 impl<ValueType> Quaternion<ValueType>
 where
-    Vector<ValueType, 3>: Copy + std::ops::Mul<ValueType, Output = Vector<ValueType, 3>>,
-    ValueType: Copy + std::convert::From<i8>,
+    ValueType: Copy + std::ops::Neg<Output = ValueType>,
 {
     /// Generate the conjugate.
     ///
@@ -17,10 +14,18 @@ where
     /// ```text
     /// q* = [s, -v]
     /// ```
+    ///
+    /// Unsigned quaternions have no conjugate, their values can't be negated.
+    ///
+    /// ```compile_fail
+    /// # use quaternion::Quaternion;
+    /// # use lina::v;
+    /// Quaternion::new_parts(1_u32, v![2, 3, 4]).conjugate();
+    /// ```
     pub fn conjugate(&self) -> Quaternion<ValueType> {
         Quaternion {
             scalar: self.scalar,
-            vector: self.vector * ValueType::from(-1),
+            vector: self.vector.map(|value| -value),
         }
     }
 }
@@ -1,17 +1,14 @@
-use lina::vector::Vector;
+use lina::vector::{One, Zero};
 
 use crate::Quaternion;
 
 impl<ValueType> Default for Quaternion<ValueType>
 where
-    ValueType: Default + Copy,
-    ValueType: std::convert::From<i8>,
+    ValueType: Copy + Zero + One,
 {
+    /// The [identity](Quaternion::IDENTITY).
     fn default() -> Self {
-        Self {
-            scalar: ValueType::from(1),
-            vector: Vector::from_value(ValueType::from(0)),
-        }
+        Self::IDENTITY
     }
 }
 
@@ -28,14 +28,14 @@ macro_rules! impl_matrix_from_for_float_types {
         /// components of `VR` as its imaginary part.
         ///
         /// ```
+        /// # use lina::angle::Rad;
         /// # use lina::v;
         /// # use quaternion::Quaternion;
         /// # use lina::matrix::Matrix;
         /// # use std::f32::consts::PI;
-        /// # use float_eq::assert_float_eq;
         /// let v = v![1.0, 2.0, 3.0, 1.0];
         /// let p = Quaternion::<f32>::from_vector(v.xyz().unwrap());
-        /// let q = Quaternion::<f32>::new_unit(PI / 2.0, v![1.0, 0.0, 0.0]);
+        /// let q = Quaternion::<f32>::new_unit(Rad(PI / 2.0), v![1.0, 0.0, 0.0]);
         ///
         /// let mq: Matrix<f32, 4, 4> = q.into();
         ///
@@ -44,7 +44,7 @@ macro_rules! impl_matrix_from_for_float_types {
         ///
         /// let lhs = with_mq.xyz().unwrap();
         /// let rhs = with_conjugate.vector();
-        /// lhs.as_slice().iter().zip(rhs.as_slice()).for_each(|(l, r)| assert_float_eq!(l, r, ulps <= 4));
+        /// assert!(lhs.ulps_eq(&rhs, 4));
         /// ```
         impl std::convert::From<Quaternion<$T>> for Matrix<$T, 4, 4> {
             fn from(q: Quaternion<$T>) -> Matrix<$T, 4, 4> {
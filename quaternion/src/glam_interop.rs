@@ -0,0 +1,56 @@
+//! Conversions from and to the [glam] quaternions, enabled by the `glam`
+//! feature.
+//!
+//! glam orders the components `x, y, z, w`, with `w` the scalar part.
+//!
+//! ```
+//! # use std::f32::consts::PI;
+//! # use quaternion::Quaternion;
+//! # use lina::angle::Rad;
+//! # use lina::v;
+//! let q = Quaternion::<f32>::new_unit(Rad(PI / 2.0), v![0.0, 1.0, 0.0]);
+//! let quat = glam::Quat::from(q);
+//!
+//! assert!(quat.abs_diff_eq(glam::Quat::from_rotation_y(PI / 2.0), 1e-6));
+//! assert_eq!(Quaternion::from(quat), q);
+//! ```
+
+use lina::vector::Vector;
+
+use crate::Quaternion;
+
+macro_rules! impl_glam_conversions {
+    ($($T: ty => $Glam: ty),* $(,)*) => {$(
+        impl From<Quaternion<$T>> for $Glam {
+            fn from(q: Quaternion<$T>) -> Self {
+                <$Glam>::from_xyzw(q.vector[0], q.vector[1], q.vector[2], q.scalar)
+            }
+        }
+
+        impl From<$Glam> for Quaternion<$T> {
+            fn from(q: $Glam) -> Self {
+                Quaternion::new_parts(q.w, Vector::from_array([q.x, q.y, q.z]))
+            }
+        }
+    )*};
+}
+
+impl_glam_conversions!(f32 => glam::Quat, f64 => glam::DQuat);
+
+#[cfg(test)]
+mod tests {
+    use lina::{angle::Rad, v};
+
+    use crate::Quaternion;
+
+    #[test]
+    fn rotations_agree() {
+        let q = Quaternion::<f64>::new_unit(Rad(1.2), v![1.0, -2.0, 0.5]);
+        let quat = glam::DQuat::from(q);
+
+        let rotated = quat * glam::DVec3::new(3.0, 1.0, -2.0);
+        let expected = q.rotate_vector_unit(v![3.0, 1.0, -2.0]);
+        assert!(v![rotated.x, rotated.y, rotated.z].approx_eq(&expected, 1e-12));
+        assert_eq!(Quaternion::from(quat), q);
+    }
+}
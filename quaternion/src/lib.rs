@@ -39,20 +39,35 @@
 
 use std::ops::{Add, Mul};
 
-use lina::vector::Vector;
+use lina::angle::Rad;
+use lina::vector::{One, Vector, Zero};
 
 mod add;
 mod add_assign;
+mod axis_angle;
 mod conjugate;
 mod default;
 mod div;
 mod div_assign;
 mod from;
+#[cfg(feature = "glam")]
+mod glam_interop;
+mod integrate;
 mod length;
+mod look_rotation;
+#[cfg(feature = "mint")]
+mod mint_interop;
 mod mul;
 mod mul_assign;
+mod normalize;
+#[cfg(feature = "rand")]
+mod random;
+mod rotate_vector;
+mod rotation_arc;
+mod slerp;
 mod sub;
 mod sub_assign;
+mod swing_twist;
 
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub struct Quaternion<ValueType> {
@@ -82,14 +97,35 @@ where
     /// ```
     ///
     /// Where `s` is the scalar and `v = [x, y, z]` vector.
-    pub fn new_parts(scalar: ValueType, vector: Vector<ValueType, 3>) -> Quaternion<ValueType> {
+    pub const fn new_parts(
+        scalar: ValueType,
+        vector: Vector<ValueType, 3>,
+    ) -> Quaternion<ValueType> {
         Quaternion { scalar, vector }
     }
 }
 
 impl<ValueType> Quaternion<ValueType>
 where
-    ValueType: Copy + std::convert::From<i8>,
+    ValueType: Copy + Zero + One,
+{
+    /// The rotation by nothing, the [default](Quaternion::default) quaternion,
+    /// usable in constants.
+    ///
+    /// ```
+    /// # use quaternion::Quaternion;
+    /// # use lina::v;
+    /// const IDENTITY: Quaternion<i32> = Quaternion::IDENTITY;
+    ///
+    /// assert_eq!(IDENTITY, Quaternion::new_parts(1, v![0, 0, 0]));
+    /// ```
+    pub const IDENTITY: Quaternion<ValueType> =
+        Quaternion::new_parts(ValueType::ONE, Vector::from_array([ValueType::ZERO; 3]));
+}
+
+impl<ValueType> Quaternion<ValueType>
+where
+    ValueType: Copy + Zero,
 {
     /// Create a quaternion from a 3 element long [Vector].
     ///
@@ -108,7 +144,7 @@ where
     /// ```
     pub fn from_vector(v: Vector<ValueType, 3>) -> Quaternion<ValueType> {
         Quaternion {
-            scalar: 0.into(),
+            scalar: ValueType::ZERO,
             vector: v,
         }
     }
@@ -147,8 +183,7 @@ where
         + Default
         + std::ops::Add<Output = ValueType>
         + std::ops::Mul<Output = ValueType>
-        + std::convert::From<i8>,
-    Vector<ValueType, 3>: Copy + std::ops::Mul<ValueType, Output = Vector<ValueType, 3>>,
+        + std::ops::Neg<Output = ValueType>,
     Quaternion<ValueType>: std::ops::Div<ValueType, Output = Quaternion<ValueType>>
         + std::ops::Mul<Output = Quaternion<ValueType>>,
 {
@@ -163,34 +198,36 @@ where
     /// ```
     /// # use std::f32::consts::PI;
     /// # use quaternion::Quaternion;
+    /// # use lina::angle::Rad;
     /// # use lina::v;
     /// # use float_eq::assert_float_eq;
     /// let q = Quaternion::<f32>::new_parts(2.39, v![1.0, 2.0, 3.0]);
     ///
     /// let a = q * q.inverse();
     ///  assert_float_eq!(a.scalar(), 1.0, ulps <= 1);
-    /// a.vector().as_slice().iter().zip([0.0, 0.0, 0.0]).for_each(|(l, r)| assert_float_eq!(*l, r, ulps <= 1));
+    /// assert!(a.vector().ulps_eq(&v![0.0, 0.0, 0.0], 1));
     ///
     /// // Commutative
     /// let b = q.inverse() * q;
     ///
     /// assert_float_eq!(a.scalar(), b.scalar(), ulps <= 1);
-    /// a.vector().as_slice().iter().zip(b.vector().as_slice()).for_each(|(l, r)| assert_float_eq!(*l, *r, ulps <= 1));
+    /// assert!(a.vector().ulps_eq(&b.vector(), 1));
     /// ```
     ///
     /// For a **unit** quaternion the inverse is equal to its conjugate:
     /// ```
     /// # use std::f32::consts::PI;
     /// # use quaternion::Quaternion;
+    /// # use lina::angle::Rad;
     /// # use lina::v;
     /// # use float_eq::assert_float_eq;
-    /// let q = Quaternion::<f32>::new_unit(PI/2.0, v![1.0, 2.0, 3.0]);
+    /// let q = Quaternion::<f32>::new_unit(Rad(PI/2.0), v![1.0, 2.0, 3.0]);
     ///
     /// let q_inverse = q.inverse();
     /// let q_conjugate = q.conjugate();
     ///
     /// assert_float_eq!(q_inverse.scalar(), q_conjugate.scalar(), ulps <= 1);
-    /// q_inverse.vector().as_slice().iter().zip(q_conjugate.vector().as_slice()).for_each(|(l, r)| assert_float_eq!(*l, *r, ulps <= 1));
+    /// assert!(q_inverse.vector().ulps_eq(&q_conjugate.vector(), 1));
     /// ```
     pub fn inverse(&self) -> Quaternion<ValueType> {
         self.conjugate() / self.length_squared()
@@ -212,23 +249,25 @@ where
     /// Rotate `p` by `q`, while preserving it's length.
     ///
     /// `q` must be a **unit** quaternion or the operation is
-    /// undefined.
+    /// undefined. Use [normalize](Quaternion::normalize) to correct
+    /// accumulated drift before rotating.
     ///
     /// ```
     /// # use std::f32::consts::PI;
     /// # use quaternion::Quaternion;
+    /// # use lina::angle::Rad;
     /// # use lina::v;
     /// # use float_eq::assert_float_eq;
     ///
     /// let p = Quaternion::<f32>::from_vector(v!{1.0, 0.0, 0.0});
     /// // A quaternion to rotate 90 degrees around the Y axis.
     /// // Notice the rotation axis isn't normalized.
-    /// let q = Quaternion::<f32>::new_unit(PI/2.0, v![0.0, 1.0, 0.0]);
+    /// let q = Quaternion::<f32>::new_unit(Rad(PI/2.0), v![0.0, 1.0, 0.0]);
     ///
     /// let rotated_p = p.conjugate_by(q);
     ///
     /// assert_float_eq!(rotated_p.length(), 1.0, ulps <= 1);
-    /// rotated_p.vector().as_slice().iter().zip([0.0, 0.0, -1.0]).for_each(|(l, r)| assert_float_eq!(*l, r, ulps <= 1));
+    /// assert!(rotated_p.vector().ulps_eq(&v![0.0, 0.0, -1.0], 1));
     /// ```
     pub fn conjugate_by(self, q: Quaternion<ValueType>) -> Quaternion<ValueType> {
         q * self * q.inverse()
@@ -251,36 +290,57 @@ macro_rules! impl_float_restricted_members {
             /// precalculated by the user. This is why `theta` and `rotation_axis`
             /// are required to be provided separately.
             ///
-            /// `theta` is rotation degrees in radians. The function internally
-            /// divides this value by 2, ensuring that the resulting quaternion
-            /// only rotates `theta` degrees.
+            /// `theta` is the angle of the rotation, either a [Rad] or a
+            /// [Deg](lina::angle::Deg). The function internally divides this value
+            /// by 2, ensuring that the resulting quaternion only rotates `theta`.
             /// `rotation_axis` is internally normalized.
-            pub fn new(tensor: $T, theta: $T, rotation_axis: Vector<$T, 3>) -> Quaternion<$T> {
+            ///
+            /// ```
+            /// # use quaternion::Quaternion;
+            /// # use lina::angle::Deg;
+            /// # use lina::v;
+            /// let q = Quaternion::<f32>::new(2.0, Deg(90.0), v![0.0, 0.0, 3.0]);
+            ///
+            /// assert!((q.length() - 2.0).abs() < 1e-6);
+            /// // The axis is normalized, a quarter turn around Z.
+            /// assert!((q.vector()[2] - q.scalar()).abs() < 1e-6);
+            /// ```
+            pub fn new(
+                tensor: $T,
+                theta: impl Into<Rad<$T>>,
+                rotation_axis: Vector<$T, 3>,
+            ) -> Quaternion<$T> {
+                let Rad(theta) = theta.into();
                 let theta = theta / 2.0;
+                let normalized = rotation_axis.normalized();
 
                 Quaternion {
                     scalar: tensor * theta.cos(),
-                    vector: tensor * theta.sin() * rotation_axis,
+                    vector: tensor * theta.sin() * normalized,
                 }
             }
 
             /// Create a **unit** quaternion.
             ///
-            /// `theta` is rotation degrees in radians. The function internally
-            /// divides this value by 2, ensuring that the resulting quaternion
-            /// only rotates `theta` degrees.
+            /// `theta` is the angle of the rotation, either a [Rad] or a
+            /// [Deg](lina::angle::Deg). The function internally divides this value
+            /// by 2, ensuring that the resulting quaternion only rotates `theta`.
             /// `rotation_axis` is internally normalized.
             ///
             /// ```
             /// # use std::f32::consts::PI;
             /// # use quaternion::Quaternion;
+            /// # use lina::angle::{Deg, Rad};
             /// # use lina::v;
             /// # use float_eq::assert_float_eq;
-            /// let q = Quaternion::<f32>::new_unit(PI/3.0, v![1.0, 2.0, 3.0]);
+            /// let q = Quaternion::<f32>::new_unit(Rad(PI/3.0), v![1.0, 2.0, 3.0]);
             ///
             /// assert_float_eq!(q.length(), 1.0, ulps <= 1);
+            /// let in_degrees = Quaternion::<f32>::new_unit(Deg(60.0), v![1.0, 2.0, 3.0]);
+            /// assert!((q.dot(&in_degrees) - 1.0).abs() < 1e-6);
             /// ```
-            pub fn new_unit(theta: $T, rotation_axis: Vector<$T, 3>) -> Quaternion<$T> {
+            pub fn new_unit(theta: impl Into<Rad<$T>>, rotation_axis: Vector<$T, 3>) -> Quaternion<$T> {
+                let Rad(theta) = theta.into();
                 let theta = theta / 2.0;
                 let normalized = rotation_axis.normalized();
 
@@ -306,4 +366,13 @@ mod tests {
         let q = Quaternion::new_parts(1, v![2, 3, 4]);
         assert_eq!(q.length_squared(), 30);
     }
+
+    #[test]
+    fn unsigned_quaternions() {
+        let identity = Quaternion::<u8>::default();
+        let pure = Quaternion::from_vector(v![1_u8, 2, 3]);
+
+        assert_eq!(identity * pure, pure);
+        assert_eq!(pure.length_squared(), 14);
+    }
 }
@@ -0,0 +1,97 @@
+use lina::vector::Vector;
+
+use crate::Quaternion;
+
+macro_rules! impl_look_rotation_for_float_types {
+    ($($T: ty),* $(,)*) => {$(
+        impl Quaternion<$T> {
+            /// The orientation of an object looking along `forward`, with its
+            /// top facing `up` as much as possible.
+            ///
+            /// Objects look down their -Z axis with +Y up, like the cameras
+            /// do, so the result turns -Z onto `forward` and +Y onto `up` made
+            /// perpendicular to it. It is the rotation of
+            /// `graphic::transform::point_at` from any point towards a target
+            /// along `forward`, the rotation of `look_at` is its
+            /// [conjugate](Quaternion::conjugate).
+            ///
+            /// Neither has to be normalized, but `up` must not be parallel to
+            /// `forward`.
+            ///
+            /// ```
+            /// # use quaternion::Quaternion;
+            /// # use lina::v;
+            /// let forward = v![1.0, 0.0, -1.0];
+            /// let q = Quaternion::<f32>::look_rotation(forward, v![0.0, 1.0, 0.0]);
+            ///
+            /// assert!(q.rotate_vector_unit(v![0.0, 0.0, -1.0]).approx_eq(&forward.normalized(), 1e-6));
+            /// assert!(q.rotate_vector_unit(v![0.0, 1.0, 0.0]).approx_eq(&v![0.0, 1.0, 0.0], 1e-6));
+            /// ```
+            pub fn look_rotation(forward: Vector<$T, 3>, up: Vector<$T, 3>) -> Quaternion<$T> {
+                // The columns of the rotation matrix, where the axes end up.
+                let back = forward.normalized() * -1.0;
+                let right = up.cross(back).normalized();
+                let up = back.cross(right);
+                let m = |row: usize, column: usize| [right, up, back][column][row];
+
+                // Extracted from the largest of the diagonal terms, so the
+                // division is by the largest possible value.
+                let trace = m(0, 0) + m(1, 1) + m(2, 2);
+                let (scalar, vector) = if trace > 0.0 {
+                    let s = (trace + 1.0).sqrt() * 2.0;
+                    (s / 4.0, [m(2, 1) - m(1, 2), m(0, 2) - m(2, 0), m(1, 0) - m(0, 1)].map(|v| v / s))
+                } else if m(0, 0) > m(1, 1) && m(0, 0) > m(2, 2) {
+                    let s = (1.0 + m(0, 0) - m(1, 1) - m(2, 2)).sqrt() * 2.0;
+                    ((m(2, 1) - m(1, 2)) / s, [s / 4.0, (m(0, 1) + m(1, 0)) / s, (m(0, 2) + m(2, 0)) / s])
+                } else if m(1, 1) > m(2, 2) {
+                    let s = (1.0 + m(1, 1) - m(0, 0) - m(2, 2)).sqrt() * 2.0;
+                    ((m(0, 2) - m(2, 0)) / s, [(m(0, 1) + m(1, 0)) / s, s / 4.0, (m(1, 2) + m(2, 1)) / s])
+                } else {
+                    let s = (1.0 + m(2, 2) - m(0, 0) - m(1, 1)).sqrt() * 2.0;
+                    ((m(1, 0) - m(0, 1)) / s, [(m(0, 2) + m(2, 0)) / s, (m(1, 2) + m(2, 1)) / s, s / 4.0])
+                };
+
+                Quaternion::new_parts(scalar, Vector::from_array(vector)).normalized()
+            }
+        }
+    )*};
+}
+
+impl_look_rotation_for_float_types!(f32, f64);
+
+#[cfg(test)]
+mod tests {
+    use lina::v;
+
+    use crate::Quaternion;
+
+    #[test]
+    fn looking_down_minus_z_does_not_turn() {
+        let q = Quaternion::<f64>::look_rotation(v![0.0, 0.0, -2.0], v![0.0, 3.0, 0.0]);
+
+        assert_eq!(q, Quaternion::default());
+    }
+
+    #[test]
+    fn turns_onto_forward_and_up_in_every_direction() {
+        let forwards = [
+            v![0.0, 0.0, 1.0],
+            v![1.0, 0.0, 0.0],
+            v![-1.0, 0.0, 0.0],
+            v![0.3, -0.9, 0.2],
+            v![-2.0, 1.0, 5.0],
+        ];
+        let up = v![0.0, 1.0, 0.2];
+        for forward in forwards {
+            let q = Quaternion::<f64>::look_rotation(forward, up);
+
+            assert!(q.is_normalized(1e-12));
+            let turned_forward = q.rotate_vector_unit(v![0.0, 0.0, -1.0]);
+            assert!(turned_forward.approx_eq(&forward.normalized(), 1e-12));
+            // Up stays within the plane of forward and up, on the side of up.
+            let turned_up = q.rotate_vector_unit(v![0.0, 1.0, 0.0]);
+            assert!((turned_up * forward.cross(up)).abs() < 1e-12);
+            assert!(turned_up * up > 0.0);
+        }
+    }
+}
@@ -0,0 +1,40 @@
+//! Conversions from and to the [mint] quaternions, enabled by the `mint`
+//! feature.
+//!
+//! ```
+//! # use quaternion::Quaternion;
+//! # use lina::v;
+//! let q = Quaternion::new_parts(1.0, v![2.0, 3.0, 4.0]);
+//! let mint = mint::Quaternion::from(q);
+//!
+//! assert_eq!(mint.s, 1.0);
+//! assert_eq!(mint.v, mint::Vector3 { x: 2.0, y: 3.0, z: 4.0 });
+//! assert_eq!(Quaternion::from(mint), q);
+//! ```
+
+use lina::vector::Vector;
+
+use crate::Quaternion;
+
+impl<ValueType> From<Quaternion<ValueType>> for mint::Quaternion<ValueType> {
+    fn from(q: Quaternion<ValueType>) -> Self {
+        let [x, y, z] = q.vector.into_array();
+        mint::Quaternion {
+            v: mint::Vector3 { x, y, z },
+            s: q.scalar,
+        }
+    }
+}
+
+impl<ValueType> From<mint::Quaternion<ValueType>> for Quaternion<ValueType> {
+    fn from(q: mint::Quaternion<ValueType>) -> Self {
+        Quaternion {
+            scalar: q.s,
+            vector: Vector::from_array([q.v.x, q.v.y, q.v.z]),
+        }
+    }
+}
+
+impl<ValueType> mint::IntoMint for Quaternion<ValueType> {
+    type MintType = mint::Quaternion<ValueType>;
+}
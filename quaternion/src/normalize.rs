@@ -0,0 +1,155 @@
+use crate::Quaternion;
+
+macro_rules! impl_normalize_for_float_types {
+    ($($T: ty),* $(,)*) => {$(
+        impl Quaternion<$T> {
+            /// Scale the quaternion in-place to unit length.
+            ///
+            /// For a given quaternion `q`:
+            /// ```text
+            /// q' = q / n(q)
+            /// ```
+            ///
+            /// Repeatedly multiplying unit quaternions accumulates
+            /// floating point errors, slowly drifting away from unit length.
+            /// Operations like [conjugate_by](Quaternion::conjugate_by) require
+            /// a unit quaternion, so the result should be re-normalized from
+            /// time to time.
+            ///
+            /// Normalizing a zero length quaternion results in `NaN` components.
+            ///
+            /// ```
+            /// # use quaternion::Quaternion;
+            /// # use lina::v;
+            /// # use float_eq::assert_float_eq;
+            /// let mut q = Quaternion::<f32>::new_parts(1.0, v![1.0, 1.0, 1.0]);
+            /// q.normalize();
+            ///
+            /// assert_float_eq!(q.length(), 1.0, ulps <= 1);
+            /// assert_float_eq!(q.scalar(), 0.5, ulps <= 1);
+            /// ```
+            pub fn normalize(&mut self) {
+                let length = self.length();
+                self.scalar /= length;
+                self.vector /= length;
+            }
+
+            /// Generate a unit quaternion without modifying the current one.
+            ///
+            /// See [normalize](Quaternion::normalize).
+            pub fn normalized(&self) -> Quaternion<$T> {
+                let mut q = *self;
+                q.normalize();
+                q
+            }
+
+            /// Check if the length of the quaternion is within `epsilon` of 1.
+            ///
+            /// ```
+            /// # use quaternion::Quaternion;
+            /// # use lina::v;
+            /// let q = Quaternion::<f32>::new_parts(1.0, v![1.0, 1.0, 1.0]);
+            ///
+            /// assert!(!q.is_normalized(1e-6));
+            /// assert!(q.normalized().is_normalized(1e-6));
+            /// ```
+            pub fn is_normalized(&self, epsilon: $T) -> bool {
+                (self.length() - 1.0).abs() <= epsilon
+            }
+
+            /// Normalize the quaternion in-place only if its length drifted
+            /// further than `tolerance` from 1.
+            ///
+            /// Meant to be called after every accumulating multiplication,
+            /// so the correction is only applied when actually needed.
+            /// Returns `true` if the quaternion was normalized.
+            ///
+            /// ```
+            /// # use quaternion::Quaternion;
+            /// # use lina::v;
+            /// let mut q = Quaternion::<f32>::new_parts(1.0, v![0.0, 0.0, 0.01]);
+            ///
+            /// assert!(!q.renormalize_if_needed(1e-3));
+            /// assert!(q.renormalize_if_needed(1e-6));
+            /// assert!(q.is_normalized(1e-6));
+            /// ```
+            pub fn renormalize_if_needed(&mut self, tolerance: $T) -> bool {
+                if self.is_normalized(tolerance) {
+                    return false;
+                }
+                self.normalize();
+                true
+            }
+        }
+    )*};
+}
+
+impl_normalize_for_float_types!(f32, f64);
+
+#[cfg(test)]
+mod tests {
+    use float_eq::assert_float_eq;
+    use lina::{angle::Rad, v};
+
+    use crate::Quaternion;
+
+    #[test]
+    fn normalize() {
+        let mut q = Quaternion::<f64>::new_parts(2.0, v![0.0, 0.0, 0.0]);
+        q.normalize();
+
+        assert_eq!(q, Quaternion::new_parts(1.0, v![0.0, 0.0, 0.0]));
+    }
+
+    #[test]
+    fn normalized_keeps_direction() {
+        let q = Quaternion::<f32>::new_parts(1.0, v![2.0, 3.0, 4.0]);
+        let normalized = q.normalized();
+
+        let length = 30.0f32.sqrt();
+        assert_float_eq!(normalized.scalar(), 1.0 / length, ulps <= 1);
+        normalized
+            .vector()
+            .as_slice()
+            .iter()
+            .zip([2.0 / length, 3.0 / length, 4.0 / length])
+            .for_each(|(l, r)| assert_float_eq!(*l, r, ulps <= 1));
+        // The original is untouched.
+        assert_eq!(q.scalar(), 1.0);
+    }
+
+    #[test]
+    fn drift_is_corrected() {
+        let step = Quaternion::<f32>::new_unit(Rad(0.1), v![1.0, 2.0, 3.0]);
+        let mut q = step;
+        for _ in 0..10000 {
+            q *= step;
+        }
+
+        assert!(q.normalized().is_normalized(f32::EPSILON));
+    }
+
+    #[test]
+    fn is_normalized() {
+        let q = Quaternion::<f64>::new_parts(1.0, v![0.0, 0.0, 0.001]);
+
+        assert!(!q.is_normalized(1e-9));
+        assert!(q.is_normalized(1e-3));
+    }
+
+    #[test]
+    fn renormalize_if_needed() {
+        let step = Quaternion::<f32>::new_unit(Rad(0.1), v![1.0, 2.0, 3.0]);
+        let mut q = step;
+        for _ in 0..10000 {
+            q *= step;
+            q.renormalize_if_needed(1e-5);
+            assert!(q.is_normalized(1e-5));
+        }
+
+        let mut unit = Quaternion::<f64>::new_unit(Rad(1.0), v![0.0, 1.0, 0.0]);
+        let before = unit;
+        assert!(!unit.renormalize_if_needed(1e-12));
+        assert_eq!(unit, before);
+    }
+}
@@ -0,0 +1,68 @@
+//! Random rotations with [rand], enabled by the `rand` feature.
+
+use rand::Rng;
+
+use lina::vector::Vector;
+
+use crate::Quaternion;
+
+macro_rules! impl_random_for_float_types {
+    ($($T: ty),* $(,)*) => {$(
+        impl Quaternion<$T> {
+            /// A random rotation, a unit quaternion uniformly distributed over
+            /// all orientations.
+            ///
+            /// Uses Ken Shoemake's method from "Uniform Random Rotations"
+            /// (Graphics Gems III), splitting three uniform values into the
+            /// length of two complex halves and their angles:
+            /// ```text
+            /// q = [sqrt(u1) * cos(2pi * u3),
+            ///      sqrt(1 - u1) * sin(2pi * u2), sqrt(1 - u1) * cos(2pi * u2),
+            ///      sqrt(u1) * sin(2pi * u3)]
+            /// ```
+            ///
+            /// ```
+            /// # use quaternion::Quaternion;
+            /// # use rand::{SeedableRng, rngs::SmallRng};
+            /// let mut rng = SmallRng::seed_from_u64(1);
+            /// let rotation = Quaternion::<f32>::random_unit(&mut rng);
+            ///
+            /// assert!(rotation.is_normalized(1e-6));
+            /// ```
+            pub fn random_unit<R: Rng + ?Sized>(rng: &mut R) -> Quaternion<$T> {
+                let tau = std::f64::consts::TAU as $T;
+                let u1: $T = rng.random();
+                let (u2, u3): ($T, $T) = (rng.random::<$T>() * tau, rng.random::<$T>() * tau);
+                let (first, second) = ((1.0 - u1).sqrt(), u1.sqrt());
+                Quaternion::new_parts(
+                    second * u3.cos(),
+                    Vector::from_array([first * u2.sin(), first * u2.cos(), second * u3.sin()]),
+                )
+            }
+        }
+    )*};
+}
+
+impl_random_for_float_types!(f32, f64);
+
+#[cfg(test)]
+mod tests {
+    use lina::{v, vector::Vector};
+    use rand::{SeedableRng, rngs::SmallRng};
+
+    use crate::Quaternion;
+
+    #[test]
+    fn random_rotations_are_uniform() {
+        let mut rng = SmallRng::seed_from_u64(7);
+        let count = 10_000;
+        let mut sum = Vector::<f64, 3>::from_array([0.0; 3]);
+        for _ in 0..count {
+            let rotation = Quaternion::<f64>::random_unit(&mut rng);
+            assert!(rotation.is_normalized(1e-12));
+            // A uniform rotation turns a direction anywhere on the sphere.
+            sum += rotation.rotate_vector(v![0.0, 0.0, 1.0]);
+        }
+        assert!((sum / count as f64).length() < 0.05);
+    }
+}
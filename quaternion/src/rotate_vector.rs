@@ -0,0 +1,113 @@
+use lina::vector::Vector;
+
+use crate::Quaternion;
+
+macro_rules! impl_rotate_vector_for_float_types {
+    ($($T: ty),* $(,)*) => {$(
+        impl Quaternion<$T> {
+            /// Rotate a 3D vector by the quaternion.
+            ///
+            /// For quaternion `q` and vector `v` it calculates:
+            /// ```text
+            /// p = [0, v]
+            /// v' = V(qp(q^-1))
+            /// ```
+            ///
+            /// A shorthand for wrapping `v` with [from_vector](Quaternion::from_vector),
+            /// calling [conjugate_by](Quaternion::conjugate_by) and taking the
+            /// [vector](Quaternion::vector) part of the result.
+            /// As the inverse is used, the quaternion doesn't need to be a unit quaternion,
+            /// but then its length has no effect either.
+            ///
+            /// ```
+            /// # use std::f32::consts::PI;
+            /// # use quaternion::Quaternion;
+            /// # use lina::angle::Rad;
+            /// # use lina::v;
+            /// // A quaternion to rotate 90 degrees around the Y axis.
+            /// let q = Quaternion::<f32>::new_unit(Rad(PI/2.0), v![0.0, 1.0, 0.0]);
+            ///
+            /// let rotated = q.rotate_vector(v![1.0, 0.0, 0.0]);
+            ///
+            /// assert!(rotated.ulps_eq(&v![0.0, 0.0, -1.0], 1));
+            /// ```
+            pub fn rotate_vector(&self, v: Vector<$T, 3>) -> Vector<$T, 3> {
+                Quaternion::from_vector(v).conjugate_by(*self).vector()
+            }
+
+            /// Rotate a 3D vector by a **unit** quaternion.
+            ///
+            /// Gives the same result as [rotate_vector](Quaternion::rotate_vector),
+            /// but instead of two quaternion multiplications it only needs
+            /// two cross products. For `q = [s, u]`:
+            /// ```text
+            /// t = 2(u x v)
+            /// v' = v + st + u x t
+            /// ```
+            ///
+            /// `q` must be a **unit** quaternion or the result is undefined.
+            ///
+            /// ```
+            /// # use std::f32::consts::PI;
+            /// # use quaternion::Quaternion;
+            /// # use lina::angle::Rad;
+            /// # use lina::v;
+            /// let q = Quaternion::<f32>::new_unit(Rad(PI/3.0), v![1.0, 2.0, 3.0]);
+            /// let v = v![4.0, 5.0, 6.0];
+            ///
+            /// assert!(q.rotate_vector_unit(v).approx_eq(&q.rotate_vector(v), 1e-5));
+            /// ```
+            pub fn rotate_vector_unit(&self, v: Vector<$T, 3>) -> Vector<$T, 3> {
+                let t = self.vector.cross(v) * 2.0;
+                v + t * self.scalar + self.vector.cross(t)
+            }
+        }
+    )*};
+}
+
+impl_rotate_vector_for_float_types!(f32, f64);
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use lina::{angle::Rad, v};
+
+    use crate::Quaternion;
+
+    #[test]
+    fn rotate_vector() {
+        // 90 degrees around the Z axis
+        let q = Quaternion::<f64>::new_unit(Rad(PI / 2.0), v![0.0, 0.0, 1.0]);
+        let rotated = q.rotate_vector(v![1.0, 0.0, 0.0]);
+
+        assert!(rotated.approx_eq(&v![0.0, 1.0, 0.0], 1e-15));
+    }
+
+    #[test]
+    fn rotate_vector_ignores_length() {
+        let q = Quaternion::<f64>::new(3.0, Rad(PI / 2.0), v![0.0, 0.0, 1.0]);
+        let rotated = q.rotate_vector(v![1.0, 0.0, 0.0]);
+
+        assert!(rotated.approx_eq(&v![0.0, 1.0, 0.0], 1e-15));
+    }
+
+    #[test]
+    fn rotate_vector_unit_matches_sandwich_product() {
+        let q = Quaternion::<f64>::new_unit(Rad(1.234), v![-1.0, 0.5, 2.0]);
+        for v in [v![1.0, 0.0, 0.0], v![0.0, -3.0, 0.0], v![4.0, 5.0, 6.0]] {
+            assert!(
+                q.rotate_vector_unit(v)
+                    .approx_eq(&q.rotate_vector(v), 1e-12)
+            );
+        }
+    }
+
+    #[test]
+    fn rotate_vector_unit_identity() {
+        let q = Quaternion::<f32>::default();
+        let v = v![1.0, 2.0, 3.0];
+
+        assert_eq!(q.rotate_vector_unit(v), v);
+    }
+}
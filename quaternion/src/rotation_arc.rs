@@ -0,0 +1,104 @@
+use lina::{v, vector::Vector};
+
+use crate::Quaternion;
+
+macro_rules! impl_rotation_arc_for_float_types {
+    ($($T: ty),* $(,)*) => {$(
+        impl Quaternion<$T> {
+            /// The unit quaternion turning the direction `from` onto the
+            /// direction `to` the shortest way, around the axis perpendicular
+            /// to both.
+            ///
+            /// Neither has to be normalized, but both need a length. Opposite
+            /// directions can be turned onto each other around any
+            /// perpendicular axis, one of them is picked.
+            ///
+            /// ```
+            /// # use std::f32::consts::PI;
+            /// # use quaternion::Quaternion;
+            /// # use lina::v;
+            /// # use float_eq::assert_float_eq;
+            /// let up = v![0.0, 1.0, 0.0];
+            /// let slope = v![1.0, 1.0, 0.0];
+            ///
+            /// let q = Quaternion::<f32>::rotation_arc(up, slope);
+            ///
+            /// let (axis, angle) = q.to_axis_angle();
+            /// assert_float_eq!(angle, PI / 4.0, abs <= 1e-6);
+            /// assert!(axis.approx_eq(&v![0.0, 0.0, -1.0], 1e-6));
+            /// assert!(q.rotate_vector_unit(up).approx_eq(&slope.normalized(), 1e-6));
+            /// ```
+            pub fn rotation_arc(from: Vector<$T, 3>, to: Vector<$T, 3>) -> Quaternion<$T> {
+                let (from, to) = (from.normalized(), to.normalized());
+                let cos = from * to;
+                if cos <= -1.0 + <$T>::EPSILON * 16.0 {
+                    // Half a turn around any axis perpendicular to `from`,
+                    // the one along the axis `from` is least aligned with.
+                    let other = if from[0].abs() < 0.5 {
+                        v![1.0, 0.0, 0.0]
+                    } else {
+                        v![0.0, 1.0, 0.0]
+                    };
+                    return Quaternion::from_vector(from.cross(other).normalized());
+                }
+
+                // With `from` and `to` at an angle theta this is
+                // [1 + cos(theta), sin(theta) * axis], which is
+                // [cos(theta/2), sin(theta/2) * axis] scaled by 2cos(theta/2).
+                Quaternion::new_parts(1.0 + cos, from.cross(to)).normalized()
+            }
+        }
+    )*};
+}
+
+impl_rotation_arc_for_float_types!(f32, f64);
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use float_eq::assert_float_eq;
+    use lina::v;
+
+    use crate::Quaternion;
+
+    #[test]
+    fn same_directions_do_not_turn() {
+        let q = Quaternion::<f64>::rotation_arc(v![1.0, 2.0, 3.0], v![2.0, 4.0, 6.0]);
+
+        assert_float_eq!(q.dot(&Quaternion::default()), 1.0, abs <= 1e-12);
+    }
+
+    #[test]
+    fn turns_the_shortest_way() {
+        let from = v![3.0, -1.0, 2.0];
+        let to = v![-1.0, 0.5, 4.0];
+        let q = Quaternion::<f64>::rotation_arc(from, to);
+
+        assert!(q.is_normalized(1e-12));
+        assert!(
+            q.rotate_vector_unit(from.normalized())
+                .approx_eq(&to.normalized(), 1e-12)
+        );
+        let (axis, angle) = q.to_axis_angle();
+        assert_float_eq!(
+            angle,
+            (from.normalized() * to.normalized()).acos(),
+            abs <= 1e-12
+        );
+        assert!((axis * from).abs() < 1e-12 && (axis * to).abs() < 1e-12);
+    }
+
+    #[test]
+    fn opposite_directions_turn_half_way_around() {
+        for from in [v![1.0, 0.0, 0.0], v![0.0, 2.0, 0.0], v![1.0, 1.0, 1.0]] {
+            let q = Quaternion::<f64>::rotation_arc(from, from * -1.0);
+
+            assert!(q.is_normalized(1e-12));
+            let (axis, angle) = q.to_axis_angle();
+            assert_float_eq!(angle, PI, abs <= 1e-12);
+            assert!((axis * from).abs() < 1e-12);
+            assert!(q.rotate_vector(from).approx_eq(&(from * -1.0), 1e-12));
+        }
+    }
+}
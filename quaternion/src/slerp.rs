@@ -0,0 +1,130 @@
+use crate::Quaternion;
+
+/// Above this cosine of the half angle between two quaternions the spherical
+/// interpolation divides by almost zero, so they are interpolated linearly.
+const LINEAR_THRESHOLD: f64 = 0.9995;
+
+macro_rules! impl_slerp_for_float_types {
+    ($($T: ty),* $(,)*) => {$(
+        impl Quaternion<$T> {
+            /// The dot product of the quaternions as 4 element vectors.
+            ///
+            /// For two unit quaternions it is the cosine of half the angle
+            /// between the rotations they represent.
+            ///
+            /// ```
+            /// # use quaternion::Quaternion;
+            /// # use lina::v;
+            /// let q = Quaternion::<f32>::new_parts(1.0, v![2.0, 3.0, 4.0]);
+            /// let p = Quaternion::<f32>::new_parts(4.0, v![3.0, 2.0, 1.0]);
+            ///
+            /// assert_eq!(q.dot(&p), 20.0);
+            /// ```
+            pub fn dot(&self, other: &Quaternion<$T>) -> $T {
+                self.scalar * other.scalar + self.vector * other.vector
+            }
+
+            /// Spherical linear interpolation from `self` at `t = 0` to
+            /// `other` at `t = 1`.
+            ///
+            /// For unit quaternions the result rotates at a constant angular
+            /// speed as `t` advances, along the shorter way around: `q` and
+            /// `-q` are the same rotation, so the one closer to `self` is
+            /// interpolated to. Nearly equal rotations are interpolated
+            /// linearly and normalized instead, avoiding the division by a
+            /// vanishing sine.
+            ///
+            /// ```
+            /// # use std::f32::consts::PI;
+            /// # use quaternion::Quaternion;
+            /// # use lina::angle::Rad;
+            /// # use lina::v;
+            /// # use float_eq::assert_float_eq;
+            /// let start = Quaternion::<f32>::new_unit(Rad(0.0), v![0.0, 1.0, 0.0]);
+            /// let end = Quaternion::<f32>::new_unit(Rad(PI / 2.0), v![0.0, 1.0, 0.0]);
+            ///
+            /// let (axis, angle) = start.slerp(&end, 0.5).to_axis_angle();
+            ///
+            /// assert_float_eq!(angle, PI / 4.0, ulps <= 2);
+            /// assert!(axis.ulps_eq(&v![0.0, 1.0, 0.0], 1));
+            /// ```
+            pub fn slerp(&self, other: &Quaternion<$T>, t: $T) -> Quaternion<$T> {
+                let cos = self.dot(other);
+                let (other, cos) = if cos < 0.0 {
+                    (*other * -1.0, -cos)
+                } else {
+                    (*other, cos)
+                };
+
+                if cos > LINEAR_THRESHOLD as $T {
+                    return (*self * (1.0 - t) + other * t).normalized();
+                }
+
+                let theta = cos.acos();
+                let sin = theta.sin();
+                *self * (((1.0 - t) * theta).sin() / sin) + other * ((t * theta).sin() / sin)
+            }
+        }
+    )*};
+}
+
+impl_slerp_for_float_types!(f32, f64);
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use float_eq::assert_float_eq;
+    use lina::{angle::Rad, v};
+
+    use crate::Quaternion;
+
+    #[test]
+    fn slerp_hits_the_ends() {
+        let start = Quaternion::<f64>::new_unit(Rad(0.3), v![1.0, 0.0, 0.0]);
+        let end = Quaternion::<f64>::new_unit(Rad(2.0), v![0.0, 1.0, 1.0]);
+
+        let at_start = start.slerp(&end, 0.0);
+        let at_end = start.slerp(&end, 1.0);
+        assert_float_eq!(at_start.scalar(), start.scalar(), abs <= 1e-12);
+        assert!(at_start.vector().approx_eq(&start.vector(), 1e-12));
+        assert_float_eq!(at_end.scalar(), end.scalar(), abs <= 1e-12);
+        assert!(at_end.vector().approx_eq(&end.vector(), 1e-12));
+    }
+
+    #[test]
+    fn slerp_rotates_at_a_constant_speed() {
+        let start = Quaternion::<f64>::new_unit(Rad(0.0), v![0.0, 0.0, 1.0]);
+        let end = Quaternion::<f64>::new_unit(Rad(PI * 0.9), v![0.0, 0.0, 1.0]);
+
+        for step in 0..=10 {
+            let t = step as f64 / 10.0;
+            let q = start.slerp(&end, t);
+            assert!(q.is_normalized(1e-12));
+            let (_, angle) = q.to_axis_angle();
+            assert_float_eq!(angle, PI * 0.9 * t, abs <= 1e-12);
+        }
+    }
+
+    #[test]
+    fn slerp_takes_the_shorter_way() {
+        // 350 degrees one way are 10 degrees the other way.
+        let start = Quaternion::<f64>::new_unit(Rad(0.0), v![0.0, 1.0, 0.0]);
+        let end = Quaternion::<f64>::new_unit(Rad(PI * 350.0 / 180.0), v![0.0, 1.0, 0.0]);
+
+        let halfway = start.slerp(&end, 0.5);
+        let expected = Quaternion::<f64>::new_unit(Rad(-PI * 5.0 / 180.0), v![0.0, 1.0, 0.0]);
+        assert_float_eq!(halfway.dot(&expected).abs(), 1.0, abs <= 1e-12);
+    }
+
+    #[test]
+    fn slerp_of_close_rotations_stays_normalized() {
+        let start = Quaternion::<f32>::new_unit(Rad(1.0), v![1.0, 2.0, 3.0]);
+        let end = Quaternion::<f32>::new_unit(Rad(1.0001), v![1.0, 2.0, 3.0]);
+
+        let q = start.slerp(&end, 0.5);
+        assert!(q.is_normalized(1e-6));
+        let (_, angle) = q.to_axis_angle();
+        assert_float_eq!(angle, 1.00005, abs <= 1e-5);
+    }
+}
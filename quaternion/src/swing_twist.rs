@@ -0,0 +1,102 @@
+use lina::vector::Vector;
+
+use crate::Quaternion;
+
+macro_rules! impl_swing_twist_for_float_types {
+    ($($T: ty),* $(,)*) => {$(
+        impl Quaternion<$T> {
+            /// Split the rotation into a twist around `axis` and the swing
+            /// tilting `axis` away, returned as `(swing, twist)` with:
+            /// ```text
+            /// q = swing * twist
+            /// ```
+            ///
+            /// The twist is the part of the rotation that turns around `axis`,
+            /// the swing turns around an axis perpendicular to it. With `axis`
+            /// pointing up, the twist of a camera's orientation is its yaw and
+            /// the swing its pitch and roll.
+            ///
+            /// `self` has to be a unit quaternion, `axis` is normalized
+            /// internally. A swing by half a turn leaves no twist to tell, the
+            /// twist is then the identity.
+            ///
+            /// ```
+            /// # use std::f32::consts::PI;
+            /// # use quaternion::Quaternion;
+            /// # use lina::angle::Rad;
+            /// # use lina::v;
+            /// # use float_eq::assert_float_eq;
+            /// let yaw = Quaternion::<f32>::new_unit(Rad(PI / 3.0), v![0.0, 1.0, 0.0]);
+            /// let pitch = Quaternion::<f32>::new_unit(Rad(PI / 5.0), v![1.0, 0.0, 0.0]);
+            ///
+            /// let (swing, twist) = (pitch * yaw).swing_twist(v![0.0, 1.0, 0.0]);
+            ///
+            /// let (axis, angle) = twist.to_axis_angle();
+            /// assert!(axis.approx_eq(&v![0.0, 1.0, 0.0], 1e-6));
+            /// assert_float_eq!(angle, PI / 3.0, abs <= 1e-6);
+            /// assert!(swing.vector().approx_eq(&pitch.vector(), 1e-6));
+            /// ```
+            pub fn swing_twist(&self, axis: Vector<$T, 3>) -> (Quaternion<$T>, Quaternion<$T>) {
+                let axis = axis.normalized();
+                // The rotation around the axis keeps only the part of the
+                // imaginary part along it.
+                let twist = Quaternion::new_parts(self.scalar, axis * (self.vector * axis));
+                let length = twist.length();
+                if length <= <$T>::EPSILON {
+                    return (*self, Quaternion::new_parts(1.0, Vector::default()));
+                }
+                let twist = twist / length;
+
+                (*self * twist.conjugate(), twist)
+            }
+        }
+    )*};
+}
+
+impl_swing_twist_for_float_types!(f32, f64);
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use float_eq::assert_float_eq;
+    use lina::{angle::Rad, v};
+
+    use crate::Quaternion;
+
+    #[test]
+    fn swing_and_twist_make_up_the_rotation() {
+        let axis = v![1.0, 1.0, 0.0];
+        let q = Quaternion::<f64>::new_unit(Rad(2.5), v![1.0, -2.0, 3.0]);
+        let (swing, twist) = q.swing_twist(axis);
+
+        let product = swing * twist;
+        assert_float_eq!(product.scalar(), q.scalar(), abs <= 1e-12);
+        assert!(product.vector().approx_eq(&q.vector(), 1e-12));
+        // The twist turns around the axis, the swing perpendicular to it.
+        assert!(twist.vector().cross(axis).length() < 1e-12);
+        assert!((swing.vector() * axis).abs() < 1e-12);
+    }
+
+    #[test]
+    fn pure_rotations_are_all_swing_or_all_twist() {
+        let around = Quaternion::<f64>::new_unit(Rad(PI / 4.0), v![0.0, 0.0, 2.0]);
+        let (swing, twist) = around.swing_twist(v![0.0, 0.0, 1.0]);
+        assert_float_eq!(twist.dot(&around), 1.0, abs <= 1e-12);
+        assert_float_eq!(swing.scalar(), 1.0, abs <= 1e-12);
+
+        let tilt = Quaternion::<f64>::new_unit(Rad(PI / 4.0), v![1.0, 0.0, 0.0]);
+        let (swing, twist) = tilt.swing_twist(v![0.0, 0.0, 1.0]);
+        assert_eq!(swing, tilt);
+        assert_eq!(twist, Quaternion::default());
+    }
+
+    #[test]
+    fn half_a_turn_of_swing_has_no_twist() {
+        let flip = Quaternion::<f64>::new_unit(Rad(PI), v![1.0, 0.0, 0.0]);
+        let (swing, twist) = flip.swing_twist(v![0.0, 1.0, 0.0]);
+
+        assert_eq!(swing, flip);
+        assert_eq!(twist, Quaternion::default());
+    }
+}
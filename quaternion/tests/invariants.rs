@@ -0,0 +1,67 @@
+//! Algebraic invariants checked on random inputs, so optimizations of the
+//! math can't silently break it.
+
+use lina::{angle::Rad, vector::Vector};
+use proptest::{array, prelude::*};
+use quaternion::Quaternion;
+
+fn vector(range: std::ops::Range<f64>) -> impl Strategy<Value = Vector<f64, 3>> {
+    array::uniform(range).prop_map(Vector::from_array)
+}
+
+fn quaternion() -> impl Strategy<Value = Quaternion<f64>> {
+    (-10.0..10.0, vector(-10.0..10.0))
+        .prop_map(|(scalar, vector)| Quaternion::new_parts(scalar, vector))
+}
+
+/// Rotations by any angle around any axis.
+fn rotation() -> impl Strategy<Value = Quaternion<f64>> {
+    (-10.0..10.0, vector(-1.0..1.0))
+        .prop_filter("the axis needs a direction", |(_, axis)| {
+            axis.length() > 1e-3
+        })
+        .prop_map(|(angle, axis)| Quaternion::<f64>::new_unit(Rad(angle), axis))
+}
+
+proptest! {
+    #[test]
+    fn products_with_the_conjugate_are_real(q in quaternion()) {
+        let product = q * q.conjugate();
+        prop_assert!(product.vector().length() <= 1e-12 * q.length_squared().max(1.0));
+        prop_assert!((product.scalar() - q.length_squared()).abs() <= 1e-12 * q.length_squared().max(1.0));
+    }
+
+    #[test]
+    fn multiplication_is_associative(a in quaternion(), b in quaternion(), c in quaternion()) {
+        let (lhs, rhs) = ((a * b) * c, a * (b * c));
+        prop_assert!((lhs - rhs).length() <= 1e-12 * lhs.length().max(1.0));
+    }
+
+    #[test]
+    fn lengths_multiply(a in quaternion(), b in quaternion()) {
+        let product = (a * b).length();
+        prop_assert!((product - a.length() * b.length()).abs() <= 1e-12 * product.max(1.0));
+    }
+
+    #[test]
+    fn normalized_quaternions_have_unit_length(q in quaternion()) {
+        prop_assume!(q.length() > 1e-3);
+        prop_assert!(q.normalized().is_normalized(1e-12));
+    }
+
+    #[test]
+    fn rotations_stay_unit_and_keep_lengths(a in rotation(), b in rotation(), v in vector(-10.0..10.0)) {
+        prop_assert!((a * b).is_normalized(1e-12));
+        let rotated = a.rotate_vector(v);
+        prop_assert!((rotated.length() - v.length()).abs() <= 1e-12 * v.length().max(1.0));
+        prop_assert!(a.conjugate().rotate_vector(rotated).approx_eq(&v, 1e-12));
+    }
+
+    #[test]
+    fn swing_and_twist_recompose(q in rotation(), axis in vector(-1.0..1.0)) {
+        prop_assume!(axis.length() > 1e-3);
+        let (swing, twist) = q.swing_twist(axis);
+        prop_assert!((swing * twist - q).length() <= 1e-12);
+        prop_assert!(twist.vector().cross(axis.normalized()).length() <= 1e-9);
+    }
+}
@@ -0,0 +1,157 @@
+//! Choosing the adapter, i.e. the GPU, and the device the renderer runs on.
+use std::fmt;
+
+use wgpu::{
+    Adapter, AdapterInfo, Backend, Backends, Device, DownlevelFlags, ExperimentalFeatures,
+    Features, Instance, Limits, PowerPreference, Queue, Surface,
+};
+
+use crate::gpu::GpuInitError;
+
+/// Features the [Scene](crate::scene::Scene) makes use of when available:
+/// wireframe rendering, the adapter's own multisampling capabilities and
+/// GPU timing.
+const SCENE_FEATURES: Features = Features::POLYGON_MODE_LINE
+    .union(Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES)
+    .union(Features::TIMESTAMP_QUERY);
+
+/// Which adapter to render with.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum AdapterChoice {
+    /// Let wgpu pick based on the [power preference](AdapterSettings::power_preference).
+    #[default]
+    Auto,
+    /// The first adapter whose name contains the string, ignoring case.
+    Name(String),
+    /// The adapter at the index of [available_adapters].
+    Index(usize),
+}
+
+impl fmt::Display for AdapterChoice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AdapterChoice::Auto => write!(f, "automatic"),
+            AdapterChoice::Name(name) => write!(f, "named \"{name}\""),
+            AdapterChoice::Index(index) => write!(f, "#{index}"),
+        }
+    }
+}
+
+/// How the adapter and device are chosen, part of the
+/// [RenderSettings](crate::gpu::RenderSettings).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AdapterSettings {
+    /// Prefer an integrated or a discrete GPU, only used with [AdapterChoice::Auto].
+    pub power_preference: PowerPreference,
+    /// Only consider adapters of this backend, `None` allows every backend.
+    pub backend: Option<Backend>,
+    pub adapter: AdapterChoice,
+    /// Features enabled if the adapter supports them, in addition to the
+    /// ones the scene uses anyway.
+    pub optional_features: Features,
+    /// Limits the device has to support, device creation fails otherwise.
+    ///
+    /// `None` requests the downlevel defaults, or the WebGL 2 ones without
+    /// compute shaders, raised to the adapter's texture size limits.
+    pub required_limits: Option<Limits>,
+}
+
+impl AdapterSettings {
+    /// Create an instance limited to the selected backend.
+    ///
+    /// In the browser WebGPU is used where it is supported, WebGL 2 otherwise.
+    pub async fn create_instance(&self) -> Instance {
+        let descriptor = wgpu::InstanceDescriptor {
+            backends: self.backend.map_or(Backends::all(), Backends::from),
+            ..Default::default()
+        };
+        #[cfg(target_arch = "wasm32")]
+        return wgpu::util::new_instance_with_webgpu_detection(&descriptor).await;
+        #[cfg(not(target_arch = "wasm32"))]
+        Instance::new(&descriptor)
+    }
+
+    /// Find the chosen adapter, which has to support `surface` if there is one.
+    pub async fn select_adapter(
+        &self,
+        instance: &Instance,
+        surface: Option<&Surface<'_>>,
+    ) -> Result<Adapter, GpuInitError> {
+        let adapter = match &self.adapter {
+            AdapterChoice::Auto => instance
+                .request_adapter(&wgpu::RequestAdapterOptions {
+                    power_preference: self.power_preference,
+                    force_fallback_adapter: false,
+                    compatible_surface: surface,
+                })
+                .await
+                .map_err(GpuInitError::AdapterNotFound)?,
+            choice => {
+                let adapters = instance.enumerate_adapters(Backends::all()).await;
+                let adapter = match choice {
+                    AdapterChoice::Name(name) => {
+                        let name = name.to_lowercase();
+                        adapters
+                            .into_iter()
+                            .find(|adapter| adapter.get_info().name.to_lowercase().contains(&name))
+                    }
+                    AdapterChoice::Index(index) => adapters.into_iter().nth(*index),
+                    AdapterChoice::Auto => unreachable!(),
+                };
+                let adapter =
+                    adapter.ok_or_else(|| GpuInitError::UnknownAdapter(choice.clone()))?;
+                if surface.is_some_and(|surface| !adapter.is_surface_supported(surface)) {
+                    return Err(GpuInitError::UnsupportedSurface);
+                }
+                adapter
+            }
+        };
+        let info = adapter.get_info();
+        tracing::info!(name = info.name, backend = %info.backend, "Selected adapter");
+        Ok(adapter)
+    }
+
+    /// Create the logical device and command queue the [Scene](crate::scene::Scene) needs.
+    #[tracing::instrument(skip_all, fields(adapter = adapter.get_info().name))]
+    pub async fn request_device(&self, adapter: &Adapter) -> Result<(Device, Queue), GpuInitError> {
+        let required_limits = self.required_limits.clone().unwrap_or_else(|| {
+            let compute = adapter
+                .get_downlevel_capabilities()
+                .flags
+                .contains(DownlevelFlags::COMPUTE_SHADERS);
+            let limits = if compute {
+                Limits::downlevel_defaults()
+            } else {
+                Limits::downlevel_webgl2_defaults()
+            };
+            limits.using_resolution(adapter.limits())
+        });
+        let (device, queue) = adapter
+            .request_device(&wgpu::DeviceDescriptor {
+                label: Some("gpu_device"),
+                required_features: adapter.features() & (SCENE_FEATURES | self.optional_features),
+                required_limits,
+                memory_hints: wgpu::MemoryHints::Performance,
+                trace: wgpu::Trace::Off,
+                experimental_features: ExperimentalFeatures::disabled(),
+            })
+            .await
+            .map_err(GpuInitError::RequestDevice)?;
+        tracing::info!(features = ?device.features(), "Prepared device");
+        Ok((device, queue))
+    }
+}
+
+/// The adapters that can be chosen with `settings`, e.g. for a settings menu.
+///
+/// The position in the list is the index for [AdapterChoice::Index].
+pub async fn available_adapters(settings: &AdapterSettings) -> Vec<AdapterInfo> {
+    settings
+        .create_instance()
+        .await
+        .enumerate_adapters(Backends::all())
+        .await
+        .iter()
+        .map(Adapter::get_info)
+        .collect()
+}
@@ -0,0 +1,103 @@
+use lina::vector::Vector;
+use quaternion::Quaternion;
+
+use super::{Curve, Skeleton};
+use crate::components::Transform;
+
+/// Which part of a joint's pose a [Channel] animates.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Keyframes {
+    Translation(Curve<Vector<f32, 3>>),
+    Rotation(Curve<Quaternion<f32>>),
+    Scale(Curve<Vector<f32, 3>>),
+}
+
+/// Animates one part of the pose of a joint.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Channel {
+    /// The index of the joint in the [Skeleton].
+    pub joint: usize,
+    pub keyframes: Keyframes,
+}
+
+/// Keyframed poses of the joints of a [Skeleton].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Animation {
+    pub name: String,
+    channels: Vec<Channel>,
+    duration: f32,
+}
+
+impl Animation {
+    pub fn new(name: &str, channels: Vec<Channel>) -> Self {
+        let duration = channels
+            .iter()
+            .map(|channel| match &channel.keyframes {
+                Keyframes::Translation(curve) | Keyframes::Scale(curve) => curve.duration(),
+                Keyframes::Rotation(curve) => curve.duration(),
+            })
+            .fold(0.0, f32::max);
+        Self {
+            name: name.to_string(),
+            channels,
+            duration,
+        }
+    }
+
+    /// The time of the last keyframe in seconds.
+    pub fn duration(&self) -> f32 {
+        self.duration
+    }
+
+    /// Replace `poses` with the local pose of every joint of `skeleton` at
+    /// `time`. The parts of the poses without a channel are the rest pose.
+    pub fn sample(&self, skeleton: &Skeleton, time: f32, poses: &mut Vec<Transform<f32>>) {
+        poses.clear();
+        poses.extend(skeleton.joints().iter().map(|joint| joint.rest));
+        for channel in &self.channels {
+            let Some(pose) = poses.get_mut(channel.joint) else {
+                continue;
+            };
+            match &channel.keyframes {
+                Keyframes::Translation(curve) => pose.translation = curve.sample(time),
+                Keyframes::Rotation(curve) => pose.rotation = curve.sample(time),
+                Keyframes::Scale(curve) => pose.scale = curve.sample(time),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::PI;
+
+    use lina::{angle::Rad, v};
+
+    use super::*;
+    use crate::animation::tests::arm;
+
+    #[test]
+    fn unanimated_parts_keep_the_rest_pose() {
+        let skeleton = arm();
+        let turn = |angle: f32| Quaternion::<f32>::new_unit(Rad(angle), v![0.0, 1.0, 0.0]);
+        let animation = Animation::new(
+            "twist",
+            vec![Channel {
+                joint: 1,
+                keyframes: Keyframes::Rotation(Curve::new(
+                    vec![0.0, 1.0],
+                    vec![turn(0.0), turn(PI / 2.0)],
+                )),
+            }],
+        );
+        assert_eq!(animation.duration(), 1.0);
+
+        let mut poses = Vec::new();
+        animation.sample(&skeleton, 0.5, &mut poses);
+        assert_eq!(poses[0], skeleton.joints()[0].rest);
+        assert_eq!(poses[1].translation, v![0.0, 1.0, 0.0]);
+        let (axis, angle) = poses[1].rotation.to_axis_angle();
+        assert!((angle - PI / 4.0).abs() < 1e-5);
+        assert!(axis.approx_eq(&v![0.0, 1.0, 0.0], 1e-5));
+    }
+}
@@ -0,0 +1,292 @@
+use std::ops::{Add, Mul};
+
+use graphic::color::Color;
+use lina::vector::Vector;
+use quaternion::Quaternion;
+
+/// A value a [Curve] can animate.
+///
+/// Cubic interpolation sums up values and tangents weighted by `f32`s.
+pub trait Animatable: Copy + Add<Output = Self> + Mul<f32, Output = Self> {
+    /// The value between `self` at `t = 0` and `other` at `t = 1`, linearly
+    /// unless the type knows better.
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        *self * (1.0 - t) + *other * t
+    }
+
+    /// Turn the weighted sum of a cubic interpolation back into a valid
+    /// value.
+    fn normalize(self) -> Self {
+        self
+    }
+}
+
+impl Animatable for f32 {}
+
+impl Animatable for Color {}
+
+impl<const N: usize> Animatable for Vector<f32, N> {}
+
+impl Animatable for Quaternion<f32> {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        self.slerp(other, t)
+    }
+
+    fn normalize(self) -> Self {
+        self.normalized()
+    }
+}
+
+/// How the values between two keyframes are found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Interpolation {
+    /// The earlier keyframe's value is kept until the next keyframe.
+    Step,
+    /// Linearly, rotations with slerp.
+    #[default]
+    Linear,
+    /// Along a cubic Hermite spline, smoothly passing through the keyframes
+    /// with the slope of their tangents.
+    Cubic,
+}
+
+/// What a [Curve] does outside of its keyframes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LoopMode {
+    /// Keep the value of the first keyframe before and the last one after.
+    #[default]
+    Once,
+    /// Start over at the first keyframe after the last.
+    Repeat,
+    /// Play backwards after the last keyframe, then forwards again.
+    PingPong,
+}
+
+impl LoopMode {
+    /// `time` mapped into `0..=duration`.
+    pub fn apply(self, time: f32, duration: f32) -> f32 {
+        if duration <= 0.0 {
+            return 0.0;
+        }
+        match self {
+            LoopMode::Once => time.clamp(0.0, duration),
+            LoopMode::Repeat => time.rem_euclid(duration),
+            LoopMode::PingPong => {
+                let time = time.rem_euclid(2.0 * duration);
+                if time > duration {
+                    2.0 * duration - time
+                } else {
+                    time
+                }
+            }
+        }
+    }
+}
+
+/// Values at points in time, the keyframes, and what lies between them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Curve<T> {
+    // In seconds, ascending.
+    times: Vec<f32>,
+    values: Vec<T>,
+    // The in and out tangent of every keyframe for cubic interpolation,
+    // otherwise empty.
+    tangents: Vec<(T, T)>,
+    interpolation: Interpolation,
+    loop_mode: LoopMode,
+}
+
+impl<T: Animatable> Curve<T> {
+    /// Keyframes with a value for each of the `times`, interpolated linearly
+    /// and played once.
+    ///
+    /// Panics if there are no keyframes, the lengths differ or the times
+    /// aren't ascending.
+    pub fn new(times: Vec<f32>, values: Vec<T>) -> Self {
+        assert!(!times.is_empty(), "a curve needs a keyframe");
+        assert_eq!(times.len(), values.len(), "one value per keyframe");
+        assert!(
+            times.is_sorted(),
+            "the keyframes have to be in chronological order"
+        );
+        Self {
+            times,
+            values,
+            tangents: Vec::new(),
+            interpolation: Interpolation::Linear,
+            loop_mode: LoopMode::Once,
+        }
+    }
+
+    /// Keyframes interpolated cubically with an in and an out tangent each,
+    /// the change of the value per second.
+    ///
+    /// Panics like [new](Curve::new), or if the number of tangents differs.
+    pub fn cubic(times: Vec<f32>, values: Vec<T>, tangents: Vec<(T, T)>) -> Self {
+        assert_eq!(times.len(), tangents.len(), "two tangents per keyframe");
+        Self {
+            tangents,
+            interpolation: Interpolation::Cubic,
+            ..Self::new(times, values)
+        }
+    }
+
+    /// Switch to `interpolation`. Tangents pointing from the previous to the
+    /// next keyframe are made up when switching to cubic interpolation,
+    /// a Catmull-Rom spline.
+    pub fn with_interpolation(self, interpolation: Interpolation) -> Self {
+        let tangents = match interpolation {
+            Interpolation::Cubic if self.tangents.is_empty() => self.catmull_rom_tangents(),
+            Interpolation::Cubic => self.tangents,
+            _ => Vec::new(),
+        };
+        Self {
+            tangents,
+            interpolation,
+            ..self
+        }
+    }
+
+    pub fn with_loop_mode(self, loop_mode: LoopMode) -> Self {
+        Self { loop_mode, ..self }
+    }
+
+    /// The time of the last keyframe.
+    pub fn duration(&self) -> f32 {
+        *self.times.last().expect("a curve has a keyframe")
+    }
+
+    /// The value at `time`, looped between the first and the last keyframe
+    /// by the loop mode.
+    pub fn sample(&self, time: f32) -> T {
+        let start = self.times[0];
+        let time = start + self.loop_mode.apply(time - start, self.duration() - start);
+
+        let next = self.times.partition_point(|keyframe| *keyframe <= time);
+        if next == 0 {
+            return self.values[0];
+        }
+        if next == self.times.len() {
+            return self.values[next - 1];
+        }
+
+        let previous = next - 1;
+        let (start, end) = (self.times[previous], self.times[next]);
+        let t = (time - start) / (end - start);
+        let (from, to) = (self.values[previous], self.values[next]);
+        match self.interpolation {
+            Interpolation::Step => from,
+            Interpolation::Linear => from.interpolate(&to, t),
+            Interpolation::Cubic => {
+                let (_, out_tangent) = self.tangents[previous];
+                let (in_tangent, _) = self.tangents[next];
+                let (t2, t3) = (t * t, t * t * t);
+                let delta = end - start;
+                let sum = from * (2.0 * t3 - 3.0 * t2 + 1.0)
+                    + out_tangent * ((t3 - 2.0 * t2 + t) * delta)
+                    + to * (-2.0 * t3 + 3.0 * t2)
+                    + in_tangent * ((t3 - t2) * delta);
+                sum.normalize()
+            }
+        }
+    }
+
+    /// The slope between the neighbors of every keyframe, one sided at the
+    /// ends.
+    fn catmull_rom_tangents(&self) -> Vec<(T, T)> {
+        let last = self.times.len() - 1;
+        (0..=last)
+            .map(|index| {
+                let (before, after) = (index.saturating_sub(1), (index + 1).min(last));
+                let span = self.times[after] - self.times[before];
+                let tangent = if span > 0.0 {
+                    (self.values[after] + self.values[before] * -1.0) * (1.0 / span)
+                } else {
+                    self.values[index] * 0.0
+                };
+                (tangent, tangent)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::PI;
+
+    use lina::{angle::Rad, v};
+
+    use super::*;
+
+    #[test]
+    fn keyframes_are_interpolated() {
+        let curve = Curve::new(vec![1.0, 2.0, 4.0], vec![0.0_f32, 10.0, 20.0]);
+        assert_eq!(curve.sample(0.0), 0.0);
+        assert_eq!(curve.sample(1.5), 5.0);
+        assert_eq!(curve.sample(2.0), 10.0);
+        assert_eq!(curve.sample(3.0), 15.0);
+        assert_eq!(curve.sample(9.0), 20.0);
+
+        let curve = curve.with_interpolation(Interpolation::Step);
+        assert_eq!(curve.sample(1.9), 0.0);
+        assert_eq!(curve.sample(3.9), 10.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "chronological order")]
+    fn keyframes_are_in_order() {
+        Curve::new(vec![1.0, 0.5], vec![0.0, 1.0]);
+    }
+
+    #[test]
+    fn cubic_curves_follow_the_tangents() {
+        // Flat at both ends, the middle is half way but the quarters aren't.
+        let curve = Curve::cubic(vec![0.0, 2.0], vec![0.0_f32, 1.0], vec![(0.0, 0.0); 2]);
+        assert_eq!(curve.sample(0.0), 0.0);
+        assert_eq!(curve.sample(1.0), 0.5);
+        assert_eq!(curve.sample(2.0), 1.0);
+        assert!(curve.sample(0.5) < 0.25);
+        assert!(curve.sample(1.5) > 0.75);
+
+        // A straight line has the slope of the line as tangents.
+        let line = Curve::new(
+            vec![0.0, 1.0, 3.0],
+            vec![v![0.0, 0.0], v![1.0, 2.0], v![3.0, 6.0]],
+        )
+        .with_interpolation(Interpolation::Cubic);
+        for time in [0.25, 0.5, 1.5, 2.75] {
+            let value = line.sample(time);
+            assert!(value.approx_eq(&v![time, 2.0 * time], 1e-5));
+        }
+    }
+
+    #[test]
+    fn cubic_rotations_stay_normalized() {
+        let turn = |angle: f32| Quaternion::<f32>::new_unit(Rad(angle), v![0.0, 1.0, 0.0]);
+        let curve = Curve::new(vec![0.0, 1.0, 2.0], vec![turn(0.0), turn(1.0), turn(3.0)])
+            .with_interpolation(Interpolation::Cubic);
+        for step in 0..=8 {
+            assert!(curve.sample(step as f32 / 4.0).is_normalized(1e-6));
+        }
+        let (_, angle) = curve.sample(1.0).to_axis_angle();
+        assert!((angle - 1.0).abs() < 1e-5);
+        let (_, angle) = curve.sample(1.5).to_axis_angle();
+        assert!(angle > 1.0 && angle < PI);
+    }
+
+    #[test]
+    fn loop_modes() {
+        let curve = Curve::new(vec![1.0, 3.0], vec![0.0_f32, 2.0]);
+        assert_eq!(curve.sample(0.0), 0.0);
+        assert_eq!(curve.sample(5.0), 2.0);
+
+        let repeating = curve.clone().with_loop_mode(LoopMode::Repeat);
+        assert_eq!(repeating.sample(3.5), 0.5);
+        assert_eq!(repeating.sample(0.5), 1.5);
+
+        let ping_pong = curve.with_loop_mode(LoopMode::PingPong);
+        assert_eq!(ping_pong.sample(3.5), 1.5);
+        assert_eq!(ping_pong.sample(5.5), 0.5);
+        assert_eq!(ping_pong.sample(0.5), 0.5);
+    }
+}
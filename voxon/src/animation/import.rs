@@ -0,0 +1,322 @@
+use std::{collections::HashMap, fmt};
+
+use gltf::{
+    Node,
+    animation::{Interpolation as GltfInterpolation, util::ReadOutputs},
+};
+use lina::{matrix::Matrix, v, vector::Vector};
+use quaternion::Quaternion;
+
+use super::{Animatable, Animation, Channel, Curve, Interpolation, Joint, Keyframes, Skeleton};
+use crate::{
+    components::Transform,
+    mesh::{Mesh, SkinnedVertex, Vertex},
+};
+
+/// A skinned mesh with its skeleton and animations, see [import_gltf].
+pub struct SkinnedModel {
+    pub mesh: Mesh<SkinnedVertex>,
+    pub skeleton: Skeleton,
+    pub animations: Vec<Animation>,
+}
+
+#[derive(Debug)]
+pub enum ImportError {
+    Gltf(gltf::Error),
+    /// There is no node with both a mesh and a skin.
+    NoSkinnedMesh,
+    /// A primitive of the mesh lacks a vertex attribute.
+    MissingAttribute(&'static str),
+    /// The keyframes of the named animation are broken.
+    InvalidKeyframes(String),
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportError::Gltf(err) => write!(f, "invalid glTF: {err}"),
+            ImportError::NoSkinnedMesh => write!(f, "no skinned mesh"),
+            ImportError::MissingAttribute(name) => write!(f, "missing vertex attribute {name}"),
+            ImportError::InvalidKeyframes(name) => {
+                write!(f, "invalid keyframes in animation \"{name}\"")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ImportError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ImportError::Gltf(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<gltf::Error> for ImportError {
+    fn from(err: gltf::Error) -> Self {
+        ImportError::Gltf(err)
+    }
+}
+
+/// Import the first skinned mesh of a glTF or GLB file, with the skeleton
+/// of its skin and the animations moving the joints.
+///
+/// Buffers have to be embedded, materials and morph targets are ignored.
+pub fn import_gltf(bytes: &[u8]) -> Result<SkinnedModel, ImportError> {
+    let (document, buffers, _) = gltf::import_slice(bytes)?;
+    let buffer_data = |buffer: gltf::Buffer| buffers.get(buffer.index()).map(|data| &data.0[..]);
+
+    let node = document
+        .nodes()
+        .find(|node| node.mesh().is_some() && node.skin().is_some())
+        .ok_or(ImportError::NoSkinnedMesh)?;
+    let (Some(mesh), Some(skin)) = (node.mesh(), node.skin()) else {
+        unreachable!("the node was found by its mesh and skin");
+    };
+
+    // The joints are reordered so that parents come first.
+    let nodes = skin.joints().collect::<Vec<_>>();
+    let mut parents = HashMap::new();
+    for parent in document.nodes() {
+        for child in parent.children() {
+            parents.insert(child.index(), parent.index());
+        }
+    }
+    let skin_index = |node: usize| nodes.iter().position(|joint| joint.index() == node);
+    let parent_of = |joint: &Node| parents.get(&joint.index()).copied().and_then(skin_index);
+    let depth = |mut joint: usize| {
+        let mut depth = 0;
+        while let Some(parent) = parent_of(&nodes[joint]) {
+            joint = parent;
+            depth += 1;
+        }
+        depth
+    };
+    let mut order = (0..nodes.len()).collect::<Vec<_>>();
+    order.sort_by_key(|joint| depth(*joint));
+    let mut reordered = vec![0; nodes.len()];
+    for (new, old) in order.iter().enumerate() {
+        reordered[*old] = new;
+    }
+
+    let inverse_binds = skin
+        .reader(buffer_data)
+        .read_inverse_bind_matrices()
+        .map(|matrices| matrices.map(column_major).collect::<Vec<_>>());
+    let joints = order
+        .iter()
+        .map(|old| {
+            let joint = &nodes[*old];
+            let (translation, [x, y, z, w], scale) = joint.transform().decomposed();
+            Joint {
+                name: joint.name().unwrap_or_default().to_string(),
+                parent: parent_of(joint).map(|parent| reordered[parent]),
+                rest: Transform {
+                    translation: Vector::from_array(translation),
+                    rotation: Quaternion::new_parts(w, v![x, y, z]),
+                    scale: Vector::from_array(scale),
+                },
+                inverse_bind: inverse_binds
+                    .as_ref()
+                    .and_then(|matrices| matrices.get(*old).copied())
+                    .unwrap_or_else(Matrix::identity),
+            }
+        })
+        .collect();
+
+    let mut vertices = Vec::new();
+    let mut indices = Vec::new();
+    for primitive in mesh.primitives() {
+        let reader = primitive.reader(buffer_data);
+        let positions = reader
+            .read_positions()
+            .ok_or(ImportError::MissingAttribute("POSITION"))?;
+        let mut normals = reader
+            .read_normals()
+            .ok_or(ImportError::MissingAttribute("NORMAL"))?;
+        let mut joints = reader
+            .read_joints(0)
+            .ok_or(ImportError::MissingAttribute("JOINTS_0"))?
+            .into_u16();
+        let mut weights = reader
+            .read_weights(0)
+            .ok_or(ImportError::MissingAttribute("WEIGHTS_0"))?
+            .into_f32();
+        let mut uvs = reader.read_tex_coords(0).map(|uvs| uvs.into_f32());
+
+        let first = vertices.len() as u32;
+        for [x, y, z] in positions {
+            let vertex = Vertex::new(
+                v![x, y, z, 1.0],
+                Vector::from_array(normals.next().unwrap_or_default()),
+                Vector::from_array(uvs.as_mut().and_then(Iterator::next).unwrap_or_default()),
+            );
+            let joints = joints
+                .next()
+                .unwrap_or_default()
+                .map(|joint| reordered.get(joint as usize).copied().unwrap_or_default() as u32);
+            let weights = Vector::from_array(weights.next().unwrap_or_default());
+            vertices.push(SkinnedVertex::new(vertex, joints, weights));
+        }
+        match reader.read_indices() {
+            Some(read) => indices.extend(read.into_u32().map(|index| first + index)),
+            None => indices.extend(first..vertices.len() as u32),
+        }
+    }
+
+    let mut animations = Vec::new();
+    for (index, animation) in document.animations().enumerate() {
+        let name = animation
+            .name()
+            .map_or_else(|| format!("animation {index}"), str::to_string);
+        let mut channels = Vec::new();
+        for channel in animation.channels() {
+            let Some(joint) = skin_index(channel.target().node().index()) else {
+                continue;
+            };
+            let interpolation = channel.sampler().interpolation();
+            let reader = channel.reader(buffer_data);
+            let (Some(times), Some(outputs)) = (reader.read_inputs(), reader.read_outputs()) else {
+                return Err(ImportError::InvalidKeyframes(name));
+            };
+            let times = times.collect::<Vec<_>>();
+            let vectors = |values: gltf::animation::util::Translations| {
+                keyframes(
+                    &times,
+                    values.map(Vector::from_array).collect(),
+                    interpolation,
+                )
+            };
+            let keyframes = match outputs {
+                ReadOutputs::Translations(values) => vectors(values).map(Keyframes::Translation),
+                ReadOutputs::Rotations(values) => {
+                    let values = values
+                        .into_f32()
+                        .map(|[x, y, z, w]| Quaternion::new_parts(w, v![x, y, z]))
+                        .collect();
+                    keyframes(&times, values, interpolation).map(Keyframes::Rotation)
+                }
+                ReadOutputs::Scales(values) => vectors(values).map(Keyframes::Scale),
+                ReadOutputs::MorphTargetWeights(_) => continue,
+            };
+            let keyframes = keyframes.ok_or_else(|| ImportError::InvalidKeyframes(name.clone()))?;
+            channels.push(Channel {
+                joint: reordered[joint],
+                keyframes,
+            });
+        }
+        if !channels.is_empty() {
+            animations.push(Animation::new(&name, channels));
+        }
+    }
+
+    Ok(SkinnedModel {
+        mesh: Mesh::new(vertices, indices),
+        skeleton: Skeleton::new(joints),
+        animations,
+    })
+}
+
+/// A glTF matrix, which is stored column by column.
+fn column_major(columns: [[f32; 4]; 4]) -> Matrix<f32, 4, 4> {
+    Matrix::from_matrix(columns).transpose()
+}
+
+/// The curve of the keyframes at `times`, `None` if they don't fit together.
+///
+/// Cubic splines store an in tangent, the value and an out tangent for
+/// every keyframe.
+fn keyframes<T: Animatable>(
+    times: &[f32],
+    values: Vec<T>,
+    interpolation: GltfInterpolation,
+) -> Option<Curve<T>> {
+    let fits = |count| !times.is_empty() && times.len() == count && times.is_sorted();
+    match interpolation {
+        GltfInterpolation::Step | GltfInterpolation::Linear => {
+            let interpolation = match interpolation {
+                GltfInterpolation::Step => Interpolation::Step,
+                _ => Interpolation::Linear,
+            };
+            fits(values.len())
+                .then(|| Curve::new(times.to_vec(), values).with_interpolation(interpolation))
+        }
+        GltfInterpolation::CubicSpline => {
+            if !values.len().is_multiple_of(3) || !fits(values.len() / 3) {
+                return None;
+            }
+            let (values, tangents) = values
+                .chunks_exact(3)
+                .map(|keyframe| (keyframe[1], (keyframe[0], keyframe[2])))
+                .unzip();
+            Some(Curve::cubic(times.to_vec(), values, tangents))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SWAY: &[u8] = include_bytes!("../../assets/sway.gltf");
+
+    #[test]
+    fn imports_the_skeleton_and_the_animations() {
+        let model = import_gltf(SWAY).unwrap();
+
+        let joints = model.skeleton.joints();
+        let names = joints
+            .iter()
+            .map(|joint| &joint.name[..])
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["root", "middle", "tip"]);
+        let parents = joints.iter().map(|joint| joint.parent).collect::<Vec<_>>();
+        assert_eq!(parents, [None, Some(0), Some(1)]);
+        assert_eq!(joints[2].rest.translation, v![0.0, 0.7, 0.0]);
+
+        // The bind pose is the rest pose.
+        let mut matrices = Vec::new();
+        model
+            .skeleton
+            .skinning_matrices(&model.skeleton.rest_pose(), &mut matrices);
+        for matrix in &matrices {
+            assert!(matrix.approx_eq(&Matrix::identity(), 1e-6));
+        }
+
+        assert_eq!(model.animations.len(), 1);
+        assert_eq!(model.animations[0].name, "sway");
+        assert_eq!(model.animations[0].duration(), 4.0);
+    }
+
+    #[test]
+    fn imports_the_skinned_vertices() {
+        let model = import_gltf(SWAY).unwrap();
+        assert_eq!(model.mesh.vertices().len(), 76);
+        assert_eq!(model.mesh.indices().len(), 198);
+
+        // The bottom of the first side follows the root alone.
+        let vertex = Vertex::new(v![-0.15, 0.0, 0.15, 1.0], v![0.0, 0.0, 1.0], v![0.0, 2.0]);
+        assert_eq!(
+            model.mesh.vertices()[0],
+            SkinnedVertex::new(vertex, [0, 1, 0, 0], v![1.0, 0.0, 0.0, 0.0])
+        );
+    }
+
+    #[test]
+    fn keyframes_have_to_fit_together() {
+        let linear = GltfInterpolation::Linear;
+        assert!(keyframes(&[0.0, 1.0], vec![1.0, 2.0], linear).is_some());
+        assert!(keyframes(&[0.0, 1.0], vec![1.0], linear).is_none());
+        assert!(keyframes(&[1.0, 0.0], vec![1.0, 2.0], linear).is_none());
+        assert!(keyframes::<f32>(&[], vec![], linear).is_none());
+
+        // In tangent, value and out tangent per keyframe.
+        let cubic = GltfInterpolation::CubicSpline;
+        let spline = keyframes(&[0.0, 1.0], vec![0.0, 1.0, 3.0, 4.0, 2.0, 0.0], cubic);
+        let expected = Curve::cubic(vec![0.0, 1.0], vec![1.0, 2.0], vec![(0.0, 3.0), (4.0, 0.0)]);
+        assert_eq!(spline, Some(expected));
+        assert!(keyframes(&[0.0, 1.0], vec![0.0, 1.0, 3.0, 4.0, 2.0], cubic).is_none());
+        assert!(import_gltf(b"{}").is_err());
+    }
+}
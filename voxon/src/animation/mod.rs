@@ -0,0 +1,374 @@
+//! Skeletal animation
+//!
+//! A [Skeleton] is a hierarchy of joints, each posed by a [Transform] relative
+//! to its parent. The vertices of a skinned mesh follow up to four joints
+//! each, weighted, which is done on the GPU with the skinning matrices of the
+//! entity's [Skin]. [Animation]s move the joints by sampling keyframes,
+//! interpolating rotations with slerp, and are played by an [Animator].
+//!
+//! Skeletons, skinned meshes and animations are usually [imported](import)
+//! from glTF files.
+//!
+//! The keyframes are [Curve]s, which also move whole entities with a
+//! [TransformAnimator].
+
+use std::sync::Arc;
+
+use ecs::Time;
+use lina::{matrix::Matrix, vector::Vector};
+use quaternion::Quaternion;
+
+use crate::components::Transform;
+
+mod clip;
+mod curve;
+mod import;
+
+pub use clip::*;
+pub use curve::*;
+pub use import::*;
+
+/// A joint of a [Skeleton].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Joint {
+    pub name: String,
+    /// The index of the parent joint, `None` for the roots.
+    pub parent: Option<usize>,
+    /// The pose relative to the parent while no animation moves the joint.
+    pub rest: Transform<f32>,
+    /// Transforms model space into the space of the joint in the pose the
+    /// mesh was modeled in, the bind pose.
+    pub inverse_bind: Matrix<f32, 4, 4>,
+}
+
+/// The joints moving a skinned mesh.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Skeleton {
+    // Parents come before their children.
+    joints: Vec<Joint>,
+}
+
+impl Skeleton {
+    /// A skeleton of the `joints`, which are referred to by their index.
+    ///
+    /// Panics unless every parent comes before its children.
+    pub fn new(joints: Vec<Joint>) -> Self {
+        for (index, joint) in joints.iter().enumerate() {
+            assert!(
+                joint.parent.is_none_or(|parent| parent < index),
+                "the parent of joint {index} doesn't come before it"
+            );
+        }
+        Self { joints }
+    }
+
+    pub fn joints(&self) -> &[Joint] {
+        &self.joints
+    }
+
+    /// The local pose of every joint while no animation moves them.
+    pub fn rest_pose(&self) -> Vec<Transform<f32>> {
+        self.joints.iter().map(|joint| joint.rest).collect()
+    }
+
+    /// Replace `matrices` with the skinning matrix of every joint posed by
+    /// `poses`, which move the vertices from the bind pose to that pose.
+    pub fn skinning_matrices(
+        &self,
+        poses: &[Transform<f32>],
+        matrices: &mut Vec<Matrix<f32, 4, 4>>,
+    ) {
+        assert_eq!(poses.len(), self.joints.len(), "one pose per joint");
+        // First the model space matrix of every joint, parents being done first.
+        matrices.clear();
+        for (joint, pose) in self.joints.iter().zip(poses) {
+            let local = pose.matrix();
+            let global = match joint.parent {
+                Some(parent) => matrices[parent] * local,
+                None => local,
+            };
+            matrices.push(global);
+        }
+        for (matrix, joint) in matrices.iter_mut().zip(&self.joints) {
+            *matrix = *matrix * joint.inverse_bind;
+        }
+    }
+}
+
+/// The joints of an entity rendering a skinned mesh, which is only drawn
+/// with a skin.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Skin {
+    pub skeleton: Arc<Skeleton>,
+    /// One per joint, uploaded for skinning the mesh on the GPU.
+    pub matrices: Vec<Matrix<f32, 4, 4>>,
+}
+
+impl Skin {
+    /// The `skeleton` in its rest pose.
+    pub fn new(skeleton: Arc<Skeleton>) -> Self {
+        let mut matrices = Vec::new();
+        skeleton.skinning_matrices(&skeleton.rest_pose(), &mut matrices);
+        Self { skeleton, matrices }
+    }
+}
+
+/// Plays an [Animation] on the [Skin] of its entity.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Animator {
+    pub animation: Arc<Animation>,
+    /// The time within the animation in seconds.
+    pub time: f32,
+    /// How fast the animation is played, 1 being its original speed.
+    pub speed: f32,
+    /// Whether it starts over at the end, otherwise it stops there.
+    pub looping: bool,
+}
+
+impl Animator {
+    /// Play `animation` from the start, over and over again.
+    pub fn looping(animation: Arc<Animation>) -> Self {
+        Self {
+            animation,
+            time: 0.0,
+            speed: 1.0,
+            looping: true,
+        }
+    }
+
+    /// Advance the time by `delta` seconds, scaled by the speed.
+    pub fn advance(&mut self, delta: f32) {
+        let duration = self.animation.duration();
+        self.time += delta * self.speed;
+        self.time = if self.looping && duration > 0.0 {
+            self.time.rem_euclid(duration)
+        } else {
+            self.time.clamp(0.0, duration)
+        };
+    }
+}
+
+/// Advance every [Animator] and pose the [Skin] of its entity.
+pub fn animate(entities: &mut ecs::World, time: &Time) {
+    let delta = time.delta().as_secs_f32();
+    let animated = entities
+        .query2::<Animator, Skin>()
+        .map(|(entity, _, _)| entity)
+        .collect::<Vec<_>>();
+    let mut poses = Vec::new();
+    for entity in animated {
+        let Some(animator) = entities.get_mut::<Animator>(entity) else {
+            continue;
+        };
+        animator.advance(delta);
+        let (animation, time) = (animator.animation.clone(), animator.time);
+
+        let Some(Skin { skeleton, matrices }) = entities.get_mut::<Skin>(entity) else {
+            continue;
+        };
+        animation.sample(skeleton, time, &mut poses);
+        skeleton.skinning_matrices(&poses, matrices);
+    }
+}
+
+/// Moves the [Transform] of its entity along curves. The parts of the
+/// transform without a curve are left as they are.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransformAnimator {
+    pub translation: Option<Curve<Vector<f32, 3>>>,
+    pub rotation: Option<Curve<Quaternion<f32>>>,
+    pub scale: Option<Curve<Vector<f32, 3>>>,
+    /// The time on the curves in seconds, which loop on their own.
+    pub time: f32,
+    /// How fast the time passes, 1 being real time.
+    pub speed: f32,
+}
+
+impl TransformAnimator {
+    /// An animator without curves, starting at time 0.
+    pub fn new() -> Self {
+        Self {
+            translation: None,
+            rotation: None,
+            scale: None,
+            time: 0.0,
+            speed: 1.0,
+        }
+    }
+
+    pub fn with_translation(self, translation: Curve<Vector<f32, 3>>) -> Self {
+        Self {
+            translation: Some(translation),
+            ..self
+        }
+    }
+
+    pub fn with_rotation(self, rotation: Curve<Quaternion<f32>>) -> Self {
+        Self {
+            rotation: Some(rotation),
+            ..self
+        }
+    }
+
+    /// Set the animated parts of `transform` to the curves at the current
+    /// time.
+    pub fn apply(&self, transform: &mut Transform) {
+        if let Some(curve) = &self.translation {
+            transform.translation = curve.sample(self.time).map(f64::from);
+        }
+        if let Some(curve) = &self.rotation {
+            transform.rotation = curve.sample(self.time);
+        }
+        if let Some(curve) = &self.scale {
+            transform.scale = curve.sample(self.time);
+        }
+    }
+}
+
+impl Default for TransformAnimator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Advance every [TransformAnimator] and move the [Transform] of its entity.
+pub fn animate_transforms(entities: &mut ecs::World, time: &Time) {
+    let delta = time.delta().as_secs_f32();
+    let animated = entities
+        .query2::<TransformAnimator, Transform>()
+        .map(|(entity, _, _)| entity)
+        .collect::<Vec<_>>();
+    for entity in animated {
+        let Some(mut transform) = entities.get::<Transform>(entity).copied() else {
+            continue;
+        };
+        let Some(animator) = entities.get_mut::<TransformAnimator>(entity) else {
+            continue;
+        };
+        animator.time += delta * animator.speed;
+        animator.apply(&mut transform);
+        entities.insert(entity, transform);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{f32::consts::PI, time::Duration};
+
+    use lina::{angle::Rad, v};
+    use quaternion::Quaternion;
+
+    use super::*;
+
+    /// Two joints one above the other, the upper one at `y = 1`.
+    pub(super) fn arm() -> Skeleton {
+        let inverse_bind = |y: f32| Transform::from_translation(v![0.0, -y, 0.0]).matrix();
+        Skeleton::new(vec![
+            Joint {
+                name: "shoulder".to_string(),
+                parent: None,
+                rest: Transform::default(),
+                inverse_bind: inverse_bind(0.0),
+            },
+            Joint {
+                name: "elbow".to_string(),
+                parent: Some(0),
+                rest: Transform::from_translation(v![0.0, 1.0, 0.0]),
+                inverse_bind: inverse_bind(1.0),
+            },
+        ])
+    }
+
+    fn transform_point(matrix: &Matrix<f32, 4, 4>, point: [f32; 3]) -> [f32; 3] {
+        let moved = *matrix * v![point[0], point[1], point[2], 1.0];
+        [moved[0], moved[1], moved[2]]
+    }
+
+    #[test]
+    fn the_rest_pose_keeps_the_vertices() {
+        let skin = Skin::new(Arc::new(arm()));
+        for matrix in &skin.matrices {
+            assert!(matrix.approx_eq(&Matrix::identity(), 1e-6));
+        }
+    }
+
+    #[test]
+    fn children_follow_their_parents() {
+        let skeleton = arm();
+        let mut poses = skeleton.rest_pose();
+        // Bend the shoulder a quarter turn towards -X.
+        poses[0].rotation = Quaternion::<f32>::new_unit(Rad(PI / 2.0), v![0.0, 0.0, 1.0]);
+
+        let mut matrices = Vec::new();
+        skeleton.skinning_matrices(&poses, &mut matrices);
+        // A vertex at the elbow and one above it, both following the elbow.
+        let elbow = transform_point(&matrices[1], [0.0, 1.0, 0.0]);
+        let hand = transform_point(&matrices[1], [0.0, 2.0, 0.0]);
+        assert!(v![elbow[0], elbow[1], elbow[2]].approx_eq(&v![-1.0, 0.0, 0.0], 1e-6));
+        assert!(v![hand[0], hand[1], hand[2]].approx_eq(&v![-2.0, 0.0, 0.0], 1e-6));
+    }
+
+    #[test]
+    #[should_panic(expected = "doesn't come before it")]
+    fn parents_come_first() {
+        let mut joints = arm().joints().to_vec();
+        joints.swap(0, 1);
+        joints[0].parent = Some(1);
+        joints[1].parent = None;
+        Skeleton::new(joints);
+    }
+
+    #[test]
+    fn animators_loop_or_stop_at_the_end() {
+        let curve = Curve::new(vec![0.0, 2.0], vec![v![0.0, 1.0, 0.0], v![0.0, 3.0, 0.0]]);
+        let animation = Arc::new(Animation::new(
+            "rise",
+            vec![Channel {
+                joint: 1,
+                keyframes: Keyframes::Translation(curve),
+            }],
+        ));
+        let mut entities = ecs::World::new();
+        let entity = entities.spawn();
+        entities.insert(entity, Skin::new(Arc::new(arm())));
+        entities.insert(entity, Animator::looping(animation.clone()));
+
+        let mut time = Time::new();
+        time.advance(Duration::from_millis(2500));
+        animate(&mut entities, &time);
+        assert!((entities.get::<Animator>(entity).unwrap().time - 0.5).abs() < 1e-6);
+        // The elbow moved up by 0.5, everything above it with it.
+        let skin = entities.get::<Skin>(entity).unwrap();
+        let elbow = transform_point(&skin.matrices[1], [0.0, 1.0, 0.0]);
+        assert!((elbow[1] - 1.5).abs() < 1e-6);
+
+        let mut animator = Animator {
+            looping: false,
+            ..Animator::looping(animation)
+        };
+        animator.advance(3.0);
+        assert_eq!(animator.time, 2.0);
+        animator.speed = -1.0;
+        animator.advance(3.0);
+        assert_eq!(animator.time, 0.0);
+    }
+
+    #[test]
+    fn transform_animators_move_the_animated_parts() {
+        let height = Curve::new(vec![0.0, 1.0], vec![v![0.0; 3], v![0.0, 1.0, 0.0]]);
+        let animator =
+            TransformAnimator::new().with_translation(height.with_loop_mode(LoopMode::PingPong));
+        let mut entities = ecs::World::new();
+        let entity = entities.spawn();
+        let scale = v![2.0, 2.0, 2.0];
+        entities.insert(entity, <Transform>::default().with_scale(scale));
+        entities.insert(entity, animator);
+
+        let mut time = Time::new();
+        time.advance(Duration::from_millis(1250));
+        animate_transforms(&mut entities, &time);
+        let transform = entities.get::<Transform>(entity).unwrap();
+        assert!(transform.translation.approx_eq(&v![0.0, 0.75, 0.0], 1e-6));
+        assert_eq!(transform.scale, scale);
+    }
+}
@@ -0,0 +1,307 @@
+//! Saving rendered frames as images.
+//!
+//! Frames are copied back from the GPU right after they were rendered and
+//! encoded as PNGs on a thread of their own, so the render loop only waits
+//! for the copy.
+
+use std::{
+    path::{Path, PathBuf},
+    sync::mpsc::{self, SyncSender},
+    thread::JoinHandle,
+};
+
+use web_time::SystemTime;
+use wgpu::{Device, Queue, Texture, TextureFormat, TextureUsages};
+
+/// Copy the contents of `texture` back from the GPU.
+///
+/// The texture needs [TextureUsages::COPY_SRC] and an 8 bit RGBA or BGRA
+/// format, otherwise `None` is returned.
+pub fn read_texture(device: &Device, queue: &Queue, texture: &Texture) -> Option<image::RgbaImage> {
+    let swap_red_blue = match texture.format() {
+        TextureFormat::Rgba8Unorm | TextureFormat::Rgba8UnormSrgb => false,
+        TextureFormat::Bgra8Unorm | TextureFormat::Bgra8UnormSrgb => true,
+        _ => return None,
+    };
+    if !texture.usage().contains(TextureUsages::COPY_SRC) {
+        return None;
+    }
+
+    // Buffer copies have to be aligned row by row.
+    let (width, height) = (texture.width(), texture.height());
+    let row_size = width * 4;
+    let padded_row_size = row_size.next_multiple_of(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT);
+
+    let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("read back buffer"),
+        size: padded_row_size as u64 * height as u64,
+        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("read back encoder"),
+    });
+    encoder.copy_texture_to_buffer(
+        texture.as_image_copy(),
+        wgpu::TexelCopyBufferInfo {
+            buffer: &buffer,
+            layout: wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(padded_row_size),
+                rows_per_image: None,
+            },
+        },
+        texture.size(),
+    );
+    queue.submit(Some(encoder.finish()));
+
+    let slice = buffer.slice(..);
+    slice.map_async(wgpu::MapMode::Read, |result| {
+        result.expect("failed to map the read back buffer")
+    });
+    device
+        .poll(wgpu::PollType::wait_indefinitely())
+        .expect("failed to wait for the read back");
+
+    let data = slice.get_mapped_range();
+    let mut pixels: Vec<u8> = data
+        .chunks_exact(padded_row_size as usize)
+        .flat_map(|row| &row[..row_size as usize])
+        .copied()
+        .collect();
+    if swap_red_blue {
+        for pixel in pixels.chunks_exact_mut(4) {
+            pixel.swap(0, 2);
+        }
+    }
+    Some(
+        image::RgbaImage::from_raw(width, height, pixels)
+            .expect("read back buffer has the size of the image"),
+    )
+}
+
+/// The frames to save, requested by the application and captured by the
+/// renderer.
+#[derive(Default)]
+pub struct FrameCapture {
+    // Where the next frame is saved as a screenshot.
+    screenshot: Option<PathBuf>,
+    dump: Option<FrameDump>,
+    // Started with the first capture.
+    writer: Option<ImageWriter>,
+}
+
+/// Every frame saved into a directory, numbered in order.
+struct FrameDump {
+    directory: PathBuf,
+    next: u32,
+}
+
+impl FrameCapture {
+    /// Save the next frame into `directory`, named after the current time.
+    pub fn request_screenshot(&mut self, directory: impl AsRef<Path>) {
+        let name = format!("screenshot-{}.png", timestamp(SystemTime::now()));
+        self.screenshot = Some(directory.as_ref().join(name));
+    }
+
+    /// Save every frame from now on into a new directory in `directory`,
+    /// named after the current time. Returns the new directory.
+    pub fn start_dump(&mut self, directory: impl AsRef<Path>) -> PathBuf {
+        let directory = directory.as_ref().join(timestamp(SystemTime::now()));
+        self.dump = Some(FrameDump {
+            directory: directory.clone(),
+            next: 0,
+        });
+        directory
+    }
+
+    /// Stop saving every frame, returns how many were saved.
+    pub fn stop_dump(&mut self) -> Option<u32> {
+        self.dump.take().map(|dump| dump.next)
+    }
+
+    pub fn is_dumping(&self) -> bool {
+        self.dump.is_some()
+    }
+
+    /// The paths the frame about to be captured is saved to, if any.
+    fn next_paths(&mut self) -> Vec<PathBuf> {
+        let mut paths: Vec<_> = self.screenshot.take().into_iter().collect();
+        if let Some(dump) = &mut self.dump {
+            paths.push(dump.directory.join(format!("frame-{:05}.png", dump.next)));
+            dump.next += 1;
+        }
+        paths
+    }
+
+    /// Save the rendered `frame` where it was requested, if it was.
+    ///
+    /// Stops the frame dump if the frame can't be read back, see
+    /// [read_texture].
+    pub fn capture(&mut self, device: &Device, queue: &Queue, frame: &Texture) {
+        let paths = self.next_paths();
+        if paths.is_empty() {
+            return;
+        }
+        let _span = tracing::debug_span!("capture").entered();
+        let Some(image) = read_texture(device, queue, frame) else {
+            tracing::error!(
+                "Can't capture frames of {:?} with {:?}",
+                frame.format(),
+                frame.usage()
+            );
+            self.dump = None;
+            return;
+        };
+        let writer = self.writer.get_or_insert_with(ImageWriter::new);
+        for path in paths {
+            writer.write(path, image.clone());
+        }
+    }
+}
+
+/// Encodes and saves images on a thread of its own, right away where
+/// there are no threads, like in the browser.
+struct ImageWriter {
+    // Dropped first to end the thread.
+    sender: Option<SyncSender<(PathBuf, image::RgbaImage)>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl ImageWriter {
+    fn new() -> Self {
+        if cfg!(target_arch = "wasm32") {
+            return Self {
+                sender: None,
+                thread: None,
+            };
+        }
+        // A few frames may queue up, then rendering waits for the encoding,
+        // so dumping doesn't run out of memory.
+        let (sender, receiver) = mpsc::sync_channel::<(PathBuf, image::RgbaImage)>(4);
+        let thread = std::thread::spawn(move || {
+            for (path, image) in receiver {
+                save(&path, &image);
+            }
+        });
+        Self {
+            sender: Some(sender),
+            thread: Some(thread),
+        }
+    }
+
+    fn write(&self, path: PathBuf, image: image::RgbaImage) {
+        match &self.sender {
+            // Only fails if the thread panicked, which it logged.
+            Some(sender) => {
+                let _ = sender.send((path, image));
+            }
+            None => save(&path, &image),
+        }
+    }
+}
+
+impl Drop for ImageWriter {
+    /// Finish saving the images still queued up.
+    fn drop(&mut self) {
+        self.sender = None;
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Save `image` to `path`, creating its directory if needed.
+fn save(path: &Path, image: &image::RgbaImage) {
+    let result = path
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .map_err(image::ImageError::IoError)
+        .and_then(|()| image.save(path));
+    match result {
+        Ok(()) => tracing::debug!("Saved {}", path.display()),
+        Err(err) => tracing::error!("Failed to save {}: {err}", path.display()),
+    }
+}
+
+/// `time` in UTC as `YYYY-MM-DD_HH-MM-SS-mmm`, sorting chronologically and
+/// safe to use in file names.
+fn timestamp(time: SystemTime) -> String {
+    let since_epoch = time
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default();
+    let seconds = since_epoch.as_secs();
+    let (days, seconds_of_day) = (seconds / 86_400, seconds % 86_400);
+    let (year, month, day) = civil_from_days(days as i64);
+    format!(
+        "{year:04}-{month:02}-{day:02}_{:02}-{:02}-{:02}-{:03}",
+        seconds_of_day / 3600,
+        seconds_of_day / 60 % 60,
+        seconds_of_day % 60,
+        since_epoch.subsec_millis()
+    )
+}
+
+/// The year, month and day of the Gregorian calendar `days` after
+/// 1970-01-01, after Howard Hinnant's `civil_from_days`.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    // Counted from 0000-03-01, so the leap day is the last of the year.
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    // Starting with March.
+    let month = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month + 2) / 5 + 1) as u32;
+    let month = if month < 10 { month + 3 } else { month - 9 } as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn timestamps_are_utc_dates() {
+        let at = |seconds: u64, millis: u64| {
+            timestamp(
+                SystemTime::UNIX_EPOCH
+                    + Duration::from_secs(seconds)
+                    + Duration::from_millis(millis),
+            )
+        };
+        assert_eq!(at(0, 0), "1970-01-01_00-00-00-000");
+        assert_eq!(at(951_782_400, 5), "2000-02-29_00-00-00-005");
+        assert_eq!(at(1_792_165_018, 123), "2026-10-16_15-36-58-123");
+        assert_eq!(at(4_107_542_399, 999), "2100-02-28_23-59-59-999");
+    }
+
+    #[test]
+    fn screenshots_and_dumps_capture_the_next_frames() {
+        let mut capture = FrameCapture::default();
+        assert!(capture.next_paths().is_empty());
+
+        capture.request_screenshot("shots");
+        let paths = capture.next_paths();
+        assert_eq!(paths.len(), 1);
+        assert!(paths[0].starts_with("shots"));
+        assert!(capture.next_paths().is_empty());
+
+        let directory = capture.start_dump("frames");
+        assert!(capture.is_dumping());
+        capture.request_screenshot("shots");
+        assert_eq!(capture.next_paths().len(), 2);
+        assert_eq!(
+            capture.next_paths(),
+            vec![directory.join("frame-00001.png")]
+        );
+        assert_eq!(capture.stop_dump(), Some(2));
+        assert!(capture.next_paths().is_empty());
+    }
+}
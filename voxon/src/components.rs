@@ -0,0 +1,224 @@
+//! Components of the entities in the [Scene](crate::scene::Scene).
+//!
+//! Besides the ones defined here, [Camera](graphic::camera::Camera) and
+//! [Light](crate::light::Light) are used as components as they are.
+use graphic::transform::{scale_v, translate_v};
+use lina::{
+    matrix::Matrix,
+    v,
+    vector::{Vector, Zero},
+};
+use quaternion::Quaternion;
+
+use crate::{
+    physics::Obb,
+    scene::{MaterialHandle, MeshHandle},
+};
+
+/// Placement of an entity in the world.
+///
+/// Applied in the order scale, rotation, translation.
+///
+/// The translation of entities is kept in `f64`, so they stay in place
+/// kilometers away from the world's origin. Everything working in `f32`
+/// takes them [relative](Transform::relative_to) to a point nearby, like
+/// the joints of a [Skeleton](crate::animation::Skeleton) relative to their
+/// parent, or the physics relative to a [local origin](crate::scene::local_origin).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform<T = f64> {
+    pub translation: Vector<T, 3>,
+    pub rotation: Quaternion<f32>,
+    pub scale: Vector<f32, 3>,
+}
+
+impl<T: Copy + Zero> Default for Transform<T> {
+    fn default() -> Self {
+        Self {
+            translation: v![T::ZERO, T::ZERO, T::ZERO],
+            rotation: Quaternion::default(),
+            scale: v![1.0, 1.0, 1.0],
+        }
+    }
+}
+
+impl<T: Copy + Zero> Transform<T> {
+    pub fn from_translation(translation: Vector<T, 3>) -> Self {
+        Self {
+            translation,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_scale(self, scale: Vector<f32, 3>) -> Self {
+        Self { scale, ..self }
+    }
+}
+
+impl Transform {
+    /// The transform in a world moved by `-origin`, precise as long as it
+    /// is close to `origin`.
+    pub fn relative_to(&self, origin: Vector<f64, 3>) -> Transform<f32> {
+        Transform {
+            translation: (self.translation - origin).map(|value| value as f32),
+            rotation: self.rotation,
+            scale: self.scale,
+        }
+    }
+
+    /// The transform `relative` to `origin` back in the world.
+    pub fn from_relative(relative: &Transform<f32>, origin: Vector<f64, 3>) -> Self {
+        Transform {
+            translation: origin + relative.translation.map(f64::from),
+            rotation: relative.rotation,
+            scale: relative.scale,
+        }
+    }
+
+    /// The world matrix of the transform in a world moved by `-origin`,
+    /// e.g. the eye of the camera it is rendered with.
+    pub fn matrix_relative_to(&self, origin: Vector<f64, 3>) -> Matrix<f32, 4, 4> {
+        self.relative_to(origin).matrix()
+    }
+}
+
+impl Transform<f32> {
+    /// The matrix of the transform.
+    pub fn matrix(&self) -> Matrix<f32, 4, 4> {
+        let rotation: Matrix<f32, 4, 4> = self.rotation.into();
+        translate_v(&self.translation) * rotation * scale_v(self.scale)
+    }
+}
+
+/// Renders a mesh with a material at the entity's [Transform].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MeshRenderer {
+    pub mesh: MeshHandle,
+    pub material: MaterialHandle,
+}
+
+/// Marks entities the engine creates by itself, like terrain tiles and
+/// streamed chunks. They aren't saved to scene files and stay when
+/// loading one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Generated;
+
+/// An entity the player can select and order around, see
+/// [selection](crate::selection).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Unit {
+    /// How fast it moves in m/s.
+    pub speed: f32,
+    /// Half the size of its box around the [Transform]'s translation,
+    /// rotated with it.
+    pub half_extents: Vector<f32, 3>,
+}
+
+impl Default for Unit {
+    fn default() -> Self {
+        Self {
+            speed: 3.0,
+            half_extents: v![0.2, 0.4, 0.2],
+        }
+    }
+}
+
+impl Unit {
+    /// The box the unit occupies when placed at `transform`, in the same
+    /// space as the transform.
+    pub fn bounds(&self, transform: &Transform<f32>) -> Obb {
+        Obb::new(transform.translation, self.half_extents, transform.rotation)
+    }
+}
+
+/// The points a [Unit] was ordered to move its translation through, in
+/// order. Removed once it arrived at the last one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MoveOrder {
+    pub waypoints: Vec<Vector<f64, 3>>,
+}
+
+/// Switches the mesh of the entity's [MeshRenderer] by the distance of the
+/// camera to `center`, from the most detailed of the `levels` to the least.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lod {
+    pub levels: Vec<MeshHandle>,
+    /// The point the distance is measured to, in world space.
+    pub center: Vector<f64, 3>,
+    /// Up to which distance the first level is used. Every following level
+    /// is used up to twice the distance of the one before, the last one
+    /// beyond.
+    pub distance: f32,
+}
+
+impl Lod {
+    /// The mesh to use when seen from `distance`.
+    pub fn level(&self, distance: f32) -> MeshHandle {
+        let mut limit = self.distance;
+        let mut level = 0;
+        while distance > limit && level + 1 < self.levels.len() {
+            limit *= 2.0;
+            level += 1;
+        }
+        self.levels[level]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::PI;
+
+    use graphic::transform::{scale, translate};
+    use lina::{angle::Rad, v};
+    use quaternion::Quaternion;
+
+    use super::{Lod, Transform};
+    use crate::scene::MeshHandle;
+
+    #[test]
+    fn matrix_scales_rotates_then_translates() {
+        let rotation = Quaternion::<f32>::new_unit(Rad(PI / 2.0), v![0.0, 1.0, 0.0]);
+        let transform = Transform {
+            translation: v![1.0, 2.0, 3.0],
+            rotation,
+            scale: v![2.0, 1.0, 1.0],
+        };
+
+        // +X is scaled, then rotated to -Z.
+        let point = transform.matrix() * v![1.0, 0.0, 0.0, 1.0];
+        assert!(point.approx_eq(&v![1.0, 2.0, 1.0, 1.0], 1e-6));
+
+        assert_eq!(
+            Transform::from_translation(v![0.0, -1.0, 0.0])
+                .with_scale(v![3.0, 1.0, 3.0])
+                .matrix(),
+            translate(0.0, -1.0, 0.0) * scale(3.0, 1.0, 3.0)
+        );
+    }
+
+    #[test]
+    fn relative_matrices_keep_the_precision_far_away() {
+        // Thousands of kilometers away, where `f32` is only good for meters.
+        let eye = v![4_000_000.3, 12.0, -9_000_000.7];
+        let near =
+            Transform::<f32>::from_translation(v![0.25, 1.0, -0.125]).with_scale(v![2.0, 2.0, 2.0]);
+        let far = Transform::from_relative(&near, eye);
+
+        assert!(far.matrix_relative_to(eye).approx_eq(&near.matrix(), 1e-6));
+        assert_eq!(far.relative_to(eye).scale, near.scale);
+    }
+
+    #[test]
+    fn lod_levels_double_in_distance() {
+        let levels = (0..3).map(MeshHandle).collect::<Vec<_>>();
+        let lod = Lod {
+            levels: levels.clone(),
+            center: v![0.0, 0.0, 0.0],
+            distance: 10.0,
+        };
+        assert_eq!(lod.level(0.0), levels[0]);
+        assert_eq!(lod.level(10.0), levels[0]);
+        assert_eq!(lod.level(15.0), levels[1]);
+        assert_eq!(lod.level(30.0), levels[2]);
+        assert_eq!(lod.level(1000.0), levels[2]);
+    }
+}
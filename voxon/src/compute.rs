@@ -0,0 +1,131 @@
+//! Running compute shaders, e.g. to generate geometry on the GPU.
+//!
+//! A [ComputeKernel] is a single entry point of a WGSL compute shader,
+//! its resources are storage or uniform buffers in bind group 0.
+
+use std::borrow::Cow;
+
+use wgpu::{
+    Adapter, BindGroup, Buffer, BufferAddress, CommandEncoder, ComputePipeline, Device,
+    DownlevelFlags, Queue,
+};
+
+/// Whether compute shaders can be run on `adapter`, they are missing on
+/// some downlevel backends like WebGL.
+pub fn supports_compute(adapter: &Adapter) -> bool {
+    adapter
+        .get_downlevel_capabilities()
+        .flags
+        .contains(DownlevelFlags::COMPUTE_SHADERS)
+}
+
+/// A compute shader entry point ready to be dispatched.
+pub struct ComputeKernel {
+    pipeline: ComputePipeline,
+    workgroup_size: [u32; 3],
+}
+
+impl ComputeKernel {
+    /// Compile the `entry_point` of the WGSL `source`.
+    ///
+    /// `constants` set the shader's `override` declarations. `workgroup_size`
+    /// has to be the `@workgroup_size` of the entry point, it is used to
+    /// turn invocations into workgroups when [dispatching](ComputeKernel::dispatch).
+    pub fn new(
+        device: &Device,
+        label: &str,
+        source: &str,
+        entry_point: &str,
+        constants: &[(&str, f64)],
+        workgroup_size: [u32; 3],
+    ) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some(label),
+            source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(source)),
+        });
+        // The layout is derived from the shader.
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some(label),
+            layout: None,
+            module: &shader,
+            entry_point: Some(entry_point),
+            compilation_options: wgpu::PipelineCompilationOptions {
+                constants,
+                ..Default::default()
+            },
+            cache: None,
+        });
+
+        Self {
+            pipeline,
+            workgroup_size,
+        }
+    }
+
+    /// Bind the whole `buffers` to the bindings `0, 1, 2, ...` of group 0,
+    /// in the order they are declared in the shader.
+    pub fn bind_group(&self, device: &Device, buffers: &[&Buffer]) -> BindGroup {
+        let entries = buffers
+            .iter()
+            .enumerate()
+            .map(|(binding, buffer)| wgpu::BindGroupEntry {
+                binding: binding as u32,
+                resource: buffer.as_entire_binding(),
+            })
+            .collect::<Vec<_>>();
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: None,
+            layout: &self.pipeline.get_bind_group_layout(0),
+            entries: &entries,
+        })
+    }
+
+    /// Record running the kernel at least once for every cell of the
+    /// `invocations` grid, rounded up to whole workgroups. The shader has to
+    /// ignore the invocations outside of the grid.
+    pub fn dispatch(
+        &self,
+        encoder: &mut CommandEncoder,
+        bind_group: &BindGroup,
+        invocations: [u32; 3],
+    ) {
+        let [x, y, z] = std::array::from_fn(|i| invocations[i].div_ceil(self.workgroup_size[i]));
+        let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: None,
+            timestamp_writes: None,
+        });
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, bind_group, &[]);
+        pass.dispatch_workgroups(x, y, z);
+    }
+}
+
+/// Copy the first `size` bytes of `buffer` back from the GPU, waiting for
+/// all submitted work to finish. The buffer needs [wgpu::BufferUsages::COPY_SRC].
+pub fn read_buffer(
+    device: &Device,
+    queue: &Queue,
+    buffer: &Buffer,
+    size: BufferAddress,
+) -> Vec<u8> {
+    let staging = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("read back buffer"),
+        size,
+        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("read back encoder"),
+    });
+    encoder.copy_buffer_to_buffer(buffer, 0, &staging, 0, size);
+    queue.submit(Some(encoder.finish()));
+
+    let slice = staging.slice(..);
+    slice.map_async(wgpu::MapMode::Read, |result| {
+        result.expect("failed to map the read back buffer")
+    });
+    device
+        .poll(wgpu::PollType::wait_indefinitely())
+        .expect("failed to wait for the read back");
+    slice.get_mapped_range().to_vec()
+}
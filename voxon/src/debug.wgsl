@@ -0,0 +1,31 @@
+// Only the beginning of the global uniforms is needed.
+struct Globals {
+    view_projection: mat4x4f,
+};
+
+@group(0)
+@binding(0)
+var<uniform> global: Globals;
+
+struct Vertex {
+    @location(0) position: vec3f,
+    @location(1) color: vec4f,
+};
+
+struct VSOutput {
+    @builtin(position) position: vec4f,
+    @location(0) color: vec4f,
+};
+
+@vertex
+fn vs_main(vertex: Vertex) -> VSOutput {
+    var vsOut: VSOutput;
+    vsOut.position = global.view_projection * vec4f(vertex.position, 1.0);
+    vsOut.color = vertex.color;
+    return vsOut;
+}
+
+@fragment
+fn fs_main(vsOut: VSOutput) -> @location(0) vec4f {
+    return vsOut.color;
+}
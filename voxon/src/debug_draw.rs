@@ -0,0 +1,331 @@
+//! Immediate mode debug line drawing.
+//!
+//! Lines are accumulated with [DebugDraw] during the frame, then uploaded and
+//! rendered on top of the scene in a separate `LineList` pass and cleared.
+//! Mostly meant for diagnosing camera and transformation issues.
+
+use std::borrow::Cow;
+
+use bytemuck::{Pod, Zeroable};
+use graphic::color::Color;
+use lina::{v, vector::Vector};
+use wgpu::{
+    BindGroupLayout, Buffer, BufferAddress, BufferUsages, CommandEncoder, DepthBiasState,
+    DepthStencilState, Device, RenderPass, RenderPipeline, StencilState, TextureFormat,
+};
+
+use crate::{
+    physics::Obb,
+    upload::UploadBelt,
+    vertex::{VertexLayout, vertex_layout},
+};
+
+vertex_layout! {
+    #[repr(C)]
+    #[derive(Debug, Clone, Copy, Pod, Zeroable)]
+    struct LineVertex {
+        position: Vector<f32, 3>,
+        color: Color,
+    }
+}
+
+// A line's end, in world space.
+#[derive(Debug, Clone, Copy)]
+struct LinePoint {
+    position: Vector<f64, 3>,
+    color: Color,
+}
+
+/// Collects lines to be drawn in the current frame.
+///
+/// All positions are in world space, translucent colors are blended
+/// over the scene.
+#[derive(Debug, Default)]
+pub struct DebugDraw {
+    // Every two consecutive entries form a line.
+    points: Vec<LinePoint>,
+}
+
+impl DebugDraw {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Draw a line between `from` and `to`.
+    pub fn line(&mut self, from: Vector<f64, 3>, to: Vector<f64, 3>, color: Color) {
+        self.points.push(LinePoint {
+            position: from,
+            color,
+        });
+        self.points.push(LinePoint {
+            position: to,
+            color,
+        });
+    }
+
+    /// Draw the three coordinate axes starting from `origin`.
+    ///
+    /// Following the usual convention X is red, Y is green and Z is blue.
+    pub fn axis(&mut self, origin: Vector<f64, 3>, length: f64) {
+        self.line(origin, origin + v![length, 0.0, 0.0], Color::RED);
+        self.line(origin, origin + v![0.0, length, 0.0], Color::GREEN);
+        self.line(origin, origin + v![0.0, 0.0, length], Color::BLUE);
+    }
+
+    /// Draw the 12 edges of the axis aligned box between `min` and `max`.
+    pub fn aabb(&mut self, min: Vector<f64, 3>, max: Vector<f64, 3>, color: Color) {
+        // Corner `i` takes the coordinate from `max` on the axes where the
+        // corresponding bit of `i` is set.
+        let corner = |i: usize| {
+            v![
+                if i & 1 == 0 { min[0] } else { max[0] },
+                if i & 2 == 0 { min[1] } else { max[1] },
+                if i & 4 == 0 { min[2] } else { max[2] }
+            ]
+        };
+
+        for i in 0..8 {
+            for bit in [1, 2, 4] {
+                // Every edge connects two corners differing in one bit,
+                // only draw it from the corner where the bit isn't set.
+                if i & bit == 0 {
+                    self.line(corner(i), corner(i | bit), color);
+                }
+            }
+        }
+    }
+
+    /// Draw the edges of a rotated box, which is relative to `origin`.
+    pub fn obb(&mut self, obb: &Obb, origin: Vector<f64, 3>, color: Color) {
+        let corners = obb
+            .corners()
+            .map(|corner| origin + corner.map(f64::from))
+            .collect::<Vec<_>>();
+        for i in 0..8 {
+            for bit in [1, 2, 4] {
+                // Corners are numbered like the ones of `aabb`.
+                if i & bit == 0 {
+                    self.line(corners[i], corners[i | bit], color);
+                }
+            }
+        }
+    }
+
+    /// Draw a grid on the XZ plane centered at `center`.
+    ///
+    /// The grid has `cells` number of cells along each axis, each being
+    /// `cell_size` big.
+    pub fn grid(&mut self, center: Vector<f64, 3>, cell_size: f64, cells: usize, color: Color) {
+        let half_extent = cell_size * cells as f64 / 2.0;
+        for i in 0..=cells {
+            let offset = i as f64 * cell_size - half_extent;
+            self.line(
+                center + v![offset, 0.0, -half_extent],
+                center + v![offset, 0.0, half_extent],
+                color,
+            );
+            self.line(
+                center + v![-half_extent, 0.0, offset],
+                center + v![half_extent, 0.0, offset],
+                color,
+            );
+        }
+    }
+
+    pub fn vertex_count(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn clear(&mut self) {
+        self.points.clear();
+    }
+}
+
+/// The GPU side of [DebugDraw].
+pub struct DebugDrawPipeline {
+    pipeline: RenderPipeline,
+    vertex_buffer: Buffer,
+    // The vertices of the last flush, kept to reuse the allocation.
+    vertices: Vec<LineVertex>,
+    // The number of vertices the buffer can hold.
+    capacity: usize,
+    // The number of vertices uploaded in the last flush.
+    vertex_count: usize,
+}
+
+impl DebugDrawPipeline {
+    /// Create the line pipeline.
+    ///
+    /// `global_layout` is the layout of the global uniforms, only the
+    /// view projection matrix is used from it.
+    pub fn new(
+        device: &Device,
+        global_layout: &BindGroupLayout,
+        color_format: TextureFormat,
+        sample_count: u32,
+    ) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("debug_shader"),
+            source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(include_str!("debug.wgsl"))),
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("debug_pipeline_layout"),
+            bind_group_layouts: &[global_layout],
+            immediate_size: 0,
+        });
+
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("debug_pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                buffers: &[LineVertex::layout()],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: color_format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::LineList,
+                ..Default::default()
+            },
+            // Lines are hidden by the geometry in front of them, but don't occlude anything.
+            depth_stencil: Some(DepthStencilState {
+                format: wgpu::TextureFormat::Depth32Float,
+                depth_compare: wgpu::CompareFunction::GreaterEqual,
+                depth_write_enabled: false,
+                stencil: StencilState::default(),
+                bias: DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState {
+                count: sample_count,
+                ..Default::default()
+            },
+            multiview_mask: None,
+            cache: None,
+        });
+
+        let capacity = 1024;
+        let vertex_buffer = Self::create_vertex_buffer(device, capacity);
+
+        Self {
+            pipeline,
+            vertex_buffer,
+            vertices: Vec::new(),
+            capacity,
+            vertex_count: 0,
+        }
+    }
+
+    fn create_vertex_buffer(device: &Device, capacity: usize) -> Buffer {
+        device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("debug_vertex_buffer"),
+            size: capacity as BufferAddress * LineVertex::SIZE,
+            usage: BufferUsages::VERTEX | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        })
+    }
+
+    /// Record the upload of all lines from `debug_draw` into `encoder`,
+    /// relative to `origin`, then clear it.
+    pub fn flush(
+        &mut self,
+        device: &Device,
+        uploads: &mut UploadBelt,
+        encoder: &mut CommandEncoder,
+        debug_draw: &mut DebugDraw,
+        origin: Vector<f64, 3>,
+    ) {
+        self.vertices.clear();
+        self.vertices
+            .extend(debug_draw.points.iter().map(|point| LineVertex {
+                position: (point.position - origin).map(|value| value as f32),
+                color: point.color,
+            }));
+        self.vertex_count = debug_draw.vertex_count();
+        if self.vertex_count > self.capacity {
+            self.capacity = self.vertex_count.next_power_of_two();
+            self.vertex_buffer = Self::create_vertex_buffer(device, self.capacity);
+        }
+
+        uploads.write(
+            encoder,
+            &self.vertex_buffer,
+            0,
+            bytemuck::cast_slice(&self.vertices),
+        );
+        debug_draw.clear();
+    }
+
+    /// Draw the lines uploaded by the last [flush](DebugDrawPipeline::flush).
+    ///
+    /// The global uniforms are expected to be bound to group 0 already.
+    ///
+    /// Returns the number of vertices drawn.
+    pub fn draw(&self, render_pass: &mut RenderPass) -> u32 {
+        if self.vertex_count == 0 {
+            return 0;
+        }
+
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+        render_pass.draw(0..self.vertex_count as u32, 0..1);
+        self.vertex_count as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lina::angle::Rad;
+    use quaternion::Quaternion;
+
+    use super::*;
+
+    #[test]
+    fn aabb_has_twelve_edges() {
+        let mut debug_draw = DebugDraw::new();
+        debug_draw.aabb(v![0.0, 0.0, 0.0], v![1.0, 1.0, 1.0], Color::WHITE);
+
+        assert_eq!(debug_draw.vertex_count(), 24);
+        // Every edge is axis aligned and of unit length.
+        for line in debug_draw.points.chunks(2) {
+            assert_eq!((line[1].position - line[0].position).length_squared(), 1.0);
+        }
+    }
+
+    #[test]
+    fn obb_edges_follow_the_rotation() {
+        let rotation = Quaternion::<f32>::new_unit(Rad(0.5), v![0.0, 1.0, 0.0]);
+        let obb = Obb::new(v![1.0, 2.0, 3.0], v![1.0, 0.5, 0.25], rotation);
+        let mut debug_draw = DebugDraw::new();
+        debug_draw.obb(&obb, v![1e6, 0.0, -1e6], Color::WHITE);
+
+        assert_eq!(debug_draw.vertex_count(), 24);
+        for line in debug_draw.points.chunks(2) {
+            let edge = (line[1].position - line[0].position).map(|value| value as f32);
+            // Along one of the axes, as long as the box is along it.
+            let axis = (0..3)
+                .find(|axis| (edge * obb.axes[*axis]).abs() > 1e-3)
+                .unwrap();
+            assert!(edge.approx_eq(&(obb.axes[axis] * (2.0 * obb.half_extents[axis])), 1e-5));
+        }
+    }
+
+    #[test]
+    fn grid_lines() {
+        let mut debug_draw = DebugDraw::new();
+        debug_draw.grid(v![0.0, 0.0, 0.0], 1.0, 4, Color::WHITE);
+
+        // 5 lines along each axis
+        assert_eq!(debug_draw.vertex_count(), 2 * 2 * 5);
+    }
+}
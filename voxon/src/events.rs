@@ -0,0 +1,27 @@
+//! The events the parts of voxon send each other over the [Events](engine::events::Events)
+//! bus, published where they happen and handled once per frame.
+
+use winit::{dpi::PhysicalSize, window::WindowId};
+
+use crate::world::Block;
+
+/// A window of the app changed its size.
+#[derive(Debug, Clone, Copy)]
+pub struct WindowResized {
+    pub window: WindowId,
+    pub size: PhysicalSize<u32>,
+}
+
+/// A block of the world was broken or placed.
+#[derive(Debug, Clone, Copy)]
+pub struct BlockChanged {
+    pub position: [i32; 3],
+    pub previous: Block,
+    pub block: Block,
+}
+
+/// The selected units were replaced.
+#[derive(Debug, Clone, Copy)]
+pub struct UnitsSelected {
+    pub count: usize,
+}
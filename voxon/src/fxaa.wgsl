@@ -0,0 +1,56 @@
+// Fast approximate anti-aliasing, after Timothy Lottes' FXAA.
+//
+// Finds the direction of the edges by the luma of the corner neighbours and
+// blurs along them, unless that overshoots the local contrast.
+
+const REDUCE_MIN: f32 = 1.0 / 128.0;
+const REDUCE_MUL: f32 = 1.0 / 8.0;
+// The furthest the blur reaches, in texels.
+const SPAN_MAX: f32 = 8.0;
+
+// Perceived brightness, the square root approximates the gamma of the
+// linear colors.
+fn luma(color: vec3f) -> f32 {
+    return sqrt(dot(color, vec3f(0.299, 0.587, 0.114)));
+}
+
+fn sample_at(uv: vec2f) -> vec3f {
+    return textureSampleLevel(input_texture, input_sampler, uv, 0.0).rgb;
+}
+
+@fragment
+fn fs_main(in: VSOutput) -> @location(0) vec4f {
+    let texel = 1.0 / vec2f(textureDimensions(input_texture));
+    let uv = in.uv;
+
+    let luma_nw = luma(sample_at(uv + vec2f(-1.0, -1.0) * texel));
+    let luma_ne = luma(sample_at(uv + vec2f(1.0, -1.0) * texel));
+    let luma_sw = luma(sample_at(uv + vec2f(-1.0, 1.0) * texel));
+    let luma_se = luma(sample_at(uv + vec2f(1.0, 1.0) * texel));
+    let center = sample_at(uv);
+    let luma_m = luma(center);
+
+    let luma_min = min(luma_m, min(min(luma_nw, luma_ne), min(luma_sw, luma_se)));
+    let luma_max = max(luma_m, max(max(luma_nw, luma_ne), max(luma_sw, luma_se)));
+
+    // Perpendicular to the gradient, along the edge.
+    var direction = vec2f(
+        -((luma_nw + luma_ne) - (luma_sw + luma_se)),
+        (luma_nw + luma_sw) - (luma_ne + luma_se),
+    );
+    let reduce = max((luma_nw + luma_ne + luma_sw + luma_se) * (0.25 * REDUCE_MUL), REDUCE_MIN);
+    let scale = 1.0 / (min(abs(direction.x), abs(direction.y)) + reduce);
+    direction = clamp(direction * scale, vec2f(-SPAN_MAX), vec2f(SPAN_MAX)) * texel;
+
+    let near = 0.5 * (sample_at(uv + direction * (1.0 / 3.0 - 0.5))
+        + sample_at(uv + direction * (2.0 / 3.0 - 0.5)));
+    let far = near * 0.5 + 0.25 * (sample_at(uv + direction * -0.5)
+        + sample_at(uv + direction * 0.5));
+
+    // The wider blur crossed another edge, the narrow one is safer.
+    let luma_far = luma(far);
+    if luma_far < luma_min || luma_far > luma_max {
+        return vec4f(near, 1.0);
+    }
+    return vec4f(far, 1.0);
+}
@@ -1,89 +1,501 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
-use graphic::camera::Camera;
-use wgpu::{Adapter, Device, ExperimentalFeatures, Queue, Surface};
-use winit::{dpi::PhysicalSize, window::Window};
+use ecs::{Entity, Time};
+use web_time::Instant;
+use wgpu::{
+    Adapter, Device, Instance, PresentMode, Queue, Surface, SurfaceConfiguration, SurfaceError,
+    TextureUsages,
+};
+use winit::{
+    dpi::PhysicalSize,
+    window::{Window, WindowId},
+};
 
-use crate::scene::Scene;
+use crate::{
+    adapter::{AdapterChoice, AdapterSettings},
+    capture::FrameCapture,
+    overlay::Overlay,
+    scene::{DrawStats, Scene},
+    view::{RenderTarget, View, Viewport},
+};
 
+/// Configuration of the [Wgpu] renderer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderSettings {
+    /// How finished frames are handed to the display.
+    ///
+    /// `Fifo` waits for vertical sync, `Mailbox` replaces the waiting frame
+    /// without tearing and `Immediate` presents right away. Modes the surface
+    /// doesn't support fall back to [PresentMode::AutoVsync].
+    pub present_mode: PresentMode,
+    /// Samples per pixel for multisample anti-aliasing, 1 disables it.
+    ///
+    /// Usually 4 or 8, falls back to the highest count the adapter supports.
+    /// Can't be changed after creation.
+    pub sample_count: u32,
+    /// Which GPU to render with. Can't be changed after creation.
+    pub adapter: AdapterSettings,
+}
+
+impl Default for RenderSettings {
+    fn default() -> Self {
+        Self {
+            present_mode: PresentMode::AutoVsync,
+            sample_count: 4,
+            adapter: AdapterSettings::default(),
+        }
+    }
+}
+
+/// Why the GPU could not be set up for rendering.
+#[derive(Debug)]
+pub enum GpuInitError {
+    /// No surface could be created for the window.
+    CreateSurface(wgpu::CreateSurfaceError),
+    /// There is no adapter matching the requirements, e.g. on a machine
+    /// without a GPU or software renderer.
+    AdapterNotFound(wgpu::RequestAdapterError),
+    /// No adapter matches the [AdapterChoice].
+    UnknownAdapter(AdapterChoice),
+    /// The adapter refused to create the device.
+    RequestDevice(wgpu::RequestDeviceError),
+    /// The adapter can't present to the window's surface.
+    UnsupportedSurface,
+}
+
+impl std::fmt::Display for GpuInitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GpuInitError::CreateSurface(err) => write!(f, "failed to create the surface: {err}"),
+            GpuInitError::AdapterNotFound(err) => write!(f, "no suitable adapter: {err}"),
+            GpuInitError::UnknownAdapter(choice) => write!(f, "there is no adapter {choice}"),
+            GpuInitError::RequestDevice(err) => write!(f, "failed to create the device: {err}"),
+            GpuInitError::UnsupportedSurface => {
+                write!(f, "the adapter can't present to the window's surface")
+            }
+        }
+    }
+}
+
+impl std::error::Error for GpuInitError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            GpuInitError::CreateSurface(err) => Some(err),
+            GpuInitError::AdapterNotFound(err) => Some(err),
+            GpuInitError::RequestDevice(err) => Some(err),
+            GpuInitError::UnknownAdapter(_) | GpuInitError::UnsupportedSurface => None,
+        }
+    }
+}
+
+/// Why a frame was not rendered to a window by [Wgpu::render].
+///
+/// Except for [RenderError::OutOfMemory] the frame is simply skipped and
+/// rendering can continue with the next one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenderError {
+    /// The surface was outdated or lost, e.g. after a resize or alt-tab,
+    /// and has been reconfigured.
+    SurfaceReconfigured,
+    /// The next frame did not become available in time.
+    Timeout,
+    /// There is not enough memory left to render, the application should exit.
+    OutOfMemory,
+    /// Any other error reported by the surface.
+    Other,
+}
+
+impl std::fmt::Display for RenderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RenderError::SurfaceReconfigured => write!(f, "the surface had to be reconfigured"),
+            RenderError::Timeout => write!(f, "timed out acquiring the next frame"),
+            RenderError::OutOfMemory => write!(f, "out of memory"),
+            RenderError::Other => write!(f, "unknown surface error"),
+        }
+    }
+}
+
+/// Performance statistics of the [Wgpu] renderer, updated every second.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FrameStats {
+    /// FPS statistics of the last second.
+    pub frametime: frametime::Stats,
+    /// Average CPU time spent simulating and recording a frame.
+    pub cpu_time: Duration,
+    /// GPU time of each render pass in a recent frame,
+    /// `None` if the adapter doesn't support timestamp queries.
+    pub gpu_pass_times: Option<Vec<(&'static str, Duration)>>,
+    /// Draw calls of the last frame.
+    pub draw_calls: u32,
+    /// Triangles drawn in the last frame.
+    pub triangles: u64,
+}
+
+impl std::fmt::Display for FrameStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.frametime)?;
+        writeln!(f, "CPU: {:.3} ms", self.cpu_time.as_secs_f64() * 1000.0)?;
+        match &self.gpu_pass_times {
+            Some(pass_times) => {
+                for (pass, time) in pass_times {
+                    writeln!(f, "GPU {pass}: {:.3} ms", time.as_secs_f64() * 1000.0)?;
+                }
+            }
+            None => writeln!(f, "GPU: not supported")?,
+        }
+        writeln!(f, "Draw calls: {}", self.draw_calls)?;
+        write!(f, "Triangles: {}", self.triangles)
+    }
+}
+
+/// Renders the [Scene] to any number of windows, sharing the device, the
+/// queue and the scene between them.
+///
+/// Every window shows the scene from its own camera. The first one is the
+/// main window, the only one showing the [Overlay].
 pub struct Wgpu {
-    pub inner_size: PhysicalSize<u32>,
-    pub adapter: Adapter,
-    pub surface: Surface<'static>,
+    instance: Instance,
+    adapter: Adapter,
     pub device: Device,
     pub queue: Queue,
     pub scene: Scene,
+    windows: Vec<WindowSurface>,
     frametimes: frametime::Sampler<1024>,
-    elapsed_time: std::time::Duration,
+    // The statistics of the last full second.
+    frame_stats: FrameStats,
+    elapsed_time: Duration,
+    // CPU time spent rendering and the number of frames since the last update.
+    cpu_time: Duration,
+    cpu_frames: u32,
+    // Frames of the main window to save.
+    capture: FrameCapture,
+}
+
+/// A window [Wgpu] presents to, with the [View]s of the scene it shows.
+struct WindowSurface {
+    id: WindowId,
+    surface: Surface<'static>,
+    config: SurfaceConfiguration,
+    present_modes: Vec<PresentMode>,
+    target: RenderTarget,
+    // Drawn in order, the later ones on top.
+    views: Vec<View>,
+}
+
+impl WindowSurface {
+    fn new(
+        id: WindowId,
+        surface: Surface<'static>,
+        config: SurfaceConfiguration,
+        adapter: &Adapter,
+        target: RenderTarget,
+        view: View,
+    ) -> Self {
+        let capabilities = surface.get_capabilities(adapter);
+        let present_modes = capabilities.present_modes;
+        tracing::debug!(?present_modes, "Supported present modes");
+        // Frames can only be captured if they can be copied.
+        let mut config = config;
+        if capabilities.usages.contains(TextureUsages::COPY_SRC) {
+            config.usage |= TextureUsages::COPY_SRC;
+        }
+        Self {
+            id,
+            surface,
+            config,
+            present_modes,
+            target,
+            views: vec![view],
+        }
+    }
+
+    /// Reconfigure the surface with `present_mode`.
+    ///
+    /// Unsupported modes fall back to [PresentMode::AutoVsync].
+    fn set_present_mode(&mut self, device: &Device, present_mode: PresentMode) {
+        let supported = matches!(
+            present_mode,
+            PresentMode::AutoVsync | PresentMode::AutoNoVsync
+        ) || self.present_modes.contains(&present_mode);
+
+        self.config.present_mode = if supported {
+            present_mode
+        } else {
+            tracing::warn!("Present mode {present_mode:?} is not supported, using AutoVsync");
+            PresentMode::AutoVsync
+        };
+        self.surface.configure(device, &self.config);
+    }
+
+    fn resize(&mut self, device: &Device, scene: &Scene, size: PhysicalSize<u32>) {
+        self.config.width = size.width;
+        self.config.height = size.height;
+        self.surface.configure(device, &self.config);
+        self.target.resize(device, scene.post_process(), size);
+    }
+
+    /// Render the prepared `scene` to the next frame of the window and present it,
+    /// saving it first if the `capture` asks for it.
+    fn render(
+        &mut self,
+        scene: &mut Scene,
+        device: &Device,
+        queue: &Queue,
+        overlay: Option<&mut Overlay>,
+        capture: Option<&mut FrameCapture>,
+    ) -> Result<DrawStats, RenderError> {
+        let frame = match self.surface.get_current_texture() {
+            Ok(frame) => frame,
+            Err(error) => {
+                // Drop the widgets of the skipped frame, so they don't pile up.
+                if let Some(overlay) = overlay {
+                    overlay.clear();
+                }
+                return Err(match error {
+                    SurfaceError::Outdated | SurfaceError::Lost => {
+                        self.surface.configure(device, &self.config);
+                        RenderError::SurfaceReconfigured
+                    }
+                    SurfaceError::Timeout => RenderError::Timeout,
+                    SurfaceError::OutOfMemory => RenderError::OutOfMemory,
+                    SurfaceError::Other => RenderError::Other,
+                });
+            }
+        };
+
+        let stats = scene.render(
+            &self.target,
+            &self.views,
+            &frame.texture,
+            device,
+            queue,
+            overlay,
+        );
+        if let Some(capture) = capture {
+            capture.capture(device, queue, &frame.texture);
+        }
+
+        // A suboptimal frame can still be presented, but the surface
+        // should be reconfigured before the next one.
+        let suboptimal = frame.suboptimal;
+        frame.present();
+        if suboptimal {
+            self.surface.configure(device, &self.config);
+        }
+        Ok(stats)
+    }
 }
 
 impl Wgpu {
-    pub async fn new(window: Arc<Window>) -> Self {
-        let instance = wgpu::Instance::default();
+    /// Set up rendering to the main `window`, showing the scene from its
+    /// [main camera](Scene::main_camera).
+    #[tracing::instrument(name = "gpu_setup", skip_all)]
+    pub async fn new(window: Arc<Window>, settings: RenderSettings) -> Result<Self, GpuInitError> {
+        let instance = settings.adapter.create_instance().await;
+        let id = window.id();
         let inner_size = window.inner_size();
-        let surface = instance.create_surface(window).unwrap();
+        let surface = instance
+            .create_surface(window)
+            .map_err(GpuInitError::CreateSurface)?;
         // Request an adapter that can support our surface
-        let adapter = instance
-            .request_adapter(&wgpu::RequestAdapterOptions {
-                power_preference: wgpu::PowerPreference::default(),
-                force_fallback_adapter: false,
-                compatible_surface: Some(&surface),
-            })
-            .await
-            .expect("Failed to find an appropriate adapter");
-
-        // Create logical device and command queue
-        let (device, queue) = adapter
-            .request_device(&wgpu::DeviceDescriptor {
-                label: Some("gpu_device"),
-                required_features: wgpu::Features::empty(),
-                required_limits: wgpu::Limits::downlevel_defaults()
-                    .using_resolution(adapter.limits()),
-                memory_hints: wgpu::MemoryHints::Performance,
-                trace: wgpu::Trace::Off,
-                experimental_features: ExperimentalFeatures::disabled(),
-            })
-            .await
-            .expect("Failed to create device");
-        println!("Prepared device: {device:?}",);
+        let adapter = settings
+            .adapter
+            .select_adapter(&instance, Some(&surface))
+            .await?;
 
         // Configure surface
-        let config = surface
+        let surface_config = surface
             .get_default_config(&adapter, inner_size.width, inner_size.height)
-            .unwrap();
-        surface.configure(&device, &config);
+            .ok_or(GpuInitError::UnsupportedSurface)?;
+
+        let (device, queue) = settings.adapter.request_device(&adapter).await?;
 
-        let scene = Scene::new(&adapter, &surface, &device, &queue);
+        let scene = Scene::new(
+            &adapter,
+            surface_config.format,
+            &device,
+            &queue,
+            settings.sample_count,
+        );
+        let target = scene.create_target(&device, inner_size);
+        let view = View::new(scene.main_camera(), Viewport::FULL);
+        let window = WindowSurface::new(id, surface, surface_config, &adapter, target, view);
 
-        Wgpu {
-            inner_size,
+        let mut gpu = Wgpu {
+            instance,
             adapter,
-            surface,
             device,
             queue,
             scene,
+            windows: vec![window],
             frametimes: frametime::Sampler::new(),
-            elapsed_time: std::time::Duration::default(),
+            frame_stats: FrameStats::default(),
+            elapsed_time: Duration::default(),
+            cpu_time: Duration::default(),
+            cpu_frames: 0,
+            capture: FrameCapture::default(),
+        };
+        // Configures the surface as well.
+        gpu.set_present_mode(settings.present_mode);
+        Ok(gpu)
+    }
+
+    /// Render to another `window` as well, showing the scene from `camera`.
+    ///
+    /// Fails if the window's surface can't be rendered to with the format
+    /// of the main window.
+    pub fn add_window(&mut self, window: Arc<Window>, camera: Entity) -> Result<(), GpuInitError> {
+        let id = window.id();
+        let inner_size = window.inner_size();
+        let surface = self
+            .instance
+            .create_surface(window)
+            .map_err(GpuInitError::CreateSurface)?;
+
+        let format = self.scene.color_format();
+        if !surface
+            .get_capabilities(&self.adapter)
+            .formats
+            .contains(&format)
+        {
+            return Err(GpuInitError::UnsupportedSurface);
         }
+        let mut surface_config = surface
+            .get_default_config(&self.adapter, inner_size.width, inner_size.height)
+            .ok_or(GpuInitError::UnsupportedSurface)?;
+        surface_config.format = format;
+
+        let target = self.scene.create_target(&self.device, inner_size);
+        let view = View::new(camera, Viewport::FULL);
+        let mut window =
+            WindowSurface::new(id, surface, surface_config, &self.adapter, target, view);
+        window.set_present_mode(&self.device, self.present_mode());
+        self.windows.push(window);
+        Ok(())
+    }
+
+    /// Replace the views drawn into the window of `id`, in drawing order.
+    ///
+    /// E.g. two halves for split screen, or a minimap in a corner after
+    /// the view covering the whole window.
+    pub fn set_views(&mut self, id: WindowId, views: Vec<View>) {
+        if let Some(window) = self.windows.iter_mut().find(|window| window.id == id) {
+            window.views = views;
+        }
+    }
+
+    /// Stop rendering to the window of `id`, e.g. once it was closed.
+    pub fn remove_window(&mut self, id: WindowId) {
+        self.windows.retain(|window| window.id != id);
+    }
+
+    pub fn frame_stats(&self) -> &FrameStats {
+        &self.frame_stats
     }
 
-    pub fn render(&mut self, camera: &Camera, delta_t: std::time::Duration) {
+    /// The frames of the main window to save, e.g. as screenshots.
+    pub fn frame_capture(&mut self) -> &mut FrameCapture {
+        &mut self.capture
+    }
+
+    pub fn vsync(&self) -> bool {
+        matches!(
+            self.present_mode(),
+            PresentMode::AutoVsync | PresentMode::Fifo | PresentMode::FifoRelaxed
+        )
+    }
+
+    /// Reconfigure the surfaces to wait for vertical sync or not.
+    ///
+    /// The automatic present modes fall back to a supported mode.
+    pub fn set_vsync(&mut self, vsync: bool) {
+        self.set_present_mode(if vsync {
+            PresentMode::AutoVsync
+        } else {
+            PresentMode::AutoNoVsync
+        });
+    }
+
+    /// The present mode of the main window.
+    pub fn present_mode(&self) -> PresentMode {
+        self.windows
+            .first()
+            .map_or(PresentMode::AutoVsync, |window| window.config.present_mode)
+    }
+
+    /// The present modes the main window supports, besides the automatic ones.
+    pub fn supported_present_modes(&self) -> &[PresentMode] {
+        self.windows
+            .first()
+            .map_or(&[], |window| &window.present_modes)
+    }
+
+    /// Reconfigure the surface of every window with `present_mode`.
+    ///
+    /// Unsupported modes fall back to [PresentMode::AutoVsync].
+    pub fn set_present_mode(&mut self, present_mode: PresentMode) {
+        for window in &mut self.windows {
+            window.set_present_mode(&self.device, present_mode);
+        }
+    }
+
+    /// Reconfigure the surface of the window of `id` and everything
+    /// depending on its size.
+    ///
+    /// A minimized window reports a zero size, which can't be rendered to,
+    /// so it is ignored until the window is restored.
+    pub fn resize(&mut self, id: WindowId, size: PhysicalSize<u32>) {
+        if size.width == 0 || size.height == 0 {
+            return;
+        }
+        if let Some(window) = self.windows.iter_mut().find(|window| window.id == id) {
+            window.resize(&self.device, &self.scene, size);
+        }
+    }
+
+    /// Simulate the next frame and render it to every window.
+    ///
+    /// The simulation always advances to `time`, even if the frame has to
+    /// be skipped for some windows because of a [RenderError]. Of multiple
+    /// errors the most severe one is returned.
+    #[tracing::instrument(name = "frame", skip_all, fields(frame = time.frame()))]
+    pub fn render(&mut self, overlay: &mut Overlay, time: &Time) -> Result<(), RenderError> {
+        let start = Instant::now();
+        let delta_t = time.real_delta();
         self.frametimes.add_frametime(delta_t.as_nanos());
         self.elapsed_time += delta_t;
 
-        if self.elapsed_time > std::time::Duration::from_secs(1) {
-            self.elapsed_time -= std::time::Duration::from_secs(1);
-            let stats = self.frametimes.stats();
-            println!("{}", stats);
+        if self.elapsed_time > Duration::from_secs(1) {
+            self.elapsed_time -= Duration::from_secs(1);
+            self.frame_stats.frametime = self.frametimes.stats();
+            self.frame_stats.cpu_time = self.cpu_time / self.cpu_frames.max(1);
+            self.frame_stats.gpu_pass_times = self.scene.gpu_pass_times();
+            self.cpu_time = Duration::ZERO;
+            self.cpu_frames = 0;
+            tracing::debug!("Frame statistics\n{}", self.frame_stats);
         }
 
-        self.scene.simulate(delta_t);
-        self.scene.render(
-            &self.inner_size,
-            &self.surface,
-            &self.device,
-            &self.queue,
-            camera,
-        );
+        self.scene.simulate(time);
+        self.scene.prepare(&self.device, &self.queue);
+
+        let mut stats = DrawStats::default();
+        let mut result = Ok(());
+        for (index, window) in self.windows.iter_mut().enumerate() {
+            let main = index == 0;
+            let overlay = main.then_some(&mut *overlay);
+            let capture = main.then_some(&mut self.capture);
+            match window.render(&mut self.scene, &self.device, &self.queue, overlay, capture) {
+                Ok(window_stats) => stats += window_stats,
+                Err(error) if result != Err(RenderError::OutOfMemory) => result = Err(error),
+                Err(_) => {}
+            }
+        }
+
+        self.frame_stats.draw_calls = stats.draw_calls;
+        self.frame_stats.triangles = stats.triangles;
+        self.cpu_time += start.elapsed();
+        self.cpu_frames += 1;
+        result
     }
 }
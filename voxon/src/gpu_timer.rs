@@ -0,0 +1,198 @@
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicU8, Ordering},
+    },
+    time::Duration,
+};
+
+use wgpu::{
+    Buffer, BufferAddress, BufferUsages, CommandEncoder, Device, Features, QuerySet, Queue,
+    RenderPassTimestampWrites,
+};
+
+/// Measures how long the render passes take on the GPU with timestamp queries.
+///
+/// Requires [Features::TIMESTAMP_QUERY], without it nothing is measured.
+///
+/// The results are read back asynchronously, so they lag a few frames behind.
+/// While a read back is in flight the frames are not measured, to avoid
+/// stalling the GPU.
+pub struct GpuTimer {
+    passes: &'static [&'static str],
+    // Nanoseconds per timestamp tick.
+    period: f32,
+    // `None` if timestamp queries are not supported.
+    queries: Option<Queries>,
+    times: Option<Vec<Duration>>,
+}
+
+struct Queries {
+    query_set: QuerySet,
+    resolve_buffer: Buffer,
+    read_buffer: Buffer,
+    // Whether the current frame is measured.
+    active: bool,
+    // Whether the read buffer is waiting to be mapped or is mapped.
+    in_flight: bool,
+    // The outcome of mapping the read buffer, written by the map callback.
+    map_status: Arc<AtomicU8>,
+}
+
+const MAP_PENDING: u8 = 0;
+const MAP_DONE: u8 = 1;
+const MAP_FAILED: u8 = 2;
+
+impl GpuTimer {
+    /// Time each of the render passes named in `passes`, in the order they run.
+    pub fn new(device: &Device, queue: &Queue, passes: &'static [&'static str]) -> Self {
+        let queries = device
+            .features()
+            .contains(Features::TIMESTAMP_QUERY)
+            .then(|| {
+                // A beginning and an end timestamp for each pass.
+                let count = 2 * passes.len() as u32;
+                let size = count as BufferAddress * wgpu::QUERY_SIZE as BufferAddress;
+                Queries {
+                    query_set: device.create_query_set(&wgpu::QuerySetDescriptor {
+                        label: Some("gpu_timer_queries"),
+                        ty: wgpu::QueryType::Timestamp,
+                        count,
+                    }),
+                    resolve_buffer: device.create_buffer(&wgpu::BufferDescriptor {
+                        label: Some("gpu_timer_resolve_buffer"),
+                        size,
+                        usage: BufferUsages::QUERY_RESOLVE | BufferUsages::COPY_SRC,
+                        mapped_at_creation: false,
+                    }),
+                    read_buffer: device.create_buffer(&wgpu::BufferDescriptor {
+                        label: Some("gpu_timer_read_buffer"),
+                        size,
+                        usage: BufferUsages::COPY_DST | BufferUsages::MAP_READ,
+                        mapped_at_creation: false,
+                    }),
+                    active: false,
+                    in_flight: false,
+                    map_status: Arc::new(AtomicU8::new(MAP_PENDING)),
+                }
+            });
+
+        Self {
+            passes,
+            period: queue.get_timestamp_period(),
+            queries,
+            times: None,
+        }
+    }
+
+    /// The names of the passes with the GPU time of the last measured frame,
+    /// `None` if timestamp queries aren't supported or nothing was measured yet.
+    pub fn pass_times(&self) -> Option<Vec<(&'static str, Duration)>> {
+        let times = self.times.as_ref()?;
+        Some(
+            self.passes
+                .iter()
+                .copied()
+                .zip(times.iter().copied())
+                .collect(),
+        )
+    }
+
+    /// Collect the results of an earlier frame if they arrived and decide
+    /// whether this frame is measured.
+    pub fn begin_frame(&mut self, device: &Device) {
+        let Some(queries) = &mut self.queries else {
+            return;
+        };
+
+        if queries.in_flight {
+            // Only checks for finished work, never blocks.
+            let _ = device.poll(wgpu::PollType::Poll);
+            match queries.map_status.swap(MAP_PENDING, Ordering::Acquire) {
+                MAP_DONE => {
+                    let data = queries.read_buffer.slice(..).get_mapped_range();
+                    let timestamps: &[u64] = bytemuck::cast_slice(&data);
+                    self.times = Some(
+                        timestamps
+                            .chunks_exact(2)
+                            .map(|pass| {
+                                let ticks = pass[1].saturating_sub(pass[0]);
+                                Duration::from_nanos((ticks as f64 * self.period as f64) as u64)
+                            })
+                            .collect(),
+                    );
+                    drop(data);
+                    queries.read_buffer.unmap();
+                    queries.in_flight = false;
+                }
+                // Try again with the next frame.
+                MAP_FAILED => queries.in_flight = false,
+                _ => {}
+            }
+        }
+        queries.active = !queries.in_flight;
+    }
+
+    /// The timestamp writes of the `index`th pass, `None` if this frame isn't measured.
+    pub fn timestamp_writes(&self, index: usize) -> Option<RenderPassTimestampWrites<'_>> {
+        let queries = self.queries.as_ref().filter(|queries| queries.active)?;
+        Some(RenderPassTimestampWrites {
+            query_set: &queries.query_set,
+            beginning_of_pass_write_index: Some(2 * index as u32),
+            end_of_pass_write_index: Some(2 * index as u32 + 1),
+        })
+    }
+
+    /// The timestamp writes of one of several render passes measured together as
+    /// the `index`th pass, from the beginning of the `first` to the end of the
+    /// `last` of them. `None` for the passes in between.
+    pub fn span_timestamp_writes(
+        &self,
+        index: usize,
+        first: bool,
+        last: bool,
+    ) -> Option<RenderPassTimestampWrites<'_>> {
+        if !first && !last {
+            return None;
+        }
+        let writes = self.timestamp_writes(index)?;
+        Some(RenderPassTimestampWrites {
+            beginning_of_pass_write_index: writes.beginning_of_pass_write_index.filter(|_| first),
+            end_of_pass_write_index: writes.end_of_pass_write_index.filter(|_| last),
+            ..writes
+        })
+    }
+
+    /// Copy the timestamps into the read buffer, after all passes were recorded.
+    pub fn resolve(&self, encoder: &mut CommandEncoder) {
+        let Some(queries) = self.queries.as_ref().filter(|queries| queries.active) else {
+            return;
+        };
+        let count = 2 * self.passes.len() as u32;
+        encoder.resolve_query_set(&queries.query_set, 0..count, &queries.resolve_buffer, 0);
+        encoder.copy_buffer_to_buffer(
+            &queries.resolve_buffer,
+            0,
+            &queries.read_buffer,
+            0,
+            queries.resolve_buffer.size(),
+        );
+    }
+
+    /// Start reading back the timestamps, after the frame was submitted.
+    pub fn end_frame(&mut self) {
+        let Some(queries) = self.queries.as_mut().filter(|queries| queries.active) else {
+            return;
+        };
+        let map_status = Arc::clone(&queries.map_status);
+        queries
+            .read_buffer
+            .slice(..)
+            .map_async(wgpu::MapMode::Read, move |result| {
+                let status = if result.is_ok() { MAP_DONE } else { MAP_FAILED };
+                map_status.store(status, Ordering::Release);
+            });
+        queries.in_flight = true;
+        queries.active = false;
+    }
+}
@@ -1,35 +1,267 @@
-use std::sync::Arc;
+use std::{f32::consts::PI, time::Duration};
 
-use graphic::camera::Camera;
-use winit::window::Window;
+use ecs::{Entity, Time};
+use engine::{
+    events::{Events, Subscriber},
+    window::{AppWindow, WindowOptions, load_icon},
+};
+use graphic::camera::{Camera, CameraMode, Smoothing};
+use lina::{angle::Deg, v};
+use winit::{
+    dpi::LogicalSize,
+    window::{CursorGrabMode, WindowId},
+};
 
-use crate::gpu::Wgpu;
+use crate::{
+    events::{BlockChanged, UnitsSelected, WindowResized},
+    gpu::Wgpu,
+    overlay::Overlay,
+    particles::{Emitter, EmitterSettings},
+    view::{View, Viewport},
+};
 
 pub(super) struct InnerApp {
-    pub window: Arc<Window>,
+    pub window: AppWindow,
     pub gpu: Wgpu,
-    pub camera: Camera,
-    pub prev_render_time: std::time::Instant,
+    pub overlay: Overlay,
+    pub time: Time,
+    // Shows the scene from above while open.
+    map_window: Option<AppWindow>,
+    // The camera of the map window and the minimap, kept while both are closed.
+    map_camera: Entity,
+    // Views of the main window besides the one of the main camera.
+    minimap: bool,
+    split_screen: bool,
+    cursor_grabbed: bool,
+    // The horizontal field of view of the main camera.
+    fov: Deg,
+    resized: Subscriber<WindowResized>,
+    block_changes: Subscriber<BlockChanged>,
+    selections: Subscriber<UnitsSelected>,
 }
 
+/// The windows can't be made smaller, the overlay wouldn't fit.
+const MIN_WINDOW_SIZE: LogicalSize<u32> = LogicalSize::new(320, 240);
+
+/// How high above the main camera the map camera looks down from.
+const MAP_HEIGHT: f64 = 20.0;
+
+/// The main camera's half of the main window in split screen.
+const SPLIT_LEFT: Viewport = Viewport {
+    x: 0.0,
+    y: 0.0,
+    width: 0.5,
+    height: 1.0,
+};
+
+/// The minimap in the top right corner of the main window.
+const MINIMAP: Viewport = Viewport {
+    x: 0.75,
+    y: 0.0,
+    width: 0.25,
+    height: 0.25,
+};
+
 impl InnerApp {
-    pub fn new(event_loop: &winit::event_loop::ActiveEventLoop) -> Self {
-        let window_attributes = Window::default_attributes()
-            .with_title("Voxon")
-            .with_resizable(false)
-            .with_inner_size(winit::dpi::LogicalSize::new(1024.0, 768.0));
+    /// Open the main window, in the browser a canvas appended to the page.
+    ///
+    /// A `fullscreen` window covers the monitor it is opened on.
+    pub fn open_window(
+        event_loop: &winit::event_loop::ActiveEventLoop,
+        size: LogicalSize<u32>,
+        fullscreen: bool,
+    ) -> AppWindow {
+        let options = WindowOptions::new("Voxon")
+            .with_size(size)
+            .with_min_size(MIN_WINDOW_SIZE)
+            .with_fullscreen(fullscreen);
+        let options = match load_icon(include_bytes!("../assets/block.png")) {
+            Ok(icon) => options.with_icon(icon),
+            Err(err) => {
+                tracing::error!("Failed to load the window icon: {err}");
+                options
+            }
+        };
+
+        options.open(event_loop).unwrap()
+    }
 
-        let window = Arc::new(event_loop.create_window(window_attributes).unwrap());
+    /// Set up the app in the main `window`, with `gpu` rendering to it.
+    ///
+    /// Only the `events` published from now on are [handled](InnerApp::handle_events).
+    pub fn new(window: AppWindow, mut gpu: Wgpu, events: &Events) -> Self {
+        let map_camera = gpu.scene.add_camera(Camera::default());
 
-        let gpu = pollster::block_on(Wgpu::new(Arc::clone(&window)));
+        let camera = gpu.scene.camera_mut();
+        camera.set_mode(CameraMode::fps());
+        // Mouse input arrives in steps, smooth it out a little.
+        camera.set_smoothing(Some(Smoothing {
+            translation: Duration::from_millis(40),
+            rotation: Duration::from_millis(25),
+        }));
 
-        let camera = Camera::default();
+        // Keep the bitmap font readable on high DPI screens.
+        let overlay = Overlay::new(window.scale_factor().round().max(1.0) as f32);
 
         InnerApp {
             window,
             gpu,
-            camera,
-            prev_render_time: std::time::Instant::now(),
+            overlay,
+            time: Time::new(),
+            map_window: None,
+            map_camera,
+            minimap: false,
+            split_screen: false,
+            cursor_grabbed: false,
+            fov: Deg(90.0),
+            resized: events.subscribe(),
+            block_changes: events.subscribe(),
+            selections: events.subscribe(),
+        }
+    }
+
+    /// Follow the changes published since the last call, before the frame
+    /// is rendered.
+    pub fn handle_events(&mut self, events: &Events) {
+        for resized in events.read(&mut self.resized) {
+            self.gpu.resize(resized.window, resized.size);
+        }
+        for changed in events.read(&mut self.block_changes) {
+            // Breaking a block throws sparks.
+            if changed.previous.is_solid() && !changed.block.is_solid() {
+                let scene = &mut self.gpu.scene;
+                let center = scene.block_center(changed.position);
+                let sparks = EmitterSettings::explosion(48);
+                scene.add_emitter(center, Emitter::once(sparks, self.time.frame()));
+            }
+        }
+        for selected in events.read(&mut self.selections) {
+            tracing::debug!("Selected {} units", selected.count);
+        }
+    }
+
+    pub fn map_window_open(&self) -> bool {
+        self.map_window.is_some()
+    }
+
+    pub fn is_map_window(&self, id: WindowId) -> bool {
+        self.map_window
+            .as_ref()
+            .is_some_and(|window| window.id() == id)
+    }
+
+    /// Open or close the second window, showing the scene from above.
+    pub fn set_map_window(&mut self, event_loop: &winit::event_loop::ActiveEventLoop, open: bool) {
+        if !open {
+            if let Some(window) = self.map_window.take() {
+                self.gpu.remove_window(window.id());
+            }
+            return;
+        }
+        if self.map_window.is_some() {
+            return;
+        }
+
+        let options = WindowOptions::new("Voxon - Map")
+            .with_size(LogicalSize::new(512, 512))
+            .with_min_size(MIN_WINDOW_SIZE);
+        let window = match options.open(event_loop) {
+            Ok(window) => window,
+            Err(err) => {
+                tracing::error!("Failed to open the map window: {err}");
+                return;
+            }
+        };
+        match self.gpu.add_window(window.shared(), self.map_camera) {
+            Ok(()) => self.map_window = Some(window),
+            Err(err) => tracing::error!("Failed to render to the map window: {err}"),
+        }
+    }
+
+    pub fn minimap(&self) -> bool {
+        self.minimap
+    }
+
+    /// Show the map camera in a corner of the main window.
+    pub fn set_minimap(&mut self, minimap: bool) {
+        self.minimap = minimap;
+        self.update_views();
+    }
+
+    pub fn split_screen(&self) -> bool {
+        self.split_screen
+    }
+
+    /// Show the main camera on the left and the map camera on the right
+    /// half of the main window.
+    pub fn set_split_screen(&mut self, split_screen: bool) {
+        self.split_screen = split_screen;
+        self.update_views();
+    }
+
+    /// Set the horizontal field of view of the main camera.
+    pub fn set_fov(&mut self, fov: Deg) {
+        self.fov = fov;
+        self.update_views();
+    }
+
+    /// The view of the main camera in the main window, e.g. for picking
+    /// what is under the cursor.
+    pub fn main_view(&self) -> View {
+        let viewport = if self.split_screen {
+            SPLIT_LEFT
+        } else {
+            Viewport::FULL
+        };
+        View::new(self.gpu.scene.main_camera(), viewport).with_fov(self.fov)
+    }
+
+    fn update_views(&mut self) {
+        let mut views = vec![self.main_view()];
+        if self.split_screen {
+            let right = Viewport {
+                x: 0.5,
+                ..SPLIT_LEFT
+            };
+            views.push(View::new(self.map_camera, right));
+        }
+        if self.minimap {
+            views.push(View::new(self.map_camera, MINIMAP));
+        }
+        self.gpu.set_views(self.window.id(), views);
+    }
+
+    /// Move the map camera above the main camera, facing the same way.
+    pub fn update_map_camera(&mut self) {
+        let camera = self.gpu.scene.camera();
+        let (eye, yaw) = (camera.eye(), camera.yaw_angle());
+        if let Some(map_camera) = self.gpu.scene.camera_of_mut(self.map_camera) {
+            map_camera.set_pose(eye + v![0.0, MAP_HEIGHT, 0.0], -PI / 2.0, yaw, 0.0);
+        }
+    }
+
+    /// Hide the cursor and keep it inside the window while `grab` is set,
+    /// so mouse-look can't escape the window. Only acts when `grab` changes.
+    ///
+    /// Platforms support either locking the cursor in place or confining it
+    /// to the window, so both are tried. Failing both only leaves the cursor free.
+    pub fn grab_cursor(&mut self, grab: bool) {
+        if self.cursor_grabbed == grab {
+            return;
+        }
+        self.cursor_grabbed = grab;
+
+        if grab {
+            let result = self
+                .window
+                .set_cursor_grab(CursorGrabMode::Locked)
+                .or_else(|_| self.window.set_cursor_grab(CursorGrabMode::Confined));
+            if let Err(err) = result {
+                tracing::warn!("Failed to grab the cursor: {err}");
+            }
+        } else if let Err(err) = self.window.set_cursor_grab(CursorGrabMode::None) {
+            tracing::warn!("Failed to release the cursor: {err}");
         }
+        self.window.set_cursor_visible(!grab);
     }
 }
@@ -0,0 +1,79 @@
+use bytemuck::{Pod, Zeroable};
+use lina::{v, vector::Vector};
+
+/// A directional light, such as the sun.
+///
+/// The light source is considered to be infinitely far away, so
+/// all of its rays are parallel, travelling along `direction`.
+/// Surfaces are shaded using the Lambertian model:
+/// ```text
+/// color = albedo * (ambient + color * max(dot(n, -direction), 0))
+/// ```
+/// where `n` is the surface normal.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Light {
+    direction: Vector<f32, 3>,
+    color: Vector<f32, 3>,
+    ambient: Vector<f32, 3>,
+}
+
+impl Light {
+    /// Create a new [Light] shining in `direction`.
+    ///
+    /// `direction` is normalized internally, `color` and `ambient` are
+    /// linear RGB values.
+    pub fn new(direction: Vector<f32, 3>, color: Vector<f32, 3>, ambient: Vector<f32, 3>) -> Self {
+        Self {
+            direction: direction.normalized(),
+            color,
+            ambient,
+        }
+    }
+
+    /// The normalized direction the light is travelling in.
+    pub fn direction(&self) -> Vector<f32, 3> {
+        self.direction
+    }
+
+    pub fn color(&self) -> Vector<f32, 3> {
+        self.color
+    }
+
+    pub fn ambient(&self) -> Vector<f32, 3> {
+        self.ambient
+    }
+
+    /// The light data as laid out in the `Light` WGSL struct.
+    pub fn to_uniform(self) -> LightUniform {
+        LightUniform {
+            direction: self.direction,
+            _padding0: 0.0,
+            color: self.color,
+            _padding1: 0.0,
+            ambient: self.ambient,
+            _padding2: 0.0,
+        }
+    }
+}
+
+/// GPU representation of a [Light].
+///
+/// Every `vec3f` is padded to 16 bytes.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+pub struct LightUniform {
+    direction: Vector<f32, 3>,
+    _padding0: f32,
+    color: Vector<f32, 3>,
+    _padding1: f32,
+    ambient: Vector<f32, 3>,
+    _padding2: f32,
+}
+
+impl Default for Light {
+    /// A white light coming from the upper left behind the default camera
+    /// with a dim ambient term.
+    fn default() -> Self {
+        Self::new(v![1.0, -1.0, -1.0], v![1.0, 1.0, 1.0], v![0.1, 0.1, 0.1])
+    }
+}
@@ -1,55 +1,225 @@
+use adapter::{AdapterChoice, AdapterSettings};
+use animation::{
+    Curve, ImportError, Interpolation, LoopMode, SkinnedModel, TransformAnimator, import_gltf,
+};
+use capture::FrameCapture;
+use components::Transform;
+use ecs::Time;
+use engine::config::{Config, ConfigError};
+use engine::events::{Events, Subscriber};
+use engine::pacing::{Pacing, PacingSettings};
+use engine::window::AppWindow;
+use events::{BlockChanged, UnitsSelected, WindowResized};
+use gpu::{GpuInitError, RenderError, RenderSettings, Wgpu};
+use graphic::color::Color;
 use inner_app::InnerApp;
+use input::{Action, Axis, BindingsFile, Input, InputEvent, Playback, Recording};
+use lina::{angle::Rad, v};
+use offscreen::Offscreen;
+use overlay::Overlay;
+use particles::{Emitter, EmitterSettings};
+use physics::Shape;
+use post::{FXAA, PostPassDescriptor, Stage, Tonemapper, Tonemapping};
+use quaternion::Quaternion;
+use scene::Scene;
+use selection::{ScreenRect, formation};
+use std::f32::consts::TAU;
+use std::time::Duration;
+use terrain::{Heightmap, TerrainSettings, generate_heightmap};
+use tracing_subscriber::{EnvFilter, Layer, layer::SubscriberExt, util::SubscriberInitExt};
+use web_time::Instant;
+use wgpu::{Backend, PowerPreference, PresentMode};
 use winit::event::{ElementState, MouseButton, MouseScrollDelta};
-use winit::event_loop::{ControlFlow, EventLoop};
+use winit::event_loop::{ControlFlow, EventLoop, EventLoopProxy};
+use world::Block;
 
-use winit::keyboard::PhysicalKey;
 use winit::{
     application::ApplicationHandler,
     event::{DeviceEvent, WindowEvent},
 };
 
+mod adapter;
+mod animation;
+mod capture;
+mod components;
+mod compute;
+mod debug_draw;
+mod events;
 mod gpu;
+mod gpu_timer;
 mod inner_app;
+mod light;
 mod mesh;
+mod navigation;
+mod offscreen;
+mod overlay;
+mod particles;
+mod physics;
+mod post;
+mod profile;
 mod scene;
+mod scene_file;
+mod selection;
+mod shadow;
+mod skinning;
+mod skybox;
+mod terrain;
+mod texture;
+mod upload;
+mod vertex;
+mod view;
+#[cfg(target_arch = "wasm32")]
+mod web;
+mod world;
 
 struct App {
     app: Option<InnerApp>,
     focused: bool,
-    navigating: bool,
     speed: f32, // speed in m/s
-    // stores for each key if it is currently being pressed/held or not
-    key_state: std::collections::BTreeMap<winit::keyboard::KeyCode, bool>,
+    input: Input,
+    // Saved on exit if a setting changed.
+    config: Config,
+    // When the main window is rendered, slowed down while in the background.
+    pacing: Pacing,
+    // Tells the input, the game logic and the renderer what the others did.
+    events: Events,
+    // Keeps the window size of the config up to date.
+    resized: Subscriber<WindowResized>,
+    // Whether a cuboid is thrown next rather than a sphere.
+    throw_cuboid: bool,
+    // The last position of the cursor in the main window.
+    cursor: Option<[f32; 2]>,
+    // Where the left mouse button was pressed to select units.
+    drag_start: Option<[f32; 2]>,
+    // How far the mouse moved since navigation started, a right click
+    // which didn't look around orders the selected units.
+    look_distance: f32,
+    scene_options: SceneOptions,
+    settings: RenderSettings,
+    // Hands the GPU setup of the main window back to the event loop.
+    proxy: Option<EventLoopProxy<UserEvent>>,
+    // The input of the user and where it is saved on exit, with `--record`.
+    recording: Option<(String, Recording)>,
+    // Input played back instead of the user's, with `--replay`.
+    playback: Option<Playback>,
+    // When the main window was set up, input is recorded and played back
+    // relative to it.
+    started: Option<Instant>,
+}
+
+/// Sent to the event loop by the tasks it started.
+enum UserEvent {
+    /// The GPU setup of the main window is done, asynchronously in the browser.
+    GpuReady(AppWindow, Result<Wgpu, GpuInitError>),
 }
 
 impl Default for App {
     fn default() -> Self {
+        let events = Events::new();
         Self {
             app: None,
             focused: false,
-            navigating: false,
             speed: 1.0,
-            key_state: Default::default(),
+            input: Input::new(),
+            config: Config::default(),
+            pacing: Pacing::new(PacingSettings::default()),
+            resized: events.subscribe(),
+            events,
+            throw_cuboid: false,
+            cursor: None,
+            drag_start: None,
+            look_distance: 0.0,
+            scene_options: SceneOptions::default(),
+            settings: RenderSettings::default(),
+            proxy: None,
+            recording: None,
+            playback: None,
+            started: None,
         }
     }
 }
 
-impl ApplicationHandler for App {
+impl ApplicationHandler<UserEvent> for App {
     fn resumed(&mut self, event_loop: &winit::event_loop::ActiveEventLoop) {
         // The Window should be created in this call, because the winit documentation states that this
         // is the only point which they could guarantee proper initialization on all supported platforms.
-        self.app = Some(InnerApp::new(event_loop));
+        let window = InnerApp::open_window(
+            event_loop,
+            self.config.window_size(),
+            self.config.fullscreen(),
+        );
+        let proxy = self
+            .proxy
+            .clone()
+            .expect("the event loop was started with a proxy");
+        let settings = self.settings.clone();
+        let setup = async move {
+            let gpu = Wgpu::new(window.shared(), settings).await;
+            // Only fails if the event loop is gone already.
+            let _ = proxy.send_event(UserEvent::GpuReady(window, gpu));
+        };
+        // The browser can't block on the setup, the event loop picks up the
+        // result either way.
+        #[cfg(target_arch = "wasm32")]
+        wasm_bindgen_futures::spawn_local(setup);
+        #[cfg(not(target_arch = "wasm32"))]
+        pollster::block_on(setup);
+    }
+
+    fn user_event(&mut self, event_loop: &winit::event_loop::ActiveEventLoop, event: UserEvent) {
+        match event {
+            UserEvent::GpuReady(window, gpu) => {
+                let gpu = match gpu {
+                    Ok(gpu) => gpu,
+                    Err(err) => {
+                        tracing::error!("Failed to set up the GPU: {err}");
+                        event_loop.exit();
+                        return;
+                    }
+                };
+                let mut app = InnerApp::new(window, gpu, &self.events);
+                app.set_fov(self.config.fov());
+                let gpu = &mut app.gpu;
+                self.scene_options
+                    .apply(&mut gpu.scene, &gpu.device, &gpu.queue);
+                // Redraws requested during the setup were skipped.
+                app.window.request_redraw();
+                self.app = Some(app);
+                self.started = Some(Instant::now());
+            }
+        }
     }
 
     fn window_event(
         &mut self,
         event_loop: &winit::event_loop::ActiveEventLoop,
-        _window_id: winit::window::WindowId, // we only have one window
+        window_id: winit::window::WindowId,
         event: winit::event::WindowEvent,
     ) {
+        // The map window only shows the scene, all input goes to the main window.
+        if let Some(app) = self.app.as_mut()
+            && app.is_map_window(window_id)
+        {
+            match event {
+                WindowEvent::CloseRequested => app.set_map_window(event_loop, false),
+                WindowEvent::Resized(size) => self.events.publish(WindowResized {
+                    window: window_id,
+                    size,
+                }),
+                _ => {}
+            }
+            return;
+        }
+
+        self.pacing.handle_window_event(&event);
+        if let Some(input) = InputEvent::from_window_event(&event) {
+            self.user_input(input);
+            return;
+        }
+
         match event {
             WindowEvent::CloseRequested => {
-                println!("The close button was pressed; stopping");
+                tracing::info!("The close button was pressed; stopping");
                 event_loop.exit();
             }
             WindowEvent::RedrawRequested => {
@@ -59,212 +229,984 @@ impl ApplicationHandler for App {
                 // this event rather than in AboutToWait, since rendering in here allows
                 // the program to gracefully handle redraws requested by the OS.
 
-                // Before redraw, apply all navigation changes.
-                let key_left_shift = self
-                    .key_state
-                    .get(&winit::keyboard::KeyCode::ShiftLeft)
-                    .cloned()
-                    .unwrap_or(false);
-                let key_right_shift = self
-                    .key_state
-                    .get(&winit::keyboard::KeyCode::ShiftRight)
-                    .cloned()
-                    .unwrap_or(false);
-                let key_w = self
-                    .key_state
-                    .get(&winit::keyboard::KeyCode::KeyW)
-                    .cloned()
-                    .unwrap_or(false);
-                let key_s = self
-                    .key_state
-                    .get(&winit::keyboard::KeyCode::KeyS)
-                    .cloned()
-                    .unwrap_or(false);
-                let key_d = self
-                    .key_state
-                    .get(&winit::keyboard::KeyCode::KeyD)
-                    .cloned()
-                    .unwrap_or(false);
-                let key_a = self
-                    .key_state
-                    .get(&winit::keyboard::KeyCode::KeyA)
-                    .cloned()
-                    .unwrap_or(false);
-                let key_e = self
-                    .key_state
-                    .get(&winit::keyboard::KeyCode::KeyE)
-                    .cloned()
-                    .unwrap_or(false);
-                let key_q = self
-                    .key_state
-                    .get(&winit::keyboard::KeyCode::KeyQ)
-                    .cloned()
-                    .unwrap_or(false);
-
                 // Draw.
                 if let Some(app) = self.app.as_mut() {
-                    let current_time = std::time::Instant::now();
-                    let delta_t = current_time.duration_since(app.prev_render_time);
-
-                    let elapsed_s = delta_t.as_secs_f32();
-                    let speed = if key_left_shift || key_right_shift {
-                        3.0 * self.speed * elapsed_s
-                    } else {
-                        1.0 * self.speed * elapsed_s
-                    };
-
-                    if key_w {
-                        app.camera.move_on_look_at_vector(speed);
-                    };
-                    if key_s {
-                        app.camera.move_on_look_at_vector(-speed);
-                    };
-                    if key_d {
-                        app.camera.move_on_right_vector(speed);
-                    };
-                    if key_a {
-                        app.camera.move_on_right_vector(-speed);
-                    };
-                    if key_e {
-                        app.camera.move_on_up_vector(speed);
-                    };
-                    if key_q {
-                        app.camera.move_on_up_vector(-speed);
+                    app.time.update();
+                    // The camera keeps moving while the game time is paused.
+                    let delta_t = app.time.real_delta();
+
+                    if self.input.is_activated(Action::ToggleWalk) {
+                        let walking = app.gpu.scene.walking();
+                        app.gpu.scene.set_walking(!walking);
+                    }
+
+                    // Before redraw, apply all navigation changes.
+                    if app.gpu.scene.walking() {
+                        // The player keeps falling while not navigating.
+                        let (forward, right) = if self.input.is_active(Action::Navigate) {
+                            let speed = if self.input.is_active(Action::Sprint) {
+                                2.0 * WALK_SPEED
+                            } else {
+                                WALK_SPEED
+                            };
+                            (
+                                speed * self.input.axis(Axis::Forward),
+                                speed * self.input.axis(Axis::Right),
+                            )
+                        } else {
+                            (0.0, 0.0)
+                        };
+                        let jump = self.input.is_active(Action::Jump);
+                        app.gpu.scene.walk(forward, right, jump, delta_t);
+                    } else if self.input.is_active(Action::Navigate) {
+                        let elapsed_s = delta_t.as_secs_f32();
+                        let speed = if self.input.is_active(Action::Sprint) {
+                            3.0 * self.speed * elapsed_s
+                        } else {
+                            1.0 * self.speed * elapsed_s
+                        };
+
+                        let camera = app.gpu.scene.camera_mut();
+                        camera.move_on_look_at_vector(speed * self.input.axis(Axis::Forward));
+                        camera.move_on_right_vector(speed * self.input.axis(Axis::Right));
+                        camera.move_on_up_vector(speed * self.input.axis(Axis::Up));
+                    }
+
+                    // Blocks are aimed at with the middle of the view, which
+                    // only stays still while looking around.
+                    if self.input.is_active(Action::Navigate)
+                        && let Some(hit) = app.gpu.scene.pick_block(BLOCK_REACH)
+                    {
+                        let scene = &mut app.gpu.scene;
+                        scene.highlight_block(hit.position);
+                        let change = if self.input.is_activated(Action::BreakBlock) {
+                            let [x, y, z] = hit.position;
+                            let previous = scene.world_mut().remove_block(x, y, z);
+                            previous.map(|previous| (hit.position, previous, Block::Air))
+                        } else if self.input.is_activated(Action::PlaceBlock) {
+                            let [x, y, z] = hit.adjacent();
+                            let previous = scene.world_mut().set_block(x, y, z, Block::Stone);
+                            previous.map(|previous| (hit.adjacent(), previous, Block::Stone))
+                        } else {
+                            None
+                        };
+                        if let Some((position, previous, block)) = change
+                            && previous != block
+                        {
+                            self.events.publish(BlockChanged {
+                                position,
+                                previous,
+                                block,
+                            });
+                        }
+                    }
+
+                    if self.input.is_activated(Action::ThrowBody) {
+                        // Balls and crates take turns.
+                        let shape = if !self.throw_cuboid {
+                            Shape::Sphere { radius: 0.25 }
+                        } else {
+                            Shape::Cuboid {
+                                half_extents: v![0.25, 0.25, 0.25],
+                            }
+                        };
+                        app.gpu.scene.throw_body(shape, 1.0, THROW_SPEED);
+                        self.throw_cuboid = !self.throw_cuboid;
+                    }
+
+                    let view = app.main_view();
+                    let size = app.window.inner_size();
+                    app.gpu.scene.highlight_selection();
+                    if let (Some(start), Some(cursor)) = (self.drag_start, self.cursor) {
+                        let rect = ScreenRect::new(start, cursor);
+                        if !rect.is_click() {
+                            app.gpu.scene.highlight_rect(&view, size, &rect);
+                        }
                     }
 
-                    app.gpu.render(&app.camera, delta_t);
-                    // for continuos rendering
-                    app.window.request_redraw();
+                    if self.input.is_activated(Action::ToggleOverlay) {
+                        app.overlay.toggle_visible();
+                    }
+                    if self.input.is_activated(Action::ToggleWireframe) {
+                        app.gpu.scene.toggle_wireframe();
+                    }
+                    if self.input.is_activated(Action::Screenshot) {
+                        app.gpu
+                            .frame_capture()
+                            .request_screenshot(SCREENSHOT_DIRECTORY);
+                    }
+                    if self.input.is_activated(Action::ToggleFrameDump) {
+                        toggle_frame_dump(app.gpu.frame_capture());
+                    }
+                    self.input.end_frame();
+
+                    // Debug overlay
+                    let stats = app.gpu.frame_stats();
+                    app.window.show_fps(stats.frametime.average());
+                    let camera = app.gpu.scene.camera();
+                    let eye = camera.eye();
+                    app.overlay
+                        .label(&format!("FPS: {:.1}", stats.frametime.average()));
+                    app.overlay.label(&format!(
+                        "1% low: {:.1}",
+                        stats.frametime.one_percent_lows_average()
+                    ));
+                    app.overlay.label(&format!(
+                        "CPU: {:.2} ms",
+                        stats.cpu_time.as_secs_f64() * 1000.0
+                    ));
+                    for (pass, time) in stats.gpu_pass_times.iter().flatten() {
+                        app.overlay.label(&format!(
+                            "GPU {pass}: {:.2} ms",
+                            time.as_secs_f64() * 1000.0
+                        ));
+                    }
+                    app.overlay.label(&format!(
+                        "Draw calls: {} Triangles: {}",
+                        stats.draw_calls, stats.triangles
+                    ));
+                    app.overlay.label(&format!(
+                        "Position: {:.2} {:.2} {:.2}",
+                        eye[0], eye[1], eye[2]
+                    ));
+                    app.overlay.label(&format!(
+                        "Pitch: {:.1} Yaw: {:.1} Roll: {:.1}",
+                        camera.pitch_angle().to_degrees(),
+                        camera.yaw_angle().to_degrees(),
+                        camera.roll_angle().to_degrees()
+                    ));
+                    app.overlay.label(&format!("Speed: {:.1} m/s", self.speed));
+                    app.overlay.label(&format!(
+                        "Selected units: {}",
+                        app.gpu.scene.selection().len()
+                    ));
+                    app.overlay
+                        .label(&format!("MSAA: {}x", app.gpu.scene.sample_count()));
+                    if app.gpu.frame_capture().is_dumping() {
+                        app.overlay.label("Dumping frames (F11)");
+                    }
 
-                    // Dirty update time
-                    app.prev_render_time = current_time;
+                    // Bottom left corner, independent of the panel.
+                    let stats_text = format!(
+                        "Frame: {:.2} ms\nVoxels: {}\nEntities: {}",
+                        delta_t.as_secs_f64() * 1000.0,
+                        app.gpu.scene.voxel_count(),
+                        app.gpu.scene.entities().len()
+                    );
+                    let text = app.overlay.text();
+                    let bottom = app.window.inner_size().height as f32 - text.height(3);
+                    text.print(8.0, bottom - 8.0, &stats_text);
+                    let mut wireframe = app.gpu.scene.wireframe();
+                    if app.overlay.checkbox("Wireframe (F3)", &mut wireframe) {
+                        app.gpu.scene.toggle_wireframe();
+                    }
+                    let mut paused = app.time.paused();
+                    if app.overlay.checkbox("Paused", &mut paused) {
+                        app.time.set_paused(paused);
+                    }
+                    let mut minimap = app.minimap();
+                    if app.overlay.checkbox("Minimap", &mut minimap) {
+                        app.set_minimap(minimap);
+                    }
+                    let mut split_screen = app.split_screen();
+                    if app.overlay.checkbox("Split screen", &mut split_screen) {
+                        app.set_split_screen(split_screen);
+                    }
+                    let mut map = app.map_window_open();
+                    if app.overlay.checkbox("Map window", &mut map) {
+                        app.set_map_window(event_loop, map);
+                    }
+                    let post_process = app.gpu.scene.post_process_mut();
+                    for (text, label) in [("FXAA", FXAA), ("Vignette", VIGNETTE)] {
+                        let mut enabled = post_process.enabled(label).unwrap_or_default();
+                        if app.overlay.checkbox(text, &mut enabled) {
+                            post_process.set_enabled(label, enabled);
+                        }
+                    }
+                    // Radio buttons like the present modes below.
+                    let tonemapping = post_process.tonemapping();
+                    for tonemapper in Tonemapper::ALL {
+                        let mut selected = tonemapper == tonemapping.tonemapper;
+                        if app
+                            .overlay
+                            .checkbox(&format!("Tonemapping: {tonemapper:?}"), &mut selected)
+                        {
+                            post_process.set_tonemapping(Tonemapping {
+                                tonemapper,
+                                ..tonemapping
+                            });
+                        }
+                    }
+                    let mut fullscreen = app.window.is_fullscreen();
+                    if app.overlay.checkbox("Fullscreen", &mut fullscreen) {
+                        app.window.set_fullscreen(fullscreen);
+                        self.config.set_fullscreen(fullscreen);
+                    }
+                    let mut vsync = app.gpu.vsync();
+                    if app.overlay.checkbox("VSync", &mut vsync) {
+                        app.gpu.set_vsync(vsync);
+                        self.config.set_vsync(app.gpu.vsync());
+                    }
+                    // Only one mode can be selected, so the checkboxes act as radio buttons.
+                    let present_mode = app.gpu.present_mode();
+                    for mode in app.gpu.supported_present_modes().to_vec() {
+                        let mut selected = mode == present_mode;
+                        if app.overlay.checkbox(&format!("{mode:?}"), &mut selected) {
+                            app.gpu.set_present_mode(mode);
+                            self.config.set_vsync(app.gpu.vsync());
+                        }
+                    }
+
+                    app.handle_events(&self.events);
+                    for resized in self.events.read(&mut self.resized) {
+                        // The size to open the window with next time.
+                        if resized.window == app.window.id() && !app.window.is_fullscreen() {
+                            self.config.set_window_size(app.window.logical_size());
+                        }
+                    }
+                    app.update_map_camera();
+                    match app.gpu.render(&mut app.overlay, &app.time) {
+                        Ok(()) => {}
+                        Err(RenderError::OutOfMemory) => {
+                            tracing::error!("Rendering ran out of memory; stopping");
+                            event_loop.exit();
+                        }
+                        Err(error) => tracing::warn!("Skipped frame: {error}"),
+                    }
+                    self.events.end_frame();
                 }
                 // else nothing to do yet
             }
-            WindowEvent::Focused(focused) => {
+            WindowEvent::Resized(size) => self.events.publish(WindowResized {
+                window: window_id,
+                size,
+            }),
+            _ => (),
+        }
+    }
+
+    fn about_to_wait(&mut self, event_loop: &winit::event_loop::ActiveEventLoop) {
+        self.play_back(event_loop);
+        let Some(app) = self.app.as_ref() else {
+            return;
+        };
+        let wakeup = self.pacing.wakeup(Instant::now());
+        if wakeup.redraw {
+            app.window.request_redraw();
+        }
+        event_loop.set_control_flow(wakeup.control_flow);
+    }
+
+    fn exiting(&mut self, _event_loop: &winit::event_loop::ActiveEventLoop) {
+        match self.config.save() {
+            Ok(()) => {}
+            // There is no file system in the browser.
+            Err(ConfigError::Io(err)) if err.kind() == std::io::ErrorKind::Unsupported => {}
+            Err(err) => tracing::error!("Failed to save settings {CONFIG_PATH}: {err}"),
+        }
+        if let Some((path, recording)) = &self.recording {
+            match recording.save(path) {
+                Ok(()) => {
+                    tracing::info!("Saved {} input events to {path}", recording.events().len())
+                }
+                Err(err) => tracing::error!("Failed to save the input recording {path}: {err}"),
+            }
+        }
+    }
+
+    fn device_event(
+        &mut self,
+        _event_loop: &winit::event_loop::ActiveEventLoop,
+        _device_id: winit::event::DeviceId,
+        event: DeviceEvent,
+    ) {
+        if let Some(input) = InputEvent::from_device_event(&event) {
+            self.user_input(input);
+        }
+    }
+}
+
+impl App {
+    /// Record and handle input of the user, which is ignored while a
+    /// recording is played back.
+    fn user_input(&mut self, event: InputEvent) {
+        if self.playback.is_some() {
+            return;
+        }
+        if let Some((_, recording)) = &mut self.recording {
+            let time = self
+                .started
+                .map_or(Duration::ZERO, |started| started.elapsed());
+            recording.push(time, event);
+        }
+        self.handle_input(event);
+    }
+
+    /// React to live or played back input.
+    fn handle_input(&mut self, event: InputEvent) {
+        // Keyboard and mouse buttons are handled through the action map.
+        if self.focused {
+            self.input.handle_event(&event);
+        }
+
+        match event {
+            InputEvent::Focused(focused) => {
                 if !focused {
                     // If focus is lost from the application
                     // we simply clear all keys. Resetting the state.
                     // Otherwise the user could click away while
                     // navigating, then release all key, and keep moving in the
                     // last read direction.
-                    self.key_state.clear();
+                    self.input.clear();
                 }
                 self.focused = focused
             }
-            WindowEvent::CursorEntered { device_id: _ } => {}
-            WindowEvent::CursorLeft { device_id: _ } => {}
-            WindowEvent::Resized(inner_resolution) => {
-                // Recreate the surface texture according to the new inner physical resolution.
+            InputEvent::CursorLeft => {
+                self.cursor = None;
+                if let Some(app) = self.app.as_mut() {
+                    app.overlay.cursor_left();
+                }
+            }
+            InputEvent::CursorMoved { position: [x, y] } => {
+                self.cursor = Some([x as f32, y as f32]);
+                if let Some(app) = self.app.as_mut() {
+                    app.overlay.cursor_moved([x as f32, y as f32]);
+                }
+            }
+            InputEvent::MouseButton {
+                state: ElementState::Pressed,
+                button: MouseButton::Left,
+            } if self.focused && !self.input.is_active(Action::Navigate) => {
                 if let Some(app) = self.app.as_mut() {
-                    let config = app
-                        .gpu
-                        .surface
-                        .get_default_config(
-                            &app.gpu.adapter,
-                            inner_resolution.height,
-                            inner_resolution.width,
-                        )
-                        .unwrap();
-                    app.gpu.surface.configure(&app.gpu.device, &config);
+                    app.overlay.click();
+                    // Clicks on the overlay are only meant for it.
+                    if !app.overlay.is_hovered() {
+                        self.drag_start = self.cursor;
+                    }
                 }
             }
-            WindowEvent::KeyboardInput {
-                device_id: _,
-                event,
-                is_synthetic: _,
+            InputEvent::MouseButton {
+                state: ElementState::Released,
+                button: MouseButton::Left,
             } => {
-                // camera navigation controls for the engine
-                if self.focused
-                    && self.navigating
-                    && let PhysicalKey::Code(key_code) = event.physical_key
+                if let (Some(start), Some(end), Some(app)) =
+                    (self.drag_start.take(), self.cursor, self.app.as_mut())
                 {
-                    let is_pressed = event.state == ElementState::Pressed;
-                    self.key_state
-                        .entry(key_code)
-                        .and_modify(|entry| *entry = is_pressed)
-                        .or_insert(is_pressed);
+                    let view = app.main_view();
+                    let size = app.window.inner_size();
+                    if view.contains(size, start) {
+                        let rect = ScreenRect::new(start, end);
+                        app.gpu.scene.select_units(&view, size, &rect);
+                        let count = app.gpu.scene.selection().len();
+                        self.events.publish(UnitsSelected { count });
+                    }
                 }
             }
-            WindowEvent::MouseInput {
-                device_id: _,
-                state,
-                button,
-            } => {
-                if self.focused && matches!(button, MouseButton::Right) {
-                    match state {
-                        ElementState::Pressed => self.navigating = true,
-                        ElementState::Released => {
-                            self.navigating = false;
-                            // If 'navigation' is stopped
-                            // we simply clear all keys. Resetting the state.
-                            // Otherwise the user could release the 'navigation' key while
-                            // navigating, then release all key, and keep moving in the
-                            // last read direction.
-                            self.key_state.clear();
-                        }
+            InputEvent::MouseButton {
+                state: ElementState::Pressed,
+                button: MouseButton::Right,
+            } => self.look_distance = 0.0,
+            InputEvent::MouseButton {
+                state: ElementState::Released,
+                button: MouseButton::Right,
+            } if self.look_distance < CLICK_LOOK_DISTANCE => {
+                if let (Some(cursor), Some(app)) = (self.cursor, self.app.as_mut()) {
+                    let view = app.main_view();
+                    let size = app.window.inner_size();
+                    if view.contains(size, cursor) {
+                        let ray = view.picking_ray(app.gpu.scene.camera(), size, cursor);
+                        app.gpu.scene.order_move(&ray);
                     }
                 }
             }
-            WindowEvent::MouseWheel {
-                device_id: _,
-                delta,
-                phase: _, // touchpad ignored
-            } => {
-                if self.focused && self.navigating {
-                    match delta {
-                        MouseScrollDelta::LineDelta(_dx, dy) => {
-                            // To change the speed we use a logarithm function as
-                            // those types of inputs fell much more natural.
-                            // Shift it by 1 to the left so it reaches zero at zero,
-                            // then flatten the result by half.
-                            // This way within the range os 0.1 - 30 the user
-                            // gets finer control on the lower ends and coarser on the
-                            // higher ends.
-                            self.speed += dy * ((self.speed + 1.0).log2() / 2.0);
-                            self.speed = self.speed.clamp(0.1, 30.0);
-                        }
-                        MouseScrollDelta::PixelDelta(_) => {}
+            InputEvent::MouseWheel { delta }
+                if self.focused && self.input.is_active(Action::Navigate) =>
+            {
+                match delta {
+                    MouseScrollDelta::LineDelta(_dx, dy) => {
+                        // To change the speed we use a logarithm function as
+                        // those types of inputs fell much more natural.
+                        // Shift it by 1 to the left so it reaches zero at zero,
+                        // then flatten the result by half.
+                        // This way within the range os 0.1 - 30 the user
+                        // gets finer control on the lower ends and coarser on the
+                        // higher ends.
+                        self.speed += dy * ((self.speed + 1.0).log2() / 2.0);
+                        self.speed = self.speed.clamp(0.1, 30.0);
                     }
+                    MouseScrollDelta::PixelDelta(_) => {}
                 }
             }
-            _ => (),
-        }
-    }
-
-    fn device_event(
-        &mut self,
-        _event_loop: &winit::event_loop::ActiveEventLoop,
-        _device_id: winit::event::DeviceId,
-        event: DeviceEvent,
-    ) {
-        #[allow(clippy::single_match)]
-        match event {
-            DeviceEvent::MouseMotion { delta } => {
+            InputEvent::MouseMotion { delta } => {
                 if self.focused
-                    && self.navigating
+                    && self.input.is_active(Action::Navigate)
                     && let Some(app) = self.app.as_mut()
                 {
-                    // Negate all inputs, inverting the movements
-                    app.camera.pitch(-delta.1 as f32 / 50.0);
-                    app.camera.yaw(-delta.0 as f32 / 50.0);
+                    self.look_distance += (delta.0.abs() + delta.1.abs()) as f32;
+                    let (yaw, pitch) = self.input.mouse_look().turn(delta);
+                    let camera = app.gpu.scene.camera_mut();
+                    camera.pitch(pitch);
+                    camera.yaw(yaw);
                 }
             }
-            _ => (), // the rest we don't care
+            _ => (),
+        }
+
+        // Mouse-look needs the cursor, navigation ends on focus loss as well.
+        let navigating = self.focused && self.input.is_active(Action::Navigate);
+        if let Some(app) = self.app.as_mut() {
+            app.grab_cursor(navigating);
+        }
+    }
+
+    /// Hand the played back input to the app once its time has come,
+    /// stopping after the last event.
+    fn play_back(&mut self, event_loop: &winit::event_loop::ActiveEventLoop) {
+        let (Some(playback), Some(started)) = (self.playback.as_mut(), self.started) else {
+            return;
+        };
+        let due = playback.due(started.elapsed()).collect::<Vec<_>>();
+        let finished = playback.is_finished();
+        for event in due {
+            self.handle_input(event);
+        }
+        if finished {
+            tracing::info!("The input recording was played back; stopping");
+            event_loop.exit();
+        }
+    }
+}
+
+/// How far away blocks can be broken or placed.
+const BLOCK_REACH: f32 = 8.0;
+
+/// How fast the player walks in m/s, sprinting doubles it.
+const WALK_SPEED: f32 = 4.0;
+
+/// The speed bodies are thrown with in m/s.
+const THROW_SPEED: f32 = 8.0;
+
+/// How far the mouse may move while the right button is held, in device
+/// units, for releasing it to still count as a click.
+const CLICK_LOOK_DISTANCE: f32 = 4.0;
+
+/// Where screenshots are saved, relative to the working directory.
+const SCREENSHOT_DIRECTORY: &str = "screenshots";
+
+/// Where frame dumps are saved, each into a directory of its own.
+const FRAME_DUMP_DIRECTORY: &str = "frames";
+
+/// Start saving every frame, or stop if it already is.
+fn toggle_frame_dump(capture: &mut FrameCapture) {
+    match capture.stop_dump() {
+        Some(frames) => tracing::info!("Stopped dumping frames after {frames} frames"),
+        None => {
+            let directory = capture.start_dump(FRAME_DUMP_DIRECTORY);
+            tracing::info!("Dumping frames into {}", directory.display());
+        }
+    }
+}
+
+/// The settings file in the working directory, created once a setting is changed.
+const CONFIG_PATH: &str = "settings.toml";
+
+/// The settings in the file at `path`, the defaults if it is invalid.
+///
+/// The defaults aren't saved, so an invalid file is kept for the user to fix.
+fn load_config(path: &str) -> Config {
+    match Config::load(path) {
+        Ok(config) => config,
+        Err(err) => {
+            tracing::error!("Failed to load settings {path}: {err}");
+            Config::default()
+        }
+    }
+}
+
+/// The bindings file read before the settings had a `[bindings]` table.
+const BINDINGS_PATH: &str = "bindings.toml";
+
+/// Move the bindings of the old bindings file at `path` into the settings,
+/// unless they have bindings already, and save them.
+///
+/// The file is renamed afterwards, so later changes of the settings aren't
+/// overwritten by it again.
+fn migrate_bindings(config: &mut Config, path: &str) {
+    if config.has_bindings() {
+        return;
+    }
+    let source = match std::fs::read_to_string(path) {
+        Ok(source) => source,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return,
+        Err(err) => {
+            tracing::error!("Failed to read bindings {path}: {err}");
+            return;
+        }
+    };
+    match BindingsFile::parse(&source) {
+        Ok(file) => config.import_bindings(file),
+        Err(err) => {
+            tracing::error!("Failed to load bindings {path}: {err}");
+            return;
+        }
+    }
+    if let Err(err) = config.save() {
+        tracing::error!("Failed to save settings {CONFIG_PATH}: {err}");
+        return;
+    }
+    let old = format!("{path}.old");
+    match std::fs::rename(path, &old) {
+        Ok(()) => {
+            tracing::info!("Moved the bindings of {path} into {CONFIG_PATH}, kept it as {old}")
+        }
+        Err(err) => tracing::error!("Failed to rename bindings {path}: {err}"),
+    }
+}
+
+/// How the built-in scene is changed on startup.
+#[derive(Debug, Clone, Default)]
+struct SceneOptions {
+    // Scene file loaded on startup.
+    scene_path: Option<String>,
+    // Replaces the skybox if set.
+    background: Option<Color>,
+    // Grayscale image of the terrain, generated if not set.
+    heightmap: Option<String>,
+}
+
+impl SceneOptions {
+    fn apply(&self, scene: &mut Scene, device: &wgpu::Device, queue: &wgpu::Queue) {
+        // Loaded at the same time on the workers, uploaded to the GPU here.
+        let path = self.heightmap.clone();
+        let heightmap = scene.jobs().spawn(move || load_heightmap(path.as_deref()));
+        let sway = scene
+            .jobs()
+            .spawn(|| import_gltf(include_bytes!("../assets/sway.gltf")));
+
+        add_terrain(scene, device, queue, &heightmap.wait());
+        add_units(scene);
+        add_sway(scene, device, queue, sway.wait());
+        add_beacon(scene);
+        add_dust(scene);
+        add_vignette(scene, device);
+        if let Some(path) = &self.scene_path {
+            load_scene(scene, path);
+        }
+        if let Some(color) = self.background {
+            set_background(scene, device, color);
+        }
+    }
+}
+
+/// The seed of the generated terrain.
+const TERRAIN_SEED: u64 = 1;
+
+/// The height of white pixels in heightmap images.
+const HEIGHTMAP_MAX_HEIGHT: f32 = 24.0;
+
+/// The heightmap image at `path`, or a generated one if there is no path or
+/// the image can't be loaded.
+fn load_heightmap(path: Option<&str>) -> Heightmap {
+    let loaded = path.and_then(|path| {
+        let heightmap = std::fs::read(path)
+            .map_err(|err| err.to_string())
+            .and_then(|bytes| {
+                Heightmap::from_image(&bytes, HEIGHTMAP_MAX_HEIGHT).map_err(|err| err.to_string())
+            });
+        match heightmap {
+            Ok(heightmap) => {
+                tracing::info!("Loaded heightmap {path}");
+                Some(heightmap)
+            }
+            Err(err) => {
+                tracing::error!("Failed to load heightmap {path}: {err}");
+                None
+            }
+        }
+    });
+    loaded.unwrap_or_else(|| generate_heightmap(TERRAIN_SEED, 257, 257, 8.0))
+}
+
+/// Add the terrain of `heightmap`.
+fn add_terrain(
+    scene: &mut Scene,
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    heightmap: &Heightmap,
+) {
+    let Some(material) = scene.material("block") else {
+        return;
+    };
+    // Behind the voxel chunks, as seen from the starting camera.
+    let settings = TerrainSettings {
+        origin: v![-128.0, -8.0, -400.0],
+        ..Default::default()
+    };
+    scene.add_terrain(device, queue, "terrain", heightmap, &settings, material);
+}
+
+/// A squad of units on a corner of the plane, in front of the cube.
+fn add_units(scene: &mut Scene) {
+    for position in formation(v![2.0, -1.0, 2.0], 4, 0.8) {
+        scene.add_unit(position);
+    }
+}
+
+/// A swaying column on the other corner of the plane, imported from glTF.
+fn add_sway(
+    scene: &mut Scene,
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    imported: Result<SkinnedModel, ImportError>,
+) {
+    let model = match imported {
+        Ok(model) => model,
+        Err(err) => {
+            tracing::error!("Failed to import the sway model: {err}");
+            return;
         }
+    };
+    let transform = Transform::from_translation(v![-2.0, -1.0, 2.0]);
+    scene.add_skinned_model(device, queue, "sway", model, transform);
+}
+
+/// A small box spinning and bobbing up and down above the cube.
+fn add_beacon(scene: &mut Scene) {
+    let bob = Curve::new(vec![0.0, 1.5], vec![v![0.0, 1.6, 0.0], v![0.0, 2.0, 0.0]])
+        .with_interpolation(Interpolation::Cubic)
+        .with_loop_mode(LoopMode::PingPong);
+    let turn = |turns: f32| Quaternion::<f32>::new_unit(Rad(turns * TAU), v![0.0, 1.0, 0.0]);
+    // A third of a turn at a time, slerp would take the short way around.
+    let spin = Curve::new(
+        vec![0.0, 2.0, 4.0, 6.0],
+        vec![turn(0.0), turn(1.0 / 3.0), turn(2.0 / 3.0), turn(1.0)],
+    )
+    .with_loop_mode(LoopMode::Repeat);
+    let animator = TransformAnimator::new()
+        .with_translation(bob)
+        .with_rotation(spin);
+    let transform = Transform::default().with_scale(v![0.2, 0.2, 0.2]);
+    scene.add_animated_box(transform, animator);
+}
+
+/// Dust drifting up from the middle of the plane, between the cube and the
+/// units.
+fn add_dust(scene: &mut Scene) {
+    let dust = Emitter::new(EmitterSettings::dust(), 1);
+    scene.add_emitter(v![1.0, -1.0, 1.8], dust);
+}
+
+/// The label of the vignette pass, disabled until toggled in the overlay.
+const VIGNETTE: &str = "vignette";
+
+/// A lens vignette darkening the corners of the frame.
+fn add_vignette(scene: &mut Scene, device: &wgpu::Device) {
+    // Strength and radius, padded to 16 bytes.
+    let uniforms = [0.4_f32, 0.5, 0.0, 0.0];
+    let post_process = scene.post_process_mut();
+    post_process.add_pass(
+        device,
+        &PostPassDescriptor {
+            label: VIGNETTE,
+            stage: Stage::Hdr,
+            source: include_str!("vignette.wgsl"),
+            uniforms: Some(bytemuck::bytes_of(&uniforms)),
+        },
+    );
+    post_process.set_enabled(VIGNETTE, false);
+}
+
+/// Replace the scene's contents with the scene file at `path`,
+/// keeping the built-in scene if it can't be loaded.
+fn load_scene(scene: &mut Scene, path: &str) {
+    match scene.load(path) {
+        Ok(()) => tracing::info!("Loaded scene {path}"),
+        Err(err) => tracing::error!("Failed to load scene {path}: {err}"),
+    }
+}
+
+/// Clear to `color` instead of drawing the skybox.
+fn set_background(scene: &mut Scene, device: &wgpu::Device, color: Color) {
+    scene.set_skybox(device, None);
+    scene.set_clear_color(color);
+}
+
+/// A `RRGGBB` sRGB hex color, with or without a leading `#`.
+fn parse_color(hex: &str) -> Option<Color> {
+    let hex = hex.strip_prefix('#').unwrap_or(hex);
+    if hex.len() != 6 {
+        return None;
+    }
+    u32::from_str_radix(hex, 16).ok().map(Color::from_srgb_hex)
+}
+
+/// Create the scene without a window, changed by the `options`.
+///
+/// `None` if the GPU couldn't be set up, the reason is logged.
+fn headless(settings: &RenderSettings, options: &SceneOptions) -> Option<Offscreen> {
+    let size = winit::dpi::PhysicalSize::new(1024, 768);
+    let mut offscreen = match pollster::block_on(Offscreen::new(size, settings)) {
+        Ok(offscreen) => offscreen,
+        Err(err) => {
+            tracing::error!("Failed to set up the GPU: {err}");
+            return None;
+        }
+    };
+    options.apply(&mut offscreen.scene, &offscreen.device, &offscreen.queue);
+    Some(offscreen)
+}
+
+/// Render a single frame without a window and save it to `path`.
+fn capture(path: &str, settings: &RenderSettings, options: &SceneOptions) {
+    let Some(mut offscreen) = headless(settings, options) else {
+        return;
+    };
+
+    let mut overlay = Overlay::new(1.0);
+    offscreen.scene.simulate(&Time::new());
+    offscreen.render(&mut overlay);
+
+    match offscreen.read_back_to_image(path) {
+        Ok(()) => tracing::info!("Captured frame to {path}"),
+        Err(err) => tracing::error!("Failed to capture frame to {path}: {err}"),
+    }
+}
+
+/// Save the scene as a scene file to `path`, a starting point for editing.
+fn save_scene(path: &str, settings: &RenderSettings, options: &SceneOptions) {
+    let Some(offscreen) = headless(settings, options) else {
+        return;
+    };
+    match offscreen.scene.save(path) {
+        Ok(()) => tracing::info!("Saved scene to {path}"),
+        Err(err) => tracing::error!("Failed to save scene to {path}: {err}"),
+    }
+}
+
+/// Filter used if `RUST_LOG` isn't set, wgpu is very chatty at `info`.
+const DEFAULT_LOG_FILTER: &str = "info,wgpu_core=warn,wgpu_hal=warn,naga=warn";
+
+/// Print the engine's log to stderr, filtered by the `RUST_LOG` environment variable,
+/// e.g. `RUST_LOG=voxon=debug` for the statistics every second or
+/// `RUST_LOG=trace` to include every system run.
+///
+/// With a `profile_path` the engine's scopes are profiled regardless of the
+/// filter, and saved there when the returned guard is dropped.
+fn init_logging(profile_path: Option<String>) -> Option<profile::ProfileGuard> {
+    let filter =
+        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(DEFAULT_LOG_FILTER));
+    let (profile_layer, guard) = profile_path.map(profile::chrome_trace).unzip();
+    let fmt_layer = tracing_subscriber::fmt::layer();
+    // The browser has neither stderr nor the system time.
+    #[cfg(target_arch = "wasm32")]
+    let fmt_layer = fmt_layer
+        .with_writer(web::Console)
+        .with_ansi(false)
+        .without_time();
+    #[cfg(not(target_arch = "wasm32"))]
+    let fmt_layer = fmt_layer.with_writer(std::io::stderr);
+    tracing_subscriber::registry()
+        .with(fmt_layer.with_filter(filter))
+        .with(profile_layer.with_filter(profile::profiled_targets()))
+        .init();
+    guard
+}
+
+/// Adapter settings from the command line values, ignoring invalid ones.
+fn adapter_settings(
+    adapter: Option<String>,
+    backend: Option<String>,
+    power_preference: Option<String>,
+) -> AdapterSettings {
+    let mut settings = AdapterSettings::default();
+    if let Some(adapter) = adapter {
+        settings.adapter = match adapter.parse() {
+            Ok(index) => AdapterChoice::Index(index),
+            Err(_) => AdapterChoice::Name(adapter),
+        };
+    }
+    settings.backend = backend.and_then(|backend| match backend.to_lowercase().as_str() {
+        "vulkan" => Some(Backend::Vulkan),
+        "dx12" => Some(Backend::Dx12),
+        "metal" => Some(Backend::Metal),
+        "gl" => Some(Backend::Gl),
+        _ => {
+            tracing::warn!("Unknown backend {backend}, using any");
+            None
+        }
+    });
+    if let Some(power_preference) = power_preference {
+        settings.power_preference = match power_preference.as_str() {
+            "low" => PowerPreference::LowPower,
+            "high" => PowerPreference::HighPerformance,
+            _ => {
+                tracing::warn!("Unknown power preference {power_preference}, expected low or high");
+                PowerPreference::None
+            }
+        };
+    }
+    settings
+}
+
+/// Print the adapters which can be chosen with `--adapter`.
+fn list_adapters(settings: &AdapterSettings) {
+    for (index, info) in pollster::block_on(adapter::available_adapters(settings))
+        .iter()
+        .enumerate()
+    {
+        println!(
+            "{index}: {} ({:?}, {})",
+            info.name, info.device_type, info.backend
+        );
     }
 }
 
 fn main() {
-    let event_loop = EventLoop::new().unwrap();
+    // Panics end up in the browser's console instead of an opaque error.
+    #[cfg(target_arch = "wasm32")]
+    console_error_panic_hook::set_once();
+
+    // `--scene <path>` loads a scene file instead of the built-in scene.
+    // `--capture <path>` renders headless, e.g. for screenshots or image comparisons.
+    // `--save-scene <path>` writes the scene file of the scene without opening a window.
+    // `--adapter <name or index>` renders with the given adapter,
+    // `--list-adapters` shows the available ones.
+    // `--backend <vulkan|dx12|metal|gl>` only considers adapters of the backend.
+    // `--power <low|high>` prefers an integrated or a discrete GPU.
+    // `--background <RRGGBB>` replaces the skybox with an sRGB hex color.
+    // `--heightmap <path>` loads the terrain from a grayscale image instead of generating it.
+    // `--profile <path>` saves the timings of the systems and passes as a Chrome trace on exit.
+    // `--record <path>` saves all input on exit, `--replay <path>` plays such a
+    // recording back instead of taking input and stops once it is done.
+    let mut scene_path = None;
+    let mut capture_path = None;
+    let mut save_path = None;
+    let mut adapter = None;
+    let mut backend = None;
+    let mut power_preference = None;
+    let mut background = None;
+    let mut heightmap = None;
+    let mut profile_path = None;
+    let mut record_path = None;
+    let mut replay_path = None;
+    let mut list = false;
+    let mut unknown = Vec::new();
+    // The last argument, if it needed a value.
+    let mut missing_value = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let target = match arg.as_str() {
+            "--scene" => &mut scene_path,
+            "--capture" => &mut capture_path,
+            "--save-scene" => &mut save_path,
+            "--adapter" => &mut adapter,
+            "--backend" => &mut backend,
+            "--power" => &mut power_preference,
+            "--background" => &mut background,
+            "--heightmap" => &mut heightmap,
+            "--profile" => &mut profile_path,
+            "--record" => &mut record_path,
+            "--replay" => &mut replay_path,
+            "--list-adapters" => {
+                list = true;
+                continue;
+            }
+            _ => {
+                unknown.push(arg);
+                continue;
+            }
+        };
+        match args.next() {
+            Some(value) => *target = Some(value),
+            None => missing_value = Some(arg),
+        }
+    }
+    // Saved when returning from main.
+    let _profile = init_logging(profile_path);
+    for arg in unknown {
+        tracing::warn!("Unknown argument {arg}");
+    }
+    if let Some(arg) = missing_value {
+        tracing::error!("{arg} needs a value");
+        std::process::exit(2);
+    }
+
+    let settings = RenderSettings {
+        adapter: adapter_settings(adapter, backend, power_preference),
+        ..Default::default()
+    };
+    let background = background.and_then(|hex| {
+        let color = parse_color(&hex);
+        if color.is_none() {
+            tracing::warn!("Invalid background color {hex}, expected RRGGBB");
+        }
+        color
+    });
+    let scene_options = SceneOptions {
+        scene_path,
+        background,
+        heightmap,
+    };
+    if list {
+        list_adapters(&settings.adapter);
+        return;
+    }
+    if let Some(path) = save_path {
+        save_scene(&path, &settings, &scene_options);
+        return;
+    }
+    if let Some(path) = capture_path {
+        capture(&path, &settings, &scene_options);
+        return;
+    }
+
+    let mut config = load_config(CONFIG_PATH);
+    migrate_bindings(&mut config, BINDINGS_PATH);
+    let mut input = Input::new();
+    config.apply_to_input(&mut input);
+    let settings = RenderSettings {
+        present_mode: if config.vsync() {
+            PresentMode::AutoVsync
+        } else {
+            PresentMode::AutoNoVsync
+        },
+        ..settings
+    };
+
+    let playback = match replay_path.map(|path| (Recording::load(&path), path)) {
+        Some((Ok(recording), _)) => Some(Playback::new(recording)),
+        Some((Err(err), path)) => {
+            tracing::error!("Failed to load the input recording {path}: {err}");
+            return;
+        }
+        None => None,
+    };
+    if record_path.is_some() && playback.is_some() {
+        tracing::warn!("Played back input isn't recorded again");
+    }
+
+    let event_loop = EventLoop::with_user_event().build().unwrap();
     // ControlFlow::Poll continuously runs the event loop, even if the OS hasn't
     // dispatched any events. This is ideal for games and similar applications.
-    // event_loop.set_control_flow(ControlFlow::Poll);
-    // ControlFlow::Wait pauses the event loop if no events are available to process.
-    // This is ideal for non-game applications that only update in response to user
-    // input, and uses significantly less power/CPU time than ControlFlow::Poll.
+    // Once the main window is open, the pacing switches to waiting while it is
+    // in the background, which uses significantly less power/CPU time.
     event_loop.set_control_flow(ControlFlow::Poll);
 
-    let mut app = App::default();
+    #[cfg_attr(target_arch = "wasm32", allow(unused_mut))]
+    let mut app = App {
+        scene_options,
+        settings,
+        input,
+        pacing: Pacing::new(config.pacing()),
+        config,
+        proxy: Some(event_loop.create_proxy()),
+        recording: record_path.map(|path| (path, Recording::new())),
+        playback,
+        ..Default::default()
+    };
+    #[cfg(not(target_arch = "wasm32"))]
     let _ = event_loop.run_app(&mut app);
+    // Returns right away, the browser runs the event loop.
+    #[cfg(target_arch = "wasm32")]
+    winit::platform::web::EventLoopExtWebSys::spawn_app(event_loop, app);
 }
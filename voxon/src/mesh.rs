@@ -1,27 +1,70 @@
+use bytemuck::{Pod, Zeroable};
+use graphic::coordinates::Spherical;
 use lina::{v, vector::Vector};
 
-pub struct Vertex {
-    position: Vector<f32, 4>,
-    normal: Vector<f32, 3>,
+use crate::vertex::vertex_layout;
+
+vertex_layout! {
+    /// A vertex of a [Mesh], uploaded to the GPU as is.
+    #[repr(C)]
+    #[derive(Debug, Clone, Copy, Pod, Zeroable)]
+    pub struct Vertex {
+        position: Vector<f32, 4>,
+        normal: Vector<f32, 3>,
+        /// Texture coordinates, `(0, 0)` being the top left corner of the texture.
+        uv: Vector<f32, 2>,
+    }
 }
 
 impl Vertex {
-    pub fn position(&self) -> &Vector<f32, 4> {
-        &self.position
+    pub fn new(position: Vector<f32, 4>, normal: Vector<f32, 3>, uv: Vector<f32, 2>) -> Self {
+        Self {
+            position,
+            normal,
+            uv,
+        }
+    }
+}
+
+vertex_layout! {
+    /// A vertex of a skinned [Mesh], moved by up to four joints of a
+    /// [Skeleton](crate::animation::Skeleton).
+    #[repr(C)]
+    #[derive(Debug, Clone, Copy, PartialEq, Pod, Zeroable)]
+    pub struct SkinnedVertex {
+        position: Vector<f32, 4>,
+        normal: Vector<f32, 3>,
+        uv: Vector<f32, 2>,
+        /// The indices of the joints.
+        joints: [u32; 4],
+        /// How much each of the joints moves the vertex, summing up to 1.
+        weights: Vector<f32, 4>,
     }
+}
 
-    pub fn normal(&self) -> &Vector<f32, 3> {
-        &self.normal
+impl SkinnedVertex {
+    pub fn new(vertex: Vertex, joints: [u32; 4], weights: Vector<f32, 4>) -> Self {
+        Self {
+            position: vertex.position,
+            normal: vertex.normal,
+            uv: vertex.uv,
+            joints,
+            weights,
+        }
     }
 }
 
-pub struct Mesh {
-    vertices: Vec<Vertex>,
+pub struct Mesh<V = Vertex> {
+    vertices: Vec<V>,
     indices: Vec<u32>,
 }
 
-impl Mesh {
-    pub fn vertices(&self) -> &Vec<Vertex> {
+impl<V> Mesh<V> {
+    pub fn new(vertices: Vec<V>, indices: Vec<u32>) -> Self {
+        Self { vertices, indices }
+    }
+
+    pub fn vertices(&self) -> &Vec<V> {
         &self.vertices
     }
 
@@ -81,12 +124,15 @@ pub fn generate_cube() -> Mesh {
         // bottom
         v![0.0, -1.0, 0.0],
     ];
+    // Every face is listed starting from its bottom left corner counter-clockwise.
+    let uvs: [Vector<f32, 2>; 4] = [v![0.0, 1.0], v![1.0, 1.0], v![1.0, 0.0], v![0.0, 0.0]];
     let vertices = vertex_positions
         .iter()
         .enumerate()
         .map(|(i, position)| Vertex {
             position: *position,
             normal: normals[i / 4],
+            uv: uvs[i % 4],
         })
         .collect();
 
@@ -127,12 +173,15 @@ pub fn generate_plane() -> Mesh {
         v![1.0, 0.0, -1.0, 1.0], // 2
         v![-1.0, 0.0, -1.0, 1.0], // 3
     ];
+    let uvs: [Vector<f32, 2>; 4] = [v![0.0, 1.0], v![1.0, 1.0], v![1.0, 0.0], v![0.0, 0.0]];
     // The normal will be the same for each vertex, up.
     let vertices = vertex_positions
         .iter()
-        .map(|position| Vertex {
+        .zip(uvs)
+        .map(|(position, uv)| Vertex {
             position: *position,
             normal: v![0.0, 1.0, 0.0],
+            uv,
         })
         .collect();
 
@@ -145,3 +194,41 @@ pub fn generate_plane() -> Mesh {
 
     Mesh { vertices, indices }
 }
+
+/// A sphere of radius 1 centered at the origo, made of `rings` rings
+/// from pole to pole with `segments` quads each.
+pub fn generate_sphere(rings: u32, segments: u32) -> Mesh {
+    let mut vertices = Vec::new();
+    // The seam and the poles have a vertex per segment, so the texture
+    // wraps around exactly once.
+    for ring in 0..=rings {
+        let polar = std::f32::consts::PI * ring as f32 / rings as f32;
+        for segment in 0..=segments {
+            let azimuth = std::f32::consts::TAU * segment as f32 / segments as f32;
+            let normal = Spherical {
+                radius: 1.0,
+                polar,
+                azimuth,
+            }
+            .to_cartesian();
+            vertices.push(Vertex {
+                position: v![normal[0], normal[1], normal[2], 1.0],
+                normal,
+                uv: v![segment as f32 / segments as f32, ring as f32 / rings as f32],
+            });
+        }
+    }
+
+    let columns = segments + 1;
+    let mut indices = Vec::new();
+    for ring in 0..rings {
+        for segment in 0..segments {
+            let corner = ring * columns + segment;
+            let (below, next) = (corner + columns, corner + 1);
+            // Counter-clockwise seen from the outside.
+            indices.extend([corner, below, below + 1, below + 1, next, corner]);
+        }
+    }
+
+    Mesh { vertices, indices }
+}
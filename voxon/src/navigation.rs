@@ -0,0 +1,174 @@
+//! Routes for units around the static geometry
+//!
+//! The ground between where a unit stands and where it should go is cut into
+//! square cells, each blocked if something solid stands on it below the
+//! height of the unit. [find_path] searches the cells for a way around and
+//! [smooth] keeps only the corners, which become the waypoints of the route.
+
+use lina::{v, vector::Vector};
+use path::{Cell, Grid, find_path, smooth};
+
+use crate::physics::{Aabb, Collide};
+
+/// The width of a cell, in meters.
+const CELL_SIZE: f32 = 0.5;
+/// How far a route may lead past the start and the goal, in meters.
+const MARGIN: f32 = 8.0;
+/// The most cells along either side, longer routes are walked straight.
+const MAX_CELLS: usize = 256;
+/// How high something has to stand out of the ground to be in the way.
+const CLEARANCE: f32 = 0.1;
+
+/// The cells of the ground around a route, at the height of its goal.
+struct NavGrid {
+    grid: Grid,
+    /// The lower corner of the first cell.
+    origin: Vector<f32, 3>,
+}
+
+impl NavGrid {
+    /// Cut the ground around `from` and `to` into cells, blocking those
+    /// where `geometry` is in the way of a box `height` high standing on
+    /// them, or `None` if they are too far apart.
+    fn new(
+        geometry: &(impl Collide + ?Sized),
+        from: Vector<f32, 3>,
+        to: Vector<f32, 3>,
+        height: f32,
+    ) -> Option<Self> {
+        let min = from.min(to) - v![MARGIN, 0.0, MARGIN];
+        let max = from.max(to) + v![MARGIN, 0.0, MARGIN];
+        let cells = |axis: usize| ((max[axis] - min[axis]) / CELL_SIZE).ceil() as usize;
+        let (width, depth) = (cells(0), cells(2));
+        if width > MAX_CELLS || depth > MAX_CELLS {
+            return None;
+        }
+
+        let mut nav_grid = Self {
+            grid: Grid::new(width, depth),
+            origin: v![min[0], to[1], min[2]],
+        };
+        let mut colliders = Vec::new();
+        for z in 0..depth as i32 {
+            for x in 0..width as i32 {
+                let corner = nav_grid.corner([x, z]);
+                let column = Aabb::new(
+                    corner + v![0.0, CLEARANCE, 0.0],
+                    corner + v![CELL_SIZE, height, CELL_SIZE],
+                );
+                colliders.clear();
+                geometry.colliders(&column, &mut colliders);
+                if colliders
+                    .iter()
+                    .any(|collider| collider.intersects(&column))
+                {
+                    nav_grid.grid.set_blocked([x, z]);
+                }
+            }
+        }
+        Some(nav_grid)
+    }
+
+    fn corner(&self, [x, z]: Cell) -> Vector<f32, 3> {
+        self.origin + v![x as f32 * CELL_SIZE, 0.0, z as f32 * CELL_SIZE]
+    }
+
+    fn center(&self, cell: Cell) -> Vector<f32, 3> {
+        self.corner(cell) + v![CELL_SIZE / 2.0, 0.0, CELL_SIZE / 2.0]
+    }
+
+    fn cell(&self, point: Vector<f32, 3>) -> Cell {
+        let offset = point - self.origin;
+        [
+            (offset[0] / CELL_SIZE).floor() as i32,
+            (offset[2] / CELL_SIZE).floor() as i32,
+        ]
+    }
+}
+
+/// The waypoints leading the feet of a unit `height` high from `from` to
+/// `to` around `geometry`, ending at `to`.
+///
+/// The unit walks straight to `to` if there is no way around or the way
+/// would be too long to search.
+pub fn route(
+    geometry: &(impl Collide + ?Sized),
+    from: Vector<f32, 3>,
+    to: Vector<f32, 3>,
+    height: f32,
+) -> Vec<Vector<f32, 3>> {
+    let Some(nav_grid) = NavGrid::new(geometry, from, to, height) else {
+        return vec![to];
+    };
+    let Some(path) = find_path(&nav_grid.grid, nav_grid.cell(from), nav_grid.cell(to)) else {
+        return vec![to];
+    };
+
+    // The unit already is in the first cell and the last one is where it
+    // should stand exactly.
+    let corners = smooth(&nav_grid.grid, &path);
+    let inner = corners.get(1..corners.len() - 1).unwrap_or_default();
+    inner
+        .iter()
+        .map(|cell| nav_grid.center(*cell))
+        .chain([to])
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ground() -> Aabb {
+        Aabb::new(v![-20.0, -1.0, -20.0], v![20.0, 0.0, 20.0])
+    }
+
+    #[test]
+    fn open_ground_is_crossed_straight() {
+        let geometry = [ground()];
+        let to = v![5.0, 0.0, 3.0];
+        assert_eq!(route(&geometry[..], v![0.0, 0.0, 0.0], to, 1.0), vec![to]);
+    }
+
+    #[test]
+    fn walls_are_walked_around() {
+        // From one side of the wall to the other, which is open past z = 3.
+        let wall = Aabb::new(v![2.0, 0.0, -10.0], v![3.0, 2.0, 3.0]);
+        let geometry = [ground(), wall];
+        let to = v![5.0, 0.0, 0.0];
+        let route = route(&geometry[..], v![0.0, 0.0, 0.0], to, 1.0);
+
+        assert_eq!(route.last(), Some(&to));
+        assert!(route.len() > 1);
+        assert!(route.iter().any(|waypoint| waypoint[2] > 3.0));
+        // No leg of the route passes through the wall.
+        let mut from = v![0.0, 0.0, 0.0];
+        for &waypoint in &route {
+            for step in 0..=100 {
+                let point = from + (waypoint - from) * (step as f32 / 100.0);
+                let inside = (0..3).all(|axis| {
+                    axis == 1 || (wall.min[axis] < point[axis] && point[axis] < wall.max[axis])
+                });
+                assert!(!inside, "{point:?} is inside of the wall");
+            }
+            from = waypoint;
+        }
+    }
+
+    #[test]
+    fn low_obstacles_and_unreachable_goals_are_walked_straight() {
+        // Too low to be in the way.
+        let bump = Aabb::new(v![2.0, 0.0, -10.0], v![3.0, 0.05, 10.0]);
+        let to = v![5.0, 0.0, 0.0];
+        assert_eq!(
+            route(&[ground(), bump][..], v![0.0, 0.0, 0.0], to, 1.0),
+            vec![to]
+        );
+
+        let enclosing = Aabb::new(v![4.0, 0.0, -1.0], v![6.0, 2.0, 1.0]);
+        assert_eq!(
+            route(&[ground(), enclosing][..], v![0.0, 0.0, 0.0], to, 1.0),
+            vec![to]
+        );
+    }
+}
@@ -0,0 +1,235 @@
+//! Rendering without a window, for screenshots and image comparisons.
+
+use wgpu::{Device, Queue, TextureFormat, TextureUsages};
+use winit::dpi::PhysicalSize;
+
+use crate::{
+    capture::read_texture,
+    gpu::{GpuInitError, RenderSettings},
+    overlay::Overlay,
+    scene::Scene,
+    view::{RenderTarget, View, Viewport},
+};
+
+/// Sampled as sRGB like most surfaces, so captures look like the window does.
+const COLOR_FORMAT: TextureFormat = TextureFormat::Rgba8UnormSrgb;
+
+/// Renders the [Scene] into a texture instead of a surface.
+///
+/// Nothing is presented, the last rendered frame can be read back with
+/// [read_back](Offscreen::read_back) or saved with
+/// [read_back_to_image](Offscreen::read_back_to_image).
+pub struct Offscreen {
+    pub device: Device,
+    pub queue: Queue,
+    pub scene: Scene,
+    target: RenderTarget,
+    /// Drawn in order, the later ones on top.
+    pub views: Vec<View>,
+    texture: wgpu::Texture,
+}
+
+impl Offscreen {
+    /// The present mode of the `settings` is ignored, as nothing is presented.
+    ///
+    /// Fails if there is no adapter at all, e.g. on a machine without a GPU
+    /// or software renderer, see [GpuInitError].
+    pub async fn new(
+        size: PhysicalSize<u32>,
+        settings: &RenderSettings,
+    ) -> Result<Self, GpuInitError> {
+        let instance = settings.adapter.create_instance().await;
+        let adapter = settings.adapter.select_adapter(&instance, None).await?;
+        let (device, queue) = settings.adapter.request_device(&adapter).await?;
+
+        let scene = Scene::new(
+            &adapter,
+            COLOR_FORMAT,
+            &device,
+            &queue,
+            settings.sample_count,
+        );
+        let target = scene.create_target(&device, size);
+        let views = vec![View::new(scene.main_camera(), Viewport::FULL)];
+
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("offscreen texture"),
+            size: wgpu::Extent3d {
+                width: size.width,
+                height: size.height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: COLOR_FORMAT,
+            usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+
+        Ok(Self {
+            device,
+            queue,
+            scene,
+            target,
+            views,
+            texture,
+        })
+    }
+
+    /// Render a frame of the [views](Offscreen::views), by default only the
+    /// one from the scene's main camera. The [Scene] is not simulated.
+    ///
+    /// Unlike in a window, all the chunks around the camera are streamed in
+    /// first, so the frame doesn't depend on how fast they are generated.
+    pub fn render(&mut self, overlay: &mut Overlay) {
+        self.scene.finish_streaming(&self.device, &self.queue);
+        self.scene.prepare(&self.device, &self.queue);
+        self.scene.render(
+            &self.target,
+            &self.views,
+            &self.texture,
+            &self.device,
+            &self.queue,
+            Some(overlay),
+        );
+    }
+
+    /// Copy the last rendered frame back from the GPU.
+    pub fn read_back(&self) -> image::RgbaImage {
+        read_texture(&self.device, &self.queue, &self.texture)
+            .expect("the offscreen texture can be read back")
+    }
+
+    /// Save the last rendered frame, the format is chosen by the extension of `path`.
+    pub fn read_back_to_image(&self, path: impl AsRef<std::path::Path>) -> image::ImageResult<()> {
+        self.read_back().save(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::PI;
+
+    use graphic::camera::Camera;
+    use lina::v;
+    use winit::dpi::PhysicalSize;
+
+    use super::Offscreen;
+    use crate::{
+        gpu::RenderSettings,
+        overlay::Overlay,
+        post::{FXAA, Tonemapping},
+        view::{View, Viewport},
+    };
+
+    /// An [Offscreen] renderer of `size` with `sample_count` samples.
+    ///
+    /// Panics without a GPU adapter, the tests using it are only run when
+    /// asked for.
+    fn offscreen(size: PhysicalSize<u32>, sample_count: u32) -> Offscreen {
+        let settings = RenderSettings {
+            sample_count,
+            ..Default::default()
+        };
+        pollster::block_on(Offscreen::new(size, &settings))
+            .unwrap_or_else(|err| panic!("no GPU adapter available: {err}"))
+    }
+
+    #[test]
+    #[ignore = "needs a GPU adapter, run with --include-ignored"]
+    fn renders_the_same_frame_twice() {
+        let size = PhysicalSize::new(64, 48);
+        let mut offscreen = offscreen(size, 4);
+        let mut overlay = Overlay::new(1.0);
+
+        offscreen.render(&mut overlay);
+        let first = offscreen.read_back();
+        offscreen.render(&mut overlay);
+        let second = offscreen.read_back();
+
+        assert_eq!(first.dimensions(), (64, 48));
+        assert_eq!(first, second);
+        // The cube is in front of the default camera.
+        assert_ne!(first.get_pixel(32, 24).0, [0, 0, 0, 255]);
+    }
+
+    #[test]
+    #[ignore = "needs a GPU adapter, run with --include-ignored"]
+    fn draws_later_views_only_into_their_viewport() {
+        let size = PhysicalSize::new(64, 48);
+        let mut offscreen = offscreen(size, 4);
+        let mut overlay = Overlay::new(1.0);
+        offscreen.render(&mut overlay);
+        let single = offscreen.read_back();
+
+        // Looking straight up only shows the sky.
+        let mut camera = Camera::default();
+        camera.set_pose(v![0.0, 0.0, 5.0], PI / 2.0, 0.0, 0.0);
+        let sky_camera = offscreen.scene.add_camera(camera);
+        let right = Viewport {
+            x: 0.5,
+            width: 0.5,
+            ..Viewport::FULL
+        };
+        offscreen.views.push(View::new(sky_camera, right));
+        offscreen.render(&mut overlay);
+        let split = offscreen.read_back();
+
+        for (x, y, pixel) in split.enumerate_pixels() {
+            if x < 32 {
+                assert_eq!(pixel, single.get_pixel(x, y));
+            }
+        }
+        assert_ne!(split.get_pixel(48, 40), single.get_pixel(48, 40));
+    }
+
+    #[test]
+    #[ignore = "needs a GPU adapter with timestamp queries, run with --include-ignored"]
+    fn measures_every_pass_on_the_gpu() {
+        let size = PhysicalSize::new(64, 48);
+        let mut offscreen = offscreen(size, 1);
+        assert!(
+            offscreen
+                .device
+                .features()
+                .contains(wgpu::Features::TIMESTAMP_QUERY),
+            "the GPU has no timestamp queries"
+        );
+        let mut overlay = Overlay::new(1.0);
+
+        offscreen.render(&mut overlay);
+        // Waits for the GPU, so the timestamps are ready for the next frame.
+        offscreen.read_back();
+        offscreen.render(&mut overlay);
+
+        let pass_times = offscreen
+            .scene
+            .gpu_pass_times()
+            .expect("timestamps were read");
+        let passes: Vec<_> = pass_times.iter().map(|(pass, _)| *pass).collect();
+        assert_eq!(passes, ["shadow", "main", "post"]);
+    }
+
+    #[test]
+    #[ignore = "needs a GPU adapter, run with --include-ignored"]
+    fn tonemaps_with_the_exposure() {
+        let size = PhysicalSize::new(64, 48);
+        let mut offscreen = offscreen(size, 1);
+        let mut overlay = Overlay::new(1.0);
+        offscreen.render(&mut overlay);
+        let exposed = offscreen.read_back();
+
+        let post_process = offscreen.scene.post_process_mut();
+        post_process.set_tonemapping(Tonemapping {
+            exposure: 0.0,
+            ..post_process.tonemapping()
+        });
+        post_process.set_enabled(FXAA, true);
+        offscreen.render(&mut overlay);
+        let dark = offscreen.read_back();
+
+        assert_ne!(exposed.get_pixel(32, 24).0, [0, 0, 0, 255]);
+        assert!(dark.pixels().all(|pixel| pixel.0 == [0, 0, 0, 255]));
+    }
+}
@@ -0,0 +1,667 @@
+//! Immediate mode debug overlay.
+//!
+//! Widgets are declared every frame through [Overlay], which lays them out
+//! from the top left corner of the window as screen space quads.
+//! Freely placed text is printed through [DebugText].
+//! [OverlayPipeline] renders those quads on top of everything else.
+//!
+//! Text uses the public domain 8x13 X11 "fixed" bitmap font, covering the
+//! printable ASCII characters.
+
+use std::borrow::Cow;
+
+use bytemuck::{Pod, Zeroable};
+use graphic::color::Color;
+use wgpu::{
+    BindGroup, BlendState, Buffer, BufferAddress, BufferUsages, DepthBiasState, DepthStencilState,
+    Device, Queue, RenderPass, RenderPipeline, StencilState, TextureFormat,
+};
+
+use crate::{
+    texture::Texture,
+    vertex::{VertexLayout, vertex_layout},
+};
+
+// Font atlas layout: glyphs of the printable ASCII range in rows of 16.
+const GLYPH_WIDTH: u32 = 8;
+const GLYPH_HEIGHT: u32 = 13;
+const ATLAS_COLUMNS: u32 = 16;
+const ATLAS_WIDTH: u32 = GLYPH_WIDTH * ATLAS_COLUMNS;
+const ATLAS_HEIGHT: u32 = GLYPH_HEIGHT * 6;
+const FIRST_GLYPH: u8 = b' ';
+const LAST_GLYPH: u8 = b'~';
+
+// Spacing around the panel contents and between lines, in font pixels.
+const PADDING: f32 = 4.0;
+const LINE_SPACING: f32 = 2.0;
+
+const TEXT_COLOR: Color = Color::WHITE;
+const HOVER_COLOR: Color = Color::rgb(1.0, 0.8, 0.2);
+const PANEL_COLOR: Color = Color::BLACK.with_alpha(0.6);
+
+vertex_layout! {
+    #[repr(C)]
+    #[derive(Debug, Clone, Copy, Pod, Zeroable)]
+    struct OverlayVertex {
+        position: [f32; 2],
+        uv: [f32; 2],
+        color: Color,
+    }
+}
+
+/// Collects the widgets of the current frame.
+///
+/// All positions are in physical pixels, with the origin at the top left
+/// corner of the window.
+#[derive(Debug)]
+pub struct Overlay {
+    // Size of a font pixel in physical pixels.
+    scale: f32,
+    visible: bool,
+    cursor: Option<[f32; 2]>,
+    // A click not yet handled by any widget.
+    clicked: bool,
+    // Vertical position of the next widget.
+    next_line: f32,
+    // The width of the widest widget in the frame.
+    width: f32,
+    // The size of the panel as it was last drawn.
+    last_panel: [f32; 2],
+    // Every six consecutive entries form a quad.
+    vertices: Vec<OverlayVertex>,
+    text: DebugText,
+}
+
+/// Text printed anywhere on the screen, without the panel of the [Overlay].
+///
+/// Shares the font and the pipeline of the [Overlay], it is drawn on top of
+/// its widgets and cleared with them every frame.
+#[derive(Debug)]
+pub struct DebugText {
+    // Size of a font pixel in physical pixels.
+    scale: f32,
+    // Every six consecutive entries form a quad.
+    vertices: Vec<OverlayVertex>,
+}
+
+impl DebugText {
+    /// Print `text` with its top left corner at `x`, `y` physical pixels.
+    ///
+    /// Every `\n` starts a new line below the previous one.
+    pub fn print(&mut self, x: f32, y: f32, text: &str) {
+        let line_height = (GLYPH_HEIGHT as f32 + LINE_SPACING) * self.scale;
+        for (i, line) in text.lines().enumerate() {
+            glyph_quads(
+                &mut self.vertices,
+                [x, y + i as f32 * line_height],
+                self.scale,
+                line,
+                TEXT_COLOR,
+            );
+        }
+    }
+
+    /// The height of `lines` lines of text in physical pixels,
+    /// e.g. to align them to the bottom of the screen.
+    pub fn height(&self, lines: usize) -> f32 {
+        let lines = lines as f32;
+        (lines * GLYPH_HEIGHT as f32 + (lines - 1.0).max(0.0) * LINE_SPACING) * self.scale
+    }
+}
+
+impl Overlay {
+    /// Create an overlay drawing every font pixel as `scale` times `scale` pixels.
+    pub fn new(scale: f32) -> Self {
+        Self {
+            scale,
+            visible: true,
+            cursor: None,
+            clicked: false,
+            next_line: PADDING * scale,
+            width: 0.0,
+            last_panel: [0.0, 0.0],
+            vertices: Vec::new(),
+            text: DebugText {
+                scale,
+                vertices: Vec::new(),
+            },
+        }
+    }
+
+    /// Text placed freely on the screen, see [DebugText].
+    pub fn text(&mut self) -> &mut DebugText {
+        &mut self.text
+    }
+
+    pub fn toggle_visible(&mut self) {
+        self.visible = !self.visible;
+    }
+
+    pub fn cursor_moved(&mut self, position: [f32; 2]) {
+        self.cursor = Some(position);
+    }
+
+    pub fn cursor_left(&mut self) {
+        self.cursor = None;
+    }
+
+    /// Whether the cursor is over the panel drawn last, so a click is
+    /// meant for the widgets rather than for the scene behind them.
+    pub fn is_hovered(&self) -> bool {
+        let [width, height] = self.last_panel;
+        self.cursor
+            .is_some_and(|[x, y]| (0.0..width).contains(&x) && (0.0..height).contains(&y))
+    }
+
+    /// Register a click, handled by the widget under the cursor in the next frame.
+    pub fn click(&mut self) {
+        self.clicked = true;
+    }
+
+    /// A line of text.
+    pub fn label(&mut self, text: &str) {
+        if self.visible {
+            self.text_line(text, TEXT_COLOR);
+        }
+    }
+
+    /// A line of text toggling `value` when clicked.
+    ///
+    /// Returns `true` if `value` was changed.
+    pub fn checkbox(&mut self, text: &str, value: &mut bool) -> bool {
+        if !self.visible {
+            return false;
+        }
+
+        let top = self.next_line;
+        let text = format!("[{}] {text}", if *value { 'x' } else { ' ' });
+        let hovered = self.cursor.is_some_and(|[x, y]| {
+            let left = PADDING * self.scale;
+            let right = left + self.text_width(&text);
+            (left..right).contains(&x) && (top..top + self.line_height()).contains(&y)
+        });
+
+        self.text_line(&text, if hovered { HOVER_COLOR } else { TEXT_COLOR });
+
+        let toggled = hovered && self.clicked;
+        if toggled {
+            self.clicked = false;
+            *value = !*value;
+        }
+        toggled
+    }
+
+    pub fn vertex_count(&self) -> usize {
+        // The panel behind the widgets is added on upload.
+        let widgets = if self.vertices.is_empty() {
+            0
+        } else {
+            self.vertices.len() + 6
+        };
+        widgets + self.text.vertices.len()
+    }
+
+    /// Remove all widgets, starting the layout from the top again.
+    pub fn clear(&mut self) {
+        self.last_panel = if self.vertices.is_empty() {
+            [0.0, 0.0]
+        } else {
+            self.panel_size()
+        };
+        self.vertices.clear();
+        self.text.vertices.clear();
+        self.next_line = PADDING * self.scale;
+        self.width = 0.0;
+        // Clicks missing every widget are dropped.
+        self.clicked = false;
+    }
+
+    fn line_height(&self) -> f32 {
+        (GLYPH_HEIGHT as f32 + LINE_SPACING) * self.scale
+    }
+
+    fn text_width(&self, text: &str) -> f32 {
+        (text.chars().count() as u32 * GLYPH_WIDTH) as f32 * self.scale
+    }
+
+    fn text_line(&mut self, text: &str, color: Color) {
+        glyph_quads(
+            &mut self.vertices,
+            [PADDING * self.scale, self.next_line],
+            self.scale,
+            text,
+            color,
+        );
+
+        self.width = self.width.max(self.text_width(text));
+        self.next_line += self.line_height();
+    }
+
+    fn panel_size(&self) -> [f32; 2] {
+        [
+            self.width + 2.0 * PADDING * self.scale,
+            self.next_line + (PADDING - LINE_SPACING) * self.scale,
+        ]
+    }
+
+    /// The panel behind the widgets.
+    ///
+    /// It has to be drawn first, but its size is only known after all
+    /// the widgets are laid out, so it is kept separate from them.
+    fn panel(&self) -> [OverlayVertex; 6] {
+        // The solid texel in the corner of the atlas.
+        let solid_uv = [0.5 / ATLAS_WIDTH as f32, 0.5 / ATLAS_HEIGHT as f32];
+        quad(
+            [0.0, 0.0],
+            self.panel_size(),
+            solid_uv,
+            solid_uv,
+            PANEL_COLOR,
+        )
+    }
+}
+
+/// Append a quad for every glyph of the single line `text`, starting at `position`.
+fn glyph_quads(
+    vertices: &mut Vec<OverlayVertex>,
+    position: [f32; 2],
+    scale: f32,
+    text: &str,
+    color: Color,
+) {
+    let glyph_size = [GLYPH_WIDTH as f32 * scale, GLYPH_HEIGHT as f32 * scale];
+
+    for (i, character) in text.chars().enumerate() {
+        if character == ' ' {
+            continue;
+        }
+        let [u, v] = glyph_uv(character);
+        vertices.extend(quad(
+            [position[0] + i as f32 * glyph_size[0], position[1]],
+            glyph_size,
+            [u, v],
+            [
+                u + GLYPH_WIDTH as f32 / ATLAS_WIDTH as f32,
+                v + GLYPH_HEIGHT as f32 / ATLAS_HEIGHT as f32,
+            ],
+            color,
+        ));
+    }
+}
+
+/// Two triangles covering the rectangle at `position` with `size`.
+fn quad(
+    position: [f32; 2],
+    size: [f32; 2],
+    uv_min: [f32; 2],
+    uv_max: [f32; 2],
+    color: Color,
+) -> [OverlayVertex; 6] {
+    let vertex = |x: usize, y: usize| OverlayVertex {
+        position: [
+            position[0] + x as f32 * size[0],
+            position[1] + y as f32 * size[1],
+        ],
+        uv: [
+            if x == 0 { uv_min[0] } else { uv_max[0] },
+            if y == 0 { uv_min[1] } else { uv_max[1] },
+        ],
+        color,
+    };
+
+    [
+        vertex(0, 0),
+        vertex(0, 1),
+        vertex(1, 1),
+        vertex(0, 0),
+        vertex(1, 1),
+        vertex(1, 0),
+    ]
+}
+
+/// Top left texture coordinate of a glyph in the font atlas.
+///
+/// Characters missing from the font are shown as `?`.
+fn glyph_uv(character: char) -> [f32; 2] {
+    let index = match u8::try_from(character) {
+        Ok(byte @ FIRST_GLYPH..=LAST_GLYPH) => byte - FIRST_GLYPH,
+        _ => b'?' - FIRST_GLYPH,
+    } as u32;
+
+    [
+        ((index % ATLAS_COLUMNS) * GLYPH_WIDTH) as f32 / ATLAS_WIDTH as f32,
+        ((index / ATLAS_COLUMNS) * GLYPH_HEIGHT) as f32 / ATLAS_HEIGHT as f32,
+    ]
+}
+
+/// Decode the font into white glyphs, storing the coverage in the alpha channel.
+///
+/// The top left texel belongs to the space glyph, which is never drawn,
+/// so it is made opaque to serve solid quads.
+fn font_atlas() -> image::RgbaImage {
+    let font = image::load_from_memory(include_bytes!("../assets/font_8x13.png"))
+        .expect("the embedded font is a valid PNG")
+        .to_luma8();
+
+    let mut atlas = image::RgbaImage::from_fn(font.width(), font.height(), |x, y| {
+        // Glyphs are drawn white on black.
+        image::Rgba([255, 255, 255, font.get_pixel(x, y)[0]])
+    });
+    atlas.put_pixel(0, 0, image::Rgba([255, 255, 255, 255]));
+    atlas
+}
+
+/// The GPU side of [Overlay].
+pub struct OverlayPipeline {
+    pipeline: RenderPipeline,
+    screen_buffer: Buffer,
+    bind_group: BindGroup,
+    vertex_buffer: Buffer,
+    // The number of vertices the buffer can hold.
+    capacity: usize,
+    // The number of vertices uploaded in the last flush.
+    vertex_count: usize,
+}
+
+impl OverlayPipeline {
+    pub fn new(
+        device: &Device,
+        queue: &Queue,
+        color_format: TextureFormat,
+        sample_count: u32,
+    ) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("overlay_shader"),
+            source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(include_str!("overlay.wgsl"))),
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("overlay_bind_group_layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+
+        let screen_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("overlay_screen_buffer"),
+            // uniforms have to be padded to a multiple of 16
+            size: 4 * 4, // (screen size + padding) * float size
+            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let font_texture = Texture::from_rgba8(device, queue, &font_atlas(), "font_texture");
+        // Glyphs are drawn at integer scales, keep their pixels sharp.
+        let font_sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("font_sampler"),
+            mag_filter: wgpu::FilterMode::Nearest,
+            min_filter: wgpu::FilterMode::Nearest,
+            ..Default::default()
+        });
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("overlay_bind_group"),
+            layout: &bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: screen_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(font_texture.view()),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::Sampler(&font_sampler),
+                },
+            ],
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("overlay_pipeline_layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            immediate_size: 0,
+        });
+
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("overlay_pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                buffers: &[OverlayVertex::layout()],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: color_format,
+                    blend: Some(BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                ..Default::default()
+            },
+            // Drawn over everything, the depth buffer is only declared to
+            // share the render pass with the scene.
+            depth_stencil: Some(DepthStencilState {
+                format: wgpu::TextureFormat::Depth32Float,
+                depth_compare: wgpu::CompareFunction::Always,
+                depth_write_enabled: false,
+                stencil: StencilState::default(),
+                bias: DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState {
+                count: sample_count,
+                ..Default::default()
+            },
+            multiview_mask: None,
+            cache: None,
+        });
+
+        let capacity = 1024;
+        let vertex_buffer = Self::create_vertex_buffer(device, capacity);
+
+        Self {
+            pipeline,
+            screen_buffer,
+            bind_group,
+            vertex_buffer,
+            capacity,
+            vertex_count: 0,
+        }
+    }
+
+    fn create_vertex_buffer(device: &Device, capacity: usize) -> Buffer {
+        device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("overlay_vertex_buffer"),
+            size: capacity as BufferAddress * OverlayVertex::SIZE,
+            usage: BufferUsages::VERTEX | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        })
+    }
+
+    /// Upload all widgets from `overlay` for a render target of `screen_size`, then clear it.
+    pub fn flush(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        screen_size: [f32; 2],
+        overlay: &mut Overlay,
+    ) {
+        self.vertex_count = overlay.vertex_count();
+        if self.vertex_count > self.capacity {
+            self.capacity = self.vertex_count.next_power_of_two();
+            self.vertex_buffer = Self::create_vertex_buffer(device, self.capacity);
+        }
+
+        if self.vertex_count > 0 {
+            let screen: [f32; 4] = [screen_size[0], screen_size[1], 0.0, 0.0];
+            queue.write_buffer(&self.screen_buffer, 0, bytemuck::bytes_of(&screen));
+
+            let mut offset = 0;
+            if !overlay.vertices.is_empty() {
+                queue.write_buffer(&self.vertex_buffer, 0, bytemuck::bytes_of(&overlay.panel()));
+                offset += 6 * OverlayVertex::SIZE;
+                queue.write_buffer(
+                    &self.vertex_buffer,
+                    offset,
+                    bytemuck::cast_slice(&overlay.vertices),
+                );
+                offset += overlay.vertices.len() as BufferAddress * OverlayVertex::SIZE;
+            }
+            if !overlay.text.vertices.is_empty() {
+                queue.write_buffer(
+                    &self.vertex_buffer,
+                    offset,
+                    bytemuck::cast_slice(&overlay.text.vertices),
+                );
+            }
+        }
+        overlay.clear();
+    }
+
+    /// Draw the widgets uploaded by the last [flush](OverlayPipeline::flush).
+    ///
+    /// Returns the number of vertices drawn.
+    pub fn draw(&self, render_pass: &mut RenderPass) -> u32 {
+        if self.vertex_count == 0 {
+            return 0;
+        }
+
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, &self.bind_group, &[]);
+        render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+        render_pass.draw(0..self.vertex_count as u32, 0..1);
+        self.vertex_count as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn font_atlas_layout() {
+        let atlas = font_atlas();
+        assert_eq!(atlas.dimensions(), (ATLAS_WIDTH, ATLAS_HEIGHT));
+        // Apart from the solid texel the space glyph is empty.
+        assert_eq!(atlas.get_pixel(0, 0)[3], 255);
+        assert_eq!(atlas.get_pixel(1, 1)[3], 0);
+        // But '#' isn't.
+        let [u, v] = glyph_uv('#');
+        let (x, y) = (
+            (u * ATLAS_WIDTH as f32) as u32,
+            (v * ATLAS_HEIGHT as f32) as u32,
+        );
+        assert!(
+            (x..x + GLYPH_WIDTH)
+                .flat_map(|x| (y..y + GLYPH_HEIGHT).map(move |y| (x, y)))
+                .any(|(x, y)| atlas.get_pixel(x, y)[3] == 255)
+        );
+    }
+
+    #[test]
+    fn spaces_are_skipped() {
+        let mut overlay = Overlay::new(1.0);
+        overlay.label("a b");
+
+        // Two glyphs and the panel.
+        assert_eq!(overlay.vertex_count(), 3 * 6);
+    }
+
+    #[test]
+    fn debug_text_has_no_panel() {
+        let mut overlay = Overlay::new(2.0);
+        overlay.text().print(100.0, 50.0, "ab\nc");
+
+        assert_eq!(overlay.vertex_count(), 3 * 6);
+        // The second line starts below the first one.
+        let c = &overlay.text.vertices[2 * 6];
+        assert_eq!(c.position, [100.0, 50.0 + (13.0 + 2.0) * 2.0]);
+
+        overlay.clear();
+        assert_eq!(overlay.vertex_count(), 0);
+    }
+
+    #[test]
+    fn checkbox_toggles_on_click() {
+        let mut overlay = Overlay::new(2.0);
+        let mut value = false;
+        overlay.cursor_moved([20.0, 20.0]);
+
+        assert!(!overlay.checkbox("Wireframe", &mut value));
+        overlay.clear();
+
+        overlay.click();
+        assert!(overlay.checkbox("Wireframe", &mut value));
+        assert!(value);
+        // The click is only handled once.
+        assert!(!overlay.checkbox("Wireframe", &mut value));
+        assert!(value);
+    }
+
+    #[test]
+    fn hovering_the_panel_drawn_last() {
+        let mut overlay = Overlay::new(1.0);
+        overlay.cursor_moved([10.0, 10.0]);
+        assert!(!overlay.is_hovered());
+
+        overlay.label("FPS");
+        overlay.clear();
+        assert!(overlay.is_hovered());
+        overlay.cursor_moved([500.0, 10.0]);
+        assert!(!overlay.is_hovered());
+
+        // Hiding the overlay leaves no panel behind.
+        overlay.toggle_visible();
+        overlay.label("FPS");
+        overlay.clear();
+        overlay.cursor_moved([10.0, 10.0]);
+        assert!(!overlay.is_hovered());
+    }
+
+    #[test]
+    fn click_outside_is_dropped() {
+        let mut overlay = Overlay::new(1.0);
+        let mut value = false;
+        overlay.cursor_moved([500.0, 500.0]);
+        overlay.click();
+
+        assert!(!overlay.checkbox("Wireframe", &mut value));
+        overlay.clear();
+
+        overlay.cursor_moved([10.0, 10.0]);
+        assert!(!overlay.checkbox("Wireframe", &mut value));
+    }
+}
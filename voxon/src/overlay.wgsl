@@ -0,0 +1,47 @@
+struct Screen {
+    // Size of the render target in pixels.
+    size: vec2f,
+};
+
+@group(0)
+@binding(0)
+var<uniform> screen: Screen;
+
+@group(0)
+@binding(1)
+var font_texture: texture_2d<f32>;
+
+@group(0)
+@binding(2)
+var font_sampler: sampler;
+
+struct Vertex {
+    // Position in pixels, the origin is the top left corner.
+    @location(0) position: vec2f,
+    @location(1) uv: vec2f,
+    @location(2) color: vec4f,
+};
+
+struct VSOutput {
+    @builtin(position) position: vec4f,
+    @location(0) uv: vec2f,
+    @location(1) color: vec4f,
+};
+
+@vertex
+fn vs_main(vertex: Vertex) -> VSOutput {
+    var vsOut: VSOutput;
+    let ndc = vertex.position / screen.size * 2.0 - 1.0;
+    // Pixel rows grow downwards, NDC Y grows upwards.
+    vsOut.position = vec4f(ndc.x, -ndc.y, 0.0, 1.0);
+    vsOut.uv = vertex.uv;
+    vsOut.color = vertex.color;
+    return vsOut;
+}
+
+@fragment
+fn fs_main(vsOut: VSOutput) -> @location(0) vec4f {
+    // The atlas only stores coverage in the alpha channel.
+    let coverage = textureSample(font_texture, font_sampler, vsOut.uv).a;
+    return vec4f(vsOut.color.rgb, vsOut.color.a * coverage);
+}
@@ -0,0 +1,571 @@
+//! Particle effects, simulated on the CPU and drawn as instanced quads.
+//!
+//! An [Emitter] spawns particles at the position of its entity, which fly
+//! off with a random velocity, change their size and color along the
+//! emitter's curves and die at the end of their lifetime. Every frame the
+//! living particles of all emitters are uploaded as instances and drawn by
+//! the [ParticlePipeline] as quads facing the camera, blended over the
+//! scene without writing depth.
+
+use std::{borrow::Cow, ops::Range};
+
+use bytemuck::{Pod, Zeroable};
+use ecs::{Rng, Time};
+use graphic::color::Color;
+use lina::{matrix::Matrix, v, vector::Vector};
+use wgpu::{
+    BindGroup, Buffer, BufferAddress, BufferUsages, CommandEncoder, DepthBiasState,
+    DepthStencilState, Device, Queue, RenderPass, RenderPipeline, StencilState, TextureFormat,
+};
+
+use crate::{
+    animation::Curve,
+    components::Transform,
+    upload::UploadBelt,
+    vertex::{VertexLayout, vertex_layout},
+};
+
+/// How an [Emitter] spawns its particles and how they behave.
+///
+/// The curves are sampled with the age of a particle divided by its
+/// lifetime, from 0 at its birth to 1 at its death.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EmitterSettings {
+    /// Particles spawned per second.
+    pub rate: f32,
+    /// Particles spawned at once when the emitter starts.
+    pub burst: u32,
+    /// The lifetime of a particle in seconds, picked randomly in the range.
+    pub lifetime: Range<f32>,
+    /// The velocity particles start with.
+    pub velocity: Vector<f32, 3>,
+    /// A random velocity up to this fast in any direction is added.
+    pub spread: f32,
+    /// Speeds up all particles, like gravity.
+    pub acceleration: Vector<f32, 3>,
+    /// Scales the velocity, 1 keeping it.
+    pub speed: Curve<f32>,
+    pub color: Curve<Color>,
+    /// The edge length of the quads.
+    pub size: Curve<f32>,
+    /// No more particles are spawned while this many are alive.
+    pub max_particles: usize,
+}
+
+impl Default for EmitterSettings {
+    fn default() -> Self {
+        Self {
+            rate: 10.0,
+            burst: 0,
+            lifetime: 1.0..2.0,
+            velocity: v![0.0, 1.0, 0.0],
+            spread: 0.5,
+            acceleration: v![0.0; 3],
+            speed: Curve::new(vec![0.0], vec![1.0]),
+            color: Curve::new(vec![0.0, 1.0], vec![Color::WHITE, Color::TRANSPARENT]),
+            size: Curve::new(vec![0.0], vec![0.1]),
+            max_particles: 1000,
+        }
+    }
+}
+
+impl EmitterSettings {
+    /// Slowly rising, fading specks of dust, a few at a time.
+    pub fn dust() -> Self {
+        let color = Color::rgb(0.6, 0.55, 0.45);
+        Self {
+            rate: 8.0,
+            lifetime: 2.0..4.0,
+            velocity: v![0.0, 0.15, 0.0],
+            spread: 0.2,
+            color: Curve::new(
+                vec![0.0, 0.3, 1.0],
+                vec![
+                    color.with_alpha(0.0),
+                    color.with_alpha(0.5),
+                    color.with_alpha(0.0),
+                ],
+            ),
+            size: Curve::new(vec![0.0, 1.0], vec![0.05, 0.12]),
+            ..Default::default()
+        }
+    }
+
+    /// A burst of `count` sparks, flying off in every direction and slowing
+    /// down as they fall, cooling from yellow to a dark red.
+    pub fn explosion(count: u32) -> Self {
+        Self {
+            rate: 0.0,
+            burst: count,
+            lifetime: 0.4..0.9,
+            velocity: v![0.0, 1.0, 0.0],
+            spread: 4.0,
+            acceleration: v![0.0, -9.81, 0.0],
+            speed: Curve::new(vec![0.0, 1.0], vec![1.0, 0.2]),
+            color: Curve::new(
+                vec![0.0, 0.3, 1.0],
+                vec![
+                    Color::rgb(1.0, 0.9, 0.4),
+                    Color::rgb(1.0, 0.4, 0.1),
+                    Color::new(0.3, 0.05, 0.0, 0.0),
+                ],
+            ),
+            size: Curve::new(vec![0.0, 1.0], vec![0.12, 0.04]),
+            max_particles: count as usize,
+        }
+    }
+}
+
+/// A particle of an [Emitter], in world space.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Particle {
+    pub position: Vector<f64, 3>,
+    pub velocity: Vector<f32, 3>,
+    /// Seconds since it was spawned.
+    pub age: f32,
+    /// Seconds it lives for.
+    pub lifetime: f32,
+}
+
+impl Particle {
+    /// The age divided by the lifetime, where the curves are sampled.
+    fn life(&self) -> f32 {
+        self.age / self.lifetime
+    }
+}
+
+/// Spawns and simulates particles at the translation of its entity's
+/// [Transform], see [simulate_particles].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Emitter {
+    pub settings: EmitterSettings,
+    /// Whether new particles are spawned, the living ones live on.
+    pub emitting: bool,
+    /// Despawn the entity once the emitter is [finished](Emitter::is_finished),
+    /// for effects playing only once like explosions.
+    pub despawn_when_finished: bool,
+    particles: Vec<Particle>,
+    // Particles still to be spawned, the fraction is carried over to the
+    // next frame.
+    pending: f32,
+    rng: Rng,
+}
+
+impl Emitter {
+    /// An emitter spawning the burst of `settings` on its first update,
+    /// randomized by `seed`.
+    pub fn new(settings: EmitterSettings, seed: u64) -> Self {
+        Self {
+            pending: settings.burst as f32,
+            settings,
+            emitting: true,
+            despawn_when_finished: false,
+            particles: Vec::new(),
+            rng: Rng::new(seed),
+        }
+    }
+
+    /// An emitter despawned with its entity once its particles are gone.
+    pub fn once(settings: EmitterSettings, seed: u64) -> Self {
+        Self {
+            despawn_when_finished: true,
+            ..Self::new(settings, seed)
+        }
+    }
+
+    /// Whether every particle died and no more will be spawned.
+    pub fn is_finished(&self) -> bool {
+        let spawning = self.emitting && (self.settings.rate > 0.0 || self.pending >= 1.0);
+        self.particles.is_empty() && !spawning
+    }
+
+    /// Move the particles `delta` seconds on, letting the old ones die and
+    /// spawning new ones at `origin`.
+    pub fn update(&mut self, origin: Vector<f64, 3>, delta: f32) {
+        let settings = &self.settings;
+        self.particles.retain_mut(|particle| {
+            particle.age += delta;
+            if particle.age >= particle.lifetime {
+                return false;
+            }
+            particle.velocity += settings.acceleration * delta;
+            let speed = settings.speed.sample(particle.life());
+            particle.position += (particle.velocity * (speed * delta)).map(f64::from);
+            true
+        });
+
+        if !self.emitting {
+            return;
+        }
+        self.pending += settings.rate * delta;
+        let room = settings.max_particles.saturating_sub(self.particles.len());
+        let count = (self.pending as usize).min(room);
+        // Whatever didn't fit is dropped, not spawned in a burst later.
+        self.pending = self.pending.fract();
+        for _ in 0..count {
+            let lifetime = self.rng.range(&settings.lifetime);
+            let velocity = settings.velocity + in_unit_sphere(&mut self.rng) * settings.spread;
+            self.particles.push(Particle {
+                position: origin,
+                velocity,
+                age: 0.0,
+                lifetime,
+            });
+        }
+    }
+
+    /// Append the instance of every living particle to `instances`,
+    /// relative to `origin`.
+    fn instances(&self, origin: Vector<f64, 3>, instances: &mut Vec<ParticleInstance>) {
+        instances.extend(self.particles.iter().map(|particle| {
+            let life = particle.life();
+            ParticleInstance {
+                position: (particle.position - origin).map(|value| value as f32),
+                size: self.settings.size.sample(life),
+                color: self.settings.color.sample(life),
+            }
+        }));
+    }
+}
+
+/// Update every [Emitter] at the translation of its entity, despawning the
+/// finished ones meant to play once.
+pub fn simulate_particles(entities: &mut ecs::World, time: &Time) {
+    let delta = time.delta().as_secs_f32();
+    let emitters = entities
+        .query2::<Emitter, Transform>()
+        .map(|(entity, _, transform)| (entity, transform.translation))
+        .collect::<Vec<_>>();
+    for (entity, origin) in emitters {
+        let Some(emitter) = entities.get_mut::<Emitter>(entity) else {
+            continue;
+        };
+        emitter.update(origin, delta);
+        if emitter.despawn_when_finished && emitter.is_finished() {
+            entities.despawn(entity);
+        }
+    }
+}
+
+/// Uniformly in the ball of radius 1.
+fn in_unit_sphere(rng: &mut Rng) -> Vector<f32, 3> {
+    loop {
+        let point = v![
+            rng.unit() * 2.0 - 1.0,
+            rng.unit() * 2.0 - 1.0,
+            rng.unit() * 2.0 - 1.0
+        ];
+        if point.length_squared() <= 1.0 {
+            return point;
+        }
+    }
+}
+
+vertex_layout! {
+    #[repr(C)]
+    #[derive(Debug, Clone, Copy, Pod, Zeroable)]
+    struct ParticleInstance {
+        position: Vector<f32, 3>,
+        size: f32,
+        color: Color,
+    }
+}
+
+/// The `Camera` WGSL struct of the particle shader.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+struct CameraUniform {
+    // Column by column.
+    view_projection: [f32; 16],
+    // The directions of the screen's X and Y axes in world space, padded
+    // to a `vec4f`.
+    right: Vector<f32, 4>,
+    up: Vector<f32, 4>,
+}
+
+/// The GPU side of the [Emitter]s.
+pub struct ParticlePipeline {
+    pipeline: RenderPipeline,
+    camera_buffer: Buffer,
+    camera_bind_group: BindGroup,
+    instance_buffer: Buffer,
+    // The number of instances the buffer can hold.
+    capacity: usize,
+    // The instances of the last flush, kept to reuse the allocation.
+    instances: Vec<ParticleInstance>,
+}
+
+impl ParticlePipeline {
+    pub fn new(device: &Device, color_format: TextureFormat, sample_count: u32) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("particle_shader"),
+            source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(include_str!("particles.wgsl"))),
+        });
+
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("particle_bind_group_layout"),
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("particle_pipeline_layout"),
+            bind_group_layouts: &[&layout],
+            immediate_size: 0,
+        });
+
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("particle_pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                // The quads are generated in the shader, one per instance.
+                buffers: &[wgpu::VertexBufferLayout {
+                    step_mode: wgpu::VertexStepMode::Instance,
+                    ..ParticleInstance::layout()
+                }],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: color_format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                cull_mode: None,
+                ..Default::default()
+            },
+            // Hidden behind the geometry, but unsorted translucent particles
+            // mustn't hide each other.
+            depth_stencil: Some(DepthStencilState {
+                format: wgpu::TextureFormat::Depth32Float,
+                depth_compare: wgpu::CompareFunction::GreaterEqual,
+                depth_write_enabled: false,
+                stencil: StencilState::default(),
+                bias: DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState {
+                count: sample_count,
+                ..Default::default()
+            },
+            multiview_mask: None,
+            cache: None,
+        });
+
+        let camera_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("particle_camera_buffer"),
+            size: std::mem::size_of::<CameraUniform>() as BufferAddress,
+            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let camera_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("particle_bind_group"),
+            layout: &layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: camera_buffer.as_entire_binding(),
+            }],
+        });
+
+        let capacity = 1024;
+        Self {
+            pipeline,
+            camera_buffer,
+            camera_bind_group,
+            instance_buffer: Self::create_instance_buffer(device, capacity),
+            capacity,
+            instances: Vec::new(),
+        }
+    }
+
+    fn create_instance_buffer(device: &Device, capacity: usize) -> Buffer {
+        device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("particle_instance_buffer"),
+            size: capacity as BufferAddress * ParticleInstance::SIZE,
+            usage: BufferUsages::VERTEX | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        })
+    }
+
+    /// Record the upload of the particles of all `emitters` into `encoder`,
+    /// relative to `origin`.
+    pub fn flush<'a>(
+        &mut self,
+        device: &Device,
+        uploads: &mut UploadBelt,
+        encoder: &mut CommandEncoder,
+        emitters: impl Iterator<Item = &'a Emitter>,
+        origin: Vector<f64, 3>,
+    ) {
+        self.instances.clear();
+        for emitter in emitters {
+            emitter.instances(origin, &mut self.instances);
+        }
+        if self.instances.len() > self.capacity {
+            self.capacity = self.instances.len().next_power_of_two();
+            self.instance_buffer = Self::create_instance_buffer(device, self.capacity);
+        }
+
+        uploads.write(
+            encoder,
+            &self.instance_buffer,
+            0,
+            bytemuck::cast_slice(&self.instances),
+        );
+    }
+
+    /// Upload the camera for the next [draw](ParticlePipeline::draw).
+    pub fn update(&self, queue: &Queue, view: Matrix<f32, 4, 4>, projection: Matrix<f32, 4, 4>) {
+        // The rows of the view rotation are the camera's axes.
+        let axis = |row| {
+            let axis = view.row(row);
+            v![axis[0], axis[1], axis[2], 0.0]
+        };
+        let camera = CameraUniform {
+            view_projection: (projection * view).to_cols_array(),
+            right: axis(0),
+            up: axis(1),
+        };
+        queue.write_buffer(&self.camera_buffer, 0, bytemuck::bytes_of(&camera));
+    }
+
+    /// Draw the particles uploaded by the last [flush](ParticlePipeline::flush).
+    ///
+    /// Returns the number of vertices drawn.
+    pub fn draw(&self, render_pass: &mut RenderPass) -> u32 {
+        if self.instances.is_empty() {
+            return 0;
+        }
+
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, &self.camera_bind_group, &[]);
+        render_pass.set_vertex_buffer(0, self.instance_buffer.slice(..));
+        render_pass.draw(0..6, 0..self.instances.len() as u32);
+        6 * self.instances.len() as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn emitters_spawn_at_their_rate() {
+        let settings = EmitterSettings {
+            rate: 10.0,
+            burst: 5,
+            lifetime: 10.0..10.0,
+            ..Default::default()
+        };
+        let mut emitter = Emitter::new(settings, 1);
+        emitter.update(v![0.0; 3], 0.0);
+        assert_eq!(emitter.particles.len(), 5);
+        // Fractions of particles add up over the frames.
+        for _ in 0..4 {
+            emitter.update(v![0.0; 3], 0.025);
+        }
+        assert_eq!(emitter.particles.len(), 6);
+
+        emitter.emitting = false;
+        emitter.update(v![0.0; 3], 1.0);
+        assert_eq!(emitter.particles.len(), 6);
+    }
+
+    #[test]
+    fn particles_die_of_old_age() {
+        let mut emitter = Emitter::once(EmitterSettings::explosion(20), 7);
+        emitter.update(v![1.0, 2.0, 3.0], 0.0);
+        assert_eq!(emitter.particles.len(), 20);
+        assert!(!emitter.is_finished());
+        for particle in &emitter.particles {
+            assert_eq!(particle.position, v![1.0, 2.0, 3.0]);
+            assert!((0.4..0.9).contains(&particle.lifetime));
+        }
+
+        emitter.update(v![1.0, 2.0, 3.0], 0.5);
+        assert!(emitter.particles.len() < 20);
+        emitter.update(v![1.0, 2.0, 3.0], 0.5);
+        assert!(emitter.is_finished());
+    }
+
+    #[test]
+    fn particles_fly_and_fall() {
+        let settings = EmitterSettings {
+            burst: 1,
+            rate: 0.0,
+            velocity: v![1.0, 0.0, 0.0],
+            spread: 0.0,
+            acceleration: v![0.0, -2.0, 0.0],
+            lifetime: 10.0..10.0,
+            ..Default::default()
+        };
+        let mut emitter = Emitter::new(settings, 3);
+        emitter.update(v![0.0; 3], 0.0);
+        emitter.update(v![0.0; 3], 0.5);
+        emitter.update(v![0.0; 3], 0.5);
+        // Semi-implicit Euler, the velocity changes first.
+        let particle = emitter.particles[0];
+        assert!(particle.position.approx_eq(&v![1.0, -1.5, 0.0], 1e-6));
+        assert!(particle.velocity.approx_eq(&v![1.0, -2.0, 0.0], 1e-6));
+    }
+
+    #[test]
+    fn finished_one_shot_emitters_are_despawned() {
+        let mut entities = ecs::World::new();
+        let entity = entities.spawn();
+        entities.insert(entity, <Transform>::default());
+        entities.insert(entity, Emitter::once(EmitterSettings::explosion(3), 1));
+
+        let mut time = Time::new();
+        time.advance(std::time::Duration::from_millis(100));
+        simulate_particles(&mut entities, &time);
+        assert!(entities.is_alive(entity));
+        time.advance(std::time::Duration::from_secs(1));
+        simulate_particles(&mut entities, &time);
+        assert!(!entities.is_alive(entity));
+    }
+
+    #[test]
+    fn random_points_are_in_the_unit_sphere() {
+        let mut rng = Rng::new(42);
+        for _ in 0..100 {
+            assert!(in_unit_sphere(&mut rng).length_squared() <= 1.0);
+        }
+    }
+
+    #[test]
+    fn particles_fly_the_same_far_away() {
+        let settings = EmitterSettings {
+            burst: 1,
+            rate: 0.0,
+            spread: 0.0,
+            lifetime: 10.0..10.0,
+            ..Default::default()
+        };
+        let origin = v![7_000_000.0, 0.0, 7_000_000.0];
+        let mut emitter = Emitter::new(settings, 3);
+        for _ in 0..100 {
+            emitter.update(origin, 0.01);
+        }
+
+        // Spawned by the first update, moved by the others.
+        let mut instances = Vec::new();
+        emitter.instances(origin, &mut instances);
+        assert!(instances[0].position.approx_eq(&v![0.0, 0.99, 0.0], 1e-5));
+    }
+}
@@ -0,0 +1,55 @@
+struct Camera {
+    view_projection: mat4x4f,
+    // The screen's axes in world space, the quads are spanned along them.
+    right: vec3f,
+    up: vec3f,
+};
+
+@group(0)
+@binding(0)
+var<uniform> camera: Camera;
+
+struct Particle {
+    @location(0) position: vec3f,
+    @location(1) size: f32,
+    @location(2) color: vec4f,
+};
+
+struct VSOutput {
+    @builtin(position) position: vec4f,
+    // From the center of the quad, -1 to 1 towards its edges.
+    @location(0) offset: vec2f,
+    @location(1) color: vec4f,
+};
+
+// Two triangles covering the quad.
+const CORNERS = array<vec2f, 6>(
+    vec2f(-1.0, -1.0),
+    vec2f(1.0, -1.0),
+    vec2f(1.0, 1.0),
+    vec2f(-1.0, -1.0),
+    vec2f(1.0, 1.0),
+    vec2f(-1.0, 1.0),
+);
+
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32, particle: Particle) -> VSOutput {
+    let corner = CORNERS[index];
+    let offset = (camera.right * corner.x + camera.up * corner.y) * (particle.size * 0.5);
+
+    var out: VSOutput;
+    out.position = camera.view_projection * vec4f(particle.position + offset, 1.0);
+    out.offset = corner;
+    out.color = particle.color;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VSOutput) -> @location(0) vec4f {
+    // Round, fading out towards the edge.
+    let fade = 1.0 - smoothstep(0.5, 1.0, length(in.offset));
+    if fade <= 0.0 {
+        discard;
+    }
+    return vec4f(in.color.rgb, in.color.a * fade);
+}
@@ -0,0 +1,236 @@
+use lina::{v, vector::Vector};
+
+use super::{Aabb, Collide, Sweep, sweep};
+
+/// The shape and the movement of a [CharacterController], in meters and seconds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CharacterSettings {
+    /// Half the width of the character along X and Z.
+    pub radius: f32,
+    pub height: f32,
+    /// How high the eyes are above the feet.
+    pub eye_height: f32,
+    /// The downwards acceleration.
+    pub gravity: f32,
+    /// The upwards speed at the start of a jump.
+    pub jump_speed: f32,
+    /// The highest ledge which is walked up without jumping.
+    pub step_height: f32,
+}
+
+impl Default for CharacterSettings {
+    fn default() -> Self {
+        Self {
+            radius: 0.3,
+            height: 1.8,
+            eye_height: 1.6,
+            gravity: 20.0,
+            // Jumps up to about 1.2, high enough to get onto a block.
+            jump_speed: 7.0,
+            step_height: 0.5,
+        }
+    }
+}
+
+/// A box shaped character moved by its own velocity alone.
+///
+/// Nothing pushes it around, it slides along whatever it runs into, walks
+/// up small steps and falls until it stands on something.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CharacterController {
+    pub settings: CharacterSettings,
+    /// The middle of the bottom of the character, in world space.
+    pub position: Vector<f64, 3>,
+    pub velocity: Vector<f32, 3>,
+    // Whether it stood on something after the last update.
+    on_ground: bool,
+}
+
+impl CharacterController {
+    /// A character standing at `position`, which falls if there is no ground.
+    pub fn new(position: Vector<f64, 3>, settings: CharacterSettings) -> Self {
+        Self {
+            settings,
+            position,
+            velocity: v![0.0, 0.0, 0.0],
+            on_ground: false,
+        }
+    }
+
+    /// The box the character occupies in a world moved by `-origin`.
+    pub fn bounds_relative_to(&self, origin: Vector<f64, 3>) -> Aabb {
+        let CharacterSettings { radius, height, .. } = self.settings;
+        let position = (self.position - origin).map(|value| value as f32);
+        Aabb::new(
+            position - v![radius, 0.0, radius],
+            position + v![radius, height, radius],
+        )
+    }
+
+    /// Where the eyes of the character are.
+    pub fn eye(&self) -> Vector<f64, 3> {
+        self.position + v![0.0, f64::from(self.settings.eye_height), 0.0]
+    }
+
+    /// Advance the character by `delta` seconds, walking with the
+    /// horizontal velocity `walk` and jumping if `jump` and on the ground.
+    ///
+    /// `geometry` is in the world moved by `-origin`, which should be close
+    /// to the character to keep the precision.
+    pub fn update(
+        &mut self,
+        geometry: &(impl Collide + ?Sized),
+        origin: Vector<f64, 3>,
+        walk: Vector<f32, 3>,
+        jump: bool,
+        delta: f32,
+    ) {
+        self.velocity[0] = walk[0];
+        self.velocity[2] = walk[2];
+        if jump && self.on_ground {
+            self.velocity[1] = self.settings.jump_speed;
+        }
+        self.velocity[1] -= self.settings.gravity * delta;
+
+        let motion = self.velocity * delta;
+        let bounds = self.bounds_relative_to(origin);
+        let mut moved = sweep(&bounds, motion, geometry);
+        if self.on_ground && (moved.blocked[0] || moved.blocked[2]) {
+            let stepped = self.step_up(&bounds, motion, geometry);
+            if horizontal_distance(&stepped) > horizontal_distance(&moved) {
+                moved = stepped;
+            }
+        }
+
+        self.position += moved.motion.map(f64::from);
+        // Landing or bumping the head ends the vertical movement.
+        if moved.blocked[1] {
+            self.velocity[1] = 0.0;
+        }
+        self.on_ground = moved.blocked[1] && motion[1] < 0.0;
+    }
+
+    /// Try the horizontal part of `motion` lifted by the step height, then
+    /// put the character back down onto whatever it stepped on.
+    fn step_up(
+        &self,
+        bounds: &Aabb,
+        motion: Vector<f32, 3>,
+        geometry: &(impl Collide + ?Sized),
+    ) -> Sweep {
+        let up = sweep(bounds, v![0.0, self.settings.step_height, 0.0], geometry);
+        let lifted = bounds.translated(up.motion);
+        let across = sweep(&lifted, v![motion[0], 0.0, motion[2]], geometry);
+        let moved = lifted.translated(across.motion);
+        let down = sweep(&moved, v![0.0, motion[1] - up.motion[1], 0.0], geometry);
+        Sweep {
+            motion: up.motion + across.motion + down.motion,
+            blocked: [across.blocked[0], down.blocked[1], across.blocked[2]],
+        }
+    }
+}
+
+fn horizontal_distance(sweep: &Sweep) -> f32 {
+    sweep.motion[0].hypot(sweep.motion[2])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DELTA: f32 = 1.0 / 60.0;
+
+    const ORIGIN: Vector<f64, 3> = v![0.0, 0.0, 0.0];
+
+    fn floor() -> Aabb {
+        Aabb::new(v![-10.0, -1.0, -10.0], v![10.0, 0.0, 10.0])
+    }
+
+    fn run(
+        character: &mut CharacterController,
+        geometry: &[Aabb],
+        walk: Vector<f32, 3>,
+        frames: usize,
+    ) {
+        for _ in 0..frames {
+            character.update(geometry, ORIGIN, walk, false, DELTA);
+        }
+    }
+
+    #[test]
+    fn falls_until_it_lands() {
+        let mut character = CharacterController::new(v![0.0, 3.0, 0.0], Default::default());
+        run(&mut character, &[floor()], v![0.0, 0.0, 0.0], 60);
+
+        assert!(character.on_ground);
+        assert!(character.position[1].abs() < 1e-5);
+        assert_eq!(character.velocity, v![0.0, 0.0, 0.0]);
+        assert!(character.eye().approx_eq(&v![0.0, 1.6, 0.0], 1e-5));
+    }
+
+    #[test]
+    fn walks_up_steps_but_not_walls() {
+        let step = Aabb::new(v![1.0, 0.0, -1.0], v![2.0, 0.4, 1.0]);
+        let wall = Aabb::new(v![4.0, 0.0, -1.0], v![5.0, 1.0, 1.0]);
+        let mut character = CharacterController::new(v![0.0, 0.0, 0.0], Default::default());
+        run(
+            &mut character,
+            &[floor(), step, wall],
+            v![2.0, 0.0, 0.0],
+            30,
+        );
+
+        // Half way over the step.
+        assert!(character.on_ground);
+        assert!((character.position[1] - 0.4).abs() < 1e-5);
+
+        run(
+            &mut character,
+            &[floor(), step, wall],
+            v![2.0, 0.0, 0.0],
+            120,
+        );
+        assert!(character.on_ground);
+        assert!(character.position[1].abs() < 1e-5);
+        assert!((character.position[0] - 3.7).abs() < 1e-5);
+    }
+
+    #[test]
+    fn jumps_only_from_the_ground() {
+        let geometry = [floor()];
+        let mut character = CharacterController::new(v![0.0, 0.0, 0.0], Default::default());
+        run(&mut character, &geometry, v![0.0, 0.0, 0.0], 1);
+
+        character.update(&geometry[..], ORIGIN, v![0.0, 0.0, 0.0], true, DELTA);
+        assert!(!character.on_ground);
+        let rising = character.velocity[1];
+        assert!(rising > 0.0);
+
+        // Jumping again in the air does nothing.
+        character.update(&geometry[..], ORIGIN, v![0.0, 0.0, 0.0], true, DELTA);
+        assert!(character.velocity[1] < rising);
+
+        let mut highest: f64 = 0.0;
+        for _ in 0..120 {
+            character.update(&geometry[..], ORIGIN, v![0.0, 0.0, 0.0], false, DELTA);
+            highest = highest.max(character.position[1]);
+        }
+        assert!(character.on_ground);
+        assert!(highest > 1.0 && highest < 1.5);
+    }
+
+    #[test]
+    fn walks_the_same_far_away() {
+        // Kilometers away, but in the frame of a local origin nearby.
+        let origin = v![3_000_000.0, 0.0, -5_000_000.0];
+        let mut near = CharacterController::new(v![0.0, 0.0, 0.0], Default::default());
+        let mut far = CharacterController::new(origin, Default::default());
+        for _ in 0..30 {
+            near.update(&[floor()][..], ORIGIN, v![1.0, 0.0, 0.5], false, DELTA);
+            far.update(&[floor()][..], origin, v![1.0, 0.0, 0.5], false, DELTA);
+        }
+
+        assert!(near.position[0] > 0.4);
+        assert!((far.position - origin).approx_eq(&near.position, 1e-6));
+    }
+}
@@ -0,0 +1,253 @@
+//! Collision detection and movement
+//!
+//! Everything solid is an axis aligned box: the blocks of the [World], the
+//! static [Aabb]s placed in the scene and the bounds of whatever moves.
+//! Moving a box with [sweep] stops it at the first box in its way, letting it
+//! slide along the box instead of sticking to it.
+//!
+//! [RigidBody]s are simulated by [Dynamics] instead, colliding as spheres or
+//! rotated boxes, [Obb]s, and bouncing off each other and the static geometry.
+//!
+//! Everything is simulated in `f32`, in the world moved to an origin close
+//! by, see [local_origin](crate::scene::local_origin), so it is as precise
+//! far away from the world's origin as next to it.
+
+use lina::{v, vector::Vector};
+
+use crate::world::{Ray, World};
+
+mod character;
+mod obb;
+mod rigid_body;
+
+pub use character::*;
+pub use obb::*;
+pub use rigid_body::*;
+
+/// Boxes closer than this are touching rather than overlapping, which keeps
+/// the rounding errors of moved boxes from catching on neighboring boxes.
+const EPSILON: f32 = 1e-3;
+
+/// An axis aligned bounding box.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+    pub min: Vector<f32, 3>,
+    pub max: Vector<f32, 3>,
+}
+
+impl Aabb {
+    pub fn new(min: Vector<f32, 3>, max: Vector<f32, 3>) -> Self {
+        Self { min, max }
+    }
+
+    pub fn translated(&self, offset: Vector<f32, 3>) -> Self {
+        Self::new(self.min + offset, self.max + offset)
+    }
+
+    /// The smallest box containing both boxes.
+    pub fn union(&self, other: &Aabb) -> Self {
+        Self::new(self.min.min(other.min), self.max.max(other.max))
+    }
+
+    /// Whether the boxes overlap, boxes which only touch don't.
+    pub fn intersects(&self, other: &Aabb) -> bool {
+        (0..3).all(|axis| self.overlaps_along(other, axis))
+    }
+
+    /// How far along `ray` it enters the box, 0 if the ray starts inside.
+    pub fn ray_distance(&self, ray: &Ray) -> Option<f32> {
+        let (mut enter, mut exit) = (0.0_f32, f32::INFINITY);
+        for axis in 0..3 {
+            // Parallel rays give infinite distances, which work out as well.
+            let inverse = 1.0 / ray.direction[axis];
+            let near = (self.min[axis] - ray.origin[axis]) * inverse;
+            let far = (self.max[axis] - ray.origin[axis]) * inverse;
+            enter = enter.max(near.min(far));
+            exit = exit.min(near.max(far));
+        }
+        (enter <= exit).then_some(enter)
+    }
+
+    fn overlaps_along(&self, other: &Aabb, axis: usize) -> bool {
+        self.min[axis] < other.max[axis] - EPSILON && other.min[axis] < self.max[axis] - EPSILON
+    }
+}
+
+/// Static geometry moving boxes collide with.
+pub trait Collide {
+    /// Append the boxes overlapping `bounds` to `colliders`, more are fine.
+    fn colliders(&self, bounds: &Aabb, colliders: &mut Vec<Aabb>);
+}
+
+impl<C: Collide + ?Sized> Collide for &C {
+    fn colliders(&self, bounds: &Aabb, colliders: &mut Vec<Aabb>) {
+        (*self).colliders(bounds, colliders);
+    }
+}
+
+impl Collide for [Aabb] {
+    fn colliders(&self, bounds: &Aabb, colliders: &mut Vec<Aabb>) {
+        colliders.extend(self.iter().filter(|collider| collider.intersects(bounds)));
+    }
+}
+
+impl<A: Collide, B: Collide> Collide for (A, B) {
+    fn colliders(&self, bounds: &Aabb, colliders: &mut Vec<Aabb>) {
+        self.0.colliders(bounds, colliders);
+        self.1.colliders(bounds, colliders);
+    }
+}
+
+/// Every solid block is a unit box in block coordinates.
+impl Collide for World {
+    fn colliders(&self, bounds: &Aabb, colliders: &mut Vec<Aabb>) {
+        let range = |axis: usize| bounds.min[axis].floor() as i32..bounds.max[axis].ceil() as i32;
+        for z in range(2) {
+            for y in range(1) {
+                for x in range(0) {
+                    if self.block(x, y, z).is_solid() {
+                        let min = v![x as f32, y as f32, z as f32];
+                        colliders.push(Aabb::new(min, min + v![1.0, 1.0, 1.0]));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Geometry placed at `offset`, e.g. the [World] when block coordinates
+/// don't match world space.
+#[derive(Debug, Clone, Copy)]
+pub struct Translated<C> {
+    pub collider: C,
+    pub offset: Vector<f32, 3>,
+}
+
+impl<C: Collide> Collide for Translated<C> {
+    fn colliders(&self, bounds: &Aabb, colliders: &mut Vec<Aabb>) {
+        let first = colliders.len();
+        self.collider
+            .colliders(&bounds.translated(-self.offset), colliders);
+        for collider in &mut colliders[first..] {
+            *collider = collider.translated(self.offset);
+        }
+    }
+}
+
+/// How far a box got with [sweep].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sweep {
+    /// The part of the motion which was possible.
+    pub motion: Vector<f32, 3>,
+    /// Along which axes a box was in the way.
+    pub blocked: [bool; 3],
+}
+
+/// Move `bounds` by `motion` until it runs into `geometry`.
+///
+/// The motion is applied one axis after the other, vertically first, so a
+/// box is stopped along the blocked axes but keeps moving along the others.
+/// Boxes the moving box already overlaps are ignored, so it can always get
+/// out of them.
+pub fn sweep(bounds: &Aabb, motion: Vector<f32, 3>, geometry: &(impl Collide + ?Sized)) -> Sweep {
+    let mut colliders = Vec::new();
+    geometry.colliders(&bounds.union(&bounds.translated(motion)), &mut colliders);
+
+    let mut bounds = *bounds;
+    let mut moved = v![0.0, 0.0, 0.0];
+    let mut blocked = [false; 3];
+    for axis in [1, 0, 2] {
+        let mut distance = motion[axis];
+        for collider in &colliders {
+            // Only the boxes overlapping along the other axes are in the way.
+            let in_the_way = (0..3)
+                .filter(|other| *other != axis)
+                .all(|other| bounds.overlaps_along(collider, other));
+            if !in_the_way {
+                continue;
+            }
+            if distance > 0.0 && collider.min[axis] >= bounds.max[axis] - EPSILON {
+                distance = distance.min((collider.min[axis] - bounds.max[axis]).max(0.0));
+            } else if distance < 0.0 && collider.max[axis] <= bounds.min[axis] + EPSILON {
+                distance = distance.max((collider.max[axis] - bounds.min[axis]).min(0.0));
+            }
+        }
+
+        blocked[axis] = distance != motion[axis];
+        moved[axis] = distance;
+        bounds.min[axis] += distance;
+        bounds.max[axis] += distance;
+    }
+
+    Sweep {
+        motion: moved,
+        blocked,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::Block;
+
+    fn unit_box(min: Vector<f32, 3>) -> Aabb {
+        Aabb::new(min, min + v![1.0, 1.0, 1.0])
+    }
+
+    #[test]
+    fn stops_at_the_first_box_in_the_way() {
+        let walls = [unit_box(v![5.0, 0.0, 0.0]), unit_box(v![3.0, 0.0, 0.0])];
+        let sweep = sweep(&unit_box(v![0.0, 0.0, 0.0]), v![10.0, 0.0, 0.0], &walls[..]);
+        assert_eq!(sweep.motion, v![2.0, 0.0, 0.0]);
+        assert_eq!(sweep.blocked, [true, false, false]);
+    }
+
+    #[test]
+    fn slides_along_walls() {
+        let floor = [Aabb::new(v![-10.0, -1.0, -10.0], v![10.0, 0.0, 10.0])];
+        let sweep = sweep(&unit_box(v![0.0, 0.5, 0.0]), v![2.0, -3.0, 1.0], &floor[..]);
+        assert_eq!(sweep.motion, v![2.0, -0.5, 1.0]);
+        assert_eq!(sweep.blocked, [false, true, false]);
+
+        // Touching boxes, like the floor now, don't stop anything.
+        let resting = unit_box(v![0.0, 0.0, 0.0]);
+        let sweep = super::sweep(&resting, v![1.0, 0.0, 0.0], &floor[..]);
+        assert_eq!(sweep.blocked, [false; 3]);
+    }
+
+    #[test]
+    fn rays_enter_boxes() {
+        let aabb = unit_box(v![2.0, 0.0, 0.0]);
+        let ray = Ray::new(v![0.0, 0.5, 0.5], v![1.0, 0.0, 0.0]);
+        assert_eq!(aabb.ray_distance(&ray), Some(2.0));
+        assert_eq!(aabb.translated(v![0.0, 2.0, 0.0]).ray_distance(&ray), None);
+        // Behind the ray.
+        assert_eq!(aabb.translated(v![-4.0, 0.0, 0.0]).ray_distance(&ray), None);
+
+        let inside = Ray::new(v![2.5, 0.5, 0.5], v![1.0, -1.0, 0.0]);
+        assert_eq!(aabb.ray_distance(&inside), Some(0.0));
+    }
+
+    #[test]
+    fn collides_with_solid_blocks() {
+        let mut world = World::new();
+        world.insert_chunk(v![0, 0, 0], Default::default());
+        world.set_block(2, 0, 0, Block::Stone);
+
+        let geometry = Translated {
+            collider: &world,
+            offset: v![0.0, -2.0, 0.0],
+        };
+        let bounds = Aabb::new(v![0.2, -1.8, 0.2], v![0.8, -1.2, 0.8]);
+        let sweep = sweep(&bounds, v![3.0, 0.0, 0.0], &geometry);
+        assert!(sweep.motion.approx_eq(&v![1.2, 0.0, 0.0], 1e-6));
+
+        // Above the block.
+        let sweep = super::sweep(
+            &bounds.translated(v![0.0, 1.0, 0.0]),
+            v![3.0, 0.0, 0.0],
+            &geometry,
+        );
+        assert_eq!(sweep.blocked, [false; 3]);
+    }
+}
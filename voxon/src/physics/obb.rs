@@ -0,0 +1,193 @@
+use lina::{v, vector::Vector};
+use quaternion::Quaternion;
+
+use crate::world::Ray;
+
+/// An oriented bounding box, a box rotated around its center.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Obb {
+    pub center: Vector<f32, 3>,
+    /// The unit vectors along the edges of the box, in world space.
+    pub axes: [Vector<f32, 3>; 3],
+    /// Half the size of the box along each of its axes.
+    pub half_extents: Vector<f32, 3>,
+}
+
+/// Which features of two boxes touch along the axis they overlap the least.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Feature {
+    /// A face of the first box, along its axis.
+    FaceA(usize),
+    FaceB(usize),
+    /// An edge of each, along their axes.
+    Edges(usize, usize),
+}
+
+/// How deep two boxes overlap, see [Obb::penetration].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Penetration {
+    pub depth: f32,
+    /// The axis of the least overlap, pointing from the first box towards
+    /// the second.
+    pub normal: Vector<f32, 3>,
+    pub feature: Feature,
+}
+
+impl Obb {
+    pub fn new(
+        center: Vector<f32, 3>,
+        half_extents: Vector<f32, 3>,
+        rotation: Quaternion<f32>,
+    ) -> Self {
+        Self {
+            center,
+            axes: [v![1.0, 0.0, 0.0], v![0.0, 1.0, 0.0], v![0.0, 0.0, 1.0]]
+                .map(|axis| rotation.rotate_vector_unit(axis)),
+            half_extents,
+        }
+    }
+
+    /// How far the box reaches from its center along `direction`.
+    pub fn reach(&self, direction: Vector<f32, 3>) -> f32 {
+        (0..3)
+            .map(|axis| (self.axes[axis] * direction).abs() * self.half_extents[axis])
+            .sum()
+    }
+
+    /// The corner, or the middle of the edge along `skip`, furthest along `direction`.
+    pub fn support(&self, direction: Vector<f32, 3>, skip: Option<usize>) -> Vector<f32, 3> {
+        let mut support = self.center;
+        for axis in (0..3).filter(|axis| Some(*axis) != skip) {
+            let sign = (self.axes[axis] * direction).signum();
+            support += self.axes[axis] * (sign * self.half_extents[axis]);
+        }
+        support
+    }
+
+    /// The eight corners, corner `i` on the positive side of the axes where
+    /// the corresponding bit of `i` is set.
+    pub fn corners(&self) -> impl Iterator<Item = Vector<f32, 3>> + '_ {
+        (0..8).map(|corner: usize| {
+            let mut point = self.center;
+            for axis in 0..3 {
+                let sign = if corner & (1 << axis) == 0 { -1.0 } else { 1.0 };
+                point += self.axes[axis] * (sign * self.half_extents[axis]);
+            }
+            point
+        })
+    }
+
+    /// The separating axis test over the face normals and the cross
+    /// products of the edges, `None` if the boxes don't overlap.
+    ///
+    /// Otherwise the axis of the least overlap, which pushes the boxes
+    /// apart the shortest way. Faces win ties with edges.
+    pub fn penetration(&self, other: &Obb) -> Option<Penetration> {
+        let offset = other.center - self.center;
+
+        let faces = (0..3)
+            .map(|axis| (self.axes[axis], Feature::FaceA(axis)))
+            .chain((0..3).map(|axis| (other.axes[axis], Feature::FaceB(axis))));
+        let edges = (0..3)
+            .flat_map(|i| (0..3).map(move |j| (i, j)))
+            .filter_map(|(i, j)| {
+                // Parallel edges are covered by the faces.
+                let axis = self.axes[i].cross(other.axes[j]);
+                (axis.length() > 1e-4).then(|| (axis.normalized(), Feature::Edges(i, j)))
+            });
+
+        let mut best: Option<(f32, Penetration)> = None;
+        for (axis, feature) in faces.chain(edges) {
+            let distance = offset * axis;
+            let depth = self.reach(axis) + other.reach(axis) - distance.abs();
+            if depth < 0.0 {
+                return None;
+            }
+            // Faces win ties, their contacts are steadier.
+            let score = match feature {
+                Feature::Edges(..) => depth + 1e-3,
+                _ => depth,
+            };
+            if best.is_none_or(|(best_score, _)| score < best_score) {
+                let normal = if distance < 0.0 { -axis } else { axis };
+                best = Some((
+                    score,
+                    Penetration {
+                        depth,
+                        normal,
+                        feature,
+                    },
+                ));
+            }
+        }
+        best.map(|(_, penetration)| penetration)
+    }
+
+    /// How far along `ray` it enters the box, 0 if the ray starts inside.
+    pub fn ray_distance(&self, ray: &Ray) -> Option<f32> {
+        // The slabs between the faces, in the box's own space.
+        let offset = self.center - ray.origin;
+        let (mut enter, mut exit) = (0.0_f32, f32::INFINITY);
+        for axis in 0..3 {
+            let along = self.axes[axis] * offset;
+            // Parallel rays give infinite distances, which work out as well.
+            let inverse = 1.0 / (self.axes[axis] * ray.direction);
+            let near = (along - self.half_extents[axis]) * inverse;
+            let far = (along + self.half_extents[axis]) * inverse;
+            enter = enter.max(near.min(far));
+            exit = exit.min(near.max(far));
+        }
+        (enter <= exit).then_some(enter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::PI;
+
+    use lina::angle::Rad;
+
+    use super::*;
+
+    fn turned(center: Vector<f32, 3>, angle: f32) -> Obb {
+        let rotation = Quaternion::<f32>::new_unit(Rad(angle), v![0.0, 1.0, 0.0]);
+        Obb::new(center, v![1.0, 0.5, 0.25], rotation)
+    }
+
+    #[test]
+    fn rotated_boxes_overlap_only_where_they_reach() {
+        let a = turned(v![0.0, 0.0, 0.0], 0.0);
+        // Side by side the long sides almost touch.
+        assert!(a.penetration(&turned(v![0.0, 0.0, 0.6], 0.0)).is_none());
+        // Turned by 90°, the long side of the second reaches over.
+        let across = a.penetration(&turned(v![0.0, 0.0, 0.6], PI / 2.0)).unwrap();
+        assert!((across.depth - 0.65).abs() < 1e-5);
+        assert!(across.normal.approx_eq(&v![0.0, 0.0, 1.0], 1e-5));
+
+        // Diamonds in a row along X touch face to face, on a diagonal.
+        let rotation = Quaternion::<f32>::new_unit(Rad(PI / 4.0), v![0.0, 0.0, 1.0]);
+        let diamond = |x: f32| Obb::new(v![x, 0.0, 0.0], v![1.0, 1.0, 1.0], rotation);
+        assert!(diamond(0.0).penetration(&diamond(2.9)).is_none());
+        let diagonal = diamond(0.0).penetration(&diamond(2.7)).unwrap();
+        assert!((diagonal.depth - (2.0 - 2.7 / 2.0f32.sqrt())).abs() < 1e-5);
+        assert!(matches!(diagonal.feature, Feature::FaceA(_)));
+    }
+
+    #[test]
+    fn rays_hit_rotated_boxes() {
+        let obb = turned(v![0.0, 0.0, 0.0], PI / 2.0);
+        // Turned, the long side is along Z.
+        let along_z = Ray::new(v![0.0, 0.0, 5.0], v![0.0, 0.0, -1.0]);
+        assert!((obb.ray_distance(&along_z).unwrap() - 4.0).abs() < 1e-5);
+        let along_x = Ray::new(v![5.0, 0.0, 0.0], v![-1.0, 0.0, 0.0]);
+        assert!((obb.ray_distance(&along_x).unwrap() - 4.75).abs() < 1e-5);
+
+        // Past the turned box, but not past its bounds without rotation.
+        let past = Ray::new(v![0.6, 0.0, 5.0], v![0.0, 0.0, -1.0]);
+        assert_eq!(obb.ray_distance(&past), None);
+        let away = Ray::new(v![0.0, 0.0, 5.0], v![0.0, 0.0, 1.0]);
+        assert_eq!(obb.ray_distance(&away), None);
+        let inside = Ray::new(v![0.1, 0.2, 0.3], v![1.0, 1.0, 0.0]);
+        assert_eq!(obb.ray_distance(&inside), Some(0.0));
+    }
+}
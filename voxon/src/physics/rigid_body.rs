@@ -0,0 +1,767 @@
+use lina::{v, vector::Vector};
+use quaternion::Quaternion;
+
+use crate::components::Transform;
+
+use super::{Aabb, Collide, Feature, Obb, Penetration};
+
+/// Collisions slower than this in m/s don't bounce, so resting bodies settle.
+const BOUNCE_THRESHOLD: f32 = 1.0;
+
+/// Penetration which is left alone, so resting contacts don't jitter.
+const SLOP: f32 = 0.005;
+
+/// The part of the remaining penetration pushed apart every step.
+const CORRECTION: f32 = 0.4;
+
+/// The collision shape of a [RigidBody], centered on its center of mass and
+/// rotated with it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Shape {
+    Sphere { radius: f32 },
+    Cuboid { half_extents: Vector<f32, 3> },
+}
+
+impl Shape {
+    /// The diagonal of the inertia tensor of the solid shape in body space,
+    /// the tensor of both shapes is diagonal there.
+    fn inertia(&self, mass: f32) -> Vector<f32, 3> {
+        match *self {
+            Shape::Sphere { radius } => v![1.0, 1.0, 1.0] * (0.4 * mass * radius * radius),
+            Shape::Cuboid { half_extents } => {
+                let [x, y, z] = [0, 1, 2].map(|axis| half_extents[axis] * half_extents[axis]);
+                v![y + z, x + z, x + y] * (mass / 3.0)
+            }
+        }
+    }
+
+    /// The world space bounds of the shape placed at `transform`.
+    fn bounds(&self, transform: &Transform<f32>) -> Aabb {
+        let extents = match *self {
+            Shape::Sphere { radius } => v![radius, radius, radius],
+            Shape::Cuboid { half_extents } => {
+                let axes = axes(transform.rotation);
+                Vector::from_array(std::array::from_fn(|component| {
+                    (0..3)
+                        .map(|axis| axes[axis][component].abs() * half_extents[axis])
+                        .sum()
+                }))
+            }
+        };
+        Aabb::new(
+            transform.translation - extents,
+            transform.translation + extents,
+        )
+    }
+}
+
+/// A body moved by forces and collisions, placed by the [Transform] of
+/// its entity. The translation is its center of mass, the scale is ignored.
+///
+/// Forces and impulses are collected until the next [Dynamics::step].
+#[derive(Debug, Clone, PartialEq)]
+pub struct RigidBody {
+    pub shape: Shape,
+    inverse_mass: f32,
+    inverse_inertia: Vector<f32, 3>,
+    pub velocity: Vector<f32, 3>,
+    /// In radians per second around the axes of the world.
+    pub angular_velocity: Vector<f32, 3>,
+    /// How much of the speed is kept bouncing off something, from 0 to 1.
+    pub restitution: f32,
+    /// The friction coefficient, how hard sliding along something is.
+    pub friction: f32,
+    force: Vector<f32, 3>,
+    torque: Vector<f32, 3>,
+    impulse: Vector<f32, 3>,
+    angular_impulse: Vector<f32, 3>,
+}
+
+impl RigidBody {
+    /// A resting body of `mass` kilograms, evenly distributed in `shape`.
+    ///
+    /// # Panics
+    ///
+    /// If `mass` isn't positive.
+    pub fn new(shape: Shape, mass: f32) -> Self {
+        assert!(mass > 0.0, "a rigid body needs a positive mass");
+        let zero = v![0.0, 0.0, 0.0];
+        let inertia = shape.inertia(mass);
+        Self {
+            shape,
+            inverse_mass: 1.0 / mass,
+            inverse_inertia: Vector::from_array(std::array::from_fn(|axis| 1.0 / inertia[axis])),
+            velocity: zero,
+            angular_velocity: zero,
+            restitution: 0.3,
+            friction: 0.5,
+            force: zero,
+            torque: zero,
+            impulse: zero,
+            angular_impulse: zero,
+        }
+    }
+
+    pub fn mass(&self) -> f32 {
+        1.0 / self.inverse_mass
+    }
+
+    /// Push with `force` newtons during the next step, at `offset` from the
+    /// center of mass in world space. Off center forces spin the body.
+    pub fn apply_force(&mut self, force: Vector<f32, 3>, offset: Vector<f32, 3>) {
+        self.force += force;
+        self.torque += offset.cross(force);
+    }
+
+    /// Change the momentum by `impulse` at once, at `offset` from the center
+    /// of mass in world space.
+    pub fn apply_impulse(&mut self, impulse: Vector<f32, 3>, offset: Vector<f32, 3>) {
+        self.impulse += impulse;
+        self.angular_impulse += offset.cross(impulse);
+    }
+
+    /// The inverse inertia tensor in world space applied to `vector`,
+    /// rotating into body space where the tensor is diagonal and back.
+    fn inverse_inertia_times(
+        &self,
+        rotation: Quaternion<f32>,
+        vector: Vector<f32, 3>,
+    ) -> Vector<f32, 3> {
+        let local = rotation.conjugate().rotate_vector_unit(vector);
+        let scaled = Vector::from_array(std::array::from_fn(|axis| {
+            self.inverse_inertia[axis] * local[axis]
+        }));
+        rotation.rotate_vector_unit(scaled)
+    }
+}
+
+/// The unit vectors along the axes of a body rotated by `rotation`.
+fn axes(rotation: Quaternion<f32>) -> [Vector<f32, 3>; 3] {
+    [v![1.0, 0.0, 0.0], v![0.0, 1.0, 0.0], v![0.0, 0.0, 1.0]]
+        .map(|axis| rotation.rotate_vector_unit(axis))
+}
+
+/// Where two shapes touch.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct ContactPoint {
+    point: Vector<f32, 3>,
+    /// Pointing from the first shape towards the second one.
+    normal: Vector<f32, 3>,
+    depth: f32,
+}
+
+/// A contact of the body `a` with the body `b`, or with static geometry.
+#[derive(Debug, Clone, Copy)]
+struct Contact {
+    a: usize,
+    b: Option<usize>,
+    contact: ContactPoint,
+    restitution: f32,
+    friction: f32,
+    // The speed along the normal the solver aims for, the bounce.
+    target_speed: f32,
+    // The impulse along the normal applied so far this step.
+    normal_impulse: f32,
+    // Friction acts along two directions across the normal.
+    tangents: [Vector<f32, 3>; 2],
+    tangent_impulses: [f32; 2],
+}
+
+impl Contact {
+    fn new(
+        a: usize,
+        b: Option<usize>,
+        contact: ContactPoint,
+        restitution: f32,
+        friction: f32,
+    ) -> Self {
+        let normal = contact.normal;
+        let across = if normal[0].abs() < 0.9 {
+            v![1.0, 0.0, 0.0]
+        } else {
+            v![0.0, 1.0, 0.0]
+        };
+        let tangent = normal.cross(across).normalized();
+        Self {
+            a,
+            b,
+            contact,
+            restitution,
+            friction,
+            target_speed: 0.0,
+            normal_impulse: 0.0,
+            tangents: [tangent, normal.cross(tangent)],
+            tangent_impulses: [0.0; 2],
+        }
+    }
+}
+
+/// Simulates [RigidBody]s in steps of a fixed length.
+///
+/// Every step applies the forces and gravity, resolves the collisions of the
+/// bodies among each other and with the static geometry with impulses, then
+/// moves them. The static geometry never moves, it's as heavy as it gets.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Dynamics {
+    /// The acceleration of every body in m/s².
+    pub gravity: Vector<f32, 3>,
+    /// How often the contacts are solved per step, more iterations keep
+    /// stacked bodies steadier.
+    pub iterations: usize,
+}
+
+impl Default for Dynamics {
+    fn default() -> Self {
+        Self {
+            gravity: v![0.0, -9.81, 0.0],
+            iterations: 8,
+        }
+    }
+}
+
+impl Dynamics {
+    /// Advance `bodies` by `delta` seconds, colliding with `geometry`.
+    pub fn step(
+        &self,
+        bodies: &mut [(Transform<f32>, RigidBody)],
+        geometry: &(impl Collide + ?Sized),
+        delta: f32,
+    ) {
+        let zero = v![0.0, 0.0, 0.0];
+        for (transform, body) in bodies.iter_mut() {
+            body.apply_force(self.gravity * body.mass(), zero);
+            let angular_impulse = body.torque * delta + body.angular_impulse;
+            body.velocity += (body.force * delta + body.impulse) * body.inverse_mass;
+            body.angular_velocity +=
+                body.inverse_inertia_times(transform.rotation, angular_impulse);
+            body.force = zero;
+            body.torque = zero;
+            body.impulse = zero;
+            body.angular_impulse = zero;
+        }
+
+        let mut contacts = find_contacts(bodies, geometry);
+        for _ in 0..self.iterations {
+            for contact in &mut contacts {
+                solve(bodies, contact);
+            }
+        }
+        for contact in &contacts {
+            separate(bodies, contact);
+        }
+
+        for (transform, body) in bodies.iter_mut() {
+            transform.translation += body.velocity * delta;
+            transform.rotation = transform.rotation.integrate(body.angular_velocity, delta);
+        }
+    }
+}
+
+fn find_contacts(
+    bodies: &[(Transform<f32>, RigidBody)],
+    geometry: &(impl Collide + ?Sized),
+) -> Vec<Contact> {
+    let mut contacts = Vec::new();
+    let mut colliders = Vec::new();
+    for (a, (transform, body)) in bodies.iter().enumerate() {
+        for (b, (other_transform, other)) in bodies.iter().enumerate().skip(a + 1) {
+            for contact in shape_contacts(&body.shape, transform, &other.shape, other_transform) {
+                contacts.push(Contact::new(
+                    a,
+                    Some(b),
+                    contact,
+                    body.restitution.max(other.restitution),
+                    (body.friction * other.friction).sqrt(),
+                ));
+            }
+        }
+
+        colliders.clear();
+        geometry.colliders(&body.shape.bounds(transform), &mut colliders);
+        for collider in &colliders {
+            let half_extents = (collider.max - collider.min) * 0.5;
+            let center = Transform::from_translation(collider.min + half_extents);
+            for contact in shape_contacts(
+                &body.shape,
+                transform,
+                &Shape::Cuboid { half_extents },
+                &center,
+            ) {
+                contacts.push(Contact::new(
+                    a,
+                    None,
+                    contact,
+                    body.restitution,
+                    body.friction,
+                ));
+            }
+        }
+    }
+
+    // Only approaching contacts fast enough bounce, from the speed before solving.
+    for contact in &mut contacts {
+        let speed = relative_velocity(bodies, contact) * contact.contact.normal;
+        if speed < -BOUNCE_THRESHOLD {
+            contact.target_speed = -contact.restitution * speed;
+        }
+    }
+    contacts
+}
+
+/// The velocity of the point of contact on `b` relative to the one on `a`.
+fn relative_velocity(bodies: &[(Transform<f32>, RigidBody)], contact: &Contact) -> Vector<f32, 3> {
+    let point_velocity = |index: Option<usize>| {
+        index.map_or(v![0.0, 0.0, 0.0], |index| {
+            let (transform, body) = &bodies[index];
+            let offset = contact.contact.point - transform.translation;
+            body.velocity + body.angular_velocity.cross(offset)
+        })
+    };
+    point_velocity(contact.b) - point_velocity(Some(contact.a))
+}
+
+/// How much `impulse` pushing the bodies apart along `direction` changes
+/// their relative speed along it, the inverse of the effective mass.
+fn inverse_effective_mass(
+    bodies: &[(Transform<f32>, RigidBody)],
+    contact: &Contact,
+    direction: Vector<f32, 3>,
+) -> f32 {
+    [Some(contact.a), contact.b]
+        .into_iter()
+        .flatten()
+        .map(|index| {
+            let (transform, body) = &bodies[index];
+            let offset = contact.contact.point - transform.translation;
+            let turn = body.inverse_inertia_times(transform.rotation, offset.cross(direction));
+            body.inverse_mass + direction * turn.cross(offset)
+        })
+        .sum()
+}
+
+/// Apply `impulse` to `b` at the point of contact and the opposite to `a`.
+fn apply_impulse(
+    bodies: &mut [(Transform<f32>, RigidBody)],
+    contact: &Contact,
+    impulse: Vector<f32, 3>,
+) {
+    for (index, sign) in [(Some(contact.a), -1.0), (contact.b, 1.0)] {
+        let Some((transform, body)) = index.map(|index| &mut bodies[index]) else {
+            continue;
+        };
+        let offset = contact.contact.point - transform.translation;
+        body.velocity += impulse * (sign * body.inverse_mass);
+        body.angular_velocity +=
+            body.inverse_inertia_times(transform.rotation, offset.cross(impulse)) * sign;
+    }
+}
+
+fn solve(bodies: &mut [(Transform<f32>, RigidBody)], contact: &mut Contact) {
+    let normal = contact.contact.normal;
+    let speed = relative_velocity(bodies, contact) * normal;
+    let impulse = (contact.target_speed - speed) / inverse_effective_mass(bodies, contact, normal);
+    // The total only ever pushes, earlier iterations which pushed too hard
+    // are taken back.
+    let total = (contact.normal_impulse + impulse).max(0.0);
+    apply_impulse(bodies, contact, normal * (total - contact.normal_impulse));
+    contact.normal_impulse = total;
+
+    // Friction stops sliding, up to the friction coefficient times the
+    // impulse pressing the bodies together.
+    let limit = contact.friction * contact.normal_impulse;
+    for (index, tangent) in contact.tangents.into_iter().enumerate() {
+        let speed = relative_velocity(bodies, contact) * tangent;
+        let impulse = -speed / inverse_effective_mass(bodies, contact, tangent);
+        let applied = contact.tangent_impulses[index];
+        let total = (applied + impulse).clamp(-limit, limit);
+        apply_impulse(bodies, contact, tangent * (total - applied));
+        contact.tangent_impulses[index] = total;
+    }
+}
+
+/// Push the bodies out of each other, the lighter one further.
+fn separate(bodies: &mut [(Transform<f32>, RigidBody)], contact: &Contact) {
+    let inverse_masses = [Some(contact.a), contact.b]
+        .map(|index| index.map_or(0.0, |index| bodies[index].1.inverse_mass));
+    let total = inverse_masses[0] + inverse_masses[1];
+    let correction = (contact.contact.depth - SLOP).max(0.0) * CORRECTION / total;
+    let push = contact.contact.normal * correction;
+    bodies[contact.a].0.translation -= push * inverse_masses[0];
+    if let Some(b) = contact.b {
+        bodies[b].0.translation += push * inverse_masses[1];
+    }
+}
+
+fn shape_contacts(
+    a: &Shape,
+    a_transform: &Transform<f32>,
+    b: &Shape,
+    b_transform: &Transform<f32>,
+) -> Vec<ContactPoint> {
+    let flip = |contact: ContactPoint| ContactPoint {
+        normal: -contact.normal,
+        ..contact
+    };
+    match (*a, *b) {
+        (Shape::Sphere { radius }, Shape::Sphere { radius: other }) => sphere_sphere(
+            a_transform.translation,
+            radius,
+            b_transform.translation,
+            other,
+        )
+        .into_iter()
+        .collect(),
+        (Shape::Sphere { radius }, Shape::Cuboid { half_extents }) => {
+            sphere_cuboid(a_transform.translation, radius, b_transform, half_extents)
+                .into_iter()
+                .collect()
+        }
+        (Shape::Cuboid { half_extents }, Shape::Sphere { radius }) => {
+            sphere_cuboid(b_transform.translation, radius, a_transform, half_extents)
+                .map(flip)
+                .into_iter()
+                .collect()
+        }
+        (
+            Shape::Cuboid { half_extents },
+            Shape::Cuboid {
+                half_extents: other,
+            },
+        ) => cuboid_cuboid(a_transform, half_extents, b_transform, other),
+    }
+}
+
+fn sphere_sphere(
+    a: Vector<f32, 3>,
+    a_radius: f32,
+    b: Vector<f32, 3>,
+    b_radius: f32,
+) -> Option<ContactPoint> {
+    let offset = b - a;
+    let distance = offset.length();
+    let depth = a_radius + b_radius - distance;
+    if depth < 0.0 {
+        return None;
+    }
+    // Any direction separates spheres at the same place.
+    let normal = if distance > 1e-6 {
+        offset / distance
+    } else {
+        v![0.0, 1.0, 0.0]
+    };
+    Some(ContactPoint {
+        point: a + normal * (a_radius - depth * 0.5),
+        normal,
+        depth,
+    })
+}
+
+fn sphere_cuboid(
+    center: Vector<f32, 3>,
+    radius: f32,
+    cuboid: &Transform<f32>,
+    half_extents: Vector<f32, 3>,
+) -> Option<ContactPoint> {
+    let rotation = cuboid.rotation;
+    let local = rotation
+        .conjugate()
+        .rotate_vector_unit(center - cuboid.translation);
+    let closest = local.clamp(-half_extents, half_extents);
+    let outside = local - closest;
+    let distance = outside.length();
+
+    let (outwards, depth, point) = if distance > 1e-6 {
+        if distance > radius {
+            return None;
+        }
+        (outside / distance, radius - distance, closest)
+    } else {
+        // The center is inside, it leaves through the nearest face.
+        let axis = (0..3)
+            .min_by(|a, b| {
+                let gap = |axis: usize| half_extents[axis] - local[axis].abs();
+                gap(*a).total_cmp(&gap(*b))
+            })
+            .expect("there are three axes");
+        let mut outwards = v![0.0, 0.0, 0.0];
+        outwards[axis] = local[axis].signum();
+        (
+            outwards,
+            radius + half_extents[axis] - local[axis].abs(),
+            local,
+        )
+    };
+    Some(ContactPoint {
+        point: cuboid.translation + rotation.rotate_vector_unit(point),
+        normal: -rotation.rotate_vector_unit(outwards),
+        depth,
+    })
+}
+
+/// The contacts of two cuboids, the axis of their least overlap is the
+/// contact normal, see [Obb::penetration].
+fn cuboid_cuboid(
+    a_transform: &Transform<f32>,
+    a_half_extents: Vector<f32, 3>,
+    b_transform: &Transform<f32>,
+    b_half_extents: Vector<f32, 3>,
+) -> Vec<ContactPoint> {
+    let a = Obb::new(
+        a_transform.translation,
+        a_half_extents,
+        a_transform.rotation,
+    );
+    let b = Obb::new(
+        b_transform.translation,
+        b_half_extents,
+        b_transform.rotation,
+    );
+    let Some(Penetration {
+        depth,
+        normal,
+        feature,
+    }) = a.penetration(&b)
+    else {
+        return Vec::new();
+    };
+
+    match feature {
+        Feature::FaceA(axis) => face_contacts(&a, axis, &b, normal, depth),
+        Feature::FaceB(axis) => face_contacts(&b, axis, &a, -normal, depth)
+            .into_iter()
+            .map(|contact| ContactPoint { normal, ..contact })
+            .collect(),
+        Feature::Edges(i, j) => {
+            let on_a = a.support(normal, Some(i));
+            let on_b = b.support(-normal, Some(j));
+            let (s, t) = closest_on_edges(
+                on_a,
+                a.axes[i],
+                a.half_extents[i],
+                on_b,
+                b.axes[j],
+                b.half_extents[j],
+            );
+            let point = (on_a + a.axes[i] * s + on_b + b.axes[j] * t) * 0.5;
+            vec![ContactPoint {
+                point,
+                normal,
+                depth,
+            }]
+        }
+    }
+}
+
+/// The corners of `incident` below the face of `reference` along `axis`,
+/// `normal` pointing out of that face towards `incident`.
+fn face_contacts(
+    reference: &Obb,
+    axis: usize,
+    incident: &Obb,
+    normal: Vector<f32, 3>,
+    depth: f32,
+) -> Vec<ContactPoint> {
+    let face = reference.center * normal + reference.half_extents[axis];
+    let within_face = |point: Vector<f32, 3>| {
+        (0..3).filter(|other| *other != axis).all(|other| {
+            ((point - reference.center) * reference.axes[other]).abs()
+                <= reference.half_extents[other] + 1e-3
+        })
+    };
+
+    let contacts = incident
+        .corners()
+        .filter_map(|corner| {
+            let below = face - corner * normal;
+            (below > 0.0 && within_face(corner)).then(|| ContactPoint {
+                point: corner + normal * (below * 0.5),
+                normal,
+                depth: below,
+            })
+        })
+        .collect::<Vec<_>>();
+    if !contacts.is_empty() {
+        return contacts;
+    }
+    // Only edges or corners outside of the face overlap, like a cuboid
+    // hanging over the edge of another.
+    vec![ContactPoint {
+        point: incident.support(-normal, None),
+        normal,
+        depth,
+    }]
+}
+
+/// The positions along two edges, given by their middles, directions and
+/// half lengths, where they come closest.
+fn closest_on_edges(
+    a: Vector<f32, 3>,
+    a_direction: Vector<f32, 3>,
+    a_half_length: f32,
+    b: Vector<f32, 3>,
+    b_direction: Vector<f32, 3>,
+    b_half_length: f32,
+) -> (f32, f32) {
+    let offset = a - b;
+    let cosine = a_direction * b_direction;
+    let (along_a, along_b) = (a_direction * offset, b_direction * offset);
+    let denominator = 1.0 - cosine * cosine;
+    if denominator < 1e-6 {
+        return (0.0, 0.0);
+    }
+    let s = ((cosine * along_b - along_a) / denominator).clamp(-a_half_length, a_half_length);
+    let t = ((along_b - cosine * along_a) / denominator).clamp(-b_half_length, b_half_length);
+    (s, t)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::PI;
+
+    use lina::angle::Rad;
+
+    use super::*;
+
+    const DELTA: f32 = 1.0 / 60.0;
+
+    fn floor() -> [Aabb; 1] {
+        [Aabb::new(v![-10.0, -1.0, -10.0], v![10.0, 0.0, 10.0])]
+    }
+
+    fn run(dynamics: &Dynamics, bodies: &mut [(Transform<f32>, RigidBody)], seconds: f32) {
+        for _ in 0..(seconds / DELTA).round() as usize {
+            dynamics.step(bodies, &floor()[..], DELTA);
+        }
+    }
+
+    fn weightless() -> Dynamics {
+        Dynamics {
+            gravity: v![0.0, 0.0, 0.0],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn inertia_of_the_shapes() {
+        let sphere = Shape::Sphere { radius: 2.0 };
+        assert_eq!(sphere.inertia(5.0), v![8.0, 8.0, 8.0]);
+        // A 2x4x6 cuboid.
+        let cuboid = Shape::Cuboid {
+            half_extents: v![1.0, 2.0, 3.0],
+        };
+        assert_eq!(cuboid.inertia(3.0), v![13.0, 10.0, 5.0]);
+    }
+
+    #[test]
+    fn falling_bodies_come_to_rest_on_the_ground() {
+        let sphere = RigidBody::new(Shape::Sphere { radius: 0.5 }, 1.0);
+        let cuboid = RigidBody::new(
+            Shape::Cuboid {
+                half_extents: v![0.5, 0.25, 0.5],
+            },
+            2.0,
+        );
+        let mut bodies = [
+            (Transform::from_translation(v![-3.0, 2.0, 0.0]), sphere),
+            (Transform::from_translation(v![3.0, 3.0, 0.0]), cuboid),
+        ];
+        run(&Dynamics::default(), &mut bodies, 3.0);
+
+        let [(sphere_at, sphere), (cuboid_at, cuboid)] = &bodies;
+        assert!((sphere_at.translation[1] - 0.5).abs() < 0.02);
+        assert!((cuboid_at.translation[1] - 0.25).abs() < 0.02);
+        assert!(sphere.velocity.length() < 0.05 && cuboid.velocity.length() < 0.05);
+        // Landing flat doesn't tip the cuboid over.
+        let up = cuboid_at.rotation.rotate_vector_unit(v![0.0, 1.0, 0.0]);
+        assert!(up.approx_eq(&v![0.0, 1.0, 0.0], 1e-2));
+    }
+
+    #[test]
+    fn elastic_collisions_swap_the_velocities() {
+        let mut ball = RigidBody::new(Shape::Sphere { radius: 0.5 }, 1.0);
+        ball.restitution = 1.0;
+        let mut moving = ball.clone();
+        moving.velocity = v![4.0, 0.0, 0.0];
+        let mut bodies = [
+            (Transform::from_translation(v![0.0, 5.0, 0.0]), moving),
+            (Transform::from_translation(v![1.02, 5.0, 0.0]), ball),
+        ];
+        run(&weightless(), &mut bodies, 0.1);
+
+        assert!(bodies[0].1.velocity.approx_eq(&v![0.0, 0.0, 0.0], 1e-4));
+        assert!(bodies[1].1.velocity.approx_eq(&v![4.0, 0.0, 0.0], 1e-4));
+    }
+
+    #[test]
+    fn off_center_impulses_spin_the_body() {
+        let mut body = RigidBody::new(
+            Shape::Cuboid {
+                half_extents: v![1.0, 1.0, 1.0],
+            },
+            3.0,
+        );
+        body.apply_impulse(v![0.0, 0.0, 6.0], v![1.0, 0.0, 0.0]);
+        let mut bodies = [(Transform::from_translation(v![0.0, 5.0, 0.0]), body)];
+        weightless().step(&mut bodies, &floor()[..], DELTA);
+
+        let body = &bodies[0].1;
+        assert!(body.velocity.approx_eq(&v![0.0, 0.0, 2.0], 1e-6));
+        // 6 Nms around -Y with a moment of inertia of 2.
+        assert!(body.angular_velocity.approx_eq(&v![0.0, -3.0, 0.0], 1e-5));
+    }
+
+    #[test]
+    fn spinning_turns_the_orientation() {
+        let mut body = RigidBody::new(Shape::Sphere { radius: 1.0 }, 1.0);
+        body.angular_velocity = v![0.0, PI / 2.0, 0.0];
+        let mut bodies = [(Transform::from_translation(v![0.0, 5.0, 0.0]), body)];
+        run(&weightless(), &mut bodies, 1.0);
+
+        let expected = Quaternion::<f32>::new_unit(Rad(PI / 2.0), v![0.0, 1.0, 0.0]);
+        let turned = bodies[0].0.rotation.rotate_vector_unit(v![1.0, 0.0, 0.0]);
+        assert!(turned.approx_eq(&expected.rotate_vector_unit(v![1.0, 0.0, 0.0]), 1e-4));
+    }
+
+    #[test]
+    fn cuboids_touch_face_to_face_on_four_corners() {
+        let half_extents = v![1.0, 1.0, 1.0];
+        let contacts = cuboid_cuboid(
+            &Transform::default(),
+            half_extents,
+            &Transform::from_translation(v![0.0, 1.9, 0.0]),
+            half_extents,
+        );
+        assert_eq!(contacts.len(), 4);
+        for contact in contacts {
+            assert!(contact.normal.approx_eq(&v![0.0, 1.0, 0.0], 1e-6));
+            assert!((contact.depth - 0.1).abs() < 1e-5);
+            assert!((contact.point[1] - 0.95).abs() < 1e-5);
+        }
+
+        let rotated = Transform {
+            translation: v![0.0, 2.3, 0.0],
+            rotation: Quaternion::<f32>::new_unit(Rad(PI / 4.0), v![0.0, 0.0, 1.0]),
+            ..Default::default()
+        };
+        // Standing on an edge, reaching down to 2.3 - √2.
+        let contacts = cuboid_cuboid(&Transform::default(), half_extents, &rotated, half_extents);
+        assert_eq!(contacts.len(), 2);
+        assert!((contacts[0].depth - (2.0f32.sqrt() - 1.3)).abs() < 1e-5);
+
+        let apart = Transform::from_translation(v![2.1, 0.0, 0.0]);
+        assert!(
+            cuboid_cuboid(&Transform::default(), half_extents, &apart, half_extents).is_empty()
+        );
+    }
+
+    #[test]
+    fn spheres_touch_cuboids() {
+        let cuboid = Transform::from_translation(v![0.0, -1.0, 0.0]);
+        let contact = sphere_cuboid(v![0.5, 0.4, 0.0], 0.5, &cuboid, v![1.0, 1.0, 1.0]).unwrap();
+        assert!(contact.normal.approx_eq(&v![0.0, -1.0, 0.0], 1e-6));
+        assert!((contact.depth - 0.1).abs() < 1e-6);
+        assert!(contact.point.approx_eq(&v![0.5, 0.0, 0.0], 1e-6));
+
+        assert!(sphere_cuboid(v![0.0, 0.6, 0.0], 0.5, &cuboid, v![1.0, 1.0, 1.0]).is_none());
+    }
+}
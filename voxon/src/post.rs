@@ -0,0 +1,471 @@
+//! Post-processing of the rendered frame.
+//!
+//! The scene is drawn into a [HDR_FORMAT] target, so lighting isn't clamped
+//! to the displayable range. A chain of full screen passes then turns it into
+//! the frame: the passes of [Stage::Hdr] on the HDR image, the tonemapping
+//! into the displayable range and the passes of [Stage::Ldr] on the result,
+//! the last of them writing the frame.
+//!
+//! The WGSL source of a pass is appended to `post.wgsl`, which provides the
+//! full screen triangle, the previous pass' output as `input_texture` with a
+//! linear `input_sampler`, both `@group(0)`, and the `VSOutput` with its `uv`.
+//! The pass implements the `fs_main` fragment entry point and declares its
+//! uniforms as `@group(1) @binding(0)`, if it has any.
+
+use std::borrow::Cow;
+
+use bytemuck::{Pod, Zeroable};
+use wgpu::{
+    BindGroup, BindGroupLayout, Buffer, BufferUsages, CommandEncoder, Device, Queue,
+    RenderPassTimestampWrites, RenderPipeline, Sampler, TextureFormat, TextureUsages, TextureView,
+};
+use winit::dpi::PhysicalSize;
+
+/// The format the scene is rendered in, before tonemapping.
+pub const HDR_FORMAT: TextureFormat = TextureFormat::Rgba16Float;
+
+/// The label of the built-in FXAA pass.
+pub const FXAA: &str = "fxaa";
+
+/// Where in the chain a post-processing pass runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    /// Before tonemapping, on the linear HDR image, e.g. for bloom.
+    Hdr,
+    /// After tonemapping, on the displayable image, e.g. for anti-aliasing.
+    Ldr,
+}
+
+/// How the HDR colors are mapped into the displayable range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Tonemapper {
+    /// Cut off above 1, as if there was no HDR.
+    Clamp,
+    /// `c / (1 + c)`, never reaches white.
+    Reinhard,
+    /// The filmic curve of the Academy Color Encoding System, fitted by
+    /// Krzysztof Narkowicz. Rolls off the highlights like film does.
+    #[default]
+    Aces,
+}
+
+impl Tonemapper {
+    pub const ALL: [Tonemapper; 3] = [Tonemapper::Clamp, Tonemapper::Reinhard, Tonemapper::Aces];
+}
+
+/// Settings of the tonemapping pass.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tonemapping {
+    pub tonemapper: Tonemapper,
+    /// Multiplies the colors before tonemapping, brightening or darkening
+    /// the frame.
+    pub exposure: f32,
+}
+
+impl Default for Tonemapping {
+    fn default() -> Self {
+        Self {
+            tonemapper: Tonemapper::default(),
+            exposure: 1.0,
+        }
+    }
+}
+
+/// The `Tonemapping` WGSL struct.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+struct TonemapUniforms {
+    exposure: f32,
+    tonemapper: u32,
+    // Whether the shader has to encode the colors in sRGB, as the output
+    // format doesn't.
+    encode_srgb: u32,
+    _padding: u32,
+}
+
+/// Describes a post-processing pass for [PostProcess::add_pass].
+pub struct PostPassDescriptor<'a> {
+    /// Identifies the pass, e.g. to [enable](PostProcess::set_enabled) it.
+    pub label: &'static str,
+    pub stage: Stage,
+    /// The WGSL source with the `fs_main` entry point, see the [module
+    /// docs](self) for what it can use.
+    pub source: &'a str,
+    /// The initial contents of the uniform buffer bound as `@group(1)`, `None`
+    /// if the pass has no uniforms.
+    pub uniforms: Option<&'a [u8]>,
+}
+
+/// A full screen pass of the [PostProcess] chain.
+struct PostPass {
+    label: &'static str,
+    pipeline: RenderPipeline,
+    uniforms: Option<(Buffer, BindGroup)>,
+    enabled: bool,
+}
+
+/// The chain of post-processing passes, from the HDR image of the scene to
+/// the frame.
+pub struct PostProcess {
+    output_format: TextureFormat,
+    // The previous pass' output, `@group(0)` of every pass.
+    input_layout: BindGroupLayout,
+    uniform_layout: BindGroupLayout,
+    sampler: Sampler,
+    hdr_passes: Vec<PostPass>,
+    tonemap: PostPass,
+    tonemapping: Tonemapping,
+    ldr_passes: Vec<PostPass>,
+}
+
+impl PostProcess {
+    /// Create the chain writing frames of `output_format`, with the
+    /// tonemapping and a disabled FXAA pass.
+    pub fn new(device: &Device, output_format: TextureFormat) -> Self {
+        let input_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("post_input_bind_group_layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+        let uniform_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("post_uniform_bind_group_layout"),
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+        });
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("post_sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        let mut post = Self {
+            output_format,
+            tonemap: create_pass(
+                device,
+                &input_layout,
+                &uniform_layout,
+                output_format,
+                &PostPassDescriptor {
+                    label: "tonemap",
+                    stage: Stage::Ldr,
+                    source: include_str!("tonemap.wgsl"),
+                    uniforms: Some(bytemuck::bytes_of(&TonemapUniforms::zeroed())),
+                },
+            ),
+            input_layout,
+            uniform_layout,
+            sampler,
+            hdr_passes: Vec::new(),
+            tonemapping: Tonemapping::default(),
+            ldr_passes: Vec::new(),
+        };
+        // MSAA smooths the edges of the geometry already, FXAA is for
+        // adapters without it.
+        post.add_pass(
+            device,
+            &PostPassDescriptor {
+                label: FXAA,
+                stage: Stage::Ldr,
+                source: include_str!("fxaa.wgsl"),
+                uniforms: None,
+            },
+        );
+        post.set_enabled(FXAA, false);
+        post
+    }
+
+    /// Append a pass to the passes of its stage.
+    pub fn add_pass(&mut self, device: &Device, descriptor: &PostPassDescriptor) {
+        let format = match descriptor.stage {
+            Stage::Hdr => HDR_FORMAT,
+            Stage::Ldr => self.output_format,
+        };
+        let pass = create_pass(
+            device,
+            &self.input_layout,
+            &self.uniform_layout,
+            format,
+            descriptor,
+        );
+        match descriptor.stage {
+            Stage::Hdr => self.hdr_passes.push(pass),
+            Stage::Ldr => self.ldr_passes.push(pass),
+        }
+    }
+
+    fn pass_mut(&mut self, label: &str) -> Option<&mut PostPass> {
+        self.hdr_passes
+            .iter_mut()
+            .chain(&mut self.ldr_passes)
+            .find(|pass| pass.label == label)
+    }
+
+    /// Whether the pass added with `label` runs, `None` if there is none.
+    pub fn enabled(&self, label: &str) -> Option<bool> {
+        self.hdr_passes
+            .iter()
+            .chain(&self.ldr_passes)
+            .find(|pass| pass.label == label)
+            .map(|pass| pass.enabled)
+    }
+
+    /// Skip the pass added with `label` while disabled.
+    ///
+    /// Panics if there is no such pass.
+    pub fn set_enabled(&mut self, label: &str, enabled: bool) {
+        self.pass_mut(label)
+            .unwrap_or_else(|| panic!("there is no post-processing pass {label}"))
+            .enabled = enabled;
+    }
+
+    pub fn tonemapping(&self) -> Tonemapping {
+        self.tonemapping
+    }
+
+    pub fn set_tonemapping(&mut self, tonemapping: Tonemapping) {
+        self.tonemapping = tonemapping;
+    }
+
+    /// Create the intermediate images of the chain for targets of `size`.
+    pub fn create_targets(&self, device: &Device, size: PhysicalSize<u32>) -> PostTargets {
+        let create = |format| {
+            let texture = device.create_texture(&wgpu::TextureDescriptor {
+                label: Some("post_texture"),
+                size: wgpu::Extent3d {
+                    width: size.width,
+                    height: size.height,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format,
+                usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING,
+                view_formats: &[],
+            });
+            let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+            let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("post_input_bind_group"),
+                layout: &self.input_layout,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: wgpu::BindingResource::TextureView(&view),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: wgpu::BindingResource::Sampler(&self.sampler),
+                    },
+                ],
+            });
+            (view, bind_group)
+        };
+        PostTargets {
+            hdr: [create(HDR_FORMAT), create(HDR_FORMAT)],
+            ldr: [create(self.output_format), create(self.output_format)],
+        }
+    }
+
+    /// Run the enabled passes on the HDR image of `targets`, writing the
+    /// result into `frame`. Returns the number of passes, each drawing a
+    /// single triangle.
+    ///
+    /// The passes are timed together with `timestamp_writes`, given the
+    /// index of the first and the last pass.
+    pub fn run<'a>(
+        &self,
+        encoder: &mut CommandEncoder,
+        queue: &Queue,
+        targets: &PostTargets,
+        frame: &TextureView,
+        timestamp_writes: impl Fn(bool, bool) -> Option<RenderPassTimestampWrites<'a>>,
+    ) -> usize {
+        let tonemapping = self.tonemapping;
+        let (tonemap_uniforms, _) = self.tonemap.uniforms.as_ref().expect("tonemap uniforms");
+        queue.write_buffer(
+            tonemap_uniforms,
+            0,
+            bytemuck::bytes_of(&TonemapUniforms {
+                exposure: tonemapping.exposure,
+                tonemapper: tonemapping.tonemapper as u32,
+                encode_srgb: !self.output_format.is_srgb() as u32,
+                _padding: 0,
+            }),
+        );
+
+        let hdr_passes = self.hdr_passes.iter().filter(|pass| pass.enabled);
+        let ldr_passes = self.ldr_passes.iter().filter(|pass| pass.enabled);
+
+        // Every pass reads the output of the previous one, alternating
+        // between the two images of its stage. The last one writes the frame.
+        let mut steps = Vec::new();
+        let mut input = &targets.hdr[0];
+        for (index, pass) in hdr_passes.enumerate() {
+            let output = &targets.hdr[(index + 1) % 2];
+            steps.push((pass, &input.1, &output.0));
+            input = output;
+        }
+        steps.push((&self.tonemap, &input.1, &targets.ldr[0].0));
+        input = &targets.ldr[0];
+        for (index, pass) in ldr_passes.enumerate() {
+            let output = &targets.ldr[(index + 1) % 2];
+            steps.push((pass, &input.1, &output.0));
+            input = output;
+        }
+        if let Some((_, _, output)) = steps.last_mut() {
+            *output = frame;
+        }
+
+        let count = steps.len();
+        for (index, (pass, input, output)) in steps.into_iter().enumerate() {
+            let _span = tracing::debug_span!("post_pass", pass = pass.label).entered();
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some(pass.label),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: output,
+                    depth_slice: None,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        // Every pixel is drawn over.
+                        load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: timestamp_writes(index == 0, index + 1 == count),
+                occlusion_query_set: None,
+                multiview_mask: None,
+            });
+            render_pass.set_pipeline(&pass.pipeline);
+            render_pass.set_bind_group(0, input, &[]);
+            if let Some((_, uniforms)) = &pass.uniforms {
+                render_pass.set_bind_group(1, uniforms, &[]);
+            }
+            render_pass.draw(0..3, 0..1);
+        }
+        count
+    }
+}
+
+/// The size dependent images of a [PostProcess] chain, kept by the
+/// [RenderTarget](crate::view::RenderTarget).
+pub struct PostTargets {
+    // The scene is rendered into the first, the passes before tonemapping
+    // alternate between them.
+    hdr: [(TextureView, BindGroup); 2],
+    // The same for the passes after tonemapping.
+    ldr: [(TextureView, BindGroup); 2],
+}
+
+impl PostTargets {
+    /// The image the scene is rendered into.
+    pub fn hdr_view(&self) -> &TextureView {
+        &self.hdr[0].0
+    }
+}
+
+fn create_pass(
+    device: &Device,
+    input_layout: &BindGroupLayout,
+    uniform_layout: &BindGroupLayout,
+    format: TextureFormat,
+    descriptor: &PostPassDescriptor,
+) -> PostPass {
+    let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some(descriptor.label),
+        source: wgpu::ShaderSource::Wgsl(Cow::Owned(format!(
+            "{}\n{}",
+            include_str!("post.wgsl"),
+            descriptor.source
+        ))),
+    });
+
+    let uniforms = descriptor.uniforms.map(|contents| {
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some(descriptor.label),
+            size: contents.len() as wgpu::BufferAddress,
+            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+            mapped_at_creation: true,
+        });
+        buffer
+            .slice(..)
+            .get_mapped_range_mut()
+            .copy_from_slice(contents);
+        buffer.unmap();
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some(descriptor.label),
+            layout: uniform_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: buffer.as_entire_binding(),
+            }],
+        });
+        (buffer, bind_group)
+    });
+
+    let bind_group_layouts: &[&BindGroupLayout] = match uniforms {
+        Some(_) => &[input_layout, uniform_layout],
+        None => &[input_layout],
+    };
+    let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: Some(descriptor.label),
+        bind_group_layouts,
+        immediate_size: 0,
+    });
+    let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some(descriptor.label),
+        layout: Some(&pipeline_layout),
+        vertex: wgpu::VertexState {
+            module: &shader,
+            entry_point: Some("vs_main"),
+            // The triangle is generated in the shader.
+            buffers: &[],
+            compilation_options: Default::default(),
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: &shader,
+            entry_point: Some("fs_main"),
+            targets: &[Some(format.into())],
+            compilation_options: Default::default(),
+        }),
+        primitive: wgpu::PrimitiveState::default(),
+        depth_stencil: None,
+        multisample: wgpu::MultisampleState::default(),
+        multiview_mask: None,
+        cache: None,
+    });
+
+    PostPass {
+        label: descriptor.label,
+        pipeline,
+        uniforms,
+        enabled: true,
+    }
+}
@@ -0,0 +1,27 @@
+// Shared by every post-processing pass, the pass' source is appended.
+
+@group(0)
+@binding(0)
+var input_texture: texture_2d<f32>;
+
+@group(0)
+@binding(1)
+var input_sampler: sampler;
+
+struct VSOutput {
+    @builtin(position) position: vec4f,
+    // 0 to 1 across the image, the origin in the top left corner.
+    @location(0) uv: vec2f,
+};
+
+// A single triangle covering the whole image, the corners outside of it
+// are clipped.
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> VSOutput {
+    let uv = vec2f(f32((index << 1u) & 2u), f32(index & 2u));
+
+    var out: VSOutput;
+    out.position = vec4f(uv * vec2f(2.0, -2.0) + vec2f(-1.0, 1.0), 0.0, 1.0);
+    out.uv = uv;
+    return out;
+}
@@ -0,0 +1,310 @@
+//! CPU timings of the engine's scopes in the Chrome trace format.
+//!
+//! The scopes are `tracing` spans, like the ones around every system of the
+//! schedule, every render pass, the chunk meshing and the uniform uploads.
+//! While profiling, every time a span is entered is recorded until it is
+//! exited again, and the timeline of every thread is saved as JSON once
+//! profiling ends, to be opened with `chrome://tracing` or Perfetto.
+
+use std::{
+    cell::Cell,
+    collections::BTreeMap,
+    fmt::Debug,
+    path::PathBuf,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+use serde::Serialize;
+use tracing::{
+    Level, Subscriber,
+    field::{Field, Visit},
+    span::{Attributes, Id},
+};
+use tracing_subscriber::{filter::Targets, layer::Context, registry::LookupSpan};
+use web_time::Instant;
+
+/// The crates whose spans are recorded, at every level.
+pub fn profiled_targets() -> Targets {
+    Targets::new()
+        .with_target("voxon", Level::TRACE)
+        .with_target("ecs", Level::TRACE)
+}
+
+/// Start profiling, with the layer recording the spans and the guard saving
+/// them to `path` when dropped.
+///
+/// Every scope is kept in memory until then, a few thousand per second.
+pub fn chrome_trace(path: impl Into<PathBuf>) -> (ChromeTraceLayer, ProfileGuard) {
+    let trace = Arc::new(Trace {
+        start: Instant::now(),
+        events: Mutex::default(),
+    });
+    let layer = ChromeTraceLayer {
+        trace: trace.clone(),
+    };
+    let guard = ProfileGuard {
+        path: path.into(),
+        trace,
+    };
+    (layer, guard)
+}
+
+/// Records how long spans are entered, see [chrome_trace].
+pub struct ChromeTraceLayer {
+    trace: Arc<Trace>,
+}
+
+/// Saves the recorded scopes when dropped, see [chrome_trace].
+pub struct ProfileGuard {
+    path: PathBuf,
+    trace: Arc<Trace>,
+}
+
+impl ProfileGuard {
+    fn save(&self) -> std::io::Result<usize> {
+        let events = self.trace.take_events();
+        let file = std::io::BufWriter::new(std::fs::File::create(&self.path)?);
+        serde_json::to_writer(file, &ChromeTrace::new(&events))?;
+        Ok(events.len())
+    }
+}
+
+impl Drop for ProfileGuard {
+    fn drop(&mut self) {
+        match self.save() {
+            Ok(scopes) => tracing::info!("Saved {scopes} scopes to {}", self.path.display()),
+            Err(err) => {
+                tracing::error!("Failed to save the profile {}: {err}", self.path.display())
+            }
+        }
+    }
+}
+
+struct Trace {
+    // Timestamps are relative to it.
+    start: Instant,
+    events: Mutex<Vec<Scope>>,
+}
+
+impl Trace {
+    fn take_events(&self) -> Vec<Scope> {
+        std::mem::take(&mut *self.events.lock().expect("a profiled thread panicked"))
+    }
+}
+
+/// A span which was entered once, on one thread.
+#[derive(Debug, Clone)]
+struct Scope {
+    name: String,
+    target: &'static str,
+    args: BTreeMap<&'static str, String>,
+    thread: u64,
+    thread_name: Option<String>,
+    // In microseconds since the start of the trace.
+    start: f64,
+    duration: f64,
+}
+
+/// The fields of a span and when it was entered last, kept in its extensions.
+struct SpanTiming {
+    args: BTreeMap<&'static str, String>,
+    entered: Option<Instant>,
+}
+
+impl Visit for SpanTiming {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.args.insert(field.name(), value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        self.args.insert(field.name(), format!("{value:?}"));
+    }
+}
+
+/// A small number per thread, the trace format has no use for [std::thread::ThreadId].
+fn thread_number() -> u64 {
+    static NEXT: AtomicU64 = AtomicU64::new(1);
+    thread_local! {
+        static NUMBER: Cell<u64> = const { Cell::new(0) };
+    }
+    NUMBER.with(|number| {
+        if number.get() == 0 {
+            number.set(NEXT.fetch_add(1, Ordering::Relaxed));
+        }
+        number.get()
+    })
+}
+
+impl<S> tracing_subscriber::Layer<S> for ChromeTraceLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attributes: &Attributes<'_>, id: &Id, context: Context<'_, S>) {
+        let Some(span) = context.span(id) else {
+            return;
+        };
+        let mut timing = SpanTiming {
+            args: BTreeMap::new(),
+            entered: None,
+        };
+        attributes.record(&mut timing);
+        span.extensions_mut().insert(timing);
+    }
+
+    fn on_enter(&self, id: &Id, context: Context<'_, S>) {
+        if let Some(span) = context.span(id)
+            && let Some(timing) = span.extensions_mut().get_mut::<SpanTiming>()
+        {
+            timing.entered = Some(Instant::now());
+        }
+    }
+
+    fn on_exit(&self, id: &Id, context: Context<'_, S>) {
+        let exited = Instant::now();
+        let Some(span) = context.span(id) else {
+            return;
+        };
+        let mut extensions = span.extensions_mut();
+        let Some(timing) = extensions.get_mut::<SpanTiming>() else {
+            return;
+        };
+        let Some(entered) = timing.entered.take() else {
+            return;
+        };
+
+        // Systems are all called "system", their name is more telling.
+        let name = timing
+            .args
+            .get("name")
+            .cloned()
+            .unwrap_or_else(|| span.name().to_string());
+        let scope = Scope {
+            name,
+            target: span.metadata().target(),
+            args: timing.args.clone(),
+            thread: thread_number(),
+            thread_name: std::thread::current().name().map(str::to_string),
+            start: micros(entered.saturating_duration_since(self.trace.start)),
+            duration: micros(exited - entered),
+        };
+        if let Ok(mut events) = self.trace.events.lock() {
+            events.push(scope);
+        }
+    }
+}
+
+fn micros(duration: web_time::Duration) -> f64 {
+    duration.as_secs_f64() * 1e6
+}
+
+/// The JSON object format of the Chrome trace event format.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ChromeTrace<'a> {
+    trace_events: Vec<TraceEvent<'a>>,
+    display_time_unit: &'static str,
+}
+
+#[derive(Serialize)]
+struct TraceEvent<'a> {
+    name: &'a str,
+    cat: &'a str,
+    // "X" for a complete event with a duration, "M" for metadata.
+    ph: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ts: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dur: Option<f64>,
+    pid: u32,
+    tid: u64,
+    args: BTreeMap<&'a str, &'a str>,
+}
+
+impl<'a> ChromeTrace<'a> {
+    fn new(scopes: &'a [Scope]) -> Self {
+        let pid = std::process::id();
+        // Named after the first name seen, so threads show up by name.
+        let mut thread_names = BTreeMap::new();
+        for scope in scopes {
+            if let Some(name) = &scope.thread_name {
+                thread_names.entry(scope.thread).or_insert(name.as_str());
+            }
+        }
+        let metadata = thread_names.into_iter().map(|(tid, name)| TraceEvent {
+            name: "thread_name",
+            cat: "",
+            ph: "M",
+            ts: None,
+            dur: None,
+            pid,
+            tid,
+            args: BTreeMap::from([("name", name)]),
+        });
+        let scopes = scopes.iter().map(|scope| TraceEvent {
+            name: &scope.name,
+            cat: scope.target,
+            ph: "X",
+            ts: Some(scope.start),
+            dur: Some(scope.duration),
+            pid,
+            tid: scope.thread,
+            args: scope
+                .args
+                .iter()
+                .map(|(field, value)| (*field, value.as_str()))
+                .collect(),
+        });
+        Self {
+            trace_events: metadata.chain(scopes).collect(),
+            display_time_unit: "ms",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing_subscriber::layer::SubscriberExt;
+
+    use super::*;
+
+    #[test]
+    fn nested_scopes_are_saved_per_thread() {
+        let path = std::env::temp_dir().join(format!("voxon-profile-{}.json", std::process::id()));
+        let (layer, guard) = chrome_trace(&path);
+        let subscriber = tracing_subscriber::registry().with(layer);
+        tracing::subscriber::with_default(subscriber, || {
+            let _frame = tracing::debug_span!("frame", frame = 3).entered();
+            for name in ["animate", "spin"] {
+                let _span = tracing::trace_span!("system", name).entered();
+            }
+        });
+        drop(guard);
+
+        let json: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let events = json["traceEvents"].as_array().unwrap();
+        // The test's thread is named after the test, the scopes are recorded
+        // when exited.
+        let names: Vec<_> = events.iter().map(|event| &event["name"]).collect();
+        assert_eq!(names, ["thread_name", "animate", "spin", "frame"]);
+        assert_eq!(events[0]["ph"], "M");
+
+        let frame = &events[3];
+        assert_eq!(frame["ph"], "X");
+        assert_eq!(frame["cat"], "voxon::profile::tests");
+        assert_eq!(frame["args"]["frame"], "3");
+        let end = |event: &serde_json::Value| {
+            event["ts"].as_f64().unwrap() + event["dur"].as_f64().unwrap()
+        };
+        for system in &events[1..3] {
+            assert_eq!(system["args"]["name"], system["name"]);
+            assert_eq!(system["tid"], frame["tid"]);
+            assert!(system["ts"].as_f64() >= frame["ts"].as_f64());
+            assert!(end(system) <= end(frame));
+        }
+    }
+}
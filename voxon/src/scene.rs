@@ -1,29 +1,259 @@
-use std::{borrow::Cow, f32::consts::PI, time::Duration};
+use std::{
+    borrow::Cow, collections::HashMap, ops::AddAssign, path::Path, sync::Arc, time::Duration,
+};
 
-use graphic::{camera::Camera, identity_matrix};
-use lina::{m, matrix::Matrix, v};
+use bytemuck::{Pod, Zeroable};
+use ecs::{Entity, Schedule, Time};
+use engine::jobs::Jobs;
+use graphic::{camera::Camera, color::Color, transform::normal_matrix};
+use lina::{matrix::Matrix, v, vector::Vector};
 
-use quaternion::Quaternion;
 use wgpu::{
-    Adapter, BindGroup, BindGroupEntry, BindGroupLayoutDescriptor, BindGroupLayoutEntry, Buffer,
-    BufferBinding, BufferUsages, DepthBiasState, DepthStencilState, Device, Face, Operations,
-    Queue, RenderPassDepthStencilAttachment, RenderPipeline, StencilState, Surface,
-    TextureDescriptor, TextureUsages, VertexAttribute, VertexBufferLayout, util::align_to,
+    Adapter, BindGroup, BindGroupEntry, BindGroupLayout, BindGroupLayoutDescriptor,
+    BindGroupLayoutEntry, Buffer, BufferAddress, BufferBinding, BufferUsages, DepthBiasState,
+    DepthStencilState, Device, Face, Features, Operations, PrimitiveTopology, Queue,
+    RenderPassDepthStencilAttachment, RenderPipeline, StencilState, TextureFormat,
+    TextureFormatFeatureFlags, util::align_to,
 };
 use winit::dpi::PhysicalSize;
 
-use crate::mesh::{generate_cube, generate_plane};
+use crate::{
+    animation::{Animator, Skin, SkinnedModel, TransformAnimator, animate, animate_transforms},
+    components::{Generated, Lod, MeshRenderer, MoveOrder, Transform, Unit},
+    compute::supports_compute,
+    debug_draw::{DebugDraw, DebugDrawPipeline},
+    gpu_timer::GpuTimer,
+    light::{Light, LightUniform},
+    mesh::{Mesh, SkinnedVertex, Vertex, generate_cube, generate_plane, generate_sphere},
+    navigation::route,
+    overlay::{Overlay, OverlayPipeline},
+    particles::{Emitter, ParticlePipeline, simulate_particles},
+    physics::{
+        Aabb, CharacterController, CharacterSettings, Collide, Dynamics, RigidBody, Shape,
+        Translated,
+    },
+    post::{HDR_FORMAT, PostProcess},
+    scene_file::{CameraDesc, ObjectDesc, SCENE_FORMAT_VERSION, SceneFile, SceneFileError},
+    selection::{
+        ScreenRect, Selection, draw_rect, draw_selection, formation, move_units, pick_units,
+    },
+    shadow::{ShadowMap, ShadowUniforms},
+    skinning::{JointBuffer, MATRIX_SIZE, supports_skinning, write_matrices},
+    skybox::SkyboxPipeline,
+    terrain::{Heightmap, TerrainSettings, tile_mesh},
+    texture::{Cubemap, Material, Texture, create_repeating_sampler},
+    upload::UploadBelt,
+    vertex::VertexLayout,
+    view::{RenderTarget, View},
+    world::{
+        CHUNK_SIZE, ChunkCoord, ChunkMesh, ChunkStreamer, GpuMesher, Ray, RaycastHit,
+        StreamSettings, StreamedChunk, World, greedy_mesh,
+    },
+};
+
+/// The seed of the generated voxel world.
+const WORLD_SEED: u64 = 1;
+
+/// Where the origin of the voxel world is placed, so that its hills end
+/// below the plane of the built-in scene.
+const VOXEL_ORIGIN: Vector<f64, 3> = v![0.0, -2.0, 0.0];
+
+/// The size of the cells the local origin snaps to, see [local_origin].
+const LOCAL_ORIGIN_CELL: f64 = 1024.0;
+
+/// The longest step the player is moved in at once by [Scene::walk].
+const MAX_WALK_STEP: Duration = Duration::from_millis(100);
+
+/// Bodies falling below this height are gone for good and despawned.
+const LOWEST_BODY: f64 = -100.0;
+
+/// How far away units can be ordered to.
+const ORDER_REACH: f32 = 100.0;
 
-pub struct Entity {
-    // Mesh data
+/// The distance between the units ordered to the same spot.
+const FORMATION_SPACING: f64 = 0.8;
+
+/// Handle to a [Mesh] uploaded to the GPU by [Scene::add_mesh].
+///
+/// The same mesh can be shared by any number of [MeshRenderer]s.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MeshHandle(pub(crate) usize);
+
+/// Handle to a [Material] created by [Scene::add_material].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MaterialHandle(usize);
+
+/// Mesh data residing on the GPU.
+struct GpuMesh {
+    name: String,
     vertex_buffer: wgpu::Buffer,
     index_buffer: wgpu::Buffer,
     index_format: wgpu::IndexFormat,
     index_count: usize,
-    // Transformation data
+    // Whether the vertices are [SkinnedVertex]s rather than [Vertex]s.
+    skinned: bool,
+}
+
+/// A [MeshRenderer] entity collected for drawing.
+///
+/// Every object owns a slot in the object uniform buffer for the frame,
+/// which is selected with a dynamic offset at draw time.
+struct RenderObject {
+    mesh: MeshHandle,
+    material: MaterialHandle,
     uniform_offset: wgpu::DynamicOffset,
-    world_matrix: Matrix<f32, 4, 4>,
-    normal_matrix: Matrix<f32, 3, 3>,
+    skinned: bool,
+}
+
+/// The per object uniform buffer with all the state necessary to grow it.
+struct ObjectUniforms {
+    buffer: Buffer,
+    bind_group: BindGroup,
+    layout: BindGroupLayout,
+    // The size of one slot, already aligned to `min_uniform_buffer_offset_alignment`.
+    alignment: BufferAddress,
+    // The number of objects the buffer can hold.
+    capacity: usize,
+}
+
+/// The `Object` WGSL struct.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+struct ObjectUniform {
+    // Column by column, like the normal matrix.
+    world_matrix: [f32; 16],
+    // Every column is padded to 16 bytes.
+    normal_matrix: [Vector<f32, 4>; 3],
+    first_joint: u32,
+    // The struct is padded to 16 bytes as well.
+    _padding: [u32; 3],
+}
+
+const OBJECT_UNIFORM_SIZE: BufferAddress = std::mem::size_of::<ObjectUniform>() as BufferAddress;
+
+/// The amount of work submitted by [Scene::render], summed up over the
+/// views of a frame.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DrawStats {
+    pub draw_calls: u32,
+    pub triangles: u64,
+}
+
+impl DrawStats {
+    fn add(&mut self, vertex_count: u32, primitive: PrimitiveTopology) {
+        if vertex_count == 0 {
+            return;
+        }
+        self.draw_calls += 1;
+        if primitive == PrimitiveTopology::TriangleList {
+            self.triangles += vertex_count as u64 / 3;
+        }
+    }
+}
+
+impl AddAssign for DrawStats {
+    fn add_assign(&mut self, other: Self) {
+        self.draw_calls += other.draw_calls;
+        self.triangles += other.triangles;
+    }
+}
+
+/// Drawing skinned meshes, where the device supports it.
+struct Skinning {
+    joints: JointBuffer,
+    pipeline: RenderPipeline,
+    // Only available if the device supports `POLYGON_MODE_LINE`.
+    wireframe_pipeline: Option<RenderPipeline>,
+}
+
+/// The `Globals` WGSL struct.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+struct GlobalUniforms {
+    // Column by column.
+    view_projection: [f32; 16],
+    light: LightUniform,
+    shadows: ShadowUniforms,
+}
+
+impl ObjectUniforms {
+    fn new(device: &Device, capacity: usize) -> Self {
+        let alignment = {
+            let alignment =
+                device.limits().min_uniform_buffer_offset_alignment as wgpu::BufferAddress;
+            align_to(OBJECT_UNIFORM_SIZE, alignment)
+        };
+
+        let layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: Some("Local bind group layout"),
+            entries: &[BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: true,
+                    min_binding_size: wgpu::BufferSize::new(OBJECT_UNIFORM_SIZE),
+                },
+                count: None,
+            }],
+        });
+
+        let (buffer, bind_group) = Self::create_buffer(device, &layout, alignment, capacity);
+
+        Self {
+            buffer,
+            bind_group,
+            layout,
+            alignment,
+            capacity,
+        }
+    }
+
+    fn create_buffer(
+        device: &Device,
+        layout: &BindGroupLayout,
+        alignment: BufferAddress,
+        capacity: usize,
+    ) -> (Buffer, BindGroup) {
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Object uniform buffer"),
+            size: capacity as u64 * alignment,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Object bind group"),
+            layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                    buffer: &buffer,
+                    offset: 0,
+                    size: wgpu::BufferSize::new(OBJECT_UNIFORM_SIZE),
+                }),
+            }],
+        });
+
+        (buffer, bind_group)
+    }
+
+    /// Make sure at least `required` objects fit into the buffer.
+    ///
+    /// The buffer contents are not preserved, but every object
+    /// is rewritten each frame anyway.
+    fn reserve(&mut self, device: &Device, required: usize) {
+        if required <= self.capacity {
+            return;
+        }
+
+        let capacity = required.next_power_of_two();
+        let (buffer, bind_group) =
+            Self::create_buffer(device, &self.layout, self.alignment, capacity);
+        self.buffer = buffer;
+        self.bind_group = bind_group;
+        self.capacity = capacity;
+    }
 }
 
 //
@@ -37,128 +267,86 @@ pub struct Entity {
 // but for the time being it has been moved here as well.
 // Mostly to keep things simple.
 pub struct Scene {
-    // Delta_t associated with the cube rotation
-    cube_delta_t: std::time::Duration,
+    // Every object, light and camera of the scene.
+    entities: ecs::World,
+    // Systems updating the entities every frame.
+    schedule: Schedule,
+    // The camera of the main view, saved to scene files.
+    camera: Entity,
     // Prepared render pipeline and all the necessary info for rendering the scene
     render_pipeline: RenderPipeline,
-    entities: Vec<Entity>,
+    // Only available if the device supports `POLYGON_MODE_LINE`.
+    wireframe_pipeline: Option<RenderPipeline>,
+    wireframe: bool,
+    skinning: Option<Skinning>,
+    // Runs the work which doesn't need the GPU, like meshing chunks or
+    // planning routes, on the worker threads.
+    jobs: Jobs,
+    // Loads the chunks of `world` around the main camera.
+    streamer: ChunkStreamer,
+    // Meshes the streamed chunks if the device supports compute shaders,
+    // otherwise the streamer's worker does.
+    gpu_mesher: Option<GpuMesher>,
+    // The object and mesh of every chunk with visible faces.
+    chunk_objects: HashMap<ChunkCoord, (Entity, MeshHandle)>,
+    chunk_material: MaterialHandle,
+    debug_draw: DebugDraw,
+    debug_pipeline: DebugDrawPipeline,
+    overlay_pipeline: OverlayPipeline,
+    skybox_pipeline: SkyboxPipeline,
+    particle_pipeline: ParticlePipeline,
+    // Fills the background where there is no skybox.
+    clear_color: Color,
+    // `None` for removed meshes, whose slots are listed in `free_meshes`.
+    meshes: Vec<Option<GpuMesh>>,
+    free_meshes: Vec<MeshHandle>,
+    materials: Vec<Material>,
+    // Parallel to `materials`, referenced by scene files.
+    material_names: Vec<String>,
+    material_layout: BindGroupLayout,
+    // Rebuilt from the entities every frame.
+    objects: Vec<RenderObject>,
+    // Everything of the frame is uploaded relative to it, the main camera's eye.
+    render_origin: Vector<f64, 3>,
+    // The skinning matrices of the skinned objects, rebuilt every frame.
+    joint_matrices: Vec<Matrix<f32, 4, 4>>,
+    world: World,
+    // Static geometry the player collides with besides the blocks.
+    colliders: Vec<Aabb>,
+    // The main camera follows the player while walking.
+    player: Option<CharacterController>,
+    dynamics: Dynamics,
+    selection: Selection,
+    shadow_map: ShadowMap,
+    post_process: PostProcess,
+    color_format: TextureFormat,
+    sample_count: u32,
     global_uniforms: (Buffer, BindGroup),
-    entity_uniforms: (Buffer, BindGroup),
+    object_uniforms: ObjectUniforms,
+    // Staging memory for the per-frame uploads of `prepare`.
+    uploads: UploadBelt,
+    gpu_timer: GpuTimer,
 }
 
 impl Scene {
-    pub fn new(adapter: &Adapter, surface: &Surface, device: &Device, queue: &Queue) -> Self {
+    /// Create the scene rendering into `color_format` targets, see [Scene::create_target].
+    ///
+    /// The scene itself is drawn in [HDR_FORMAT], the post-processing turns
+    /// it into `color_format`.
+    #[tracing::instrument(skip_all, fields(?color_format))]
+    pub fn new(
+        adapter: &Adapter,
+        color_format: TextureFormat,
+        device: &Device,
+        queue: &Queue,
+        sample_count: u32,
+    ) -> Self {
         // Load the shaders
         let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
             label: Some("shader"),
             source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(include_str!("shader.wgsl"))),
         });
 
-        // CUBE
-        let cube_mesh = generate_cube();
-        let cube_vertex_data = cube_mesh
-            .vertices()
-            .iter()
-            .flat_map(|entry| {
-                entry
-                    .position()
-                    .as_slice()
-                    .iter()
-                    .chain(entry.normal().as_slice().iter().chain([&0.0]))
-                    .flat_map(|value| value.to_le_bytes())
-            })
-            .collect::<Vec<u8>>();
-
-        let cube_vertex_buffer = device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("cube_vertex_buffer"),
-            size: cube_vertex_data.len() as u64,
-            usage: BufferUsages::VERTEX | BufferUsages::COPY_DST,
-            mapped_at_creation: false,
-        });
-        queue.write_buffer(&cube_vertex_buffer, 0, &cube_vertex_data);
-
-        let cube_index_data = cube_mesh
-            .indices()
-            .iter()
-            .flat_map(|index| index.to_le_bytes())
-            .collect::<Vec<_>>();
-        let cube_index_buffer = device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("cube_index_buffer"),
-            size: cube_index_data.len() as u64,
-            usage: BufferUsages::INDEX | BufferUsages::COPY_DST,
-            mapped_at_creation: false,
-        });
-        queue.write_buffer(&cube_index_buffer, 0, &cube_index_data);
-
-        // PLANE
-        let plane_mesh = generate_plane();
-        let plane_vertex_data = plane_mesh
-            .vertices()
-            .iter()
-            .flat_map(|entry| {
-                entry
-                    .position()
-                    .as_slice()
-                    .iter()
-                    .chain(entry.normal().as_slice().iter().chain([&0.0]))
-                    .flat_map(|value| value.to_le_bytes())
-            })
-            .collect::<Vec<u8>>();
-
-        let plane_vertex_buffer = device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("plane_vertex_buffer"),
-            size: plane_vertex_data.len() as u64,
-            usage: BufferUsages::VERTEX | BufferUsages::COPY_DST,
-            mapped_at_creation: false,
-        });
-        queue.write_buffer(&plane_vertex_buffer, 0, &plane_vertex_data);
-
-        let plane_index_data = plane_mesh
-            .indices()
-            .iter()
-            .flat_map(|index| index.to_le_bytes())
-            .collect::<Vec<_>>();
-        let plane_index_buffer = device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("plane_index_buffer"),
-            size: plane_index_data.len() as u64,
-            usage: BufferUsages::INDEX | BufferUsages::COPY_DST,
-            mapped_at_creation: false,
-        });
-        queue.write_buffer(&plane_index_buffer, 0, &plane_index_data);
-
-        let entity_uniform_size = (16 + 16) * 4;
-        let entity_uniform_alignment = {
-            let alignment =
-                device.limits().min_uniform_buffer_offset_alignment as wgpu::BufferAddress;
-            align_to(entity_uniform_size, alignment)
-        };
-
-        let entities = {
-            [
-                Entity {
-                    vertex_buffer: cube_vertex_buffer,
-                    index_buffer: cube_index_buffer,
-                    index_format: wgpu::IndexFormat::Uint32,
-                    index_count: cube_mesh.indices().len(),
-                    world_matrix: identity_matrix(),
-                    normal_matrix: Matrix::<f32, 3, 3>::from_value(0.0),
-                    uniform_offset: 0,
-                },
-                Entity {
-                    vertex_buffer: plane_vertex_buffer,
-                    index_buffer: plane_index_buffer,
-                    index_format: wgpu::IndexFormat::Uint32,
-                    index_count: plane_mesh.indices().len(),
-                    world_matrix: graphic::transform::translate(0.0, -1.0, 0.0)
-                        * graphic::transform::scale(3.0, 1.0, 3.0),
-                    normal_matrix: m![[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0],],
-                    uniform_offset: entity_uniform_alignment as u32,
-                },
-            ]
-            .into_iter()
-            .collect::<Vec<Entity>>()
-        };
-
         // Bind group layout
         let global_uniform_bind_group_layout =
             device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
@@ -177,13 +365,12 @@ impl Scene {
 
         // Uniform buffer
         let global_uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
-                label: Some("uniforms"),
-                // uniforms have to be padded to a multiple of 8
-                #[allow(clippy::identity_op)] // for clearer explanation
-                size: (16 + 4 + 4 + 3 + 1 + 3 + 1) * 4, // (view projection matrix + light color + light position + view position + shininess + light direction + limit) * float size + padding
-                usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
-                mapped_at_creation: false,
-            });
+            label: Some("uniforms"),
+            // uniforms have to be padded to a multiple of 16
+            size: std::mem::size_of::<GlobalUniforms>() as BufferAddress,
+            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
 
         // Create bind group
         let global_uniform_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
@@ -201,347 +388,1635 @@ impl Scene {
 
         let global_uniforms = (global_uniform_buffer, global_uniform_bind_group);
 
-        let entity_uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("Entity uniform buffer"),
-            size: entities.len() as u64 * entity_uniform_alignment,
-            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
-            mapped_at_creation: false,
-        });
+        let object_uniforms = ObjectUniforms::new(device, 16);
 
-        let entity_uniform_bind_group_layout =
-            device.create_bind_group_layout(&BindGroupLayoutDescriptor {
-                label: Some("Local bind group layout"),
-                entries: &[BindGroupLayoutEntry {
-                    binding: 0,
-                    visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
-                    ty: wgpu::BindingType::Buffer {
-                        ty: wgpu::BufferBindingType::Uniform,
-                        has_dynamic_offset: true,
-                        min_binding_size: wgpu::BufferSize::new(entity_uniform_size), // (world matrix + normal matrix) * float size, no padding needed
-                    },
-                    count: None,
-                }],
-            });
+        let material_layout = Material::create_bind_group_layout(device);
 
-        let entity_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-            label: Some("Entity bind group"),
-            layout: &entity_uniform_bind_group_layout,
-            entries: &[wgpu::BindGroupEntry {
-                binding: 0,
-                resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
-                    buffer: &entity_uniform_buffer,
-                    offset: 0,
-                    size: wgpu::BufferSize::new(entity_uniform_size),
-                }),
-            }],
-        });
-        let entity_uniforms = (entity_uniform_buffer, entity_bind_group);
+        let joints =
+            supports_skinning(adapter).then(|| JointBuffer::new(device, OBJECT_UNIFORM_SIZE, 64));
+
+        // Reaches across the generated terrain when standing in its middle.
+        let shadow_map = ShadowMap::new(
+            device,
+            &object_uniforms.layout,
+            joints.as_ref().map(JointBuffer::layout),
+            2048,
+            3,
+            64.0,
+        );
 
         // Pipeline
         let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
             label: Some("pipeline_layout"),
             bind_group_layouts: &[
                 &global_uniform_bind_group_layout,
-                &entity_uniform_bind_group_layout,
+                &object_uniforms.layout,
+                &material_layout,
+                shadow_map.layout(),
             ],
             immediate_size: 0,
         });
+        let sample_count = supported_sample_count(adapter, device, HDR_FORMAT, sample_count);
 
-        let swapchain_capabilities = surface.get_capabilities(adapter);
-        let swapchain_format = swapchain_capabilities.formats[0];
-
-        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: Some("render_pipeline_descriptor"),
-            layout: Some(&pipeline_layout),
-            vertex: wgpu::VertexState {
-                module: &shader,
-                entry_point: Some("vs_main"),
-                buffers: &[VertexBufferLayout {
-                    array_stride: (4 + 3 + 1) * 4, // (4 floats for position + 3 floats for normal + 1 padding) * f32 byte count
-                    step_mode: wgpu::VertexStepMode::Vertex,
-                    attributes: &[
-                        // position
-                        VertexAttribute {
-                            format: wgpu::VertexFormat::Float32x4,
-                            offset: 0,
-                            shader_location: 0,
-                        },
-                        // normal
-                        VertexAttribute {
-                            format: wgpu::VertexFormat::Float32x3,
-                            offset: 16,
-                            shader_location: 1,
-                        },
-                    ],
-                }],
-                compilation_options: Default::default(),
-            },
-            fragment: Some(wgpu::FragmentState {
-                module: &shader,
-                entry_point: Some("fs_main"),
-                targets: &[Some(swapchain_format.into())],
-                compilation_options: Default::default(),
-            }),
-            primitive: wgpu::PrimitiveState {
-                topology: wgpu::PrimitiveTopology::TriangleList,
-                strip_index_format: None,
-                front_face: wgpu::FrontFace::Ccw,
-                cull_mode: Some(Face::Back),
-                unclipped_depth: false,
-                polygon_mode: wgpu::PolygonMode::Fill,
-                conservative: false,
-            },
-            depth_stencil: Some(DepthStencilState {
-                format: wgpu::TextureFormat::Depth24Plus,
-                depth_compare: wgpu::CompareFunction::Less,
-                depth_write_enabled: true,
-                stencil: StencilState::default(),
-                bias: DepthBiasState::default(),
-            }),
-            multisample: wgpu::MultisampleState::default(),
-            multiview_mask: None,
-            cache: None,
+        let create_pipeline = |layout: &wgpu::PipelineLayout, entry_point, buffer, polygon_mode| {
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some("render_pipeline_descriptor"),
+                layout: Some(layout),
+                vertex: wgpu::VertexState {
+                    module: &shader,
+                    entry_point: Some(entry_point),
+                    buffers: &[buffer],
+                    compilation_options: Default::default(),
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &shader,
+                    entry_point: Some("fs_main"),
+                    targets: &[Some(HDR_FORMAT.into())],
+                    compilation_options: Default::default(),
+                }),
+                primitive: wgpu::PrimitiveState {
+                    topology: wgpu::PrimitiveTopology::TriangleList,
+                    strip_index_format: None,
+                    front_face: wgpu::FrontFace::Ccw,
+                    cull_mode: Some(Face::Back),
+                    unclipped_depth: false,
+                    polygon_mode,
+                    conservative: false,
+                },
+                depth_stencil: Some(DepthStencilState {
+                    format: wgpu::TextureFormat::Depth32Float,
+                    // Reverse-Z, closer fragments have greater depth.
+                    depth_compare: wgpu::CompareFunction::Greater,
+                    depth_write_enabled: true,
+                    stencil: StencilState::default(),
+                    bias: DepthBiasState::default(),
+                }),
+                multisample: wgpu::MultisampleState {
+                    count: sample_count,
+                    ..Default::default()
+                },
+                multiview_mask: None,
+                cache: None,
+            })
+        };
+
+        let render_pipeline = create_pipeline(
+            &pipeline_layout,
+            "vs_main",
+            Vertex::layout(),
+            wgpu::PolygonMode::Fill,
+        );
+        // Line polygon mode is an optional feature, without it there is no wireframe mode.
+        let line_mode = device
+            .features()
+            .contains(wgpu::Features::POLYGON_MODE_LINE);
+        let wireframe_pipeline = line_mode.then(|| {
+            create_pipeline(
+                &pipeline_layout,
+                "vs_main",
+                Vertex::layout(),
+                wgpu::PolygonMode::Line,
+            )
         });
 
-        Self {
-            cube_delta_t: Duration::default(),
-            render_pipeline,
+        // The same, with the skinning matrices next to the object uniforms.
+        let skinning = joints.map(|joints| {
+            let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("skinned_pipeline_layout"),
+                bind_group_layouts: &[
+                    &global_uniform_bind_group_layout,
+                    joints.layout(),
+                    &material_layout,
+                    shadow_map.layout(),
+                ],
+                immediate_size: 0,
+            });
+            let create_pipeline = |polygon_mode| {
+                create_pipeline(&layout, "vs_skinned", SkinnedVertex::layout(), polygon_mode)
+            };
+            Skinning {
+                pipeline: create_pipeline(wgpu::PolygonMode::Fill),
+                wireframe_pipeline: line_mode.then(|| create_pipeline(wgpu::PolygonMode::Line)),
+                joints,
+            }
+        });
+
+        let debug_pipeline = DebugDrawPipeline::new(
+            device,
+            &global_uniform_bind_group_layout,
+            HDR_FORMAT,
+            sample_count,
+        );
+
+        // Drawn over the finished frame, after the post-processing.
+        let overlay_pipeline = OverlayPipeline::new(device, queue, color_format, 1);
+
+        let skybox_pipeline = SkyboxPipeline::new(device, HDR_FORMAT, sample_count);
+
+        let particle_pipeline = ParticlePipeline::new(device, HDR_FORMAT, sample_count);
+
+        let mut entities = ecs::World::new();
+        let camera = entities.spawn();
+        entities.insert(camera, Camera::default());
+        let sun = entities.spawn();
+        entities.insert(sun, Light::default());
+
+        let mut schedule = Schedule::new();
+        schedule.add_system("update_cameras", update_cameras);
+        schedule.add_system("select_lods", move |entities, _| {
+            select_lods(entities, camera)
+        });
+        schedule.add_fixed_system("move_units", move_units);
+        schedule.add_system("animate_transforms", animate_transforms);
+        schedule.add_system("animate", animate);
+        schedule.add_system("simulate_particles", simulate_particles);
+
+        // Chunks are meshed by a compute shader where available, which scales
+        // to many chunks, otherwise on the CPU with fewer but merged faces.
+        let gpu_meshing = supports_compute(adapter);
+        let jobs = Jobs::default();
+
+        let mut scene = Self {
             entities,
+            schedule,
+            camera,
+            render_pipeline,
+            wireframe_pipeline,
+            wireframe: false,
+            skinning,
+            streamer: ChunkStreamer::new(
+                WORLD_SEED,
+                StreamSettings {
+                    radius: 4,
+                    // The hills are cut off at the top of the layer.
+                    layers: -1..=-1,
+                    uploads_per_frame: 2,
+                },
+                !gpu_meshing,
+                jobs.clone(),
+            ),
+            jobs,
+            gpu_mesher: gpu_meshing.then(|| GpuMesher::new(device)),
+            chunk_objects: HashMap::new(),
+            // Replaced by the block material right below.
+            chunk_material: MaterialHandle(0),
+            debug_draw: DebugDraw::new(),
+            debug_pipeline,
+            overlay_pipeline,
+            skybox_pipeline,
+            particle_pipeline,
+            clear_color: Color::BLACK,
+            meshes: Vec::new(),
+            free_meshes: Vec::new(),
+            materials: Vec::new(),
+            material_names: Vec::new(),
+            material_layout,
+            objects: Vec::new(),
+            render_origin: v![0.0, 0.0, 0.0],
+            joint_matrices: Vec::new(),
+            world: World::new(),
+            colliders: Vec::new(),
+            player: None,
+            dynamics: Dynamics::default(),
+            selection: Selection::default(),
+            shadow_map,
+            post_process: PostProcess::new(device, color_format),
+            color_format,
+            sample_count,
             global_uniforms,
-            entity_uniforms,
-        }
+            object_uniforms,
+            uploads: UploadBelt::new(device),
+            gpu_timer: GpuTimer::new(device, queue, &["shadow", "main", "post"]),
+        };
+
+        let block_texture = Texture::from_bytes(
+            device,
+            queue,
+            include_bytes!("../assets/block.png"),
+            "block_texture",
+        )
+        .expect("the embedded block texture is a valid PNG");
+        let block_material = scene.add_material(device, "block", block_texture);
+        scene.chunk_material = block_material;
+
+        let sky = Cubemap::from_bytes(
+            device,
+            queue,
+            [
+                include_bytes!("../assets/skybox/px.png"),
+                include_bytes!("../assets/skybox/nx.png"),
+                include_bytes!("../assets/skybox/py.png"),
+                include_bytes!("../assets/skybox/ny.png"),
+                include_bytes!("../assets/skybox/pz.png"),
+                include_bytes!("../assets/skybox/nz.png"),
+            ],
+            "skybox_texture",
+        )
+        .expect("the embedded skybox faces are valid PNGs");
+        scene.set_skybox(device, Some(sky));
+
+        let cube_mesh = scene.add_mesh(device, queue, "cube", &generate_cube());
+        scene.add_object(cube_mesh, block_material, Transform::default());
+        scene.add_collider(Aabb::new(v![-1.0, -1.0, -1.0], v![1.0, 1.0, 1.0]));
+
+        let plane_mesh = scene.add_mesh(device, queue, "plane", &generate_plane());
+        scene.add_object(
+            plane_mesh,
+            block_material,
+            Transform::from_translation(v![0.0, -1.0, 0.0]).with_scale(v![3.0, 1.0, 3.0]),
+        );
+        // Down to the top of the blocks, so nothing gets stuck below the plane.
+        scene.add_collider(Aabb::new(v![-3.0, -2.0, -3.0], v![3.0, -1.0, 3.0]));
+
+        // For the spheres among the rigid bodies.
+        scene.add_mesh(device, queue, "sphere", &generate_sphere(12, 24));
+
+        scene
+    }
+
+    /// Upload a [Mesh] to the GPU, making it available for [MeshRenderer]s.
+    ///
+    /// Scene files refer to the mesh by `name`.
+    pub fn add_mesh(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        name: &str,
+        mesh: &Mesh,
+    ) -> MeshHandle {
+        self.add_chunk_mesh(name, upload_mesh(device, queue, mesh))
     }
 
-    pub fn simulate(&mut self, delta_t: Duration) {
-        // World simulation.
-        // It will not be part of the render pipeline later on.
-        // Only temporarily for now.
+    /// Upload a skinned [Mesh], which is only drawn for entities with a
+    /// [Skin] besides the [MeshRenderer], and only where the device supports
+    /// skinning.
+    pub fn add_skinned_mesh(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        name: &str,
+        mesh: &Mesh<SkinnedVertex>,
+    ) -> MeshHandle {
+        let mesh = upload_mesh(device, queue, mesh);
+        self.insert_mesh(GpuMesh {
+            name: name.to_string(),
+            vertex_buffer: mesh.vertex_buffer,
+            index_buffer: mesh.index_buffer,
+            index_format: wgpu::IndexFormat::Uint32,
+            index_count: mesh.index_count,
+            skinned: true,
+        })
+    }
 
-        // For now the cube transformations are hacked in here.
-        let cube_full_rotation_time = std::time::Duration::from_secs(10);
-        self.cube_delta_t = self.cube_delta_t.saturating_add(delta_t);
-        if self.cube_delta_t > cube_full_rotation_time {
-            self.cube_delta_t = self.cube_delta_t.saturating_sub(cube_full_rotation_time);
+    /// Make a mesh generated on the GPU available for [MeshRenderer]s,
+    /// like [Scene::add_mesh].
+    pub fn add_chunk_mesh(&mut self, name: &str, mesh: ChunkMesh) -> MeshHandle {
+        self.insert_mesh(GpuMesh {
+            name: name.to_string(),
+            vertex_buffer: mesh.vertex_buffer,
+            index_buffer: mesh.index_buffer,
+            index_format: wgpu::IndexFormat::Uint32,
+            index_count: mesh.index_count,
+            skinned: false,
+        })
+    }
+
+    fn insert_mesh(&mut self, mesh: GpuMesh) -> MeshHandle {
+        if let Some(handle) = self.free_meshes.pop() {
+            self.meshes[handle.0] = Some(mesh);
+            return handle;
         }
+        self.meshes.push(Some(mesh));
+        MeshHandle(self.meshes.len() - 1)
+    }
 
-        // for quick rotation checks
-        #[allow(unused_variables)]
-        let rotate_y: Matrix<f32, 4, 4> = Quaternion::<f32>::new_unit(
-            2.0 * PI
-                * (self.cube_delta_t.as_millis() as f32
-                    / cube_full_rotation_time.as_millis() as f32),
-            v![0.0, 1.0, 0.0],
-        )
-        .into();
-
-        let cube_world_matrix = graphic::identity_matrix();
-
-        let cube_normal_matrix = {
-            let mut matrix = Matrix::<f32, 3, 3>::new();
-            // may be padded incorrectly!!! check
-            matrix[(0, 0)] = cube_world_matrix[(0, 0)];
-            matrix[(0, 1)] = cube_world_matrix[(0, 1)];
-            matrix[(0, 2)] = cube_world_matrix[(0, 2)];
-
-            matrix[(1, 0)] = cube_world_matrix[(1, 0)];
-            matrix[(1, 1)] = cube_world_matrix[(1, 1)];
-            matrix[(1, 2)] = cube_world_matrix[(1, 2)];
-
-            matrix[(2, 0)] = cube_world_matrix[(2, 0)];
-            matrix[(2, 1)] = cube_world_matrix[(2, 1)];
-            matrix[(2, 2)] = cube_world_matrix[(2, 2)];
-
-            // Adjoint is better as it always exists
-            // , unlike the inverse. The only difference
-            // is that the inverse is the adjoint divided by
-            // the determinant.
-            // So there is a scaling issue, but normals have
-            // be renormalized later anyways.
-            // Normal matrix would need to be transposed,
-            // but WGPU already expects matrices in row major form
-            // and we work with column major form.
-            // So by omitting transposition on our normal matrix in
-            // column major form, we provide WGPU with the transposed
-            // in row major form.
-            matrix.adjoint()
+    /// Free the buffers of a mesh no [MeshRenderer] uses anymore, its handle
+    /// is reused by the next mesh added.
+    fn remove_mesh(&mut self, handle: MeshHandle) {
+        self.meshes[handle.0] = None;
+        self.free_meshes.push(handle);
+    }
+
+    /// The pipeline of the main pass drawing meshes with [SkinnedVertex]s
+    /// if `skinned`, otherwise with [Vertex]s.
+    fn pipeline(&self, skinned: bool) -> &RenderPipeline {
+        let (fill, wireframe) = match &self.skinning {
+            Some(skinning) if skinned => (&skinning.pipeline, &skinning.wireframe_pipeline),
+            _ => (&self.render_pipeline, &self.wireframe_pipeline),
         };
+        match wireframe {
+            Some(wireframe) if self.wireframe => wireframe,
+            _ => fill,
+        }
+    }
 
-        self.entities[0].world_matrix = cube_world_matrix;
-        self.entities[0].normal_matrix = cube_normal_matrix;
+    /// The per object uniforms, with the skinning matrices if `skinned`.
+    fn object_bind_group(&self, skinned: bool) -> &BindGroup {
+        match &self.skinning {
+            Some(skinning) if skinned => skinning.joints.bind_group(),
+            _ => &self.object_uniforms.bind_group,
+        }
     }
 
-    pub fn render(
-        &self,
-        inner_size: &PhysicalSize<u32>,
-        surface: &Surface,
+    fn mesh(&self, handle: MeshHandle) -> &GpuMesh {
+        self.meshes[handle.0]
+            .as_ref()
+            .expect("removed meshes aren't rendered")
+    }
+
+    /// Create a [Material] sampling `texture` with a repeating sampler.
+    ///
+    /// Scene files refer to the material by `name`.
+    pub fn add_material(
+        &mut self,
+        device: &Device,
+        name: &str,
+        texture: Texture,
+    ) -> MaterialHandle {
+        let sampler = create_repeating_sampler(device);
+        self.materials.push(Material::new(
+            device,
+            &self.material_layout,
+            texture,
+            sampler,
+        ));
+        self.material_names.push(name.to_string());
+
+        MaterialHandle(self.materials.len() - 1)
+    }
+
+    /// The material added with `name`.
+    pub fn material(&self, name: &str) -> Option<MaterialHandle> {
+        self.material_names
+            .iter()
+            .position(|material| material == name)
+            .map(MaterialHandle)
+    }
+
+    /// Add the `heightmap` as terrain, split into tiles with a mesh for every
+    /// level of detail, see [terrain](crate::terrain).
+    ///
+    /// The tiles aren't saved to scene files, their meshes are named
+    /// `"{name} x z lod"` after the first sample and the level.
+    pub fn add_terrain(
+        &mut self,
         device: &Device,
         queue: &Queue,
-        camera: &Camera,
+        name: &str,
+        heightmap: &Heightmap,
+        settings: &TerrainSettings,
+        material: MaterialHandle,
     ) {
-        // Create render texture
-        let frame = surface
-            .get_current_texture()
-            .expect("failed to acquire next swap-chain texture");
-        let frame_view = frame
-            .texture
-            .create_view(&wgpu::TextureViewDescriptor::default());
-
-        // Create depth texture
-        let depth_texture = device.create_texture(&TextureDescriptor {
-            label: Some("depth texture"),
-            size: frame.texture.size(),
-            mip_level_count: 1, // no extra mips, has to be 1
-            sample_count: 1,    // no multisampling, so 1
-            dimension: wgpu::TextureDimension::D2,
-            format: wgpu::TextureFormat::Depth24Plus,
-            usage: TextureUsages::RENDER_ATTACHMENT,
-            view_formats: &[], // no special view format needed
-        });
-        let depth_view = depth_texture.create_view(&wgpu::TextureViewDescriptor::default());
-
-        for entity in &self.entities {
-            let padded_flattened_normal_matrix = [
-                entity.normal_matrix[(0, 0)],
-                entity.normal_matrix[(0, 1)],
-                entity.normal_matrix[(0, 2)],
-                0.0,
-                entity.normal_matrix[(1, 0)],
-                entity.normal_matrix[(1, 1)],
-                entity.normal_matrix[(1, 2)],
-                0.0,
-                entity.normal_matrix[(2, 0)],
-                entity.normal_matrix[(2, 1)],
-                entity.normal_matrix[(2, 2)],
-                0.0,
-            ];
-
-            let gpu_entity_bytes = entity
-                .world_matrix
-                .transpose()
-                .as_slices()
-                .iter()
-                .flatten()
-                .flat_map(|entry| entry.to_le_bytes())
-                .chain(
-                    padded_flattened_normal_matrix
-                        .as_slice()
-                        .iter()
-                        .flat_map(|entry| entry.to_le_bytes()),
-                )
-                .collect::<Vec<u8>>();
-
-            queue.write_buffer(
-                &self.entity_uniforms.0,
-                entity.uniform_offset as wgpu::BufferAddress,
-                &gpu_entity_bytes,
+        let cell_size = settings.cell_size;
+        for z in settings.tile_origins(heightmap.depth()) {
+            for x in settings.tile_origins(heightmap.width()) {
+                let levels = (0..settings.lod_levels.max(1))
+                    .map(|lod| {
+                        let mesh = tile_mesh(heightmap, settings, [x, z], lod);
+                        self.add_mesh(device, queue, &format!("{name} {x} {z} {lod}"), &mesh)
+                    })
+                    .collect::<Vec<_>>();
+
+                let offset = v![x as f32 * cell_size, 0.0, z as f32 * cell_size];
+                let origin = settings.origin + offset;
+                // The distance is measured to the middle of the tile.
+                let half_tile = settings.tile_cells as f32 * cell_size / 2.0;
+                let middle = v![
+                    (x + settings.tile_cells / 2).min(heightmap.width() - 1),
+                    (z + settings.tile_cells / 2).min(heightmap.depth() - 1)
+                ];
+                let center =
+                    origin + v![half_tile, heightmap.height(middle[0], middle[1]), half_tile];
+
+                let entity = self.add_object(
+                    levels[0],
+                    material,
+                    Transform::from_translation(origin.map(f64::from)),
+                );
+                self.entities.insert(entity, Generated);
+                self.entities.insert(
+                    entity,
+                    Lod {
+                        levels,
+                        center: center.map(f64::from),
+                        distance: settings.lod_distance,
+                    },
+                );
+            }
+        }
+    }
+
+    /// Describe the camera, the lights and the objects for a scene file,
+    /// without the [Generated] ones.
+    pub fn to_file(&self) -> SceneFile {
+        let camera = self.camera();
+        SceneFile {
+            version: SCENE_FORMAT_VERSION,
+            camera: Some(CameraDesc {
+                eye: [camera.eye()[0], camera.eye()[1], camera.eye()[2]],
+                pitch: camera.pitch_angle().to_degrees(),
+                yaw: camera.yaw_angle().to_degrees(),
+                roll: camera.roll_angle().to_degrees(),
+            }),
+            lights: self
+                .entities
+                .query::<Light>()
+                .map(|(_, light)| (*light).into())
+                .collect(),
+            objects: self
+                .entities
+                .query2::<Transform, MeshRenderer>()
+                .filter(|(entity, _, _)| self.entities.get::<Generated>(*entity).is_none())
+                .map(|(_, transform, renderer)| ObjectDesc {
+                    mesh: self.mesh(renderer.mesh).name.clone(),
+                    material: self.material_names[renderer.material.0].clone(),
+                    transform: (*transform).into(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Replace the lights and objects with the ones in `file` and move the camera.
+    /// [Generated] objects are kept.
+    ///
+    /// Nothing is changed if a mesh or material of the file is unknown.
+    pub fn load_file(&mut self, file: &SceneFile) -> Result<(), SceneFileError> {
+        let objects = file
+            .objects
+            .iter()
+            .map(|object| {
+                let mesh = self
+                    .find_mesh(&object.mesh)
+                    .ok_or_else(|| SceneFileError::UnknownMesh(object.mesh.clone()))?;
+                let material = self
+                    .material(&object.material)
+                    .ok_or_else(|| SceneFileError::UnknownMaterial(object.material.clone()))?;
+                Ok((
+                    MeshRenderer { mesh, material },
+                    Transform::from(object.transform),
+                ))
+            })
+            .collect::<Result<Vec<_>, SceneFileError>>()?;
+
+        let despawned = self
+            .entities
+            .query::<Light>()
+            .map(|(entity, _)| entity)
+            .chain(
+                self.entities
+                    .query::<MeshRenderer>()
+                    .filter(|(entity, _)| self.entities.get::<Generated>(*entity).is_none())
+                    .map(|(entity, _)| entity),
+            )
+            .collect::<Vec<_>>();
+        for entity in despawned {
+            self.entities.despawn(entity);
+        }
+
+        for light in &file.lights {
+            let entity = self.entities.spawn();
+            self.entities.insert(entity, Light::from(*light));
+        }
+        for (renderer, transform) in objects {
+            self.add_object(renderer.mesh, renderer.material, transform);
+        }
+        if let Some(camera) = file.camera {
+            self.camera_mut().set_pose(
+                camera.eye.into(),
+                camera.pitch.to_radians(),
+                camera.yaw.to_radians(),
+                camera.roll.to_radians(),
             );
         }
+        Ok(())
+    }
+
+    /// Save the scene as a RON [SceneFile] to `path`.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), SceneFileError> {
+        std::fs::write(path, self.to_file().to_ron()?)?;
+        Ok(())
+    }
+
+    /// Load a RON [SceneFile] from `path`, see [Scene::load_file].
+    pub fn load(&mut self, path: impl AsRef<Path>) -> Result<(), SceneFileError> {
+        let file = SceneFile::from_ron(&std::fs::read_to_string(path)?)?;
+        self.load_file(&file)
+    }
+
+    /// Spawn an entity rendering `mesh` with `material` at `transform`.
+    pub fn add_object(
+        &mut self,
+        mesh: MeshHandle,
+        material: MaterialHandle,
+        transform: Transform,
+    ) -> Entity {
+        let entity = self.entities.spawn();
+        self.entities.insert(entity, transform);
+        self.entities
+            .insert(entity, MeshRenderer { mesh, material });
+        entity
+    }
+
+    /// The entities of the scene.
+    ///
+    /// Entities with both a [Transform] and a [MeshRenderer] are drawn,
+    /// the first [Light] lights the scene.
+    pub fn entities(&self) -> &ecs::World {
+        &self.entities
+    }
+
+    /// The entity of the main camera, the one saved to scene files.
+    pub fn main_camera(&self) -> Entity {
+        self.camera
+    }
+
+    /// The main camera.
+    pub fn camera(&self) -> &Camera {
+        self.camera_of(self.camera)
+            .expect("the camera entity keeps its Camera")
+    }
+
+    pub fn camera_mut(&mut self) -> &mut Camera {
+        self.camera_of_mut(self.camera)
+            .expect("the camera entity keeps its Camera")
+    }
+
+    /// Spawn another camera, e.g. for a second [View].
+    pub fn add_camera(&mut self, camera: Camera) -> Entity {
+        let entity = self.entities.spawn();
+        self.entities.insert(entity, camera);
+        entity
+    }
+
+    /// The camera of `entity`, `None` if it has none.
+    pub fn camera_of(&self, entity: Entity) -> Option<&Camera> {
+        self.entities.get(entity)
+    }
+
+    pub fn camera_of_mut(&mut self, entity: Entity) -> Option<&mut Camera> {
+        self.entities.get_mut(entity)
+    }
+
+    /// Switch between rendering filled or wireframe polygons.
+    ///
+    /// Does nothing if the device doesn't support wireframe rendering.
+    pub fn toggle_wireframe(&mut self) {
+        self.wireframe = !self.wireframe && self.wireframe_pipeline.is_some();
+    }
+
+    pub fn wireframe(&self) -> bool {
+        self.wireframe
+    }
+
+    /// The color the views are cleared to before drawing the scene,
+    /// only visible without a skybox. Black by default.
+    pub fn set_clear_color(&mut self, color: Color) {
+        self.clear_color = color;
+    }
+
+    /// Draw `cubemap` behind the scene, `None` leaves the background in the
+    /// [clear color](Scene::set_clear_color).
+    pub fn set_skybox(&mut self, device: &Device, cubemap: Option<Cubemap>) {
+        self.skybox_pipeline.set_cubemap(device, cubemap);
+    }
+
+    /// The number of solid voxels in the world.
+    pub fn voxel_count(&self) -> usize {
+        self.world.block_count()
+    }
+
+    /// Lines to be drawn on top of the scene in the next frame.
+    pub fn debug_draw(&mut self) -> &mut DebugDraw {
+        &mut self.debug_draw
+    }
+
+    /// The worker threads of the scene, e.g. for loading assets.
+    pub fn jobs(&self) -> &Jobs {
+        &self.jobs
+    }
+
+    /// Stream in the chunks around the main camera, a few per call, and
+    /// remove the ones out of range.
+    pub fn stream_chunks(&mut self, device: &Device, queue: &Queue) {
+        let eye = self.camera().eye() - VOXEL_ORIGIN;
+        for coord in self.streamer.update(eye, &self.world) {
+            self.unload_chunk(coord);
+        }
+        for streamed in self.streamer.finished() {
+            self.load_chunk(device, queue, streamed);
+        }
+    }
+
+    /// Load every chunk around the main camera at once, waiting for them
+    /// to be generated, e.g. before capturing a frame.
+    pub fn finish_streaming(&mut self, device: &Device, queue: &Queue) {
+        self.stream_chunks(device, queue);
+        while let Some(streamed) = self.streamer.wait() {
+            self.load_chunk(device, queue, streamed);
+        }
+    }
+
+    fn load_chunk(&mut self, device: &Device, queue: &Queue, streamed: StreamedChunk) {
+        let StreamedChunk { coord, chunk, mesh } = streamed;
+        self.world.insert_chunk(coord, chunk);
+        self.spawn_chunk(device, queue, coord, mesh);
+    }
+
+    /// Add the object of the chunk at `coord` with its `mesh`, meshing it
+    /// here if there is none.
+    fn spawn_chunk(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        coord: ChunkCoord,
+        mesh: Option<Mesh>,
+    ) {
+        let name = format!("chunk {} {} {}", coord[0], coord[1], coord[2]);
+        let span = tracing::debug_span!("mesh_chunk", ?coord).entered();
+        let mesh = match mesh {
+            Some(mesh) if mesh.indices().is_empty() => None,
+            Some(mesh) => Some(self.add_mesh(device, queue, &name, &mesh)),
+            None => match &self.gpu_mesher {
+                Some(mesher) => mesher
+                    .mesh(device, queue, &self.world, coord)
+                    .map(|chunk_mesh| self.add_chunk_mesh(&name, chunk_mesh)),
+                None => {
+                    let mesh = greedy_mesh(&self.world, coord);
+                    (!mesh.indices().is_empty()).then(|| self.add_mesh(device, queue, &name, &mesh))
+                }
+            },
+        };
+        span.exit();
+        let Some(mesh) = mesh else {
+            return;
+        };
+
+        let origin = coord.map(|value| f64::from(value * CHUNK_SIZE as i32));
+        let translation = origin + VOXEL_ORIGIN;
+        let entity = self.add_object(
+            mesh,
+            self.chunk_material,
+            Transform::from_translation(translation),
+        );
+        self.entities.insert(entity, Generated);
+        self.chunk_objects.insert(coord, (entity, mesh));
+    }
+
+    fn unload_chunk(&mut self, coord: ChunkCoord) {
+        self.world.remove_chunk(coord);
+        self.despawn_chunk(coord);
+    }
+
+    fn despawn_chunk(&mut self, coord: ChunkCoord) {
+        if let Some((entity, mesh)) = self.chunk_objects.remove(&coord) {
+            self.entities.despawn(entity);
+            self.remove_mesh(mesh);
+        }
+    }
+
+    /// Mesh the chunks with edited blocks again.
+    fn remesh_chunks(&mut self, device: &Device, queue: &Queue) {
+        let mut dirty = self.world.take_dirty_chunks();
+        for coord in &dirty {
+            self.despawn_chunk(*coord);
+        }
+        dirty.retain(|coord| self.world.chunk(*coord).is_some());
+        // The GPU mesher records the passes here, otherwise the chunks are
+        // meshed on the workers.
+        let meshes = match &self.gpu_mesher {
+            Some(_) => dirty.iter().map(|_| None).collect(),
+            None => {
+                let world = &self.world;
+                self.jobs
+                    .map(&dirty, |coord| Some(greedy_mesh(world, *coord)))
+            }
+        };
+        for (coord, mesh) in dirty.into_iter().zip(meshes) {
+            self.spawn_chunk(device, queue, coord, mesh);
+        }
+    }
+
+    /// The voxel world, edits are shown after the next [Scene::prepare].
+    ///
+    /// Chunks are streamed in and out around the main camera, blocks can
+    /// only be changed in the loaded ones.
+    pub fn world_mut(&mut self) -> &mut World {
+        &mut self.world
+    }
+
+    /// The block in the middle of the main camera's view, up to
+    /// `max_distance` away. The hit is in block coordinates.
+    pub fn pick_block(&self, max_distance: f32) -> Option<RaycastHit> {
+        let camera = self.camera();
+        let (start, origin) = split_block(camera.eye() - VOXEL_ORIGIN);
+        let ray = Ray::new(origin, camera.look_direction());
+        self.world.raycast(start, &ray, max_distance)
+    }
+
+    /// Outline the block at `position` in the next frame.
+    pub fn highlight_block(&mut self, position: [i32; 3]) {
+        // Slightly larger, so the outline isn't hidden by the faces.
+        let min = Vector::from_array(position.map(|value| f64::from(value) - 0.01)) + VOXEL_ORIGIN;
+        let max = min + v![1.02, 1.02, 1.02];
+        self.debug_draw.aabb(min, max, Color::WHITE);
+    }
+
+    /// The center of the block at `position` in world space.
+    pub fn block_center(&self, position: [i32; 3]) -> Vector<f64, 3> {
+        Vector::from_array(position.map(|value| f64::from(value) + 0.5)) + VOXEL_ORIGIN
+    }
+
+    /// The mesh added with `name`.
+    fn find_mesh(&self, name: &str) -> Option<MeshHandle> {
+        self.meshes
+            .iter()
+            .position(|mesh| mesh.as_ref().is_some_and(|mesh| mesh.name == name))
+            .map(MeshHandle)
+    }
+
+    /// Spawn a [RigidBody] of `shape` and `mass` at `transform`, drawn
+    /// with `material`. The scale of the transform is set to the shape.
+    ///
+    /// Bodies move in [Scene::simulate], colliding with each other, the
+    /// blocks and the colliders. They aren't saved to scene files.
+    pub fn add_body(
+        &mut self,
+        shape: Shape,
+        mass: f32,
+        transform: Transform,
+        material: MaterialHandle,
+    ) -> Entity {
+        // Both built-in meshes are 2 wide.
+        let (mesh, scale) = match shape {
+            Shape::Sphere { radius } => ("sphere", v![radius, radius, radius]),
+            Shape::Cuboid { half_extents } => ("cube", half_extents),
+        };
+        let mesh = self
+            .find_mesh(mesh)
+            .expect("the built-in meshes are never removed");
+        let entity = self.add_object(mesh, material, transform.with_scale(scale));
+        self.entities.insert(entity, RigidBody::new(shape, mass));
+        self.entities.insert(entity, Generated);
+        entity
+    }
+
+    /// Throw a block textured body from the main camera along its view
+    /// with `speed` m/s.
+    pub fn throw_body(&mut self, shape: Shape, mass: f32, speed: f32) -> Entity {
+        let camera = self.camera();
+        let direction = camera.look_direction();
+        // Far enough not to start out inside of the player.
+        let start = camera.eye() + direction.map(f64::from);
+        let entity = self.add_body(
+            shape,
+            mass,
+            Transform::from_translation(start),
+            self.chunk_material,
+        );
+        let body = self
+            .entities
+            .get_mut::<RigidBody>(entity)
+            .expect("the body was just added");
+        // Let go of it a bit below the center, so that it spins.
+        body.apply_impulse(direction * (speed * mass), v![0.0, -0.1, 0.0]);
+        entity
+    }
+
+    /// Spawn a [Unit] standing on `position`, drawn as a box.
+    pub fn add_unit(&mut self, position: Vector<f64, 3>) -> Entity {
+        let unit = Unit::default();
+        let cube = self
+            .find_mesh("cube")
+            .expect("the built-in meshes are never removed");
+        let half_height = v![0.0, f64::from(unit.half_extents[1]), 0.0];
+        let transform =
+            Transform::from_translation(position + half_height).with_scale(unit.half_extents);
+        let entity = self.add_object(cube, self.chunk_material, transform);
+        self.entities.insert(entity, unit);
+        self.entities.insert(entity, Generated);
+        entity
+    }
+
+    /// Spawn a box at `transform`, moved by `animator`.
+    ///
+    /// Like the units, animated boxes aren't saved to scene files.
+    pub fn add_animated_box(
+        &mut self,
+        transform: Transform,
+        animator: TransformAnimator,
+    ) -> Entity {
+        let cube = self
+            .find_mesh("cube")
+            .expect("the built-in meshes are never removed");
+        let entity = self.add_object(cube, self.chunk_material, transform);
+        self.entities.insert(entity, animator);
+        self.entities.insert(entity, Generated);
+        entity
+    }
+
+    /// Spawn the particle `emitter` at `position`.
+    ///
+    /// Like the units, emitters aren't saved to scene files.
+    pub fn add_emitter(&mut self, position: Vector<f64, 3>, emitter: Emitter) -> Entity {
+        let entity = self.entities.spawn();
+        self.entities
+            .insert(entity, Transform::from_translation(position));
+        self.entities.insert(entity, emitter);
+        self.entities.insert(entity, Generated);
+        entity
+    }
+
+    /// Spawn the skinned `model` at `transform`, playing its first
+    /// animation over and over again.
+    ///
+    /// Like the units, models aren't saved to scene files.
+    pub fn add_skinned_model(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        name: &str,
+        model: SkinnedModel,
+        transform: Transform,
+    ) -> Entity {
+        let mesh = self.add_skinned_mesh(device, queue, name, &model.mesh);
+        let entity = self.add_object(mesh, self.chunk_material, transform);
+        self.entities
+            .insert(entity, Skin::new(Arc::new(model.skeleton)));
+        if let Some(animation) = model.animations.into_iter().next() {
+            self.entities
+                .insert(entity, Animator::looping(Arc::new(animation)));
+        }
+        self.entities.insert(entity, Generated);
+        entity
+    }
+
+    pub fn selection(&self) -> &Selection {
+        &self.selection
+    }
+
+    /// Select the units in `rect`, dragged over `view` in a target of `size`.
+    pub fn select_units(&mut self, view: &View, size: PhysicalSize<u32>, rect: &ScreenRect) {
+        let picked = pick_units(&self.entities, view, size, rect);
+        self.selection.replace(picked);
+    }
+
+    /// Order the selected units to where `ray` hits the blocks or the
+    /// colliders, in world space relative to the main camera's eye like
+    /// the [picking rays](View::picking_ray).
+    ///
+    /// Returns `false` if nothing is selected or hit.
+    pub fn order_move(&mut self, ray: &Ray) -> bool {
+        if self.selection.is_empty() {
+            return false;
+        }
+        let Some(distance) = self.ground_distance(ray, ORDER_REACH) else {
+            return false;
+        };
+        let eye = self.camera().eye();
+        let target = eye + (ray.origin + ray.direction * distance).map(f64::from);
+
+        let units = self
+            .selection
+            .iter()
+            .filter_map(|entity| {
+                let unit = *self.entities.get::<Unit>(entity)?;
+                let translation = self.entities.get::<Transform>(entity)?.translation;
+                Some((entity, unit, translation))
+            })
+            .collect::<Vec<_>>();
+        let spots = formation(target, units.len(), FORMATION_SPACING);
+        let orders = units.into_iter().zip(spots).collect::<Vec<_>>();
+        let origin = local_origin(eye);
+        let geometry = static_geometry(&self.world, &self.colliders, origin);
+        let local = |position: Vector<f64, 3>| (position - origin).map(|value| value as f32);
+        // Every unit searches its own way, in parallel.
+        let routes = self.jobs.map(&orders, |((_, unit, translation), spot)| {
+            // Routes are planned for the feet, the waypoints are the middle
+            // of the unit standing there.
+            let half_height = v![0.0, unit.half_extents[1], 0.0];
+            route(
+                &geometry,
+                local(*translation) - half_height,
+                local(*spot),
+                unit.half_extents[1] * 2.0,
+            )
+            .into_iter()
+            .map(|waypoint| origin + (waypoint + half_height).map(f64::from))
+            .collect::<Vec<_>>()
+        });
+        for (((entity, _, _), _), waypoints) in orders.into_iter().zip(routes) {
+            self.entities.insert(entity, MoveOrder { waypoints });
+        }
+        true
+    }
+
+    /// How far along `ray` in world space relative to the main camera's eye
+    /// the first block or collider is, up to `max_distance`.
+    fn ground_distance(&self, ray: &Ray, max_distance: f32) -> Option<f32> {
+        let eye = self.camera().eye();
+        let (start, origin) = split_block(eye + ray.origin.map(f64::from) - VOXEL_ORIGIN);
+        let in_blocks = Ray {
+            origin,
+            direction: ray.direction,
+        };
+        let block = self
+            .world
+            .raycast(start, &in_blocks, max_distance)
+            .map(|hit| hit.distance);
+        let offset = eye.map(|value| -value as f32);
+        self.colliders
+            .iter()
+            .filter_map(|collider| collider.translated(offset).ray_distance(ray))
+            .filter(|distance| *distance <= max_distance)
+            .chain(block)
+            .min_by(f32::total_cmp)
+    }
+
+    /// Outline the selected units and their orders in the next frame.
+    pub fn highlight_selection(&mut self) {
+        self.selection.retain_alive(&self.entities);
+        draw_selection(&mut self.debug_draw, &self.entities, &self.selection);
+    }
+
+    /// Outline `rect` while it is dragged over `view` in a target of `size`.
+    pub fn highlight_rect(&mut self, view: &View, size: PhysicalSize<u32>, rect: &ScreenRect) {
+        if let Some(camera) = self.entities.get::<Camera>(view.camera()) {
+            draw_rect(&mut self.debug_draw, view, camera, size, rect);
+        }
+    }
+
+    /// Make `aabb` solid for the player and the bodies, in world space.
+    pub fn add_collider(&mut self, aabb: Aabb) {
+        self.colliders.push(aabb);
+    }
+
+    /// Attach the main camera to a player standing where it is, or go
+    /// back to flying around freely.
+    pub fn set_walking(&mut self, walking: bool) {
+        self.player = walking.then(|| {
+            let settings = CharacterSettings::default();
+            let feet = self.camera().eye() - v![0.0, f64::from(settings.eye_height), 0.0];
+            CharacterController::new(feet, settings)
+        });
+    }
+
+    /// Whether the main camera is attached to the player.
+    pub fn walking(&self) -> bool {
+        self.player.is_some()
+    }
+
+    /// Move the player for `delta` with `forward` and `right` meters per
+    /// second along where the main camera looks, and the camera with it.
+    ///
+    /// Does nothing unless [walking](Scene::walking).
+    pub fn walk(&mut self, forward: f32, right: f32, jump: bool, delta: Duration) {
+        let Some(player) = &mut self.player else {
+            return;
+        };
+        // Yawing turns -Z, where the camera looks at first, towards -X.
+        let camera = self
+            .entities
+            .get::<Camera>(self.camera)
+            .expect("the camera entity keeps its Camera");
+        let (sin, cos) = camera.yaw_angle().sin_cos();
+        let ahead = v![-sin, 0.0, -cos];
+        let beside = v![cos, 0.0, -sin];
+        let origin = local_origin(camera.eye());
+
+        // A long frame would otherwise move the player in one big leap.
+        let delta = delta.min(MAX_WALK_STEP).as_secs_f32();
+        player.update(
+            &static_geometry(&self.world, &self.colliders, origin),
+            origin,
+            ahead * forward + beside * right,
+            jump,
+            delta,
+        );
+
+        let eye = player.eye();
+        self.camera_mut().set_eye(eye);
+    }
+
+    /// Advance the entities to `time`, running every system of the schedule.
+    #[tracing::instrument(skip_all)]
+    pub fn simulate(&mut self, time: &Time) {
+        self.schedule.run(&mut self.entities, time);
+        for _ in 0..time.fixed_steps() {
+            self.step_bodies(time.fixed_delta());
+        }
+
+        // Visualize the world origin and the bounds of the cube.
+        let wireframe = self.wireframe;
+        let debug_draw = self.debug_draw();
+        debug_draw.axis(v![0.0, 0.0, 0.0], 2.0);
+        debug_draw.aabb(v![-1.0, -1.0, -1.0], v![1.0, 1.0, 1.0], Color::YELLOW);
+        // In wireframe mode the plane is just two triangles, a grid reads better.
+        if wireframe {
+            debug_draw.grid(v![0.0, -1.0, 0.0], 1.0, 6, Color::GRAY);
+        }
+    }
+
+    /// Move the [RigidBody]s by one fixed step of `delta`, around the
+    /// [local origin](local_origin) of the main camera.
+    fn step_bodies(&mut self, delta: Duration) {
+        let origin = local_origin(self.camera().eye());
+        let (entities, mut bodies): (Vec<_>, Vec<_>) = self
+            .entities
+            .query2::<Transform, RigidBody>()
+            .map(|(entity, transform, body)| {
+                (entity, (transform.relative_to(origin), body.clone()))
+            })
+            .unzip();
+        if bodies.is_empty() {
+            return;
+        }
+
+        self.dynamics.step(
+            &mut bodies,
+            &static_geometry(&self.world, &self.colliders, origin),
+            delta.as_secs_f32(),
+        );
+
+        for (entity, (relative, body)) in entities.into_iter().zip(bodies) {
+            let transform = Transform::from_relative(&relative, origin);
+            if transform.translation[1] < LOWEST_BODY {
+                self.entities.despawn(entity);
+                continue;
+            }
+            self.entities.insert(entity, transform);
+            self.entities.insert(entity, body);
+        }
+    }
+
+    /// Create the buffers for rendering into targets of `size`.
+    pub fn create_target(&self, device: &Device, size: PhysicalSize<u32>) -> RenderTarget {
+        RenderTarget::new(device, size, &self.post_process, self.sample_count)
+    }
+
+    /// The passes turning the rendered HDR image into the frame.
+    pub fn post_process(&self) -> &PostProcess {
+        &self.post_process
+    }
+
+    pub fn post_process_mut(&mut self) -> &mut PostProcess {
+        &mut self.post_process
+    }
+
+    /// The format of the targets the scene renders into.
+    pub fn color_format(&self) -> TextureFormat {
+        self.color_format
+    }
+
+    /// Number of samples per pixel, 1 without multisampling.
+    pub fn sample_count(&self) -> u32 {
+        self.sample_count
+    }
+
+    /// GPU time of the shadow, the main and the post-processing pass, see
+    /// [GpuTimer::pass_times].
+    pub fn gpu_pass_times(&self) -> Option<Vec<(&'static str, Duration)>> {
+        self.gpu_timer.pass_times()
+    }
+
+    /// Upload the streamed chunks, objects and debug lines of the frame,
+    /// shared by every [View] rendered afterwards.
+    #[tracing::instrument(skip_all)]
+    pub fn prepare(&mut self, device: &Device, queue: &Queue) {
+        self.stream_chunks(device, queue);
+        self.remesh_chunks(device, queue);
+
+        self.render_origin = self.camera().eye();
+        let renderers = self.entities.query2::<Transform, MeshRenderer>().count();
+        self.object_uniforms.reserve(device, renderers);
+        self.objects.clear();
+        self.joint_matrices.clear();
 
         let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
-            label: Some("encoder"),
+            label: Some("upload_encoder"),
         });
+        let upload_span = tracing::debug_span!("upload_uniforms", renderers).entered();
+        let alignment = self.object_uniforms.alignment;
+        // All uniforms are written in one go, straight into the staging memory.
+        self.uploads.write_with(
+            &mut encoder,
+            &self.object_uniforms.buffer,
+            0,
+            renderers as BufferAddress * alignment,
+            |bytes| {
+                let renderers = self.entities.query2::<Transform, MeshRenderer>();
+                for (entity, transform, renderer) in renderers {
+                    let skinned = self.meshes[renderer.mesh.0]
+                        .as_ref()
+                        .expect("removed meshes aren't rendered")
+                        .skinned;
+                    let first_joint = self.joint_matrices.len() as u32;
+                    if skinned {
+                        // Nothing to skin the mesh with.
+                        let Some(skin) = self.entities.get::<Skin>(entity) else {
+                            continue;
+                        };
+                        if self.skinning.is_none() {
+                            continue;
+                        }
+                        self.joint_matrices.extend(&skin.matrices);
+                    }
+
+                    let uniform_offset = self.objects.len() as BufferAddress * alignment;
+                    let world_matrix = transform.matrix_relative_to(self.render_origin);
+                    // The shader reads matrices column by column, so the
+                    // columns are uploaded one after the other, each padded to
+                    // a `vec4f`.
+                    let normal_matrix = normal_matrix(&world_matrix);
+                    let column = |j| {
+                        let column = normal_matrix.col(j);
+                        v![column[0], column[1], column[2], 0.0]
+                    };
+                    let uniform = ObjectUniform {
+                        world_matrix: world_matrix.to_cols_array(),
+                        normal_matrix: [column(0), column(1), column(2)],
+                        first_joint,
+                        _padding: [0; 3],
+                    };
+
+                    let start = uniform_offset as usize;
+                    bytes[start..start + OBJECT_UNIFORM_SIZE as usize]
+                        .copy_from_slice(bytemuck::bytes_of(&uniform));
+                    self.objects.push(RenderObject {
+                        mesh: renderer.mesh,
+                        material: renderer.material,
+                        uniform_offset: uniform_offset as wgpu::DynamicOffset,
+                        skinned,
+                    });
+                }
+            },
+        );
+        // Drawn last, switching the pipelines only once.
+        self.objects.sort_by_key(|object| object.skinned);
+
+        if let Some(skinning) = &mut self.skinning
+            && !self.joint_matrices.is_empty()
+        {
+            skinning.joints.reserve(device, self.joint_matrices.len());
+            skinning.joints.bind(device, &self.object_uniforms.buffer);
+            self.uploads.write_with(
+                &mut encoder,
+                skinning.joints.buffer(),
+                0,
+                self.joint_matrices.len() as BufferAddress * MATRIX_SIZE,
+                |bytes| write_matrices(&self.joint_matrices, bytes),
+            );
+        }
+        upload_span.exit();
+
+        self.particle_pipeline.flush(
+            device,
+            &mut self.uploads,
+            &mut encoder,
+            self.entities.query::<Emitter>().map(|(_, emitter)| emitter),
+            self.render_origin,
+        );
+        self.debug_pipeline.flush(
+            device,
+            &mut self.uploads,
+            &mut encoder,
+            &mut self.debug_draw,
+            self.render_origin,
+        );
+        self.uploads.submit(queue, encoder);
+    }
+
+    /// Render the objects [prepared](Scene::prepare) for the frame into
+    /// `texture`, once for each of the `views` in order, with the `overlay`
+    /// on top of them.
+    ///
+    /// Only the first view clears the frame, later ones are drawn over the
+    /// earlier ones inside their viewport. Where they draw nothing, not even
+    /// a sky, the earlier views stay visible. Views with an empty viewport
+    /// are skipped. `target` has to match the size of the texture.
+    pub fn render(
+        &mut self,
+        target: &RenderTarget,
+        views: &[View],
+        texture: &wgpu::Texture,
+        device: &Device,
+        queue: &Queue,
+        overlay: Option<&mut Overlay>,
+    ) -> DrawStats {
+        let _span = tracing::info_span!("render").entered();
+        self.gpu_timer.begin_frame(device);
+        let mut stats = DrawStats::default();
+
+        let frame_view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let frame_size = target.size();
+
+        let light = self
+            .entities
+            .query::<Light>()
+            .next()
+            .map(|(_, light)| *light)
+            .unwrap_or_default();
+
+        let draw_overlay = overlay.is_some();
+        if let Some(overlay) = overlay {
+            self.overlay_pipeline.flush(
+                device,
+                queue,
+                [frame_size.width as f32, frame_size.height as f32],
+                overlay,
+            );
+        }
+
+        let views = views
+            .iter()
+            .map(|view| (view, view.viewport().pixels(frame_size)))
+            .filter(|(_, [_, _, width, height])| *width > 0 && *height > 0)
+            .collect::<Vec<_>>();
+        for (index, (view, [x, y, width, height])) in views.iter().copied().enumerate() {
+            let (first, last) = (index == 0, index + 1 == views.len());
+            let camera = self
+                .camera_of(view.camera())
+                .expect("the camera of a view keeps its Camera");
+            // Relative to the origin of the frame, like the objects.
+            let eye = (camera.eye() - self.render_origin).map(|value| value as f32);
+            let look_at = camera.as_relative_transform_matrix(self.render_origin);
+
+            // Every view writes the uniforms, so each one is submitted on its own.
+            let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("encoder"),
+            });
+            // Only the first view is timed.
+            let timestamp_writes = |pass, first_pass, last_pass| {
+                if first {
+                    self.gpu_timer
+                        .span_timestamp_writes(pass, first_pass, last_pass)
+                } else {
+                    None
+                }
+            };
+
+            let projection = view.projection(width, height);
+            let forward = camera.look_direction();
+            let cascades = self
+                .shadow_map
+                .cascades(light.direction(), eye, forward, &projection);
+            for (index, cascade) in cascades.iter().enumerate() {
+                let _span = tracing::debug_span!("shadow_pass", cascade = index).entered();
+                // The cascades are timed together as the shadow pass.
+                let timestamp_writes = timestamp_writes(0, index == 0, index + 1 == cascades.len());
+                let mut shadow_pass = self.shadow_map.begin_pass(
+                    &mut encoder,
+                    queue,
+                    index,
+                    cascade,
+                    timestamp_writes,
+                );
+                // The skinned objects come last.
+                let mut skinned = false;
+                for object in &self.objects {
+                    if object.skinned && !skinned {
+                        skinned = true;
+                        if let Some(pipeline) = self.shadow_map.skinned_pipeline() {
+                            shadow_pass.set_pipeline(pipeline);
+                        }
+                    }
+                    let mesh = self.mesh(object.mesh);
+                    shadow_pass.set_bind_group(
+                        1,
+                        self.object_bind_group(object.skinned),
+                        &[object.uniform_offset],
+                    );
+                    shadow_pass.set_index_buffer(mesh.index_buffer.slice(..), mesh.index_format);
+                    shadow_pass.set_vertex_buffer(0, mesh.vertex_buffer.slice(..));
+                    shadow_pass.draw_indexed(0..mesh.index_count as u32, 0, 0..1);
+                    stats.add(mesh.index_count as u32, PrimitiveTopology::TriangleList);
+                }
+            }
+
+            {
+                let _span = tracing::debug_span!("main_pass").entered();
+                // The first view clears the HDR image, the others draw over it.
+                let load = if first {
+                    wgpu::LoadOp::Clear(wgpu_color(self.clear_color))
+                } else {
+                    wgpu::LoadOp::Load
+                };
+                let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                    label: Some("render_pass"),
+                    color_attachments: &[Some(match target.msaa_view() {
+                        // The samples are kept for the views still to come,
+                        // after the last one only the resolved image is needed.
+                        Some(msaa_view) => wgpu::RenderPassColorAttachment {
+                            view: msaa_view,
+                            depth_slice: None,
+                            resolve_target: Some(target.post().hdr_view()),
+                            ops: wgpu::Operations {
+                                load,
+                                store: if last {
+                                    wgpu::StoreOp::Discard
+                                } else {
+                                    wgpu::StoreOp::Store
+                                },
+                            },
+                        },
+                        None => wgpu::RenderPassColorAttachment {
+                            view: target.post().hdr_view(),
+                            depth_slice: None,
+                            resolve_target: None,
+                            ops: wgpu::Operations {
+                                load,
+                                store: wgpu::StoreOp::Store,
+                            },
+                        },
+                    })],
+                    depth_stencil_attachment: Some(RenderPassDepthStencilAttachment {
+                        view: target.depth_view(),
+                        depth_ops: Some(Operations {
+                            // Reverse-Z, 0.0 is infinitely far away.
+                            load: wgpu::LoadOp::Clear(0.0),
+                            store: wgpu::StoreOp::Store,
+                        }),
+                        stencil_ops: None,
+                    }),
+                    timestamp_writes: timestamp_writes(1, true, true),
+                    occlusion_query_set: None,
+                    multiview_mask: None,
+                });
+                render_pass.set_viewport(x as f32, y as f32, width as f32, height as f32, 0.0, 1.0);
+                render_pass.set_scissor_rect(x, y, width, height);
+                render_pass.set_pipeline(self.pipeline(false));
 
+                // view matrix
+                let view_matrix = look_at;
+
+                let projection_matrix = projection.matrix();
+                self.skybox_pipeline
+                    .update(queue, view_matrix, projection_matrix);
+                self.particle_pipeline
+                    .update(queue, view_matrix, projection_matrix);
+
+                let view_projection_matrix = projection_matrix * view_matrix;
+
+                // UPDATE Uniforms
+
+                let global_uniforms = GlobalUniforms {
+                    view_projection: view_projection_matrix.to_cols_array(),
+                    light: light.to_uniform(),
+                    shadows: ShadowUniforms::new(&cascades, eye, forward),
+                };
+
+                queue.write_buffer(
+                    &self.global_uniforms.0,
+                    0,
+                    bytemuck::bytes_of(&global_uniforms),
+                );
+                render_pass.set_bind_group(0, &self.global_uniforms.1, &[]);
+                render_pass.set_bind_group(3, self.shadow_map.bind_group(), &[]);
+
+                // objects
+                let mut skinned = false;
+                for object in &self.objects {
+                    if object.skinned && !skinned {
+                        skinned = true;
+                        render_pass.set_pipeline(self.pipeline(true));
+                    }
+                    let mesh = self.mesh(object.mesh);
+                    render_pass.set_bind_group(
+                        1,
+                        self.object_bind_group(object.skinned),
+                        &[object.uniform_offset],
+                    );
+                    render_pass.set_bind_group(
+                        2,
+                        self.materials[object.material.0].bind_group(),
+                        &[],
+                    );
+                    render_pass.set_index_buffer(mesh.index_buffer.slice(..), mesh.index_format);
+                    render_pass.set_vertex_buffer(0, mesh.vertex_buffer.slice(..));
+                    render_pass.draw_indexed(0..mesh.index_count as u32, 0, 0..1);
+                    stats.add(mesh.index_count as u32, PrimitiveTopology::TriangleList);
+                }
+
+                // Only fills what the objects left empty.
+                let sky = self.skybox_pipeline.draw(&mut render_pass);
+                stats.add(sky, PrimitiveTopology::TriangleList);
+                // Translucent, blended over the sky as well.
+                let particles = self.particle_pipeline.draw(&mut render_pass);
+                stats.add(particles, PrimitiveTopology::TriangleList);
+                // The skybox and the particles took over group 0, the debug
+                // lines need the globals back.
+                render_pass.set_bind_group(0, &self.global_uniforms.1, &[]);
+                let lines = self.debug_pipeline.draw(&mut render_pass);
+                stats.add(lines, PrimitiveTopology::LineList);
+            }
+
+            let _span = tracing::debug_span!("submit").entered();
+            queue.submit(Some(encoder.finish()));
+        }
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("post_encoder"),
+        });
         {
+            let _span = tracing::debug_span!("post_process").entered();
+            let passes = self.post_process.run(
+                &mut encoder,
+                queue,
+                target.post(),
+                &frame_view,
+                |first, last| self.gpu_timer.span_timestamp_writes(2, first, last),
+            );
+            for _ in 0..passes {
+                stats.add(3, PrimitiveTopology::TriangleList);
+            }
+        }
+
+        // The overlay covers the whole frame, on top of every view.
+        if draw_overlay {
+            let _span = tracing::debug_span!("overlay_pass").entered();
             let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-                label: Some("render_pass"),
+                label: Some("overlay_pass"),
                 color_attachments: &[Some(wgpu::RenderPassColorAttachment {
                     view: &frame_view,
                     depth_slice: None,
                     resolve_target: None,
                     ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                        load: wgpu::LoadOp::Load,
                         store: wgpu::StoreOp::Store,
                     },
                 })],
-                depth_stencil_attachment: Some(RenderPassDepthStencilAttachment {
-                    view: &depth_view,
-                    depth_ops: Some(Operations {
-                        load: wgpu::LoadOp::Clear(1.0),
-                        store: wgpu::StoreOp::Store,
-                    }),
-                    stencil_ops: None,
-                }),
+                depth_stencil_attachment: None,
                 timestamp_writes: None,
                 occlusion_query_set: None,
                 multiview_mask: None,
             });
-            render_pass.set_pipeline(&self.render_pipeline);
-
-            // the camera matrix
-            let look_at = camera.as_transform_matrix();
-            // view matrix
-            let view_matrix = look_at;
-
-            let aspect_ratio = inner_size.width as f32 / inner_size.height as f32;
-            let projection_matrix = graphic::transform::perspective_proj_sym_h_fov(
-                PI / 2.0,
-                aspect_ratio,
-                -1.0,
-                -20000.0,
-            );
+            let overlay = self.overlay_pipeline.draw(&mut render_pass);
+            stats.add(overlay, PrimitiveTopology::TriangleList);
+        }
 
-            let view_projection_matrix = projection_matrix * view_matrix;
-
-            // Serialize to the gpu
-            // WGPU works with row major matrices
-
-            let view_projection_matrix = view_projection_matrix.transpose();
-
-            // UPDATE Uniforms
-
-            let global_uniforms = view_projection_matrix
-                .as_slices()
-                .iter()
-                .flatten()
-                .flat_map(|entry| entry.to_le_bytes())
-                .chain(
-                    // light color
-                    [0.2f32, 1.0, 0.2, 1.0]
-                        .iter()
-                        .flat_map(|entry| entry.to_le_bytes()),
-                )
-                .chain(
-                    // light position
-                    // last value is padding
-                    [-10.0f32, 10.0, 10.0, 0.0]
-                        .iter()
-                        .flat_map(|entry| entry.to_le_bytes()),
-                )
-                .chain(
-                    // view position
-                    [camera.eye()[0], camera.eye()[1], camera.eye()[2]]
-                        .iter()
-                        .flat_map(|entry| entry.to_le_bytes()),
-                )
-                // shininess
-                .chain([100.0f32].iter().flat_map(|entry| entry.to_le_bytes()))
-                .chain(
-                    // light direction
-                    ((v![1.0f32, -1.0, -1.0]).normalized())
-                        .as_slice()
-                        .iter()
-                        .flat_map(|entry| entry.to_le_bytes()),
-                )
-                .chain(
-                    [(10.0f32 * (PI / 180.0f32)).cos()]
-                        .iter()
-                        .flat_map(|entry| entry.to_le_bytes()),
-                )
-                .collect::<Vec<u8>>();
-
-            queue.write_buffer(&self.global_uniforms.0, 0, &global_uniforms);
-            render_pass.set_bind_group(0, &self.global_uniforms.1, &[]);
-
-            // entities
-            for entity in &self.entities {
-                render_pass.set_bind_group(1, &self.entity_uniforms.1, &[entity.uniform_offset]);
-                render_pass.set_index_buffer(entity.index_buffer.slice(..), entity.index_format);
-                render_pass.set_vertex_buffer(0, entity.vertex_buffer.slice(..));
-                render_pass.draw_indexed(0..entity.index_count as u32, 0, 0..1);
-            }
+        self.gpu_timer.resolve(&mut encoder);
+        {
+            let _span = tracing::debug_span!("submit").entered();
+            queue.submit(Some(encoder.finish()));
+        }
+        self.gpu_timer.end_frame();
+        stats
+    }
+}
+
+/// The vertex and index buffers of `mesh`.
+fn upload_mesh<V: Pod>(device: &Device, queue: &Queue, mesh: &Mesh<V>) -> ChunkMesh {
+    let vertex_data: &[u8] = bytemuck::cast_slice(mesh.vertices());
+
+    let vertex_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("vertex_buffer"),
+        size: vertex_data.len() as u64,
+        usage: BufferUsages::VERTEX | BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+    queue.write_buffer(&vertex_buffer, 0, vertex_data);
+
+    let index_data: &[u8] = bytemuck::cast_slice(mesh.indices());
+    let index_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("index_buffer"),
+        size: index_data.len() as u64,
+        usage: BufferUsages::INDEX | BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+    queue.write_buffer(&index_buffer, 0, index_data);
+
+    ChunkMesh {
+        vertex_buffer,
+        index_buffer,
+        index_count: mesh.indices().len(),
+    }
+}
+
+/// What the player and the bodies collide with, the blocks and the
+/// `colliders`, in the world moved by `-origin`.
+fn static_geometry<'a>(
+    world: &'a World,
+    colliders: &'a [Aabb],
+    origin: Vector<f64, 3>,
+) -> impl Collide + 'a {
+    (
+        Translated {
+            collider: world,
+            offset: (VOXEL_ORIGIN - origin).map(|value| value as f32),
+        },
+        Translated {
+            collider: colliders,
+            offset: origin.map(|value| -value as f32),
+        },
+    )
+}
+
+/// The origin physics, routes and picking close to `position` run
+/// relative to, the center of its cell.
+///
+/// Positions are stored in `f64`, but simulated in `f32`, which is only
+/// precise to a millimeter up to a few kilometers away from its origin.
+/// Snapping the origin to cells moves it only now and then, and keeps the
+/// blocks at whole numbers relative to it.
+pub fn local_origin(position: Vector<f64, 3>) -> Vector<f64, 3> {
+    position.map(|value| (value / LOCAL_ORIGIN_CELL).round() * LOCAL_ORIGIN_CELL)
+}
+
+/// The block containing `position` in block coordinates, and where in the
+/// block it is.
+fn split_block(position: Vector<f64, 3>) -> ([i32; 3], Vector<f32, 3>) {
+    let block: [i32; 3] = std::array::from_fn(|axis| position[axis].floor() as i32);
+    let offset = std::array::from_fn(|axis| (position[axis] - f64::from(block[axis])) as f32);
+    (block, Vector::from_array(offset))
+}
+
+/// Advance the smoothing of every camera, also while the game is paused.
+fn update_cameras(entities: &mut ecs::World, time: &Time) {
+    for (_, camera) in entities.query_mut::<Camera>() {
+        camera.update(time.real_delta());
+    }
+}
+
+/// Switch every [Lod] entity to the level for its distance to `camera`.
+fn select_lods(entities: &mut ecs::World, camera: Entity) {
+    let Some(eye) = entities.get::<Camera>(camera).map(|camera| camera.eye()) else {
+        return;
+    };
+    let selected = entities
+        .query::<Lod>()
+        .map(|(entity, lod)| (entity, lod.level((lod.center - eye).length() as f32)))
+        .collect::<Vec<_>>();
+    for (entity, mesh) in selected {
+        if let Some(renderer) = entities.get_mut::<MeshRenderer>(entity) {
+            renderer.mesh = mesh;
+        }
+    }
+}
+
+/// The linear `color` as used for clearing render passes.
+fn wgpu_color(color: Color) -> wgpu::Color {
+    wgpu::Color {
+        r: color.r as f64,
+        g: color.g as f64,
+        b: color.b as f64,
+        a: color.a as f64,
+    }
+}
+
+/// The highest sample count up to `requested`, which both the color and the
+/// depth buffer support.
+///
+/// Without [Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES] only the
+/// counts guaranteed by WebGPU, 1 and 4, are available.
+fn supported_sample_count(
+    adapter: &Adapter,
+    device: &Device,
+    color_format: TextureFormat,
+    requested: u32,
+) -> u32 {
+    let format_flags = |format: TextureFormat| {
+        if device
+            .features()
+            .contains(Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES)
+        {
+            adapter.get_texture_format_features(format).flags
+        } else {
+            format.guaranteed_format_features(device.features()).flags
         }
+    };
+    let color = format_flags(color_format);
+    let depth = format_flags(TextureFormat::Depth32Float);
+
+    let sample_count = [16, 8, 4, 2]
+        .into_iter()
+        .filter(|&count| count <= requested)
+        .find(|&count| {
+            color.sample_count_supported(count)
+                && color.contains(TextureFormatFeatureFlags::MULTISAMPLE_RESOLVE)
+                && depth.sample_count_supported(count)
+        })
+        .unwrap_or(1);
+    if sample_count != requested {
+        tracing::warn!("{requested}x multisampling is not supported, using {sample_count}x");
+    }
+    sample_count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn local_origin_snaps_to_the_cell_of_the_position() {
+        assert_eq!(local_origin(v![300.0, -500.0, 0.0]), v![0.0, 0.0, 0.0]);
+        assert_eq!(
+            local_origin(v![20000.3, 10.0, -1600.0]),
+            v![20480.0, 0.0, -2048.0]
+        );
 
-        queue.submit(Some(encoder.finish()));
-        frame.present();
+        // Blocks stay at whole numbers relative to it.
+        let origin = local_origin(v![40_000_000.3, 10.0, -1600.0]);
+        let (block, offset) = split_block(v![40_000_000.3, 10.0, -1600.0] - origin);
+        assert_eq!(block, [-512, 10, 448]);
+        assert!(offset.approx_eq(&v![0.3, 0.0, 0.0], 1e-6));
     }
 }
@@ -0,0 +1,275 @@
+//! Human editable scene files in [RON](https://github.com/ron-rs/ron).
+//!
+//! A scene file describes the camera, the lights and the objects of a
+//! [Scene](crate::scene::Scene). Meshes and materials are referenced by
+//! the name they were added to the scene with, so they have to exist
+//! before a file is loaded.
+//!
+//! ```ron
+//! (
+//!     version: 1,
+//!     camera: Some((eye: (0.0, 1.0, 5.0), pitch: -10.0, yaw: 0.0, roll: 0.0)),
+//!     lights: [
+//!         (direction: (1.0, -1.0, -1.0), color: (1.0, 1.0, 1.0), ambient: (0.1, 0.1, 0.1)),
+//!     ],
+//!     objects: [
+//!         (mesh: "cube", material: "block"),
+//!         (
+//!             mesh: "plane",
+//!             material: "block",
+//!             transform: (translation: (0.0, -1.0, 0.0), scale: (3.0, 1.0, 3.0)),
+//!         ),
+//!     ],
+//! )
+//! ```
+use std::{fmt, io};
+
+use lina::{v, vector::Vector};
+use quaternion::Quaternion;
+use serde::{Deserialize, Serialize};
+
+use crate::{components::Transform, light::Light};
+
+/// The version written by [SceneFile::to_ron].
+///
+/// Files of older versions are migrated when loaded once the format changes,
+/// newer versions are rejected.
+pub const SCENE_FORMAT_VERSION: u32 = 1;
+
+/// Contents of a scene file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SceneFile {
+    pub version: u32,
+    /// Leaves the camera where it is if missing.
+    #[serde(default)]
+    pub camera: Option<CameraDesc>,
+    #[serde(default)]
+    pub lights: Vec<LightDesc>,
+    #[serde(default)]
+    pub objects: Vec<ObjectDesc>,
+}
+
+/// Pose of the camera, the angles are in degrees.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CameraDesc {
+    pub eye: [f64; 3],
+    #[serde(default)]
+    pub pitch: f32,
+    #[serde(default)]
+    pub yaw: f32,
+    #[serde(default)]
+    pub roll: f32,
+}
+
+/// A directional [Light], the colors are linear RGB.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LightDesc {
+    pub direction: [f32; 3],
+    pub color: [f32; 3],
+    pub ambient: [f32; 3],
+}
+
+/// An entity rendering the mesh and material with the given names.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ObjectDesc {
+    pub mesh: String,
+    pub material: String,
+    #[serde(default)]
+    pub transform: TransformDesc,
+}
+
+/// A [Transform], every field defaults to the identity.
+///
+/// The rotation is a unit quaternion in `(x, y, z, w)` order.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TransformDesc {
+    pub translation: [f64; 3],
+    pub rotation: [f32; 4],
+    pub scale: [f32; 3],
+}
+
+impl Default for TransformDesc {
+    fn default() -> Self {
+        Transform::default().into()
+    }
+}
+
+impl From<Transform> for TransformDesc {
+    fn from(transform: Transform) -> Self {
+        let axis = transform.rotation.vector();
+        Self {
+            translation: array(transform.translation),
+            rotation: [axis[0], axis[1], axis[2], transform.rotation.scalar()],
+            scale: array(transform.scale),
+        }
+    }
+}
+
+impl From<TransformDesc> for Transform {
+    fn from(desc: TransformDesc) -> Self {
+        let [x, y, z, w] = desc.rotation;
+        Self {
+            translation: desc.translation.into(),
+            rotation: Quaternion::new_parts(w, v![x, y, z]).normalized(),
+            scale: desc.scale.into(),
+        }
+    }
+}
+
+impl From<Light> for LightDesc {
+    fn from(light: Light) -> Self {
+        Self {
+            direction: array(light.direction()),
+            color: array(light.color()),
+            ambient: array(light.ambient()),
+        }
+    }
+}
+
+impl From<LightDesc> for Light {
+    fn from(desc: LightDesc) -> Self {
+        Light::new(
+            desc.direction.into(),
+            desc.color.into(),
+            desc.ambient.into(),
+        )
+    }
+}
+
+fn array<T: Copy>(vector: Vector<T, 3>) -> [T; 3] {
+    [vector[0], vector[1], vector[2]]
+}
+
+impl SceneFile {
+    /// Parse a scene file, checking its version.
+    pub fn from_ron(source: &str) -> Result<Self, SceneFileError> {
+        let file: SceneFile = ron::from_str(source).map_err(SceneFileError::Parse)?;
+        if file.version != SCENE_FORMAT_VERSION {
+            return Err(SceneFileError::UnsupportedVersion(file.version));
+        }
+        Ok(file)
+    }
+
+    /// Write the scene file in the current [SCENE_FORMAT_VERSION].
+    pub fn to_ron(&self) -> Result<String, SceneFileError> {
+        let file = SceneFile {
+            version: SCENE_FORMAT_VERSION,
+            ..self.clone()
+        };
+        ron::ser::to_string_pretty(&file, ron::ser::PrettyConfig::default())
+            .map_err(SceneFileError::Write)
+    }
+}
+
+/// Why a scene file could not be saved or loaded.
+#[derive(Debug)]
+pub enum SceneFileError {
+    Io(io::Error),
+    /// The file is not valid RON or doesn't match the format.
+    Parse(ron::error::SpannedError),
+    Write(ron::Error),
+    /// The file has a version this engine can't read, e.g. written by a newer one.
+    UnsupportedVersion(u32),
+    /// No mesh was added to the scene with the name.
+    UnknownMesh(String),
+    /// No material was added to the scene with the name.
+    UnknownMaterial(String),
+}
+
+impl fmt::Display for SceneFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SceneFileError::Io(err) => write!(f, "{err}"),
+            SceneFileError::Parse(err) => write!(f, "invalid scene file: {err}"),
+            SceneFileError::Write(err) => write!(f, "failed to write the scene: {err}"),
+            SceneFileError::UnsupportedVersion(version) => write!(
+                f,
+                "unsupported scene format version {version}, expected {SCENE_FORMAT_VERSION}"
+            ),
+            SceneFileError::UnknownMesh(name) => write!(f, "unknown mesh \"{name}\""),
+            SceneFileError::UnknownMaterial(name) => write!(f, "unknown material \"{name}\""),
+        }
+    }
+}
+
+impl std::error::Error for SceneFileError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SceneFileError::Io(err) => Some(err),
+            SceneFileError::Parse(err) => Some(err),
+            SceneFileError::Write(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for SceneFileError {
+    fn from(err: io::Error) -> Self {
+        SceneFileError::Io(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lina::v;
+
+    use super::{ObjectDesc, SCENE_FORMAT_VERSION, SceneFile, SceneFileError, TransformDesc};
+    use crate::components::Transform;
+
+    #[test]
+    fn missing_fields_use_defaults() {
+        let file = SceneFile::from_ron(
+            r#"(
+                version: 1,
+                objects: [(mesh: "cube", material: "block", transform: (scale: (2.0, 2.0, 2.0)))],
+            )"#,
+        )
+        .unwrap();
+
+        assert_eq!(file.camera, None);
+        assert!(file.lights.is_empty());
+        let transform: Transform = file.objects[0].transform.into();
+        assert_eq!(
+            transform,
+            Transform::default().with_scale(v![2.0, 2.0, 2.0])
+        );
+    }
+
+    #[test]
+    fn round_trips_through_ron() {
+        let file = SceneFile {
+            version: SCENE_FORMAT_VERSION,
+            camera: None,
+            lights: vec![crate::light::Light::default().into()],
+            objects: vec![ObjectDesc {
+                mesh: "plane".to_string(),
+                material: "block".to_string(),
+                transform: TransformDesc {
+                    translation: [0.0, -1.0, 0.0],
+                    ..Default::default()
+                },
+            }],
+        };
+
+        let ron = file.to_ron().unwrap();
+        assert_eq!(SceneFile::from_ron(&ron).unwrap(), file);
+    }
+
+    #[test]
+    fn parses_the_example_scene() {
+        let file = SceneFile::from_ron(include_str!("../assets/scenes/example.ron")).unwrap();
+        assert_eq!(file.objects.len(), 3);
+        assert_eq!(file.camera.unwrap().roll, 0.0);
+    }
+
+    #[test]
+    fn rejects_newer_versions() {
+        let result = SceneFile::from_ron("(version: 2)");
+        assert!(matches!(result, Err(SceneFileError::UnsupportedVersion(2))));
+        assert!(matches!(
+            SceneFile::from_ron("(objects: [])"),
+            Err(SceneFileError::Parse(_))
+        ));
+    }
+}
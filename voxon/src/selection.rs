@@ -0,0 +1,326 @@
+//! Selecting [Unit]s and ordering them around, RTS style.
+//!
+//! Units are picked with the cursor through a [View]: clicking picks the
+//! one under the cursor, dragging a [ScreenRect] the ones inside of it.
+//! Move orders are stored on the units as [MoveOrder]s, routed around
+//! obstacles by [route](crate::navigation::route), and carried out by
+//! [move_units].
+
+use std::collections::BTreeSet;
+
+use ecs::{Entity, Time};
+use graphic::{camera::Camera, color::Color};
+use lina::{v, vector::Vector};
+use winit::dpi::PhysicalSize;
+
+use crate::{
+    components::{MoveOrder, Transform, Unit},
+    debug_draw::DebugDraw,
+    view::View,
+};
+
+/// Rectangles smaller than this many pixels along both axes are clicks.
+const CLICK_SIZE: f32 = 4.0;
+
+const SELECTED_COLOR: Color = Color::GREEN;
+const ORDER_COLOR: Color = Color::rgb(1.0, 0.8, 0.2);
+
+/// The units the player selected, the ones move orders are given to.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Selection {
+    entities: BTreeSet<Entity>,
+}
+
+impl Selection {
+    /// Select `entities` instead of the ones before.
+    pub fn replace(&mut self, entities: impl IntoIterator<Item = Entity>) {
+        self.entities = entities.into_iter().collect();
+    }
+
+    /// Forget the entities which were despawned.
+    pub fn retain_alive(&mut self, entities: &ecs::World) {
+        self.entities.retain(|entity| entities.is_alive(*entity));
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.entities.iter().copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+}
+
+/// A rectangle dragged with the cursor, in pixels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScreenRect {
+    pub min: [f32; 2],
+    pub max: [f32; 2],
+}
+
+impl ScreenRect {
+    /// The rectangle between the opposite corners `a` and `b`.
+    pub fn new(a: [f32; 2], b: [f32; 2]) -> Self {
+        Self {
+            min: [a[0].min(b[0]), a[1].min(b[1])],
+            max: [a[0].max(b[0]), a[1].max(b[1])],
+        }
+    }
+
+    pub fn contains(&self, point: [f32; 2]) -> bool {
+        (0..2).all(|axis| (self.min[axis]..=self.max[axis]).contains(&point[axis]))
+    }
+
+    /// Whether it is too small to be dragged on purpose.
+    pub fn is_click(&self) -> bool {
+        (0..2).all(|axis| self.max[axis] - self.min[axis] < CLICK_SIZE)
+    }
+}
+
+/// The units picked with `rect` in a target of `size` seen through `view`.
+///
+/// A click picks the nearest unit under the cursor, a dragged rectangle
+/// every unit whose center is inside of it.
+pub fn pick_units(
+    entities: &ecs::World,
+    view: &View,
+    size: PhysicalSize<u32>,
+    rect: &ScreenRect,
+) -> Vec<Entity> {
+    let Some(camera) = entities.get::<Camera>(view.camera()) else {
+        return Vec::new();
+    };
+    let units = entities.query2::<Unit, Transform>();
+    if rect.is_click() {
+        // Relative to the eye, like the ray.
+        let ray = view.picking_ray(camera, size, rect.min);
+        units
+            .filter_map(|(entity, unit, transform)| {
+                let distance = unit
+                    .bounds(&transform.relative_to(camera.eye()))
+                    .ray_distance(&ray)?;
+                Some((entity, distance))
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(entity, _)| entity)
+            .into_iter()
+            .collect()
+    } else {
+        units
+            .filter(|(_, _, transform)| {
+                view.screen_position(camera, size, transform.translation)
+                    .is_some_and(|point| rect.contains(point))
+            })
+            .map(|(entity, _, _)| entity)
+            .collect()
+    }
+}
+
+/// Where `count` units ordered to `target` go, in rows of a square grid
+/// `spacing` apart around it, so they don't crowd the same spot.
+pub fn formation(target: Vector<f64, 3>, count: usize, spacing: f64) -> Vec<Vector<f64, 3>> {
+    let columns = (count as f64).sqrt().ceil().max(1.0) as usize;
+    let rows = count.div_ceil(columns);
+    // Offsets from the middle of the grid.
+    let offset = |index: usize, length: usize| (index as f64 - (length - 1) as f64 / 2.0) * spacing;
+    (0..count)
+        .map(|index| {
+            let (row, column) = (index / columns, index % columns);
+            target + v![offset(column, columns), 0.0, offset(row, rows)]
+        })
+        .collect()
+}
+
+/// Move the units with a [MoveOrder] straight from one waypoint to the
+/// next, removing the order once they arrived at the last one.
+///
+/// A fixed system, the units move the same on every machine.
+pub fn move_units(entities: &mut ecs::World, time: &Time) {
+    let delta = time.fixed_delta().as_secs_f64();
+    let moving = entities
+        .query2::<Unit, MoveOrder>()
+        .map(|(entity, unit, _)| (entity, f64::from(unit.speed) * delta))
+        .collect::<Vec<_>>();
+    for (entity, mut step) in moving {
+        let Some(mut translation) = entities
+            .get::<Transform>(entity)
+            .map(|transform| transform.translation)
+        else {
+            continue;
+        };
+        let order = entities
+            .get_mut::<MoveOrder>(entity)
+            .expect("the entity was queried with its order");
+        // What is left of the step after reaching a waypoint goes on
+        // towards the next one.
+        while let Some(&waypoint) = order.waypoints.first() {
+            let offset = waypoint - translation;
+            let distance = offset.length();
+            if distance > step {
+                translation += offset * (step / distance);
+                break;
+            }
+            translation = waypoint;
+            step -= distance;
+            order.waypoints.remove(0);
+        }
+
+        if order.waypoints.is_empty() {
+            entities.remove::<MoveOrder>(entity);
+        }
+        if let Some(transform) = entities.get_mut::<Transform>(entity) {
+            transform.translation = translation;
+        }
+    }
+}
+
+/// Outline the selected units and mark where they were ordered to.
+pub fn draw_selection(debug_draw: &mut DebugDraw, entities: &ecs::World, selection: &Selection) {
+    for entity in selection.iter() {
+        let (Some(unit), Some(transform)) = (
+            entities.get::<Unit>(entity),
+            entities.get::<Transform>(entity),
+        ) else {
+            continue;
+        };
+        // Slightly larger, so the outline isn't hidden by the faces, and
+        // relative to the unit.
+        let mut bounds = unit.bounds(&transform.relative_to(transform.translation));
+        bounds.half_extents += v![0.02, 0.02, 0.02];
+        debug_draw.obb(&bounds, transform.translation, SELECTED_COLOR);
+
+        if let Some(order) = entities.get::<MoveOrder>(entity)
+            && let Some(&target) = order.waypoints.last()
+        {
+            let mut from = transform.translation;
+            for &waypoint in &order.waypoints {
+                debug_draw.line(from, waypoint, ORDER_COLOR);
+                from = waypoint;
+            }
+            // A cross on the ground the unit is going to stand on.
+            let ground = target - v![0.0, f64::from(unit.half_extents[1]), 0.0];
+            let (along_x, along_z) = (v![0.2, 0.0, 0.0], v![0.0, 0.0, 0.2]);
+            debug_draw.line(ground - along_x, ground + along_x, ORDER_COLOR);
+            debug_draw.line(ground - along_z, ground + along_z, ORDER_COLOR);
+        }
+    }
+}
+
+/// Outline `rect` in a target of `size`, as seen through `view` by `camera`.
+pub fn draw_rect(
+    debug_draw: &mut DebugDraw,
+    view: &View,
+    camera: &Camera,
+    size: PhysicalSize<u32>,
+    rect: &ScreenRect,
+) {
+    let ScreenRect { min, max } = *rect;
+    // Just past the near plane, in front of everything else.
+    let corners = [min, [max[0], min[1]], max, [min[0], max[1]]].map(|corner| {
+        let ray = view.picking_ray(camera, size, corner);
+        camera.eye() + (ray.origin + ray.direction * 0.01).map(f64::from)
+    });
+    for (index, corner) in corners.iter().enumerate() {
+        debug_draw.line(*corner, corners[(index + 1) % 4], SELECTED_COLOR);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::view::Viewport;
+
+    fn world_with_units(positions: &[Vector<f64, 3>]) -> (ecs::World, View, Vec<Entity>) {
+        let mut entities = ecs::World::new();
+        let camera = entities.spawn();
+        entities.insert(camera, Camera::default());
+        let units = positions
+            .iter()
+            .map(|position| {
+                let unit = entities.spawn();
+                entities.insert(unit, Unit::default());
+                entities.insert(unit, Transform::from_translation(*position));
+                unit
+            })
+            .collect();
+        (entities, View::new(camera, Viewport::FULL), units)
+    }
+
+    #[test]
+    fn clicks_pick_the_nearest_unit_and_drags_all_inside() {
+        let size = PhysicalSize::new(800, 600);
+        // The camera at Z 5 looks at all of them, the first two are in line.
+        let (entities, view, units) =
+            world_with_units(&[v![0.0, 0.0, 0.0], v![0.0, 0.0, 2.0], v![2.0, 0.0, 0.0]]);
+
+        let center = ScreenRect::new([400.0, 300.0], [401.0, 301.0]);
+        assert!(center.is_click());
+        assert_eq!(pick_units(&entities, &view, size, &center), [units[1]]);
+
+        let nothing = ScreenRect::new([10.0, 10.0], [10.0, 10.0]);
+        assert!(pick_units(&entities, &view, size, &nothing).is_empty());
+
+        // From the top left to right of the middle, but not as far as X 2.
+        let left = ScreenRect::new([450.0, 400.0], [0.0, 0.0]);
+        assert!(!left.is_click());
+        let mut picked = pick_units(&entities, &view, size, &left);
+        picked.sort();
+        assert_eq!(picked, [units[0], units[1]]);
+    }
+
+    #[test]
+    fn formations_are_centered_on_the_target() {
+        let target = v![1.0, 2.0, 3.0];
+        assert_eq!(formation(target, 1, 1.0), [target]);
+        assert!(formation(target, 0, 1.0).is_empty());
+
+        let spots = formation(target, 4, 2.0);
+        assert_eq!(
+            spots,
+            [
+                v![0.0, 2.0, 2.0],
+                v![2.0, 2.0, 2.0],
+                v![0.0, 2.0, 4.0],
+                v![2.0, 2.0, 4.0]
+            ]
+        );
+        // Three in a row of three, then two below.
+        let spots = formation(target, 5, 1.0);
+        assert_eq!(spots[2], v![2.0, 2.0, 2.5]);
+        assert_eq!(spots[4], v![1.0, 2.0, 3.5]);
+    }
+
+    #[test]
+    fn units_move_until_they_arrive() {
+        let (mut entities, _, units) = world_with_units(&[v![0.0, 0.0, 0.0]]);
+        let unit = units[0];
+        entities.insert(
+            unit,
+            MoveOrder {
+                waypoints: vec![v![2.0, 0.0, 0.0], v![2.0, 0.0, 4.0]],
+            },
+        );
+
+        // Around the corner at the first waypoint.
+        let mut time = Time::new();
+        time.set_fixed_delta(Duration::from_secs(1));
+        move_units(&mut entities, &time);
+        let translation = entities.get::<Transform>(unit).unwrap().translation;
+        assert!(translation.approx_eq(&v![2.0, 0.0, 1.0], 1e-5));
+        assert_eq!(entities.get::<MoveOrder>(unit).unwrap().waypoints.len(), 1);
+
+        move_units(&mut entities, &time);
+        move_units(&mut entities, &time);
+        assert_eq!(
+            entities.get::<Transform>(unit).unwrap().translation,
+            v![2.0, 0.0, 4.0]
+        );
+        assert!(entities.get::<MoveOrder>(unit).is_none());
+    }
+}
@@ -1,17 +1,35 @@
+struct Light {
+    // Direction the light is travelling in, normalized.
+    direction: vec3f,
+    color: vec3f,
+    ambient: vec3f,
+};
+
+// The cascades of the shadow map, each covering the view up to a depth.
+struct Shadows {
+    // World space to the clip space of each cascade's layer.
+    light_view_projections: array<mat4x4f, 4>,
+    // The view depth each cascade reaches up to.
+    splits: vec4f,
+    // In world units, about one and a half texels of each cascade.
+    normal_offsets: vec4f,
+    camera_position: vec3f,
+    count: u32,
+    // The camera's view direction, normalized.
+    camera_forward: vec3f,
+};
 
 struct Globals {
     view_projection: mat4x4f,
-    light_color: vec4f,
-    light_position: vec3f,
-    view_world_position: vec3f,
-    shininess: f32,
-    light_direction: vec3f,
-    limit: f32,
+    light: Light,
+    shadows: Shadows,
 };
 
-struct Entity {
+struct Object {
     world: mat4x4f,
     normal: mat3x3f,
+    // The index of the object's first skinning matrix in `joints`.
+    first_joint: u32,
 }
 
 @group(0)
@@ -20,12 +38,42 @@ var<uniform> global: Globals;
 
 @group(1)
 @binding(0)
-var<uniform> entity: Entity;
+var<uniform> object: Object;
+
+@group(2)
+@binding(0)
+var diffuse_texture: texture_2d<f32>;
+
+@group(2)
+@binding(1)
+var diffuse_sampler: sampler;
+
+@group(3)
+@binding(0)
+var shadow_map: texture_depth_2d_array;
+
+@group(3)
+@binding(1)
+var shadow_sampler: sampler_comparison;
+
+// Only bound for skinned meshes.
+@group(1)
+@binding(1)
+var<storage, read> joints: array<mat4x4f>;
 
 struct Vertex {
     // The position of the vertex.
     @location(0) position: vec4f,
     @location(1) normal: vec3f,
+    @location(2) uv: vec2f,
+};
+
+struct SkinnedVertex {
+    @location(0) position: vec4f,
+    @location(1) normal: vec3f,
+    @location(2) uv: vec2f,
+    @location(3) joints: vec4u,
+    @location(4) weights: vec4f,
 };
 
 struct VSOutput {
@@ -33,54 +81,104 @@ struct VSOutput {
     @builtin(position) position: vec4f,
     // Will be interpolated and have to renormalized.
     @location(0) normal: vec3f,
-    @location(1) surface_to_light: vec3f,
-    @location(2) surface_to_view: vec3f,
+    @location(1) uv: vec2f,
+    @location(2) world_position: vec3f,
 };
 
 @vertex
 fn vs_main(vertex: Vertex) -> VSOutput {
+    return vertex_output(vertex.position, vertex.normal, vertex.uv);
+}
+
+@vertex
+fn vs_skinned(vertex: SkinnedVertex) -> VSOutput {
+    let skin = skin_matrix(vertex.joints, vertex.weights);
+    // Assumes the joints don't scale non-uniformly, like the normal matrix
+    // of the object would otherwise have to account for.
+    let normal = mat3x3f(skin[0].xyz, skin[1].xyz, skin[2].xyz) * vertex.normal;
+    return vertex_output(skin * vertex.position, normal, vertex.uv);
+}
+
+// The skinning matrices of the vertex's joints, blended by their weights.
+fn skin_matrix(vertex_joints: vec4u, weights: vec4f) -> mat4x4f {
+    let first = object.first_joint;
+    return joints[first + vertex_joints.x] * weights.x
+        + joints[first + vertex_joints.y] * weights.y
+        + joints[first + vertex_joints.z] * weights.z
+        + joints[first + vertex_joints.w] * weights.w;
+}
+
+// Place a vertex given in the object's space.
+fn vertex_output(position: vec4f, normal: vec3f, uv: vec2f) -> VSOutput {
     var vsOut: VSOutput;
 
     // Compute the vertex position in device coordinates
-    vsOut.position = global.view_projection * entity.world * vertex.position;
+    vsOut.position = global.view_projection * object.world * position;
 
     // Orient the normals in world space
-    vsOut.normal = entity.normal * vertex.normal;
+    vsOut.normal = object.normal * normal;
 
-    // Compute surface_to_light vector in world space
-    let surface_world_position = (entity.world * vertex.position).xyz;
-    vsOut.surface_to_light = global.light_position - surface_world_position;
+    vsOut.uv = uv;
 
-    // Compute the surface_to_view vector in world space
-    vsOut.surface_to_view = global.view_world_position - surface_world_position;
+    vsOut.world_position = (object.world * position).xyz;
 
     // the returned vector will automatically be normalized using w
     // [x,y,z,w] => [x/w, y/w, z/w, 1]
     return vsOut;
 }
 
+// The fraction of the light reaching `world_position`, 0 in full shadow.
+//
+// Averages the comparison of the 3x3 neighbouring texels (percentage closer
+// filtering) of the first cascade reaching the position, the sampler
+// interpolates each of them further.
+fn shadow(world_position: vec3f, normal: vec3f) -> f32 {
+    let shadows = global.shadows;
+    let depth = dot(world_position - shadows.camera_position, shadows.camera_forward);
+    var cascade = 0u;
+    while cascade < shadows.count && depth > shadows.splits[cascade] {
+        cascade++;
+    }
+    // Beyond the last cascade everything is lit.
+    if cascade == shadows.count {
+        return 1.0;
+    }
+
+    // Offset along the normal by about a texel of the cascade,
+    // so lit surfaces don't shadow themselves (shadow acne).
+    let offset = normal * shadows.normal_offsets[cascade];
+    let light_position = shadows.light_view_projections[cascade] * vec4f(world_position + offset, 1.0);
+    let ndc = light_position.xyz / light_position.w;
+    // The texture Y axis points down.
+    let uv = ndc.xy * vec2f(0.5, -0.5) + 0.5;
+
+    // Outside of the shadow map everything is lit.
+    if any(uv < vec2f(0.0)) || any(uv > vec2f(1.0)) || ndc.z > 1.0 {
+        return 1.0;
+    }
+
+    let texel = 1.0 / vec2f(textureDimensions(shadow_map));
+    var visibility = 0.0;
+    for (var y = -1; y <= 1; y++) {
+        for (var x = -1; x <= 1; x++) {
+            let offset = vec2f(f32(x), f32(y)) * texel;
+            visibility += textureSampleCompareLevel(shadow_map, shadow_sampler, uv + offset, cascade, ndc.z);
+        }
+    }
+    return visibility / 9.0;
+}
+
 @fragment
 fn fs_main(vsOut: VSOutput) -> @location(0) vec4<f32> {
     // All inter-stage variables get interpolated, so they
     // have to be renormalized if necessary.
     let normal = normalize(vsOut.normal);
 
-    let surface_to_light_direction = normalize(vsOut.surface_to_light);
+    // Lambertian diffuse term
+    let diffuse = max(dot(normal, -global.light.direction), 0.0) * shadow(vsOut.world_position, normal);
 
-    let surface_to_view_direction = normalize(vsOut.surface_to_view);
-    let half_vector = normalize(surface_to_light_direction + surface_to_view_direction);
-
-    var light = 0.0;
-    var specular = 0.0;
-
-    let dot_from_direction = dot(surface_to_light_direction, -global.light_direction);
-    if (dot_from_direction > global.limit) {
-        light = dot(normal, surface_to_light_direction);
-
-        specular = dot(normal, half_vector);
-        specular = select(0.0, pow(specular, global.shininess), specular > 0.0);
-    }
+    let albedo = textureSample(diffuse_texture, diffuse_sampler, vsOut.uv).rgb;
 
-    let color = global.light_color.rgb * light + specular;
-    return vec4f(color, global.light_color.a);
+    let color = albedo * (global.light.ambient + global.light.color * diffuse);
+    return vec4f(color, 1.0);
 }
@@ -0,0 +1,534 @@
+use std::borrow::Cow;
+
+use bytemuck::{Pod, Zeroable};
+use graphic::{
+    projection::{Projection, ProjectionMode},
+    transform::{look_at, orthographic_centered, translate},
+};
+use lina::{angle::Rad, matrix::Matrix, v, vector::Vector};
+use wgpu::{
+    BindGroup, BindGroupLayout, Buffer, BufferAddress, BufferUsages, CommandEncoder,
+    DepthBiasState, DepthStencilState, Device, Face, Queue, RenderPass, RenderPassTimestampWrites,
+    RenderPipeline, StencilState, TextureFormat, TextureUsages, TextureView, VertexBufferLayout,
+};
+
+use crate::{
+    mesh::{SkinnedVertex, Vertex},
+    vertex::VertexLayout,
+};
+
+const SHADOW_FORMAT: TextureFormat = TextureFormat::Depth32Float;
+
+/// The most cascades a [ShadowMap] can have, the length of the arrays in
+/// the shader's uniforms.
+pub const MAX_CASCADES: usize = 4;
+
+// Blends the cascade splits between uniform (0) and logarithmic (1) spacing.
+// Logarithmic splits match the perspective best, but leave the first
+// cascade tiny.
+const SPLIT_LAMBDA: f32 = 0.75;
+
+// How far in front of a cascade, towards the light, objects still cast
+// shadows into it.
+const CASTER_DISTANCE: f32 = 32.0;
+
+// The offset of positions along their normal in the main shader, in texels
+// of a cascade, so lit surfaces don't shadow themselves (shadow acne).
+const NORMAL_OFFSET_TEXELS: f32 = 1.5;
+
+/// Cascaded shadow map of a directional light.
+///
+/// The scene is rendered depth only from the light's point of view, using an
+/// orthographic projection as all rays of the light are parallel. The main pass
+/// then compares the depth of each fragment in light space with the map,
+/// filtering the result over the neighbouring texels (PCF) for soft edges.
+///
+/// The camera frustum up to `distance` is split by depth into cascades, each
+/// with its own layer of the map covering its slice of the frustum. Close to
+/// the camera a texel covers little of the scene, far away a lot, so the
+/// shadows are sharp where it matters while reaching far. Everything beyond
+/// `distance` is lit.
+pub struct ShadowMap {
+    resolution: u32,
+    cascades: usize,
+    distance: f32,
+    pipeline: RenderPipeline,
+    // Draws skinned meshes, if supported.
+    skinned_pipeline: Option<RenderPipeline>,
+    // A view of each cascade's layer to render into.
+    layer_views: Vec<TextureView>,
+    // The light view projection matrix of each cascade for its depth pass.
+    light_buffers: Vec<(Buffer, BindGroup)>,
+    // The shadow map and its comparison sampler for the main pass.
+    layout: BindGroupLayout,
+    bind_group: BindGroup,
+}
+
+impl ShadowMap {
+    /// Create `cascades` layers, each `resolution` x `resolution` large,
+    /// covering the view up to `distance` away from the camera.
+    ///
+    /// Panics unless there are 1 to [MAX_CASCADES] cascades.
+    ///
+    /// `object_layout` is the layout of the per object uniforms, which the
+    /// depth pass uses to place the objects. Skinned meshes are only drawn
+    /// with the `skinned_layout`, which adds their skinning matrices.
+    pub fn new(
+        device: &Device,
+        object_layout: &BindGroupLayout,
+        skinned_layout: Option<&BindGroupLayout>,
+        resolution: u32,
+        cascades: usize,
+        distance: f32,
+    ) -> Self {
+        assert!(
+            (1..=MAX_CASCADES).contains(&cascades),
+            "a shadow map has 1 to {MAX_CASCADES} cascades"
+        );
+
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("shadow_shader"),
+            source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(include_str!("shadow.wgsl"))),
+        });
+
+        let light_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("shadow_light_bind_group_layout"),
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+        });
+        // Each cascade is rendered in a pass of its own, all recorded before
+        // the uniforms are written, so each needs its own buffer.
+        let light_buffers = (0..cascades)
+            .map(|_| {
+                let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+                    label: Some("shadow_light_buffer"),
+                    size: std::mem::size_of::<Matrix<f32, 4, 4>>() as BufferAddress,
+                    usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+                    mapped_at_creation: false,
+                });
+                let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                    label: Some("shadow_light_bind_group"),
+                    layout: &light_layout,
+                    entries: &[wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: buffer.as_entire_binding(),
+                    }],
+                });
+                (buffer, bind_group)
+            })
+            .collect();
+
+        let create_pipeline =
+            |label, bind_group_layouts: &[&BindGroupLayout], entry_point, buffer| {
+                let pipeline_layout =
+                    device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                        label: Some(label),
+                        bind_group_layouts,
+                        immediate_size: 0,
+                    });
+                device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                    label: Some(label),
+                    layout: Some(&pipeline_layout),
+                    vertex: wgpu::VertexState {
+                        module: &shader,
+                        entry_point: Some(entry_point),
+                        buffers: &[buffer],
+                        compilation_options: Default::default(),
+                    },
+                    fragment: None,
+                    primitive: wgpu::PrimitiveState {
+                        topology: wgpu::PrimitiveTopology::TriangleList,
+                        front_face: wgpu::FrontFace::Ccw,
+                        cull_mode: Some(Face::Back),
+                        ..Default::default()
+                    },
+                    depth_stencil: Some(DepthStencilState {
+                        format: SHADOW_FORMAT,
+                        depth_compare: wgpu::CompareFunction::Less,
+                        depth_write_enabled: true,
+                        stencil: StencilState::default(),
+                        // Push the depth away from the light, together with the normal
+                        // offset in the main shader this avoids shadow acne.
+                        bias: DepthBiasState {
+                            constant: 2,
+                            slope_scale: 2.0,
+                            clamp: 0.0,
+                        },
+                    }),
+                    multisample: wgpu::MultisampleState::default(),
+                    multiview_mask: None,
+                    cache: None,
+                })
+            };
+        // Same layouts as the main pass, only the position and the skinning
+        // attributes are used.
+        let pipeline = create_pipeline(
+            "shadow_pipeline",
+            &[&light_layout, object_layout],
+            "vs_main",
+            VertexBufferLayout {
+                attributes: &Vertex::ATTRIBUTES[..1],
+                ..Vertex::layout()
+            },
+        );
+        let skinned_attributes = [
+            SkinnedVertex::ATTRIBUTES[0],
+            SkinnedVertex::ATTRIBUTES[3],
+            SkinnedVertex::ATTRIBUTES[4],
+        ];
+        let skinned_pipeline = skinned_layout.map(|skinned_layout| {
+            create_pipeline(
+                "skinned_shadow_pipeline",
+                &[&light_layout, skinned_layout],
+                "vs_skinned",
+                VertexBufferLayout {
+                    attributes: &skinned_attributes,
+                    ..SkinnedVertex::layout()
+                },
+            )
+        });
+
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("shadow_texture"),
+            size: wgpu::Extent3d {
+                width: resolution,
+                height: resolution,
+                depth_or_array_layers: cascades as u32,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: SHADOW_FORMAT,
+            usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor {
+            dimension: Some(wgpu::TextureViewDimension::D2Array),
+            ..Default::default()
+        });
+        let layer_views = (0..cascades as u32)
+            .map(|layer| {
+                texture.create_view(&wgpu::TextureViewDescriptor {
+                    label: Some("shadow_cascade_view"),
+                    dimension: Some(wgpu::TextureViewDimension::D2),
+                    base_array_layer: layer,
+                    array_layer_count: Some(1),
+                    ..Default::default()
+                })
+            })
+            .collect();
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("shadow_sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            // Linear filtering blends the comparison results of four texels.
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            compare: Some(wgpu::CompareFunction::LessEqual),
+            ..Default::default()
+        });
+
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("shadow_bind_group_layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Depth,
+                        view_dimension: wgpu::TextureViewDimension::D2Array,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Comparison),
+                    count: None,
+                },
+            ],
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("shadow_bind_group"),
+            layout: &layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&sampler),
+                },
+            ],
+        });
+
+        Self {
+            resolution,
+            cascades,
+            distance,
+            pipeline,
+            skinned_pipeline,
+            layer_views,
+            light_buffers,
+            layout,
+            bind_group,
+        }
+    }
+
+    /// Layout of the shadow map bind group (`@group(3)` in the main shader).
+    pub fn layout(&self) -> &BindGroupLayout {
+        &self.layout
+    }
+
+    pub fn bind_group(&self) -> &BindGroup {
+        &self.bind_group
+    }
+
+    /// Split the view of a camera at `eye`, looking along `forward` through
+    /// `projection`, into the cascades for a light shining in `direction`.
+    pub fn cascades(
+        &self,
+        direction: Vector<f32, 3>,
+        eye: Vector<f32, 3>,
+        forward: Vector<f32, 3>,
+        projection: &Projection,
+    ) -> Vec<Cascade> {
+        // The distance of the frustum's corners from the view axis at a depth.
+        let radial = |depth: f32| match projection.mode() {
+            ProjectionMode::Perspective { fov_x: Rad(fov_x) } => {
+                let tan_x = (fov_x / 2.0).tan();
+                let tan_y = tan_x / projection.aspect_ratio();
+                depth * (tan_x * tan_x + tan_y * tan_y).sqrt()
+            }
+            ProjectionMode::Orthographic { height } => {
+                let width = height * projection.aspect_ratio();
+                (width * width + height * height).sqrt() / 2.0
+            }
+        };
+
+        let near = -projection.near();
+        let far = (-projection.far()).min(self.distance);
+        let splits = split_depths(near, far, self.cascades, SPLIT_LAMBDA);
+        std::iter::once(near)
+            .chain(splits.iter().copied())
+            .zip(splits.iter().copied())
+            .map(|(start, end)| {
+                let (depth, radius) = bounding_sphere(start, end, radial(start), radial(end));
+                let center = eye + forward * depth;
+                Cascade {
+                    light_view_projection: self.light_view_projection(direction, center, radius),
+                    far: end,
+                    texel_size: 2.0 * radius / self.resolution as f32,
+                }
+            })
+            .collect()
+    }
+
+    /// The matrix transforming world space into the clip space of the light
+    /// shining in `direction`, covering the sphere around `center`.
+    ///
+    /// The center is snapped to whole texels in light space, otherwise the
+    /// shadow edges would flicker as the center moves.
+    fn light_view_projection(
+        &self,
+        direction: Vector<f32, 3>,
+        center: Vector<f32, 3>,
+        radius: f32,
+    ) -> Matrix<f32, 4, 4> {
+        // Any up vector works as long as it isn't parallel to the light.
+        let up = if direction[1].abs() < 0.99 {
+            v![0.0, 1.0, 0.0]
+        } else {
+            v![0.0, 0.0, 1.0]
+        };
+        let rotation = look_at(v![0.0, 0.0, 0.0], direction, up);
+
+        let center = rotation * v![center[0], center[1], center[2], 1.0];
+        let texel = 2.0 * radius / self.resolution as f32;
+        let snap = |value: f32| (value / texel).round() * texel;
+
+        // Move the sphere in front of the light, far enough from the near
+        // plane to include the casters in front of it.
+        let view = translate(
+            -snap(center[0]),
+            -snap(center[1]),
+            -center[2] - radius - CASTER_DISTANCE - 1.0,
+        ) * rotation;
+        let size = 2.0 * radius;
+        let projection = orthographic_centered(size, size, -1.0, -1.0 - CASTER_DISTANCE - size);
+
+        projection * view
+    }
+
+    /// The depth pipeline for meshes with [SkinnedVertex]s instead of the
+    /// one bound by [begin_pass](ShadowMap::begin_pass), which binds the
+    /// skinning matrices next to the object uniforms (`@group(1)`).
+    pub fn skinned_pipeline(&self) -> Option<&RenderPipeline> {
+        self.skinned_pipeline.as_ref()
+    }
+
+    /// Render the depth of the scene into the layer of the cascade with the
+    /// `index`.
+    ///
+    /// The returned pass has the depth pipeline and the light bound, the caller
+    /// binds the per object uniforms (`@group(1)`) and draws the objects.
+    pub fn begin_pass<'a>(
+        &'a self,
+        encoder: &'a mut CommandEncoder,
+        queue: &Queue,
+        index: usize,
+        cascade: &Cascade,
+        timestamp_writes: Option<RenderPassTimestampWrites<'a>>,
+    ) -> RenderPass<'a> {
+        let (light_buffer, light_bind_group) = &self.light_buffers[index];
+        queue.write_buffer(
+            light_buffer,
+            0,
+            bytemuck::bytes_of(&cascade.light_view_projection.to_cols_array()),
+        );
+
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("shadow_pass"),
+            color_attachments: &[],
+            depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                view: &self.layer_views[index],
+                depth_ops: Some(wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(1.0),
+                    store: wgpu::StoreOp::Store,
+                }),
+                stencil_ops: None,
+            }),
+            timestamp_writes,
+            occlusion_query_set: None,
+            multiview_mask: None,
+        });
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, light_bind_group, &[]);
+        render_pass
+    }
+}
+
+/// The part of the view covered by one layer of a [ShadowMap].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cascade {
+    /// World space to the clip space of the layer.
+    pub light_view_projection: Matrix<f32, 4, 4>,
+    /// The view depth the cascade reaches up to.
+    pub far: f32,
+    /// The size of a texel of the layer in world units.
+    pub texel_size: f32,
+}
+
+/// The `Shadows` WGSL struct, the cascades of a view.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+pub struct ShadowUniforms {
+    // Column by column.
+    light_view_projections: [[f32; 16]; MAX_CASCADES],
+    splits: [f32; MAX_CASCADES],
+    normal_offsets: [f32; MAX_CASCADES],
+    camera_position: Vector<f32, 3>,
+    count: u32,
+    camera_forward: Vector<f32, 3>,
+    _padding: f32,
+}
+
+impl ShadowUniforms {
+    /// The `cascades` of a camera at `eye` looking along `forward`, which
+    /// the shader selects by the view depth.
+    pub fn new(cascades: &[Cascade], eye: Vector<f32, 3>, forward: Vector<f32, 3>) -> Self {
+        let mut uniforms = Self::zeroed();
+        for (index, cascade) in cascades.iter().enumerate() {
+            uniforms.light_view_projections[index] = cascade.light_view_projection.to_cols_array();
+            uniforms.splits[index] = cascade.far;
+            uniforms.normal_offsets[index] = cascade.texel_size * NORMAL_OFFSET_TEXELS;
+        }
+        uniforms.camera_position = eye;
+        uniforms.count = cascades.len() as u32;
+        uniforms.camera_forward = forward;
+        uniforms
+    }
+}
+
+/// The view depths splitting `near` to `far` into `count` cascades, the
+/// far end of each.
+///
+/// `lambda` blends between uniform (0) and logarithmic (1) splits, the
+/// "practical split scheme".
+fn split_depths(near: f32, far: f32, count: usize, lambda: f32) -> Vec<f32> {
+    (1..=count)
+        .map(|index| {
+            let fraction = index as f32 / count as f32;
+            let logarithmic = near * (far / near).powf(fraction);
+            let uniform = near + (far - near) * fraction;
+            lambda * logarithmic + (1.0 - lambda) * uniform
+        })
+        .collect()
+}
+
+/// The smallest sphere around a slice of a frustum between the view depths
+/// `near` and `far`, whose corners are `near_radial` and `far_radial` away
+/// from the view axis. Returns the view depth of its center and its radius.
+fn bounding_sphere(near: f32, far: f32, near_radial: f32, far_radial: f32) -> (f32, f32) {
+    // Equally far from the corners of both ends, unless the far end alone
+    // is wider.
+    let depth = (far * far - near * near + far_radial * far_radial - near_radial * near_radial)
+        / (2.0 * (far - near));
+    let depth = depth.clamp(near, far);
+    let radius = ((depth - near).powi(2) + near_radial * near_radial)
+        .max((far - depth).powi(2) + far_radial * far_radial)
+        .sqrt();
+    (depth, radius)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_cover_the_range_in_order() {
+        let splits = split_depths(1.0, 64.0, 3, SPLIT_LAMBDA);
+        assert_eq!(splits.len(), 3);
+        assert!(splits.is_sorted());
+        assert!(splits[0] > 1.0);
+        assert!((splits[2] - 64.0).abs() < 1e-4);
+
+        // The extremes are spaced evenly and by powers.
+        let uniform = split_depths(1.0, 9.0, 2, 0.0);
+        assert_eq!(uniform, vec![5.0, 9.0]);
+        let logarithmic = split_depths(1.0, 9.0, 2, 1.0);
+        assert!((logarithmic[0] - 3.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn bounding_spheres_contain_the_slices() {
+        for (near, far, near_radial, far_radial) in [
+            (1.0, 4.0, 1.0, 4.0),
+            (1.0, 64.0, 1.4, 90.0),
+            (10.0, 11.0, 10.0, 11.0),
+            (0.0, 2.0, 3.0, 3.0),
+        ] {
+            let (depth, radius) = bounding_sphere(near, far, near_radial, far_radial);
+            assert!((near..=far).contains(&depth));
+            for (corner_depth, corner_radial) in [(near, near_radial), (far, far_radial)] {
+                let distance = ((corner_depth - depth).powi(2) + corner_radial.powi(2)).sqrt();
+                assert!(distance <= radius + 1e-4);
+            }
+        }
+
+        // A slice of an orthographic view is centered in its middle.
+        assert_eq!(bounding_sphere(0.0, 2.0, 3.0, 3.0), (1.0, 10.0_f32.sqrt()));
+    }
+}
@@ -0,0 +1,39 @@
+// Depth only pass, rendering the scene as seen from the light.
+
+struct Object {
+    world: mat4x4f,
+    normal: mat3x3f,
+    first_joint: u32,
+}
+
+@group(0)
+@binding(0)
+var<uniform> light_view_projection: mat4x4f;
+
+@group(1)
+@binding(0)
+var<uniform> object: Object;
+
+// Only bound for skinned meshes, see the main shader.
+@group(1)
+@binding(1)
+var<storage, read> joints: array<mat4x4f>;
+
+@vertex
+fn vs_main(@location(0) position: vec4f) -> @builtin(position) vec4f {
+    return light_view_projection * object.world * position;
+}
+
+@vertex
+fn vs_skinned(
+    @location(0) position: vec4f,
+    @location(3) vertex_joints: vec4u,
+    @location(4) weights: vec4f,
+) -> @builtin(position) vec4f {
+    let first = object.first_joint;
+    let skin = joints[first + vertex_joints.x] * weights.x
+        + joints[first + vertex_joints.y] * weights.y
+        + joints[first + vertex_joints.z] * weights.z
+        + joints[first + vertex_joints.w] * weights.w;
+    return light_view_projection * object.world * skin * position;
+}
@@ -0,0 +1,161 @@
+//! Skinning meshes on the GPU.
+//!
+//! The skinning matrices of every [Skin](crate::animation::Skin) drawn in a
+//! frame are uploaded into one storage buffer, each object's uniforms point
+//! to its first joint. The vertex shader blends the matrices of the joints
+//! of a vertex by their weights.
+//!
+//! Downlevel devices only have four bind groups, all taken by the main
+//! shader, so the joints are bound next to the object uniforms.
+
+use lina::matrix::Matrix;
+use wgpu::{
+    Adapter, BindGroup, BindGroupLayout, Buffer, BufferAddress, BufferSize, BufferUsages, Device,
+    DownlevelFlags,
+};
+
+/// The size of one skinning matrix in the buffer.
+pub const MATRIX_SIZE: BufferAddress = std::mem::size_of::<Matrix<f32, 4, 4>>() as BufferAddress;
+
+/// Whether vertex shaders can read storage buffers on `adapter`, which
+/// skinning needs. They can't on some downlevel backends like WebGL.
+pub fn supports_skinning(adapter: &Adapter) -> bool {
+    adapter
+        .get_downlevel_capabilities()
+        .flags
+        .contains(DownlevelFlags::VERTEX_STORAGE)
+}
+
+/// The storage buffer of the skinning matrices with all the state necessary
+/// to grow it, bound as `@group(1)` together with the object uniforms.
+pub struct JointBuffer {
+    buffer: Buffer,
+    layout: BindGroupLayout,
+    // `None` until bound, or after either buffer was replaced.
+    bind_group: Option<BindGroup>,
+    // The object uniform buffer the bind group was created with.
+    objects: Option<Buffer>,
+    // The size of the uniforms of one object.
+    object_size: BufferAddress,
+    // The number of matrices the buffer can hold.
+    capacity: usize,
+}
+
+impl JointBuffer {
+    /// Room for `capacity` matrices, bound next to object uniforms of
+    /// `object_size` bytes, selected with a dynamic offset.
+    pub fn new(device: &Device, object_size: BufferAddress, capacity: usize) -> Self {
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("skinned_object_bind_group_layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: true,
+                        min_binding_size: BufferSize::new(object_size),
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Storage { read_only: true },
+                        has_dynamic_offset: false,
+                        min_binding_size: BufferSize::new(MATRIX_SIZE),
+                    },
+                    count: None,
+                },
+            ],
+        });
+
+        Self {
+            buffer: Self::create_buffer(device, capacity),
+            layout,
+            bind_group: None,
+            objects: None,
+            object_size,
+            capacity,
+        }
+    }
+
+    fn create_buffer(device: &Device, capacity: usize) -> Buffer {
+        device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("joint_buffer"),
+            size: capacity as BufferAddress * MATRIX_SIZE,
+            usage: BufferUsages::STORAGE | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        })
+    }
+
+    /// Make sure at least `required` matrices fit into the buffer.
+    ///
+    /// The buffer contents are not preserved, but every skin is rewritten
+    /// each frame anyway.
+    pub fn reserve(&mut self, device: &Device, required: usize) {
+        if required <= self.capacity {
+            return;
+        }
+
+        let capacity = required.next_power_of_two();
+        self.buffer = Self::create_buffer(device, capacity);
+        self.bind_group = None;
+        self.capacity = capacity;
+    }
+
+    /// Bind the joints together with the object uniforms in `objects`,
+    /// unless they already are.
+    pub fn bind(&mut self, device: &Device, objects: &Buffer) {
+        if self.bind_group.is_some() && self.objects.as_ref() == Some(objects) {
+            return;
+        }
+
+        self.bind_group = Some(device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("skinned_object_bind_group"),
+            layout: &self.layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                        buffer: objects,
+                        offset: 0,
+                        size: BufferSize::new(self.object_size),
+                    }),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: self.buffer.as_entire_binding(),
+                },
+            ],
+        }));
+        self.objects = Some(objects.clone());
+    }
+
+    pub fn buffer(&self) -> &Buffer {
+        &self.buffer
+    }
+
+    pub fn layout(&self) -> &BindGroupLayout {
+        &self.layout
+    }
+
+    /// The bind group of the last [bind](Self::bind).
+    pub fn bind_group(&self) -> &BindGroup {
+        self.bind_group
+            .as_ref()
+            .expect("the joints are bound before drawing")
+    }
+}
+
+/// Write `matrices` into `bytes` the way the shader reads them, column by
+/// column.
+pub fn write_matrices(matrices: &[Matrix<f32, 4, 4>], bytes: &mut [u8]) {
+    for (matrix, bytes) in matrices
+        .iter()
+        .zip(bytes.chunks_exact_mut(MATRIX_SIZE as usize))
+    {
+        bytes.copy_from_slice(bytemuck::bytes_of(&matrix.to_cols_array()));
+    }
+}
@@ -0,0 +1,185 @@
+use std::borrow::Cow;
+
+use lina::matrix::Matrix;
+use wgpu::{
+    BindGroup, BindGroupLayout, Buffer, BufferAddress, BufferUsages, DepthBiasState,
+    DepthStencilState, Device, Queue, RenderPass, RenderPipeline, Sampler, StencilState,
+    TextureFormat,
+};
+
+use crate::texture::Cubemap;
+
+/// Draws a [Cubemap] behind everything else.
+///
+/// Only the rotation of the camera is applied, so the sky never gets closer.
+/// It is drawn after the opaque geometry with depth writes disabled,
+/// only covering the pixels nothing else was drawn to.
+pub struct SkyboxPipeline {
+    pipeline: RenderPipeline,
+    layout: BindGroupLayout,
+    uniform_buffer: Buffer,
+    sampler: Sampler,
+    // The cubemap is kept alive for the lifetime of the bind group.
+    skybox: Option<(Cubemap, BindGroup)>,
+}
+
+impl SkyboxPipeline {
+    pub fn new(device: &Device, color_format: TextureFormat, sample_count: u32) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("skybox_shader"),
+            source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(include_str!("skybox.wgsl"))),
+        });
+
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("skybox_bind_group_layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::Cube,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("skybox_pipeline_layout"),
+            bind_group_layouts: &[&layout],
+            immediate_size: 0,
+        });
+
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("skybox_pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                // The cube is generated in the shader.
+                buffers: &[],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(color_format.into())],
+                compilation_options: Default::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                cull_mode: None,
+                ..Default::default()
+            },
+            // The sky is at depth 0.0, reverse-Z's infinity, which only
+            // passes where the depth buffer is still cleared.
+            depth_stencil: Some(DepthStencilState {
+                format: wgpu::TextureFormat::Depth32Float,
+                depth_compare: wgpu::CompareFunction::GreaterEqual,
+                depth_write_enabled: false,
+                stencil: StencilState::default(),
+                bias: DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState {
+                count: sample_count,
+                ..Default::default()
+            },
+            multiview_mask: None,
+            cache: None,
+        });
+
+        let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("skybox_uniform_buffer"),
+            size: std::mem::size_of::<Matrix<f32, 4, 4>>() as BufferAddress,
+            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("skybox_sampler"),
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        Self {
+            pipeline,
+            layout,
+            uniform_buffer,
+            sampler,
+            skybox: None,
+        }
+    }
+
+    /// Replace the drawn [Cubemap], `None` leaves the background black.
+    pub fn set_cubemap(&mut self, device: &Device, cubemap: Option<Cubemap>) {
+        self.skybox = cubemap.map(|cubemap| {
+            let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("skybox_bind_group"),
+                layout: &self.layout,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: self.uniform_buffer.as_entire_binding(),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: wgpu::BindingResource::TextureView(cubemap.view()),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 2,
+                        resource: wgpu::BindingResource::Sampler(&self.sampler),
+                    },
+                ],
+            });
+            (cubemap, bind_group)
+        });
+    }
+
+    /// Upload the camera for the next [draw](SkyboxPipeline::draw).
+    ///
+    /// `view` is the view matrix of the camera, its translation is dropped.
+    pub fn update(&self, queue: &Queue, view: Matrix<f32, 4, 4>, projection: Matrix<f32, 4, 4>) {
+        let mut rotation = view;
+        for i in 0..3 {
+            rotation[(i, 3)] = 0.0;
+        }
+        let view_rotation_projection = (projection * rotation).to_cols_array();
+        queue.write_buffer(
+            &self.uniform_buffer,
+            0,
+            bytemuck::bytes_of(&view_rotation_projection),
+        );
+    }
+
+    /// Draw the sky, if there is one.
+    ///
+    /// Returns the number of vertices drawn.
+    pub fn draw(&self, render_pass: &mut RenderPass) -> u32 {
+        let Some((_, bind_group)) = &self.skybox else {
+            return 0;
+        };
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, bind_group, &[]);
+        render_pass.draw(0..36, 0..1);
+        36
+    }
+}
@@ -0,0 +1,58 @@
+// The skybox is a unit cube around the camera, pushed infinitely far away.
+
+@group(0)
+@binding(0)
+// The view projection matrix without the translation of the camera.
+var<uniform> view_rotation_projection: mat4x4f;
+
+@group(0)
+@binding(1)
+var skybox_texture: texture_cube<f32>;
+
+@group(0)
+@binding(2)
+var skybox_sampler: sampler;
+
+// The camera is inside the cube, so the winding of the faces doesn't matter.
+const CORNERS = array<vec3f, 8>(
+    vec3f(-1.0, -1.0, -1.0),
+    vec3f(1.0, -1.0, -1.0),
+    vec3f(-1.0, 1.0, -1.0),
+    vec3f(1.0, 1.0, -1.0),
+    vec3f(-1.0, -1.0, 1.0),
+    vec3f(1.0, -1.0, 1.0),
+    vec3f(-1.0, 1.0, 1.0),
+    vec3f(1.0, 1.0, 1.0),
+);
+
+const INDICES = array<u32, 36>(
+    0u, 1u, 2u, 2u, 1u, 3u, // -Z
+    4u, 6u, 5u, 5u, 6u, 7u, // +Z
+    0u, 2u, 4u, 4u, 2u, 6u, // -X
+    1u, 5u, 3u, 3u, 5u, 7u, // +X
+    0u, 4u, 1u, 1u, 4u, 5u, // -Y
+    2u, 3u, 6u, 6u, 3u, 7u, // +Y
+);
+
+struct VSOutput {
+    @builtin(position) position: vec4f,
+    // Direction from the camera, interpolated over the faces.
+    @location(0) direction: vec3f,
+};
+
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> VSOutput {
+    let corner = CORNERS[INDICES[index]];
+
+    var vsOut: VSOutput;
+    let position = view_rotation_projection * vec4f(corner, 1.0);
+    // Reverse-Z, a depth of 0 is infinitely far away.
+    vsOut.position = vec4f(position.xy, 0.0, position.w);
+    vsOut.direction = corner;
+    return vsOut;
+}
+
+@fragment
+fn fs_main(vsOut: VSOutput) -> @location(0) vec4f {
+    return textureSample(skybox_texture, skybox_sampler, vsOut.direction);
+}
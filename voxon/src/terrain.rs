@@ -0,0 +1,291 @@
+//! Heightmap terrain.
+//!
+//! A [Heightmap] is a regular grid of height samples in the XZ plane, loaded
+//! from a grayscale image or generated. For rendering it is split into square
+//! tiles, each with meshes of decreasing detail: level `n` only uses every
+//! `2^n`th sample. The tiles switch between the levels by their distance to
+//! the camera, see [Lod](crate::components::Lod).
+//!
+//! Neighboring tiles of different levels don't line up exactly, so every tile
+//! mesh has a skirt, a strip hanging down from its edges hiding the cracks.
+
+use graphic::noise::{Fbm, Noise, Simplex};
+use lina::{v, vector::Vector};
+
+use crate::mesh::{Mesh, Vertex};
+
+/// Height samples on a grid, `x` changing the fastest.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Heightmap {
+    width: usize,
+    depth: usize,
+    heights: Vec<f32>,
+}
+
+impl Heightmap {
+    /// Sample `height` at every point of a `width` by `depth` grid.
+    ///
+    /// # Panics
+    ///
+    /// If the grid has less than 2 samples along either axis, as it
+    /// wouldn't have any cells.
+    pub fn from_fn(width: usize, depth: usize, height: impl Fn(usize, usize) -> f32) -> Self {
+        assert!(
+            width >= 2 && depth >= 2,
+            "a heightmap needs at least 2x2 samples"
+        );
+        let heights = (0..depth)
+            .flat_map(|z| (0..width).map(move |x| (x, z)))
+            .map(|(x, z)| height(x, z))
+            .collect();
+        Self {
+            width,
+            depth,
+            heights,
+        }
+    }
+
+    /// Decode a grayscale image, e.g. a 16 bit PNG, with a sample per pixel.
+    ///
+    /// Black is at height 0 and white at `max_height`. The top row of the
+    /// image is at the lowest `z`.
+    pub fn from_image(bytes: &[u8], max_height: f32) -> Result<Self, image::ImageError> {
+        let image = image::load_from_memory(bytes)?.into_luma16();
+        let (width, depth) = image.dimensions();
+        if width < 2 || depth < 2 {
+            return Err(image::ImageError::Limits(
+                image::error::LimitError::from_kind(image::error::LimitErrorKind::DimensionError),
+            ));
+        }
+        Ok(Self::from_fn(width as usize, depth as usize, |x, z| {
+            image.get_pixel(x as u32, z as u32).0[0] as f32 / u16::MAX as f32 * max_height
+        }))
+    }
+
+    /// The number of samples along the X axis.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// The number of samples along the Z axis.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// The height of the sample at `(x, z)`, clamped to the grid.
+    pub fn height(&self, x: usize, z: usize) -> f32 {
+        self.heights[x.min(self.width - 1) + z.min(self.depth - 1) * self.width]
+    }
+
+    /// The surface normal at the sample `(x, z)` with samples `cell_size` apart,
+    /// from the slope towards the neighboring samples.
+    pub fn normal(&self, x: usize, z: usize, cell_size: f32) -> Vector<f32, 3> {
+        let slope = |low: usize, high: usize, height: &dyn Fn(usize) -> f32| {
+            (height(high) - height(low)) / ((high - low) as f32 * cell_size)
+        };
+        let dx = slope(x.saturating_sub(1), (x + 1).min(self.width - 1), &|x| {
+            self.height(x, z)
+        });
+        let dz = slope(z.saturating_sub(1), (z + 1).min(self.depth - 1), &|z| {
+            self.height(x, z)
+        });
+        v![-dx, 1.0, -dz].normalized()
+    }
+}
+
+/// Rolling hills up to `amplitude` above and below 0, `width` by `depth`
+/// samples large. The same `seed` always generates the same hills.
+pub fn generate_heightmap(seed: u64, width: usize, depth: usize, amplitude: f32) -> Heightmap {
+    let hills = Fbm::new(Simplex::new(seed), 5).with_frequency(1.0 / 96.0);
+    Heightmap::from_fn(width, depth, |x, z| {
+        amplitude * hills.sample2(x as f32, z as f32)
+    })
+}
+
+/// How a [Heightmap] is placed in the world and split into tiles,
+/// see [Scene::add_terrain](crate::scene::Scene::add_terrain).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TerrainSettings {
+    /// Where the first sample of the heightmap is placed.
+    pub origin: Vector<f32, 3>,
+    /// The distance between neighboring samples.
+    pub cell_size: f32,
+    /// The number of cells along the edges of a tile, the tiles on the far
+    /// edges of the heightmap may be smaller.
+    pub tile_cells: usize,
+    /// The number of detail levels of each tile, at least 1.
+    pub lod_levels: u32,
+    /// Up to which distance the most detailed level is used, see
+    /// [Lod::distance](crate::components::Lod::distance).
+    pub lod_distance: f32,
+}
+
+impl Default for TerrainSettings {
+    fn default() -> Self {
+        Self {
+            origin: v![0.0, 0.0, 0.0],
+            cell_size: 1.0,
+            tile_cells: 32,
+            lod_levels: 4,
+            lod_distance: 48.0,
+        }
+    }
+}
+
+impl TerrainSettings {
+    /// The first sample of every tile along an axis with `samples` samples.
+    pub fn tile_origins(&self, samples: usize) -> impl Iterator<Item = usize> {
+        (0..samples - 1).step_by(self.tile_cells)
+    }
+}
+
+/// The mesh of the tile starting at the sample `origin`, using every
+/// `2^lod`th sample. The tile is `tile_cells` cells large unless the
+/// heightmap ends before.
+///
+/// The vertices are relative to the `origin` sample at height 0, the
+/// texture repeats once per cell.
+pub fn tile_mesh(
+    heightmap: &Heightmap,
+    settings: &TerrainSettings,
+    origin: [usize; 2],
+    lod: u32,
+) -> Mesh {
+    let step = 1 << lod;
+    // The sample offsets used along each axis, always including the last one.
+    let offsets = |origin: usize, samples: usize| {
+        let extent = settings.tile_cells.min(samples - 1 - origin);
+        (0..extent)
+            .step_by(step)
+            .chain([extent])
+            .collect::<Vec<_>>()
+    };
+    let xs = offsets(origin[0], heightmap.width());
+    let zs = offsets(origin[1], heightmap.depth());
+
+    let cell_size = settings.cell_size;
+    let vertex = |x: usize, z: usize, drop: f32| {
+        let (sample_x, sample_z) = (origin[0] + x, origin[1] + z);
+        let height = heightmap.height(sample_x, sample_z) - drop;
+        let normal = heightmap.normal(sample_x, sample_z, cell_size);
+        Vertex::new(
+            v![x as f32 * cell_size, height, z as f32 * cell_size, 1.0],
+            normal,
+            v![x as f32, z as f32],
+        )
+    };
+
+    let mut vertices = Vec::new();
+    let mut indices = Vec::new();
+
+    for &z in &zs {
+        for &x in &xs {
+            vertices.push(vertex(x, z, 0.0));
+        }
+    }
+    let columns = xs.len() as u32;
+    for row in 0..zs.len() as u32 - 1 {
+        for column in 0..columns - 1 {
+            let corner = row * columns + column;
+            let (below, right) = (corner + columns, corner + 1);
+            // Counter-clockwise seen from above.
+            indices.extend([corner, below, right, right, below, below + 1]);
+        }
+    }
+
+    // Walk around the edge so that the skirt faces outwards: along +X at the
+    // lowest z, along +Z at the highest x, then back along -X and -Z.
+    let (last_x, last_z) = (*xs.last().unwrap(), *zs.last().unwrap());
+    let edge = xs
+        .iter()
+        .map(|&x| (x, 0))
+        .chain(zs.iter().skip(1).map(|&z| (last_x, z)))
+        .chain(xs.iter().rev().skip(1).map(|&x| (x, last_z)))
+        .chain(zs.iter().rev().skip(1).map(|&z| (0, z)))
+        .collect::<Vec<_>>();
+    // Deep enough to cover the height error of the level.
+    let skirt_depth = step as f32 * cell_size;
+    for pair in edge.windows(2) {
+        let first = vertices.len() as u32;
+        for &(x, z) in pair {
+            vertices.push(vertex(x, z, 0.0));
+            vertices.push(vertex(x, z, skirt_depth));
+        }
+        // Top and bottom of the two edge samples.
+        let [top, bottom, next_top, next_bottom] = [first, first + 1, first + 2, first + 3];
+        indices.extend([top, next_top, bottom, bottom, next_top, next_bottom]);
+    }
+
+    Mesh::new(vertices, indices)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use lina::v;
+
+    use super::*;
+
+    fn flat(width: usize, depth: usize) -> Heightmap {
+        Heightmap::from_fn(width, depth, |_, _| 0.0)
+    }
+
+    fn skirt_quads(cells_x: usize, cells_z: usize) -> usize {
+        2 * (cells_x + cells_z)
+    }
+
+    #[test]
+    fn tile_levels_skip_samples() {
+        let heightmap = flat(33, 33);
+        let settings = TerrainSettings {
+            tile_cells: 32,
+            ..Default::default()
+        };
+
+        let detailed = tile_mesh(&heightmap, &settings, [0, 0], 0);
+        assert_eq!(detailed.indices().len() / 6, 32 * 32 + skirt_quads(32, 32));
+        let coarse = tile_mesh(&heightmap, &settings, [0, 0], 3);
+        assert_eq!(coarse.indices().len() / 6, 4 * 4 + skirt_quads(4, 4));
+    }
+
+    #[test]
+    fn tiles_at_the_edge_are_cut_off() {
+        let heightmap = flat(39, 33);
+        let settings = TerrainSettings::default();
+        assert_eq!(settings.tile_origins(39).collect::<Vec<_>>(), [0, 32]);
+
+        // 6 cells are left along X, which the level's step of 4 doesn't divide.
+        let mesh = tile_mesh(&heightmap, &settings, [32, 0], 2);
+        assert_eq!(mesh.indices().len() / 6, 2 * 8 + skirt_quads(2, 8));
+        let positions = bytemuck::cast_slice::<_, [f32; 9]>(mesh.vertices());
+        let max_x = positions.iter().map(|vertex| vertex[0]).fold(0.0, f32::max);
+        assert_eq!(max_x, 6.0);
+    }
+
+    #[test]
+    fn normals_follow_the_slope() {
+        let slope = Heightmap::from_fn(4, 4, |x, _| x as f32 * 2.0);
+        let normal = slope.normal(1, 1, 2.0);
+        assert!(normal.approx_eq(&v![-1.0, 1.0, 0.0].normalized(), 1e-6));
+        // One sided at the edges.
+        assert!(slope.normal(0, 0, 2.0).approx_eq(&normal, 1e-6));
+        assert_eq!(flat(2, 2).normal(1, 1, 1.0), v![0.0, 1.0, 0.0]);
+    }
+
+    #[test]
+    fn loads_grayscale_images() {
+        let image = image::ImageBuffer::from_fn(3, 2, |x, y| {
+            image::Luma([if (x, y) == (2, 1) { u16::MAX } else { 0 }])
+        });
+        let mut png = Vec::new();
+        image
+            .write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+
+        let heightmap = Heightmap::from_image(&png, 10.0).unwrap();
+        assert_eq!((heightmap.width(), heightmap.depth()), (3, 2));
+        assert_eq!(heightmap.height(2, 1), 10.0);
+        assert_eq!(heightmap.height(1, 1), 0.0);
+    }
+}
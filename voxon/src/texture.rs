@@ -0,0 +1,276 @@
+use wgpu::{
+    BindGroup, BindGroupLayout, Device, Extent3d, Queue, Sampler, TexelCopyBufferLayout,
+    TexelCopyTextureInfo, TextureDescriptor, TextureUsages, TextureView,
+};
+
+/// A 2D texture residing on the GPU.
+pub struct Texture {
+    #[allow(dead_code)] // the view keeps it alive, but it will be needed for updates
+    texture: wgpu::Texture,
+    view: TextureView,
+}
+
+impl Texture {
+    /// The format of all color textures.
+    ///
+    /// Image files are expected to be stored in sRGB color space,
+    /// the GPU converts them into linear space when sampling.
+    pub const FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8UnormSrgb;
+
+    /// Decode an encoded PNG or JPEG image and upload it to the GPU.
+    ///
+    /// The image format is detected from the data itself.
+    pub fn from_bytes(
+        device: &Device,
+        queue: &Queue,
+        bytes: &[u8],
+        label: &str,
+    ) -> Result<Self, image::ImageError> {
+        let image = image::load_from_memory(bytes)?.to_rgba8();
+        Ok(Self::from_rgba8(device, queue, &image, label))
+    }
+
+    /// Upload an already decoded image to the GPU.
+    pub fn from_rgba8(
+        device: &Device,
+        queue: &Queue,
+        image: &image::RgbaImage,
+        label: &str,
+    ) -> Self {
+        let size = Extent3d {
+            width: image.width(),
+            height: image.height(),
+            depth_or_array_layers: 1,
+        };
+
+        let texture = device.create_texture(&TextureDescriptor {
+            label: Some(label),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: Self::FORMAT,
+            usage: TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+
+        queue.write_texture(
+            TexelCopyTextureInfo {
+                texture: &texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            image.as_raw(),
+            TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(4 * image.width()),
+                rows_per_image: Some(image.height()),
+            },
+            size,
+        );
+
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        Self { texture, view }
+    }
+
+    pub fn view(&self) -> &TextureView {
+        &self.view
+    }
+}
+
+/// A cube texture, one square image for each face.
+pub struct Cubemap {
+    #[allow(dead_code)] // the view keeps it alive
+    texture: wgpu::Texture,
+    view: TextureView,
+}
+
+impl Cubemap {
+    /// Decode six encoded PNG or JPEG images and upload them to the GPU.
+    ///
+    /// The faces are ordered +X, -X, +Y, -Y, +Z, -Z.
+    pub fn from_bytes(
+        device: &Device,
+        queue: &Queue,
+        faces: [&[u8]; 6],
+        label: &str,
+    ) -> Result<Self, image::ImageError> {
+        let mut images = Vec::with_capacity(6);
+        for bytes in faces {
+            images.push(image::load_from_memory(bytes)?.to_rgba8());
+        }
+        Ok(Self::from_rgba8(device, queue, &images, label))
+    }
+
+    /// Upload six already decoded images to the GPU, ordered as in
+    /// [from_bytes](Cubemap::from_bytes).
+    ///
+    /// Panics if there aren't six faces or they differ in size.
+    pub fn from_rgba8(
+        device: &Device,
+        queue: &Queue,
+        faces: &[image::RgbaImage],
+        label: &str,
+    ) -> Self {
+        assert_eq!(faces.len(), 6, "a cubemap has six faces");
+        let (width, height) = faces[0].dimensions();
+        assert!(
+            faces
+                .iter()
+                .all(|face| face.dimensions() == (width, height)),
+            "all faces of a cubemap have the same size"
+        );
+
+        let texture = device.create_texture(&TextureDescriptor {
+            label: Some(label),
+            size: Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 6,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: Texture::FORMAT,
+            usage: TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+
+        for (layer, face) in faces.iter().enumerate() {
+            queue.write_texture(
+                TexelCopyTextureInfo {
+                    texture: &texture,
+                    mip_level: 0,
+                    origin: wgpu::Origin3d {
+                        x: 0,
+                        y: 0,
+                        z: layer as u32,
+                    },
+                    aspect: wgpu::TextureAspect::All,
+                },
+                face.as_raw(),
+                TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(4 * width),
+                    rows_per_image: Some(height),
+                },
+                Extent3d {
+                    width,
+                    height,
+                    depth_or_array_layers: 1,
+                },
+            );
+        }
+
+        let view = texture.create_view(&wgpu::TextureViewDescriptor {
+            dimension: Some(wgpu::TextureViewDimension::Cube),
+            ..Default::default()
+        });
+
+        Self { texture, view }
+    }
+
+    pub fn view(&self) -> &TextureView {
+        &self.view
+    }
+}
+
+/// Create a sampler repeating the texture in every direction.
+///
+/// Magnification uses the nearest texel, keeping the blocky look
+/// of low resolution textures.
+pub fn create_repeating_sampler(device: &Device) -> Sampler {
+    device.create_sampler(&wgpu::SamplerDescriptor {
+        label: Some("repeating_sampler"),
+        address_mode_u: wgpu::AddressMode::Repeat,
+        address_mode_v: wgpu::AddressMode::Repeat,
+        address_mode_w: wgpu::AddressMode::Repeat,
+        mag_filter: wgpu::FilterMode::Nearest,
+        min_filter: wgpu::FilterMode::Linear,
+        ..Default::default()
+    })
+}
+
+/// The surface description of an object.
+///
+/// Binds a [Texture] and a [Sampler] into the material bind group
+/// (`@group(2)` in the shader).
+pub struct Material {
+    #[allow(dead_code)] // kept alive for the lifetime of the bind group
+    texture: Texture,
+    #[allow(dead_code)]
+    sampler: Sampler,
+    bind_group: BindGroup,
+}
+
+impl Material {
+    /// Create the bind group layout every [Material] is created with.
+    pub fn create_bind_group_layout(device: &Device) -> BindGroupLayout {
+        device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("material_bind_group_layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        })
+    }
+
+    pub fn new(
+        device: &Device,
+        layout: &BindGroupLayout,
+        texture: Texture,
+        sampler: Sampler,
+    ) -> Self {
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("material_bind_group"),
+            layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(texture.view()),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&sampler),
+                },
+            ],
+        });
+
+        Self {
+            texture,
+            sampler,
+            bind_group,
+        }
+    }
+
+    pub fn bind_group(&self) -> &BindGroup {
+        &self.bind_group
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn block_texture_decodes() {
+        let image = image::load_from_memory(include_bytes!("../assets/block.png"))
+            .unwrap()
+            .to_rgba8();
+        assert_eq!(image.dimensions(), (16, 16));
+    }
+}
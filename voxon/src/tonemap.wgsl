@@ -0,0 +1,54 @@
+// Maps the HDR colors of the scene into the displayable range.
+
+struct Tonemapping {
+    exposure: f32,
+    // 0 clamps, 1 is Reinhard, 2 ACES.
+    tonemapper: u32,
+    // Whether the output format leaves the sRGB encoding to the shader.
+    encode_srgb: u32,
+};
+
+@group(1)
+@binding(0)
+var<uniform> tonemapping: Tonemapping;
+
+// Krzysztof Narkowicz's fit of the ACES filmic curve.
+fn aces(color: vec3f) -> vec3f {
+    let a = 2.51;
+    let b = 0.03;
+    let c = 2.43;
+    let d = 0.59;
+    let e = 0.14;
+    return (color * (a * color + b)) / (color * (c * color + d) + e);
+}
+
+fn srgb_encode(color: vec3f) -> vec3f {
+    let low = color * 12.92;
+    let high = 1.055 * pow(color, vec3f(1.0 / 2.4)) - 0.055;
+    return select(high, low, color <= vec3f(0.0031308));
+}
+
+@fragment
+fn fs_main(in: VSOutput) -> @location(0) vec4f {
+    let hdr = textureSample(input_texture, input_sampler, in.uv);
+    let color = max(hdr.rgb * tonemapping.exposure, vec3f(0.0));
+
+    var mapped: vec3f;
+    switch tonemapping.tonemapper {
+        case 1u: {
+            mapped = color / (1.0 + color);
+        }
+        case 2u: {
+            mapped = aces(color);
+        }
+        default: {
+            mapped = color;
+        }
+    }
+    mapped = clamp(mapped, vec3f(0.0), vec3f(1.0));
+
+    if tonemapping.encode_srgb != 0u {
+        mapped = srgb_encode(mapped);
+    }
+    return vec4f(mapped, 1.0);
+}
@@ -0,0 +1,75 @@
+//! Per-frame buffer writes through a ring of reused staging buffers.
+//!
+//! [Queue::write_buffer] allocates staging memory for every call, which adds
+//! up with one write per object each frame. [UploadBelt] sub-allocates the
+//! writes from a few mapped staging buffers instead, which are handed back
+//! for reuse once the GPU has copied them.
+
+use std::num::NonZeroU64;
+
+use wgpu::{Buffer, BufferAddress, CommandEncoder, Device, Queue, util::StagingBelt};
+
+/// The size of a staging buffer, fitting a few thousand objects' uniforms.
+/// Larger writes get a buffer of their own size.
+const CHUNK_SIZE: BufferAddress = 1 << 20;
+
+/// Records buffer writes as copies into an encoder, see [UploadBelt::submit].
+pub struct UploadBelt {
+    belt: StagingBelt,
+}
+
+impl UploadBelt {
+    pub fn new(device: &Device) -> Self {
+        Self {
+            belt: StagingBelt::new(device.clone(), CHUNK_SIZE),
+        }
+    }
+
+    /// Copy `data` to `target` at `offset`, the same as [Queue::write_buffer]
+    /// but ordered with the other commands of `encoder`.
+    ///
+    /// `offset` and the length of `data` have to be multiples of
+    /// [wgpu::COPY_BUFFER_ALIGNMENT].
+    pub fn write(
+        &mut self,
+        encoder: &mut CommandEncoder,
+        target: &Buffer,
+        offset: BufferAddress,
+        data: &[u8],
+    ) {
+        self.write_with(
+            encoder,
+            target,
+            offset,
+            data.len() as BufferAddress,
+            |bytes| bytes.copy_from_slice(data),
+        );
+    }
+
+    /// Fill `size` bytes of `target` starting at `offset` by writing them into
+    /// the staging memory directly, without collecting them first.
+    ///
+    /// The bytes start out with unspecified contents. Nothing is written if
+    /// `size` is 0.
+    pub fn write_with(
+        &mut self,
+        encoder: &mut CommandEncoder,
+        target: &Buffer,
+        offset: BufferAddress,
+        size: BufferAddress,
+        fill: impl FnOnce(&mut [u8]),
+    ) {
+        let Some(size) = NonZeroU64::new(size) else {
+            return;
+        };
+        fill(&mut self.belt.write_buffer(encoder, target, offset, size));
+    }
+
+    /// Submit `encoder` with the writes recorded into it, then take back the
+    /// staging buffers to be reused after the GPU is done with them.
+    pub fn submit(&mut self, queue: &Queue, encoder: CommandEncoder) {
+        self.belt.finish();
+        queue.submit(Some(encoder.finish()));
+        self.belt.recall();
+    }
+}
@@ -0,0 +1,164 @@
+//! Vertex buffer layouts derived from the vertex structs.
+//!
+//! Declaring a `#[repr(C)]` struct inside [vertex_layout!] implements
+//! [VertexLayout] for it, the fields become the attributes of the shader
+//! locations `0, 1, 2, ...` in declaration order. Their offsets, formats and
+//! the stride come from the struct itself, so they can't get out of sync.
+//!
+//! ```ignore
+//! vertex_layout! {
+//!     #[repr(C)]
+//!     #[derive(Clone, Copy, Pod, Zeroable)]
+//!     struct LineVertex {
+//!         position: Vector<f32, 3>, // @location(0) position: vec3f
+//!         color: Color,             // @location(1) color: vec4f
+//!     }
+//! }
+//!
+//! let layout = LineVertex::layout();
+//! ```
+
+use bytemuck::Pod;
+use graphic::color::Color;
+use lina::vector::Vector;
+use wgpu::{BufferAddress, VertexAttribute, VertexBufferLayout, VertexFormat, VertexStepMode};
+
+/// A vertex buffer entry, implemented through [vertex_layout!].
+pub trait VertexLayout: Pod {
+    /// One attribute per field, the shader location being the field's index.
+    const ATTRIBUTES: &'static [VertexAttribute];
+    const SIZE: BufferAddress = std::mem::size_of::<Self>() as BufferAddress;
+
+    /// The layout of a buffer of these vertices, advancing once per vertex.
+    fn layout() -> VertexBufferLayout<'static> {
+        VertexBufferLayout {
+            array_stride: Self::SIZE,
+            step_mode: VertexStepMode::Vertex,
+            attributes: Self::ATTRIBUTES,
+        }
+    }
+}
+
+/// The format a field of this type is read with in the shader.
+pub trait AttributeFormat {
+    const FORMAT: VertexFormat;
+}
+
+macro_rules! attribute_formats {
+    ($($ty:ty => $format:ident),+ $(,)?) => {
+        $(impl AttributeFormat for $ty {
+            const FORMAT: VertexFormat = VertexFormat::$format;
+        })+
+    };
+}
+
+attribute_formats! {
+    f32 => Float32,
+    [f32; 2] => Float32x2,
+    [f32; 3] => Float32x3,
+    [f32; 4] => Float32x4,
+    Vector<f32, 2> => Float32x2,
+    Vector<f32, 3> => Float32x3,
+    Vector<f32, 4> => Float32x4,
+    Color => Float32x4,
+    u32 => Uint32,
+    [u32; 2] => Uint32x2,
+    [u32; 3] => Uint32x3,
+    [u32; 4] => Uint32x4,
+}
+
+/// Number the `(format, offset)` pairs of the fields as shader locations.
+pub const fn attributes<const N: usize>(
+    fields: [(VertexFormat, BufferAddress); N],
+) -> [VertexAttribute; N] {
+    let mut attributes = [VertexAttribute {
+        format: VertexFormat::Float32,
+        offset: 0,
+        shader_location: 0,
+    }; N];
+    let mut i = 0;
+    while i < N {
+        attributes[i] = VertexAttribute {
+            format: fields[i].0,
+            offset: fields[i].1,
+            shader_location: i as u32,
+        };
+        i += 1;
+    }
+    attributes
+}
+
+/// Declare a vertex struct and implement [VertexLayout] for it.
+///
+/// The struct has to be `#[repr(C)]` and every field's type has to
+/// implement [AttributeFormat].
+macro_rules! vertex_layout {
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident {
+            $($(#[$field_meta:meta])* $field_vis:vis $field:ident: $ty:ty),+ $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        $vis struct $name {
+            $($(#[$field_meta])* $field_vis $field: $ty),+
+        }
+
+        impl $crate::vertex::VertexLayout for $name {
+            const ATTRIBUTES: &'static [wgpu::VertexAttribute] = &$crate::vertex::attributes([
+                $((
+                    <$ty as $crate::vertex::AttributeFormat>::FORMAT,
+                    std::mem::offset_of!($name, $field) as wgpu::BufferAddress,
+                )),+
+            ]);
+        }
+    };
+}
+
+pub(crate) use vertex_layout;
+
+#[cfg(test)]
+mod tests {
+    use bytemuck::{Pod, Zeroable};
+    use lina::vector::Vector;
+    use wgpu::{VertexAttribute, VertexFormat};
+
+    use super::VertexLayout;
+
+    vertex_layout! {
+        #[repr(C)]
+        #[derive(Clone, Copy, Pod, Zeroable)]
+        struct TestVertex {
+            position: Vector<f32, 3>,
+            uv: [f32; 2],
+            layer: u32,
+        }
+    }
+
+    #[test]
+    fn attributes_follow_the_fields() {
+        let layout = TestVertex::layout();
+
+        assert_eq!(layout.array_stride, 24);
+        assert_eq!(
+            layout.attributes,
+            [
+                VertexAttribute {
+                    format: VertexFormat::Float32x3,
+                    offset: 0,
+                    shader_location: 0,
+                },
+                VertexAttribute {
+                    format: VertexFormat::Float32x2,
+                    offset: 12,
+                    shader_location: 1,
+                },
+                VertexAttribute {
+                    format: VertexFormat::Uint32,
+                    offset: 20,
+                    shader_location: 2,
+                },
+            ]
+        );
+    }
+}
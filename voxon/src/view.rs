@@ -0,0 +1,367 @@
+use ecs::Entity;
+use graphic::{
+    camera::Camera,
+    projection::Projection,
+    viewport::{ndc_to_screen, screen_to_ndc},
+};
+use lina::{
+    angle::{Deg, Rad},
+    matrix::Matrix,
+    v,
+    vector::Vector,
+};
+use wgpu::{Device, TextureDescriptor, TextureFormat, TextureUsages, TextureView};
+use winit::dpi::PhysicalSize;
+
+use crate::{
+    post::{HDR_FORMAT, PostProcess, PostTargets},
+    world::Ray,
+};
+
+/// The part of a render target a [View] is drawn into, in fractions of
+/// the target's size with the origin in the top left corner.
+///
+/// Being relative, it keeps covering the same part when the target is resized.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Viewport {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Viewport {
+    /// The whole target.
+    pub const FULL: Viewport = Viewport {
+        x: 0.0,
+        y: 0.0,
+        width: 1.0,
+        height: 1.0,
+    };
+
+    /// The `[x, y, width, height]` pixels covered in a target of `size`,
+    /// clamped to the target. Empty viewports have a zero width or height.
+    pub fn pixels(&self, size: PhysicalSize<u32>) -> [u32; 4] {
+        let to_pixels = |fraction: f32, length: u32| {
+            ((fraction * length as f32).round().max(0.0) as u32).min(length)
+        };
+        let (left, right) = (
+            to_pixels(self.x, size.width),
+            to_pixels(self.x + self.width, size.width),
+        );
+        let (top, bottom) = (
+            to_pixels(self.y, size.height),
+            to_pixels(self.y + self.height, size.height),
+        );
+        [
+            left,
+            top,
+            right.saturating_sub(left),
+            bottom.saturating_sub(top),
+        ]
+    }
+}
+
+impl Default for Viewport {
+    fn default() -> Self {
+        Self::FULL
+    }
+}
+
+/// The [Scene](crate::scene::Scene) as seen by one camera entity,
+/// drawn into a [Viewport] of the render target.
+///
+/// Any number of views can be drawn into the same target, e.g. two halves
+/// for split screen or a minimap in a corner on top of the main view.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct View {
+    camera: Entity,
+    projection: Projection,
+    viewport: Viewport,
+}
+
+impl View {
+    /// The view of `camera` with a perspective projection, its aspect
+    /// ratio follows the size of the viewport.
+    pub fn new(camera: Entity, viewport: Viewport) -> Self {
+        let mut projection = Projection::perspective(Deg(90.0), 1.0, -1.0, f32::NEG_INFINITY);
+        // The depth buffer is set up for reverse-Z.
+        projection.set_reverse_z(true);
+
+        Self {
+            camera,
+            projection,
+            viewport,
+        }
+    }
+
+    /// Replace the horizontal field of view of the perspective projection.
+    pub fn with_fov(mut self, fov: impl Into<Rad>) -> Self {
+        self.projection.set_fov(fov);
+        self
+    }
+
+    /// The entity the scene is seen from, it has to have a
+    /// [Camera](graphic::camera::Camera).
+    pub fn camera(&self) -> Entity {
+        self.camera
+    }
+
+    /// The projection for a viewport of `width` by `height` pixels.
+    pub fn projection(&self, width: u32, height: u32) -> Projection {
+        let mut projection = self.projection;
+        projection.set_aspect_ratio(width as f32 / height as f32);
+        projection
+    }
+
+    pub fn viewport(&self) -> Viewport {
+        self.viewport
+    }
+
+    /// Whether the pixel `cursor` of a target of `size` is inside the viewport.
+    pub fn contains(&self, size: PhysicalSize<u32>, cursor: [f32; 2]) -> bool {
+        let [x, y, width, height] = self.viewport.pixels(size).map(|value| value as f32);
+        (x..x + width).contains(&cursor[0]) && (y..y + height).contains(&cursor[1])
+    }
+
+    /// The ray from `camera` through the pixel `cursor` of a target of
+    /// `size`, in world space relative to the camera's eye. It starts on the
+    /// near plane.
+    ///
+    /// Used for picking what is under the cursor.
+    pub fn picking_ray(&self, camera: &Camera, size: PhysicalSize<u32>, cursor: [f32; 2]) -> Ray {
+        let [x, y, width, height] = self.pixel_rect(size);
+        let ndc =
+            screen_to_ndc(width, height, 0.0, 1.0) * v![cursor[0] - x, cursor[1] - y, 0.0, 1.0];
+        let inverse = self
+            .view_projection(camera, size)
+            .inverse()
+            .expect("the view projection matrix is invertible");
+        let unproject = |depth: f32| {
+            let point = inverse * v![ndc[0], ndc[1], depth, 1.0];
+            v![point[0], point[1], point[2]] * (1.0 / point[3])
+        };
+
+        // Half way in depth is still finite with an infinite far plane.
+        let near_depth = if self.projection.reverse_z() {
+            1.0
+        } else {
+            0.0
+        };
+        let near = unproject(near_depth);
+        Ray::new(near, unproject(0.5) - near)
+    }
+
+    /// Where `point` in world space ends up in a target of `size` as seen
+    /// by `camera`, in pixels. `None` if it is behind the camera.
+    pub fn screen_position(
+        &self,
+        camera: &Camera,
+        size: PhysicalSize<u32>,
+        point: Vector<f64, 3>,
+    ) -> Option<[f32; 2]> {
+        let point = (point - camera.eye()).map(|value| value as f32);
+        let clip = self.view_projection(camera, size) * v![point[0], point[1], point[2], 1.0];
+        if clip[3] <= 0.0 {
+            return None;
+        }
+        let [x, y, width, height] = self.pixel_rect(size);
+        let screen = ndc_to_screen(width, height, 0.0, 1.0)
+            * v![clip[0] / clip[3], clip[1] / clip[3], 0.0, 1.0];
+        Some([x + screen[0], y + screen[1]])
+    }
+
+    // Relative to the camera's eye.
+    fn view_projection(&self, camera: &Camera, size: PhysicalSize<u32>) -> Matrix<f32, 4, 4> {
+        let [_, _, width, height] = self.viewport.pixels(size);
+        self.projection(width.max(1), height.max(1)).matrix()
+            * camera.as_relative_transform_matrix(camera.eye())
+    }
+
+    // The pixels of the viewport, at least one wide and high.
+    fn pixel_rect(&self, size: PhysicalSize<u32>) -> [f32; 4] {
+        let [x, y, width, height] = self.viewport.pixels(size);
+        [x, y, width.max(1), height.max(1)].map(|value| value as f32)
+    }
+}
+
+/// The depth, HDR color and multisampled color buffers for targets of a
+/// given size, shared by every [View] drawn into the target.
+pub struct RenderTarget {
+    size: PhysicalSize<u32>,
+    sample_count: u32,
+    // Recreated whenever the target is resized.
+    depth_view: TextureView,
+    // Rendered into and resolved to the HDR image, only used with multisampling.
+    msaa_view: Option<TextureView>,
+    // The HDR image and the other images the post-processing passes
+    // write, the last one writes the frame.
+    post: PostTargets,
+}
+
+impl RenderTarget {
+    /// The sample count has to match the one of the scene, `post` is the
+    /// scene's post-processing chain.
+    pub fn new(
+        device: &Device,
+        size: PhysicalSize<u32>,
+        post: &PostProcess,
+        sample_count: u32,
+    ) -> Self {
+        Self {
+            size,
+            sample_count,
+            depth_view: create_depth_view(device, size, sample_count),
+            msaa_view: create_msaa_view(device, size, HDR_FORMAT, sample_count),
+            post: post.create_targets(device, size),
+        }
+    }
+
+    pub fn size(&self) -> PhysicalSize<u32> {
+        self.size
+    }
+
+    pub fn depth_view(&self) -> &TextureView {
+        &self.depth_view
+    }
+
+    /// `None` without multisampling.
+    pub fn msaa_view(&self) -> Option<&TextureView> {
+        self.msaa_view.as_ref()
+    }
+
+    pub fn post(&self) -> &PostTargets {
+        &self.post
+    }
+
+    /// Adapt to the new size of the target, `post` is the scene's
+    /// post-processing chain.
+    pub fn resize(&mut self, device: &Device, post: &PostProcess, size: PhysicalSize<u32>) {
+        self.size = size;
+        self.depth_view = create_depth_view(device, size, self.sample_count);
+        self.msaa_view = create_msaa_view(device, size, HDR_FORMAT, self.sample_count);
+        self.post = post.create_targets(device, size);
+    }
+}
+
+/// Multisampled color buffer, `None` without multisampling.
+fn create_msaa_view(
+    device: &Device,
+    size: PhysicalSize<u32>,
+    format: TextureFormat,
+    sample_count: u32,
+) -> Option<TextureView> {
+    if sample_count == 1 {
+        return None;
+    }
+    let msaa_texture = device.create_texture(&TextureDescriptor {
+        label: Some("msaa texture"),
+        size: wgpu::Extent3d {
+            width: size.width,
+            height: size.height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count,
+        dimension: wgpu::TextureDimension::D2,
+        format,
+        usage: TextureUsages::RENDER_ATTACHMENT,
+        view_formats: &[],
+    });
+    Some(msaa_texture.create_view(&wgpu::TextureViewDescriptor::default()))
+}
+
+/// The depth buffer has to match the size and sample count of the color
+/// buffer it is used with.
+fn create_depth_view(device: &Device, size: PhysicalSize<u32>, sample_count: u32) -> TextureView {
+    let depth_texture = device.create_texture(&TextureDescriptor {
+        label: Some("depth texture"),
+        size: wgpu::Extent3d {
+            width: size.width,
+            height: size.height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1, // no extra mips, has to be 1
+        sample_count,
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::Depth32Float,
+        usage: TextureUsages::RENDER_ATTACHMENT,
+        view_formats: &[], // no special view format needed
+    });
+    depth_texture.create_view(&wgpu::TextureViewDescriptor::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use ecs::World;
+    use graphic::camera::Camera;
+    use lina::v;
+    use winit::dpi::PhysicalSize;
+
+    use super::{View, Viewport};
+
+    #[test]
+    fn viewport_pixels_are_clamped_to_the_target() {
+        let size = PhysicalSize::new(800, 600);
+        assert_eq!(Viewport::FULL.pixels(size), [0, 0, 800, 600]);
+
+        let minimap = Viewport {
+            x: 0.75,
+            y: 0.0,
+            width: 0.5,
+            height: 0.25,
+        };
+        assert_eq!(minimap.pixels(size), [600, 0, 200, 150]);
+
+        let outside = Viewport {
+            x: 1.5,
+            ..Viewport::FULL
+        };
+        assert_eq!(outside.pixels(size)[2], 0);
+    }
+
+    #[test]
+    fn picking_rays_go_through_the_cursor() {
+        let size = PhysicalSize::new(800, 600);
+        let right_half = Viewport {
+            x: 0.5,
+            width: 0.5,
+            ..Viewport::FULL
+        };
+        let view = View::new(World::new().spawn(), right_half);
+        let camera = Camera::default();
+
+        // The middle of the viewport is straight ahead.
+        let ray = view.picking_ray(&camera, size, [600.0, 300.0]);
+        assert!(ray.direction.approx_eq(&camera.look_direction(), 1e-5));
+        assert!((camera.eye() + ray.origin.map(f64::from)).approx_eq(&v![0.0, 0.0, 4.0], 1e-5));
+
+        let cursor = [450.0, 100.0];
+        let ray = view.picking_ray(&camera, size, cursor);
+        let point = camera.eye() + (ray.origin + ray.direction * 10.0).map(f64::from);
+        let [x, y] = view.screen_position(&camera, size, point).unwrap();
+        assert!((x - cursor[0]).abs() < 1e-2 && (y - cursor[1]).abs() < 1e-2);
+        assert!(view.contains(size, cursor));
+        assert!(!view.contains(size, [300.0, 100.0]));
+
+        // Behind the camera.
+        assert_eq!(view.screen_position(&camera, size, v![0.0, 0.0, 6.0]), None);
+    }
+
+    #[test]
+    fn picks_the_same_far_away() {
+        let size = PhysicalSize::new(800, 600);
+        let view = View::new(World::new().spawn(), Viewport::FULL);
+        let near = Camera::default();
+        let mut far = Camera::default();
+        let offset = v![6_000_000.0, 0.0, -8_000_000.0];
+        far.set_eye(near.eye() + offset);
+
+        let cursor = [123.0, 456.0];
+        let ray = view.picking_ray(&far, size, cursor);
+        assert_eq!(ray, view.picking_ray(&near, size, cursor));
+        let point = far.eye() + (ray.origin + ray.direction * 10.0).map(f64::from);
+        let [x, y] = view.screen_position(&far, size, point).unwrap();
+        assert!((x - cursor[0]).abs() < 1e-2 && (y - cursor[1]).abs() < 1e-2);
+    }
+}
@@ -0,0 +1,22 @@
+// Darkens the corners like a camera lens does, before tonemapping so the
+// highlights stay bright.
+
+struct Vignette {
+    // How much light the corners lose, 0 to 1.
+    strength: f32,
+    // From the center to the corners where darkening starts, 0 to 1.
+    radius: f32,
+};
+
+@group(1)
+@binding(0)
+var<uniform> vignette: Vignette;
+
+@fragment
+fn fs_main(in: VSOutput) -> @location(0) vec4f {
+    let color = textureSample(input_texture, input_sampler, in.uv);
+    // 1 in the corners.
+    let distance = length(in.uv - 0.5) * sqrt(2.0);
+    let falloff = smoothstep(vignette.radius, 1.0, distance);
+    return vec4f(color.rgb * (1.0 - vignette.strength * falloff), color.a);
+}
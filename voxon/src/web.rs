@@ -0,0 +1,57 @@
+//! Running in the browser, built for `wasm32`.
+
+use std::io;
+
+use tracing::Level;
+use tracing_subscriber::fmt::MakeWriter;
+
+/// Writes the log to the browser's console, the errors and warnings as
+/// such.
+pub struct Console;
+
+impl<'a> MakeWriter<'a> for Console {
+    type Writer = ConsoleWriter;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        ConsoleWriter {
+            level: Level::INFO,
+            line: Vec::new(),
+        }
+    }
+
+    fn make_writer_for(&'a self, meta: &tracing::Metadata<'_>) -> Self::Writer {
+        ConsoleWriter {
+            level: *meta.level(),
+            line: Vec::new(),
+        }
+    }
+}
+
+/// Collects one log line, logged to the console when dropped.
+pub struct ConsoleWriter {
+    level: Level,
+    line: Vec<u8>,
+}
+
+impl io::Write for ConsoleWriter {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+        self.line.extend_from_slice(bytes);
+        Ok(bytes.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Drop for ConsoleWriter {
+    fn drop(&mut self) {
+        let line = String::from_utf8_lossy(&self.line);
+        let line = line.trim_end().into();
+        match self.level {
+            Level::ERROR => web_sys::console::error_1(&line),
+            Level::WARN => web_sys::console::warn_1(&line),
+            _ => web_sys::console::log_1(&line),
+        }
+    }
+}
@@ -0,0 +1,24 @@
+/// The type of a single voxel in the [World](super::World).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Block {
+    #[default]
+    Air,
+    Stone,
+    Dirt,
+    Grass,
+}
+
+impl Block {
+    /// Whether the block hides the faces of its neighbors.
+    ///
+    /// Only faces between an opaque and a non opaque block
+    /// are ever meshed.
+    pub fn is_opaque(self) -> bool {
+        !matches!(self, Block::Air)
+    }
+
+    /// Whether nothing can move through the block.
+    pub fn is_solid(self) -> bool {
+        !matches!(self, Block::Air)
+    }
+}
@@ -0,0 +1,67 @@
+use super::Block;
+
+/// The number of blocks along each edge of a [Chunk].
+pub const CHUNK_SIZE: usize = 32;
+
+/// The number of blocks in a [Chunk].
+pub const CHUNK_VOLUME: usize = CHUNK_SIZE * CHUNK_SIZE * CHUNK_SIZE;
+
+/// A `CHUNK_SIZE`³ big cube of blocks.
+///
+/// The blocks are stored in a flat heap allocated array in `x`, `y`, `z`
+/// order, `x` being the fastest changing coordinate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Chunk {
+    blocks: Box<[Block]>,
+}
+
+impl Default for Chunk {
+    fn default() -> Self {
+        Self::filled(Block::Air)
+    }
+}
+
+impl Chunk {
+    /// Create a [Chunk] where every block is `block`.
+    pub fn filled(block: Block) -> Self {
+        Self {
+            blocks: vec![block; CHUNK_VOLUME].into_boxed_slice(),
+        }
+    }
+
+    fn index(x: usize, y: usize, z: usize) -> usize {
+        debug_assert!(x < CHUNK_SIZE && y < CHUNK_SIZE && z < CHUNK_SIZE);
+        x + y * CHUNK_SIZE + z * CHUNK_SIZE * CHUNK_SIZE
+    }
+
+    /// Query the block at the chunk local coordinates.
+    ///
+    /// # Panics
+    ///
+    /// If any coordinate is not smaller than [CHUNK_SIZE].
+    pub fn get(&self, x: usize, y: usize, z: usize) -> Block {
+        self.blocks[Self::index(x, y, z)]
+    }
+
+    /// Overwrite the block at the chunk local coordinates.
+    ///
+    /// # Panics
+    ///
+    /// If any coordinate is not smaller than [CHUNK_SIZE].
+    pub fn set(&mut self, x: usize, y: usize, z: usize, block: Block) {
+        self.blocks[Self::index(x, y, z)] = block;
+    }
+
+    /// The number of blocks which aren't air.
+    pub fn block_count(&self) -> usize {
+        self.blocks
+            .iter()
+            .filter(|block| **block != Block::Air)
+            .count()
+    }
+
+    /// Whether the chunk contains only air, so it has nothing to mesh.
+    pub fn is_empty(&self) -> bool {
+        self.blocks.iter().all(|block| *block == Block::Air)
+    }
+}
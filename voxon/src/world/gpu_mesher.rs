@@ -0,0 +1,240 @@
+use wgpu::{BindGroup, Buffer, BufferAddress, BufferUsages, Device, Queue};
+
+use crate::{
+    compute::{ComputeKernel, read_buffer},
+    mesh::Vertex,
+    vertex::VertexLayout,
+};
+
+use super::{CHUNK_SIZE, CHUNK_VOLUME, ChunkCoord, World};
+
+/// Chunks are uploaded with a border of one block on every side.
+const PADDED_SIZE: usize = CHUNK_SIZE + 2;
+
+/// A checkerboard of blocks shows every face of half the blocks.
+const MAX_FACES: usize = CHUNK_VOLUME / 2 * 6;
+
+const WORKGROUP_SIZE: u32 = 4;
+
+/// The two triangles of a face, whose corners are stored counter-clockwise.
+const QUAD_INDICES: [u32; 6] = [0, 1, 2, 2, 3, 0];
+
+/// The mesh of a chunk generated by the [GpuMesher], already residing on the GPU.
+pub struct ChunkMesh {
+    pub vertex_buffer: Buffer,
+    pub index_buffer: Buffer,
+    pub index_count: usize,
+}
+
+/// Generates chunk meshes with a compute shader, for large numbers of chunks.
+///
+/// Like [greedy_mesh](super::greedy_mesh) only faces between an opaque and a
+/// non opaque block are generated, including the ones on the chunk borders.
+/// The faces aren't merged though, every visible face becomes its own quad
+/// with the same vertex layout, so the result can be drawn the same way.
+/// The order of the faces is unspecified.
+pub struct GpuMesher {
+    kernel: ComputeKernel,
+    blocks: Buffer,
+    face_count: Buffer,
+    // Big enough for the most faces a chunk can have, the used part is
+    // copied into a buffer of its own for every chunk.
+    vertices: Buffer,
+    bind_group: BindGroup,
+}
+
+impl GpuMesher {
+    /// The device has to support compute shaders, see
+    /// [supports_compute](crate::compute::supports_compute).
+    pub fn new(device: &Device) -> Self {
+        let kernel = ComputeKernel::new(
+            device,
+            "mesher",
+            include_str!("mesher.wgsl"),
+            "cs_main",
+            &[
+                ("chunk_size", CHUNK_SIZE as f64),
+                ("workgroup_size", WORKGROUP_SIZE as f64),
+            ],
+            [WORKGROUP_SIZE; 3],
+        );
+
+        let storage_buffer = |label, size, usage| {
+            device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some(label),
+                size,
+                usage: BufferUsages::STORAGE | usage,
+                mapped_at_creation: false,
+            })
+        };
+        let blocks = storage_buffer(
+            "mesher_blocks",
+            (PADDED_SIZE.pow(3) * size_of::<u32>()) as BufferAddress,
+            BufferUsages::COPY_DST,
+        );
+        let face_count = storage_buffer(
+            "mesher_face_count",
+            size_of::<u32>() as BufferAddress,
+            BufferUsages::COPY_DST | BufferUsages::COPY_SRC,
+        );
+        let vertices = storage_buffer(
+            "mesher_vertices",
+            MAX_FACES as BufferAddress * 4 * Vertex::SIZE,
+            BufferUsages::COPY_SRC,
+        );
+        let bind_group = kernel.bind_group(device, &[&blocks, &face_count, &vertices]);
+
+        Self {
+            kernel,
+            blocks,
+            face_count,
+            vertices,
+            bind_group,
+        }
+    }
+
+    /// Generate the mesh of the chunk at `coord` in chunk local space,
+    /// waiting for the GPU to finish.
+    ///
+    /// `None` if the chunk doesn't exist or has no visible faces.
+    pub fn mesh(
+        &self,
+        device: &Device,
+        queue: &Queue,
+        world: &World,
+        coord: ChunkCoord,
+    ) -> Option<ChunkMesh> {
+        let chunk = world.chunk(coord).filter(|chunk| !chunk.is_empty())?;
+
+        let size = CHUNK_SIZE as i32;
+        let origin = coord.map(|value| value * size - 1);
+        let mut blocks = Vec::with_capacity(PADDED_SIZE.pow(3));
+        for z in 0..PADDED_SIZE {
+            for y in 0..PADDED_SIZE {
+                for x in 0..PADDED_SIZE {
+                    let inside = [x, y, z]
+                        .iter()
+                        .all(|value| (1..=CHUNK_SIZE).contains(value));
+                    let block = if inside {
+                        chunk.get(x - 1, y - 1, z - 1)
+                    } else {
+                        world.block(
+                            origin[0] + x as i32,
+                            origin[1] + y as i32,
+                            origin[2] + z as i32,
+                        )
+                    };
+                    // The shader only tells opaque blocks from the others.
+                    blocks.push(block.is_opaque() as u32);
+                }
+            }
+        }
+
+        queue.write_buffer(&self.blocks, 0, bytemuck::cast_slice(&blocks));
+        queue.write_buffer(&self.face_count, 0, bytemuck::bytes_of(&0u32));
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("mesher_encoder"),
+        });
+        self.kernel
+            .dispatch(&mut encoder, &self.bind_group, [CHUNK_SIZE as u32; 3]);
+        queue.submit(Some(encoder.finish()));
+
+        let face_count = read_buffer(device, queue, &self.face_count, 4);
+        let face_count = *bytemuck::from_bytes::<u32>(&face_count);
+        if face_count == 0 {
+            return None;
+        }
+
+        let vertex_size = face_count as BufferAddress * 4 * Vertex::SIZE;
+        let vertex_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("vertex_buffer"),
+            size: vertex_size,
+            usage: BufferUsages::VERTEX | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("mesher_copy_encoder"),
+        });
+        encoder.copy_buffer_to_buffer(&self.vertices, 0, &vertex_buffer, 0, vertex_size);
+        queue.submit(Some(encoder.finish()));
+
+        let indices = (0..face_count)
+            .flat_map(|face| QUAD_INDICES.map(|index| face * 4 + index))
+            .collect::<Vec<_>>();
+        let index_data: &[u8] = bytemuck::cast_slice(&indices);
+        let index_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("index_buffer"),
+            size: index_data.len() as u64,
+            usage: BufferUsages::INDEX | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        queue.write_buffer(&index_buffer, 0, index_data);
+
+        Some(ChunkMesh {
+            vertex_buffer,
+            index_buffer,
+            index_count: indices.len(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lina::v;
+    use wgpu::{Device, Queue};
+
+    use super::*;
+    use crate::{
+        adapter::AdapterSettings,
+        compute::supports_compute,
+        world::{Block, Chunk, greedy_mesh},
+    };
+
+    /// Panics without a GPU adapter supporting compute shaders, the tests
+    /// using it are only run when asked for.
+    fn device() -> (Device, Queue) {
+        let settings = AdapterSettings::default();
+        let instance = pollster::block_on(settings.create_instance());
+        let adapter = pollster::block_on(settings.select_adapter(&instance, None))
+            .unwrap_or_else(|err| panic!("no GPU adapter available: {err}"));
+        assert!(
+            supports_compute(&adapter),
+            "the GPU adapter has no compute shaders"
+        );
+        pollster::block_on(settings.request_device(&adapter))
+            .unwrap_or_else(|err| panic!("the GPU device can't be created: {err}"))
+    }
+
+    fn face_count(mesher: &GpuMesher, device: &Device, queue: &Queue, world: &World) -> usize {
+        mesher
+            .mesh(device, queue, world, v![0, 0, 0])
+            .map_or(0, |mesh| mesh.index_count / 6)
+    }
+
+    #[test]
+    #[ignore = "needs a GPU adapter, run with --include-ignored"]
+    fn meshes_the_visible_faces() {
+        let (device, queue) = device();
+        let mesher = GpuMesher::new(&device);
+
+        let mut chunk = Chunk::default();
+        chunk.set(1, 2, 3, Block::Stone);
+        chunk.set(2, 2, 3, Block::Dirt);
+        let mut world = World::new();
+        world.insert_chunk(v![0, 0, 0], chunk);
+        assert_eq!(face_count(&mesher, &device, &queue, &world), 10);
+        // The greedy mesher can't merge the faces of different blocks either.
+        assert_eq!(greedy_mesh(&world, v![0, 0, 0]).indices().len(), 10 * 6);
+
+        // Only the faces not covered by the neighboring chunk.
+        world.insert_chunk(v![0, 0, 0], Chunk::filled(Block::Stone));
+        world.insert_chunk(v![1, 0, 0], Chunk::filled(Block::Stone));
+        assert_eq!(
+            face_count(&mesher, &device, &queue, &world),
+            5 * CHUNK_SIZE * CHUNK_SIZE
+        );
+
+        world.insert_chunk(v![0, 0, 0], Chunk::default());
+        assert!(mesher.mesh(&device, &queue, &world, v![0, 0, 0]).is_none());
+    }
+}
@@ -0,0 +1,204 @@
+use lina::v;
+
+use crate::mesh::{Mesh, Vertex};
+
+use super::{Block, CHUNK_SIZE, ChunkCoord, World};
+
+/// Generate the [Mesh] of the chunk at `coord` using greedy meshing.
+///
+/// Only faces between an opaque and a non opaque block are generated,
+/// every other face is hidden. On the chunk borders the neighboring
+/// chunks are consulted, so hidden faces between chunks are culled as well.
+///
+/// The visible faces are then merged slice by slice into as few and as large
+/// rectangles as possible, as long as they belong to the same block type.
+/// A completely filled chunk will produce only 6 quads.
+///
+/// The vertices are in chunk local space, with the chunk origin at `(0, 0, 0)`.
+/// If the chunk does not exist or is empty, an empty [Mesh] is produced.
+pub fn greedy_mesh(world: &World, coord: ChunkCoord) -> Mesh {
+    let mut vertices = Vec::new();
+    let mut indices = Vec::new();
+
+    let Some(chunk) = world.chunk(coord).filter(|chunk| !chunk.is_empty()) else {
+        return Mesh::new(vertices, indices);
+    };
+
+    let size = CHUNK_SIZE as i32;
+    let origin = coord.map(|value| value * size);
+    // Positions are chunk local, but may step out of the chunk by one block.
+    let block_at = |position: [i32; 3]| -> Block {
+        if position.iter().all(|value| (0..size).contains(value)) {
+            chunk.get(
+                position[0] as usize,
+                position[1] as usize,
+                position[2] as usize,
+            )
+        } else {
+            world.block(
+                origin[0] + position[0],
+                origin[1] + position[1],
+                origin[2] + position[2],
+            )
+        }
+    };
+
+    // The visible faces of the current slice.
+    let mut mask = vec![None::<Block>; CHUNK_SIZE * CHUNK_SIZE];
+
+    for axis in 0..3 {
+        // The two axes spanning the slice. Chosen in cyclic order so that
+        // `u x v` points along the positive `axis`.
+        let u = (axis + 1) % 3;
+        let v = (axis + 2) % 3;
+
+        for direction in [-1, 1] {
+            let mut normal = [0.0f32; 3];
+            normal[axis] = direction as f32;
+
+            for layer in 0..size {
+                for j in 0..size {
+                    for i in 0..size {
+                        let mut position = [0; 3];
+                        position[axis] = layer;
+                        position[u] = i;
+                        position[v] = j;
+
+                        let block = block_at(position);
+                        let mut neighbor = position;
+                        neighbor[axis] += direction;
+
+                        mask[(i + j * size) as usize] =
+                            (block.is_opaque() && !block_at(neighbor).is_opaque()).then_some(block);
+                    }
+                }
+
+                // The faces of a block facing in the positive direction lie on the
+                // far side of the block.
+                let plane = if direction > 0 { layer + 1 } else { layer };
+
+                for j in 0..CHUNK_SIZE {
+                    let mut i = 0;
+                    while i < CHUNK_SIZE {
+                        let Some(block) = mask[i + j * CHUNK_SIZE] else {
+                            i += 1;
+                            continue;
+                        };
+
+                        let mut width = 1;
+                        while i + width < CHUNK_SIZE
+                            && mask[i + width + j * CHUNK_SIZE] == Some(block)
+                        {
+                            width += 1;
+                        }
+
+                        let mut height = 1;
+                        while j + height < CHUNK_SIZE
+                            && mask[(i + (j + height) * CHUNK_SIZE)..][..width]
+                                .iter()
+                                .all(|face| *face == Some(block))
+                        {
+                            height += 1;
+                        }
+
+                        for row in j..j + height {
+                            mask[(i + row * CHUNK_SIZE)..][..width].fill(None);
+                        }
+
+                        let mut base = [0.0f32; 3];
+                        base[axis] = plane as f32;
+                        base[u] = i as f32;
+                        base[v] = j as f32;
+                        let mut du = [0.0f32; 3];
+                        du[u] = width as f32;
+                        let mut dv = [0.0f32; 3];
+                        dv[v] = height as f32;
+
+                        let first = vertices.len() as u32;
+                        for corner in [[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]] {
+                            let position: [f32; 3] = std::array::from_fn(|k| {
+                                base[k] + corner[0] * du[k] + corner[1] * dv[k]
+                            });
+                            // The texture is repeated once per block.
+                            vertices.push(Vertex::new(
+                                v![position[0], position[1], position[2], 1.0],
+                                v![normal[0], normal[1], normal[2]],
+                                v![corner[0] * width as f32, (1.0 - corner[1]) * height as f32],
+                            ));
+                        }
+
+                        // Counter-clockwise when looking at the face from the direction
+                        // of its normal.
+                        let quad = if direction > 0 {
+                            [0, 1, 2, 2, 3, 0]
+                        } else {
+                            [0, 3, 2, 2, 1, 0]
+                        };
+                        indices.extend(quad.map(|index| first + index));
+
+                        i += width;
+                    }
+                }
+            }
+        }
+    }
+
+    Mesh::new(vertices, indices)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::Chunk;
+
+    fn quad_count(mesh: &Mesh) -> usize {
+        mesh.indices().len() / 6
+    }
+
+    #[test]
+    fn single_block() {
+        let mut chunk = Chunk::default();
+        chunk.set(1, 2, 3, Block::Stone);
+        let mut world = World::new();
+        world.insert_chunk(v![0, 0, 0], chunk);
+
+        let mesh = greedy_mesh(&world, v![0, 0, 0]);
+        assert_eq!(quad_count(&mesh), 6);
+        assert_eq!(mesh.vertices().len(), 24);
+    }
+
+    #[test]
+    fn filled_chunk_merges_into_six_quads() {
+        let mut world = World::new();
+        world.insert_chunk(v![0, 0, 0], Chunk::filled(Block::Stone));
+
+        assert_eq!(quad_count(&greedy_mesh(&world, v![0, 0, 0])), 6);
+    }
+
+    #[test]
+    fn different_blocks_are_not_merged() {
+        let mut chunk = Chunk::default();
+        chunk.set(0, 0, 0, Block::Stone);
+        chunk.set(1, 0, 0, Block::Dirt);
+        let mut world = World::new();
+        world.insert_chunk(v![0, 0, 0], chunk);
+
+        // The shared face is hidden, but the other 4 sides can't be merged.
+        assert_eq!(quad_count(&greedy_mesh(&world, v![0, 0, 0])), 10);
+    }
+
+    #[test]
+    fn faces_between_chunks_are_culled() {
+        let mut world = World::new();
+        world.insert_chunk(v![0, 0, 0], Chunk::filled(Block::Stone));
+        world.insert_chunk(v![1, 0, 0], Chunk::filled(Block::Stone));
+
+        assert_eq!(quad_count(&greedy_mesh(&world, v![0, 0, 0])), 5);
+    }
+
+    #[test]
+    fn missing_chunk_is_empty() {
+        let world = World::new();
+        assert_eq!(quad_count(&greedy_mesh(&world, v![0, 0, 0])), 0);
+    }
+}
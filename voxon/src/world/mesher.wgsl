@@ -0,0 +1,86 @@
+// Emits a quad for every visible face of a chunk, see `GpuMesher`.
+
+override chunk_size: i32;
+override workgroup_size: u32;
+
+// The blocks of the chunk surrounded by a layer of blocks from the neighboring
+// chunks, `chunk_size + 2` along every axis with x changing the fastest.
+// 0 is air, every other block is opaque.
+@group(0) @binding(0)
+var<storage, read> blocks: array<u32>;
+
+@group(0) @binding(1)
+var<storage, read_write> face_count: atomic<u32>;
+
+// 4 vertices per face, each being the 9 floats of a mesh `Vertex`:
+// position (4), normal (3), uv (2). Packed tightly, unlike a `vec3f`.
+@group(0) @binding(2)
+var<storage, read_write> vertices: array<f32>;
+
+fn is_opaque(position: vec3i) -> bool {
+    let padded = chunk_size + 2;
+    let p = position + vec3i(1);
+    return blocks[p.x + p.y * padded + p.z * padded * padded] != 0u;
+}
+
+fn write_vertex(index: u32, position: vec3f, normal: vec3f, uv: vec2f) {
+    let base = index * 9u;
+    vertices[base] = position.x;
+    vertices[base + 1u] = position.y;
+    vertices[base + 2u] = position.z;
+    vertices[base + 3u] = 1.0;
+    vertices[base + 4u] = normal.x;
+    vertices[base + 5u] = normal.y;
+    vertices[base + 6u] = normal.z;
+    vertices[base + 7u] = uv.x;
+    vertices[base + 8u] = uv.y;
+}
+
+@compute @workgroup_size(workgroup_size, workgroup_size, workgroup_size)
+fn cs_main(@builtin(global_invocation_id) id: vec3u) {
+    let position = vec3i(id);
+    if any(position >= vec3i(chunk_size)) || !is_opaque(position) {
+        return;
+    }
+
+    for (var axis = 0; axis < 3; axis++) {
+        // The two axes spanning the face, `u x v` points along the positive `axis`.
+        var du = vec3f(0.0);
+        du[(axis + 1) % 3] = 1.0;
+        var dv = vec3f(0.0);
+        dv[(axis + 2) % 3] = 1.0;
+
+        for (var direction = -1; direction <= 1; direction += 2) {
+            var neighbor = position;
+            neighbor[axis] += direction;
+            if is_opaque(neighbor) {
+                continue;
+            }
+
+            var normal = vec3f(0.0);
+            normal[axis] = f32(direction);
+            // Faces in the positive direction lie on the far side of the block.
+            var base = vec3f(position);
+            if direction > 0 {
+                base[axis] += 1.0;
+            }
+            // Counter-clockwise when looking at the face from the direction
+            // of its normal, the corners of negative faces go the other way.
+            var corners = array(vec2f(0.0, 0.0), vec2f(1.0, 0.0), vec2f(1.0, 1.0), vec2f(0.0, 1.0));
+            if direction < 0 {
+                corners = array(vec2f(0.0, 0.0), vec2f(0.0, 1.0), vec2f(1.0, 1.0), vec2f(1.0, 0.0));
+            }
+
+            let first = atomicAdd(&face_count, 1u) * 4u;
+            for (var i = 0u; i < 4u; i++) {
+                let corner = corners[i];
+                write_vertex(
+                    first + i,
+                    base + corner.x * du + corner.y * dv,
+                    normal,
+                    vec2f(corner.x, 1.0 - corner.y),
+                );
+            }
+        }
+    }
+}
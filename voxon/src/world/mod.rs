@@ -0,0 +1,238 @@
+//! Voxel world
+//!
+//! The world is an unbounded grid of [Block]s, partitioned into
+//! [Chunk]s of [CHUNK_SIZE]³ blocks. Chunks are only stored when
+//! they were explicitly inserted, everything else is considered air.
+//!
+//! Block coordinates are global integer coordinates, where the block at
+//! `(x, y, z)` occupies the unit cube between `(x, y, z)` and
+//! `(x + 1, y + 1, z + 1)` in world space.
+
+use std::collections::{HashMap, HashSet};
+
+use graphic::noise::{Fbm, Noise, Perlin};
+use lina::{v, vector::Vector};
+
+mod block;
+mod chunk;
+mod gpu_mesher;
+mod mesher;
+mod raycast;
+mod streaming;
+
+pub use block::*;
+pub use chunk::*;
+pub use gpu_mesher::*;
+pub use mesher::*;
+pub use raycast::*;
+pub use streaming::*;
+
+/// Coordinates of a [Chunk] in chunk units.
+///
+/// The chunk at `[1, 0, 0]` starts at block `[CHUNK_SIZE, 0, 0]`.
+pub type ChunkCoord = Vector<i32, 3>;
+
+#[derive(Debug, Default)]
+pub struct World {
+    chunks: HashMap<ChunkCoord, Chunk>,
+    // Kept up to date with the chunks, so that counting is cheap.
+    block_count: usize,
+    // Chunks whose meshes are outdated because of edited blocks.
+    dirty: HashSet<ChunkCoord>,
+}
+
+impl World {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Insert or replace the chunk at `coord`.
+    pub fn insert_chunk(&mut self, coord: ChunkCoord, chunk: Chunk) {
+        self.block_count += chunk.block_count();
+        if let Some(replaced) = self.chunks.insert(coord, chunk) {
+            self.block_count -= replaced.block_count();
+        }
+    }
+
+    /// Remove the chunk at `coord`, its blocks become air.
+    pub fn remove_chunk(&mut self, coord: ChunkCoord) -> Option<Chunk> {
+        let chunk = self.chunks.remove(&coord)?;
+        self.block_count -= chunk.block_count();
+        Some(chunk)
+    }
+
+    pub fn chunk(&self, coord: ChunkCoord) -> Option<&Chunk> {
+        self.chunks.get(&coord)
+    }
+
+    /// The number of blocks which aren't air in all chunks.
+    pub fn block_count(&self) -> usize {
+        self.block_count
+    }
+
+    /// Change the block at the global block coordinates, returning the
+    /// previous one.
+    ///
+    /// Nothing is changed and `None` returned if the chunk isn't stored.
+    /// The chunk is marked [dirty](World::take_dirty_chunks), as well as the
+    /// neighboring chunks if the block is on their border.
+    pub fn set_block(&mut self, x: i32, y: i32, z: i32, block: Block) -> Option<Block> {
+        let (coord, local) = Self::split_coords(x, y, z);
+        let chunk = self.chunks.get_mut(&coord)?;
+        let previous = chunk.get(local[0], local[1], local[2]);
+        chunk.set(local[0], local[1], local[2], block);
+        self.block_count += (block != Block::Air) as usize;
+        self.block_count -= (previous != Block::Air) as usize;
+
+        self.dirty.insert(coord);
+        for axis in 0..3 {
+            let direction = match local[axis] {
+                0 => -1,
+                value if value == CHUNK_SIZE - 1 => 1,
+                _ => continue,
+            };
+            let mut neighbor = coord;
+            neighbor[axis] += direction;
+            if self.chunks.contains_key(&neighbor) {
+                self.dirty.insert(neighbor);
+            }
+        }
+        Some(previous)
+    }
+
+    /// Replace the block at the global block coordinates with air, see
+    /// [World::set_block].
+    pub fn remove_block(&mut self, x: i32, y: i32, z: i32) -> Option<Block> {
+        self.set_block(x, y, z, Block::Air)
+    }
+
+    /// The chunks changed by [World::set_block] since the last call, which
+    /// have to be meshed again.
+    pub fn take_dirty_chunks(&mut self) -> Vec<ChunkCoord> {
+        self.dirty.drain().collect()
+    }
+
+    /// Iterate over all stored chunks in no particular order.
+    pub fn chunks(&self) -> impl Iterator<Item = (&ChunkCoord, &Chunk)> {
+        self.chunks.iter()
+    }
+
+    /// Split global block coordinates into the coordinates of the chunk
+    /// containing it and the local coordinates within that chunk.
+    pub fn split_coords(x: i32, y: i32, z: i32) -> (ChunkCoord, [usize; 3]) {
+        let size = CHUNK_SIZE as i32;
+        (
+            v![x.div_euclid(size), y.div_euclid(size), z.div_euclid(size)],
+            [
+                x.rem_euclid(size) as usize,
+                y.rem_euclid(size) as usize,
+                z.rem_euclid(size) as usize,
+            ],
+        )
+    }
+
+    /// Query the block at the global block coordinates.
+    ///
+    /// Blocks in chunks which are not stored are [Block::Air].
+    pub fn block(&self, x: i32, y: i32, z: i32) -> Block {
+        let (coord, [x, y, z]) = Self::split_coords(x, y, z);
+        self.chunks
+            .get(&coord)
+            .map_or(Block::Air, |chunk| chunk.get(x, y, z))
+    }
+}
+
+/// Generate the terrain of the chunk at `coord` in the world of `seed`.
+///
+/// Gentle hills around `y = 0`, with grass on top of a few layers of dirt
+/// over stone. Neighboring chunks of the same seed fit together.
+pub fn generate_chunk(seed: u64, coord: ChunkCoord) -> Chunk {
+    let mut chunk = Chunk::default();
+    let size = CHUNK_SIZE as i32;
+    let hills = Fbm::new(Perlin::new(seed), 3).with_frequency(1.0 / 24.0);
+
+    for z in 0..CHUNK_SIZE {
+        for x in 0..CHUNK_SIZE {
+            let world_x = (coord[0] * size + x as i32) as f32;
+            let world_z = (coord[2] * size + z as i32) as f32;
+            let height = (6.0 * hills.sample2(world_x, world_z)).round() as i32;
+
+            for y in 0..CHUNK_SIZE {
+                let world_y = coord[1] * size + y as i32;
+                let block = if world_y > height {
+                    Block::Air
+                } else if world_y == height {
+                    Block::Grass
+                } else if world_y > height - 3 {
+                    Block::Dirt
+                } else {
+                    Block::Stone
+                };
+                chunk.set(x, y, z, block);
+            }
+        }
+    }
+
+    chunk
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_negative_coords() {
+        let (coord, local) = World::split_coords(-1, 0, CHUNK_SIZE as i32);
+        assert_eq!(coord, [-1, 0, 1]);
+        assert_eq!(local, [CHUNK_SIZE - 1, 0, 0]);
+    }
+
+    #[test]
+    fn missing_chunks_are_air() {
+        let mut world = World::new();
+        world.insert_chunk(v![0, 0, 0], Chunk::filled(Block::Stone));
+
+        assert_eq!(world.block(0, 0, 0), Block::Stone);
+        assert_eq!(world.block(-1, 0, 0), Block::Air);
+    }
+
+    #[test]
+    fn edits_mark_the_touching_chunks_dirty() {
+        let mut world = World::new();
+        world.insert_chunk(v![0, 0, 0], Chunk::default());
+        world.insert_chunk(v![-1, 0, 0], Chunk::default());
+        world.insert_chunk(v![0, 1, 0], Chunk::default());
+
+        assert_eq!(world.set_block(5, 5, 5, Block::Stone), Some(Block::Air));
+        assert_eq!(world.block_count(), 1);
+        assert_eq!(world.take_dirty_chunks(), [[0, 0, 0]]);
+        assert!(world.take_dirty_chunks().is_empty());
+
+        // On the border to the chunk at -X, the one at -Z isn't stored.
+        assert_eq!(world.set_block(0, 5, 0, Block::Dirt), Some(Block::Air));
+        let mut dirty = world.take_dirty_chunks();
+        dirty.sort();
+        assert_eq!(dirty, [[-1, 0, 0], [0, 0, 0]]);
+
+        assert_eq!(world.remove_block(5, 5, 5), Some(Block::Stone));
+        assert_eq!(world.block(5, 5, 5), Block::Air);
+        assert_eq!(world.block_count(), 1);
+        assert_eq!(world.remove_block(100, 0, 0), None);
+    }
+
+    #[test]
+    fn block_count_skips_air() {
+        let mut world = World::new();
+        let mut chunk = Chunk::default();
+        chunk.set(1, 2, 3, Block::Stone);
+        world.insert_chunk(v![0, 0, 0], chunk);
+        world.insert_chunk(v![1, 0, 0], Chunk::filled(Block::Stone));
+
+        assert_eq!(world.block_count(), 1 + CHUNK_VOLUME);
+
+        world.insert_chunk(v![0, 0, 0], Chunk::default());
+        assert_eq!(world.block_count(), CHUNK_VOLUME);
+        world.remove_chunk(v![1, 0, 0]);
+        assert_eq!(world.block_count(), 0);
+    }
+}
@@ -0,0 +1,169 @@
+use lina::vector::Vector;
+
+use super::{Block, World};
+
+/// A half line starting at `origin`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ray {
+    pub origin: Vector<f32, 3>,
+    /// Always normalized.
+    pub direction: Vector<f32, 3>,
+}
+
+impl Ray {
+    pub fn new(origin: Vector<f32, 3>, direction: Vector<f32, 3>) -> Self {
+        Self {
+            origin,
+            direction: direction.normalized(),
+        }
+    }
+}
+
+/// The block hit by [World::raycast].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RaycastHit {
+    pub position: [i32; 3],
+    pub block: Block,
+    /// The normal of the face the ray entered the block through, all zeros
+    /// if the ray started inside of it.
+    pub normal: [i32; 3],
+    /// How far along the ray the block was entered.
+    pub distance: f32,
+}
+
+impl RaycastHit {
+    /// The block in front of the hit face, where a block placed against it goes.
+    pub fn adjacent(&self) -> [i32; 3] {
+        std::array::from_fn(|axis| self.position[axis] + self.normal[axis])
+    }
+}
+
+impl World {
+    /// Find the first block along `ray` which isn't air, up to `max_distance`.
+    /// The ray is in block coordinates relative to the corner of the block
+    /// `start`, which keeps it precise far away from the world's origin.
+    ///
+    /// The blocks are visited in the order the ray passes through them, so
+    /// the cost only grows with the distance, not with the size of the world.
+    pub fn raycast(&self, start: [i32; 3], ray: &Ray, max_distance: f32) -> Option<RaycastHit> {
+        // Relative to `start` until a block is looked up.
+        let mut position: [i32; 3] = std::array::from_fn(|axis| ray.origin[axis].floor() as i32);
+        let step: [i32; 3] = std::array::from_fn(|axis| {
+            let direction = ray.direction[axis];
+            if direction > 0.0 {
+                1
+            } else if direction < 0.0 {
+                -1
+            } else {
+                0
+            }
+        });
+        // How far along the ray a whole block is crossed along each axis,
+        // and where the next block border along each axis is crossed.
+        let delta: [f32; 3] = std::array::from_fn(|axis| (1.0 / ray.direction[axis]).abs());
+        let mut next: [f32; 3] = std::array::from_fn(|axis| {
+            let border = match step[axis] {
+                1 => position[axis] as f32 + 1.0,
+                -1 => position[axis] as f32,
+                _ => return f32::INFINITY,
+            };
+            (border - ray.origin[axis]) * delta[axis] * step[axis] as f32
+        });
+
+        let mut normal = [0; 3];
+        let mut distance = 0.0;
+        while distance <= max_distance {
+            let absolute: [i32; 3] = std::array::from_fn(|axis| start[axis] + position[axis]);
+            let block = self.block(absolute[0], absolute[1], absolute[2]);
+            if block != Block::Air {
+                return Some(RaycastHit {
+                    position: absolute,
+                    block,
+                    normal,
+                    distance,
+                });
+            }
+
+            // Step into the neighbor whose border is crossed first.
+            let axis = (0..3)
+                .min_by(|a, b| next[*a].total_cmp(&next[*b]))
+                .expect("there are three axes");
+            distance = next[axis];
+            next[axis] += delta[axis];
+            position[axis] += step[axis];
+            normal = [0; 3];
+            normal[axis] = -step[axis];
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lina::v;
+
+    use super::*;
+
+    fn world_with(blocks: &[[i32; 3]]) -> World {
+        let mut world = World::new();
+        for [x, y, z] in blocks {
+            let (coord, _) = World::split_coords(*x, *y, *z);
+            if world.chunk(coord).is_none() {
+                world.insert_chunk(coord, Default::default());
+            }
+            world.set_block(*x, *y, *z, Block::Stone);
+        }
+        world
+    }
+
+    #[test]
+    fn hits_the_first_block_along_the_ray() {
+        let world = world_with(&[[3, 0, 0], [5, 0, 0]]);
+        let ray = Ray::new(v![0.5, 0.5, 0.5], v![1.0, 0.0, 0.0]);
+
+        let hit = world.raycast([0; 3], &ray, 10.0).unwrap();
+        assert_eq!(hit.position, [3, 0, 0]);
+        assert_eq!(hit.block, Block::Stone);
+        assert_eq!(hit.normal, [-1, 0, 0]);
+        assert_eq!(hit.distance, 2.5);
+        assert_eq!(hit.adjacent(), [2, 0, 0]);
+
+        assert_eq!(world.raycast([0; 3], &ray, 2.0), None);
+    }
+
+    #[test]
+    fn crosses_chunk_borders_in_negative_directions() {
+        let world = world_with(&[[-2, -3, -1]]);
+        // Diagonally down, entering the block through its top.
+        let ray = Ray::new(v![0.5, 0.5, 0.5], v![-2.5, -3.2, -1.5]);
+
+        let hit = world.raycast([0; 3], &ray, 10.0).unwrap();
+        assert_eq!(hit.position, [-2, -3, -1]);
+        assert_eq!(hit.normal, [0, 1, 0]);
+        // The top at y = -2 is 2.5 below the origin.
+        assert!((hit.distance - 2.5 / ray.direction[1].abs()).abs() < 1e-5);
+    }
+
+    #[test]
+    fn starting_inside_a_block_hits_it() {
+        let world = world_with(&[[0, 0, 0]]);
+        let hit = world
+            .raycast([0; 3], &Ray::new(v![0.5, 0.5, 0.5], v![0.0, 1.0, 0.0]), 1.0)
+            .unwrap();
+        assert_eq!(
+            (hit.position, hit.normal, hit.distance),
+            ([0, 0, 0], [0; 3], 0.0)
+        );
+    }
+
+    #[test]
+    fn starts_from_blocks_far_away() {
+        let far = 50_000_000;
+        let world = world_with(&[[far + 3, 0, 0]]);
+        let ray = Ray::new(v![0.25, 0.5, 0.5], v![1.0, 0.0, 0.0]);
+
+        let hit = world.raycast([far, 0, 0], &ray, 10.0).unwrap();
+        assert_eq!(hit.position, [far + 3, 0, 0]);
+        assert_eq!(hit.distance, 2.75);
+    }
+}
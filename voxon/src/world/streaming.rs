@@ -0,0 +1,244 @@
+use std::{collections::HashMap, ops::RangeInclusive};
+
+use engine::jobs::{Jobs, Task};
+use lina::{v, vector::Vector};
+
+use crate::mesh::Mesh;
+
+use super::{CHUNK_SIZE, Chunk, ChunkCoord, World, generate_chunk, greedy_mesh};
+
+/// Which chunks a [ChunkStreamer] keeps loaded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StreamSettings {
+    /// Chunks up to this many chunks away from the camera's chunk along X
+    /// and Z are loaded. They are unloaded once they are one chunk farther
+    /// away, so moving back and forth over a chunk border doesn't reload them.
+    pub radius: i32,
+    /// The chunk layers along Y which are loaded.
+    pub layers: RangeInclusive<i32>,
+    /// The most chunks handed out per frame by [ChunkStreamer::finished].
+    pub uploads_per_frame: usize,
+}
+
+/// A chunk generated by a job of the [ChunkStreamer].
+pub struct StreamedChunk {
+    pub coord: ChunkCoord,
+    pub chunk: Chunk,
+    /// The [greedy_mesh] of the chunk on its own, `None` if the jobs
+    /// don't mesh the chunks.
+    pub mesh: Option<Mesh>,
+}
+
+/// Generates the chunks around the camera on the [Jobs] workers, so
+/// moving through the world doesn't stall rendering. Where there are no
+/// threads, like in the browser, they are generated a few at a time when
+/// picking them up instead.
+///
+/// Every frame [ChunkStreamer::update] requests the chunks missing around
+/// the camera and tells which ones to unload, then the chunks which are
+/// done are picked up with [ChunkStreamer::finished]. The streamer doesn't
+/// own the [World], the chunks have to be inserted and removed by the caller.
+pub struct ChunkStreamer {
+    settings: StreamSettings,
+    seed: u64,
+    mesh: bool,
+    jobs: Jobs,
+    // The chunk column the camera was in at the last update.
+    center: [i32; 2],
+    // Requested but not picked up yet.
+    pending: HashMap<ChunkCoord, Task<StreamedChunk>>,
+}
+
+/// Generate the chunk at `coord`, meshed on its own with `mesh`.
+fn stream_chunk(seed: u64, coord: ChunkCoord, mesh: bool) -> StreamedChunk {
+    let _span = tracing::debug_span!("stream_chunk", ?coord).entered();
+    let chunk = generate_chunk(seed, coord);
+    let (chunk, mesh) = if mesh {
+        let _span = tracing::debug_span!("mesh_chunk").entered();
+        let mut world = World::new();
+        world.insert_chunk(coord, chunk);
+        let mesh = greedy_mesh(&world, coord);
+        let chunk = world.remove_chunk(coord);
+        (chunk.expect("the chunk was just inserted"), Some(mesh))
+    } else {
+        (chunk, None)
+    };
+    StreamedChunk { coord, chunk, mesh }
+}
+
+impl ChunkStreamer {
+    /// Generate the chunks of the world of `seed` on the workers of `jobs`.
+    ///
+    /// With `mesh` the jobs also mesh them on their own, the faces on the
+    /// chunk borders are always generated then. Otherwise the chunks have to
+    /// be meshed after inserting them, e.g. with the [GpuMesher](super::GpuMesher).
+    pub fn new(seed: u64, settings: StreamSettings, mesh: bool, jobs: Jobs) -> Self {
+        Self {
+            settings,
+            seed,
+            mesh,
+            jobs,
+            center: [0, 0],
+            pending: HashMap::new(),
+        }
+    }
+
+    /// Request the chunks missing from `world` around `position`, the
+    /// camera's position in block coordinates, the nearest ones first.
+    ///
+    /// Returns the chunks of `world` which are out of range now.
+    pub fn update(&mut self, position: Vector<f64, 3>, world: &World) -> Vec<ChunkCoord> {
+        let size = CHUNK_SIZE as f64;
+        self.center = [
+            (position[0] / size).floor() as i32,
+            (position[2] / size).floor() as i32,
+        ];
+
+        // Out of range by now, their jobs finish but the results are dropped.
+        let pending = std::mem::take(&mut self.pending);
+        self.pending = pending
+            .into_iter()
+            .filter(|(coord, _)| self.in_range(*coord))
+            .collect();
+
+        let (radius, center) = (self.settings.radius, self.center);
+        let layers = &self.settings.layers;
+        let mut wanted = (-radius..=radius)
+            .flat_map(|z| (-radius..=radius).map(move |x| [center[0] + x, center[1] + z]))
+            .flat_map(|[x, z]| layers.clone().map(move |y| v![x, y, z]))
+            .filter(|coord| world.chunk(*coord).is_none() && !self.pending.contains_key(coord))
+            .collect::<Vec<_>>();
+        // The workers start with the jobs spawned first.
+        wanted.sort_by_key(|coord| self.distance(*coord));
+        let (seed, mesh) = (self.seed, self.mesh);
+        for coord in wanted {
+            let task = self.jobs.spawn(move || stream_chunk(seed, coord, mesh));
+            self.pending.insert(coord, task);
+        }
+
+        world
+            .chunks()
+            .map(|(coord, _)| *coord)
+            .filter(|coord| !self.in_range(*coord))
+            .collect()
+    }
+
+    /// The chunks generated since the last call, at most
+    /// [StreamSettings::uploads_per_frame], the nearest ones first.
+    pub fn finished(&mut self) -> Vec<StreamedChunk> {
+        let mut finished = Vec::new();
+        for coord in self.pending_by_distance() {
+            if finished.len() == self.settings.uploads_per_frame {
+                break;
+            }
+            let task = self.pending.get_mut(&coord).expect("the chunk is pending");
+            if let Some(streamed) = task.take() {
+                self.pending.remove(&coord);
+                finished.push(streamed);
+            }
+        }
+        finished
+    }
+
+    /// Wait for the nearest of the requested chunks, `None` if there are
+    /// no more.
+    pub fn wait(&mut self) -> Option<StreamedChunk> {
+        let coord = *self.pending_by_distance().first()?;
+        let task = self.pending.remove(&coord).expect("the chunk is pending");
+        Some(task.wait())
+    }
+
+    fn pending_by_distance(&self) -> Vec<ChunkCoord> {
+        let mut coords = self.pending.keys().copied().collect::<Vec<_>>();
+        coords.sort_by_key(|coord| self.distance(*coord));
+        coords
+    }
+
+    /// The distance to the camera's chunk column in chunks, the larger of
+    /// the distances along X and Z.
+    fn distance(&self, coord: ChunkCoord) -> i32 {
+        (coord[0] - self.center[0])
+            .abs()
+            .max((coord[2] - self.center[1]).abs())
+    }
+
+    fn in_range(&self, coord: ChunkCoord) -> bool {
+        self.distance(coord) <= self.settings.radius + 1 && self.settings.layers.contains(&coord[1])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lina::v;
+
+    use super::*;
+
+    fn settings() -> StreamSettings {
+        StreamSettings {
+            radius: 1,
+            layers: -1..=-1,
+            uploads_per_frame: 4,
+        }
+    }
+
+    #[test]
+    fn loads_the_chunks_around_the_camera() {
+        let mut streamer = ChunkStreamer::new(1, settings(), true, Jobs::new(2));
+        let mut world = World::new();
+
+        assert!(streamer.update(v![16.0, 0.0, 16.0], &world).is_empty());
+        while let Some(streamed) = streamer.wait() {
+            assert!(streamed.mesh.is_some());
+            world.insert_chunk(streamed.coord, streamed.chunk);
+        }
+        let mut coords = world.chunks().map(|(coord, _)| *coord).collect::<Vec<_>>();
+        coords.sort();
+        let expected = (-1..=1)
+            .flat_map(|x| (-1..=1).map(move |z| [x, -1, z]))
+            .collect::<Vec<_>>();
+        assert_eq!(coords, expected);
+
+        // Chunks are kept one chunk beyond the radius.
+        let size = CHUNK_SIZE as f64;
+        assert!(
+            streamer
+                .update(v![size + 16.0, 0.0, 16.0], &world)
+                .is_empty()
+        );
+        let mut unloaded = streamer.update(v![2.0 * size + 16.0, 0.0, 16.0], &world);
+        unloaded.sort();
+        assert_eq!(unloaded, [[-1, -1, -1], [-1, -1, 0], [-1, -1, 1]]);
+    }
+
+    #[test]
+    fn hands_out_a_few_chunks_per_frame() {
+        for jobs in [Jobs::new(2), Jobs::inline()] {
+            let thread = jobs.threads() > 0;
+            let mut streamer = ChunkStreamer::new(1, settings(), false, jobs);
+            streamer.update(v![0.0, 0.0, 0.0], &World::new());
+
+            let mut count = 0;
+            while count < 9 {
+                let finished = streamer.finished();
+                assert!(finished.len() <= 4);
+                // Without a thread every call generates the chunks it hands out.
+                assert!(thread || finished.len() == 4 || count + finished.len() == 9);
+                assert!(finished.iter().all(|streamed| streamed.mesh.is_none()));
+                count += finished.len();
+            }
+            assert!(streamer.wait().is_none());
+        }
+    }
+
+    #[test]
+    fn waits_for_chunks_without_a_thread() {
+        let mut streamer = ChunkStreamer::new(1, settings(), true, Jobs::inline());
+        streamer.update(v![0.0, 0.0, 0.0], &World::new());
+        let mut count = 0;
+        while let Some(streamed) = streamer.wait() {
+            assert!(streamed.mesh.is_some());
+            count += 1;
+        }
+        assert_eq!(count, 9);
+    }
+}